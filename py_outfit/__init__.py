@@ -5,11 +5,28 @@ from .py_outfit import (
     Observer,
     IODParams,
     TrajectorySet,
+    TrajectoryIdIterator,
     GaussResult,
+    FitStatistics,
+    IodCosts,
     KeplerianElements,
     EquinoctialElements,
     CometaryElements,
     Observations,
+    OrbitResults,
+    ResultRow,
+    TripletScorerError,
+    EphemerisIncompleteError,
+    EphemerisCoverageError,
+    UnsortedEpochsError,
+    StrictModeError,
+    FallbackWarning,
+    schemas,
+    set_strict,
+    strict_checks,
+    read_orbfit_elements,
+    parse_sexagesimal,
+    SCHEMA_VERSION,
     DPI,
     SECONDS_PER_DAY,
     AU,
@@ -35,11 +52,28 @@ __all__ = [
     "Observer",
     "IODParams",
     "TrajectorySet",
+    "TrajectoryIdIterator",
     "GaussResult",
+    "FitStatistics",
+    "IodCosts",
     "KeplerianElements",
     "EquinoctialElements",
     "CometaryElements",
     "Observations",
+    "OrbitResults",
+    "ResultRow",
+    "TripletScorerError",
+    "EphemerisIncompleteError",
+    "EphemerisCoverageError",
+    "UnsortedEpochsError",
+    "StrictModeError",
+    "FallbackWarning",
+    "schemas",
+    "set_strict",
+    "strict_checks",
+    "read_orbfit_elements",
+    "parse_sexagesimal",
+    "SCHEMA_VERSION",
     "DPI",
     "SECONDS_PER_DAY",
     "AU",
@@ -2,6 +2,10 @@ from .py_outfit import *  # re-export types/symbols for IDEs
 from .orbit_type.keplerian import KeplerianElements
 from .orbit_type.equinoctial import EquinoctialElements
 from .orbit_type.cometary import CometaryElements
+from .schemas import SCHEMA_VERSION, schemas
+from .strict import set_strict, strict_checks
+from .orbfit import read_orbfit_elements
+from .sexagesimal import parse_sexagesimal
 from . import (
     AU,
     DPI,
@@ -23,6 +27,12 @@ __all__ = [
     "KeplerianElements",
     "EquinoctialElements",
     "CometaryElements",
+    "schemas",
+    "SCHEMA_VERSION",
+    "set_strict",
+    "strict_checks",
+    "read_orbfit_elements",
+    "parse_sexagesimal",
     "AU",
     "DPI",
     "EPS",
@@ -0,0 +1,55 @@
+from __future__ import annotations
+
+from typing import Any, Dict
+
+class IodCosts:
+    """
+    Work spent on the Gauss IOD of one trajectory.
+
+    Recorded when the run used `IODParamsBuilder.collect_costs(True)`; available as
+    `GaussResult.costs`, `OrbitResults.costs()` (failed trajectories included) and
+    aggregated by `OrbitResults.summary()`.
+
+    Attributes
+    ----------
+    wall_ms : float
+        Wall-clock time of the search (milliseconds), uncertainty calibration included.
+    triplets_attempted : int
+        Number of ranked triplets whose realizations were evaluated.
+    realizations : int
+        Number of noise realizations solved, the unperturbed one of each triplet included.
+    corrected_solutions : int
+        Number of realizations whose Gauss solution passed the velocity corrector
+        (corrected orbits).
+
+    Notes
+    -----
+    The iterations performed inside the velocity corrector are not reported by the
+    core solver; `corrected_solutions` counts its successful runs instead.
+    """
+
+    @property
+    def wall_ms(self) -> float: ...
+    @property
+    def triplets_attempted(self) -> int: ...
+    @property
+    def realizations(self) -> int: ...
+    @property
+    def corrected_solutions(self) -> int: ...
+    def to_dict(self, include_units: bool = False) -> Dict[str, Any]:
+        """
+        Convert the counters to a dict with one key per attribute.
+
+        Parameters
+        ----------
+        include_units : bool, optional
+            Add a `"units"` block mapping each key to its unit (default: False).
+
+        Returns
+        -------
+        dict[str, Any]
+            `wall_ms`, `triplets_attempted`, `realizations`, `corrected_solutions`. Plus `"units"` when requested.
+        """
+        ...
+    def __eq__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
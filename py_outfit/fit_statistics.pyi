@@ -0,0 +1,68 @@
+from __future__ import annotations
+
+from typing import Any, Dict
+
+class FitStatistics:
+    """
+    Goodness-of-fit statistics of an orbit over its arc, in physical units.
+
+    Available as `GaussResult.fit_statistics` on results returned by the IOD entry
+    points. Residuals are `observed - computed` over the whole arc; the RA residual is
+    projected on the sky (`dRA * cos(dec)`).
+
+    Attributes
+    ----------
+    rms_arcsec : float
+        Total RMS of the on-sky residuals (arcsec):
+        `rms_arcsec**2 = (rms_ra_arcsec**2 + rms_dec_arcsec**2) / 2`.
+    rms_ra_arcsec : float
+        RMS of the RA residuals projected on the sky (arcsec).
+    rms_dec_arcsec : float
+        RMS of the declination residuals (arcsec).
+    chi2 : float
+        Sum over observations of `(dRA*cos(dec)/sigma_ra)**2 + (dDec/sigma_dec)**2`,
+        with the uncertainties used by the fit.
+    reduced_chi2 : float
+        `chi2 / (2 * n_points - 6)`; `nan` when `n_points <= 3`.
+    n_points : int
+        Number of observations.
+
+    Notes
+    -----
+    The bare `rms` returned next to a `GaussResult` is the engine's selection score:
+    the RMS of the residuals normalized by their uncertainties (dimensionless),
+    evaluated over the time window used during the search.
+
+    See also
+    --------
+    * `Observations.residuals` – Per-observation residuals in arcseconds.
+    """
+
+    @property
+    def rms_arcsec(self) -> float: ...
+    @property
+    def rms_ra_arcsec(self) -> float: ...
+    @property
+    def rms_dec_arcsec(self) -> float: ...
+    @property
+    def chi2(self) -> float: ...
+    @property
+    def reduced_chi2(self) -> float: ...
+    @property
+    def n_points(self) -> int: ...
+    def to_dict(self, include_units: bool = False) -> Dict[str, Any]:
+        """
+        Convert the statistics to a dict with one key per attribute.
+
+        Parameters
+        ----------
+        include_units : bool, optional
+            Add a `"units"` block mapping each key to its unit (default: False).
+
+        Returns
+        -------
+        dict[str, Any]
+            `rms_arcsec`, `rms_ra_arcsec`, `rms_dec_arcsec`, `chi2`, `reduced_chi2`, `n_points`. Plus `"units"` when requested.
+        """
+        ...
+    def __repr__(self) -> str: ...
@@ -1,6 +1,11 @@
 from __future__ import annotations
-from typing import Optional, Literal, Dict, Any
+from typing import Optional, Literal, Dict, Any, List, Mapping
 
+import numpy as np
+from numpy.typing import NDArray
+
+from py_outfit.costs import IodCosts
+from py_outfit.fit_statistics import FitStatistics
 from py_outfit.orbit_type.cometary import CometaryElements
 from py_outfit.orbit_type.equinoctial import EquinoctialElements
 from py_outfit.orbit_type.keplerian import KeplerianElements
@@ -58,6 +63,39 @@ class GaussResult:
         """
         ...
 
+    @classmethod
+    def from_keplerian_arrays(
+        cls,
+        epoch: NDArray[np.float64],
+        a: NDArray[np.float64],
+        e: NDArray[np.float64],
+        i: NDArray[np.float64],
+        node: NDArray[np.float64],
+        argp: NDArray[np.float64],
+        m: NDArray[np.float64],
+        corrected: bool = False,
+    ) -> List[GaussResult]:
+        """
+        Build many results from Keplerian element arrays in a single Rust pass.
+
+        Parameters
+        -----------------
+        * `epoch`, `a`, `e`, `i`, `node`, `argp`, `m`: float64 arrays of equal length
+          (MJD TDB, AU, radians).
+        * `corrected`: If `True`, produce corrected-stage results; otherwise preliminary.
+
+        Returns
+        ----------
+        list[GaussResult]
+            One result per row.
+
+        Raises
+        ----------
+        ValueError
+            If the arrays have different lengths.
+        """
+        ...
+
     @classmethod
     def from_cometary(cls, cometary: CometaryElements, corrected: Optional[bool] = ...) -> GaussResult:
         """
@@ -145,14 +183,133 @@ class GaussResult:
         ...
 
     # --- Structured representation ---
-    def to_dict(self) -> Dict[str, Any]:
+    @property
+    def fit_statistics(self) -> Optional[FitStatistics]:
+        """
+        Fit statistics of the orbit over the fitted arc.
+
+        Returns
+        ----------
+        FitStatistics | None
+            Set on results returned by the IOD entry points; `None` for results built
+            from element sets, or when the orbit cannot be propagated over the arc.
+        """
+        ...
+
+    @property
+    def costs(self) -> Optional[IodCosts]:
+        """
+        Cost counters of the IOD run that produced this result.
+
+        Returns
+        ----------
+        IodCosts | None
+            Set when the run used `IODParamsBuilder.collect_costs(True)`, `None` otherwise.
+        """
+        ...
+
+    @property
+    def fit_epoch(self) -> Optional[float]:
+        """
+        Reference epoch of the orbit as fitted, in MJD (TDB).
+
+        Returns
+        ----------
+        float | None
+            Set on results returned by the IOD entry points. It differs from the
+            elements' `reference_epoch` when `IODParamsBuilder.output_epoch` is used.
+            `None` for results built from element sets.
+        """
+        ...
+
+    @property
+    def subsample_indices(self) -> Optional[List[int]]:
+        """
+        Observations kept for triplet enumeration when the arc was subsampled.
+
+        Returns
+        ----------
+        list[int] | None
+            Increasing positions in the time-sorted arc, selected by
+            `IODParams.subsample`, when the arc was longer than
+            `max_obs_for_triplets`. `None` otherwise and for results built from
+            element sets.
+        """
+        ...
+
+    @property
+    def params_index(self) -> Optional[int]:
+        """
+        Parameter set that produced the orbit in a batch run with fallbacks.
+
+        Returns
+        ----------
+        int | None
+            `0` when the primary `params` of `estimate_all_orbits` /
+            `estimate_orbits` succeeded, `k` when the orbit was obtained with
+            `fallback_params[k - 1]`. `None` for runs without `fallback_params` and
+            for results built from element sets.
+        """
+        ...
+
+    @property
+    def iod_path(self) -> Optional[str]:
+        """
+        Strategy that produced the orbit in a batch run with initial guesses.
+
+        Returns
+        ----------
+        str | None
+            `"guess"` when the orbit passed in `initial_guesses` was corrected over
+            the whole arc (no triplet search), `"fallback"` when that correction
+            diverged and the Gauss IOD ran instead, `"gauss"` for trajectories without
+            a guess. `None` for runs without `initial_guesses` and for results built
+            from element sets.
+        """
+        ...
+
+    def propagate(self, epoch: float) -> GaussResult:
+        """
+        Propagate the orbit to another epoch with the two-body model.
+
+        Parameters
+        ----------
+        epoch : float
+            Target epoch in MJD (TDB).
+
+        Returns
+        ----------
+        GaussResult
+            Same stage and element family at `epoch`; fit statistics, costs and
+            `fit_epoch` are kept.
+
+        Raises
+        ----------
+        RuntimeError
+            The orbit is unbound (cometary elements), which the propagator does not support.
+        """
+        ...
+
+    def to_dict(self, include_units: bool = False, degrees: bool = False) -> Dict[str, Any]:
         """
         Convert the result to a structured Python dict.
 
+        Parameters
+        ----------
+        include_units : bool, optional
+            Add `"units"` blocks mapping each numeric field to its unit (default: False).
+        degrees : bool, optional
+            Export angles in degrees instead of radians (default: False). The units
+            block is then always included.
+
         Schema
         ----------
         * `"stage"`: `"preliminary"` | `"corrected"`
         * `"type"`: `"keplerian"` | `"equinoctial"` | `"cometary"`
+        * `"fit_statistics"`: `FitStatistics.to_dict()`, or `None` when unavailable.
+        * `"costs"`: `IodCosts.to_dict()`, or `None` when costs were not collected.
+        * `"fit_epoch"`: epoch of the fit, on the scale of `reference_epoch` (MJD TDB),
+          or `None` for results built from element sets.
         * `"elements"`: dict of concrete fields for the stored family:
           - Keplerian: `reference_epoch`, `semi_major_axis`, `eccentricity`,
             `inclination`, `ascending_node_longitude`, `periapsis_argument`, `mean_anomaly`
@@ -161,6 +318,9 @@ class GaussResult:
             `tan_half_incl_sin_node`, `tan_half_incl_cos_node`, `mean_longitude`
           - Cometary: `reference_epoch`, `perihelion_distance`, `eccentricity`,
             `inclination`, `ascending_node_longitude`, `periapsis_argument`, `true_anomaly`
+        * `"units"` (with units): unit of every `"elements"` field (`"AU"`, `"rad"` or
+          `"deg"`, `"MJD_TDB"`, `""` for dimensionless values). The nested statistics and
+          costs dicts carry their own `"units"` block.
 
         Returns
         ----------
@@ -169,6 +329,105 @@ class GaussResult:
         """
         ...
 
+    def to_json(
+        self,
+        include_units: bool = True,
+        degrees: bool = False,
+        indent: Optional[int] = None,
+    ) -> str:
+        """
+        Serialize the result to JSON.
+
+        Parameters
+        ----------
+        include_units : bool, optional
+            Embed the `"units"` blocks (default: True).
+        degrees : bool, optional
+            Export angles in degrees instead of radians (default: False).
+        indent : int, optional
+            Indentation passed to `json.dumps` (default: compact output).
+
+        Returns
+        ----------
+        str
+            The JSON form of `to_dict(include_units, degrees)`.
+        """
+        ...
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> GaussResult:
+        """
+        Rebuild a result from the output of `to_dict`.
+
+        Without a `"units"` block, values are read in radians / AU / MJD (TDB). Angles
+        declared `"deg"` are converted back to radians.
+
+        Parameters
+        ----------
+        data : dict
+            Mapping with `"stage"`, `"type"`, `"elements"` and optionally `"units"`,
+            `"fit_statistics"`, `"costs"`, `"fit_epoch"` and `"rms"` (accepted, not kept).
+            Any other key is rejected.
+
+        Returns
+        ----------
+        GaussResult
+            The element set at the recorded stage, with the fit statistics, costs and
+            `"fit_epoch"` found in `data`.
+
+        Raises
+        ----------
+        KeyError
+            A required key or element field is missing; the message names it, and the
+            nested dict (`"fit_statistics"`, `"costs"`) it belongs to.
+        ValueError
+            Unknown key, stage or element type, a non-string label, or an unsupported unit.
+        """
+        ...
+
+    @classmethod
+    def from_row(cls, row: Mapping[str, Any], units: Optional[Mapping[str, str]] = None) -> GaussResult:
+        """
+        Rebuild a result from one row of the `OrbitResults` exports.
+
+        Rows are flat, with the columns of `py_outfit.schemas()["results"]`: a `dict`
+        built from `OrbitResults.to_dict()`, a `pandas.Series` of its DataFrame, or a
+        record read back from Parquet.
+
+        Parameters
+        ----------
+        row : Mapping[str, Any]
+            Row with `"stage"`, `"element_type"` and the element columns of that family;
+            optionally `"fit_epoch"` and the fit statistics columns (`rms_arcsec`, ...).
+            Other columns are ignored. Missing values may be `None` or `NaN`.
+        units : Mapping[str, str], optional
+            Units block of the export (`to_dict(include_units=True)["units"]`), required
+            when angles were exported in degrees. Without it, values are read in
+            radians / AU / MJD (TDB).
+
+        Returns
+        ----------
+        GaussResult
+            The element set at the recorded stage, with its `fit_epoch` and fit
+            statistics when the row has them. Costs are not part of the rows.
+
+        Raises
+        ----------
+        KeyError
+            A required column is missing; the message names it.
+        ValueError
+            The row is a failed trajectory (`"error"` is set), an unknown stage or element
+            type, or an unsupported unit.
+        """
+        ...
+
+    @classmethod
+    def from_json(cls, s: str) -> GaussResult:
+        """
+        Rebuild a result from the output of `to_json` (see `from_dict`).
+        """
+        ...
+
     # --- Representations ---
     def __str__(self) -> str: ...
     def __repr__(self) -> str: ...
@@ -1,3 +1,20 @@
+from typing import Callable, Literal, Optional, Tuple, Union
+
+TripletScorer = Callable[[Tuple[float, float, float], Tuple[int, int, int]], float]
+"""
+User hook ranking Gauss triplets: `scorer(epochs, indices) -> float` (lower is tried first).
+"""
+
+OutputEpoch = Union[float, Literal["first_obs", "last_obs", "middle"]]
+"""
+Epoch at which IOD results are quoted: an MJD (TDB) or a per-trajectory choice.
+"""
+
+Subsample = Literal["uniform", "leverage"]
+"""
+Thinning strategy of arcs longer than `max_obs_for_triplets`.
+"""
+
 class IODParams:
     """
     Configuration for Gauss Initial Orbit Determination (IOD).
@@ -16,7 +33,8 @@ class IODParams:
 
     1. Triplet generation. Candidate observation triplets are constrained by
     `dt_min`, `dt_max_triplet`, and `optimal_interval_time`. Oversized datasets
-    may be downsampled to `max_obs_for_triplets` before selection.
+    may be downsampled to `max_obs_for_triplets` before selection, following the
+    `subsample` strategy.
 
     2. Monte Carlo perturbation. Each triplet is expanded into multiple noisy copies
     (`n_noise_realizations`) drawn from Gaussian perturbations scaled by
@@ -45,6 +63,7 @@ class IODParams:
         - `dt_max_triplet`       : 150.0  (days)
         - `optimal_interval_time`: 20.0   (days)
         - `max_obs_for_triplets` : 100
+        - `subsample`            : "uniform"
         - `max_triplets`         : 10
         - `gap_max`              : 8/24   (days; 8 hours)
 
@@ -245,6 +264,63 @@ class IODParams:
         """
         ...
 
+    @property
+    def triplet_scorer(self) -> Optional[TripletScorer]:
+        """
+        Optional user callback replacing the built-in triplet ranking. **Default:** None.
+
+        See also
+        ----------
+        * `IODParamsBuilder.triplet_scorer` – Calling convention and restrictions.
+        """
+        ...
+
+    @property
+    def collect_costs(self) -> bool:
+        """
+        Whether per-trajectory cost counters are recorded. **Default:** False.
+
+        See also
+        ----------
+        * `IodCosts` – Recorded counters.
+        """
+        ...
+
+    @property
+    def collect_rejection_stats(self) -> bool:
+        """
+        Whether rejected candidates are tallied per reason. **Default:** False.
+
+        See also
+        ----------
+        * `OrbitResults.rejection_stats` – Aggregated tallies.
+        """
+        ...
+
+    @property
+    def output_epoch(self) -> Optional[OutputEpoch]:
+        """
+        Epoch at which the returned elements are quoted. **Default:** None (the fit epoch).
+
+        See also
+        ----------
+        * `IODParamsBuilder.output_epoch` – Accepted values.
+        * `GaussResult.fit_epoch` – Epoch of the fit.
+        """
+        ...
+
+    @property
+    def subsample(self) -> Subsample:
+        """
+        Thinning strategy of arcs longer than `max_obs_for_triplets`. **Default:** "uniform".
+
+        See also
+        ----------
+        * `IODParamsBuilder.subsample` – Available strategies.
+        * `GaussResult.subsample_indices` – Observations kept for a result.
+        """
+        ...
+
 class IODParamsBuilder:
     """
     Fluent builder for `IODParams`.
@@ -370,6 +446,121 @@ class IODParamsBuilder:
         """Request sequential execution in higher-level APIs that accept it."""
         ...
 
+    def triplet_scorer(self, scorer: Optional[TripletScorer]) -> "IODParamsBuilder":
+        """
+        Install a custom triplet ranking callback (or `None` to restore the default).
+
+        Parameters
+        ----------
+        scorer : Callable[[tuple[float, float, float], tuple[int, int, int]], float] or None
+            Called once per time-feasible triplet as `scorer(epochs, indices)` where
+            `epochs` are the three MJD (TT) epochs and `indices` the positions of the
+            observations in the time-sorted trajectory. Lower scores are tried first;
+            non-finite scores discard the triplet. Only the `max_triplets` best-ranked
+            triplets are passed to the Gauss solver.
+
+        Raises
+        ----------
+        ValueError
+            If `scorer` is not callable.
+
+        Notes
+        ----------
+        * The callback runs with the GIL held, after the triplet enumeration of each
+          trajectory and before any Gauss solve.
+        * An exception raised by the callback aborts only the current trajectory:
+          `Observations.estimate_best_orbit` raises `TripletScorerError`, while
+          `TrajectorySet.estimate_all_orbits` reports it in the error map with a
+          `TripletScorerError:` prefix.
+        * Not supported together with `do_parallel()`; `build()` raises `ValueError`.
+        """
+        ...
+
+    def collect_costs(self, v: bool) -> "IODParamsBuilder":
+        """
+        Record per-trajectory cost counters (`IodCosts`).
+
+        When enabled, results carry `GaussResult.costs` and `OrbitResults.summary()`
+        aggregates them. When disabled (default) the search measures nothing.
+
+        Notes
+        ----------
+        * Not supported together with `do_parallel()`; `build()` raises `ValueError`.
+        * Entries recomputed by `OrbitResults.update_with` carry no costs.
+        """
+        ...
+
+    def collect_rejection_stats(self, v: bool) -> "IODParamsBuilder":
+        """
+        Tally why candidate triplets were rejected.
+
+        When enabled, every noise realization solved by the search is attributed to a
+        rejection reason (`"spacing"`, `"root_finding"`, `"physical"`, `"rms_cut"`) or
+        to the selected solution, and `OrbitResults.rejection_stats()` aggregates the
+        tallies over the batch. When disabled (default) nothing is counted.
+
+        Notes
+        ----------
+        * Supported on every path, `do_parallel()` included: each trajectory keeps its
+          own counters, merged once the batch is complete.
+        * Entries recomputed by `OrbitResults.update_with` carry no tally.
+        """
+        ...
+
+    def output_epoch(self, v: Optional[OutputEpoch]) -> "IODParamsBuilder":
+        """
+        Quote every returned orbit at a chosen epoch instead of the fit epoch.
+
+        Parameters
+        ----------
+        v : float | {"first_obs", "last_obs", "middle"} | None
+            An MJD (TDB) shared by all trajectories; the first or last observation
+            epoch of each trajectory, or the midpoint between them; `None` (default)
+            keeps the fit epoch.
+
+        Notes
+        ----------
+        * Orbits are two-body propagated (no planetary perturbation) from the fit
+          epoch, which remains available as `GaussResult.fit_epoch`. Fit statistics
+          and RMS are those of the fit.
+        * Unbound (cometary) orbits cannot be propagated: their trajectory fails with
+          the propagation error (`RuntimeError` for `Observations.estimate_best_orbit`).
+
+        Raises
+        ----------
+        ValueError
+            Unknown string option or non-finite MJD.
+        """
+        ...
+
+    def subsample(self, v: Subsample) -> "IODParamsBuilder":
+        """
+        Choose how arcs longer than `max_obs_for_triplets` are thinned before the
+        triplets are enumerated.
+
+        Parameters
+        ----------
+        v : {"uniform", "leverage"}
+            `"uniform"` (default) keeps observations evenly spaced in index, endpoints
+            included. `"leverage"` scores each observation by its contribution to a
+            weighted linear-motion fit of the arc (its share of the fit information
+            plus its share of the χ²) and keeps the endpoints and the
+            `max_obs_for_triplets - 2` best scores.
+
+        Notes
+        ----------
+        * Both strategies are deterministic; the kept observations are reported by
+          `GaussResult.subsample_indices`.
+        * `"leverage"` is not supported together with `do_parallel()`; `build()`
+          raises `ValueError`.
+
+        Raises
+        ----------
+        ValueError
+            Unknown strategy.
+        """
+        ...
+
     def build(self) -> IODParams:
         """
         Finalize and materialize an immutable `IODParams` with the chosen settings.
@@ -378,5 +569,11 @@ class IODParamsBuilder:
         ----------
         IODParams
             A read-only `IODParams` instance with the configured parameters.
+
+        Raises
+        ----------
+        ValueError
+            If `triplet_scorer`, `collect_costs` or `subsample("leverage")` is combined
+            with `do_parallel()`.
         """
         ...
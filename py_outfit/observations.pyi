@@ -1,11 +1,12 @@
 # py_outfit/observations.pyi
 from __future__ import annotations
 
-from typing import Iterator, Optional, Tuple
+from typing import Any, Iterator, List, Literal, Optional, Tuple, Union, overload
 import numpy as np
 from numpy.typing import NDArray
 
 from py_outfit.py_outfit import PyOutfit
+from .observer import Observer
 from .iod_params import IODParams
 from .iod_gauss import GaussResult
 
@@ -62,20 +63,147 @@ class Observations:
             If `idx` is out of range.
         """
         ...
+
+    def canonicalize(self) -> bool:
+        """
+        Put the observations in canonical order, in place.
+
+        The canonical order is time ascending, ties broken by RA, then Dec, then
+        observing site (compared by its geocentric position at the epoch), then the
+        uncertainties. Every mutating operation of `TrajectorySet` (ingestion,
+        `extend`, `add_from_*`, `split_on_gaps`) leaves its trajectories in this
+        order, so two pipelines holding the same observations export them identically.
+
+        Returns
+        -------
+        bool
+            True when the order changed.
+        """
+        ...
+    def filter_by_time(
+        self, mjd_min: Optional[float] = None, mjd_max: Optional[float] = None
+    ) -> Observations:
+        """
+        Observations of a time window, as a new view.
+
+        Parameters
+        ----------
+        mjd_min, mjd_max : float, optional
+            Bounds of the window (MJD TT, inclusive); `None` leaves the window open on
+            that side.
+
+        Returns
+        -------
+        Observations
+            The observations within the window (possibly none), in storage order.
+
+        Raises
+        ------
+        ValueError
+            On a NaN bound, or when `mjd_min > mjd_max`.
+        """
+        ...
+    def filter_by_uncertainty(self, max_sigma_arcsec: float) -> Observations:
+        """
+        Observations whose RA and Dec uncertainties are both within a threshold, as a
+        new view.
+
+        Parameters
+        ----------
+        max_sigma_arcsec : float
+            Largest uncertainty kept, in arcseconds.
+
+        Returns
+        -------
+        Observations
+            The observations within the threshold (possibly none), in storage order.
+
+        Raises
+        ------
+        ValueError
+            On a NaN or negative threshold.
+        """
+        ...
     # ---------------
     # Columnar export
     # ---------------
+    @overload
     def to_numpy(
-        self,
-    ) -> tuple[NDArray[np.float64], NDArray[np.float64], NDArray[np.float64], NDArray[np.float64], NDArray[np.float64]]:
+        self, photometry: Literal[False] = False
+    ) -> tuple[NDArray[np.float64], NDArray[np.float64], NDArray[np.float64], NDArray[np.float64], NDArray[np.float64]]: ...
+    @overload
+    def to_numpy(
+        self, photometry: Literal[True]
+    ) -> tuple[
+        NDArray[np.float64],
+        NDArray[np.float64],
+        NDArray[np.float64],
+        NDArray[np.float64],
+        NDArray[np.float64],
+        NDArray[np.float64],
+        NDArray[np.object_],
+    ]:
         """
         Export arrays to NumPy (rad / days).
 
+        Parameters
+        ----------
+        photometry : bool, optional
+            Append the `(mag, band)` arrays of `magnitudes()` (default: False).
+
         Returns
         -------
-        tuple[np.ndarray, np.ndarray, np.ndarray, np.ndarray, np.ndarray]
+        tuple[np.ndarray, ...]
             Five 1D arrays of dtype float64:
-            `(mjd_tt, ra_rad, dec_rad, sigma_ra, sigma_dec)`.
+            `(mjd_tt, ra_rad, dec_rad, sigma_ra, sigma_dec)`, followed by `mag` (float64,
+            NaN where unknown) and `band` (object, `None` where unknown) with
+            `photometry=True`.
+        """
+        ...
+
+    def magnitudes(self) -> tuple[NDArray[np.float64], NDArray[np.object_]]:
+        """
+        Magnitude and photometric band of each observation, as given at ingestion
+        (`mag=` / `band=` of the NumPy loaders of `TrajectorySet`).
+
+        The photometry follows the observations through the operations of the set
+        (sorting, `extend`, `split_on_gaps`, `from_observations`).
+
+        Returns
+        -------
+        tuple[np.ndarray, np.ndarray]
+            `mag` (float64, NaN where unknown) and `band` (object array of `str`, `None`
+            where unknown), in storage order.
+        """
+        ...
+
+    def rejected(self) -> NDArray[np.bool_]:
+        """
+        Whether each observation is flagged as rejected by its ADES file (`selAst` of
+        `D` / `d`, or an `exclude` field), for sets read with `include_rejected=True`.
+
+        Returns
+        -------
+        np.ndarray
+            Boolean array in storage order, all `False` for observations read without
+            flags.
+        """
+        ...
+
+    def to_pandas(self, degrees: bool = False) -> "pandas.DataFrame":
+        """
+        Export the observations as a pandas `DataFrame`, one row per observation.
+
+        Parameters
+        ----------
+        degrees : bool, optional
+            Angles in degrees instead of radians (default: False).
+
+        Returns
+        -------
+        pandas.DataFrame
+            The columns of `to_dict()`, `mag` and `band` included when the observations
+            carry photometry.
         """
         ...
 
@@ -90,6 +218,81 @@ class Observations:
         """
         ...
 
+    def to_dict(self, include_units: bool = False, degrees: bool = False) -> dict[str, Any]:
+        """
+        Export the observations as a dict of columns.
+
+        Parameters
+        ----------
+        include_units : bool, optional
+            Add a `"units"` block mapping each column to its unit (default: False).
+        degrees : bool, optional
+            Export `ra`, `dec` and the uncertainties in degrees instead of radians
+            (default: False). The units block is then always included.
+
+        Returns
+        -------
+        dict[str, Any]
+            List columns `mjd_tt`, `ra`, `dec`, `sigma_ra`, `sigma_dec`, then `mag` and
+            `band` (`None` where unknown) when at least one observation carries
+            photometry, plus `"units"` when requested.
+        """
+        ...
+
+    def to_json(
+        self,
+        include_units: bool = True,
+        degrees: bool = False,
+        indent: Optional[int] = None,
+    ) -> str:
+        """
+        Serialize the observations to JSON (the form of `to_dict(include_units, degrees)`).
+
+        Parameters
+        ----------
+        include_units : bool, optional
+            Embed the `"units"` block (default: True).
+        degrees : bool, optional
+            Export angles in degrees instead of radians (default: False).
+        indent : int, optional
+            Indentation passed to `json.dumps` (default: compact output).
+        """
+        ...
+
+    def to_mpc_80col(self, designation: Union[int, str], observer: str) -> List[str]:
+        """
+        Format the observations as MPC 80-column records.
+
+        Parameters
+        ----------
+        designation : int | str
+            Identifier of the object: an integer is written as a number in columns
+            1-5 (packed from 100000), a string of at most 7 characters as is in
+            columns 6-12.
+        observer : str
+            MPC code of the observing site (columns 78-80).
+
+        Returns
+        -------
+        list[str]
+            One 80-character line per observation, in storage order (no line
+            terminator).
+
+        Raises
+        ------
+        ValueError
+            if the designation has no 80-column form or the code is not 3 letters or
+            digits.
+
+        Notes
+        -----
+        Dates are written in UTC as `YYYY MM DD.ddddd`, RA as `HH MM SS.SS` and Dec as
+        `sDD MM SS.S`, each rounded to its last digit with carries propagated
+        (`23 59 59.996` becomes `00 00 00.00`). Note 2 is `C` (CCD); magnitude, band
+        and reference columns are left blank.
+        """
+        ...
+
     # -----------------
     # Display (compact)
     # -----------------
@@ -279,7 +482,95 @@ class Observations:
         Returns
         -------
         (GaussResult, float)
-            The orbit result and the RMS value (radians).
+            The orbit result and its RMS. The RMS is dimensionless: residuals are
+            normalized by the astrometric uncertainties. The `GaussResult` carries
+            `fit_statistics` (RMS in arcseconds per coordinate, chi2) over the whole arc.
+        """
+        ...
+
+    def residuals(
+        self, env: PyOutfit, orbit: GaussResult
+    ) -> Tuple[NDArray[np.float64], NDArray[np.float64], NDArray[np.float64]]:
+        """
+        Residuals of this trajectory against an orbit, in arcseconds.
+
+        Parameters
+        ----------
+        env : PyOutfit
+            Global environment providing ephemerides and observers.
+        orbit : GaussResult
+            Orbit to evaluate (e.g. the result of `estimate_best_orbit`).
+
+        Returns
+        -------
+        (ndarray, ndarray, ndarray)
+            `(mjd_tt, ra_arcsec, dec_arcsec)`, one entry per observation in storage order.
+            Residuals are `observed - computed`; the RA residual is projected on the sky
+            (`dRA * cos(dec)`).
+
+        Raises
+        ------
+        RuntimeError
+            If the orbit cannot be propagated (e.g. unbound orbits).
+
+        Notes
+        -----
+        These residuals are aggregated by `GaussResult.fit_statistics`:
+        `rms_ra_arcsec = sqrt(mean(ra_arcsec**2))`, `rms_dec_arcsec = sqrt(mean(dec_arcsec**2))`
+        and `rms_arcsec**2 = (rms_ra_arcsec**2 + rms_dec_arcsec**2) / 2`.
         """
         ...
 
+    def geometry(
+        self, env: PyOutfit, observer: Optional[Observer] = None
+    ) -> dict[str, NDArray[np.float64]]:
+        """
+        Per-observation geometry used by the Gauss IOD, without the solver.
+
+        Exposes the expensive, convention-sensitive inputs of the internal IOD so that
+        alternative methods (Gooding, double-r iteration, ...) can be prototyped in
+        Python on the same footing.
+
+        Parameters
+        ----------
+        env : PyOutfit
+            Global environment providing ephemerides and Earth orientation.
+        observer : Observer, optional
+            Site to recompute the observer positions for. When omitted, the positions
+            computed at ingestion for each observation's own site are returned; these
+            are exactly the ones used by the IOD.
+
+        Returns
+        -------
+        dict[str, ndarray]
+            * `"observer_position"`: `(n, 3)` heliocentric observer positions (AU).
+            * `"los"`: `(n, 3)` unit line-of-sight vectors built from RA/DEC.
+            * `"mjd_tt"`: `(n,)` epochs on the TT axis, as used internally.
+            * `"mjd_tdb"`: `(n,)` the same epochs in TDB.
+
+        Notes
+        -----
+        * Vectors are expressed in the equatorial mean J2000 frame; rows follow the
+          storage order.
+        * No light-time correction is applied: the Gauss solver shifts the reference
+          epoch by `rho / c` once the topocentric distance is known.
+        """
+        ...
+
+    def topocentric(self, env: PyOutfit) -> NDArray[np.bool_]:
+        """
+        Whether the topocentric correction applies to each observation.
+
+        Parameters
+        ----------
+        env : PyOutfit
+            Global environment holding the observer registry.
+
+        Returns
+        -------
+        ndarray of bool
+            Storage order; `False` for observations whose site is at the geocentre
+            (ingested with `topocentric=False`, or geocentric sites such as MPC code
+            `500`), `True` otherwise.
+        """
+        ...
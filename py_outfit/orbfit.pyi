@@ -0,0 +1,63 @@
+from __future__ import annotations
+
+from os import PathLike
+from typing import Dict, Literal, Optional, Tuple, Union, overload
+
+import numpy as np
+from numpy.typing import NDArray
+
+from py_outfit.orbit_type.equinoctial import EquinoctialElements
+from py_outfit.orbit_type.keplerian import KeplerianElements
+
+Elements = Union[KeplerianElements, EquinoctialElements]
+
+@overload
+def read_orbfit_elements(
+    path: Union[str, PathLike[str]], covariance: Literal[False] = False
+) -> Dict[str, Elements]: ...
+@overload
+def read_orbfit_elements(
+    path: Union[str, PathLike[str]], covariance: Literal[True]
+) -> Dict[str, Tuple[Elements, Optional[NDArray[np.float64]]]]: ...
+def read_orbfit_elements(
+    path: Union[str, PathLike[str]], covariance: bool = False
+) -> Union[Dict[str, Elements], Dict[str, Tuple[Elements, Optional[NDArray[np.float64]]]]]:
+    """
+    Read the orbits of an OrbFit element file (OEF2.0, `.eq0` / `.oel`).
+
+    Each object is a designation line followed by its element record (`KEP`: a, e,
+    i, Ω, ω, M; `EQU`: a, h, k, p, q, λ; angles in degrees) and its epoch
+    (` MJD <mjd> TDT`). Optional records (`MAG`, `LSP`, `COR`, `NOR`, …) and `!`
+    comments are skipped; the optional `key = value` header closed by `END_OF_HEADER`
+    is checked.
+
+    Parameters
+    -----------------
+    path : str or PathLike
+        OrbFit element file.
+    covariance : bool, default False
+        Also return the covariance (`COV` block) of each object.
+
+    Returns
+    ----------
+    dict
+        Designation → `KeplerianElements` / `EquinoctialElements` (radians, epoch in
+        MJD TDB), in file order. With `covariance=True`, designation →
+        `(elements, cov)`, `cov` being a `(6, 6)` array in the units of the element
+        object (AU, radians), or `None` without `COV` block.
+
+    Raises
+    ----------
+    OSError
+        The file cannot be read.
+    ValueError
+        Unsupported format, reference system (only `ECLM J2000`), time scale (only
+        `TDT`) or element record (`COM`, `CAR`, …); malformed record; duplicate
+        designation; object without elements or epoch. The message gives the line.
+
+    See also
+    ------------
+    * `KeplerianElements.to_orbfit_string`, `EquinoctialElements.to_orbfit_string` —
+      Writers of the same format.
+    """
+    ...
@@ -21,6 +21,7 @@ class CometaryElements:
     ------------
     * `to_keplerian` — Convert to Keplerian (hyperbolic only).
     * `to_equinoctial` — Convert to Equinoctial (hyperbolic only).
+    * `perihelion_time` / `from_perihelion_time` — Time of perihelion passage.
     """
 
     def __init__(
@@ -53,12 +54,102 @@ class CometaryElements:
         """
         ...
 
+    @classmethod
+    def from_perihelion_time(
+        cls,
+        t_p: float,
+        q: float,
+        e: float,
+        i: float,
+        node: float,
+        argp: float,
+        reference_epoch: float,
+    ) -> CometaryElements:
+        """
+        Build a cometary element set from the time of perihelion passage.
+
+        Catalogues give T_p rather than the true anomaly at an epoch; the true anomaly at
+        `reference_epoch` is computed here. The solution starts from Barker's equation
+        near `e = 1` and from the elliptic or hyperbolic Kepler equation otherwise, then is
+        refined on a time of flight free of the `1/(1 - e)` singularity.
+
+        Parameters
+        -----------------
+        t_p : float
+            Time of perihelion passage, MJD (TDB).
+        q : float
+            Perihelion distance (AU), > 0.
+        e : float
+            Eccentricity, ≥ 0 (elliptical, parabolic or hyperbolic).
+        i, node, argp : float
+            Inclination, longitude of the ascending node Ω and argument of periapsis ω
+            (radians).
+        reference_epoch : float
+            Epoch of the element set, MJD (TDB).
+
+        Returns
+        ----------
+        CometaryElements
+            Elements with `true_anomaly` in `(-π, π]`, within the asymptotes for `e > 1`.
+
+        Raises
+        ----------
+        ValueError
+            if `q <= 0` or `e < 0`.
+
+        Notes
+        ----------
+        * Round trips with `perihelion_time` agree to ~1e-9 day for q ~ 1 AU, including
+          near-parabolic orbits (`|e - 1|` down to 0).
+        * For `e < 1`, T_p may be any passage: the true anomaly is periodic.
+        """
+        ...
+
+    def perihelion_time(self) -> float:
+        """
+        Time of perihelion passage T_p derived from (epoch, q, e, ν).
+
+        Returns
+        ----------
+        float
+            MJD (TDB) of the perihelion passage. For `e < 1`, the passage closest to
+            the reference epoch (within half a period).
+
+        Raises
+        ----------
+        ValueError
+            if the true anomaly lies beyond the asymptotes of a hyperbolic orbit
+            (`|ν| >= acos(-1/e)`), or if `q <= 0` or `e < 0`.
+
+        Notes
+        ----------
+        * The time of flight is written with `D = tan(ν/2)` and Stumpff series, so it is
+          continuous across `e = 1`, where it reduces to Barker's equation: the accuracy
+          near parabolic orbits is that of the elliptic and hyperbolic cases.
+        """
+        ...
+
     # --- Read-only properties ---
     @property
     def reference_epoch(self) -> float:
         """Reference epoch of the element set (MJD, TDB)."""
         ...
 
+    @property
+    def reference_epoch_tdb(self) -> float:
+        """Reference epoch in TDB (MJD); same value as `reference_epoch`."""
+        ...
+
+    @property
+    def reference_epoch_tt(self) -> float:
+        """
+        Reference epoch converted to TT (MJD), the scale of observation epochs.
+
+        `TDB - TT` is a periodic term of at most ~1.7 ms (Fairhead & Bretagnon series,
+        accuracy ~10 µs).
+        """
+        ...
+
     @property
     def perihelion_distance(self) -> float:
         """Perihelion distance q (AU)."""
@@ -62,6 +62,21 @@ class EquinoctialElements:
         """Reference epoch of the element set (MJD, TDB)."""
         ...
 
+    @property
+    def reference_epoch_tdb(self) -> float:
+        """Reference epoch in TDB (MJD); same value as `reference_epoch`."""
+        ...
+
+    @property
+    def reference_epoch_tt(self) -> float:
+        """
+        Reference epoch converted to TT (MJD), the scale of observation epochs.
+
+        `TDB - TT` is a periodic term of at most ~1.7 ms (Fairhead & Bretagnon series,
+        accuracy ~10 µs).
+        """
+        ...
+
     @property
     def semi_major_axis(self) -> float:
         """Semi-major axis a (AU)."""
@@ -104,6 +119,36 @@ class EquinoctialElements:
         """
         ...
 
+    def to_orbfit_string(self, designation: str, header: bool = True) -> str:
+        """
+        Format the elements as an OrbFit (OEF2.0) `EQU` record.
+
+        Parameters
+        -----------------
+        designation : str
+            Object name written on the designation line (no whitespace).
+        header : bool, default True
+            Prepend the OEF2.0 header (`format`, `rectype`, `refsys = ECLM J2000`,
+            `END_OF_HEADER`), making the text a complete `.eq0` file. Use `False` for
+            the records appended after the first one.
+
+        Returns
+        ----------
+        str
+            The designation line, a comment, the `EQU` record (a in AU, mean longitude in degrees within [0, 360)) and the
+            epoch as ` MJD <mjd> TDT` (the reference epoch converted from TDB to TT).
+
+        Raises
+        ----------
+        ValueError
+            Empty designation or designation containing whitespace.
+
+        See also
+        ------------
+        * `py_outfit.read_orbfit_elements` — Read the records back.
+        """
+        ...
+
     # --- Representations ---
     def __str__(self) -> str: ...
     def __repr__(self) -> str: ...
@@ -1,5 +1,8 @@
 from __future__ import annotations
 
+import numpy as np
+from numpy.typing import NDArray
+
 from py_outfit.orbit_type.equinoctial import EquinoctialElements
 
 
@@ -52,12 +55,58 @@ class KeplerianElements:
         """
         ...
 
+    @classmethod
+    def from_arrays(
+        cls,
+        epoch: NDArray[np.float64],
+        a: NDArray[np.float64],
+        e: NDArray[np.float64],
+        i: NDArray[np.float64],
+        node: NDArray[np.float64],
+        argp: NDArray[np.float64],
+        m: NDArray[np.float64],
+    ) -> list[KeplerianElements]:
+        """
+        Build many element sets from column arrays in a single Rust pass.
+
+        Parameters
+        -----------------
+        * `epoch`, `a`, `e`, `i`, `node`, `argp`, `m`: float64 arrays of equal length,
+          in the units of the constructor (MJD TDB, AU, radians).
+
+        Returns
+        ----------
+        list[KeplerianElements]
+            One element set per row.
+
+        Raises
+        ----------
+        ValueError
+            If the arrays have different lengths.
+        """
+        ...
+
     # --- Read-only properties ---
     @property
     def reference_epoch(self) -> float:
         """Reference epoch of the element set (MJD, TDB)."""
         ...
 
+    @property
+    def reference_epoch_tdb(self) -> float:
+        """Reference epoch in TDB (MJD); same value as `reference_epoch`."""
+        ...
+
+    @property
+    def reference_epoch_tt(self) -> float:
+        """
+        Reference epoch converted to TT (MJD), the scale of observation epochs.
+
+        `TDB - TT` is a periodic term of at most ~1.7 ms (Fairhead & Bretagnon series,
+        accuracy ~10 µs).
+        """
+        ...
+
     @property
     def semi_major_axis(self) -> float:
         """Semi-major axis a (AU)."""
@@ -100,6 +149,36 @@ class KeplerianElements:
         """
         ...
 
+    def to_orbfit_string(self, designation: str, header: bool = True) -> str:
+        """
+        Format the elements as an OrbFit (OEF2.0) `KEP` record.
+
+        Parameters
+        -----------------
+        designation : str
+            Object name written on the designation line (no whitespace).
+        header : bool, default True
+            Prepend the OEF2.0 header (`format`, `rectype`, `refsys = ECLM J2000`,
+            `END_OF_HEADER`), making the text a complete `.oel` file. Use `False` for
+            the records appended after the first one.
+
+        Returns
+        ----------
+        str
+            The designation line, a comment, the `KEP` record (a in AU, angles in degrees within [0, 360)) and the
+            epoch as ` MJD <mjd> TDT` (the reference epoch converted from TDB to TT).
+
+        Raises
+        ----------
+        ValueError
+            Empty designation or designation containing whitespace.
+
+        See also
+        ------------
+        * `py_outfit.read_orbfit_elements` — Read the records back.
+        """
+        ...
+
     # --- Representations ---
     def __str__(self) -> str: ...
     def __repr__(self) -> str: ...
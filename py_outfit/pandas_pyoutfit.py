@@ -103,18 +103,23 @@ def _ensure_object_ids(a: Iterable[ObjectID]) -> np.ndarray:
     Returns
     ----------
     * `np.ndarray` with a stable dtype:
-      - `np.uint32` for pure-integer inputs,
+      - `np.uint32` for `uint32` inputs, `np.uint64` for other unsigned integers,
+      - `np.int64` for signed integers (negative ids are rejected on the Rust side),
       - `object` for string/heterogeneous inputs.
 
     Notes
     ----------
-    * Keeping a compact integer dtype improves memory locality.
+    * Integer ids are never narrowed here: 64-bit ids (e.g. ZTF / Rubin `diaObjectId`)
+      would collide once truncated to `uint32`.
     * Mixed types are coerced to `object` for safety.
     """
     arr = np.asarray(a)
+    if arr.dtype == np.uint32:
+        return np.ascontiguousarray(arr)
+    if np.issubdtype(arr.dtype, np.unsignedinteger):
+        return np.ascontiguousarray(arr.astype(np.uint64))
     if np.issubdtype(arr.dtype, np.integer):
-        # Normalize to a stable integer dtype (compact and hash-friendly)
-        return np.ascontiguousarray(arr.astype(np.uint32))
+        return np.ascontiguousarray(arr.astype(np.int64))
     if np.issubdtype(arr.dtype, np.str_) or arr.dtype == object:
         return np.ascontiguousarray(arr.astype(object))
     # Fallback: coerce to object (e.g., unexpected dtypes)
@@ -150,6 +155,16 @@ def _detect_element_set(
     return "keplerian"
 
 
+_FIT_COLUMNS = (
+    "rms_arcsec",
+    "rms_ra_arcsec",
+    "rms_dec_arcsec",
+    "chi2",
+    "reduced_chi2",
+    "n_points",
+)
+
+
 def _rows_from_ok_map(ok: Dict[int | str, Tuple[GaussResult, float]]) -> pd.DataFrame:
     """
     Flatten the success map from `TrajectorySet.estimate_all_orbits` to a DataFrame.
@@ -164,6 +179,8 @@ def _rows_from_ok_map(ok: Dict[int | str, Tuple[GaussResult, float]]) -> pd.Data
       - `object_id`, `variant` (`PrelimOrbit`|`CorrectedOrbit`),
       - `element_set` (`keplerian`|`equinoctial`|`cometary`),
       - `rms` (dimensionless residual metric),
+      - `rms_arcsec`, `rms_ra_arcsec`, `rms_dec_arcsec`, `chi2`, `reduced_chi2`,
+        `n_points` (from `GaussResult.fit_statistics`, NaN when unavailable),
       - plus the native orbital element fields.
 
     Notes
@@ -174,6 +191,8 @@ def _rows_from_ok_map(ok: Dict[int | str, Tuple[GaussResult, float]]) -> pd.Data
     records: list[Dict[str, Any]] = []
     for obj_id, (res, rms) in ok.items():
         edict = res.to_dict()  # native element keys
+        fit = edict.pop("fit_statistics", None) or dict.fromkeys(_FIT_COLUMNS, np.nan)
+        edict.pop("costs", None)  # diagnostics, see OrbitResults.summary()
         element_set = _detect_element_set(edict)
         variant = "CorrectedOrbit" if "corrected" in str(res).lower() else "PrelimOrbit"
         records.append(
@@ -182,6 +201,7 @@ def _rows_from_ok_map(ok: Dict[int | str, Tuple[GaussResult, float]]) -> pd.Data
                 "variant": variant,
                 "element_set": element_set,
                 "rms": float(rms),
+                **{c: fit[c] for c in _FIT_COLUMNS},
                 **edict,
             }
         )
@@ -1,14 +1,74 @@
 # py_outfit.pyi
 from __future__ import annotations
 
+from os import PathLike
+from typing import Dict, List, Optional, Tuple, Union
+
 from .iod_params import IODParams
 from .iod_gauss import GaussResult
+from .costs import IodCosts
+from .fit_statistics import FitStatistics
 from .observer import Observer
 from .orbit_type.cometary import CometaryElements
 from .orbit_type.equinoctial import EquinoctialElements
 from .orbit_type.keplerian import KeplerianElements
-from .trajectories import TrajectorySet
+from .trajectories import TrajectoryIdIterator, TrajectorySet
 from .observations import Observations
+from .results import OrbitResults, ResultRow
+
+class TripletScorerError(RuntimeError):
+    """Raised when a user `triplet_scorer` fails or returns a non-float value."""
+
+class EphemerisIncompleteError(RuntimeError):
+    """
+    Raised at environment construction when the loaded JPL kernel lacks a body
+    required by the IOD pipeline.
+
+    Attributes
+    ----------
+    missing_bodies : list[str]
+        Names of the required bodies absent from the kernel.
+    suggested_kernels : list[str]
+        Ephemeris selectors known to provide every required body.
+    """
+
+    missing_bodies: list[str]
+    suggested_kernels: list[str]
+
+class EphemerisCoverageError(ValueError):
+    """
+    Raised when observation epochs do not lie strictly inside a single ephemeris
+    segment (at least one day from its boundaries, see `PyOutfit.ephemeris_segments`).
+
+    Batch estimations record it per trajectory, with messages prefixed by
+    `EphemerisCoverageError:`.
+
+    Attributes
+    ----------
+    epochs : list[float]
+        Offending epochs (MJD).
+    nearest_boundaries : list[float]
+        For each epoch, the closest segment boundary (MJD, TDB).
+    """
+
+    epochs: list[float]
+    nearest_boundaries: list[float]
+
+class UnsortedEpochsError(ValueError):
+    """
+    Raised when a trajectory is not stored in time order.
+
+    Every construction path keeps the trajectories sorted by epoch, so this signals a
+    broken invariant rather than bad input. `TrajectorySet.estimate_all_orbits(...,
+    validate=True)` records it per trajectory, with messages prefixed by
+    `UnsortedEpochsError:`.
+    """
+
+class StrictModeError(ValueError):
+    """Raised in strict mode instead of applying a documented fallback (see `strict_checks`)."""
+
+class FallbackWarning(UserWarning):
+    """Issued in permissive mode when a documented fallback is applied (see `strict_checks`)."""
 
 """
 Physical and astronomical constants exposed by Outfit.
@@ -113,7 +173,14 @@ class PyOutfit:
     * `Observer` – Observing site handle.
     """
 
-    def __init__(self, ephem: str, error_model: str) -> None:
+    def __init__(
+        self,
+        ephem: str,
+        error_model: str,
+        time_span: Optional[Tuple[float, float]] = None,
+        strict: Optional[bool] = None,
+        check_ephemeris_coverage: bool = True,
+    ) -> None:
         """
         Create a new Outfit environment.
 
@@ -127,20 +194,140 @@ class PyOutfit:
             include "horizon:DE440" and "naif:DE441". The ephemeris file is resolved
             into the user cache and opened lazily; when downloads are enabled at build
             time, a missing file may be fetched automatically, otherwise an error is
-            raised.
+            raised. A `[YYYY-YYYY]` suffix (e.g. "horizon:DE440[2020-2030]") restricts
+            the environment to January 1st of the first year through the end of the last.
+            "builtin:approx" selects a compiled-in analytic ephemeris covering
+            1900-2100 that needs no download, for tests and CI. It is accurate only to
+            the arcminute level and is **not for science**.
         error_model : str
             Astrometric error model. Accepted values are "FCCT14", "VFCC17", and
-            "CBM10". Unknown strings default to "FCCT14". The model provides per-site
-            RA/DEC bias and RMS used during orbit determination.
+            "CBM10". Unknown strings default to "FCCT14" with a `FallbackWarning`
+            (`StrictModeError` in strict mode). The model provides per-site RA/DEC bias
+            and RMS used during orbit determination.
+        time_span : tuple[float, float], optional
+            Explicit restriction `(mjd_start, mjd_end)` in TDB, exclusive with the
+            selector suffix.
+        strict : bool, optional
+            Override of the process-wide strict mode for this call (`None`: the
+            setting of `set_strict`).
+        check_ephemeris_coverage : bool, default True
+            Reject observation epochs that do not lie strictly inside a single
+            ephemeris segment, at ingestion (`EphemerisCoverageError`) and estimation
+            (raised for a single trajectory, recorded per trajectory in batches).
 
         Returns
         ----------
         PyOutfit
             An initialized `PyOutfit` environment.
+
+        Notes
+        ----------
+        The kernel is always read whole by the core; a restricted span does not reduce
+        the download. It bounds the epochs accepted at ingestion and must lie within the
+        kernel coverage.
+
+        "builtin:approx" models the Earth-Moon barycenter with Keplerian elements drifting
+        linearly (Standish, JPL), the Sun offset from the barycenter by the giant
+        planets. It provides no other body, and it is available on Linux and macOS only.
+
+        Raises
+        ----------
+        RuntimeError
+            If the ephemeris cannot be resolved or loaded.
+        NotImplementedError
+            "builtin:approx" on Windows.
+        EphemerisIncompleteError
+            If the kernel lacks a body used by the IOD pipeline (Earth, Moon and Sun
+            for Horizons files, the Earth-Moon barycenter for NAIF kernels).
+        ValueError
+            Unknown "builtin:*" name, malformed span suffix, both a suffix and `time_span`, or a span that is
+            empty or not covered by the kernel (the message gives the valid range).
+        StrictModeError
+            Unknown `error_model` in strict mode.
+        """
+        ...
+
+    def ephemeris_time_range(self) -> Tuple[float, float]:
+        """
+        Epochs accepted by this environment.
+
+        Returns
+        ----------
+        tuple[float, float]
+            `(mjd_start, mjd_end)` in TDB: the kernel coverage, or the restricted span
+            given at construction. Ingesting observations outside this range raises
+            `ValueError`.
+        """
+        ...
+
+    def ephemeris_segments(self) -> list[Tuple[float, float]]:
+        """
+        Continuous segments of the ephemeris within `ephemeris_time_range()`.
+
+        Returns
+        ----------
+        list[tuple[float, float]]
+            `(mjd_start, mjd_end)` in TDB, in time order. With
+            `check_ephemeris_coverage`, observation epochs must lie at least one day
+            inside one of them.
+
+        Notes
+        ----------
+        The kernel is scanned on the first call (every 16 days, transitions bisected)
+        and the table is cached; gaps shorter than the scan step may go unnoticed.
         """
         ...
 
-    def add_observer(self, observer: Observer) -> None:
+    def clone_shallow(self) -> PyOutfit:
+        """
+        Cheap copy of the environment, with its own observatory registry.
+
+        The clone shares the core engine (loaded ephemeris, MPC table, error model)
+        with `self` for its whole life; the engine is never copied. The sites
+        registered with `add_observer`, `replace_observer` and `remove_observer` are
+        private to each side: mutations of one side are never seen by the other.
+
+        Returns
+        ----------
+        PyOutfit
+            A new environment with the same settings and user-registered sites (same
+            handles).
+
+        Notes
+        ----------
+        * The core indexes every site it sees (registrations, ingestion) in a table
+          that only grows and is not visible from Python; clones share that index,
+          which keeps the observations of either side valid in the other.
+        * Ingestion takes the engine exclusively: it waits for the calls running on the
+          other clones (and holds them back) while it indexes the sites.
+        """
+        ...
+
+    def shares_engine_with(self, other: PyOutfit) -> bool:
+        """
+        Whether two environments use the same loaded ephemeris in memory.
+
+        Returns
+        ----------
+        bool
+            `True` for an environment and its `clone_shallow()` copies (the address of
+            the loaded kernel is compared), `False` otherwise.
+        """
+        ...
+
+    def available_bodies(self) -> list[str]:
+        """
+        List the bodies that can be interpolated from the loaded ephemeris.
+
+        Returns
+        ----------
+        list[str]
+            Body names, e.g. "Earth", "Moon", "Sun" for Horizons files or
+            "Earth-Moon barycenter", "Sun", ... for NAIF kernels.
+        """
+        ...
+
+    def add_observer(self, observer: Observer) -> int:
         """
         Register an `Observer` in the current environment.
 
@@ -148,17 +335,194 @@ class PyOutfit:
         -----------------
         observer : Observer
             The observatory/site descriptor to register.
+
+        Returns
+        ----------
+        int
+            Handle identifying the registration, accepted by `user_observer`,
+            `replace_observer` and `remove_observer`. Handles are never reused.
         """
         ...
 
-    def show_observatories(self) -> str:
+    def user_observer(self, handle_or_name: Union[int, str]) -> Observer:
+        """
+        Look up a site registered with `add_observer`.
+
+        Parameters
+        -----------------
+        handle_or_name : int | str
+            Handle returned by `add_observer`, or the site name.
+
+        Raises
+        ----------
+        KeyError
+            If no registered site matches, or if a name matches several sites.
+        TypeError
+            If `handle_or_name` is neither an int nor a str.
+        """
+        ...
+
+    def replace_observer(
+        self, handle_or_name: Union[int, str], new_observer: Observer
+    ) -> int:
+        """
+        Replace a site registered with `add_observer`, keeping its handle and position.
+
+        Parameters
+        -----------------
+        handle_or_name : int | str
+            Handle returned by `add_observer`, or the site name.
+        new_observer : Observer
+            Site taking over the registration.
+
+        Returns
+        ----------
+        int
+            The (unchanged) handle.
+
+        Raises
+        ----------
+        KeyError
+            If no registered site matches.
+
+        Notes
+        ----------
+        Only future lookups and listings see the new site. Observations ingested
+        earlier with the old site (e.g. an existing `TrajectorySet`) keep referring to
+        it and remain valid: the environment never forgets a site it has resolved.
+        """
+        ...
+
+    def remove_observer(self, handle_or_name: Union[int, str]) -> Observer:
+        """
+        Unregister a site added with `add_observer`.
+
+        Parameters
+        -----------------
+        handle_or_name : int | str
+            Handle returned by `add_observer`, or the site name.
+
+        Returns
+        ----------
+        Observer
+            The removed site.
+
+        Raises
+        ----------
+        KeyError
+            If no registered site matches.
+
+        Notes
+        ----------
+        The site disappears from lookups and listings and its handle becomes invalid.
+        Observations ingested earlier with it remain valid (see `replace_observer`).
+        """
+        ...
+
+    def user_observatories(self) -> list[Observer]:
+        """
+        Observers registered with `add_observer`, in registration order.
+
+        Returns
+        ----------
+        list[Observer]
+            The user-added sites (MPC sites are not included).
+        """
+        ...
+
+    def export_user_observers(self, path: Union[str, PathLike[str]]) -> int:
+        """
+        Write the sites registered with `add_observer` to a JSON file.
+
+        Parameters
+        ----------
+        path : str or PathLike
+            Destination file (overwritten).
+
+        Returns
+        ----------
+        int
+            Number of sites written, in registration order.
+
+        Notes
+        ----------
+        The document is `{"format": "py_outfit.user_observers", "version": 1,
+        "observers": [...]}`. Each record holds `handle`, `name`, `longitude_deg`, the
+        parallax constants `rho_cos_phi` and `rho_sin_phi` (which define the site
+        exactly), `latitude_deg` and `elevation_m` (informative, WGS-84), and the
+        accuracy overrides `ra_accuracy` and `dec_accuracy` (radians, or `null`).
+        """
+        ...
+
+    def import_user_observers(
+        self, path: Union[str, PathLike[str]], merge: bool = True
+    ) -> Dict[str, list]:
+        """
+        Register the sites of a file written by `export_user_observers`.
+
+        Sites are matched to the registered ones by name: a site identical to a
+        registered one is left alone, a site whose name is registered with other
+        parameters is a conflict.
+
+        Parameters
+        ----------
+        path : str or PathLike
+            JSON file to read.
+        merge : bool, default True
+            Keep the registered site on conflicts. With `False` the file wins and the
+            registered site is replaced, keeping its handle.
+
+        Returns
+        ----------
+        dict[str, list]
+            * `"added"`: handles of the newly registered sites;
+            * `"unchanged"`: handles of the registered sites identical to a record;
+            * `"replaced"`: handles of the sites replaced (`merge=False`);
+            * `"conflicts"`: names of the registered sites kept on conflict (`merge=True`).
+
+        Raises
+        ----------
+        ValueError
+            Document of another format or version, or a record with an invalid value.
+        KeyError
+            Missing key; the message names the record and the key.
+        OSError
+            The file cannot be read.
+
+        Notes
+        ----------
+        The whole file is validated before any registration. Handles stored in the
+        file are informative; new sites get fresh handles.
+        """
+        ...
+
+    def show_observatories(
+        self,
+        only_user_added: bool = False,
+        filter: Optional[str] = None,
+        limit: Optional[int] = None,
+    ) -> str:
         """
         Render a human-readable list of currently known observatories.
 
+        User-added sites come first (registration order), followed by the MPC sites
+        sorted by code once the environment has resolved MPC codes (e.g. after
+        `get_observer_from_mpc_code` or an ingestion reading observatory codes).
+
+        Parameters
+        -----------------
+        only_user_added : bool, default False
+            List only the sites added with `add_observer`.
+        filter : str, optional
+            Case-insensitive substring matched against the MPC code and the site name.
+        limit : int, optional
+            Maximum number of sites to list; a trailing `... N more observatories` line
+            reports how many were omitted.
+
         Returns
         ----------
         str
-            A formatted `str` (table/list) of observatories.
+            One line per observatory. Units are spelled in ASCII (`deg`, `m`).
         """
         ...
 
@@ -175,5 +539,54 @@ class PyOutfit:
         ----------
         Observer
             an `Observer` handle usable with `add_observer`.
+
+        Raises
+        ----------
+        ValueError
+            If `code` is not in the MPC table.
+        """
+        ...
+
+    def nearest_observatories(
+        self,
+        latitude_deg: float,
+        longitude_deg: float,
+        max_distance_km: float = 50.0,
+        limit: Optional[int] = 5,
+    ) -> List[Tuple[Observer, float]]:
+        """
+        Find the MPC observatories closest to a geodetic position.
+
+        Distances are great-circle distances on the mean Earth sphere (heights
+        ignored). MPC sites are published as parallax constants; their geodetic
+        latitude is recovered on the WGS-84 ellipsoid.
+
+        Parameters
+        -----------------
+        latitude_deg : float
+            Geodetic latitude in degrees, in [-90, 90].
+        longitude_deg : float
+            Longitude in degrees, east positive.
+        max_distance_km : float, default 50.0
+            Search radius in km.
+        limit : int or None, default 5
+            Maximum number of sites returned; `None` returns every site in range.
+
+        Returns
+        ----------
+        list[tuple[Observer, float]]
+            `(observer, distance_km)` pairs, nearest first.
+
+        Raises
+        ----------
+        ValueError
+            If the latitude is out of range, the longitude is not finite or the radius
+            is negative.
+
+        Notes
+        ----------
+        * The MPC table is loaded on first use (network access).
+        * Sites without a ground position (geocentre, space telescopes) and sites added
+          with `add_observer` are not considered.
         """
         ...
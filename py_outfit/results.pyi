@@ -0,0 +1,480 @@
+from __future__ import annotations
+
+from os import PathLike
+from typing import Any, Dict, Iterator, List, Literal, Optional, Sequence, Tuple, Union
+
+import numpy as np
+from numpy.typing import NDArray
+
+from py_outfit.costs import IodCosts
+from py_outfit.iod_gauss import GaussResult
+from py_outfit.iod_params import IODParams
+from py_outfit.observations import Observations
+from py_outfit.observer import Observer
+from py_outfit.py_outfit import PyOutfit
+from py_outfit.trajectories import Key, TrajectorySet
+
+class OrbitResults:
+    """
+    Results of a batch Gauss IOD run, keyed by trajectory identifier.
+
+    An `OrbitResults` is produced by `TrajectorySet.estimate_orbits`. For every
+    trajectory it stores the IOD outcome (orbit and RMS, or an error message) together
+    with the observations that were fitted. Keeping the arcs makes follow-up workflows
+    possible, such as refining orbits when a few new observations arrive.
+
+    The container behaves like a read-only mapping: `results[key]` returns
+    `(GaussResult, rms)` for successful fits, `key in results` and `len(results)`
+    cover both successes and failures.
+
+    See also
+    ----------
+    * `TrajectorySet.estimate_orbits` – Producer of this container.
+    * `TrajectorySet.estimate_all_orbits` – Dict-based variant.
+    """
+
+    def __len__(self) -> int:
+        """Number of trajectories (successful or not)."""
+        ...
+
+    def __contains__(self, key: Key) -> bool:
+        """Whether `key` was processed (successfully or not)."""
+        ...
+
+    def __getitem__(self, key: Key) -> Tuple[GaussResult, float]:
+        """
+        Return `(GaussResult, rms)` for `key`.
+
+        Raises
+        ----------
+        KeyError
+            If `key` is unknown or its IOD failed (the message carries the error).
+        """
+        ...
+
+    def keys(self) -> List[Key]:
+        """All trajectory identifiers, sorted."""
+        ...
+
+    def ok(self) -> Dict[Key, Tuple[GaussResult, float]]:
+        """Successful fits, same layout as the first dict of `estimate_all_orbits`."""
+        ...
+
+    def errors(self) -> Dict[Key, str]:
+        """Failures, same layout as the second dict of `estimate_all_orbits`."""
+        ...
+
+    def rows(self) -> Iterator[ResultRow]:
+        """
+        Iterate over every trajectory as a `ResultRow`, in identifier order.
+
+        Successes and failures share one type, so loops need no `isinstance` checks:
+
+        ```python
+        for row in results.rows():
+            if row.ok:
+                print(row.id, row.rms)
+            else:
+                print(row.id, row.error_kind, row.error_message)
+        ```
+
+        Rows are views on this object: the orbit, error and metadata are only read when
+        the attribute is accessed.
+        """
+        ...
+
+    def observations(self, key: Key) -> Observations:
+        """
+        Observations fitted for `key`, as provided (before uncertainty inflation).
+
+        Raises
+        ----------
+        KeyError
+            If `key` is unknown.
+        """
+        ...
+
+    def effective_weights(
+        self, key: Key
+    ) -> Tuple[NDArray[np.float64], NDArray[np.float64]]:
+        """
+        Weights of the observations of `key` in its accepted fit.
+
+        Each coordinate of an observation weighs `1 / sigma**2` in the chi-square of
+        the fit, where `sigma` is the uncertainty the fit actually used: as ingested
+        (or substituted by the `bad_sigma` policy, e.g. from the site accuracy), then
+        inflated by the batch RMS correction of the error model.
+
+        Parameters
+        -----------------
+        key : int | str
+            Trajectory identifier.
+
+        Returns
+        ----------
+        tuple[NDArray[np.float64], NDArray[np.float64]]
+            `(weight_ra, weight_dec)` in arcsec**-2, aligned with
+            `observations(key)`.
+
+        Raises
+        ----------
+        KeyError
+            If `key` is unknown or its IOD failed.
+        """
+        ...
+
+    def costs(self) -> Dict[Key, IodCosts]:
+        """
+        Cost counters per trajectory, failed trajectories included.
+
+        Only trajectories processed with `IODParamsBuilder.collect_costs(True)` appear.
+        """
+        ...
+
+    def summary(self) -> Dict[str, Any]:
+        """
+        Aggregate view of the batch.
+
+        Returns
+        ----------
+        dict[str, Any]
+            * `"n_trajectories"`, `"n_ok"`, `"n_err"`: entry counts.
+            * `"costs"`: `IodCosts.to_dict()` of the counters summed over every entry
+              carrying costs (failures included), or `None` when no entry does.
+            * `"n_costed"`: number of entries contributing to `"costs"`.
+        """
+        ...
+
+    def rejection_stats(self) -> Optional[Dict[str, Any]]:
+        """
+        Why candidate triplets were rejected, aggregated over the batch.
+
+        Every noise realization solved by the search is counted once, either under the
+        reason it was rejected for or as the selected solution of its trajectory:
+
+        * `"spacing"`: degenerate lines of sight (singular direction matrix), typically
+          observations too close in time or along a great circle;
+        * `"root_finding"`: no admissible root of the Gauss polynomial;
+        * `"physical"`: solution that cannot be propagated over the arc (hyperbolic
+          orbit, Kepler solver failure);
+        * `"rms_cut"`: RMS not computable or not finite, or candidate scored but not
+          retained (not the lowest RMS of its trajectory).
+
+        Returns
+        ----------
+        dict[str, Any] or None
+            `None` when no entry was processed with
+            `IODParamsBuilder.collect_rejection_stats(True)`, otherwise:
+
+            * `"attempts"`: realizations solved over the batch;
+            * `"selected"`: realizations retained as a solution (one per successful fit);
+            * `"rejected"`: reason → count; the counts and `"selected"` sum to
+              `"attempts"`;
+            * `"examples"`: reason → up to five trajectory ids with at least one
+              rejection for that reason, in id order;
+            * `"no_feasible_triplets"`: trajectories without any time-feasible triplet
+              (no realization attempted);
+            * `"n_collected"`: number of entries contributing.
+
+        Notes
+        ----------
+        * With fallback parameter sets, a trajectory contributes the tally of the attempt
+          that produced its entry, as for `costs()`.
+        * Trajectories fitted from an initial guess or by `update_with` do not contribute.
+        """
+        ...
+
+    def to_dict(self, include_units: bool = False, degrees: bool = False) -> Dict[str, Any]:
+        """
+        Export the batch as a columnar dict, one row per trajectory.
+
+        Parameters
+        -----------------
+        include_units : bool, default False
+            Add a `"units"` block mapping each numeric column to its unit.
+        degrees : bool, default False
+            Export angles in degrees instead of radians (the units block is then
+            always included).
+
+        Returns
+        ----------
+        dict[str, Any]
+            Equally long lists with the columns of `py_outfit.schemas()["results"]`, in
+            that order, ready for `pandas.DataFrame(...)`. Rows are sorted by identifier.
+            Failed trajectories only fill `object_id` and `error`; element columns of the
+            other families are `None`. `weight_min`, `weight_median` and `weight_max`
+            summarize `effective_weights()` over both coordinates (arcsec**-2).
+        """
+        ...
+
+    def to_qtable(self, degrees: bool = True) -> Any:
+        """
+        Export the batch as an `astropy.table.QTable`, one row per trajectory.
+
+        The columns are those of `to_dict()` (names and order of
+        `py_outfit.schemas()["results"]`), with astropy types attached:
+
+        * `fit_epoch` and `reference_epoch` are `astropy.time.Time` columns in TDB;
+        * distances, angles and arcsecond fields (`rms_arcsec`, ...) are `Quantity`
+          columns in AU, deg (or rad) and arcsec;
+        * dimensionless numbers (`rms`, `eccentricity`, `chi2`, ...) are plain float columns;
+        * `n_points`, `stage`, `element_type` and `error` are `MaskedColumn`s.
+
+        Parameters
+        -----------------
+        degrees : bool, default True
+            Angle columns in degrees instead of radians.
+
+        Returns
+        ----------
+        astropy.table.QTable
+            One row per trajectory, sorted by identifier. Missing numbers are `NaN`
+            (masked for the epochs); missing labels and `n_points` are masked.
+
+        Raises
+        ----------
+        ImportError
+            If astropy is not installed.
+        """
+        ...
+
+    def to_sqlite(
+        self,
+        path: Union[str, PathLike[str]],
+        table: str = "orbits",
+        if_exists: Literal["fail", "replace", "append"] = "fail",
+        degrees: bool = False,
+    ) -> int:
+        """
+        Write the batch to a table of a SQLite database, one row per trajectory.
+
+        The columns are those of `to_dict()` (names and order of
+        `py_outfit.schemas()["results"]`), typed `REAL`, `INTEGER` (`n_points`) or
+        `TEXT`. The database is written by SQLite compiled into the extension, with
+        the GIL released, inside a single transaction; neither pandas nor the
+        `sqlite3` module is used.
+
+        Parameters
+        -----------------
+        path : str | os.PathLike
+            Database file, created if needed.
+        table : str, default "orbits"
+            Destination table.
+        if_exists : {"fail", "replace", "append"}, default "fail"
+            Behaviour when the table exists: raise, drop it first, or insert into it
+            (its columns must match).
+        degrees : bool, default False
+            Write angles in degrees instead of radians.
+
+        Returns
+        ----------
+        int
+            Number of rows written.
+
+        Raises
+        ----------
+        ValueError
+            on an invalid `if_exists` or table name, an existing table with
+            `if_exists="fail"`, or an existing table with other columns with
+            `"append"`.
+        OSError
+            on database errors (file that cannot be opened, constraint violation); the
+            transaction is then rolled back.
+
+        Notes
+        ----------
+        `object_id` is `INTEGER` when every id is an integer and `TEXT` otherwise, and
+        is indexed (`"{table}_object_id"`). Missing values are `NULL`.
+        """
+        ...
+
+    def predict_all(
+        self,
+        env: PyOutfit,
+        observer: Observer,
+        epochs_mjd_tt: Union[Sequence[float], NDArray[np.float64]],
+        path: Optional[Union[str, PathLike[str]]] = None,
+    ) -> Dict[str, Any]:
+        """
+        Predict the apparent position of every successful orbit at shared epochs.
+
+        Observer positions are computed once per epoch, then the orbits are evaluated
+        in parallel (the GIL is released) with the model used for the residuals:
+        two-body propagation and aberration correction.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Environment providing ephemerides and Earth orientation.
+        observer : Observer
+            Observing site.
+        epochs_mjd_tt : sequence of float
+            Prediction epochs in MJD (TT).
+        path : str or PathLike, optional
+            Also write the predictions to this Parquet file (requires `pyarrow`), in
+            long layout: one row per `(object_id, mjd_tt)` with the columns `object_id`,
+            `mjd_tt`, `ra`, `dec`, `ra_rate` and `dec_rate`.
+
+        Returns
+        ----------
+        dict
+            Rows follow `object_id`:
+
+            * `"object_id"`: `(n,)` object array of the identifiers of the successful fits;
+            * `"mjd_tt"`: `(m,)` the prediction epochs;
+            * `"ra"`, `"dec"`: `(n, m)` apparent position in degrees;
+            * `"ra_rate"`, `"dec_rate"`: `(n, m)` rates in deg/day, `dRA/dt · cos(dec)`
+              for RA;
+            * `"failed_rows"`: `(k,)` rows holding `NaN` predictions;
+            * `"errors"`: the first error of each failed row.
+
+        Raises
+        ----------
+        ValueError
+            If an epoch is not finite.
+
+        Notes
+        ----------
+        * A prediction that cannot be made is `NaN` rather than an error: a whole row
+          for orbits that cannot be propagated (unbound orbits), a whole column for
+          epochs the ephemeris does not cover.
+        * Rates are central differences over two minutes.
+        """
+        ...
+
+    def find_duplicates(
+        self, d_max: float = 0.1, kind: Literal["SH", "D"] = "SH"
+    ) -> List[List[Key]]:
+        """
+        Find trajectories whose orbits look like the same object.
+
+        Pairwise D-criteria are evaluated among the successful fits. Orbits are first
+        binned on perihelion distance, eccentricity and inclination with bin widths
+        derived from `d_max`, so only pairs that can pass the threshold are compared.
+
+        Parameters
+        ----------
+        d_max : float, default 0.1
+            Similarity threshold (inclusive).
+        kind : {"SH", "D"}, default "SH"
+            `"SH"` for the Southworth-Hawkins criterion, `"D"` for Drummond's.
+
+        Returns
+        ----------
+        list[list[Key]]
+            Clusters of identifiers linked by chains of pairs with `D <= d_max`, each
+            sorted, largest cluster first. Trajectories without a similar orbit are
+            omitted.
+
+        Raises
+        ----------
+        ValueError
+            If `kind` is unknown or `d_max` is negative or not finite.
+
+        Notes
+        ----------
+        Merge the observations of a cluster and re-fit to obtain a single orbit.
+        """
+        ...
+
+    def update_with(
+        self,
+        env: PyOutfit,
+        new_observations: TrajectorySet,
+        params: IODParams,
+        seed: Optional[int] = None,
+        divergence_factor: float = 3.0,
+    ) -> Tuple["OrbitResults", Dict[Key, str]]:
+        """
+        Update orbits with newly arrived observations.
+
+        For identifiers present in both `self` and `new_observations`, the new points
+        are appended to the stored arc and the existing orbit is refined instead of
+        running a fresh Gauss solve: the previous orbit seeds a differential correction
+        (least-squares fit of the elements) over the combined arc.
+
+        Parameters
+        ----------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        new_observations : TrajectorySet
+            Trajectories holding only the new observations.
+        params : IODParams
+            IOD configuration used by both the incremental and the full path.
+        seed : int, optional
+            RNG seed for deterministic runs.
+        divergence_factor : float, default 3.0
+            Tolerated RMS growth on the incremental path. When the correction diverges
+            or its RMS exceeds `divergence_factor × previous RMS`, a full Gauss IOD is
+            run on the combined arc.
+
+        Returns
+        ----------
+        results : OrbitResults
+            Updated results; `self` is left untouched.
+        paths : dict[Key, str]
+            Path taken per identifier: `"incremental"` (refined), `"full"` (refinement
+            diverged or previous fit failed), `"new"` (identifier unknown to `self`) or
+            `"unchanged"` (no new observations).
+
+        Notes
+        ----------
+        * On the incremental path the RMS is the one reported by the Gauss IOD,
+          evaluated over the whole combined arc.
+        * Updated entries carry no cost counters, even with `collect_costs`.
+        """
+        ...
+
+class ResultRow:
+    """
+    One trajectory of an `OrbitResults`, as yielded by `OrbitResults.rows()`.
+
+    A view on the (immutable) results: it holds the results object and the identifier,
+    and reads the entry when an attribute is accessed.
+    """
+
+    @property
+    def id(self) -> Key:
+        """Trajectory identifier."""
+        ...
+
+    @property
+    def ok(self) -> bool:
+        """`True` when the IOD produced an orbit."""
+        ...
+
+    @property
+    def orbit(self) -> Optional[GaussResult]:
+        """Orbit of a success, `None` for a failure."""
+        ...
+
+    @property
+    def rms(self) -> Optional[float]:
+        """RMS of the normalized residuals of a success, `None` for a failure."""
+        ...
+
+    @property
+    def error_kind(self) -> Optional[str]:
+        """
+        Short name of the error of a failure, `None` for a success.
+
+        The Python exception class for errors of the bindings (e.g.
+        `"EphemerisCoverageError"`, `"UnsortedEpochsError"`), the core error variant
+        otherwise (e.g. `"SingularDirectionMatrix"`).
+        """
+        ...
+
+    @property
+    def error_message(self) -> Optional[str]:
+        """Error message of a failure (as in `OrbitResults.errors()`), `None` for a success."""
+        ...
+
+    @property
+    def n_obs(self) -> int:
+        """Number of observations of the fitted arc."""
+        ...
+
+    @property
+    def costs(self) -> Optional[IodCosts]:
+        """Cost counters of the run, `None` unless `IODParams.collect_costs` was set."""
+        ...
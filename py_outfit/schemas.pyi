@@ -0,0 +1,33 @@
+from __future__ import annotations
+
+from typing import Any, Dict, List, Union
+
+SCHEMA_VERSION: int
+
+def schemas(degrees: bool = False) -> Dict[str, Union[int, List[Dict[str, Any]]]]:
+    """
+    Describe the columnar exports.
+
+    The descriptions are generated from the same definitions that build
+    `OrbitResults.to_dict`, `Observations.to_dict`, `TrajectorySet.to_dict` and
+    `TrajectorySet.to_parquet`, so they always match the exported columns.
+
+    Parameters
+    -----------------
+    degrees : bool, default False
+        Describe the exports produced with `degrees=True` (angle units `"deg"`).
+
+    Returns
+    ----------
+    dict
+        * `"schema_version"`: integer bumped on any change of a column list.
+        * `"results"`, `"observations"`, `"trajectory_set"`: ordered lists of columns,
+          each a dict `{"name": str, "dtype": str, "unit": str | None, "nullable": bool}`.
+          `dtype` is a NumPy/pandas type name (`"object"` for int-or-str identifiers);
+          `unit` follows the `"units"` blocks of the exports and is `None` for
+          non-numeric columns.
+        * `"trajectory_set_parquet"`: columns of the files written by
+          `TrajectorySet.to_parquet` (angles always in radians), those of
+          `"trajectory_set"` followed by the nullable `observer` MPC code.
+    """
+    ...
@@ -0,0 +1,44 @@
+from __future__ import annotations
+
+from typing import Sequence, Tuple, Union
+
+import numpy as np
+from numpy.typing import NDArray
+
+def parse_sexagesimal(
+    ra_strings: Union[Sequence[str], NDArray[np.str_], NDArray[np.object_]],
+    dec_strings: Union[Sequence[str], NDArray[np.str_], NDArray[np.object_]],
+) -> Tuple[NDArray[np.float64], NDArray[np.float64]]:
+    """
+    Convert sexagesimal right ascensions and declinations to radians.
+
+    Right ascensions are read as `h m s` (hours, below 24), declinations as `±d m s`
+    (degrees, at most 90). Fields are separated by colons or whitespace
+    (`"12:34:56.78"`, `"-05 43 21.0"`). The seconds may be omitted, the minutes then
+    carrying the fraction (`"12 34.5"`); only the last field may be fractional. A
+    leading sign applies to the whole angle (`"-00 30 00"` is half a degree south);
+    right ascensions only take `+`.
+
+    Parameters
+    -----------------
+    ra_strings : sequence of str or numpy.ndarray
+        Right ascensions, as a list or a NumPy string / object array.
+    dec_strings : sequence of str or numpy.ndarray
+        Declinations, same length.
+
+    Returns
+    ----------
+    tuple of numpy.ndarray
+        `(ra, dec)`, `float64` arrays in radians, ready for
+        `TrajectorySet.from_numpy_radians`.
+
+    Raises
+    ----------
+    ValueError
+        On the first malformed or out-of-range string, naming its row and value
+        (e.g. `dec_strings[3] = "+91 00 00": declination beyond 90 degrees`), and
+        when the lengths differ.
+    TypeError
+        If an element is not a `str`, naming the first one.
+    """
+    ...
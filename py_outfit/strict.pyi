@@ -0,0 +1,38 @@
+from __future__ import annotations
+
+from typing import Dict
+
+def set_strict(enabled: bool) -> bool:
+    """
+    Enable or disable strict mode for the whole process.
+
+    In permissive mode (the default), the documented fallbacks listed by
+    `strict_checks()` are applied and reported with `FallbackWarning`. In strict mode
+    they raise `StrictModeError` instead. Calls taking a `strict` argument override the
+    setting when it is not `None`.
+
+    Parameters
+    -----------------
+    enabled : bool
+        `True` to promote fallbacks to errors, `False` to only warn.
+
+    Returns
+    ----------
+    bool
+        The previous setting, e.g. to restore it afterwards.
+    """
+    ...
+
+def strict_checks() -> Dict[str, str]:
+    """
+    Behaviours affected by strict mode.
+
+    Returns
+    ----------
+    dict[str, str]
+        Check name (the prefix of the warning/exception message, e.g.
+        `"unknown_error_model"`, `"patched_sigma"`, `"unsorted_epochs"`,
+        `"skipped_lines"`, `"missing_ids"`) mapped to the fallback applied in permissive
+        mode.
+    """
+    ...
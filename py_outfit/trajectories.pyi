@@ -1,7 +1,10 @@
 from __future__ import annotations
 
 from pathlib import Path
-from typing import Any, Dict, Iterator, Optional, Tuple, Union
+from typing import (
+    Any, Dict, Iterable, Iterator, List, Literal, Mapping, Optional, Sequence, Tuple, TypeVar,
+    Union, overload,
+)
 
 import numpy as np
 from numpy.typing import NDArray
@@ -10,16 +13,66 @@ from py_outfit.iod_gauss import GaussResult
 from py_outfit.iod_params import IODParams
 from py_outfit.observations import Observations
 from py_outfit.observer import Observer
+from py_outfit.orbit_type.keplerian import KeplerianElements
 from py_outfit.py_outfit import PyOutfit
+from py_outfit.results import OrbitResults
+
+_T = TypeVar("_T")
 
 Key = Union[int, str]
 """
 Key used to identify a trajectory (either by its MPC code, a string ID or just an integer).
 """
+RealArray = Union[NDArray[np.floating], NDArray[np.integer]]
+"""
+Numeric column of the NumPy loaders (coordinates, epochs, per-row uncertainties): any
+integer or floating dtype and any memory layout. A contiguous `np.float64` array is read
+in place; anything else (e.g. `float32`, a strided `df["ra"].values[::2]`) is converted
+once, in Rust. Other dtypes (complex, strings) raise `TypeError` naming the argument.
+"""
+TrajectoryIds = Union[
+    NDArray[np.integer],
+    Sequence[int],
+    Sequence[str],
+    NDArray[np.str_],
+    NDArray[np.object_],
+]
+"""
+Trajectory ids of the NumPy loaders: one non-negative integer (any integer dtype and
+memory layout; a contiguous `uint32` array is read in place) or one `str` per observation.
+Object arrays and lists hold Python ints or strings, not a mix of both.
+"""
+Epochs = Union[
+    RealArray,
+    NDArray[np.datetime64],
+    Sequence[str],
+    NDArray[np.str_],
+    NDArray[np.object_],
+]
+"""
+Epochs of the NumPy loaders: one `float64` MJD (or JD) per observation, one
+`datetime64[ns]` / `datetime64[us]` value, or one ISO-8601 timestamp string such as
+`"2024-03-14T02:31:18.220Z"`.
+"""
 PathLike = Union[str, Path]
 """
 Path-like type (either a `str` or a `Path` from `pathlib`).
 """
+BadSigmaPolicy = Literal["error", "model", "value"]
+"""
+Ingestion policy for non-positive or non-finite uncertainties:
+`"error"` raises `ValueError`, `"model"` substitutes the observer's error-model accuracy,
+`"value"` substitutes `bad_sigma_value`.
+
+Every ingestion entry point also rejects, with `ValueError`, observations whose epoch
+lies outside `PyOutfit.ephemeris_time_range()`.
+"""
+Dedup = Union[bool, Tuple[float, float]]
+"""
+Duplicate removal of the batch loaders: `False` keeps every row, `True` drops the
+observations of a trajectory lying within 1e-7 day and 1 mas of an earlier one, and a
+`(time_tol, sep_mas)` pair (days, milliarcseconds) sets other tolerances.
+"""
 
 class TrajectorySet:
     """
@@ -29,11 +82,11 @@ class TrajectorySet:
 
     The container behaves like a Python dictionary for common operations such as membership tests, iteration, indexing, and length queries. Each entry provides a read‑only Observations view that exposes per‑trajectory data without copying, keeping memory usage predictable. This structure is intended to integrate cleanly with scientific Python workflows while delegating all heavy computation to the Rust engine underneath.
 
-    Ingestion supports two main paths. A zero‑copy path accepts right ascension and declination in radians, epochs in MJD (TT), and a single Observer for the entire batch. A compatible degrees and arcseconds path performs a single conversion to radians before storing data. Trajectories can also be constructed from standard astronomy formats such as MPC 80‑column and ADES (JSON or XML), and an existing set can be extended by appending additional files when needed.
+    Ingestion supports two main paths. A zero‑copy path accepts right ascension and declination in radians, epochs in MJD (TT), and a single Observer for the entire batch. A compatible degrees and arcseconds path converts to radians while storing the data, without copying the arrays. Trajectories can also be constructed from standard astronomy formats such as MPC 80‑column and ADES (JSON or XML), and an existing set can be extended by appending additional files when needed.
 
     The container is optimized for batch IOD. The dedicated batch method executes the Gauss solver over all stored trajectories using parameters supplied by IODParams and returns per‑trajectory outcomes together with error messages for failures. Execution may be sequential or parallel depending on configuration, with optional deterministic seeding for reproducibility. When run sequentially, cooperative cancellation allows returning partial results if interrupted by the user.
 
-    The type does not perform de‑duplication or cross‑trajectory merging and assumes inputs are pre‑grouped as intended. Units follow the package conventions: angles are treated in radians internally, epochs use MJD (TT), and when ingesting degrees the provided uncertainties are interpreted in arcseconds. A single observing site applies per ingestion call. The overall goal is to make data flow explicit, predictable, and efficient for production pipelines.
+    Duplicate observations are only removed on request (`dedup=` at ingestion, `deduplicate`), and the type does not perform cross‑trajectory merging: it assumes inputs are pre‑grouped as intended. Units follow the package conventions: angles are treated in radians internally, epochs use MJD (TT), and when ingesting degrees the provided uncertainties are interpreted in arcseconds. A single observing site applies per ingestion call. The overall goal is to make data flow explicit, predictable, and efficient for production pipelines.
     """
 
     # --- Introspection & stats ---
@@ -47,15 +100,23 @@ class TrajectorySet:
 
     def __contains__(self, key: Key) -> bool:
         """
-        Membership test (like a Python dict).
+        Membership test (like a Python dict): `if tid in ts: ...`.
+
+        A constant-time lookup of the id that builds no `Observations`.
 
         Parameters
         -----------------
-        * `key`: Object identifier (int MPC packed code or string id).
+        * `key`: Object identifier (`int`, NumPy integer scalar, or string id).
 
         Returns
         ----------
-        * `True` if the trajectory exists in the set, `False` otherwise.
+        * `True` if the trajectory exists in the set, `False` otherwise (negative ints
+          included).
+
+        Raises
+        ----------
+        ValueError
+            If `key` is neither an integer nor a `str`.
 
         See also
         ------------
@@ -77,7 +138,9 @@ class TrajectorySet:
 
         Raises
         ----------
-        * `KeyError` if the key is not present.
+        * `KeyError` (holding `key`) if the set has no such trajectory, e.g. for a
+          negative int.
+        * `ValueError` if `key` is neither an int nor a str.
 
         See also
         ------------
@@ -87,6 +150,38 @@ class TrajectorySet:
         """
         ...
 
+    @overload
+    def get(self, key: Key) -> Optional[Observations]: ...
+    @overload
+    def get(self, key: Key, default: _T) -> Union[Observations, _T]: ...
+    def get(self, key: Key, default: Any = None) -> Any:
+        """
+        Non-throwing lookup (like `dict.get`): the `Observations` of a given object, or
+        `default` when the set has no such trajectory.
+
+        Parameters
+        -----------------
+        key : Key
+            Object identifier (int or str).
+        default : Any, default None
+            Value returned for a missing trajectory.
+
+        Returns
+        ----------
+        Observations or default
+            The `Observations` view for that trajectory, or `default`.
+
+        Raises
+        ----------
+        ValueError
+            If `key` is neither an int nor a str.
+
+        See also
+        ------------
+        * `__getitem__` – Lookup raising `KeyError`.
+        """
+        ...
+
     def keys(self) -> list[Key]:
         """
         Return the list of keys (like `dict.keys()`).
@@ -103,19 +198,69 @@ class TrajectorySet:
         """
         ...
 
+    def arc_statistics(self, env: PyOutfit) -> Dict[str, Union[NDArray[Any], list[Key]]]:
+        """
+        Arc statistics of every trajectory, as columns.
+
+        Computed in Rust with the GIL released, to pre-select the trajectories worth an
+        IOD attempt without iterating in Python (e.g. `stats["n_nights"] >= 2`).
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Environment the set was ingested with (resolves the observing sites).
+
+        Returns
+        ----------
+        dict[str, numpy.ndarray]
+            One entry per trajectory, sorted by id: `trajectory_id` (as `ids()`),
+            `first_mjd`, `last_mjd` and `arc_days` (`float64`, MJD TT and days), `n_obs`,
+            `n_nights` and `n_observers` (`int64`). Empty trajectories have NaN epochs and
+            arc, and zero counts.
+
+        Notes
+        ----------
+        A night runs from local mean noon to the next one at the observing site (from
+        its longitude), so the detections of one night count once even across 0h UTC;
+        nights at different sites are told apart by their date only.
+        """
+        ...
+
+    def ids(self) -> Union[NDArray[np.uint64], list[Key]]:
+        """
+        Trajectory identifiers, in the order of `keys()`, without copying any
+        observation.
+
+        Returns
+        ----------
+        numpy.ndarray or list[Key]
+            A `uint64` array when every id is an integer (64-bit ids included), the
+            list of `keys()` (int and str ids) otherwise.
+
+        See also
+        ------------
+        * `keys` – The identifiers as a list.
+        """
+        ...
+
     def values(self) -> list[Observations]:
         """
         Return the list of trajectories (like `dict.values()`).
 
+        Each `Observations` is an owned copy of its trajectory, so the list costs as much
+        memory as the set itself. On large sets, `for tid in ts: obs = ts[tid]` copies
+        one trajectory at a time.
+
         Returns
         ----------
         list[Observations]
-            A list of all `Observations` currently stored.
+            A list of all `Observations` currently stored, in the order of `keys()`.
 
         See also
         ------------
         * `keys` – All keys.
         * `items` – Key/value pairs.
+        * `__iter__` – Streaming iteration over the keys.
         """
         ...
 
@@ -123,21 +268,26 @@ class TrajectorySet:
         """
         Return the list of `(key, Observations)` pairs (like `dict.items()`).
 
+        As with `values`, each `Observations` is an owned copy of its trajectory; on
+        large sets, `for tid in ts: obs = ts[tid]` copies one trajectory at a time.
+
         Returns
         ----------
         list[tuple[Key, Observations]]
-            A list of all `(object_id, Observations)` pairs currently stored.
+            A list of all `(object_id, Observations)` pairs currently stored, in the
+            order of `keys()`.
 
         See also
         ------------
         * `keys` – All keys.
         * `values` – All trajectories.
+        * `__iter__` – Streaming iteration over the keys.
         """
         ...
 
-    def __iter__(self) -> Iterator[Key]:
+    def __iter__(self) -> TrajectoryIdIterator:
         """
-        Iterate over keys (like a dict).
+        Iterate over keys (like a dict): `for tid in ts: obs = ts[tid]`.
 
         Parameters
         -----------------
@@ -145,7 +295,14 @@ class TrajectorySet:
 
         Returns
         ----------
-        * `Iterator[Key]` yielding object identifiers.
+        * `TrajectoryIdIterator` yielding object identifiers (`int` or `str`), one Python
+          object at a time.
+
+        Raises
+        ----------
+        RuntimeError
+            On the next step once the set changed size (e.g. `extend`) during the
+            iteration.
 
         See also
         ------------
@@ -176,281 +333,2865 @@ class TrajectorySet:
         """
         ...
 
-    def get_traj_stat(self) -> str:
+    @property
+    def patched_sigma_count(self) -> int:
         """
-        Pretty-printed statistics about observations per trajectory.
+        Number of observations whose uncertainties were substituted at ingestion.
 
         Returns
         ----------
-        str
-            A formatted `str` (histogram/stats), or
-            `"No trajectories available."` if empty.
+        int
+            Non-zero only with `bad_sigma="model"` or `bad_sigma="value"`;
+            accumulates over `add_from_*` calls.
         """
         ...
 
-    # --- Ingestion from NumPy ---
-    @staticmethod
-    def from_numpy_radians(
-        pyoutfit: PyOutfit,
-        trajectory_id: NDArray[np.uint32],
-        ra: NDArray[np.float64],
-        dec: NDArray[np.float64],
-        error_ra_rad: float,
-        error_dec_rad: float,
-        mjd_tt: NDArray[np.float64],
-        observer: Observer,
-    ) -> "TrajectorySet":
+    @property
+    def duplicates_removed(self) -> Dict[Key, int]:
         """
-        Build a `TrajectorySet` from arrays already in **radians** (RA/DEC) and **MJD (TT)**.
+        Number of observations dropped as duplicates, per trajectory.
 
-        This path uses a zero-copy ingestion under the hood.
+        Returns
+        ----------
+        dict[Key, int]
+            Trajectories that had duplicates, mapped to the number of observations
+            removed by the loaders (`dedup=`) and by `deduplicate`; accumulates over
+            calls and through `extend`.
+        """
+        ...
+
+    @property
+    def invalid_rows_dropped(self) -> Dict[Optional[Key], int]:
+        """
+        Number of rows dropped at ingestion, per trajectory.
+
+        Returns
+        ----------
+        dict[Key | None, int]
+            Trajectories that lost rows, mapped to the number of rows dropped by the
+            NumPy loaders and `from_chunks`: masked rows of `numpy.ma.MaskedArray` inputs
+            and, with `drop_invalid=True`, rows holding NaN or infinite values. Rows whose
+            `trajectory_id` is masked are counted under `None`. Accumulates over calls
+            and through `extend`.
+        """
+        ...
+
+    def deduplicate(self, time_tol: float = 1e-7, sep_mas: float = 1.0) -> Dict[Key, int]:
+        """
+        Drop the duplicate observations of every trajectory, in place.
+
+        Two observations of a trajectory are duplicates when their epochs differ by at
+        most `time_tol` days and their positions by at most `sep_mas` milliarcseconds;
+        the site and the uncertainties are not compared. The earliest observation of a
+        group of duplicates is kept.
 
         Parameters
         -----------------
-        pyoutfit : PyOutfit 
-            Global environment (ephemerides, observers, error model).
-        trajectory_id : NDArray[np.uint32]
-            `np.uint32` array — one ID per observation.
-        ra : NDArray[np.float64]
-            `np.float64` array — Right Ascension in **radians**.
-        dec : NDArray[np.float64]
-            `np.float64` array — Declination in **radians**.
-        error_ra_rad : float
-            1-σ RA uncertainty (**radians**) applied to the whole batch.
-        error_dec_rad : float
-            1-σ DEC uncertainty (**radians**) applied to the whole batch.
-        mjd_tt : NDArray[np.float64]
-            `np.float64` array — epochs in **MJD (TT)** (days).
-        observer : Observer
-            Single observing site for the whole batch.
+        time_tol : float, default 1e-7
+            Epoch tolerance, in days (~9 ms).
+        sep_mas : float, default 1.0
+            Position tolerance, in milliarcseconds.
 
         Returns
         ----------
-        TrajectorySet
-            A new `TrajectorySet` populated from the provided inputs.
+        dict[Key, int]
+            Trajectories that had duplicates, mapped to the number of observations
+            removed by this call (also added to `duplicates_removed`).
 
         Raises
         ----------
         ValueError
-            if input arrays have mismatched lengths.
+            If a tolerance is negative or not finite.
         """
         ...
 
-    @staticmethod
-    def from_numpy_degrees(
-        pyoutfit: PyOutfit,
-        trajectory_id: NDArray[np.uint32],
-        ra_deg: NDArray[np.float64],
-        dec_deg: NDArray[np.float64],
-        error_ra_arcsec: float,
-        error_dec_arcsec: float,
-        mjd_tt: NDArray[np.float64],
-        observer: Observer,
-    ) -> "TrajectorySet":
+    def find_shared_observations(
+        self, time_tol_days: float = 1e-6, pos_tol_arcsec: float = 0.05
+    ) -> List[Tuple[Key, Key, int]]:
         """
-        Build a `TrajectorySet` from **degrees** (RA/DEC), **arcseconds** (uncertainties),
-        and **MJD (TT)** for epochs.
+        Find the detections placed in more than one trajectory.
 
-        Internally converts once to radians, then ingests.
+        Two observations of different trajectories are the same detection when their
+        epochs differ by at most `time_tol_days`, their positions by at most
+        `pos_tol_arcsec` and their sites are the same (geocentric positions within 1 km).
+        Observations are bucketed by epoch and position, so the search scales linearly
+        with the size of the set; it runs with the GIL released.
 
         Parameters
         -----------------
-        pyoutfit : PyOutfit
-            Global environment (ephemerides, observers, error model).
-        trajectory_id : NDArray[np.uint32]
-            `np.uint32` array — one ID per observation.
-        ra_deg : NDArray[np.float64]
-            `np.float64` array — Right Ascension in **degrees**.
-        dec_deg : NDArray[np.float64]
-            `np.float64` array — Declination in **degrees**.
-        error_ra_arcsec : float
-            1-σ RA uncertainty (**arcseconds**) applied to the batch.
-        error_dec_arcsec : float
-            1-σ DEC uncertainty (**arcseconds**) applied to the batch.
-        mjd_tt : NDArray[np.float64]
-            `np.float64` array — epochs in **MJD (TT)** (days).
-        observer : Observer
-            Single observing site for the whole batch.
+        time_tol_days : float, default 1e-6
+            Epoch tolerance, in days (~86 ms).
+        pos_tol_arcsec : float, default 0.05
+            Position tolerance, in arcseconds.
 
         Returns
         ----------
-        TrajectorySet
-            A new `TrajectorySet` populated from the provided inputs.
+        list[tuple[Key, Key, int]]
+            One `(traj_id_a, traj_id_b, n_shared)` tuple per pair of trajectories sharing
+            detections, `traj_id_a` first in sorted id order, `n_shared` the number of
+            matching pairs of observations. Sorted by ids.
 
         Raises
         ----------
         ValueError
-            if input arrays have mismatched lengths.
+            If a tolerance is negative or not finite.
 
         See also
         ------------
-        * `from_numpy_radians` — Zero-copy variant for radian inputs.
+        * `remove_shared` – Remove the shared copies.
+        * `deduplicate` – Duplicates within a trajectory.
         """
         ...
 
-    # --- Ingestion from files ---
-    @staticmethod
-    def new_from_mpc_80col(
-        pyoutfit: PyOutfit,
-        path: PathLike,
-    ) -> "TrajectorySet":
+    def remove_shared(
+        self,
+        keep: Literal["first", "last", "none"] = "first",
+        time_tol_days: float = 1e-6,
+        pos_tol_arcsec: float = 0.05,
+    ) -> Dict[Key, int]:
         """
-        Build a `TrajectorySet` from a **MPC 80-column** file.
+        Remove the detections placed in more than one trajectory, in place.
+
+        Shared detections are found as in `find_shared_observations`. Trajectories left
+        without observations are removed.
 
         Parameters
         -----------------
-        pyoutfit : PyOutfit
-            Global environment (ephemerides, observers, error model).
-        path : PathLike
-            File path (`str` or Path from pathlib) to a MPC 80-column text file.
+        keep : {"first", "last", "none"}, default "first"
+            `"first"` keeps a shared detection in the trajectory with the smaller id and
+            removes it from the others, `"last"` keeps it in the trajectory with the
+            larger id, `"none"` removes every copy.
+        time_tol_days : float, default 1e-6
+            Epoch tolerance, in days.
+        pos_tol_arcsec : float, default 0.05
+            Position tolerance, in arcseconds.
 
         Returns
         ----------
-        TrajectorySet
-            A new `TrajectorySet` populated from the file contents.
+        dict[Key, int]
+            Trajectories that lost observations, mapped to the number removed.
 
-        Notes
+        Raises
         ----------
-        * Mirrors the Rust API semantics and may **panic** on parse errors.
-
-        See also
-        ------------
-        * `add_from_mpc_80col` — Append a second 80-column file into an existing set.
-        * `new_from_ades` — Create from ADES JSON/XML.
+        ValueError
+            If `keep` is unknown or a tolerance is negative or not finite.
         """
         ...
 
-    def add_from_mpc_80col(
-        self,
-        pyoutfit: PyOutfit,
-        path: PathLike,
-    ) -> None:
+    def drop_rejected(self) -> Dict[Key, int]:
         """
-        Append observations from a **MPC 80-column** file into this set.
+        Drop the observations flagged as rejected by their ADES file.
 
-        Parameters
-        -----------------
-        pyoutfit : PyOutfit
-            Global environment (ephemerides, observers, error model).
-        path : PathLike
-            File path (`str` or Path from pathlib) to a MPC 80-column text file.
+        Only sets read with `include_rejected=True` hold such observations (see
+        `from_ades_xml`); trajectories left without observations are removed.
 
         Returns
         ----------
-        None
-            The internal map is updated in place.
+        dict
+            Trajectories that had rejected observations, mapped to the number of
+            observations removed.
+        """
+        ...
 
-        Notes
-        ----------
-        * **No de-duplication** is performed; avoid ingesting the same file twice.
+    def memory_usage(self) -> Dict[str, int]:
+        """
+        Memory held by the set, in bytes, per category.
 
-        See also
-        ------------
-        * `new_from_mpc_80col` — Create a brand-new set from a single file.
+        The figures come from the sizes of the stored types and the capacities of
+        the containers, not from an estimate per observation.
+
+        Returns
+        ----------
+        dict[str, int]
+            * `observations` – astrometry (angles, uncertainties, epochs);
+            * `prepared_geometry` – observer positions computed at ingestion;
+            * `observer_interning` – per-observation indices into the environment's
+              observer registry (the registry itself is not counted);
+            * `spare_capacity` – allocated but unused observation slots;
+            * `trajectory_overhead` – hash table, unused inline slots, string keys;
+            * `total` – sum of the above.
         """
         ...
 
-    @staticmethod
-    def new_from_ades(
-        pyoutfit: PyOutfit,
-        path: PathLike,
-        error_ra_arcsec: Optional[float],
-        error_dec_arcsec: Optional[float],
-    ) -> "TrajectorySet":
+    def shrink_to_fit(self) -> int:
         """
-        Build a `TrajectorySet` from an **ADES** file (JSON or XML).
+        Release the capacity over-allocated during ingestion.
 
-        Parameters
-        -----------------
-        pyoutfit : PyOutfit
-            Global environment (ephemerides, observers, error model).
-        path : PathLike
-            File path (`str` or Path from pathlib) to an ADES JSON/XML file.
-        error_ra_arcsec : Optional[float]
-            Optional global RA 1-σ (arcsec) if not specified per row.
-        error_dec_arcsec : Optional[float]
-            Optional global DEC 1-σ (arcsec) if not specified per row.
+        Observations are left untouched; only their containers are reallocated.
 
         Returns
         ----------
-        TrajectorySet
-            A new `TrajectorySet` populated from the ADES file.
+        int
+            Bytes released, as accounted by `memory_usage()["total"]`.
+        """
+        ...
 
-        Notes
-        ----------
-        * Error-handling policy follows the underlying parser (may log or panic).
+    def extend(self, other: "TrajectorySet") -> None:
+        """
+        Append the trajectories of another set to this one.
 
-        See also
-        ------------
-        * `add_from_ades` — Append ADES observations into an existing set.
+        Observations of a trajectory id present in both sets are merged in canonical
+        order (see `Observations.canonicalize`). Each observation keeps the site it was ingested
+        with, so sets ingested with `topocentric=True` and `topocentric=False` can be
+        combined.
+
+        Parameters
+        -----------------
+        other : TrajectorySet
+            Set to append; left unchanged.
         """
         ...
 
-    def add_from_ades(
-        self,
-        pyoutfit: PyOutfit,
-        path: PathLike,
-        error_ra_arcsec: Optional[float],
-        error_dec_arcsec: Optional[float],
+    def merge(
+        self, other: "TrajectorySet", on_conflict: Literal["concat", "error"] = "concat"
     ) -> None:
         """
-        Append observations from an **ADES** file (JSON/XML) into this set.
+        Move the trajectories of another set into this one.
+
+        Unlike `extend`, the observations are moved rather than copied, and `other` is
+        left empty (its ingestion counters and photometry included). Ids are compared as
+        stored: the int `5` and the string `"5"` are distinct trajectories.
 
         Parameters
         -----------------
-        pyoutfit : PyOutfit
-            Global environment (ephemerides, observers, error model).
-        path : PathLike
-            File path (`str` or Path from pathlib) to an ADES JSON/XML file.
-        error_ra_arcsec : Optional[float]
-            Optional global RA 1-σ (arcsec) if not specified per row.
-        error_dec_arcsec : Optional[float]
-            Optional global DEC 1-σ (arcsec) if not specified per row.
+        other : TrajectorySet
+            Set to empty into this one (e.g. a per-night set built by a worker).
+        on_conflict : {"concat", "error"}, default "concat"
+            `"concat"` merges the observations of an id present in both sets in canonical
+            order, as `extend`; `"error"` raises instead.
 
-        Returns
+        Raises
         ----------
-        None
-            The internal map is updated in place.
+        ValueError
+            With `on_conflict="error"`, naming the ids present in both sets (both sets are
+            left unchanged); when `other` is this set; on an unknown `on_conflict`.
+        """
+        ...
 
-        Notes
-        ----------
-        * **No de-duplication** is performed; avoid re-ingesting the same file.
+    def remap_ids(self, mapping: Mapping[Key, Key]) -> None:
+        """
+        Rename trajectories in place.
 
-        See also
-        ------------
-        * `new_from_ades` — Create a brand-new set from a single ADES file.
+        The mapping is checked before anything is renamed. `duplicates_removed` and
+        `invalid_rows_dropped` follow the new ids, as do the results of the IODs run
+        afterwards.
+
+        Parameters
+        -----------------
+        mapping : dict
+            `{old_id: new_id}` (`int` and/or `str` ids). Ids may be exchanged
+            (`{1: 2, 2: 1}`).
+
+        Raises
+        ----------
+        KeyError
+            If an `old_id` is not in the set.
+        ValueError
+            If a `new_id` is held by a trajectory that is not renamed, is given to several
+            trajectories, or is neither an `int` nor a `str`.
+        TypeError
+            If `mapping` is not a `dict`.
         """
         ...
 
-    # --- Batch IOD ---
-    def estimate_all_orbits(
-        self,
-        env: PyOutfit,
-        params: IODParams,
-        seed: Optional[int] = ...,
-    ) -> Tuple[Dict[Any, Tuple[GaussResult, float]], Dict[Any, str]]:
+    def offset_ids(self, offset: int) -> None:
         """
-        Estimate the best orbit for **all trajectories** in this set.
+        Add `offset` to every integer trajectory id, in place, e.g. to keep the ids of
+        several campaigns apart before a `merge`. `str` ids are left unchanged.
 
-        Runs Gauss-based IOD for each trajectory using the provided environment
-        and parameters. Internally creates a RNG:
-        - if `seed` is provided → deterministic `StdRng::seed_from_u64(seed)`;
-        - else → `StdRng::from_os_rng()`.
+        Parameters
+        -----------------
+        offset : int
+            Added to the `int` ids (64-bit ones included), possibly negative.
 
-        Cancellation
+        Raises
         ----------
-        The computation periodically checks for `KeyboardInterrupt` (Ctrl-C). 
-        This work only if parallel is disabled (`params.do_parallel() == False`).
-        If parallel is enabled, the computation cannot be interrupted and you will need to kill the process manually.
+        ValueError
+            Before anything is renamed, when an id would become negative or exceed 64
+            bits, or collide with a `str` id of the set.
+        """
+        ...
 
-        If cancellation is triggered, partial results accumulated so far are returned:
+    def subset(
+        self, ids: Union[Iterable[Key], NDArray[np.integer]], strict: Optional[bool] = None
+    ) -> "TrajectorySet":
+        """
+        New set holding copies of the trajectories `ids`; this set is left untouched.
 
-        - the first dict contains successful `(GaussResult, rms)` per object,
-        - the second dict contains error messages per object.
+        Only the selected observations are copied. `duplicates_removed` and
+        `invalid_rows_dropped` keep the entries of the selected ids, while
+        `patched_sigma_count()` (not tracked per trajectory) starts at 0.
 
         Parameters
         -----------------
-        env : PyOutfit
+        ids : iterable of int or str, or numpy.ndarray
+            Trajectory ids to keep; repeated ids are selected once.
+        strict : bool, optional
+            Missing ids are left out with a `FallbackWarning` naming them (`missing_ids`
+            check), or raise `StrictModeError` in strict mode. `None` follows
+            `py_outfit.set_strict`.
+
+        Returns
+        ----------
+        TrajectorySet
+            The selected trajectories.
+
+        Raises
+        ----------
+        StrictModeError
+            In strict mode, when some ids are not in the set.
+        TypeError
+            If `ids` is a single `str` or is not iterable.
+        ValueError
+            If an id is neither an integer nor a `str`.
+        """
+        ...
+
+    def sample(self, n: int, seed: Optional[int] = None) -> "TrajectorySet":
+        """
+        New set holding copies of `n` trajectories drawn at random, without replacement.
+
+        Parameters
+        -----------------
+        n : int
+            Number of trajectories, at most `len(self)`.
+        seed : int, optional
+            The same seed draws the same ids from the same set, whatever its internal
+            order. `None` uses a random seed.
+
+        Returns
+        ----------
+        TrajectorySet
+            The sampled trajectories, as with `subset`; this set is left untouched.
+
+        Raises
+        ----------
+        ValueError
+            If `n` exceeds the number of trajectories.
+        """
+        ...
+
+    def filter_by_obs_count(
+        self, min_obs: int = 3, max_obs: Optional[int] = None, inplace: bool = False
+    ) -> Tuple["TrajectorySet", list[Key]]:
+        """
+        Keep the trajectories whose number of observations lies in `[min_obs, max_obs]`.
+
+        Gauss IOD needs at least 3 observations, while very long trajectories make the
+        triplet selection slow; `get_traj_stat()` summarizes the distribution of counts.
+
+        Parameters
+        -----------------
+        min_obs : int, default 3
+            Smallest number of observations kept.
+        max_obs : int, optional
+            Largest number kept; `None` for no upper bound.
+        inplace : bool, default False
+            Remove the other trajectories from this set instead of copying the kept ones
+            into a new set (as `subset`).
+
+        Returns
+        ----------
+        tuple[TrajectorySet, list[Key]]
+            The filtered set (this set when `inplace=True`) and the sorted ids of the
+            trajectories left out, e.g. for logging.
+
+        Raises
+        ----------
+        ValueError
+            If `max_obs` is below `min_obs`.
+        """
+        ...
+
+    def filter_by_time(
+        self,
+        mjd_min: Optional[float] = None,
+        mjd_max: Optional[float] = None,
+        drop_empty: bool = True,
+        min_obs: int = 1,
+    ) -> "TrajectorySet":
+        """
+        New set keeping, in every trajectory, the observations of a time window.
+
+        Parameters
+        -----------------
+        mjd_min, mjd_max : float, optional
+            Bounds of the window (MJD TT, inclusive); `None` leaves the window open on
+            that side.
+        drop_empty : bool, default True
+            Leave out the trajectories with fewer than `min_obs` observations in the
+            window; with `False` every trajectory is kept, possibly empty.
+        min_obs : int, default 1
+            Threshold of `drop_empty` (1: only the empty trajectories are left out).
+
+        Returns
+        ----------
+        TrajectorySet
+            The filtered set, empty when no trajectory qualifies; this set is left
+            untouched.
+
+        Raises
+        ----------
+        ValueError
+            On a NaN bound, or when `mjd_min > mjd_max`.
+
+        See also
+        ------------
+        * `Observations.filter_by_time` – Same window on a single trajectory.
+        """
+        ...
+
+    def filter_by_uncertainty(
+        self, max_sigma_arcsec: float, drop_trajectories_below: int = 3
+    ) -> Dict[str, Any]:
+        """
+        Remove, in place, the observations whose RA or Dec uncertainty exceeds a
+        threshold, then the trajectories left with too few observations.
+
+        Parameters
+        -----------------
+        max_sigma_arcsec : float
+            Largest uncertainty kept, in arcseconds.
+        drop_trajectories_below : int, default 3
+            Trajectories with fewer observations than this once filtered are removed,
+            whether they lost observations or not (3 is the minimum of a Gauss IOD); 0
+            keeps them all, possibly empty.
+
+        Returns
+        ----------
+        dict
+            `observations_dropped` (every observation removed from the set, those of the
+            dropped trajectories included), `trajectories_dropped` and the sorted
+            `dropped_ids`.
+
+        Raises
+        ----------
+        ValueError
+            On a NaN or negative threshold, before anything is removed.
+
+        See also
+        ------------
+        * `Observations.filter_by_uncertainty` – Same threshold on a single trajectory.
+        """
+        ...
+
+    def split_at(self, mjd: float) -> Tuple["TrajectorySet", "TrajectorySet"]:
+        """
+        Split every trajectory at an epoch into two new sets.
+
+        Parameters
+        -----------------
+        mjd : float
+            Epoch of the cut (MJD TT): observations strictly before it go to the first
+            set, the others to the second.
+
+        Returns
+        ----------
+        tuple[TrajectorySet, TrajectorySet]
+            `(before, after)`; a trajectory lying entirely on one side of the cut only
+            appears in that set. This set is left untouched.
+
+        Raises
+        ----------
+        ValueError
+            If `mjd` is NaN.
+        """
+        ...
+
+    def split_fraction(
+        self, frac: float, by: Literal["time", "count"] = "time"
+    ) -> Tuple["TrajectorySet", "TrajectorySet"]:
+        """
+        Split every trajectory into its earliest observations and the rest, e.g. to fit
+        orbits on the first nights and check their predictions on the others.
+
+        Parameters
+        -----------------
+        frac : float
+            Fraction kept for fitting, in `[0, 1]`.
+        by : {"time", "count"}, default "time"
+            `"time"` keeps the observations within the first `frac` of the time span of
+            each trajectory; `"count"` keeps its first `ceil(frac * n)` observations.
+
+        Returns
+        ----------
+        tuple[TrajectorySet, TrajectorySet]
+            `(fit, holdout)`, as with `split_at`.
+
+        Raises
+        ----------
+        ValueError
+            If `frac` lies outside `[0, 1]` or `by` is unknown.
+        """
+        ...
+
+    def sort_observations(self) -> int:
+        """
+        Sort the observations of every trajectory by epoch, in place.
+
+        The loaders already store every trajectory in canonical order (epoch, then RA,
+        Dec and site), so this only reorders a set whose invariant was broken; it can be
+        called as an explicit guarantee before a batch IOD.
+
+        Returns
+        ----------
+        int
+            Number of trajectories that were out of time order.
+        """
+        ...
+
+    def is_time_sorted(self) -> bool:
+        """
+        Whether every trajectory is stored in time order.
+
+        Every construction path sorts the observations of each trajectory by epoch
+        (observations sharing an epoch keep their input order) and every append merges
+        the new observations into place; this method scans the set to verify it.
+
+        Returns
+        ----------
+        bool
+            `True` when the epochs of each trajectory are non-decreasing.
+        """
+        ...
+
+    def get_traj_stat(self) -> str:
+        """
+        Pretty-printed statistics about observations per trajectory.
+
+        Returns
+        ----------
+        str
+            A formatted `str` (histogram/stats), or
+            `"No trajectories available."` if empty.
+
+        See also
+        ------------
+        * `obs_count_stats_dict` – The same statistics as a dict, for pipelines.
+        """
+        ...
+
+    def obs_count_stats_dict(self, histogram: bool = False) -> Dict[str, Any]:
+        """
+        Statistics of `get_traj_stat` as a dict, for programmatic thresholds.
+
+        Parameters
+        -----------------
+        histogram : bool, default False
+            Add a `"histogram"` entry mapping each number of observations to the number
+            of trajectories having it, by increasing number of observations.
+
+        Returns
+        ----------
+        dict
+            `min`, `p25`, `median`, `p95` and `max` (`int`, nearest-rank percentiles as
+            in `get_traj_stat`, `None` for an empty set), `mean` (`float`, NaN for an
+            empty set), `total` (number of observations) and `n_trajectories`.
+        """
+        ...
+
+    def time_span(self) -> Tuple[float, float, float]:
+        """
+        Time coverage of the whole set, e.g. to check it against
+        `PyOutfit.ephemeris_time_range()`.
+
+        Returns
+        ----------
+        tuple[float, float, float]
+            `(mjd_min, mjd_max, span_days)` over every observation (MJD TT); NaNs for a
+            set without observations.
+        """
+        ...
+
+    def epoch_histogram(
+        self, bin_days: float = 1.0
+    ) -> Tuple[NDArray[np.float64], NDArray[np.int64]]:
+        """
+        Histogram of the epochs of every observation, computed in Rust.
+
+        Parameters
+        -----------------
+        bin_days : float, default 1.0
+            Width of the bins, in days. The edges are multiples of `bin_days` (whole MJDs
+            by default).
+
+        Returns
+        ----------
+        tuple[numpy.ndarray, numpy.ndarray]
+            `(edges, counts)`: `float64` bin edges (MJD TT, one more than the bins) and
+            `int64` counts, the last bin including its right edge. Both are empty for a
+            set without observations.
+
+        Raises
+        ----------
+        ValueError
+            If `bin_days` is not a positive finite number, or would make more than 10
+            million bins.
+        """
+        ...
+
+    # --- Ingestion from NumPy ---
+    @staticmethod
+    def from_numpy_radians(
+        pyoutfit: PyOutfit,
+        trajectory_id: TrajectoryIds,
+        ra: RealArray,
+        dec: RealArray,
+        error_ra_rad: Union[float, RealArray],
+        error_dec_rad: Union[float, RealArray],
+        mjd_tt: Epochs,
+        observer: Observer,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        time_scale: Optional[Literal["TT", "TAI", "UTC"]] = None,
+        epoch_format: Literal["mjd", "jd"] = "mjd",
+        mag: Optional[RealArray] = None,
+        band: Optional[Union[Sequence[Optional[str]], NDArray[np.str_], NDArray[np.object_]]] = None,
+        dedup: Dedup = False,
+        validate: bool = True,
+        sort: bool = False,
+        drop_invalid: bool = False,
+        parallel: bool = False,
+        n_threads: Optional[int] = None,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from arrays already in **radians** (RA/DEC) and **MJD (TT)**.
+
+        This path uses a zero-copy ingestion under the hood.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit 
+            Global environment (ephemerides, observers, error model).
+        trajectory_id : TrajectoryIds
+            One ID per observation: an integer array of any dtype and layout
+            (negative ids raise `ValueError`), or string ids (`list[str]`, NumPy string
+            array). An object array (as pandas stores ids) or a list holds Python ints,
+            read as integer ids, or strings; an element of another type (`None`,
+            `float`, ...) or not of the type of the first raises `TypeError` naming it.
+            The ids are the keys of the set and of the `estimate_all_orbits` results;
+            64-bit ids are returned as full Python ints.
+        ra : RealArray
+            Real array — Right Ascension in **radians**.
+        dec : RealArray
+            Real array — Declination in **radians**.
+        error_ra_rad : float | RealArray
+            1-σ RA uncertainty (**radians**): one value applied to the whole batch, or
+            an array with one value per observation.
+        error_dec_rad : float | RealArray
+            1-σ DEC uncertainty (**radians**), as `error_ra_rad`.
+        mjd_tt : Epochs
+            Real array — epochs in **MJD** (or JD with
+            `epoch_format="jd"`, days) on the `time_scale` scale; or ISO-8601 timestamp
+            strings (`YYYY-MM-DD[Thh:mm[:ss[.fff]]]` with an optional `Z` or `±hh[:mm]`
+            offset), parsed in Rust. An unparsable string raises `ValueError` naming its
+            row and value.
+            A `datetime64[ns]` or `datetime64[us]` array (Parquet, pandas) is also
+            accepted and converted in Rust to the precision of the `float64` MJD
+            (~0.5 µs); `NaT` entries raise `ValueError` listing their rows.
+        observer : Observer
+            Single observing site for the whole batch.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive or non-finite uncertainties (e.g. `0` or `-1`
+            used as "unknown" by some catalogues). The substituted values are the ones
+            later reported by `Observations`; their count is `patched_sigma_count`.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (**radians**), required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call. Substituted uncertainties and trajectories given out of time order
+            (sorted at ingestion) are reported with `FallbackWarning`, or rejected with
+            `StrictModeError` in strict mode.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre, see
+            `from_numpy_degrees`.
+        time_scale : {"TT", "TAI", "UTC"}, optional
+            Time scale of `mjd_tt`: TT by default for numbers, UTC for timestamps.
+            TAI and UTC epochs are converted to TT at ingestion (UTC with the IERS
+            leap-second table, `23:59:60` accepted on leap-second days); UTC epochs before
+            1972-01-01 are rejected.
+        epoch_format : {"mjd", "jd"}, default "mjd"
+            Day count of `mjd_tt`: Julian Dates are converted with `MJD = JD - 2400000.5`.
+            Epochs above 2 000 000 given as MJD, or below given as JD, raise `ValueError`
+            since they are almost certainly the other format. Timestamps only take
+            `"mjd"`.
+        mag : RealArray, optional
+            Magnitude of every observation (NaN where unknown), kept alongside the
+            astrometry and returned by `Observations.magnitudes()`.
+        band : sequence of str, optional
+            Photometric band of every observation (`None` where unknown), as `mag`.
+        dedup : Dedup, default False
+            Drop duplicate observations, see `from_numpy_degrees`.
+        validate : bool, default True
+            Check the rows before ingesting them, see `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+        drop_invalid : bool, default False
+            Drop masked rows and rows with NaN or infinite values, see
+            `from_numpy_degrees`.
+        parallel : bool, default False
+            Group the rows into trajectories on several threads, see
+            `from_numpy_degrees`.
+        n_threads : int, optional
+            Number of worker threads with `parallel=True`, see `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet` populated from the provided inputs.
+
+        Raises
+        ----------
+        ValueError
+            if input arrays (uncertainty arrays included) have mismatched lengths, if
+            rows hold invalid values (`validate`; the message lists the failing columns
+            and rows), if uncertainties are non-positive with `bad_sigma="error"`, or if
+            an epoch lies outside `pyoutfit.ephemeris_time_range()`.
+        """
+        ...
+
+    @staticmethod
+    def from_numpy_degrees(
+        pyoutfit: PyOutfit,
+        trajectory_id: TrajectoryIds,
+        ra_deg: RealArray,
+        dec_deg: RealArray,
+        error_ra_arcsec: Union[float, RealArray],
+        error_dec_arcsec: Union[float, RealArray],
+        mjd_tt: Epochs,
+        observer: Observer,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        time_scale: Optional[Literal["TT", "TAI", "UTC"]] = None,
+        epoch_format: Literal["mjd", "jd"] = "mjd",
+        mag: Optional[RealArray] = None,
+        band: Optional[Union[Sequence[Optional[str]], NDArray[np.str_], NDArray[np.object_]]] = None,
+        dedup: Dedup = False,
+        validate: bool = True,
+        sort: bool = False,
+        drop_invalid: bool = False,
+        parallel: bool = False,
+        n_threads: Optional[int] = None,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from **degrees** (RA/DEC), **arcseconds** (uncertainties),
+        and **MJD (TT)** for epochs.
+
+        The angles are converted to radians as the observations are streamed into the
+        set, so no converted copy of the arrays is allocated.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        trajectory_id : TrajectoryIds
+            One ID per observation: an integer array of any dtype and layout
+            (negative ids raise `ValueError`), or string ids (`list[str]`, NumPy string
+            array). An object array (as pandas stores ids) or a list holds Python ints,
+            read as integer ids, or strings; an element of another type (`None`,
+            `float`, ...) or not of the type of the first raises `TypeError` naming it.
+            The ids are the keys of the set and of the `estimate_all_orbits` results;
+            64-bit ids are returned as full Python ints.
+        ra_deg : RealArray
+            Real array — Right Ascension in **degrees**.
+        dec_deg : RealArray
+            Real array — Declination in **degrees**.
+        error_ra_arcsec : float | RealArray
+            1-σ RA uncertainty (**arcseconds**): one value applied to the batch, or an
+            array with one value per observation (heteroscedastic astrometry).
+        error_dec_arcsec : float | RealArray
+            1-σ DEC uncertainty (**arcseconds**), as `error_ra_arcsec`.
+        mjd_tt : Epochs
+            Real array — epochs in **MJD** (or JD with
+            `epoch_format="jd"`, days) on the `time_scale` scale; or ISO-8601 timestamp
+            strings (`YYYY-MM-DD[Thh:mm[:ss[.fff]]]` with an optional `Z` or `±hh[:mm]`
+            offset), parsed in Rust. An unparsable string raises `ValueError` naming its
+            row and value.
+            A `datetime64[ns]` or `datetime64[us]` array (Parquet, pandas) is also
+            accepted and converted in Rust to the precision of the `float64` MJD
+            (~0.5 µs); `NaT` entries raise `ValueError` listing their rows.
+        observer : Observer
+            Single observing site for the whole batch.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive or non-finite uncertainties (e.g. `0` or `-1`
+            used as "unknown" by some catalogues). The substituted values are the ones
+            later reported by `Observations`; their count is `patched_sigma_count`.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (**arcseconds**), required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call. Substituted uncertainties and trajectories given out of time order
+            (sorted at ingestion) are reported with `FallbackWarning`, or rejected with
+            `StrictModeError` in strict mode.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre: the rows are
+            attributed to the geocentric counterpart of `observer` (same name suffixed
+            with " (geocentric)", same accuracies) and the topocentric correction is not
+            applied again by the IOD, the residuals or the predictions.
+        time_scale : {"TT", "TAI", "UTC"}, optional
+            Time scale of `mjd_tt`: TT by default for numbers, UTC for timestamps.
+            TAI and UTC epochs are converted to TT at ingestion (UTC with the IERS
+            leap-second table, `23:59:60` accepted on leap-second days); UTC epochs before
+            1972-01-01 are rejected.
+        epoch_format : {"mjd", "jd"}, default "mjd"
+            Day count of `mjd_tt`: Julian Dates are converted with `MJD = JD - 2400000.5`.
+            Epochs above 2 000 000 given as MJD, or below given as JD, raise `ValueError`
+            since they are almost certainly the other format. Timestamps only take
+            `"mjd"`.
+        mag : RealArray, optional
+            Magnitude of every observation (NaN where unknown), kept alongside the
+            astrometry and returned by `Observations.magnitudes()`.
+        band : sequence of str, optional
+            Photometric band of every observation (`None` where unknown), as `mag`.
+        dedup : Dedup, default False
+            `True` drops the duplicate observations of each trajectory (epochs within
+            1e-7 day and positions within 1 mas of an earlier observation); a
+            `(time_tol, sep_mas)` pair (days, milliarcseconds) sets other tolerances.
+            The rows removed per trajectory are counted in `duplicates_removed`.
+        validate : bool, default True
+            Check every row before the ingestion: RA in [0, 360) and Dec in [-90, 90]
+            (radians: [0, 2π) and [-π/2, π/2]), epochs between the years 1800 and 2200
+            and, with `bad_sigma="error"`, positive per-row uncertainties, all finite.
+            `False` skips the check for data known to be clean.
+        sort : bool, default False
+            With `False`, trajectories given out of time order are reported
+            (`FallbackWarning`, `StrictModeError` in strict mode) and sorted. `True`
+            declares the rows unordered (e.g. straight out of a `groupby`): every
+            trajectory is sorted by epoch without a report, strict mode included.
+        drop_invalid : bool, default False
+            `True` drops the rows holding NaN or infinite values (`NaT` for `datetime64`
+            epochs) in the coordinates, the epochs or, with `bad_sigma="error"`, the
+            per-row uncertainties, instead of rejecting them. Independently of it, every
+            array argument may be a `numpy.ma.MaskedArray` whose masked rows are always
+            dropped (a masked `mag` only marks an unknown magnitude). The remaining rows
+            stay aligned across the columns; the rows dropped per trajectory are counted
+            in `invalid_rows_dropped`.
+        parallel : bool, default False
+            `True` partitions the rows by a hash of their trajectory id across worker
+            threads, each building its own trajectories, and merges them. The site
+            positions are still computed once per distinct epoch, and the set is
+            identical to the serial build, observation order within each trajectory
+            included. Worth it for large batches (millions of rows).
+        n_threads : int, optional
+            Number of worker threads with `parallel=True`. Default: one per core (the
+            global rayon pool).
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet` populated from the provided inputs.
+
+        Raises
+        ----------
+        ValueError
+            if input arrays (uncertainty arrays included) have mismatched lengths, if
+            rows hold invalid values (`validate`; the message lists the failing columns
+            and rows), if uncertainties are non-positive with `bad_sigma="error"`, if
+            an epoch lies outside `pyoutfit.ephemeris_time_range()`, or if `n_threads`
+            is zero or given without `parallel=True`.
+
+        See also
+        ------------
+        * `from_numpy_radians` — Zero-copy variant for radian inputs.
+        """
+        ...
+
+    @staticmethod
+    def from_numpy_multi_site(
+        pyoutfit: PyOutfit,
+        trajectory_id: TrajectoryIds,
+        ra: RealArray,
+        dec: RealArray,
+        error_ra: Union[float, RealArray],
+        error_dec: Union[float, RealArray],
+        mjd_tt: Epochs,
+        observer_codes: Union[NDArray[np.integer], Sequence[str]],
+        observers: Optional[Sequence[Observer]] = None,
+        units: Literal["degrees", "radians"] = "degrees",
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        time_scale: Optional[Literal["TT", "TAI", "UTC"]] = None,
+        epoch_format: Literal["mjd", "jd"] = "mjd",
+        mag: Optional[RealArray] = None,
+        band: Optional[Union[Sequence[Optional[str]], NDArray[np.str_], NDArray[np.object_]]] = None,
+        dedup: Dedup = False,
+        validate: bool = True,
+        sort: bool = False,
+        drop_invalid: bool = False,
+        parallel: bool = False,
+        n_threads: Optional[int] = None,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from NumPy arrays whose rows come from several sites.
+
+        Same inputs as `from_numpy_degrees` / `from_numpy_radians`, with one observing
+        site per row instead of one for the whole batch: a linked trajectory mixing
+        e.g. ZTF, ATLAS and Pan-STARRS observations is fitted with the topocentric
+        position of each.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        trajectory_id : TrajectoryIds
+            One ID per observation: an integer array of any dtype and layout
+            (negative ids raise `ValueError`), or string ids (`list[str]`, NumPy string
+            array). An object array (as pandas stores ids) or a list holds Python ints,
+            read as integer ids, or strings; an element of another type (`None`,
+            `float`, ...) or not of the type of the first raises `TypeError` naming it.
+            The ids are the keys of the set and of the `estimate_all_orbits` results;
+            64-bit ids are returned as full Python ints.
+        ra, dec : RealArray
+            Coordinates in **degrees** (`units="degrees"`) or **radians**.
+        error_ra, error_dec : float | RealArray
+            1-σ uncertainties, **arcseconds** with `units="degrees"` and **radians**
+            otherwise: one value for the batch, or one per observation.
+        mjd_tt : Epochs
+            Real array — epochs in **MJD** (or JD with
+            `epoch_format="jd"`, days) on the `time_scale` scale; or ISO-8601 timestamp
+            strings (`YYYY-MM-DD[Thh:mm[:ss[.fff]]]` with an optional `Z` or `±hh[:mm]`
+            offset), parsed in Rust. An unparsable string raises `ValueError` naming its
+            row and value.
+            A `datetime64[ns]` or `datetime64[us]` array (Parquet, pandas) is also
+            accepted and converted in Rust to the precision of the `float64` MJD
+            (~0.5 µs); `NaT` entries raise `ValueError` listing their rows.
+        observer_codes : NDArray[np.integer] | Sequence[str]
+            Site of every row: an integer array (any integer dtype) of positions in
+            `observers`, or MPC observatory codes (list or NumPy string array).
+        observers : Sequence[Observer], optional
+            Sites indexed by integer `observer_codes`; must be omitted with codes.
+        units : {"degrees", "radians"}, default "degrees"
+            Unit of `ra`, `dec` and of the uncertainties (see above).
+        bad_sigma : BadSigmaPolicy, default "error"
+            Policy for non-positive uncertainties, see `from_numpy_degrees`;
+            `"model"` substitutes the accuracy of the site of each row.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (unit of the uncertainties), required with
+            `bad_sigma="value"`.
+        strict : bool, optional
+            Per-call strict mode, see `from_numpy_degrees`.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre, applied to every
+            site (see `from_numpy_degrees`).
+        time_scale : {"TT", "TAI", "UTC"}, optional
+            Time scale of `mjd_tt`: TT by default for numbers, UTC for timestamps.
+            TAI and UTC epochs are converted to TT at ingestion (UTC with the IERS
+            leap-second table, `23:59:60` accepted on leap-second days); UTC epochs before
+            1972-01-01 are rejected.
+        epoch_format : {"mjd", "jd"}, default "mjd"
+            Day count of `mjd_tt`: Julian Dates are converted with `MJD = JD - 2400000.5`.
+            Epochs above 2 000 000 given as MJD, or below given as JD, raise `ValueError`
+            since they are almost certainly the other format. Timestamps only take
+            `"mjd"`.
+        mag : RealArray, optional
+            Magnitude of every observation (NaN where unknown), kept alongside the
+            astrometry and returned by `Observations.magnitudes()`.
+        band : sequence of str, optional
+            Photometric band of every observation (`None` where unknown), as `mag`.
+        dedup : Dedup, default False
+            Drop duplicate observations, see `from_numpy_degrees`; applied once the
+            sites are merged, so a detection reported by two sites is found.
+        validate : bool, default True
+            Check the rows before ingesting them, see `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+        drop_invalid : bool, default False
+            Drop masked rows and rows with NaN or infinite values, see
+            `from_numpy_degrees`.
+        parallel : bool, default False
+            Group the rows into trajectories on several threads, see
+            `from_numpy_degrees`.
+        n_threads : int, optional
+            Number of worker threads with `parallel=True`, see `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new set; each observation keeps the site of its row.
+
+        Raises
+        ----------
+        ValueError
+            On length mismatches (`observer_codes` included), an index out of range of
+            `observers`, an unknown MPC code, integer codes without `observers` (or code
+            strings with them), and for the checks of `from_numpy_degrees`.
+        TypeError
+            If `observer_codes` is neither an integer array nor a sequence of strings.
+        """
+        ...
+
+    @staticmethod
+    def from_numpy_radians_indexed(
+        pyoutfit: PyOutfit,
+        trajectory_id: TrajectoryIds,
+        ra: RealArray,
+        dec: RealArray,
+        error_ra: Union[float, RealArray],
+        error_dec: Union[float, RealArray],
+        mjd_tt: Epochs,
+        observers: Sequence[Observer],
+        observer_idx: NDArray[np.uint16],
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        time_scale: Optional[Literal["TT", "TAI", "UTC"]] = None,
+        epoch_format: Literal["mjd", "jd"] = "mjd",
+        mag: Optional[RealArray] = None,
+        band: Optional[Union[Sequence[Optional[str]], NDArray[np.str_], NDArray[np.object_]]] = None,
+        dedup: Dedup = False,
+        validate: bool = True,
+        sort: bool = False,
+        drop_invalid: bool = False,
+        parallel: bool = False,
+        n_threads: Optional[int] = None,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from **radians** arrays whose rows select their site in a
+        list of observers.
+
+        Integer-indexed form of `from_numpy_multi_site` for heterogeneous batches of
+        custom sites: no code is looked up per row, and the indices are checked against
+        `observers` before the epochs are converted or the rows validated.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        trajectory_id, ra, dec, error_ra, error_dec, mjd_tt
+            As for `from_numpy_radians` (coordinates and uncertainties in **radians**).
+        observers : Sequence[Observer]
+            Sites of the batch.
+        observer_idx : NDArray[np.uint16]
+            Position in `observers` of the site of each row.
+        bad_sigma, bad_sigma_value, strict, topocentric, time_scale, epoch_format, mag, band, dedup, validate, sort, drop_invalid, parallel, n_threads
+            See `from_numpy_multi_site`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new set; each observation keeps the `Observer` selected by its row.
+
+        Raises
+        ----------
+        ValueError
+            For a length mismatch between `ra` and `observer_idx` or an index out of
+            range of `observers` (naming the first offending row), before any other work,
+            and for the checks of `from_numpy_multi_site`.
+        TypeError
+            If `observer_idx` is not a `uint16` array.
+        """
+        ...
+
+    @staticmethod
+    def from_records(
+        env: PyOutfit,
+        records: Iterable[Mapping[str, Any]],
+        observer: Observer,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        time_scale: Optional[Literal["TT", "TAI", "UTC"]] = None,
+        epoch_format: Literal["mjd", "jd"] = "mjd",
+        dedup: Dedup = False,
+        validate: bool = True,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from records, one dict per observation.
+
+        Convenience loader for interactive sessions and tests; the records are gathered
+        into columns and ingested as by `from_numpy_degrees`.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Environment used for the ingestion.
+        records : iterable of dict
+            One mapping per observation with the keys `trajectory_id` (`int` or `str`, the
+            same type for every record), `ra_deg`, `dec_deg` (degrees), `sigma_arcsec`
+            (1-σ uncertainty on both axes, arcseconds) and `mjd_tt` (MJD). `mag` and
+            `band` are optional; missing or `None` means unknown.
+        observer : Observer
+            Single observer for the whole batch.
+        bad_sigma, bad_sigma_value, strict, topocentric, time_scale, epoch_format, dedup, validate
+            See `from_numpy_degrees`; invalid values are reported by record index.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            The new set, keyed by `trajectory_id`.
+
+        Raises
+        ----------
+        KeyError
+            If a record lacks a required key; the message names the record index.
+        TypeError
+            If a record is not a mapping, holds a value of the wrong type, or its
+            `trajectory_id` is not of the type of the previous ones.
+        ValueError
+            For the checks of `from_numpy_degrees`.
+        """
+        ...
+
+    @staticmethod
+    def from_chunks(
+        env: PyOutfit,
+        chunks: Iterable[Union[tuple, Mapping[str, Any]]],
+        observer: Observer,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        time_scale: Optional[Literal["TT", "TAI", "UTC"]] = None,
+        epoch_format: Literal["mjd", "jd"] = "mjd",
+        dedup: Dedup = False,
+        validate: bool = True,
+        sort: bool = False,
+        drop_invalid: bool = False,
+        parallel: bool = False,
+        n_threads: Optional[int] = None,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from an iterator of array chunks, one chunk at a time.
+
+        Streaming counterpart of `from_numpy_degrees` for batches that do not fit in
+        memory at once: each chunk is ingested (GIL released) and merged into the set
+        before the next one is requested.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Environment used for the ingestion.
+        chunks : iterable of tuple or dict
+            Each chunk holds the arguments of `from_numpy_degrees` for a slice of the rows:
+            a tuple `(trajectory_id, ra_deg, dec_deg, error_ra_arcsec, error_dec_arcsec,
+            mjd_tt[, mag[, band]])`, or a dict with those keys.
+        observer : Observer
+            Single observer for the whole batch.
+        bad_sigma, bad_sigma_value, strict, topocentric, time_scale, epoch_format
+            See `from_numpy_degrees`; applied to every chunk.
+        dedup : Dedup, default False
+            Drop duplicate observations, see `from_numpy_degrees`; applied once the
+            chunks are merged, so duplicates spread over two chunks are found.
+        validate : bool, default True
+            Check the rows of every chunk, see `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+        drop_invalid : bool, default False
+            Drop masked rows and rows with NaN or infinite values, see
+            `from_numpy_degrees`; applied to every chunk.
+        parallel : bool, default False
+            Group the rows of each chunk on several threads, see `from_numpy_degrees`.
+        n_threads : int, optional
+            Number of worker threads with `parallel=True`, see `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            The set built from the concatenated chunks: an id spread over several chunks
+            is merged in canonical order, whatever the chunk boundaries.
+
+        Raises
+        ----------
+        KeyError
+            If a dict chunk lacks a required key.
+        TypeError
+            If a chunk is neither a tuple nor a dict, or holds an array of the wrong type.
+        ValueError
+            For the checks of `from_numpy_degrees`, prefixed with the chunk index.
+
+        Notes
+        ----------
+        The time-order check (`FallbackWarning` / `StrictModeError`) runs within each
+        chunk; rows out of order across two chunks are sorted silently.
+        """
+        ...
+
+    @staticmethod
+    def from_observations(
+        env: PyOutfit,
+        mapping: Dict[Key, Observations],
+        observer: Optional[Observer] = None,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from a mapping of trajectory ids to `Observations`.
+
+        Brings trajectories taken out of a set (`ts[key]`, `values()`, result views)
+        back into a set for batch estimation, without a round trip through NumPy.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Environment the observations were ingested with.
+        mapping : dict[int | str, Observations]
+            One trajectory per key; keys become the ids of the set.
+        observer : Observer, optional
+            `None` keeps the site of every observation. Otherwise all observations are
+            attributed to `observer` and their positions recomputed, as with
+            `from_numpy_radians`.
+        bad_sigma : BadSigmaPolicy, default "error"
+            Policy for non-positive uncertainties, see `from_numpy_degrees`.
+        bad_sigma_value : float, optional
+            Replacement uncertainty in **radians**, required with `bad_sigma="value"`.
+        strict : bool, optional
+            Per-call strict mode, see `from_numpy_degrees`.
+        topocentric : bool, default True
+            `False` attributes the rows to the geocentric counterpart of `observer`
+            (see `from_numpy_degrees`); ignored without `observer`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new set with the keys of `mapping`.
+
+        Raises
+        ----------
+        ValueError
+            For an empty trajectory, two keys naming the same id, an unsupported key,
+            and for the checks of `from_numpy_degrees` (ephemeris range, bad sigmas).
+        TypeError
+            If a value is not an `Observations`.
+
+        Notes
+        ----------
+        Without `observer`, observations keep the site and positions computed by the
+        environment that produced them; using them with another environment is not
+        detected.
+        """
+        ...
+
+    @staticmethod
+    def from_arrow_ipc(
+        pyoutfit: PyOutfit,
+        readable: Any,
+        observer: Optional[Observer] = None,
+        columns: Optional[Dict[str, str]] = None,
+        units: Literal["degrees", "radians"] = "degrees",
+        error_ra: Optional[float] = None,
+        error_dec: Optional[float] = None,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from an Arrow stream, one record batch at a time.
+
+        Any object implementing the Arrow PyCapsule stream interface
+        (`__arrow_c_stream__`) is accepted: `pyarrow.RecordBatchReader`, `pyarrow.Table`,
+        ADBC cursors, Polars frames, … Each record batch is ingested like
+        `from_numpy_degrees` / `from_numpy_radians` and merged into the result before the
+        next one is read, so only one batch is materialised at a time.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        readable : Any
+            Arrow stream source.
+        observer : Observer, optional
+            Single observing site for every row. When omitted, each row is attributed to
+            the site whose MPC code is in the `observer` column.
+        columns : dict[str, str], optional
+            Mapping from the fields `tid`, `mjd`, `ra`, `dec`, `observer` to column names.
+            Unmapped fields use their own name (same defaults as the pandas `Schema`).
+        units : {"degrees", "radians"}, default "degrees"
+            Unit of `ra`/`dec`. With `"degrees"`, uncertainties are in **arcseconds**;
+            with `"radians"`, in **radians**.
+        error_ra : float, optional
+            Uniform 1-σ RA uncertainty. `None` leaves it unknown and defers to `bad_sigma`
+            (e.g. `bad_sigma="model"`).
+        error_dec : float, optional
+            Uniform 1-σ DEC uncertainty, same conventions as `error_ra`.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive, non-finite or missing uncertainties.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (in the unit of the uncertainties), required with
+            `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call. Substituted uncertainties and trajectories given out of time order
+            (sorted at ingestion) are reported with `FallbackWarning`, or rejected with
+            `StrictModeError` in strict mode.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre, see
+            `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet`. Rows of one trajectory spread over several batches are
+            concatenated in stream order.
+
+        Raises
+        ----------
+        TypeError
+            if `readable` does not implement `__arrow_c_stream__`.
+        KeyError
+            if a mapped column is missing from a batch.
+        ValueError
+            if `tid` is not an integer column or holds negative ids, on invalid `units`
+            or `columns`, on bad uncertainties with `bad_sigma="error"`, or if an epoch
+            lies outside `pyoutfit.ephemeris_time_range()`.
+
+        Notes
+        ----------
+        * `mjd` holds epochs in **MJD (TT)**, as for the NumPy loaders.
+
+        See also
+        ------------
+        * `from_numpy_degrees` — Same ingestion from in-memory NumPy arrays.
+        """
+        ...
+
+    @staticmethod
+    def from_arrow(
+        env: PyOutfit,
+        record_batch: Any,
+        observer: Observer,
+        columns: Optional[Dict[str, str]] = None,
+        units: Literal["degrees", "radians"] = "degrees",
+        error_ra: Optional[float] = None,
+        error_dec: Optional[float] = None,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from Arrow record batches through the C Data Interface.
+
+        The batches are imported with the Arrow PyCapsule protocol and their columns are
+        read from the Arrow buffers in Rust: no Python-level column extraction happens.
+        `UInt32` ids and radian `Float64` columns are ingested without copy.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        record_batch : Any
+            Object implementing `__arrow_c_array__` (e.g. `pyarrow.RecordBatch`) or
+            `__arrow_c_stream__` (e.g. a `pyarrow.Table` with several chunks, a
+            `RecordBatchReader`), consumed one batch at a time.
+        observer : Observer
+            Observing site of every row.
+        columns : dict[str, str], optional
+            Mapping from the fields `tid`, `mjd`, `ra`, `dec`, `sigma_ra`, `sigma_dec` to
+            column names, as in `from_arrow_ipc`.
+        units : {"degrees", "radians"}, default "degrees"
+            Unit of `ra`/`dec`. With `"degrees"`, uncertainties are in **arcseconds**;
+            with `"radians"`, in **radians**.
+        error_ra : float, optional
+            Uniform 1-σ RA uncertainty. With `None` for both `error_ra` and `error_dec`,
+            the per-row `sigma_ra` / `sigma_dec` columns are read when present; the
+            uncertainties are otherwise unknown and defer to `bad_sigma`.
+        error_dec : float, optional
+            Uniform 1-σ DEC uncertainty, same conventions as `error_ra`.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive, non-finite or missing uncertainties.
+        bad_sigma_value : float, optional
+            Replacement uncertainty, required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode for this call.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre, see
+            `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet`. Rows of one trajectory spread over several batches are
+            concatenated in stream order.
+
+        Raises
+        ----------
+        TypeError
+            if `record_batch` implements neither protocol or is not a record batch, or
+            if a column has an unsupported Arrow type (`tid`: `UInt32`, `UInt64`,
+            `Int32` or `Int64`, others: `Float64`).
+        KeyError
+            if a mapped column is missing from a batch (only one of the uncertainty
+            columns being present counts as missing).
+        ValueError
+            if a column contains nulls, an id is negative, on invalid `units` or
+            `columns`, or on bad uncertainties with `bad_sigma="error"`.
+
+        See also
+        ------------
+        * `from_arrow_ipc` — Stream ingestion through `pyarrow`, with per-row sites.
+        """
+        ...
+
+    @staticmethod
+    def from_csv(
+        env: PyOutfit,
+        path: Union[str, Path],
+        observer: Observer,
+        columns: Optional[Dict[str, Union[str, int]]] = None,
+        units: Literal["degrees", "radians"] = "degrees",
+        delimiter: str = ",",
+        header: bool = True,
+        error_ra: Optional[float] = None,
+        error_dec: Optional[float] = None,
+        on_error: Literal["raise", "skip"] = "raise",
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        dedup: Dedup = False,
+        validate: bool = True,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from a CSV file, parsed in Rust.
+
+        The file is read without pandas and without the GIL; gzip-compressed files are
+        detected from their magic bytes and decompressed on the fly. The selected columns
+        are then ingested as with `from_numpy_degrees` / `from_numpy_radians`.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        path : str | pathlib.Path
+            CSV file, plain or gzip-compressed.
+        observer : Observer
+            Observing site of every row.
+        columns : dict[str, str | int], optional
+            Mapping from the fields `id`, `time`, `ra`, `dec`, `sigma_ra`, `sigma_dec` to a
+            header name or a 0-based column index, e.g.
+            `{"ra": "RA_deg", "dec": "Dec_deg", "time": "mjd", "id": "ssnamenr"}`.
+            Unmapped fields use the names `trajectory_id`, `mjd_tt`, `ra`, `dec`,
+            `sigma_ra`, `sigma_dec`.
+        units : {"degrees", "radians"}, default "degrees"
+            Unit of `ra`/`dec`. With `"degrees"`, uncertainties are in **arcseconds**;
+            with `"radians"`, in **radians**.
+        delimiter : str, default ","
+            Field delimiter, a single ASCII character.
+        header : bool, default True
+            Whether the first row names the columns. Without header, every field must
+            be selected by index.
+        error_ra, error_dec : float, optional
+            Uniform 1-σ uncertainties. With `None` for both, the `sigma_ra` /
+            `sigma_dec` columns are read when present (or mapped); the uncertainties are
+            otherwise unknown and defer to `bad_sigma`.
+        on_error : {"raise", "skip"}, default "raise"
+            What to do with rows that cannot be read (missing field, empty id, value
+            that is not a number): raise `ValueError` listing them by line number, or
+            skip them with a `FallbackWarning`.
+        bad_sigma, bad_sigma_value, strict, topocentric, dedup, validate
+            See `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet`, keyed by integer ids when every id in the file is a
+            non-negative integer, by string ids otherwise.
+
+        Raises
+        ----------
+        OSError
+            if the file cannot be read.
+        KeyError
+            if a named column is missing from the header.
+        ValueError
+            on malformed rows (`on_error="raise"`), an unterminated quoted field, invalid
+            gzip data, text that is not UTF-8, a column name with `header=False`, or
+            invalid `units`, `delimiter` or `columns`.
+
+        Notes
+        ----------
+        Fields follow RFC 4180 quoting (a quoted field may hold the delimiter, doubled
+        quotes and line breaks) and are stripped of surrounding blanks; blank lines and
+        lines starting with `#` are skipped. Epochs are MJD (TT) numbers.
+        """
+        ...
+
+    @staticmethod
+    def from_parquet(
+        env: PyOutfit,
+        path: Union[str, Path],
+        observer: Optional[Observer] = None,
+        columns: Optional[Dict[str, str]] = None,
+        units: Literal["degrees", "radians"] = "degrees",
+        error_ra: Optional[float] = None,
+        error_dec: Optional[float] = None,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        batch_size: int = 65536,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from a Parquet file, decoded in Rust one batch at a time.
+
+        The file is read row group after row group and only the mapped columns are
+        decoded; each record batch is ingested as with `from_arrow` before the next one
+        is read, so memory stays bounded for files far larger than RAM. Neither pandas
+        nor pyarrow is needed.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        path : str | pathlib.Path
+            Parquet file.
+        observer : Observer, optional
+            Observing site of every row. With `None`, each row is attributed to the site
+            whose MPC code is in the `observer` column, e.g. `columns={"observer": "stn"}`.
+        columns : dict[str, str], optional
+            Mapping from the fields `tid`, `mjd`, `ra`, `dec`, `observer`, `sigma_ra`,
+            `sigma_dec` to column names, as in `from_arrow_ipc`. Files written by
+            `to_parquet` read back with `{"tid": "trajectory_id", "mjd": "mjd_tt"}` and
+            `units="radians"`.
+        units : {"degrees", "radians"}, default "degrees"
+            Unit of `ra`/`dec`. With `"degrees"`, uncertainties are in **arcseconds**;
+            with `"radians"`, in **radians**.
+        error_ra : float, optional
+            Uniform 1-σ RA uncertainty. With `None` for both `error_ra` and `error_dec`,
+            the per-row `sigma_ra` / `sigma_dec` columns are read when present; the
+            uncertainties are otherwise unknown and defer to `bad_sigma`.
+        error_dec : float, optional
+            Uniform 1-σ DEC uncertainty, same conventions as `error_ra`.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive, non-finite or missing uncertainties.
+        bad_sigma_value : float, optional
+            Replacement uncertainty, required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode for this call.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre, see
+            `from_numpy_degrees`.
+        batch_size : int, default 65536
+            Rows per decoded record batch.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet`. Rows of one trajectory spread over several batches are
+            merged in time order.
+
+        Raises
+        ----------
+        OSError
+            if the file cannot be opened.
+        TypeError
+            if a column has an unsupported type (`tid`: integer or string, `mjd`/`ra`/`dec`
+            and the uncertainties: `Float64`, `observer`: string).
+        KeyError
+            if a mapped column is missing from the file.
+        ValueError
+            if the file is not valid Parquet or uses a compression codec other than
+            Snappy, if a column contains nulls, an id is negative, on invalid `units` or
+            `columns`, or on bad uncertainties with `bad_sigma="error"`.
+        """
+        ...
+
+    @staticmethod
+    def from_pandas(
+        env: PyOutfit,
+        df: Any,
+        observer: Observer,
+        ra_col: str = "ra",
+        dec_col: str = "dec",
+        time_col: str = "mjd_tt",
+        id_col: str = "trajectory_id",
+        units: Literal["degrees", "radians"] = "degrees",
+        error_ra: Optional[float] = None,
+        error_dec: Optional[float] = None,
+        sigma_ra_col: str = "sigma_ra",
+        sigma_dec_col: str = "sigma_dec",
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from a pandas `DataFrame`, one row per observation.
+
+        The mapped columns are checked and converted (any integer dtype, or an `object`
+        column of ints or strings, for the ids; any numeric dtype for the values) before
+        going through the same ingestion as
+        `from_numpy_degrees` / `from_numpy_radians`.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        df : pandas.DataFrame
+            Source table.
+        observer : Observer
+            Observing site of every row.
+        ra_col, dec_col : str
+            Right ascension and declination columns.
+        time_col : str, default "mjd_tt"
+            Epoch column, in **MJD (TT)**.
+        id_col : str, default "trajectory_id"
+            Trajectory id column: integers (any width) in the `uint32` range, or an
+            `object` column of Python ints or strings (read as the `trajectory_id` of
+            `from_numpy_degrees`).
+        units : {"degrees", "radians"}, default "degrees"
+            Unit of RA/DEC. With `"degrees"`, uncertainties are in **arcseconds**;
+            with `"radians"`, in **radians**.
+        error_ra, error_dec : float, optional
+            Uniform 1-σ uncertainties, given together. They take precedence over the
+            uncertainty columns.
+        sigma_ra_col, sigma_dec_col : str
+            Per-row 1-σ uncertainty columns, used when `error_ra` / `error_dec` are not
+            given and `df` has them. Without either, the uncertainties are unknown and
+            `bad_sigma` decides (e.g. `bad_sigma="model"`).
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive, non-finite or missing uncertainties.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (in the unit of the uncertainties), required with
+            `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre, see
+            `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet`.
+
+        Raises
+        ----------
+        ValueError
+            listing every missing column; if a value column is not numeric, if the id
+            column is not a non-null integer column in the `uint32` range (or, for an
+            `object` column, holds a negative int), if only one of `error_ra` /
+            `error_dec` is given, on invalid `units`, on bad uncertainties with
+            `bad_sigma="error"`, or if an epoch lies outside
+            `env.ephemeris_time_range()`.
+        TypeError
+            if an `object` id column holds an element that is neither an int nor a
+            string (`None`, `float`, ...) or mixes both, naming the first one.
+
+        See also
+        ------------
+        * `from_arrow_ipc` — Streamed ingestion of Arrow sources, per-row observers.
+        * `from_dataframe` — Any `DataFrame` implementing the interchange protocol.
+        """
+        ...
+
+    @staticmethod
+    def from_dataframe(
+        env: PyOutfit,
+        df: Any,
+        observer: Observer,
+        ra_col: str = "ra",
+        dec_col: str = "dec",
+        time_col: str = "mjd_tt",
+        id_col: str = "trajectory_id",
+        units: Literal["degrees", "radians"] = "degrees",
+        error_ra: Optional[float] = None,
+        error_dec: Optional[float] = None,
+        sigma_ra_col: str = "sigma_ra",
+        sigma_dec_col: str = "sigma_dec",
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from any `DataFrame` implementing the interchange
+        protocol (`__dataframe__`): pandas, polars, vaex, cudf frames held in host
+        memory, ...
+
+        The mapped columns are read from the buffers the protocol exposes, without the
+        library's own NumPy conversion, then ingested as in `from_pandas`. Values may be
+        integers or floats of any width, null values becoming NaN; ids may be integers,
+        strings, or categories of either.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        df : Any
+            Source table, any object with a `__dataframe__` method.
+        observer : Observer
+            Observing site of every row.
+        ra_col, dec_col : str
+            Right ascension and declination columns.
+        time_col : str, default "mjd_tt"
+            Epoch column, in **MJD (TT)**.
+        id_col : str, default "trajectory_id"
+            Trajectory id column: non-negative integers (any width, 64-bit ids kept as
+            they are), strings, or a categorical column of either.
+        units : {"degrees", "radians"}, default "degrees"
+            Unit of RA/DEC. With `"degrees"`, uncertainties are in **arcseconds**;
+            with `"radians"`, in **radians**.
+        error_ra, error_dec : float, optional
+            Uniform 1-σ uncertainties, given together. They take precedence over the
+            uncertainty columns.
+        sigma_ra_col, sigma_dec_col : str
+            Per-row 1-σ uncertainty columns, used when `error_ra` / `error_dec` are not
+            given and `df` has them. Null uncertainties are NaN and `bad_sigma` decides.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive, non-finite or missing uncertainties.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (in the unit of the uncertainties), required with
+            `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre, see
+            `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet`, equal to the one `from_pandas` builds from the same
+            columns.
+
+        Raises
+        ----------
+        TypeError
+            if `df` does not implement `__dataframe__`.
+        ValueError
+            listing every missing column; if a column has an unsupported dtype (a
+            non-numeric value column, floating ids, booleans, dates, ...) or lives in
+            device memory, on a null or negative id, if only one of `error_ra` /
+            `error_dec` is given, on invalid `units`, on bad uncertainties with
+            `bad_sigma="error"`, or if an epoch lies outside
+            `env.ephemeris_time_range()`.
+        """
+        ...
+
+    @staticmethod
+    def from_fink(
+        env: PyOutfit,
+        df: Any,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from a Fink / ZTF tracklet table.
+
+        The schema of Fink exports is fixed: `objectId` (string ids), `ra` / `dec`
+        (degrees), `jd` (Julian Date, UTC), `sigmara` / `sigmadec` (arcseconds) and
+        `fid` (ZTF filter id). Epochs are converted to MJD (TT), every row is observed
+        from the ZTF site (MPC code `I41`), and the filter of each detection is kept as
+        its band (`1`: `"g"`, `2`: `"r"`, `3`: `"i"`), with the `magpsf` magnitude when
+        the table has that column. The table is read as in `from_dataframe`.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        df : Any
+            Source table: pandas, polars, or any `DataFrame` implementing
+            `__dataframe__`.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive, non-finite or missing uncertainties.
+        bad_sigma_value : float, optional
+            Replacement uncertainty in **arcseconds**, required with
+            `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet` keyed by `objectId`, with the band (and magnitude)
+            of every detection in `Observations.magnitudes()`.
+
+        Raises
+        ----------
+        TypeError
+            if `df` does not implement `__dataframe__`.
+        ValueError
+            listing every missing column of the schema; on an unknown filter id, an
+            epoch before 1972 or that is not a Julian Date, as `from_dataframe` for the
+            column dtypes and null ids, and on bad uncertainties with
+            `bad_sigma="error"`.
+        """
+        ...
+
+    @staticmethod
+    def from_polars(
+        env: PyOutfit,
+        df: Any,
+        observer: Observer,
+        ra_col: str = "ra",
+        dec_col: str = "dec",
+        time_col: str = "mjd_tt",
+        id_col: str = "trajectory_id",
+        units: Literal["degrees", "radians"] = "radians",
+        error_ra: Optional[float] = None,
+        error_dec: Optional[float] = None,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        sort: bool = False,
+        dedup: Dedup = False,
+        validate: bool = True,
+        drop_invalid: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from a polars `DataFrame` without copying its columns.
+
+        Non-null, single-chunk `Float64` (RA, DEC, epoch) and integer (ids) columns
+        are read in place from their Arrow buffers, so ingesting a large table does not
+        double the peak memory as a conversion to NumPy would. Other columns
+        (nullable, chunked, other numeric dtypes) are cast and rechunked, costing one
+        copy of that column. The rows then go through the checks and options of the
+        NumPy loaders.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        df : polars.DataFrame
+            Source table (polars >= 1.0).
+        observer : Observer
+            Observing site of every row.
+        ra_col, dec_col : str
+            Right ascension and declination columns.
+        time_col : str, default "mjd_tt"
+            Epoch column, in **MJD (TT)**.
+        id_col : str, default "trajectory_id"
+            Trajectory id column: non-null, non-negative integers of any width
+            (`UInt64` / `Int64` survey ids included).
+        units : {"degrees", "radians"}, default "radians"
+            Unit of RA/DEC and uncertainties (arcseconds with `"degrees"`). Only
+            `"radians"` is zero-copy for RA/DEC; degrees are converted once.
+        error_ra, error_dec : float, optional
+            Uniform 1-σ uncertainties, given together. `None` leaves them unknown and
+            defers to `bad_sigma` (e.g. `bad_sigma="model"`).
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive, non-finite or missing uncertainties.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (in the unit of the uncertainties), required with
+            `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre, see
+            `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+        dedup : Dedup, default False
+            Drop duplicate observations, see `from_numpy_degrees`.
+        validate : bool, default True
+            Check the rows before ingesting them, see `from_numpy_degrees`. Null values
+            of the value columns read as NaN and are rejected.
+        drop_invalid : bool, default False
+            Drop the rows with NaN or infinite values (null values included) instead of
+            rejecting them, see `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            The set `from_numpy_radians` builds from the same columns.
+
+        Raises
+        ----------
+        ValueError
+            listing every missing column; if a value column is not numeric, if the id
+            column has nulls, a non-integer dtype or negative values,
+            if only one of `error_ra` / `error_dec` is given, on invalid `units`, or on
+            bad uncertainties with `bad_sigma="error"`.
+
+        See also
+        ------------
+        * `from_pandas` — Same mapping for pandas, with per-row uncertainty columns.
+        """
+        ...
+
+    @staticmethod
+    def from_astropy_table(
+        env: PyOutfit,
+        table: Any,
+        observer: Observer,
+        ra_col: str = "ra",
+        dec_col: str = "dec",
+        time_col: str = "time",
+        id_col: str = "trajectory_id",
+        units: Optional[Literal["degrees", "radians"]] = None,
+        error_ra: Optional[Any] = None,
+        error_dec: Optional[Any] = None,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        topocentric: bool = True,
+        sort: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from an astropy `Table` / `QTable`, honouring units.
+
+        RA/DEC are converted to radians from their attached unit. The time column may
+        be an `astropy.time.Time` in any scale (converted to TT internally) or plain
+        MJD (TT) values, unitless or with a time unit.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        table : astropy.table.Table
+            Source table (`Table` or `QTable`).
+        observer : Observer
+            Observing site of every row.
+        ra_col, dec_col : str
+            Right ascension and declination columns.
+        time_col : str, default "time"
+            Epoch column: `Time`, or MJD (TT).
+        id_col : str, default "trajectory_id"
+            Trajectory id column: integers in the `uint32` range.
+        units : {"degrees", "radians"}, optional
+            Unit of RA/DEC columns without a unit. When a unit is attached it must
+            agree with `units`.
+        error_ra, error_dec : Quantity or float, optional
+            Uniform 1-σ uncertainties, given together: angle quantities or floats in
+            **arcseconds**. `None` defers to `bad_sigma`.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive, non-finite or missing uncertainties.
+        bad_sigma_value : float, optional
+            Replacement uncertainty in arcseconds, required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call.
+        topocentric : bool, default True
+            `False` for astrometry already reduced to the geocentre, see
+            `from_numpy_degrees`.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet`.
+
+        Raises
+        ----------
+        ValueError
+            listing every missing column; if an angle column has a non-angular unit,
+            no unit without `units`, or a unit contradicting `units`; if the time
+            column has a non-temporal unit; on masked values, non-integer ids or ids
+            outside the `uint32` range.
+        astropy.units.UnitConversionError
+            if `error_ra` / `error_dec` are quantities that are not angles.
+        """
+        ...
+
+    # --- Ingestion from files ---
+    @staticmethod
+    def from_file(
+        pyoutfit: PyOutfit,
+        path: PathLike,
+        observer: Optional[Observer] = None,
+        **kwargs: Any,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from a file, detecting its format.
+
+        The format is sniffed from the content (Parquet magic bytes, ADES XML root tag,
+        ADES JSON `"ades"` member, ADES PSV `# version=` line or field header naming
+        `obsTime`, fixed 80-column records, delimiter of the header line), the extension
+        only being used when the content is inconclusive. The file is then read by the
+        dedicated loader, with `kwargs` forwarded to it:
+
+        * MPC 80-column → `new_from_mpc_80col`;
+        * ADES XML → `new_from_ades`;
+        * ADES PSV → `from_ades_psv`;
+        * ADES JSON → `from_ades_json`;
+        * CSV / PSV with a header line (`#` comment lines skipped, padded PSV columns
+          accepted) → `from_csv`, with `delimiter="|"` for PSV and the `tid` / `mjd` /
+          `ra` / `dec` columns by default;
+        * Parquet → `from_parquet`, decoded in Rust row group by row group.
+
+        When a header line holds both `,` and `|`, the more frequent one is taken as the
+        delimiter.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        path : PathLike
+            File path (`str` or Path from pathlib).
+        observer : Observer, optional
+            Site of tabular files: required for CSV and PSV; for Parquet, MPC codes are
+            read from the `observer` column without it (see `from_parquet`).
+        **kwargs
+            Keyword arguments of the selected loader (e.g. `columns`, `units`,
+            `error_ra` for tabular files, `error_ra_arcsec` for ADES, `bad_sigma`).
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet` populated from the file.
+
+        Raises
+        ----------
+        ValueError
+            if the format cannot be detected (the message lists the formats considered),
+            if `observer` is given for an MPC 80-column or ADES file, which carry their
+            own observatory codes, or if it is missing for a CSV or PSV file.
+        """
+        ...
+
+    @staticmethod
+    def from_files(
+        env: PyOutfit,
+        paths: Sequence[Union[str, Path]],
+        format: Literal["ades_xml", "parquet", "csv"] = "ades_xml",
+        observer: Optional[Observer] = None,
+        columns: Optional[Dict[str, Union[str, int]]] = None,
+        units: Literal["degrees", "radians"] = "degrees",
+        error_ra: Optional[float] = None,
+        error_dec: Optional[float] = None,
+        on_error: Literal["raise", "skip"] = "raise",
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        dedup: Dedup = True,
+        parallel: bool = True,
+        sort: bool = False,
+        include_rejected: bool = False,
+    ) -> "TrajectorySet":
+        """
+        Build one `TrajectorySet` from several observation files of the same format.
+
+        The files are read and parsed in Rust with the GIL released for the whole loop,
+        concurrently when `parallel`, then merged: observations with the same trajectory
+        id in several files (e.g. one file per night) form one trajectory, in time order.
+        The same detection found in two files is kept once with `dedup`.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        paths : sequence of str | pathlib.Path
+            Files to read.
+        format : {"ades_xml", "parquet", "csv"}, default "ades_xml"
+            Format of every file.
+        observer : Observer, optional
+            Observing site of every row of tabular files: required for CSV, optional for
+            Parquet (see `from_parquet`). Not accepted for ADES files, which carry their
+            own observatory codes.
+        columns : dict[str, str | int], optional
+            Column mapping of tabular files, as in `from_parquet` / `from_csv`.
+        units : {"degrees", "radians"}, default "degrees"
+            Units of tabular files.
+        error_ra, error_dec : float, optional
+            Uniform 1-σ uncertainties of the rows without their own, in arcseconds for
+            ADES files and in the unit of `units` otherwise.
+        on_error : {"raise", "skip"}, default "raise"
+            Raise on, or skip, the invalid records of ADES and CSV files; they are
+            reported file by file.
+        bad_sigma, bad_sigma_value, strict
+            See `from_numpy_degrees`.
+        dedup : bool | tuple[float, float], default True
+            Drop duplicate observations, see `from_numpy_degrees`.
+        parallel : bool, default True
+            Parse the files concurrently.
+        sort : bool, default False
+            Sort trajectories out of time order without a report, see
+            `from_numpy_degrees`.
+        include_rejected : bool, default False
+            Keep the ADES records flagged as rejected, see `from_ades_xml`.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet`, keyed as by the loader of a single file
+            (`from_ades_xml`, `from_parquet`, `from_csv`).
+
+        Raises
+        ----------
+        OSError
+            if a file cannot be read, before anything is ingested.
+        ValueError
+            if a file cannot be parsed, on invalid records (`on_error="raise"`), for an
+            unknown `format`, or when `observer` is missing (CSV) or given (ADES).
+
+        Notes
+        ----------
+        Parquet files are decoded whole before ingestion, so the record batches of every
+        file are held at once; `from_parquet` streams a single file row group by row
+        group. Trajectories split over files given out of chronological order are sorted
+        with a `FallbackWarning`, as any unordered input (see `sort`).
+        """
+        ...
+
+    @staticmethod
+    def new_from_mpc_80col(
+        pyoutfit: PyOutfit,
+        path: PathLike,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from a **MPC 80-column** file.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        path : PathLike
+            File path (`str` or Path from pathlib) to a MPC 80-column text file.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive or non-finite uncertainties (e.g. `0` or `-1`
+            used as "unknown" by some catalogues). The substituted values are the ones
+            later reported by `Observations`; their count is `patched_sigma_count`.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (**arcseconds**), required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call. Substituted uncertainties and trajectories given out of time order
+            (sorted at ingestion) are reported with `FallbackWarning`, or rejected with
+            `StrictModeError` in strict mode.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet` populated from the file contents.
+
+        Notes
+        ----------
+        * Mirrors the Rust API semantics and may **panic** on parse errors.
+
+        See also
+        ------------
+        * `add_from_mpc_80col` — Append a second 80-column file into an existing set.
+        * `new_from_ades` — Create from ADES JSON/XML.
+        """
+        ...
+
+    def add_from_mpc_80col(
+        self,
+        pyoutfit: PyOutfit,
+        path: PathLike,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+    ) -> None:
+        """
+        Append observations from a **MPC 80-column** file into this set.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        path : PathLike
+            File path (`str` or Path from pathlib) to a MPC 80-column text file.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive or non-finite uncertainties (e.g. `0` or `-1`
+            used as "unknown" by some catalogues). The substituted values are the ones
+            later reported by `Observations`; their count is `patched_sigma_count`.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (**arcseconds**), required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call. Substituted uncertainties and trajectories given out of time order
+            (sorted at ingestion) are reported with `FallbackWarning`, or rejected with
+            `StrictModeError` in strict mode.
+
+        Returns
+        ----------
+        None
+            The internal map is updated in place.
+
+        Notes
+        ----------
+        * **No de-duplication** is performed; avoid ingesting the same file twice.
+
+        See also
+        ------------
+        * `new_from_mpc_80col` — Create a brand-new set from a single file.
+        """
+        ...
+
+    @staticmethod
+    def from_mpc_80col(
+        pyoutfit: PyOutfit,
+        path: PathLike,
+        on_error: Literal["raise", "skip"] = "raise",
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+    ) -> TrajectorySet:
+        """
+        Build a set from a **MPC 80-column** file holding any number of objects.
+
+        Every line is parsed on its own (in parallel, without the GIL): observations are
+        grouped by designation, and each is attributed to the site of its own
+        observatory code, resolved in the MPC table of `pyoutfit`.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        path : PathLike
+            File path (`str` or Path from pathlib) to a MPC 80-column text file.
+        on_error : {"raise", "skip"}, default "raise"
+            Malformed lines raise `ValueError` (`"raise"`), or are dropped and reported
+            with a `FallbackWarning` of the `skipped_lines` check (`"skip"`;
+            `StrictModeError` in strict mode).
+        bad_sigma : BadSigmaPolicy, default "error"
+            Policy for non-positive or non-finite uncertainties, see `new_from_mpc_80col`.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (**arcseconds**), required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this call.
+
+        Returns
+        ----------
+        TrajectorySet
+            Trajectories keyed by packed designation (`"00433"`, `"K24A00A"`), the
+            observations of each object in time order (file order on equal epochs).
+
+        Raises
+        ----------
+        OSError
+            The file cannot be read.
+        ValueError
+            Malformed lines with `on_error="raise"`: too short, invalid date, RA or Dec,
+            missing designation, or unknown observatory code. The message gives the line
+            numbers (1-based) of the first ten.
+
+        Notes
+        ----------
+        * Blank lines, second lines of satellite and roving records (`s`, `v` in
+          column 15) and radar records (`R`, `r`) are ignored.
+        * Uncertainties are derived from the number of digits of RA and Dec, floored by
+          the site accuracy of the error model, as in `new_from_mpc_80col`.
+
+        See also
+        ------------
+        * `new_from_mpc_80col` — Core reader, one object per file.
+        """
+        ...
+
+    @staticmethod
+    def from_ades_xml(
+        pyoutfit: PyOutfit,
+        path: PathLike,
+        error_ra_arcsec: Optional[float] = None,
+        error_dec_arcsec: Optional[float] = None,
+        group_by: Optional[Sequence[str]] = None,
+        on_error: Literal["raise", "skip"] = "raise",
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        include_rejected: bool = False,
+    ) -> TrajectorySet:
+        """
+        Build a set from an **ADES XML** file.
+
+        Every `<optical>` record, in a flat list or in `<obsBlock>` structures, becomes
+        an observation: `obsTime` (ISO 8601, UTC) is converted to MJD (TT), `ra` / `dec`
+        from degrees to radians, `rmsRA` / `rmsDec` from arcseconds. The site is the
+        record's `stn`, or the `mpcCode` of its block, resolved in the MPC table of
+        `pyoutfit`. Parsing runs without the GIL.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        path : PathLike
+            File path (`str` or Path from pathlib) to an ADES XML file.
+        error_ra_arcsec, error_dec_arcsec : float, optional
+            Uncertainties (on-sky, arcseconds) of the records without `rmsRA` /
+            `rmsDec`. `None` leaves them unknown, which `bad_sigma` then handles.
+        group_by : Sequence[str], optional
+            Identifier fields naming the trajectory of a record, the first one present
+            wins (default: `("trkSub", "provID", "permID")`).
+        on_error : {"raise", "skip"}, default "raise"
+            Invalid records raise `ValueError`, or are dropped and reported with a
+            `FallbackWarning` (`skipped_lines` check), see `from_mpc_80col`.
+        bad_sigma : BadSigmaPolicy, default "error"
+            Policy for unknown, non-positive or non-finite uncertainties.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (**arcseconds**), required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this call.
+        include_rejected : bool, default False
+            Keep the records flagged as not to be used for orbit fitting (`selAst` of `D`
+            / `d`, or an `exclude` field other than `0`, `false` or `no`). By default they
+            are dropped; when kept, `Observations.rejected()` tells them apart and
+            `TrajectorySet.drop_rejected()` removes them later.
+
+        Returns
+        ----------
+        TrajectorySet
+            Trajectories keyed by identifier (always `str`), the observations of each in
+            document order.
+
+        Raises
+        ----------
+        OSError
+            The file cannot be read.
+        ValueError
+            XML syntax error (whatever `on_error`); invalid records with
+            `on_error="raise"` (missing identifier or field, unknown station, value out
+            of range), listed with the line of their opening tag; `group_by` empty.
+
+        Notes
+        ----------
+        * Unlike `new_from_ades`, which forwards the values to the core as written,
+          angles, uncertainties and epochs are converted to the units and time scale of
+          the bindings.
+        * `rmsRA` is on the sky; the stored RA uncertainty is that of the RA coordinate,
+          `rmsRA / cos(dec)`.
+        * `astCat`, `mag` and `band` are accepted (`mag` must be a number) but not stored.
+        * `from_ades_psv` and `from_ades_json` read the other ADES flavours.
+        """
+        ...
+
+    @staticmethod
+    def from_ades_psv(
+        pyoutfit: PyOutfit,
+        path: PathLike,
+        error_ra_arcsec: Optional[float] = None,
+        error_dec_arcsec: Optional[float] = None,
+        group_by: Optional[Sequence[str]] = None,
+        on_error: Literal["raise", "skip"] = "raise",
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        include_rejected: bool = False,
+    ) -> TrajectorySet:
+        """
+        Build a set from an **ADES PSV** file.
+
+        Pipe-separated ADES, with the field mapping and units of `from_ades_xml`. Each
+        block starts with `#` / `!` header lines (`! mpcCode` under `# observatory` gives
+        the station of rows without `stn`); the first other line of a block is detected
+        as its field header. Columns may be padded with spaces.
+
+        Parameters
+        -----------------
+        pyoutfit, path, error_ra_arcsec, error_dec_arcsec, group_by, on_error, bad_sigma, bad_sigma_value, strict,
+        include_rejected
+            See `from_ades_xml`.
+
+        Returns
+        ----------
+        TrajectorySet
+            Trajectories keyed by identifier (always `str`), the observations of each in
+            time order. Given the same records, equal to the set read by `from_ades_xml`.
+
+        Raises
+        ----------
+        OSError
+            The file cannot be read.
+        ValueError
+            Field header without `obsTime` (whatever `on_error`); invalid rows with
+            `on_error="raise"` (wrong number of fields, missing identifier or field,
+            unknown station, value out of range), listed with their line number.
+
+        Notes
+        ----------
+        * Every row is attributed to the site of its own `stn`: files mixing stations
+          need no observer.
+        * `astCat`, `mag` and `band` are accepted (`mag` must be a number) but not stored.
+        """
+        ...
+
+    @staticmethod
+    def from_ades_json(
+        pyoutfit: PyOutfit,
+        path: PathLike,
+        error_ra_arcsec: Optional[float] = None,
+        error_dec_arcsec: Optional[float] = None,
+        group_by: Optional[Sequence[str]] = None,
+        on_error: Literal["raise", "skip"] = "raise",
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+        include_rejected: bool = False,
+    ) -> TrajectorySet:
+        """
+        Build a set from an **ADES JSON** file.
+
+        JSON ADES, with the field mapping and units of `from_ades_xml`. Optical records are
+        collected from the `optical` lists of the document (flat, or inside `obsBlock`
+        entries whose `obsContext.observatory.mpcCode` gives the station of records
+        without `stn`), in document order. Values may be strings or numbers.
+
+        Parameters
+        -----------------
+        pyoutfit, path, error_ra_arcsec, error_dec_arcsec, group_by, on_error, bad_sigma, bad_sigma_value, strict,
+        include_rejected
+            See `from_ades_xml`.
+
+        Returns
+        ----------
+        TrajectorySet
+            Trajectories keyed by identifier (always `str`), the observations of each in
+            time order. Given the same records, equal to the set read by `from_ades_xml`.
+
+        Raises
+        ----------
+        OSError
+            The file cannot be read.
+        ValueError
+            JSON syntax error (whatever `on_error`); invalid records with
+            `on_error="raise"`, listed with their rank (1-based) among the optical records.
+
+        Notes
+        ----------
+        * Every record is attributed to the site of its own `stn`.
+        * `astCat`, `mag` and `band` are accepted (`mag` must be a number) but not stored.
+        """
+        ...
+
+    @staticmethod
+    def new_from_ades(
+        pyoutfit: PyOutfit,
+        path: PathLike,
+        error_ra_arcsec: Optional[float] = None,
+        error_dec_arcsec: Optional[float] = None,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+    ) -> "TrajectorySet":
+        """
+        Build a `TrajectorySet` from an **ADES** file (JSON or XML).
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        path : PathLike
+            File path (`str` or Path from pathlib) to an ADES JSON/XML file.
+        error_ra_arcsec : Optional[float]
+            Optional global RA 1-σ (arcsec) if not specified per row.
+        error_dec_arcsec : Optional[float]
+            Optional global DEC 1-σ (arcsec) if not specified per row.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive or non-finite uncertainties (e.g. `0` or `-1`
+            used as "unknown" by some catalogues). The substituted values are the ones
+            later reported by `Observations`; their count is `patched_sigma_count`.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (**arcseconds**), required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call. Substituted uncertainties and trajectories given out of time order
+            (sorted at ingestion) are reported with `FallbackWarning`, or rejected with
+            `StrictModeError` in strict mode.
+
+        Returns
+        ----------
+        TrajectorySet
+            A new `TrajectorySet` populated from the ADES file.
+
+        Notes
+        ----------
+        * Error-handling policy follows the underlying parser (may log or panic).
+
+        See also
+        ------------
+        * `add_from_ades` — Append ADES observations into an existing set.
+        """
+        ...
+
+    def add_from_ades(
+        self,
+        pyoutfit: PyOutfit,
+        path: PathLike,
+        error_ra_arcsec: Optional[float] = None,
+        error_dec_arcsec: Optional[float] = None,
+        bad_sigma: BadSigmaPolicy = "error",
+        bad_sigma_value: Optional[float] = None,
+        strict: Optional[bool] = None,
+    ) -> None:
+        """
+        Append observations from an **ADES** file (JSON/XML) into this set.
+
+        Parameters
+        -----------------
+        pyoutfit : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        path : PathLike
+            File path (`str` or Path from pathlib) to an ADES JSON/XML file.
+        error_ra_arcsec : Optional[float]
+            Optional global RA 1-σ (arcsec) if not specified per row.
+        error_dec_arcsec : Optional[float]
+            Optional global DEC 1-σ (arcsec) if not specified per row.
+        bad_sigma : BadSigmaPolicy, default "error"
+            What to do with non-positive or non-finite uncertainties (e.g. `0` or `-1`
+            used as "unknown" by some catalogues). The substituted values are the ones
+            later reported by `Observations`; their count is `patched_sigma_count`.
+        bad_sigma_value : float, optional
+            Replacement uncertainty (**arcseconds**), required with `bad_sigma="value"`.
+        strict : bool, optional
+            Override of the process-wide strict mode (`py_outfit.set_strict`) for this
+            call. Substituted uncertainties and trajectories given out of time order
+            (sorted at ingestion) are reported with `FallbackWarning`, or rejected with
+            `StrictModeError` in strict mode.
+
+        Returns
+        ----------
+        None
+            The internal map is updated in place.
+
+        Notes
+        ----------
+        * **No de-duplication** is performed; avoid re-ingesting the same file.
+
+        See also
+        ------------
+        * `new_from_ades` — Create a brand-new set from a single ADES file.
+        """
+        ...
+
+    # --- Reshaping ---
+    def to_dict(self, include_units: bool = False, degrees: bool = False) -> Dict[str, Any]:
+        """
+        Export every observation as a columnar dict, one row per observation.
+
+        Parameters
+        -----------------
+        include_units : bool, default False
+            Add a `"units"` block mapping each numeric column to its unit.
+        degrees : bool, default False
+            Export angles in degrees instead of radians (the units block is then
+            always included).
+
+        Returns
+        ----------
+        dict[str, Any]
+            Equally long lists with the columns of
+            `py_outfit.schemas()["trajectory_set"]`: `trajectory_id` followed by the
+            columns of `Observations.to_dict`. Trajectories are sorted by identifier.
+        """
+        ...
+
+    def to_parquet(
+        self,
+        path: Union[str, Path],
+        compression: Literal["snappy", "none"] = "snappy",
+        env: Optional[PyOutfit] = None,
+    ) -> int:
+        """
+        Write every observation to a Parquet file, one row per observation.
+
+        The columns are those of `py_outfit.schemas()["trajectory_set_parquet"]`:
+        `trajectory_id`, `mjd_tt`, `ra`, `dec`, `sigma_ra`, `sigma_dec` (angles in
+        radians) and `observer`, the MPC code of the observing site. The arrays are
+        built and the file is written in Rust with the GIL released.
+
+        Parameters
+        -----------------
+        path : str | pathlib.Path
+            Destination file, created or truncated.
+        compression : {"snappy", "none"}, default "snappy"
+            Codec of the column chunks.
+        env : PyOutfit, optional
+            Environment resolving the observing sites (default: the one the set was
+            ingested with).
+
+        Returns
+        ----------
+        int
+            Number of rows written.
+
+        Raises
+        ----------
+        ValueError
+            on an unknown codec, or `"zstd"`, which is not available in this build, and
+            without `env` for a set that records no environment (e.g. `from_chunks` over
+            no chunks).
+        OSError
+            if the file cannot be created or written.
+
+        Notes
+        ----------
+        * `trajectory_id` is `uint32` when every id is an integer and a string column
+          otherwise (integer ids then written as strings). Trajectories are written by
+          increasing id, their observations in time order.
+        * Sites without an MPC code (user-defined observers) have a null `observer`.
+        * Each field carries its unit in its metadata (`"unit"`) and the file the schema
+          version (`"py_outfit.schema_version"`).
+        * The file reads back with `from_parquet(env, path, columns={"tid":
+          "trajectory_id", "mjd": "mjd_tt"}, units="radians")`.
+        """
+        ...
+
+    def to_ades_xml(
+        self,
+        env: PyOutfit,
+        path: Union[str, Path],
+        obs_context: Dict[str, Any],
+        id_field: Literal["trkSub", "provID", "permID"] = "trkSub",
+        mode: str = "CCD",
+        ast_cat: Optional[str] = None,
+    ) -> int:
+        """
+        Write every observation to an ADES XML file, for submission to the MPC.
+
+        Observations are grouped by station into `<obsBlock>` elements sharing the
+        header built from `obs_context`, with one `<optical>` record per observation
+        (the inverse of the field mapping of `from_ades_xml`). The document is built and
+        written in Rust with the GIL released.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Environment the set was ingested with (resolves the station codes).
+        path : str | pathlib.Path
+            Destination file, created or truncated.
+        obs_context : dict
+            Header of the blocks, with keys:
+
+            * `observers`, `measurers`: a name or a list of names (required);
+            * `telescope`: dict with `design`, `aperture`, `detector` (required) and
+              optionally `name`, `fRatio`, `filter`, `arraySize`, `pixelScale`;
+            * `submitter`: a name or `{"name": ..., "institution": ...}` (optional,
+              default: the first measurer);
+            * `comment`: a line or a list of lines (optional).
+        id_field : {"trkSub", "provID", "permID"}, default "trkSub"
+            ADES field holding the trajectory id.
+        mode : str, default "CCD"
+            Observation mode of the records.
+        ast_cat : str, optional
+            Astrometric catalogue of the records (`astCat`, required for MPC
+            submissions); omitted when None.
+
+        Returns
+        ----------
+        int
+            Number of `<optical>` records written.
+
+        Raises
+        ----------
+        KeyError
+            if a required `obs_context` key is missing.
+        ValueError
+            on an unknown `obs_context` key or `id_field`, when an observation's site
+            has no MPC code, or when an id is not a valid value of `id_field` (`trkSub`:
+            1 to 8 letters or digits).
+        OSError
+            if the file cannot be written.
+
+        Notes
+        ----------
+        * `obsTime` is written in UTC with millisecond precision, `ra` / `dec` in
+          degrees (9 decimals), `rmsRA` (on-sky, `sigma_ra * cos(dec)`) and `rmsDec`
+          in arcseconds (4 decimals, omitted for unknown uncertainties).
+        * The file reads back with `from_ades_xml(env, path)`; ids are then strings.
+        """
+        ...
+
+    def to_mpc_80col(self, env: PyOutfit, path: Union[str, Path]) -> int:
+        """
+        Write every observation to an MPC 80-column file, one record per line.
+
+        Records are formatted as by `Observations.to_mpc_80col`, with the trajectory id
+        as designation and the MPC code of each observation's site. The file is written
+        in Rust with the GIL released.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Environment the set was ingested with (resolves the observatory codes).
+        path : str | pathlib.Path
+            Destination file, created or truncated.
+
+        Returns
+        ----------
+        int
+            Number of records written.
+
+        Raises
+        ----------
+        ValueError
+            when an id has no 80-column form (integers above 15396335, strings longer
+            than 7 characters or with whitespace) or an observation's site has no MPC
+            code.
+        OSError
+            if the file cannot be written.
+
+        Notes
+        ----------
+        * Trajectories are written by increasing id, their observations in time order.
+        * The file reads back with `from_mpc_80col(env, path)`; ids are then strings
+          (zero-padded or packed numbers for integer ids).
+        """
+        ...
+
+    def observations_to_sqlite(
+        self,
+        env: PyOutfit,
+        path: Union[str, Path],
+        table: str = "observations",
+        if_exists: Literal["fail", "replace", "append"] = "fail",
+        degrees: bool = False,
+    ) -> int:
+        """
+        Write every observation to a table of a SQLite database, one row per observation.
+
+        The columns are those of `to_parquet` (`trajectory_id`, `mjd_tt`, `ra`, `dec`,
+        `sigma_ra`, `sigma_dec`, `observer`). Rows are built in Rust and written by
+        SQLite compiled into the extension, with the GIL released, inside a single
+        transaction; neither pandas nor the `sqlite3` module is used.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Environment the set was ingested with (resolves the observing sites).
+        path : str | pathlib.Path
+            Database file, created if needed.
+        table : str, default "observations"
+            Destination table.
+        if_exists : {"fail", "replace", "append"}, default "fail"
+            Behaviour when the table exists: raise, drop it first, or insert into it
+            (its columns must match).
+        degrees : bool, default False
+            Write angles in degrees instead of radians.
+
+        Returns
+        ----------
+        int
+            Number of rows written.
+
+        Raises
+        ----------
+        ValueError
+            on an invalid `if_exists` or table name, an existing table with
+            `if_exists="fail"`, or an existing table with other columns with
+            `"append"`.
+        OSError
+            on database errors (file that cannot be opened, constraint violation); the
+            transaction is then rolled back.
+
+        Notes
+        ----------
+        * `trajectory_id` is `INTEGER` when every id is an integer and `TEXT`
+          otherwise, and is indexed. `observer` is the MPC code of the site, `NULL`
+          for sites without one.
+        * Trajectories are written by increasing id, their observations in time order.
+        """
+        ...
+
+    def split_on_gaps(self, max_gap_days: float = 30.0) -> Dict[Key, list[str]]:
+        """
+        Split trajectories at time gaps larger than `max_gap_days`.
+
+        Long identifier-merged arcs may hold several apparitions separated by months,
+        while Gauss IOD works best on a single one. Each trajectory containing a gap
+        (between consecutive epochs) strictly larger than the threshold is replaced, in
+        place, by one trajectory per segment with derived string identifiers
+        `"{id}_a"`, `"{id}_b"`, ... (`"{id}_aa"` follows `"{id}_z"`).
+
+        Parameters
+        -----------------
+        max_gap_days : float, default 30.0
+            Largest tolerated gap between consecutive epochs, in days.
+
+        Returns
+        ----------
+        dict[Key, list[str]]
+            Mapping from each split trajectory to its new identifiers, in time order.
+            Trajectories without such a gap are left untouched and omitted.
+
+        Raises
+        ----------
+        ValueError
+            If `max_gap_days` is not a positive finite number.
+
+        Notes
+        ----------
+        * Derived identifiers never collide with existing keys: suffixes already in use
+          are skipped.
+        * Observations of each new trajectory are stored in time order.
+        """
+        ...
+
+    # --- Batch IOD ---
+    def estimate_all_orbits(
+        self,
+        env: PyOutfit,
+        params: IODParams,
+        seed: Optional[int] = ...,
+        fallback_params: Sequence[IODParams] = (),
+        initial_guesses: Optional[Dict[Any, KeplerianElements]] = None,
+        validate: bool = False,
+    ) -> Tuple[Dict[Any, Tuple[GaussResult, float]], Dict[Any, str]]:
+        """
+        Estimate the best orbit for **all trajectories** in this set.
+
+        Runs Gauss-based IOD for each trajectory using the provided environment
+        and parameters. Internally creates a RNG:
+        - if `seed` is provided → deterministic `StdRng::seed_from_u64(seed)`;
+        - else → `StdRng::from_os_rng()`.
+
+        Cancellation
+        ----------
+        The computation periodically checks for `KeyboardInterrupt` (Ctrl-C). 
+        This work only if parallel is disabled (`params.do_parallel() == False`).
+        If parallel is enabled, the computation cannot be interrupted and you will need to kill the process manually.
+
+        If cancellation is triggered, partial results accumulated so far are returned:
+
+        - the first dict contains successful `(GaussResult, rms)` per object,
+        - the second dict contains error messages per object.
+
+        Parameters
+        -----------------
+        env : PyOutfit
             Global environment (ephemerides, observers, error model).
         params : IODParams
             IOD tuning parameters (`IODParams`). If `params.do_parallel()`
@@ -458,6 +3199,20 @@ class TrajectorySet:
             path with cooperative cancellation.
         seed : Optional[int]
             Optional RNG seed for reproducibility.
+        fallback_params : Sequence[IODParams]
+            Relaxed configurations, tried in order on each trajectory that fails
+            under `params` until one succeeds. Each one runs in its own mode
+            (`do_parallel` / `do_sequential`), so a sequential fallback with a
+            `triplet_scorer` may follow a parallel `params`.
+        initial_guesses : Optional[Dict[object_id, KeplerianElements]]
+            Known orbits of some trajectories. These skip the triplet search: the
+            guess is propagated to the middle of the arc and corrected by least squares
+            over every observation; the Gauss IOD only runs when that correction
+            diverges.
+        validate : bool, default False
+            Check that every trajectory is stored in time order before fitting it. A
+            trajectory breaking the invariant is not fitted: its error message starts
+            with `UnsortedEpochsError:`.
 
         Returns
         ----------
@@ -471,5 +3226,156 @@ class TrajectorySet:
         * `object_id` preserves the input trajectory identifiers (either `int`
           or `str`, depending on how trajectories were ingested).
         * The RMS value is engine-defined (e.g., post-fit residual RMS in radians).
+        * Candidates are explored by ascending triplet score, then earliest middle epoch,
+          then lowest index tuple; among identical RMS the first one wins. The
+          sequential and parallel paths share this order, so with
+          `n_noise_realizations=0` they return the same solutions. With noisy
+          realizations they draw from the seed differently (parallel: one stream per
+          object key, sequential: a single stream in iteration order).
+        * With `fallback_params`, a retry restarts from the observations as ingested,
+          with an RNG seeded from `seed`, the object key and the attempt index only.
+          `GaussResult.params_index` gives the configuration that produced each orbit
+          (`0` for `params`, `k` for `fallback_params[k - 1]`); a trajectory failing
+          every attempt reports the error of its last one.
+        * With `initial_guesses`, `GaussResult.iod_path` gives the strategy of each
+          orbit (`"guess"`, `"fallback"` or `"gauss"`). A guess for a trajectory absent
+          from the set raises `KeyError`, a guess of another type `TypeError`.
+        """
+        ...
+
+    def estimate_orbits(
+        self,
+        env: PyOutfit,
+        params: IODParams,
+        seed: Optional[int] = ...,
+        fallback_params: Sequence[IODParams] = (),
+        initial_guesses: Optional[Dict[Any, KeplerianElements]] = None,
+    ) -> OrbitResults:
+        """
+        Estimate the best orbit for all trajectories and keep the fitted arcs.
+
+        Same computation as `estimate_all_orbits`, returned as an `OrbitResults`
+        container which also stores the observations of each trajectory.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        params : IODParams
+            IOD tuning parameters.
+        seed : Optional[int]
+            Optional RNG seed for reproducibility.
+        fallback_params : Sequence[IODParams]
+            Relaxed configurations retried on failures, as in `estimate_all_orbits`.
+        initial_guesses : Optional[Dict[object_id, KeplerianElements]]
+            Orbits corrected instead of searching triplets, as in `estimate_all_orbits`.
+
+        Returns
+        ----------
+        OrbitResults
+            One entry per processed trajectory (successful or not).
+
+        See also
+        ----------
+        * `OrbitResults.update_with` – Incremental update with new observations.
+        """
+        ...
+
+    def estimate_orbits_sweep(
+        self,
+        env: PyOutfit,
+        params_list: Sequence[IODParams],
+        seed: Optional[int] = None,
+    ) -> Tuple[List[OrbitResults], List[Dict[str, float]]]:
+        """
+        Run the batch IOD once per parameter set.
+
+        Each configuration is fitted on a fresh copy of the trajectories: the
+        configurations do not influence each other and `self` is left untouched.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        params_list : Sequence[IODParams]
+            IOD configurations to compare.
+        seed : Optional[int]
+            RNG seed applied to every configuration: `results[k]` equals
+            `estimate_orbits(env, params_list[k], seed)` run on a copy of this set.
+            When omitted, one random seed is drawn and shared by all configurations.
+
+        Returns
+        ----------
+        results : list[OrbitResults]
+            One container per configuration, aligned with `params_list`.
+        table : list[dict]
+            One row per configuration with keys `config` (index in `params_list`),
+            `n_trajectories`, `n_ok`, `success_rate` and `median_rms` (over the
+            successful fits; `nan` when there is none). `pandas.DataFrame(table)` gives
+            a comparison table.
+
+        Notes
+        ----------
+        * Configurations run one after the other; within a configuration the
+          trajectories run sequentially or in parallel according to its `do_parallel`.
+        """
+        ...
+
+    def estimate_arc_growth(
+        self,
+        env: PyOutfit,
+        params: IODParams,
+        checkpoints_mjd: Sequence[float],
+        seed: Optional[int] = None,
+    ) -> Tuple[
+        Dict[Tuple[Key, float], Tuple[GaussResult, float]], Dict[Tuple[Key, float], str]
+    ]:
+        """
+        Fit every trajectory on growing arcs, one per checkpoint epoch.
+
+        For each trajectory and each checkpoint, the IOD runs on the observations
+        strictly before the checkpoint. The truncated arcs are copied one at a time
+        from the stored trajectories; `self` is left untouched.
+
+        Parameters
+        -----------------
+        env : PyOutfit
+            Global environment (ephemerides, observers, error model).
+        params : IODParams
+            IOD configuration.
+        checkpoints_mjd : Sequence[float]
+            Checkpoint epochs (MJD, TT), in any order.
+        seed : Optional[int]
+            RNG seed for reproducible runs. When omitted, a random seed is used.
+
+        Returns
+        ----------
+        ok : dict[(Key, float), tuple[GaussResult, float]]
+            `(trajectory_id, checkpoint) -> (result, rms)` for the successful fits.
+        errors : dict[(Key, float), str]
+            `(trajectory_id, checkpoint) -> message` for the failed fits.
+
+        Notes
+        ----------
+        * Checkpoints leaving fewer than 3 observations are skipped: they appear in
+          neither dict.
+        * Fits run sequentially with a single RNG, trajectory by trajectory then
+          checkpoint by checkpoint. On a one-trajectory set, a checkpoint after the
+          last observation reproduces `estimate_all_orbits(env, params, seed)` with
+          `do_parallel` disabled.
+        * `Ctrl-C` interrupts the loop; the fits completed so far are returned.
         """
         ...
+
+class TrajectoryIdIterator(Iterator[Key]):
+    """
+    Iterator over the ids of a `TrajectorySet`, as returned by `iter(ts)`.
+
+    The identifiers are snapshotted when the iteration starts and converted to Python
+    objects one at a time, so iterating over a large set does not allocate every key up
+    front. `operator.length_hint` gives the number of ids left.
+    """
+
+    def __iter__(self) -> TrajectoryIdIterator: ...
+    def __next__(self) -> Key: ...
+    def __length_hint__(self) -> int: ...
@@ -0,0 +1,405 @@
+//! Reader for ADES astrometry files (XML, PSV and JSON flavours).
+//!
+//! Every optical record becomes a `Record`, with the same field mapping in the three
+//! flavours: `obsTime` (ISO 8601, UTC) is converted to MJD (TT), `ra` / `dec` from
+//! degrees to radians, and `rmsRA` / `rmsDec` from arcseconds to radians (`rmsRA` is the
+//! on-sky uncertainty, `RA·cos(dec)`). The observatory is the record's `stn`, or the
+//! `mpcCode` of the enclosing observation block. The optional `astCat`, `mag` and `band`
+//! fields are accepted (`mag` must be a number) but not stored: observations carry no
+//! photometry. A `selAst` of `D` / `d` (deleted) or an `exclude` field other than `0`,
+//! `false` or `no` flags the record as rejected, to be left out of the orbit fit.
+//!
+//! Records are grouped by the first identifier field present among `group_by`
+//! (`trkSub`, `provID`, `permID` by default). Invalid records are returned with their
+//! position (line of the XML opening tag or PSV row, rank of the JSON record), so that
+//! the caller can report or skip them; syntax errors abort the whole file.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.from_ades_xml`, `from_ades_psv`, `from_ades_json` – Python entry
+//!   points.
+//! * [`crate::records`] – Attachment of the records to their sites.
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use hifitime::Epoch;
+use outfit::constants::RADSEC;
+use pyo3::{
+    prelude::*,
+    types::{PyBool, PyDict, PyList, PyString},
+};
+use quick_xml::{events::Event, Reader};
+
+use crate::records::{Malformed, Record};
+
+/// Field name → text value of one record.
+pub(crate) type Fields = HashMap<String, String>;
+
+/// Identifier fields tried by default, in order.
+pub(crate) const DEFAULT_GROUP_BY: [&str; 3] = ["trkSub", "provID", "permID"];
+
+/// Reading options shared by the ADES flavours.
+pub(crate) struct Options {
+    /// Identifier fields, the first one present names the trajectory.
+    pub(crate) group_by: Vec<String>,
+    /// Uncertainties (radians) of records without `rmsRA` / `rmsDec`, `NaN` for none.
+    pub(crate) default_sigma_ra: f64,
+    pub(crate) default_sigma_dec: f64,
+}
+
+/// Build a record from the fields of one observation.
+///
+/// Arguments
+/// -----------------
+/// * `fields` – Field name → text value.
+/// * `station` – Observatory code of the enclosing context, used without `stn`.
+/// * `line` – Line of the record in the file.
+/// * `opts` – Identifier fields and default uncertainties.
+///
+/// Return
+/// ----------
+/// * The record, or a description of the first missing or invalid field.
+pub(crate) fn record(
+    fields: &Fields,
+    station: Option<&str>,
+    line: usize,
+    opts: &Options,
+) -> Result<Record, String> {
+    let get = |key: &str| fields.get(key).map(|v| v.trim()).filter(|v| !v.is_empty());
+    let number = |key: &str| -> Result<Option<f64>, String> {
+        get(key)
+            .map(|v| {
+                v.parse::<f64>()
+                    .ok()
+                    .filter(|x| x.is_finite())
+                    .ok_or_else(|| format!("invalid {key} {v:?}"))
+            })
+            .transpose()
+    };
+
+    let designation = opts
+        .group_by
+        .iter()
+        .find_map(|key| get(key))
+        .ok_or_else(|| format!("none of {} is set", opts.group_by.join(", ")))?;
+    let code = get("stn")
+        .or(station)
+        .ok_or("missing field \"stn\" (and no observatory in the context)")?;
+    let time = get("obsTime").ok_or("missing field \"obsTime\"")?;
+    let mjd_tt = Epoch::from_str(time)
+        .map_err(|_| format!("invalid obsTime {time:?}"))?
+        .to_mjd_tt_days();
+    let ra = number("ra")?
+        .filter(|ra| (0.0..360.0).contains(ra))
+        .ok_or("missing or out of range field \"ra\"")?;
+    let dec = number("dec")?
+        .filter(|dec| dec.abs() <= 90.0)
+        .ok_or("missing or out of range field \"dec\"")?;
+    number("mag")?;
+    let rejected = get("selAst").is_some_and(|s| s.eq_ignore_ascii_case("d"))
+        || get("exclude").is_some_and(|v| {
+            !["0", "false", "no"]
+                .iter()
+                .any(|f| v.eq_ignore_ascii_case(f))
+        });
+
+    Ok(Record {
+        line,
+        designation: designation.to_string(),
+        code: code.to_string(),
+        mjd_tt,
+        ra: ra.to_radians(),
+        dec: dec.to_radians(),
+        sigma_ra: number("rmsRA")?.map_or(opts.default_sigma_ra, |s| s * RADSEC),
+        sigma_dec: number("rmsDec")?.map_or(opts.default_sigma_dec, |s| s * RADSEC),
+        rejected,
+    })
+}
+
+/// Convert one record and file it as valid or malformed.
+fn push(
+    records: &mut Vec<Record>,
+    malformed: &mut Malformed,
+    fields: &Fields,
+    station: Option<&str>,
+    at: usize,
+    opts: &Options,
+) {
+    match record(fields, station, at, opts) {
+        Ok(r) => records.push(r),
+        Err(reason) => malformed.push((at, reason)),
+    }
+}
+
+/// Line numbers of byte offsets, for offsets visited in increasing order.
+struct Lines<'a> {
+    text: &'a [u8],
+    offset: usize,
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text: text.as_bytes(),
+            offset: 0,
+            line: 1,
+        }
+    }
+
+    /// Line (1-based) of the byte at `offset`.
+    fn at(&mut self, offset: u64) -> usize {
+        let offset = (offset as usize).min(self.text.len());
+        if offset < self.offset {
+            (self.offset, self.line) = (0, 1);
+        }
+        self.line += self.text[self.offset..offset]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        self.offset = offset;
+        self.line
+    }
+}
+
+/// Parse an ADES XML document (flat `<optical>` list or `<obsBlock>` structure).
+///
+/// Return
+/// ----------
+/// * The valid records in document order and the `(line, reason)` of the invalid ones,
+///   or a description of the XML syntax error.
+pub(crate) fn parse_xml(text: &str, opts: &Options) -> Result<(Vec<Record>, Malformed), String> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut lines = Lines::new(text);
+
+    let mut path: Vec<String> = Vec::new();
+    let mut station: Option<String> = None;
+    let mut optical: Option<(usize, Fields)> = None;
+    let mut records = Vec::new();
+    let mut malformed = Vec::new();
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("line {}: {e}", lines.at(reader.error_position())))?;
+        match event {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                match name.as_str() {
+                    "obsBlock" => station = None,
+                    "optical" => {
+                        optical = Some((lines.at(reader.buffer_position()), HashMap::new()))
+                    }
+                    _ => {}
+                }
+                path.push(name);
+            }
+            Event::Text(t) => {
+                let value = t
+                    .unescape()
+                    .map_err(|e| format!("line {}: {e}", lines.at(reader.buffer_position())))?
+                    .into_owned();
+                let n = path.len();
+                match optical.as_mut() {
+                    Some((_, fields)) if n >= 2 && path[n - 2] == "optical" => {
+                        fields.insert(path[n - 1].clone(), value);
+                    }
+                    None if n >= 2 && path[n - 2] == "observatory" && path[n - 1] == "mpcCode" => {
+                        station = Some(value);
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) => {
+                path.pop();
+                if e.local_name().as_ref() == b"optical" {
+                    if let Some((line, fields)) = optical.take() {
+                        push(
+                            &mut records,
+                            &mut malformed,
+                            &fields,
+                            station.as_deref(),
+                            line,
+                            opts,
+                        );
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok((records, malformed))
+}
+
+/// Parse an ADES PSV document.
+///
+/// Each block starts with `#` header lines (`! mpcCode` under `# observatory` giving the
+/// station of the block); its first other line names the `|`-separated fields of the
+/// following rows. Field names and values are trimmed, so padded columns are accepted.
+///
+/// Return
+/// ----------
+/// * The valid records in file order and the `(line, reason)` of the invalid rows, or
+///   a description of a missing or invalid field header.
+pub(crate) fn parse_psv(text: &str, opts: &Options) -> Result<(Vec<Record>, Malformed), String> {
+    let mut header: Option<Vec<String>> = None;
+    let mut section = String::new();
+    let mut station: Option<String> = None;
+    let mut in_data = false;
+    let mut records = Vec::new();
+    let mut malformed = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let number = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('#') {
+            if in_data {
+                // A new block: its header and context replace the previous ones.
+                (header, station, in_data) = (None, None, false);
+            }
+            section = name.trim().to_string();
+            continue;
+        }
+        if let Some(entry) = trimmed.strip_prefix('!') {
+            let mut kv = entry.trim().splitn(2, char::is_whitespace);
+            if section == "observatory" && kv.next() == Some("mpcCode") {
+                station = kv.next().map(|v| v.trim().to_string());
+            }
+            continue;
+        }
+        let cells = line.split('|').map(|c| c.trim());
+        match header.as_ref() {
+            None => {
+                let names: Vec<String> = cells.map(str::to_string).collect();
+                if !names.iter().any(|n| n == "obsTime") {
+                    return Err(format!(
+                        "line {number}: expected a field header (with obsTime), got {trimmed:?}"
+                    ));
+                }
+                header = Some(names);
+            }
+            Some(names) => {
+                in_data = true;
+                let values: Vec<&str> = cells.collect();
+                if values.len() != names.len() {
+                    malformed.push((
+                        number,
+                        format!("expected {} fields, got {}", names.len(), values.len()),
+                    ));
+                    continue;
+                }
+                let fields: Fields = names
+                    .iter()
+                    .zip(values)
+                    .map(|(n, v)| (n.clone(), v.to_string()))
+                    .collect();
+                push(
+                    &mut records,
+                    &mut malformed,
+                    &fields,
+                    station.as_deref(),
+                    number,
+                    opts,
+                );
+            }
+        }
+    }
+    Ok((records, malformed))
+}
+
+/// Text of a JSON scalar (`None` for null, booleans and containers).
+fn json_text(value: &Bound<'_, PyAny>) -> PyResult<Option<String>> {
+    if value.is_none() || value.is_instance_of::<PyBool>() {
+        return Ok(None);
+    }
+    if let Ok(s) = value.cast::<PyString>() {
+        return Ok(Some(s.to_str()?.to_string()));
+    }
+    if value.is_instance_of::<PyDict>() || value.is_instance_of::<PyList>() {
+        return Ok(None);
+    }
+    // Numbers: `str()` round-trips floats exactly.
+    Ok(Some(value.str()?.to_str()?.to_string()))
+}
+
+/// Collect the optical records of a decoded JSON node, with the station of their block.
+fn json_walk(
+    node: &Bound<'_, PyAny>,
+    station: Option<&str>,
+    out: &mut Vec<(Fields, Option<String>)>,
+) -> PyResult<()> {
+    if let Ok(list) = node.cast::<PyList>() {
+        for item in list.iter() {
+            json_walk(&item, station, out)?;
+        }
+        return Ok(());
+    }
+    let Ok(dict) = node.cast::<PyDict>() else {
+        return Ok(());
+    };
+    let context = dict
+        .get_item("obsContext")?
+        .and_then(|c| c.get_item("observatory").ok())
+        .and_then(|o| o.get_item("mpcCode").ok())
+        .map(|code| json_text(&code))
+        .transpose()?
+        .flatten();
+    let station = context.as_deref().or(station);
+    for (key, value) in dict.iter() {
+        match key.extract::<String>()?.as_str() {
+            "obsContext" => {}
+            "optical" => {
+                let items: Vec<Bound<'_, PyAny>> = match value.cast::<PyList>() {
+                    Ok(list) => list.iter().collect(),
+                    Err(_) => vec![value],
+                };
+                for item in items {
+                    let mut fields = Fields::new();
+                    if let Ok(d) = item.cast::<PyDict>() {
+                        for (k, v) in d.iter() {
+                            if let Some(text) = json_text(&v)? {
+                                fields.insert(k.extract()?, text);
+                            }
+                        }
+                    }
+                    out.push((fields, station.map(str::to_string)));
+                }
+            }
+            _ => json_walk(&value, station, out)?,
+        }
+    }
+    Ok(())
+}
+
+/// Parse a decoded ADES JSON document (`{"ades": {"optical": [...]}}` or `obsBlock`
+/// structures with their `obsContext` and `obsData`).
+///
+/// Return
+/// ----------
+/// * The valid records in document order and the `(rank, reason)` of the invalid ones
+///   (1-based rank among the optical records).
+pub(crate) fn parse_json(
+    doc: &Bound<'_, PyAny>,
+    opts: &Options,
+) -> PyResult<(Vec<Record>, Malformed)> {
+    let mut rows = Vec::new();
+    json_walk(doc, None, &mut rows)?;
+    Ok(doc.py().detach(|| {
+        let mut records = Vec::new();
+        let mut malformed = Vec::new();
+        for (i, (fields, station)) in rows.iter().enumerate() {
+            push(
+                &mut records,
+                &mut malformed,
+                fields,
+                station.as_deref(),
+                i + 1,
+                opts,
+            );
+        }
+        (records, malformed)
+    }))
+}
@@ -0,0 +1,380 @@
+//! ADES XML export of trajectory sets, for submission to the MPC.
+//!
+//! Observations are grouped by station into `<obsBlock>` elements (by increasing MPC
+//! code), each with the `<obsContext>` given by the caller and an `<obsData>` holding one
+//! `<optical>` record per observation: trajectories by increasing id, their
+//! observations in time order. The field mapping is the inverse of the reader
+//! ([`crate::ades`]):
+//!
+//! * `obsTime` – the epoch converted from MJD (TT) to UTC, ISO 8601 with milliseconds;
+//! * `ra` / `dec` – degrees, 9 decimals (~4 µas);
+//! * `rmsRA` / `rmsDec` – arcseconds, 4 decimals; `rmsRA` is the on-sky uncertainty
+//!   `σ_RA·cos(dec)`. Both are omitted for unknown (non-finite) uncertainties;
+//! * `stn` – MPC code of the observing site, which must have one.
+//!
+//! Elements follow the order of the ADES 2022 schema; text values are XML-escaped.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.to_ades_xml` / `TrajectorySet.from_ades_xml` – Python entry points.
+use std::{collections::BTreeMap, collections::HashMap, fmt::Write};
+
+use hifitime::{Duration, Epoch, TimeScale};
+use outfit::{constants::RADSEC, observations::Observation, ObjectNumber, Observer, Outfit};
+use pyo3::{
+    exceptions::{PyKeyError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyList, PyString},
+};
+use quick_xml::escape::escape;
+
+use crate::observatories::MpcSites;
+
+/// Version written in the `version` attribute of the root element.
+const ADES_VERSION: &str = "2022";
+
+/// Identifier fields accepted for the trajectory id.
+const ID_FIELDS: [&str; 3] = ["trkSub", "provID", "permID"];
+
+/// Keys of the `obs_context` dict.
+const CONTEXT_KEYS: [&str; 5] = [
+    "submitter",
+    "observers",
+    "measurers",
+    "telescope",
+    "comment",
+];
+
+/// Telescope fields, in schema order.
+const TELESCOPE_FIELDS: [&str; 8] = [
+    "name",
+    "design",
+    "aperture",
+    "detector",
+    "fRatio",
+    "filter",
+    "arraySize",
+    "pixelScale",
+];
+
+/// Telescope fields required by the schema.
+const TELESCOPE_REQUIRED: [&str; 3] = ["design", "aperture", "detector"];
+
+/// Header of every observation block, read from the `obs_context` dict.
+#[derive(Debug, Clone)]
+pub(crate) struct ObsContext {
+    /// Submitter name and optional institution (defaults to the first measurer).
+    submitter: (String, Option<String>),
+    observers: Vec<String>,
+    measurers: Vec<String>,
+    /// `(field, value)` in schema order.
+    telescope: Vec<(&'static str, String)>,
+    comment: Vec<String>,
+}
+
+/// Text of a header value: `str`, or any object through `str()` (numbers).
+fn text(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    Ok(value.str()?.to_cow()?.trim().to_string())
+}
+
+/// A name list: one `str` or a list of them, at least one non-empty name.
+fn names(key: &str, value: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
+    let names: Vec<String> = if value.is_instance_of::<PyString>() {
+        vec![text(value)?]
+    } else {
+        value
+            .try_iter()?
+            .map(|v| text(&v?))
+            .collect::<PyResult<_>>()?
+    };
+    if names.is_empty() || names.iter().any(String::is_empty) {
+        return Err(PyValueError::new_err(format!(
+            "obs_context[{key:?}] must hold at least one non-empty name"
+        )));
+    }
+    Ok(names)
+}
+
+impl ObsContext {
+    /// Read the header from `obs_context`.
+    ///
+    /// Keys: `observers`, `measurers` (a name or a list of names), `telescope` (dict
+    /// with at least `design`, `aperture` and `detector`), and optionally `submitter`
+    /// (a name or a dict with `name` and `institution`) and `comment` (a line or a list
+    /// of lines). Missing required keys raise `KeyError`, unknown keys and empty values
+    /// `ValueError`.
+    pub(crate) fn from_dict(d: &Bound<'_, PyDict>) -> PyResult<Self> {
+        for key in d.keys() {
+            let key: String = key.extract()?;
+            if !CONTEXT_KEYS.contains(&key.as_str()) {
+                return Err(PyValueError::new_err(format!(
+                    "unknown obs_context key {key:?} (expected 'submitter', 'observers', \
+                     'measurers', 'telescope' or 'comment')"
+                )));
+            }
+        }
+        let required = |key: &str| {
+            d.get_item(key)?.ok_or_else(|| {
+                PyKeyError::new_err(format!("obs_context requires {key:?} (ADES header)"))
+            })
+        };
+
+        let observers = names("observers", &required("observers")?)?;
+        let measurers = names("measurers", &required("measurers")?)?;
+
+        let telescope_dict = required("telescope")?;
+        let telescope_dict = telescope_dict.downcast::<PyDict>()?;
+        for key in telescope_dict.keys() {
+            let key: String = key.extract()?;
+            if !TELESCOPE_FIELDS.contains(&key.as_str()) {
+                return Err(PyValueError::new_err(format!(
+                    "unknown telescope field {key:?} (expected one of {})",
+                    TELESCOPE_FIELDS.join(", ")
+                )));
+            }
+        }
+        let mut telescope = Vec::new();
+        for field in TELESCOPE_FIELDS {
+            match telescope_dict.get_item(field)? {
+                Some(v) => telescope.push((field, text(&v)?)),
+                None if TELESCOPE_REQUIRED.contains(&field) => {
+                    return Err(PyKeyError::new_err(format!(
+                        "obs_context['telescope'] requires {field:?}"
+                    )))
+                }
+                None => {}
+            }
+        }
+
+        let submitter = match d.get_item("submitter")? {
+            None => (measurers[0].clone(), None),
+            Some(s) => match s.downcast::<PyDict>() {
+                Ok(s) => {
+                    let name = s.get_item("name")?.ok_or_else(|| {
+                        PyKeyError::new_err("obs_context['submitter'] requires 'name'")
+                    })?;
+                    let institution = s.get_item("institution")?.map(|v| text(&v)).transpose()?;
+                    (text(&name)?, institution)
+                }
+                Err(_) => (text(&s)?, None),
+            },
+        };
+        let comment = match d.get_item("comment")? {
+            None => Vec::new(),
+            Some(c) if c.is_instance_of::<PyString>() => vec![text(&c)?],
+            Some(c) => c
+                .downcast::<PyList>()?
+                .iter()
+                .map(|v| text(&v))
+                .collect::<PyResult<_>>()?,
+        };
+
+        Ok(Self {
+            submitter,
+            observers,
+            measurers,
+            telescope,
+            comment,
+        })
+    }
+
+    /// `<obsContext>` of the block of station `code`.
+    fn write(&self, out: &mut String, code: &str) {
+        let _ = writeln!(out, "    <obsContext>");
+        let _ = writeln!(
+            out,
+            "      <observatory>\n        <mpcCode>{}</mpcCode>\n      </observatory>",
+            escape(code)
+        );
+        let _ = writeln!(out, "      <submitter>");
+        let _ = writeln!(out, "        <name>{}</name>", escape(&self.submitter.0));
+        if let Some(institution) = &self.submitter.1 {
+            let _ = writeln!(
+                out,
+                "        <institution>{}</institution>",
+                escape(institution)
+            );
+        }
+        let _ = writeln!(out, "      </submitter>");
+        for (tag, list) in [
+            ("observers", &self.observers),
+            ("measurers", &self.measurers),
+        ] {
+            let _ = writeln!(out, "      <{tag}>");
+            for name in list {
+                let _ = writeln!(out, "        <name>{}</name>", escape(name));
+            }
+            let _ = writeln!(out, "      </{tag}>");
+        }
+        let _ = writeln!(out, "      <telescope>");
+        for (field, value) in &self.telescope {
+            let _ = writeln!(out, "        <{field}>{}</{field}>", escape(value));
+        }
+        let _ = writeln!(out, "      </telescope>");
+        if !self.comment.is_empty() {
+            let _ = writeln!(out, "      <comment>");
+            for line in &self.comment {
+                let _ = writeln!(out, "        <line>{}</line>", escape(line));
+            }
+            let _ = writeln!(out, "      </comment>");
+        }
+        let _ = writeln!(out, "    </obsContext>");
+    }
+}
+
+/// Options of the `<optical>` records.
+#[derive(Debug, Clone)]
+pub(crate) struct Options {
+    /// Identifier field of the trajectory id, one of [`ID_FIELDS`].
+    pub(crate) id_field: String,
+    /// Observation mode (`mode`), e.g. `"CCD"`.
+    pub(crate) mode: String,
+    /// Astrometric catalogue (`astCat`), omitted for `None`.
+    pub(crate) ast_cat: Option<String>,
+}
+
+impl Options {
+    /// Check the identifier field, `ValueError` otherwise.
+    pub(crate) fn new(id_field: &str, mode: &str, ast_cat: Option<&str>) -> PyResult<Self> {
+        if !ID_FIELDS.contains(&id_field) {
+            return Err(PyValueError::new_err(format!(
+                "unknown id_field {id_field:?} (expected 'trkSub', 'provID' or 'permID')"
+            )));
+        }
+        Ok(Self {
+            id_field: id_field.to_string(),
+            mode: mode.to_string(),
+            ast_cat: ast_cat.map(str::to_string),
+        })
+    }
+}
+
+/// ISO 8601 UTC time of an MJD (TT) epoch, rounded to the millisecond.
+///
+/// The calendar breakdown of `hifitime` counts a new leap second from its TAI date, so
+/// during the 36-odd seconds before a leap second it is one second early: the seconds
+/// are corrected against the exact inverse (`from_gregorian_utc`, as used by the
+/// reader), so that reading the file back gives the same epoch.
+fn obs_time(mjd_tt: f64) -> String {
+    let epoch = Epoch::from_mjd_in_time_scale(mjd_tt, TimeScale::TT)
+        .round(Duration::from_milliseconds(1.0));
+    let (y, mo, d, h, mi, s, ns) = epoch.to_gregorian_utc();
+    let early = (epoch - Epoch::from_gregorian_utc(y, mo, d, h, mi, s, ns)).to_seconds();
+    let s = s + early.round() as u8;
+    format!(
+        "{y:04}-{mo:02}-{d:02}T{h:02}:{mi:02}:{s:02}.{:03}Z",
+        ns / 1_000_000
+    )
+}
+
+/// Right ascension in `[0, 360)` degrees, 9 decimals.
+fn ra_degrees(ra: f64) -> String {
+    let deg = (ra.to_degrees().rem_euclid(360.0) * 1e9).round() / 1e9;
+    format!("{:.9}", if deg >= 360.0 { 0.0 } else { deg })
+}
+
+/// Identifier `id` for the `field` of the records, `ValueError` when the schema rejects
+/// it (`trkSub`: 1 to 8 letters or digits; others: no whitespace).
+fn designation(id: &ObjectNumber, field: &str) -> PyResult<String> {
+    let id = id.to_string();
+    let valid = match field {
+        "trkSub" => (1..=8).contains(&id.len()) && id.chars().all(|c| c.is_ascii_alphanumeric()),
+        _ => !id.is_empty() && !id.chars().any(char::is_whitespace),
+    };
+    if !valid {
+        return Err(PyValueError::new_err(format!(
+            "trajectory id {id:?} is not a valid ADES {field}{}",
+            if field == "trkSub" {
+                " (1 to 8 letters or digits); choose another id_field"
+            } else {
+                ""
+            }
+        )));
+    }
+    Ok(id)
+}
+
+/// One `<optical>` record.
+fn write_optical(out: &mut String, id: &str, code: &str, o: &Observation, opts: &Options) {
+    let _ = writeln!(out, "      <optical>");
+    let _ = writeln!(out, "        <{0}>{1}</{0}>", opts.id_field, escape(id));
+    let _ = writeln!(out, "        <mode>{}</mode>", escape(&opts.mode));
+    let _ = writeln!(out, "        <stn>{}</stn>", escape(code));
+    let _ = writeln!(out, "        <obsTime>{}</obsTime>", obs_time(o.time));
+    let _ = writeln!(out, "        <ra>{}</ra>", ra_degrees(o.ra));
+    let _ = writeln!(out, "        <dec>{:.9}</dec>", o.dec.to_degrees());
+    let rms_ra = o.error_ra * o.dec.cos() / RADSEC;
+    let rms_dec = o.error_dec / RADSEC;
+    if rms_ra.is_finite() && rms_dec.is_finite() {
+        let _ = writeln!(out, "        <rmsRA>{rms_ra:.4}</rmsRA>");
+        let _ = writeln!(out, "        <rmsDec>{rms_dec:.4}</rmsDec>");
+    }
+    if let Some(cat) = &opts.ast_cat {
+        let _ = writeln!(out, "        <astCat>{}</astCat>", escape(cat));
+    }
+    let _ = writeln!(out, "      </optical>");
+}
+
+/// ADES XML document of `set`.
+///
+/// Arguments
+/// -----------------
+/// * `set` – Trajectories to export.
+/// * `env` – Environment holding the observer registry of the observations.
+/// * `sites` – MPC sites of the environment, giving the code of each site.
+/// * `context` – Header of every observation block.
+/// * `opts` – Identifier field, mode and catalogue of the records.
+///
+/// Return
+/// ----------
+/// * The document and the number of `<optical>` records, or `ValueError` for an
+///   observation whose site has no MPC code or an id rejected by the schema.
+pub(crate) fn document(
+    set: &outfit::TrajectorySet,
+    env: &Outfit,
+    sites: &MpcSites,
+    context: &ObsContext,
+    opts: &Options,
+) -> PyResult<(String, usize)> {
+    let mut keys: Vec<&ObjectNumber> = set.keys().collect();
+    keys.sort();
+
+    // Records grouped by station code, trajectories by id within a block.
+    let codes = sites.site_codes(env);
+    let mut resolved: HashMap<*const Observer, Option<&str>> = HashMap::new();
+    let mut blocks: BTreeMap<&str, Vec<(String, &Observation)>> = BTreeMap::new();
+    for key in keys {
+        let id = designation(key, &opts.id_field)?;
+        for o in &set[key] {
+            let site = o.get_observer(env);
+            let code = *resolved
+                .entry(site as *const Observer)
+                .or_insert_with(|| codes.get(site).map(String::as_str));
+            let code = code.ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "trajectory {id}: site {:?} has no MPC code (ADES requires a station \
+                     code per observation)",
+                    site.name.as_deref().unwrap_or("Unnamed")
+                ))
+            })?;
+            blocks.entry(code).or_default().push((id.clone(), o));
+        }
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(out, "<ades version=\"{ADES_VERSION}\">");
+    let mut n = 0;
+    for (code, records) in &blocks {
+        let _ = writeln!(out, "  <obsBlock>");
+        context.write(&mut out, code);
+        let _ = writeln!(out, "    <obsData>");
+        for (id, o) in records {
+            write_optical(&mut out, id, code, o, opts);
+        }
+        n += records.len();
+        let _ = writeln!(out, "    </obsData>");
+        let _ = writeln!(out, "  </obsBlock>");
+    }
+    let _ = writeln!(out, "</ades>");
+    Ok((out, n))
+}
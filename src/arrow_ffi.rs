@@ -0,0 +1,238 @@
+//! Arrow C Data Interface import.
+//!
+//! Record batches are received through the Arrow PyCapsule protocol and read from their
+//! buffers on the Rust side, without any Python-level column extraction:
+//!
+//! * `__arrow_c_array__` – a single record batch (a struct array and its schema);
+//! * `__arrow_c_stream__` – a stream of record batches (tables with several chunks,
+//!   readers, …), consumed one batch at a time.
+//!
+//! The column accessors are shared with the Parquet reader, whose record batches are
+//! decoded on the Rust side as well.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.from_arrow` / `TrajectorySet.from_parquet` – Python entry points.
+use std::{borrow::Cow, collections::HashMap, ffi::CStr};
+
+use arrow_array::{
+    cast::AsArray,
+    ffi::{from_ffi, FFI_ArrowArray, FFI_ArrowSchema},
+    ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream},
+    types::{Float64Type, Int32Type, Int64Type, UInt32Type, UInt64Type},
+    Array, RecordBatch, StructArray,
+};
+use arrow_schema::{ArrowError, DataType};
+use pyo3::{
+    exceptions::{PyKeyError, PyTypeError, PyValueError},
+    prelude::*,
+    types::PyCapsule,
+};
+
+use crate::ingest::IdNumbering;
+
+/// Record batches of an imported Arrow object, in order.
+pub(crate) type Batches = Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>>>;
+
+pub(crate) fn arrow_err(e: ArrowError) -> PyErr {
+    PyValueError::new_err(format!("Arrow import failed: {e}"))
+}
+
+/// Capsule `name` of an Arrow PyCapsule, `ValueError` for another capsule.
+fn capsule<'py>(obj: &Bound<'py, PyAny>, name: &CStr) -> PyResult<Bound<'py, PyCapsule>> {
+    let capsule = obj.downcast::<PyCapsule>()?.clone();
+    if capsule.name()? != Some(name) {
+        return Err(PyValueError::new_err(format!(
+            "expected an Arrow {:?} capsule",
+            name.to_string_lossy()
+        )));
+    }
+    Ok(capsule)
+}
+
+/// Import the record batches of `source` through the Arrow PyCapsule protocol.
+///
+/// Arguments
+/// -----------------
+/// * `source` – Object implementing `__arrow_c_array__` (one record batch) or
+///   `__arrow_c_stream__` (any number of record batches).
+///
+/// Return
+/// ----------
+/// * The record batches, read lazily for streams. Raises `TypeError` when `source`
+///   implements neither method or does not export a struct (record batch) array.
+pub(crate) fn import(source: &Bound<'_, PyAny>) -> PyResult<Batches> {
+    if source.hasattr("__arrow_c_array__")? {
+        let (schema, array): (Bound<'_, PyAny>, Bound<'_, PyAny>) =
+            source.call_method0("__arrow_c_array__")?.extract()?;
+        let schema = capsule(&schema, c"arrow_schema")?;
+        let array = capsule(&array, c"arrow_array")?;
+        // SAFETY: the capsules were checked by name; the array is moved out of its
+        // capsule (its release callback is cleared) and the schema is only borrowed.
+        let data = unsafe {
+            let array = FFI_ArrowArray::from_raw(array.pointer().cast());
+            from_ffi(array, &*schema.pointer().cast::<FFI_ArrowSchema>())
+        }
+        .map_err(arrow_err)?;
+        if !matches!(data.data_type(), DataType::Struct(_)) {
+            return Err(PyTypeError::new_err(format!(
+                "expected a record batch, got an Arrow array of type {}",
+                data.data_type()
+            )));
+        }
+        let batch = RecordBatch::from(StructArray::from(data));
+        return Ok(Box::new(std::iter::once(Ok(batch))));
+    }
+    if source.hasattr("__arrow_c_stream__")? {
+        let stream = capsule(
+            &source.call_method0("__arrow_c_stream__")?,
+            c"arrow_array_stream",
+        )?;
+        // SAFETY: checked by name; the stream is moved out of its capsule.
+        let reader = unsafe {
+            ArrowArrayStreamReader::try_new(FFI_ArrowArrayStream::from_raw(stream.pointer().cast()))
+        }
+        .map_err(arrow_err)?;
+        return Ok(Box::new(reader));
+    }
+    Err(PyTypeError::new_err(
+        "expected an Arrow record batch or stream (__arrow_c_array__ or __arrow_c_stream__)",
+    ))
+}
+
+/// Non-null column `name` of `batch`, `KeyError` when absent.
+fn column<'a>(batch: &'a RecordBatch, name: &str) -> PyResult<&'a dyn Array> {
+    let array = batch.column_by_name(name).ok_or_else(|| {
+        PyKeyError::new_err(format!("column {name:?} not found in the record batch"))
+    })?;
+    if array.null_count() > 0 {
+        return Err(PyValueError::new_err(format!(
+            "column {name:?} contains {} null value(s)",
+            array.null_count()
+        )));
+    }
+    Ok(array.as_ref())
+}
+
+/// `Float64` column `name` of `batch`, borrowed from its Arrow buffer.
+pub(crate) fn f64_column<'a>(batch: &'a RecordBatch, name: &str) -> PyResult<&'a [f64]> {
+    let array = column(batch, name)?;
+    match array.data_type() {
+        DataType::Float64 => Ok(array.as_primitive::<Float64Type>().values()),
+        other => Err(PyTypeError::new_err(format!(
+            "column {name:?} has unsupported Arrow type {other} (expected Float64)"
+        ))),
+    }
+}
+
+/// Identifier column `name` of `batch`.
+///
+/// `UInt32` is borrowed from its Arrow buffer. `UInt64`, `Int32` and `Int64` columns
+/// (64-bit survey ids) must be non-negative, and go through `numbering` as the 64-bit
+/// ids of the NumPy loaders: their original values are given back by
+/// [`IdNumbering::restore`].
+pub(crate) fn id_column<'a>(
+    batch: &'a RecordBatch,
+    name: &str,
+    numbering: &mut IdNumbering,
+) -> PyResult<Cow<'a, [u32]>> {
+    fn widen<T: Copy + std::fmt::Display>(values: &[T], name: &str) -> PyResult<Vec<u64>>
+    where
+        u64: TryFrom<T>,
+    {
+        values
+            .iter()
+            .map(|&v| {
+                u64::try_from(v).map_err(|_| {
+                    PyValueError::new_err(format!("column {name:?}: trajectory id {v} is negative"))
+                })
+            })
+            .collect()
+    }
+
+    let array = column(batch, name)?;
+    let wide = match array.data_type() {
+        DataType::UInt32 => return Ok(Cow::Borrowed(array.as_primitive::<UInt32Type>().values())),
+        DataType::UInt64 => Cow::Borrowed(&array.as_primitive::<UInt64Type>().values()[..]),
+        DataType::Int32 => Cow::Owned(widen(array.as_primitive::<Int32Type>().values(), name)?),
+        DataType::Int64 => Cow::Owned(widen(array.as_primitive::<Int64Type>().values(), name)?),
+        other => {
+            return Err(PyTypeError::new_err(format!(
+                "column {name:?} has unsupported Arrow type {other} (expected UInt32, UInt64, \
+                 Int32 or Int64)"
+            )))
+        }
+    };
+    Ok(Cow::Owned(numbering.number_wide(&wide)))
+}
+
+/// Whether column `name` of `batch` holds strings (plain or dictionary-encoded).
+pub(crate) fn is_string(batch: &RecordBatch, name: &str) -> bool {
+    let is_utf8 =
+        |t: &DataType| matches!(t, DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View);
+    batch
+        .column_by_name(name)
+        .is_some_and(|array| match array.data_type() {
+            DataType::Dictionary(_, values) => is_utf8(values),
+            other => is_utf8(other),
+        })
+}
+
+/// String column `name` of `batch`, one value per row.
+///
+/// `Utf8`, `LargeUtf8` and `Utf8View` columns are accepted, plain or dictionary-encoded.
+pub(crate) fn str_column<'a>(batch: &'a RecordBatch, name: &str) -> PyResult<Vec<&'a str>> {
+    let array = column(batch, name)?;
+    let (values, keys) = match array.data_type() {
+        DataType::Dictionary(_, _) => {
+            let dict = array.as_any_dictionary();
+            (dict.values().as_ref(), Some(dict.normalized_keys()))
+        }
+        _ => (array, None),
+    };
+    let strings: Vec<&str> = match values.data_type() {
+        DataType::Utf8 => values
+            .as_string::<i32>()
+            .iter()
+            .map(Option::unwrap_or_default)
+            .collect(),
+        DataType::LargeUtf8 => values
+            .as_string::<i64>()
+            .iter()
+            .map(Option::unwrap_or_default)
+            .collect(),
+        DataType::Utf8View => values
+            .as_string_view()
+            .iter()
+            .map(Option::unwrap_or_default)
+            .collect(),
+        _ => {
+            return Err(PyTypeError::new_err(format!(
+                "column {name:?} has unsupported Arrow type {} (expected a string type)",
+                array.data_type()
+            )))
+        }
+    };
+    Ok(match keys {
+        Some(keys) => keys.into_iter().map(|k| strings[k]).collect(),
+        None => strings,
+    })
+}
+
+/// Rows of `batch` grouped by the value of the string column `name`, in order of first
+/// appearance (see [`str_column`] for the accepted types).
+pub(crate) fn string_groups(
+    batch: &RecordBatch,
+    name: &str,
+) -> PyResult<Vec<(String, Vec<usize>)>> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    for (row, value) in str_column(batch, name)?.into_iter().enumerate() {
+        let slot = *index.entry(value).or_insert_with(|| {
+            groups.push((value.to_string(), Vec::new()));
+            groups.len() - 1
+        });
+        groups[slot].1.push(row);
+    }
+    Ok(groups)
+}
@@ -0,0 +1,510 @@
+//! Compiled-in low-precision ephemeris (`PyOutfit("builtin:approx", ...)`).
+//!
+//! Downstream packages run their unit tests and CI without downloading a JPL kernel
+//! with this selector. It is **not for science**: the Earth–Moon barycenter is
+//! placed at the arcsecond-to-arcminute level, enough to smoke-test the
+//! ingestion → IOD pipeline.
+//!
+//! The model follows Standish's *Keplerian Elements for Approximate Positions of the
+//! Major Planets* (JPL, 1800–2050 AD table): the heliocentric Earth–Moon barycenter
+//! is a Keplerian orbit with linearly drifting elements, and the Sun is offset from
+//! the solar-system barycenter by the four giant planets, described the same way.
+//!
+//! The core only reads ephemerides from JPL files in its cache directory, and keeps
+//! the loaded kernel in a private cell of the engine: there is no way to hand it one
+//! built in memory. The model is therefore sampled into a NAIF SPK kernel (one type 2
+//! Chebyshev segment, Earth–Moon barycenter relative to the solar-system barycenter,
+//! 1900–2100), written to a private scratch directory which the cache directory is
+//! pointed at while the core loads it. The kernel then lives in memory like any
+//! other: every code path of the core (observer positions, light time) uses it
+//! unchanged.
+//!
+//! The redirection goes through the process environment, so it holds the lock of
+//! `ephemeris::load`: no other kernel load of the extension can observe the
+//! scratch directory. The kernel takes the cache name of `naif:DE432`, a superseded
+//! kernel, never that of a kernel suggested to users.
+//!
+//! See also
+//! ------------
+//! * [`crate::ephemeris`] – Checks run on every loaded kernel, this one included.
+use std::f64::consts::PI;
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::MutexGuard;
+use std::{env, fs, process};
+
+use outfit::{error_models::ErrorModel, Outfit};
+use pyo3::{
+    exceptions::{PyNotImplementedError, PyOSError, PyValueError},
+    prelude::*,
+};
+
+use crate::IntoPyResult;
+
+/// Source of the compiled-in selectors (`"builtin:approx"`).
+const SOURCE: &str = "builtin";
+
+/// Core selector whose cache file name the generated kernel takes, in the scratch
+/// directory only.
+const HOST_SELECTOR: &str = "naif:DE432";
+const HOST_FILE: &str = "de432.bsp";
+
+/// First epoch of the kernel, 1900-01-01 0h TDB (MJD), and its records.
+const START_MJD: f64 = 15020.0;
+const RECORD_DAYS: f64 = 16.0;
+const N_RECORDS: usize = 4566;
+/// Chebyshev coefficients per coordinate and record.
+const N_COEFFS: usize = 13;
+/// Coverage written in the text header (the end is 2100-01-08 0h).
+const COVERAGE_TEXT: &str = "01-JAN-1900 00:00 to   08-JAN-2100 00:00";
+
+/// J2000 epoch (MJD TDB), origin of the ET seconds of NAIF kernels.
+const J2000_MJD: f64 = 51544.5;
+const SECONDS_PER_DAY: f64 = 86400.0;
+const AU_KM: f64 = 149_597_870.7;
+/// Obliquity of the ecliptic at J2000 (IAU 1976), degrees.
+const OBLIQUITY_DEG: f64 = 23.439_291_1;
+
+/// Keplerian elements at J2000 and their rates per Julian century: `a` (AU), `e`,
+/// `I`, mean longitude `L`, longitude of perihelion `ϖ` and of the node `Ω`
+/// (degrees), referred to the ecliptic and equinox of J2000.
+struct Elements {
+    at_j2000: [f64; 6],
+    per_century: [f64; 6],
+}
+
+/// Earth–Moon barycenter (Standish, 1800–2050 AD table).
+const EM_BARY: Elements = Elements {
+    at_j2000: [
+        1.000_002_61,
+        0.016_711_23,
+        -0.000_015_31,
+        100.464_571_66,
+        102.937_681_93,
+        0.0,
+    ],
+    per_century: [
+        0.000_005_62,
+        -0.000_043_92,
+        -0.012_946_68,
+        35_999.372_449_81,
+        0.323_273_64,
+        0.0,
+    ],
+};
+
+/// Giant planets (same table) with their mass relative to the Sun.
+const GIANTS: [(Elements, f64); 4] = [
+    (
+        Elements {
+            at_j2000: [
+                5.202_887_00,
+                0.048_386_24,
+                1.304_396_95,
+                34.396_440_51,
+                14.728_479_83,
+                100.473_909_09,
+            ],
+            per_century: [
+                -0.000_116_07,
+                -0.000_132_53,
+                -0.001_837_14,
+                3_034.746_127_75,
+                0.212_526_68,
+                0.204_691_06,
+            ],
+        },
+        1.0 / 1_047.348_644,
+    ),
+    (
+        Elements {
+            at_j2000: [
+                9.536_675_94,
+                0.053_861_79,
+                2.485_991_87,
+                49.954_244_23,
+                92.598_878_31,
+                113.662_424_48,
+            ],
+            per_century: [
+                -0.001_250_60,
+                -0.000_509_91,
+                0.001_936_09,
+                1_222.493_622_01,
+                -0.418_972_16,
+                -0.288_677_94,
+            ],
+        },
+        1.0 / 3_497.901_8,
+    ),
+    (
+        Elements {
+            at_j2000: [
+                19.189_164_64,
+                0.047_257_44,
+                0.772_637_83,
+                313.238_104_51,
+                170.954_276_30,
+                74.016_925_03,
+            ],
+            per_century: [
+                -0.001_961_76,
+                -0.000_043_97,
+                -0.002_429_39,
+                428.482_027_85,
+                0.408_052_81,
+                0.042_405_89,
+            ],
+        },
+        1.0 / 22_902.98,
+    ),
+    (
+        Elements {
+            at_j2000: [
+                30.069_922_76,
+                0.008_590_48,
+                1.770_043_47,
+                -55.120_029_69,
+                44.964_762_27,
+                131.784_225_74,
+            ],
+            per_century: [
+                0.000_262_91,
+                0.000_051_05,
+                0.000_353_72,
+                218.459_453_25,
+                -0.322_414_64,
+                -0.005_086_64,
+            ],
+        },
+        1.0 / 19_412.26,
+    ),
+];
+
+impl Elements {
+    /// Heliocentric position (AU, ecliptic J2000) at `t` Julian centuries from J2000.
+    fn position(&self, t: f64) -> [f64; 3] {
+        let el: [f64; 6] = std::array::from_fn(|k| self.at_j2000[k] + self.per_century[k] * t);
+        let (a, e) = (el[0], el[1]);
+        let (inc, node) = (el[2].to_radians(), el[5].to_radians());
+        let peri = (el[4] - el[5]).to_radians();
+        let mean = (el[3] - el[4]).to_radians().rem_euclid(2.0 * PI);
+
+        // Kepler's equation by Newton iterations, converged well below 1e-12 rad.
+        let mut ecc = mean + e * mean.sin();
+        for _ in 0..10 {
+            ecc -= (ecc - e * ecc.sin() - mean) / (1.0 - e * ecc.cos());
+        }
+        let xp = a * (ecc.cos() - e);
+        let yp = a * (1.0 - e * e).sqrt() * ecc.sin();
+
+        let (sw, cw) = peri.sin_cos();
+        let (so, co) = node.sin_cos();
+        let (si, ci) = inc.sin_cos();
+        [
+            (cw * co - sw * so * ci) * xp + (-sw * co - cw * so * ci) * yp,
+            (cw * so + sw * co * ci) * xp + (-sw * so + cw * co * ci) * yp,
+            sw * si * xp + cw * si * yp,
+        ]
+    }
+}
+
+/// Earth–Moon barycenter relative to the solar-system barycenter, in km, in the
+/// equatorial J2000 frame, at `et` seconds (TDB) from J2000.
+fn emb_barycentric_km(et: f64) -> [f64; 3] {
+    let t = et / SECONDS_PER_DAY / 36525.0;
+    let emb = EM_BARY.position(t);
+
+    // Sun offset: Σ m·r over the Sun and the giants vanishes at the barycenter.
+    let mut weighted = [0.0; 3];
+    let mut total = 1.0;
+    for (elements, mass) in &GIANTS {
+        let r = elements.position(t);
+        for k in 0..3 {
+            weighted[k] += mass * r[k];
+        }
+        total += mass;
+    }
+    let ecl: [f64; 3] = std::array::from_fn(|k| emb[k] - weighted[k] / total);
+
+    let (se, ce) = OBLIQUITY_DEG.to_radians().sin_cos();
+    [
+        ecl[0] * AU_KM,
+        (ecl[1] * ce - ecl[2] * se) * AU_KM,
+        (ecl[1] * se + ecl[2] * ce) * AU_KM,
+    ]
+}
+
+/// Chebyshev record `[mid, radius, x[..], y[..], z[..]]` of the interval starting at
+/// `start` (ET seconds), interpolating the model at the Chebyshev nodes.
+fn record(start: f64) -> Vec<f64> {
+    let radius = 0.5 * RECORD_DAYS * SECONDS_PER_DAY;
+    let mid = start + radius;
+    let n = N_COEFFS as f64;
+    let angle = |j: usize, k: usize| PI * k as f64 * (j as f64 + 0.5) / n;
+    let samples: Vec<[f64; 3]> = (0..N_COEFFS)
+        .map(|j| emb_barycentric_km(mid + radius * angle(j, 1).cos()))
+        .collect();
+
+    let mut out = vec![mid, radius];
+    for axis in 0..3 {
+        for k in 0..N_COEFFS {
+            let sum: f64 = samples
+                .iter()
+                .enumerate()
+                .map(|(j, sample)| sample[axis] * angle(j, k).cos())
+                .sum();
+            out.push(if k == 0 { sum / n } else { 2.0 * sum / n });
+        }
+    }
+    out
+}
+
+/// The model as a NAIF SPK (DAF) file.
+///
+/// Layout, in 1024-byte records: file record, comment (JPL text header read by the
+/// core), summary record with the single segment, name record, then the segment
+/// data (Chebyshev records followed by the type 2 directory).
+fn kernel() -> Vec<u8> {
+    const RECORD: usize = 1024;
+    const DATA_ADDRESS: usize = 4 * RECORD / 8 + 1;
+    let rsize = 2 + 3 * N_COEFFS;
+    let init = (START_MJD - J2000_MJD) * SECONDS_PER_DAY;
+    let intlen = RECORD_DAYS * SECONDS_PER_DAY;
+    let end = init + N_RECORDS as f64 * intlen;
+    let final_address = DATA_ADDRESS + N_RECORDS * rsize + 4 - 1;
+
+    let pad = |mut bytes: Vec<u8>, fill: u8| {
+        bytes.resize(bytes.len().div_ceil(RECORD).max(1) * RECORD, fill);
+        bytes
+    };
+    let ints = |values: &[i32]| {
+        values
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>()
+    };
+    let doubles = |values: &[f64]| {
+        values
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>()
+    };
+
+    let mut file_record = b"DAF/SPK ".to_vec();
+    file_record.extend(ints(&[2, 6]));
+    file_record.extend(format!("{:<60}", "py_outfit builtin:approx").bytes());
+    file_record.extend(ints(&[3, 3, final_address as i32 + 1]));
+    file_record.extend(b"LTL-IEEE");
+    file_record.extend([0u8; 603]);
+    file_record.extend(b"FTPSTR:\r:\n:\r\n:\r\x00:\x81:\x10\xce:ENDFTP");
+
+    let start_jd = START_MJD + 2_400_000.5;
+    let comment = format!(
+        "JPL planetary and lunar ephemeris {SOURCE}:approx\n\
+         Integrated analytic model (Standish Keplerian elements), not for science\n\
+         Time span covered by ephemeris:\n\n\
+         {COVERAGE_TEXT}\n\
+         JD   {start_jd:.1}   to   JD   {:.1}\n",
+        start_jd + N_RECORDS as f64 * RECORD_DAYS
+    );
+
+    let mut summary = doubles(&[0.0, 0.0, 1.0, init, end]);
+    summary.extend(ints(&[
+        3,
+        0,
+        1,
+        2,
+        DATA_ADDRESS as i32,
+        final_address as i32,
+    ]));
+    let names = format!("{:<40}", "EMB wrt SSB, builtin:approx");
+
+    let mut data = Vec::with_capacity((N_RECORDS * rsize + 4) * 8);
+    for i in 0..N_RECORDS {
+        data.extend(doubles(&record(init + i as f64 * intlen)));
+    }
+    data.extend(doubles(&[init, intlen, rsize as f64, N_RECORDS as f64]));
+
+    let mut out = pad(file_record, 0);
+    out.extend(pad(comment.into_bytes(), 0));
+    out.extend(pad(summary, 0));
+    out.extend(pad(names.into_bytes(), b' '));
+    out.extend(pad(data, 0));
+    out
+}
+
+/// Whether `selector` names a compiled-in ephemeris.
+///
+/// Return
+/// ----------
+/// * `true` for `"builtin:approx"`, `false` for the JPL selectors, and `ValueError`
+///   for any other `"builtin:*"` name.
+pub(crate) fn is_builtin(selector: &str) -> PyResult<bool> {
+    match selector.split_once(':') {
+        Some((source, name)) if source.eq_ignore_ascii_case(SOURCE) => match name {
+            "approx" => Ok(true),
+            other => Err(PyValueError::new_err(format!(
+                "unknown built-in ephemeris {other:?} (expected 'builtin:approx')"
+            ))),
+        },
+        _ => Ok(false),
+    }
+}
+
+/// Environment variable locating the cache directory of the core, and the path of
+/// that directory below the variable's value.
+#[cfg(target_os = "macos")]
+const CACHE_VAR: Option<(&str, &str)> = Some(("HOME", "Library/Caches"));
+#[cfg(all(unix, not(target_os = "macos")))]
+const CACHE_VAR: Option<(&str, &str)> = Some(("XDG_CACHE_HOME", ""));
+#[cfg(not(unix))]
+const CACHE_VAR: Option<(&str, &str)> = None;
+
+/// Point the cache directory of the core at `root` until dropped, holding off every
+/// other kernel load meanwhile.
+struct CacheRedirect {
+    var: &'static str,
+    saved: Option<OsString>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl CacheRedirect {
+    fn new(var: &'static str, root: &Path) -> Self {
+        let lock = crate::ephemeris::lock_loads();
+        let saved = env::var_os(var);
+        env::set_var(var, root);
+        CacheRedirect {
+            var,
+            saved,
+            _lock: lock,
+        }
+    }
+}
+
+impl Drop for CacheRedirect {
+    fn drop(&mut self) {
+        match self.saved.take() {
+            Some(value) => env::set_var(self.var, value),
+            None => env::remove_var(self.var),
+        }
+    }
+}
+
+/// Write the kernel where the core looks for [`HOST_SELECTOR`] below `cache`.
+fn stage(cache: &Path) -> std::io::Result<()> {
+    let dir = cache.join("outfit_cache/jpl_ephem/naif");
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(HOST_FILE), kernel())
+}
+
+/// Build an engine on the compiled-in ephemeris.
+///
+/// Arguments
+/// -----------------
+/// * `model` – Astrometric error model of the engine.
+///
+/// Return
+/// ----------
+/// * The engine, its ephemeris loaded. Raises `NotImplementedError` on platforms
+///   whose cache directory cannot be redirected (Windows), `OSError` when the scratch
+///   kernel cannot be written.
+///
+/// Notes
+/// ----------
+/// * The cache directory is redirected through an environment variable of the
+///   process (`XDG_CACHE_HOME`, `HOME` on macOS) for the duration of the load only,
+///   under the lock of [`crate::ephemeris::load`]. Code outside the extension reading
+///   that variable meanwhile sees the scratch directory. Call it without the GIL.
+pub(crate) fn load(model: ErrorModel) -> PyResult<Outfit> {
+    let Some((var, below)) = CACHE_VAR else {
+        return Err(PyNotImplementedError::new_err(
+            "'builtin:approx' is only available on Linux and macOS, where the cache \
+             directory of the core can be redirected",
+        ));
+    };
+    let outfit = Outfit::new(HOST_SELECTOR, model).into_py()?;
+
+    let root = env::temp_dir().join(format!(
+        "py_outfit_builtin_{}_{:?}",
+        process::id(),
+        std::thread::current().id()
+    ));
+    let loaded = stage(&root.join(below))
+        .map_err(|e| PyOSError::new_err(format!("cannot write the built-in kernel: {e}")))
+        .and_then(|_| {
+            let _cache = CacheRedirect::new(var, &root);
+            outfit.get_jpl_ephem().map(|_| ()).into_py()
+        });
+    // The kernel is in memory once loaded; the scratch copy is not kept.
+    let _ = fs::remove_dir_all(&root);
+    loaded.map(|_| outfit)
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+    use outfit::jpl_ephem::{
+        download_jpl_file::EphemFileSource,
+        naif::naif_ids::{planet_bary::PlanetaryBary, solar_system_bary::SolarSystemBary, NaifIds},
+        JPLEphem,
+    };
+
+    #[test]
+    fn redirection_holds_off_other_loads() {
+        use std::{sync::mpsc, thread, time::Duration};
+
+        let before = env::var_os("XDG_CACHE_HOME");
+        let cache = CacheRedirect::new("XDG_CACHE_HOME", &env::temp_dir());
+        let (loaded, waiting) = mpsc::channel();
+        let other = thread::spawn(move || {
+            let _loading = crate::ephemeris::lock_loads();
+            loaded.send(env::var_os("XDG_CACHE_HOME")).unwrap();
+        });
+        assert!(waiting.recv_timeout(Duration::from_millis(200)).is_err());
+
+        drop(cache);
+        assert_eq!(waiting.recv().unwrap(), before);
+        other.join().unwrap();
+    }
+
+    #[test]
+    fn kernel_is_read_back_by_the_core() {
+        let root = env::temp_dir().join(format!("py_outfit_builtin_test_{}", process::id()));
+        stage(&root).unwrap();
+        let source: EphemFileSource = HOST_SELECTOR.try_into().unwrap();
+        let ephem = {
+            let _cache = CacheRedirect::new("XDG_CACHE_HOME", &root);
+            JPLEphem::new(&source).unwrap()
+        };
+        fs::remove_dir_all(&root).unwrap();
+        let JPLEphem::NaifFile(naif) = ephem else {
+            panic!("the kernel is not read as a NAIF file");
+        };
+
+        // Interpolation reproduces the model, across records and far from J2000.
+        for et in [0.0, 1.234e8, -2.9e9, 3.1e9] {
+            let emb = naif.ephemeris(
+                NaifIds::PB(PlanetaryBary::EarthMoon),
+                NaifIds::SSB(SolarSystemBary::SSB),
+                et,
+            );
+            let model = emb_barycentric_km(et);
+            for (k, expected) in model.iter().enumerate() {
+                assert!((emb.position[(k, 0)] - expected).abs() < 1.0);
+            }
+        }
+
+        // Heliocentric Earth-Moon barycenter at J2000 (low-precision solar theory).
+        let helio = EM_BARY.position(0.0);
+        let (se, ce) = OBLIQUITY_DEG.to_radians().sin_cos();
+        let equatorial = [
+            helio[0],
+            helio[1] * ce - helio[2] * se,
+            helio[1] * se + helio[2] * ce,
+        ];
+        for (got, expected) in equatorial.iter().zip([-0.17721, 0.88741, 0.38475]) {
+            assert!((got - expected).abs() < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Per-trajectory cost counters of the Gauss IOD search.
+//!
+//! When `IODParams.collect_costs` is enabled, the binding-side driver
+//! ([`crate::iod_engine`]) records how much work each trajectory required. The
+//! counters are plain locals updated in the search loop; with the option disabled
+//! nothing is measured (no clock reads, no counters).
+//!
+//! See also
+//! ------------
+//! * [`crate::iod_gauss::GaussResult`] – Carries the costs of the run that produced it.
+//! * [`crate::results::OrbitResults`] – Per-entry costs and `summary()` aggregation.
+use pyo3::{prelude::*, types::PyDict};
+
+use crate::units;
+
+/// Work spent on the IOD of one trajectory.
+#[pyclass(module = "py_outfit", frozen, eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IodCosts {
+    /// Wall-clock time of the search (milliseconds), uncertainty calibration included.
+    #[pyo3(get)]
+    pub wall_ms: f64,
+    /// Number of ranked triplets whose realizations were evaluated.
+    #[pyo3(get)]
+    pub triplets_attempted: usize,
+    /// Number of noise realizations solved (including the unperturbed one of each triplet).
+    #[pyo3(get)]
+    pub realizations: usize,
+    /// Number of realizations whose solution went through the velocity corrector
+    /// successfully (corrected orbits).
+    #[pyo3(get)]
+    pub corrected_solutions: usize,
+}
+
+#[pymethods]
+impl IodCosts {
+    /// Convert the counters to a Python dict (one key per attribute).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `include_units`: Add a `"units"` block mapping each key to its unit (default: `False`).
+    #[pyo3(signature = (include_units=false), text_signature = "(self, include_units=False)")]
+    pub fn to_dict<'py>(
+        &self,
+        py: Python<'py>,
+        include_units: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let d = PyDict::new(py);
+        d.set_item("wall_ms", self.wall_ms)?;
+        d.set_item("triplets_attempted", self.triplets_attempted)?;
+        d.set_item("realizations", self.realizations)?;
+        d.set_item("corrected_solutions", self.corrected_solutions)?;
+        if include_units {
+            d.set_item("units", units::units_block(py, &units::COST_FIELDS, false)?)?;
+        }
+        Ok(d)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "IodCosts(wall_ms={:.3}, triplets_attempted={}, realizations={}, corrected_solutions={})",
+            self.wall_ms, self.triplets_attempted, self.realizations, self.corrected_solutions
+        )
+    }
+}
+
+impl IodCosts {
+    /// Rebuild counters from the keys of `to_dict` (extra keys are ignored).
+    ///
+    /// Return
+    /// ----------
+    /// * The counters, or `KeyError` naming `context` and the missing key.
+    pub(crate) fn from_mapping(mapping: &Bound<'_, PyAny>, context: &str) -> PyResult<Self> {
+        let count = |key: &str| units::count(&units::field(mapping, context, key)?, key);
+        Ok(Self {
+            wall_ms: units::field(mapping, context, "wall_ms")?.extract()?,
+            triplets_attempted: count("triplets_attempted")?,
+            realizations: count("realizations")?,
+            corrected_solutions: count("corrected_solutions")?,
+        })
+    }
+}
+
+impl std::ops::AddAssign for IodCosts {
+    fn add_assign(&mut self, other: Self) {
+        self.wall_ms += other.wall_ms;
+        self.triplets_attempted += other.triplets_attempted;
+        self.realizations += other.realizations;
+        self.corrected_solutions += other.corrected_solutions;
+    }
+}
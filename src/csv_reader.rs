@@ -0,0 +1,409 @@
+//! Reader for delimited text files (CSV) of astrometry.
+//!
+//! `TrajectorySet.from_csv` reads the file in Rust, without going through pandas: the
+//! bytes are decompressed when they start with the gzip magic, then split into records
+//! by the `csv` crate (RFC 4180 quoting: fields between double quotes may hold the
+//! delimiter, line breaks and doubled quotes). Blank lines and lines starting with `#`
+//! are skipped.
+//!
+//! Columns are selected by header name or by 0-based index (`ColumnRef`). Rows that
+//! cannot be read (missing field, empty id, value that is not a number) are returned
+//! with their line number, so that the caller can report or skip them; structural
+//! problems (unknown column, unterminated quote) abort the whole file.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.from_csv` – Python entry point.
+//! * [`crate::records`] – `Malformed` rows of the other text readers.
+use miniz_oxide::{
+    inflate::stream::{inflate, InflateState},
+    DataFormat, MZFlush, MZStatus,
+};
+
+use crate::records::Malformed;
+
+/// Column of a field, by header name or by 0-based index.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ColumnRef {
+    Name(String),
+    Index(usize),
+}
+
+impl std::fmt::Display for ColumnRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnRef::Name(name) => write!(f, "{name:?}"),
+            ColumnRef::Index(i) => write!(f, "#{i}"),
+        }
+    }
+}
+
+/// Reading options of [`parse`].
+pub(crate) struct Options {
+    /// Field delimiter (an ASCII character other than `"`, `#` and line breaks).
+    pub(crate) delimiter: u8,
+    /// Whether the first record names the columns.
+    pub(crate) header: bool,
+    /// Columns of the trajectory id, epoch, RA and Dec.
+    pub(crate) columns: [ColumnRef; 4],
+    /// Columns of the per-row RA / Dec uncertainties, and whether they must exist (when
+    /// they do not, the uncertainties are not read).
+    pub(crate) sigmas: Option<([ColumnRef; 2], bool)>,
+}
+
+/// Columns read from the file, one entry per valid row.
+#[derive(Default)]
+pub(crate) struct Table {
+    /// Trajectory ids, as written.
+    pub(crate) ids: Vec<String>,
+    pub(crate) time: Vec<f64>,
+    pub(crate) ra: Vec<f64>,
+    pub(crate) dec: Vec<f64>,
+    /// Per-row uncertainties, when their columns were read.
+    pub(crate) sigmas: Option<(Vec<f64>, Vec<f64>)>,
+}
+
+impl Table {
+    /// Append the rows of `other`. Uncertainty columns read in only one of the tables are
+    /// unknown (NaN) for the rows of the other.
+    pub(crate) fn append(&mut self, other: Table) {
+        let (n, m) = (self.ids.len(), other.ids.len());
+        self.ids.extend(other.ids);
+        self.time.extend(other.time);
+        self.ra.extend(other.ra);
+        self.dec.extend(other.dec);
+        self.sigmas = match (self.sigmas.take(), other.sigmas) {
+            (None, None) => None,
+            (mine, theirs) => {
+                let (mut ra, mut dec) =
+                    mine.unwrap_or_else(|| (vec![f64::NAN; n], vec![f64::NAN; n]));
+                let (other_ra, other_dec) =
+                    theirs.unwrap_or_else(|| (vec![f64::NAN; m], vec![f64::NAN; m]));
+                ra.extend(other_ra);
+                dec.extend(other_dec);
+                Some((ra, dec))
+            }
+        };
+    }
+}
+
+/// Error aborting the whole file.
+pub(crate) enum CsvError {
+    /// A column is not in the header: `(column, header names)`.
+    MissingColumn(String, Vec<String>),
+    /// Invalid structure, with its line number.
+    Syntax(usize, String),
+}
+
+/// Records of a delimited text, with the line each one starts on.
+struct Records<'a> {
+    text: &'a str,
+    reader: csv::Reader<&'a [u8]>,
+    delimiter: u8,
+    /// Byte offset and line number of the last record start.
+    offset: usize,
+    line: usize,
+}
+
+impl<'a> Records<'a> {
+    fn new(text: &'a str, delimiter: u8) -> Self {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .comment(Some(b'#'))
+            .from_reader(text.as_bytes());
+        Records {
+            text,
+            reader,
+            delimiter,
+            offset: 0,
+            line: 1,
+        }
+    }
+
+    /// Read the next record into `record`, skipping blank and `#` lines.
+    ///
+    /// Return
+    /// ----------
+    /// * The line the record starts on, `None` at the end of the text, or the syntax
+    ///   error of an unterminated quoted field.
+    fn next_into(&mut self, record: &mut csv::StringRecord) -> Option<Result<usize, CsvError>> {
+        loop {
+            let read = self.reader.read_record(record);
+            let start = self.record_start(record.position().map_or(0, |p| p.byte() as usize));
+            let line = self.line;
+            match read {
+                Ok(false) => return None,
+                Err(e) => return Some(Err(CsvError::Syntax(line, e.to_string()))),
+                Ok(true) => {}
+            }
+            // The reader closes a quoted field left open at the end of the text.
+            let at_end = self.reader.position().byte() as usize >= self.text.len();
+            if at_end && unterminated(&self.text[start..], self.delimiter) {
+                return Some(Err(CsvError::Syntax(
+                    line,
+                    "unterminated quoted field".into(),
+                )));
+            }
+            if record.len() == 1 && record[0].trim().is_empty() {
+                continue;
+            }
+            return Some(Ok(line));
+        }
+    }
+
+    /// Move past the blank and `#` lines the reader skipped from byte `from`, and
+    /// return the offset the record starts at.
+    ///
+    /// The positions of the `csv` reader count neither of them.
+    fn record_start(&mut self, from: usize) -> usize {
+        let bytes = self.text.as_bytes();
+        let mut start = from.max(self.offset);
+        loop {
+            match bytes.get(start) {
+                Some(b'\n' | b'\r') => start += 1,
+                Some(b'#') => {
+                    start += bytes[start..]
+                        .iter()
+                        .position(|&b| b == b'\n')
+                        .map_or(bytes.len() - start, |k| k + 1)
+                }
+                _ => break,
+            }
+        }
+        self.line += bytes[self.offset..start]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        self.offset = start;
+        start
+    }
+}
+
+/// Whether the last record of a text, `raw`, ends inside a quoted field.
+fn unterminated(raw: &str, delimiter: u8) -> bool {
+    let mut quoted = false;
+    let mut field_start = true;
+    let mut bytes = raw.bytes().peekable();
+    while let Some(b) = bytes.next() {
+        match (quoted, b) {
+            (false, b'"') if field_start => quoted = true,
+            (true, b'"') if bytes.peek() == Some(&b'"') => {
+                bytes.next();
+            }
+            (true, b'"') => quoted = false,
+            (false, b) if b == delimiter || b == b'\n' => {
+                field_start = true;
+                continue;
+            }
+            _ => {}
+        }
+        field_start = false;
+    }
+    quoted
+}
+
+/// Read the astrometry of a delimited text.
+///
+/// Arguments
+/// -----------------
+/// * `text` – Content of the file.
+/// * `opts` – Delimiter, header and column selection.
+///
+/// Return
+/// ----------
+/// * The columns of the valid rows and the `(line number, reason)` of the others, or the
+///   error aborting the file: a named column missing from the header, a column name
+///   without header, or a syntax error.
+pub(crate) fn parse(text: &str, opts: &Options) -> Result<(Table, Malformed), CsvError> {
+    let mut records = Records::new(text, opts.delimiter);
+    let mut fields = csv::StringRecord::new();
+
+    let header: Option<Vec<String>> = if opts.header {
+        match records.next_into(&mut fields).transpose()? {
+            Some(_) => Some(fields.iter().map(|f| f.trim().to_string()).collect()),
+            None => Some(Vec::new()),
+        }
+    } else {
+        None
+    };
+    let resolve = |column: &ColumnRef| -> Result<Option<usize>, CsvError> {
+        match (column, &header) {
+            (ColumnRef::Index(i), _) => Ok(Some(*i)),
+            (ColumnRef::Name(name), Some(header)) => Ok(header.iter().position(|h| h == name)),
+            (ColumnRef::Name(name), None) => Err(CsvError::Syntax(
+                1,
+                format!("column {name:?} is selected by name but header=False; use an index"),
+            )),
+        }
+    };
+    let required = |column: &ColumnRef| -> Result<usize, CsvError> {
+        resolve(column)?.ok_or_else(|| {
+            CsvError::MissingColumn(column.to_string(), header.clone().unwrap_or_default())
+        })
+    };
+    let columns = Columns {
+        id: required(&opts.columns[0])?,
+        time: required(&opts.columns[1])?,
+        ra: required(&opts.columns[2])?,
+        dec: required(&opts.columns[3])?,
+        sigmas: match &opts.sigmas {
+            Some(([ra, dec], true)) => Some((required(ra)?, required(dec)?)),
+            Some(([ra, dec], false)) => match (resolve(ra)?, resolve(dec)?) {
+                (Some(ra), Some(dec)) => Some((ra, dec)),
+                _ => None,
+            },
+            None => None,
+        },
+    };
+
+    let mut table = Table {
+        ids: Vec::new(),
+        time: Vec::new(),
+        ra: Vec::new(),
+        dec: Vec::new(),
+        sigmas: columns.sigmas.map(|_| (Vec::new(), Vec::new())),
+    };
+    let mut malformed = Malformed::new();
+    while let Some(line) = records.next_into(&mut fields).transpose()? {
+        match columns.row(&fields) {
+            Ok(row) => {
+                table.ids.push(row.id.to_string());
+                table.time.push(row.time);
+                table.ra.push(row.ra);
+                table.dec.push(row.dec);
+                if let (Some((ra, dec)), Some((sigma_ra, sigma_dec))) =
+                    (table.sigmas.as_mut(), row.sigmas)
+                {
+                    ra.push(sigma_ra);
+                    dec.push(sigma_dec);
+                }
+            }
+            Err(reason) => malformed.push((line, reason)),
+        }
+    }
+    Ok((table, malformed))
+}
+
+/// Field indices of the columns read by [`parse`].
+struct Columns {
+    id: usize,
+    time: usize,
+    ra: usize,
+    dec: usize,
+    sigmas: Option<(usize, usize)>,
+}
+
+/// Values of one row.
+struct Row<'f> {
+    id: &'f str,
+    time: f64,
+    ra: f64,
+    dec: f64,
+    sigmas: Option<(f64, f64)>,
+}
+
+impl Columns {
+    /// Read the values of a record, or the reason it cannot be read.
+    fn row<'f>(&self, fields: &'f csv::StringRecord) -> Result<Row<'f>, String> {
+        let field = |k: usize| -> Result<&'f str, String> {
+            fields
+                .get(k)
+                .map(str::trim)
+                .ok_or_else(|| format!("expected at least {} fields, got {}", k + 1, fields.len()))
+        };
+        let number = |k: usize, what: &str| -> Result<f64, String> {
+            let value = field(k)?;
+            value
+                .parse()
+                .map_err(|_| format!("invalid {what} {value:?}"))
+        };
+        let id = field(self.id)?;
+        if id.is_empty() {
+            return Err("empty trajectory id".into());
+        }
+        Ok(Row {
+            id,
+            time: number(self.time, "time")?,
+            ra: number(self.ra, "ra")?,
+            dec: number(self.dec, "dec")?,
+            sigmas: match self.sigmas {
+                Some((ra, dec)) => Some((number(ra, "sigma_ra")?, number(dec, "sigma_dec")?)),
+                None => None,
+            },
+        })
+    }
+}
+
+/// Whether `data` starts with the gzip magic bytes.
+pub(crate) fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&[0x1f, 0x8b])
+}
+
+/// Decompress gzip data (RFC 1952), concatenated members included.
+///
+/// Return
+/// ----------
+/// * The decompressed bytes, or a description of the corruption (bad header, truncated
+///   or invalid deflate stream, size mismatch with the trailer).
+pub(crate) fn gunzip(mut data: &[u8]) -> Result<Vec<u8>, String> {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+    let truncated = || "truncated gzip data".to_string();
+
+    let mut out = Vec::new();
+    let mut buf = vec![0u8; 1 << 16];
+    while is_gzip(data) {
+        if data.len() < 10 {
+            return Err(truncated());
+        }
+        if data[2] != 8 {
+            return Err(format!("unsupported gzip compression method {}", data[2]));
+        }
+        let flags = data[3];
+        let mut pos = 10;
+        if flags & FEXTRA != 0 {
+            let extra = data.get(pos..pos + 2).ok_or_else(truncated)?;
+            pos += 2 + usize::from(u16::from_le_bytes([extra[0], extra[1]]));
+        }
+        for flag in [FNAME, FCOMMENT] {
+            if flags & flag != 0 {
+                let rest = data.get(pos..).ok_or_else(truncated)?;
+                pos += rest.iter().position(|&b| b == 0).ok_or_else(truncated)? + 1;
+            }
+        }
+        if flags & FHCRC != 0 {
+            pos += 2;
+        }
+        let mut input = data.get(pos..).ok_or_else(truncated)?;
+
+        let member_start = out.len();
+        let mut state = InflateState::new_boxed(DataFormat::Raw);
+        loop {
+            let res = inflate(&mut state, input, &mut buf, MZFlush::None);
+            input = &input[res.bytes_consumed..];
+            out.extend_from_slice(&buf[..res.bytes_written]);
+            match res.status {
+                Ok(MZStatus::StreamEnd) => break,
+                Ok(_) if res.bytes_consumed + res.bytes_written > 0 => {}
+                Ok(_) => return Err(truncated()),
+                Err(_) if input.is_empty() => return Err(truncated()),
+                Err(e) => return Err(format!("invalid deflate stream ({e:?})")),
+            }
+        }
+        let trailer = input.get(..8).ok_or_else(truncated)?;
+        let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+        if size != (out.len() - member_start) as u32 {
+            return Err("decompressed size does not match the gzip trailer".into());
+        }
+        data = &input[8..];
+    }
+    if data.iter().any(|&b| b != 0) {
+        return Err("trailing garbage after the gzip data".into());
+    }
+    Ok(out)
+}
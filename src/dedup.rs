@@ -0,0 +1,285 @@
+//! Removal of duplicate observations within the trajectories of a set.
+//!
+//! Catalogues merged from several sources (or an alert stream replayed twice) often hold
+//! the same detection more than once, which gives it twice the weight in the fit. Two
+//! observations of a trajectory are duplicates when their epochs differ by at most
+//! `time_tol` days and their positions by at most `sep_mas` milliarcseconds; the site and
+//! the uncertainties are not compared. Exact duplicates are the special case of zero
+//! differences.
+//!
+//! Trajectories are kept in canonical order (see `ingest::canonical_cmp`), so the
+//! duplicates of an observation can only be among the observations just before it in
+//! time; the first observation of a group of duplicates is kept.
+//!
+//! Linking errors can also place the same detection in two different trajectories.
+//! [`shared_observations`] finds such observations across a set: same epoch and position
+//! within the tolerances, and same site. Observations are bucketed in a hash of epoch and
+//! unit-vector cells as large as the tolerances, so only the neighbouring cells of an
+//! observation are searched and the cost grows linearly with the size of the set.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.deduplicate` – Method on built sets.
+//! * `TrajectorySet.from_numpy_degrees` – Ingestion with `dedup=`.
+//! * `TrajectorySet.find_shared_observations` / `TrajectorySet.remove_shared` – Detections
+//!   shared by several trajectories.
+use std::collections::HashMap;
+
+use outfit::{constants::RADSEC, observations::Observation, ObjectNumber, Observations};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::PyBool,
+};
+use rayon::prelude::*;
+
+/// Default epoch tolerance, in days (~9 ms).
+pub const DEFAULT_TIME_TOL: f64 = 1e-7;
+
+/// Default position tolerance, in milliarcseconds.
+pub const DEFAULT_SEP_MAS: f64 = 1.0;
+
+/// Differences below which two observations are duplicates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    /// Epoch difference, in days.
+    pub days: f64,
+    /// Angular separation, in radians.
+    pub sep_rad: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            days: DEFAULT_TIME_TOL,
+            sep_rad: DEFAULT_SEP_MAS * RADSEC / 1000.0,
+        }
+    }
+}
+
+impl Tolerance {
+    /// Tolerance from the Python arguments, checked to be non-negative and finite.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `time_tol` – Epoch tolerance, in days.
+    /// * `sep_mas` – Position tolerance, in milliarcseconds.
+    pub fn new(time_tol: f64, sep_mas: f64) -> PyResult<Self> {
+        for (name, value) in [("time_tol", time_tol), ("sep_mas", sep_mas)] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(PyValueError::new_err(format!(
+                    "{name} must be a non-negative finite number, got {value}"
+                )));
+            }
+        }
+        Ok(Self {
+            days: time_tol,
+            sep_rad: sep_mas * RADSEC / 1000.0,
+        })
+    }
+
+    /// Tolerance of the shared-observation search, checked like [`Tolerance::new`].
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `time_tol_days` – Epoch tolerance, in days.
+    /// * `pos_tol_arcsec` – Position tolerance, in arcseconds.
+    pub fn shared(time_tol_days: f64, pos_tol_arcsec: f64) -> PyResult<Self> {
+        for (name, value) in [
+            ("time_tol_days", time_tol_days),
+            ("pos_tol_arcsec", pos_tol_arcsec),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(PyValueError::new_err(format!(
+                    "{name} must be a non-negative finite number, got {value}"
+                )));
+            }
+        }
+        Ok(Self {
+            days: time_tol_days,
+            sep_rad: pos_tol_arcsec * RADSEC,
+        })
+    }
+
+    fn matches(&self, kept: &Observation, o: &Observation) -> bool {
+        separation(kept, o) <= self.sep_rad
+    }
+}
+
+/// Default epoch tolerance of the shared-observation search, in days (~86 ms).
+pub const DEFAULT_SHARED_TIME_TOL: f64 = 1e-6;
+
+/// Default position tolerance of the shared-observation search, in arcseconds.
+pub const DEFAULT_SHARED_POS_TOL: f64 = 0.05;
+
+/// Distance below which two geocentric site positions are the same site, in AU (1 km).
+const SITE_TOL_AU: f64 = 1.0 / 149_597_870.7;
+
+/// Equatorial speed of the Earth rotation, in AU per day: a site moves by up to this much
+/// between two epochs within the tolerance.
+const EARTH_ROTATION_AU_PER_DAY: f64 =
+    2.0 * std::f64::consts::PI * 6378.137 / 0.997_269_57 / 149_597_870.7;
+
+/// Smallest cell sizes of the hash, so that zero tolerances (exact matches) still give
+/// finite cell indices.
+const MIN_CELL_DAYS: f64 = 1e-9;
+const MIN_CELL_RAD: f64 = 1e-12;
+
+/// Hash cell of an observation: epoch bin, then the bins of its unit vector.
+type Cell = [i64; 4];
+
+/// Position of an observation: index of its trajectory, index within the trajectory.
+pub type ObsIndex = (usize, usize);
+
+fn cell(o: &Observation, tol: &Tolerance) -> Cell {
+    let step = tol.sep_rad.max(MIN_CELL_RAD);
+    let (sin_dec, cos_dec) = o.dec.sin_cos();
+    let (sin_ra, cos_ra) = o.ra.sin_cos();
+    [
+        (o.time / tol.days.max(MIN_CELL_DAYS)).floor() as i64,
+        (cos_dec * cos_ra / step).floor() as i64,
+        (cos_dec * sin_ra / step).floor() as i64,
+        (sin_dec / step).floor() as i64,
+    ]
+}
+
+/// Whether two observations are the same detection: epochs and positions within `tol`,
+/// and sites within 1 km once the Earth rotation between the epochs is allowed for.
+fn same_detection(a: &Observation, b: &Observation, tol: &Tolerance) -> bool {
+    let dt = (a.time - b.time).abs();
+    if dt > tol.days || !tol.matches(a, b) {
+        return false;
+    }
+    let (sa, sb) = (
+        a.get_observer_earth_position(),
+        b.get_observer_earth_position(),
+    );
+    (sa - sb).norm() <= SITE_TOL_AU + EARTH_ROTATION_AU_PER_DAY * dt
+}
+
+/// Pairs of observations shared by two different trajectories.
+///
+/// A pair `(a, b)` is reported once, with `a.0 < b.0`; an observation matching several
+/// observations of other trajectories appears in several pairs.
+///
+/// Arguments
+/// -----------------
+/// * `trajectories` – The trajectories to compare, indexed by the first element of
+///   [`ObsIndex`].
+/// * `tol` – Epoch and position tolerances, see [`Tolerance::shared`].
+///
+/// Return
+/// ----------
+/// * The shared pairs, sorted.
+pub fn shared_observations(
+    trajectories: &[&Observations],
+    tol: &Tolerance,
+) -> Vec<(ObsIndex, ObsIndex)> {
+    let mut cells: HashMap<Cell, Vec<ObsIndex>> = HashMap::new();
+    for (t, obs) in trajectories.iter().enumerate() {
+        for (i, o) in obs.iter().enumerate() {
+            cells.entry(cell(o, tol)).or_default().push((t, i));
+        }
+    }
+    let mut pairs: Vec<(ObsIndex, ObsIndex)> = trajectories
+        .par_iter()
+        .enumerate()
+        .flat_map_iter(|(t, obs)| {
+            let cells = &cells;
+            obs.iter().enumerate().flat_map(move |(i, o)| {
+                let [ct, cx, cy, cz] = cell(o, tol);
+                let mut found = Vec::new();
+                for dt in -1..=1 {
+                    for dx in -1..=1 {
+                        for dy in -1..=1 {
+                            for dz in -1..=1 {
+                                let Some(near) = cells.get(&[ct + dt, cx + dx, cy + dy, cz + dz])
+                                else {
+                                    continue;
+                                };
+                                found.extend(
+                                    near.iter()
+                                        .filter(|&&(u, j)| {
+                                            u > t && same_detection(o, &trajectories[u][j], tol)
+                                        })
+                                        .map(|&other| ((t, i), other)),
+                                );
+                            }
+                        }
+                    }
+                }
+                found
+            })
+        })
+        .collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+/// `dedup` argument of the loaders: `False`, `True` (default tolerances) or a
+/// `(time_tol, sep_mas)` pair.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Dedup(pub Option<Tolerance>);
+
+impl<'py> FromPyObject<'py> for Dedup {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(flag) = ob.cast::<PyBool>() {
+            return Ok(Dedup(flag.is_true().then(Tolerance::default)));
+        }
+        match ob.extract::<(f64, f64)>() {
+            Ok((time_tol, sep_mas)) => Tolerance::new(time_tol, sep_mas).map(|t| Dedup(Some(t))),
+            Err(_) => Err(PyTypeError::new_err(format!(
+                "dedup must be a bool or a (time_tol, sep_mas) pair, got {}",
+                ob.get_type().name()?
+            ))),
+        }
+    }
+}
+
+/// Angular separation of two observations, in radians (haversine formula, accurate for
+/// the sub-arcsecond separations compared here).
+fn separation(a: &Observation, b: &Observation) -> f64 {
+    let half_dra = 0.5 * (b.ra - a.ra);
+    let half_ddec = 0.5 * (b.dec - a.dec);
+    let h = half_ddec.sin().powi(2) + a.dec.cos() * b.dec.cos() * half_dra.sin().powi(2);
+    2.0 * h.sqrt().min(1.0).asin()
+}
+
+/// Drop the duplicates of a trajectory stored in canonical order.
+///
+/// Return
+/// ----------
+/// * The number of observations removed.
+pub fn dedup_trajectory(obs: &mut Observations, tol: &Tolerance) -> usize {
+    let mut kept = 0;
+    for i in 0..obs.len() {
+        let o = obs[i];
+        let duplicate = obs[..kept]
+            .iter()
+            .rev()
+            .take_while(|k| o.time - k.time <= tol.days)
+            .any(|k| tol.matches(k, &o));
+        if !duplicate {
+            obs[kept] = o;
+            kept += 1;
+        }
+    }
+    let removed = obs.len() - kept;
+    obs.truncate(kept);
+    removed
+}
+
+/// Drop the duplicates of every trajectory of `set`.
+///
+/// Return
+/// ----------
+/// * The number of observations removed per trajectory, for the trajectories that had
+///   duplicates.
+pub fn dedup_set(set: &mut outfit::TrajectorySet, tol: &Tolerance) -> HashMap<ObjectNumber, usize> {
+    set.iter_mut()
+        .filter_map(|(key, obs)| match dedup_trajectory(obs, tol) {
+            0 => None,
+            n => Some((key.clone(), n)),
+        })
+        .collect()
+}
@@ -0,0 +1,187 @@
+//! Full-arc differential correction of an orbit.
+//!
+//! A Levenberg-Marquardt least-squares fit of the six equinoctial elements (at a fixed
+//! epoch) to every observation of an arc, starting from a given orbit. The residuals are
+//! those of the RMS reported by the IOD (`Observation::ephemeris_error`): right ascension
+//! offsets scaled by `cos δ`, both coordinates divided by their uncertainty. Partial
+//! derivatives are central finite differences of the apparent positions.
+//!
+//! See also
+//! ------------
+//! * `iod_engine::correct_from_guess` – Batch entry point (`initial_guesses`).
+use std::f64::consts::{PI, TAU};
+
+use nalgebra::{Matrix6, Vector6};
+use outfit::{observations::Observation, EquinoctialElements, Outfit, OutfitError};
+
+/// Maximum number of accepted Levenberg-Marquardt steps.
+const MAX_ITERATIONS: usize = 50;
+/// Relative decrease of the cost below which the fit is converged.
+const COST_TOLERANCE: f64 = 1e-10;
+/// Relative finite-difference step (absolute below unit magnitude).
+const FD_STEP: f64 = 1e-6;
+/// Damping above which no descent step exists anymore (stationary point).
+const MAX_DAMPING: f64 = 1e12;
+/// Normalized RMS above which a stationary point is a spurious minimum.
+const MAX_RMS: f64 = 10.0;
+
+fn to_vector(e: &EquinoctialElements) -> Vector6<f64> {
+    Vector6::new(
+        e.semi_major_axis,
+        e.eccentricity_sin_lon,
+        e.eccentricity_cos_lon,
+        e.tan_half_incl_sin_node,
+        e.tan_half_incl_cos_node,
+        e.mean_longitude,
+    )
+}
+
+fn from_vector(epoch: f64, x: &Vector6<f64>) -> EquinoctialElements {
+    EquinoctialElements {
+        reference_epoch: epoch,
+        semi_major_axis: x[0],
+        eccentricity_sin_lon: x[1],
+        eccentricity_cos_lon: x[2],
+        tan_half_incl_sin_node: x[3],
+        tan_half_incl_cos_node: x[4],
+        mean_longitude: x[5],
+    }
+}
+
+/// Normalized residuals (`[ra_0, dec_0, ra_1, …]`) of the elements `x` over `obs`.
+fn residuals(
+    obs: &[Observation],
+    state: &Outfit,
+    epoch: f64,
+    x: &Vector6<f64>,
+) -> Result<Vec<f64>, OutfitError> {
+    if x[0].is_nan() || x[0] <= 0.0 {
+        return Err(OutfitError::InvalidOrbit(
+            "non-positive semi-major axis".to_string(),
+        ));
+    }
+    let elements = from_vector(epoch, x);
+    let mut out = Vec::with_capacity(2 * obs.len());
+    for o in obs {
+        let (alpha, delta) = o.compute_apparent_position(state, &elements)?;
+        let diff_alpha = (o.ra - alpha + PI).rem_euclid(TAU) - PI;
+        out.push(o.dec.cos() * diff_alpha / o.error_ra);
+        out.push((o.dec - delta) / o.error_dec);
+    }
+    Ok(out)
+}
+
+fn sum_of_squares(r: &[f64]) -> f64 {
+    r.iter().map(|v| v * v).sum()
+}
+
+/// Normal equations `(JᵀJ, Jᵀr)` at `x`, with `r` the residuals at `x`.
+fn normal_equations(
+    obs: &[Observation],
+    state: &Outfit,
+    epoch: f64,
+    x: &Vector6<f64>,
+    r: &[f64],
+) -> Result<(Matrix6<f64>, Vector6<f64>), OutfitError> {
+    let mut columns: Vec<Vec<f64>> = Vec::with_capacity(6);
+    for j in 0..6 {
+        let h = FD_STEP * x[j].abs().max(1.0);
+        let mut plus = *x;
+        let mut minus = *x;
+        plus[j] += h;
+        minus[j] -= h;
+        let rp = residuals(obs, state, epoch, &plus)?;
+        let rm = residuals(obs, state, epoch, &minus)?;
+        columns.push(
+            rp.iter()
+                .zip(&rm)
+                .map(|(p, m)| (p - m) / (2.0 * h))
+                .collect(),
+        );
+    }
+
+    let mut jtj = Matrix6::zeros();
+    let mut jtr = Vector6::zeros();
+    for i in 0..6 {
+        jtr[i] = columns[i].iter().zip(r).map(|(a, b)| a * b).sum();
+        for k in i..6 {
+            let v: f64 = columns[i].iter().zip(&columns[k]).map(|(a, b)| a * b).sum();
+            jtj[(i, k)] = v;
+            jtj[(k, i)] = v;
+        }
+    }
+    Ok((jtj, jtr))
+}
+
+/// Fit `guess` to every observation of `obs`.
+///
+/// Arguments
+/// -----------------
+/// * `obs` – Arc to fit (uncertainties as used for the RMS).
+/// * `state` – Global Outfit environment.
+/// * `guess` – Starting orbit; its epoch is kept for the fitted elements.
+///
+/// Return
+/// ----------
+/// * `Some((elements, rms))` once the relative decrease of the cost falls below the
+///   tolerance (or no descent step is left), with `rms` the normalized RMS over the
+///   arc; `None` when the fit diverged: the residuals or the Jacobian cannot be
+///   evaluated, the iteration budget is exhausted, or the fit ends above a normalized
+///   RMS of 10.
+pub(crate) fn correct(
+    obs: &[Observation],
+    state: &Outfit,
+    guess: &EquinoctialElements,
+) -> Option<(EquinoctialElements, f64)> {
+    let epoch = guess.reference_epoch;
+    let denom = 2.0 * obs.len() as f64;
+    let mut x = to_vector(guess);
+    let mut r = residuals(obs, state, epoch, &x).ok()?;
+    let mut cost = sum_of_squares(&r);
+    let mut damping = 1e-3;
+    let mut converged = false;
+
+    for _ in 0..MAX_ITERATIONS {
+        let (jtj, jtr) = normal_equations(obs, state, epoch, &x, &r).ok()?;
+        let accepted = loop {
+            if damping > MAX_DAMPING {
+                break None;
+            }
+            let mut a = jtj;
+            for i in 0..6 {
+                a[(i, i)] += damping * jtj[(i, i)].max(f64::MIN_POSITIVE);
+            }
+            let step = a.cholesky().map(|c| c.solve(&-jtr));
+            let trial = step
+                .map(|s| x + s)
+                .filter(|t| t.iter().all(|v| v.is_finite()));
+            if let Some(trial) = trial {
+                if let Ok(rt) = residuals(obs, state, epoch, &trial) {
+                    let ct = sum_of_squares(&rt);
+                    if ct < cost {
+                        break Some((trial, rt, ct));
+                    }
+                }
+            }
+            damping *= 10.0;
+        };
+
+        let Some((trial, rt, ct)) = accepted else {
+            // No descent direction left: `x` is a stationary point.
+            converged = true;
+            break;
+        };
+        let decrease = cost - ct;
+        x = trial;
+        r = rt;
+        cost = ct;
+        damping = (damping / 10.0).max(1e-12);
+        if decrease <= COST_TOLERANCE * cost {
+            converged = true;
+            break;
+        }
+    }
+
+    let rms = (cost / denom).sqrt();
+    (converged && rms <= MAX_RMS).then(|| (from_vector(epoch, &x), rms))
+}
@@ -0,0 +1,479 @@
+//! Introspection of the loaded JPL ephemeris.
+//!
+//! The Gauss IOD pipeline only queries a handful of bodies (Earth, Moon, Sun or the
+//! Earth–Moon barycenter, depending on the kernel flavour). When a kernel lacks one
+//! of them, the Rust core fails deep inside the interpolation code. This module
+//! probes the kernel once, at environment construction, so that the failure is
+//! reported up-front with an actionable message.
+//!
+//! It also maps the continuous segments of the kernel: epochs close to a segment
+//! boundary yield interpolation glitches (nonsense orbits rather than errors), so they
+//! are rejected at ingestion and estimation.
+//!
+//! See also
+//! ------------
+//! * [`crate::errors::EphemerisIncompleteError`] – Exception raised on missing bodies.
+//! * [`crate::errors::EphemerisCoverageError`] – Exception raised on epochs near a
+//!   segment boundary.
+//! * [`crate::PyOutfit::available_bodies`] – Python-side introspection.
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, MutexGuard, Once, PoisonError};
+
+use outfit::constants::SECONDS_PER_DAY;
+use outfit::jpl_ephem::{
+    horizon::horizon_ids::HorizonID,
+    naif::naif_ids::{
+        planet_bary::PlanetaryBary, planet_mass::PlanetMassCenter,
+        satellite_mass::SatelliteMassCenter, solar_system_bary::SolarSystemBary, NaifIds,
+    },
+    JPLEphem,
+};
+use outfit::{outfit_errors::OutfitError, Outfit};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::errors::{EphemerisCoverageError, EphemerisIncompleteError};
+
+/// Probe epoch: J2000 (MJD TDB for Horizons files, ET seconds for NAIF kernels).
+const PROBE_MJD: f64 = 51544.5;
+const PROBE_ET: f64 = 0.0;
+
+/// Serializes the kernel loads of the core. `builtin:approx` points the cache
+/// directory of the core at a scratch kernel while it loads (see
+/// [`crate::builtin_ephemeris`]); no other load may run meanwhile.
+static KERNEL_LOADS: Mutex<()> = Mutex::new(());
+
+/// Hold off every kernel load until the guard is dropped. Take it without the GIL.
+pub(crate) fn lock_loads() -> MutexGuard<'static, ()> {
+    KERNEL_LOADS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Load the kernel of `outfit`, serialized with the other loads (no-op once loaded).
+///
+/// Every first call of `Outfit::get_jpl_ephem` goes through here; call it without
+/// the GIL.
+pub(crate) fn load(outfit: &Outfit) -> Result<&JPLEphem, OutfitError> {
+    let _loading = lock_loads();
+    outfit.get_jpl_ephem()
+}
+
+/// Kernels known to contain every body required by the IOD pipeline.
+pub(crate) const COMPLETE_KERNELS: [&str; 4] =
+    ["horizon:DE440", "horizon:DE430", "naif:DE440", "naif:DE442"];
+
+const HORIZON_BODIES: [(&str, HorizonID); 11] = [
+    ("Mercury", HorizonID::Mercury),
+    ("Venus", HorizonID::Venus),
+    ("Earth", HorizonID::Earth),
+    ("Mars", HorizonID::Mars),
+    ("Jupiter", HorizonID::Jupiter),
+    ("Saturn", HorizonID::Saturn),
+    ("Uranus", HorizonID::Uranus),
+    ("Neptune", HorizonID::Neptune),
+    ("Pluto", HorizonID::Pluto),
+    ("Moon", HorizonID::Moon),
+    ("Sun", HorizonID::Sun),
+];
+
+const NAIF_BODIES: [(&str, NaifIds, NaifIds); 12] = {
+    const SSB: NaifIds = NaifIds::SSB(SolarSystemBary::SSB);
+    const EMB: NaifIds = NaifIds::PB(PlanetaryBary::EarthMoon);
+    [
+        (
+            "Mercury barycenter",
+            NaifIds::PB(PlanetaryBary::Mercury),
+            SSB,
+        ),
+        ("Venus barycenter", NaifIds::PB(PlanetaryBary::Venus), SSB),
+        ("Earth-Moon barycenter", EMB, SSB),
+        ("Mars barycenter", NaifIds::PB(PlanetaryBary::Mars), SSB),
+        (
+            "Jupiter barycenter",
+            NaifIds::PB(PlanetaryBary::Jupiter),
+            SSB,
+        ),
+        ("Saturn barycenter", NaifIds::PB(PlanetaryBary::Saturn), SSB),
+        ("Uranus barycenter", NaifIds::PB(PlanetaryBary::Uranus), SSB),
+        (
+            "Neptune barycenter",
+            NaifIds::PB(PlanetaryBary::Neptune),
+            SSB,
+        ),
+        ("Pluto barycenter", NaifIds::PB(PlanetaryBary::Pluto), SSB),
+        ("Sun", NaifIds::SSB(SolarSystemBary::Sun), SSB),
+        ("Earth", NaifIds::PMC(PlanetMassCenter::Earth), EMB),
+        ("Moon", NaifIds::SMC(SatelliteMassCenter::Moon), EMB),
+    ]
+};
+
+thread_local! {
+    /// Set while the current thread runs a [`probe`]: its panics are expected.
+    static PROBING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Install, once per process, a panic hook that stays silent for the panics of
+/// [`probe`] and forwards every other panic to the hook it replaced.
+fn install_probe_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !PROBING.with(Cell::get) {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Run `f`, turning a panic into `None` without printing the panic message.
+///
+/// Notes
+/// ----------
+/// * The panic hook is global to the process. It is not swapped around each probe,
+///   which would race with panics and hook changes on other threads (rayon workers
+///   included): the hook installed by [`install_probe_hook`] only silences the
+///   panics of the thread running the probe.
+pub(crate) fn probe<T>(f: impl FnOnce() -> T) -> Option<T> {
+    install_probe_hook();
+    PROBING.with(|p| p.set(true));
+    let out = panic::catch_unwind(AssertUnwindSafe(f)).ok();
+    PROBING.with(|p| p.set(false));
+    out
+}
+
+/// List the bodies that can be interpolated from the loaded kernel.
+///
+/// Arguments
+/// -----------------
+/// * `ephem` – Loaded JPL ephemeris (Horizons binary or NAIF SPK).
+///
+/// Return
+/// ----------
+/// * Human-readable body names, in a stable order.
+///
+/// Notes
+/// ----------
+/// * Each body is probed once at J2000; a body counts as available when the
+///   interpolation succeeds with a finite state vector.
+pub(crate) fn available_bodies(ephem: &JPLEphem) -> Vec<&'static str> {
+    match ephem {
+        JPLEphem::HorizonFile(data) => HORIZON_BODIES
+            .iter()
+            .filter(|(_, id)| {
+                probe(|| {
+                    let r = data.ephemeris(*id, *id, PROBE_MJD, false, false);
+                    r.position.iter().all(|v| v.is_finite())
+                })
+                .unwrap_or(false)
+            })
+            .map(|(name, _)| *name)
+            .collect(),
+        JPLEphem::NaifFile(data) => NAIF_BODIES
+            .iter()
+            .filter(|(_, target, center)| {
+                probe(|| {
+                    let r = data.ephemeris(*target, *center, PROBE_ET);
+                    r.position.iter().all(|v| v.is_finite())
+                })
+                .unwrap_or(false)
+            })
+            .map(|(name, _, _)| *name)
+            .collect(),
+    }
+}
+
+/// Bodies queried by the IOD pipeline for a given kernel flavour.
+pub(crate) fn required_bodies(ephem: &JPLEphem) -> &'static [&'static str] {
+    match ephem {
+        JPLEphem::HorizonFile(_) => &["Earth", "Moon", "Sun"],
+        JPLEphem::NaifFile(_) => &["Earth-Moon barycenter"],
+    }
+}
+
+/// Build the `EphemerisIncompleteError` for a list of missing bodies.
+///
+/// The exception carries `missing_bodies` and `suggested_kernels` attributes so that
+/// callers can react programmatically.
+pub(crate) fn incomplete_error(py: Python<'_>, missing: &[&str]) -> PyErr {
+    let err = EphemerisIncompleteError::new_err(format!(
+        "The loaded ephemeris lacks bodies required by the IOD pipeline: {}. \
+         Kernels providing them: {}",
+        missing.join(", "),
+        COMPLETE_KERNELS.join(", ")
+    ));
+    let value = err.value(py);
+    // Attribute assignment on a fresh exception instance cannot fail in practice.
+    let _ = value.setattr("missing_bodies", missing.to_vec());
+    let _ = value.setattr("suggested_kernels", COMPLETE_KERNELS.to_vec());
+    err
+}
+
+/// Verify that the kernel contains every body required by the IOD pipeline.
+///
+/// Arguments
+/// -----------------
+/// * `py` – Python token (used to build the exception).
+/// * `ephem` – Loaded JPL ephemeris.
+///
+/// Return
+/// ----------
+/// * `Ok(())` when complete, `EphemerisIncompleteError` otherwise.
+pub(crate) fn check_complete(py: Python<'_>, ephem: &JPLEphem) -> PyResult<()> {
+    let available = available_bodies(ephem);
+    let missing: Vec<&str> = required_bodies(ephem)
+        .iter()
+        .copied()
+        .filter(|b| !available.contains(b))
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(incomplete_error(py, &missing))
+    }
+}
+
+/// Run the Earth query used by the IOD pipeline at `mjd` (TDB).
+fn covers(ephem: &JPLEphem, mjd: f64) -> bool {
+    probe(|| {
+        let position = match ephem {
+            JPLEphem::HorizonFile(data) => {
+                data.ephemeris(HorizonID::Earth, HorizonID::Sun, mjd, false, false)
+                    .position
+            }
+            JPLEphem::NaifFile(data) => {
+                data.ephemeris(
+                    NaifIds::PB(PlanetaryBary::EarthMoon),
+                    NaifIds::SSB(SolarSystemBary::SSB),
+                    (mjd - PROBE_MJD) * SECONDS_PER_DAY,
+                )
+                .position
+            }
+        };
+        position.iter().all(|v| v.is_finite())
+    })
+    .unwrap_or(false)
+}
+
+/// Time span covered by the loaded kernel, as `(mjd_start, mjd_end)` in TDB.
+///
+/// Arguments
+/// -----------------
+/// * `ephem` – Loaded JPL ephemeris.
+///
+/// Return
+/// ----------
+/// * The interval over which the Earth state used by the IOD pipeline can be
+///   interpolated, or `None` when the kernel does not cover J2000.
+///
+/// Notes
+/// ----------
+/// * The core keeps the coverage private, so it is found by bisection around J2000
+///   (about a hundred probes, resolution below a second).
+pub(crate) fn coverage(ephem: &JPLEphem) -> Option<(f64, f64)> {
+    if !covers(ephem, PROBE_MJD) {
+        return None;
+    }
+    let edge = |direction: f64| {
+        // Grow the step until it leaves the kernel, then bisect.
+        let (mut inside, mut step) = (PROBE_MJD, 1000.0);
+        let outside = loop {
+            let t = PROBE_MJD + direction * step;
+            if !covers(ephem, t) {
+                break t;
+            }
+            inside = t;
+            if step > 1e7 {
+                return t;
+            }
+            step *= 2.0;
+        };
+        bisect(ephem, inside, outside)
+    };
+    Some((edge(-1.0), edge(1.0)))
+}
+
+/// Last covered epoch between a covered and an uncovered one (resolution below a second).
+fn bisect(ephem: &JPLEphem, mut inside: f64, mut outside: f64) -> f64 {
+    while (outside - inside).abs() > 1e-6 {
+        let mid = 0.5 * (inside + outside);
+        if covers(ephem, mid) {
+            inside = mid;
+        } else {
+            outside = mid;
+        }
+    }
+    inside
+}
+
+/// Scan step of [`segments`], half the 32-day record length of the Horizons files.
+const SEGMENT_SCAN_DAYS: f64 = 16.0;
+
+/// Distance to a segment boundary below which an epoch is not accepted, in days.
+///
+/// The IOD queries the ephemeris at light-time corrected epochs, up to a day before the
+/// observation for objects within ~170 AU.
+pub(crate) const COVERAGE_MARGIN_DAYS: f64 = 1.0;
+
+/// Continuous intervals of `range` over which the kernel can be interpolated.
+///
+/// Arguments
+/// -----------------
+/// * `ephem` – Loaded JPL ephemeris.
+/// * `range` – `(mjd_start, mjd_end)` in TDB, covered at both ends (see [`coverage`]).
+///
+/// Return
+/// ----------
+/// * The segments as `(mjd_start, mjd_end)` in TDB, in time order.
+///
+/// Notes
+/// ----------
+/// * The range is probed every [`SEGMENT_SCAN_DAYS`] and each transition is bisected:
+///   gaps shorter than the scan step may go unnoticed.
+pub(crate) fn segments(ephem: &JPLEphem, range: (f64, f64)) -> Vec<(f64, f64)> {
+    let (lo, hi) = range;
+    let mut out = Vec::new();
+    let mut start = Some(lo);
+    let mut prev = lo;
+    let n = ((hi - lo) / SEGMENT_SCAN_DAYS).ceil() as usize;
+    for k in 1..=n {
+        let t = (lo + k as f64 * SEGMENT_SCAN_DAYS).min(hi);
+        match (start, covers(ephem, t)) {
+            (Some(s), false) => {
+                out.push((s, bisect(ephem, prev, t)));
+                start = None;
+            }
+            (None, true) => start = Some(bisect(ephem, t, prev)),
+            _ => {}
+        }
+        prev = t;
+    }
+    if let Some(s) = start {
+        out.push((s, hi));
+    }
+    out
+}
+
+/// Epochs that do not lie strictly inside a single ephemeris segment.
+///
+/// Fields
+/// -----------------
+/// * `epochs` – Offending epochs (MJD), in input order.
+/// * `nearest_boundaries` – For each epoch, the closest segment boundary (MJD TDB).
+#[derive(Debug, Clone)]
+pub(crate) struct CoverageGap {
+    pub(crate) epochs: Vec<f64>,
+    pub(crate) nearest_boundaries: Vec<f64>,
+}
+
+impl CoverageGap {
+    /// Check epochs against the segments, [`COVERAGE_MARGIN_DAYS`] away from any boundary.
+    ///
+    /// Return
+    /// ----------
+    /// * `None` when every epoch is accepted, the offending epochs otherwise.
+    pub(crate) fn find(
+        segments: &[(f64, f64)],
+        epochs: impl IntoIterator<Item = f64>,
+    ) -> Option<Self> {
+        let inside = |t: f64| {
+            segments
+                .iter()
+                .any(|&(a, b)| a + COVERAGE_MARGIN_DAYS <= t && t <= b - COVERAGE_MARGIN_DAYS)
+        };
+        let nearest = |t: f64| {
+            segments
+                .iter()
+                .flat_map(|&(a, b)| [a, b])
+                .min_by(|x, y| (x - t).abs().total_cmp(&(y - t).abs()))
+                .unwrap_or(f64::NAN)
+        };
+        let epochs: Vec<f64> = epochs.into_iter().filter(|&t| !inside(t)).collect();
+        if epochs.is_empty() {
+            return None;
+        }
+        let nearest_boundaries = epochs.iter().map(|&t| nearest(t)).collect();
+        Some(Self {
+            epochs,
+            nearest_boundaries,
+        })
+    }
+
+    /// Human-readable description, listing the first offending epochs.
+    pub(crate) fn message(&self) -> String {
+        let listed: Vec<String> = self
+            .epochs
+            .iter()
+            .zip(&self.nearest_boundaries)
+            .take(5)
+            .map(|(t, b)| format!("mjd {t} (boundary {b:.6})"))
+            .collect();
+        format!(
+            "{} observation epoch(s) are not strictly inside an ephemeris segment \
+             (at least {COVERAGE_MARGIN_DAYS} day from its boundaries): {}{}; \
+             see PyOutfit.ephemeris_segments()",
+            self.epochs.len(),
+            listed.join(", "),
+            if self.epochs.len() > listed.len() {
+                ", ..."
+            } else {
+                ""
+            }
+        )
+    }
+
+    /// Build the `EphemerisCoverageError`, carrying `epochs` and `nearest_boundaries`.
+    pub(crate) fn into_pyerr(self, py: Python<'_>) -> PyErr {
+        let err = EphemerisCoverageError::new_err(self.message());
+        let value = err.value(py);
+        // Attribute assignment on a fresh exception instance cannot fail in practice.
+        let _ = value.setattr("epochs", self.epochs);
+        let _ = value.setattr("nearest_boundaries", self.nearest_boundaries);
+        err
+    }
+}
+
+/// MJD of January 1st, 0h, of a Gregorian year.
+fn mjd_of_year(year: i64) -> f64 {
+    // Days elapsed since 0001-01-01, minus those up to the MJD origin (1858-11-17).
+    let y = year - 1;
+    (365 * y + y / 4 - y / 100 + y / 400 - 678_575) as f64
+}
+
+/// Split an ephemeris selector from its optional `[YYYY-YYYY]` span suffix.
+///
+/// Arguments
+/// -----------------
+/// * `ephem` – Selector such as `"horizon:DE440"` or `"horizon:DE440[2020-2030]"`.
+///
+/// Return
+/// ----------
+/// * The bare selector and, when a suffix is present, the span as `(mjd_start, mjd_end)`
+///   from January 1st of the first year to January 1st following the last year (both
+///   years included). `ValueError` on a malformed suffix.
+pub(crate) fn split_selector(ephem: &str) -> PyResult<(&str, Option<(f64, f64)>)> {
+    let Some(open) = ephem.find('[') else {
+        return Ok((ephem, None));
+    };
+    let malformed = || {
+        PyValueError::new_err(format!(
+            "malformed ephemeris span in {ephem:?}; expected e.g. 'horizon:DE440[2020-2030]'"
+        ))
+    };
+    let span = ephem[open + 1..].strip_suffix(']').ok_or_else(malformed)?;
+    let (start, end) = span.split_once('-').ok_or_else(malformed)?;
+    let year = |s: &str| {
+        s.trim()
+            .parse::<i64>()
+            .ok()
+            .filter(|y| (1000..=9999).contains(y))
+            .ok_or_else(malformed)
+    };
+    let (start, end) = (year(start)?, year(end)?);
+    if start > end {
+        return Err(malformed());
+    }
+    Ok((
+        &ephem[..open],
+        Some((mjd_of_year(start), mjd_of_year(end + 1))),
+    ))
+}
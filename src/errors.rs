@@ -0,0 +1,80 @@
+//! Python exception types raised by the bindings.
+//!
+//! Every exception defined here derives from a builtin Python exception so that
+//! existing `except RuntimeError` / `except ValueError` handlers keep working
+//! (`FallbackWarning` derives from `UserWarning`).
+//!
+//! See also
+//! ------------
+//! * [`register_exceptions`] – Expose the types on the `py_outfit` module.
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRuntimeError, PyUserWarning, PyValueError};
+use pyo3::prelude::*;
+
+create_exception!(
+    py_outfit,
+    TripletScorerError,
+    PyRuntimeError,
+    "Raised when the user-provided `triplet_scorer` fails or returns a non-float value."
+);
+
+create_exception!(
+    py_outfit,
+    EphemerisIncompleteError,
+    PyRuntimeError,
+    "Raised when the loaded JPL kernel lacks a body required by the IOD pipeline."
+);
+
+create_exception!(
+    py_outfit,
+    EphemerisCoverageError,
+    PyValueError,
+    "Raised when observation epochs do not lie strictly inside a single ephemeris segment."
+);
+
+create_exception!(
+    py_outfit,
+    UnsortedEpochsError,
+    PyValueError,
+    "Raised when a trajectory is not stored in time order (`estimate_all_orbits(validate=True)`)."
+);
+
+create_exception!(
+    py_outfit,
+    StrictModeError,
+    PyValueError,
+    "Raised in strict mode instead of applying a documented fallback (see `strict_checks()`)."
+);
+
+create_exception!(
+    py_outfit,
+    FallbackWarning,
+    PyUserWarning,
+    "Issued in permissive mode when a documented fallback is applied (see `strict_checks()`)."
+);
+
+/// Register all custom exception types on the Python module.
+///
+/// Arguments
+/// -----------------
+/// * `m` - The `py_outfit` module being initialized.
+///
+/// Return
+/// ----------
+/// * `PyResult<()>` indicating success or a Python exception.
+pub fn register_exceptions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("TripletScorerError", py.get_type::<TripletScorerError>())?;
+    m.add(
+        "EphemerisIncompleteError",
+        py.get_type::<EphemerisIncompleteError>(),
+    )?;
+    m.add(
+        "EphemerisCoverageError",
+        py.get_type::<EphemerisCoverageError>(),
+    )?;
+    m.add("UnsortedEpochsError", py.get_type::<UnsortedEpochsError>())?;
+    m.add("StrictModeError", py.get_type::<StrictModeError>())?;
+    m.add("FallbackWarning", py.get_type::<FallbackWarning>())?;
+    Ok(())
+}
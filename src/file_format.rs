@@ -0,0 +1,144 @@
+//! Observation file format detection for `TrajectorySet.from_file`.
+//!
+//! The format is sniffed from the first bytes of the file, the extension only being
+//! used when the content is inconclusive:
+//!
+//! * `PAR1` magic → Parquet;
+//! * an XML document whose root is `<ades` → ADES XML;
+//! * a JSON object with an `"ades"` member → ADES JSON;
+//! * a `# version=` first line, or a `|`-separated field header naming `obsTime` → ADES PSV;
+//! * every line 80 characters wide with a year in columns 16–19 → MPC 80-column;
+//! * a header line (after `#` comments) with `|` or `,` separators → PSV or CSV,
+//!   whichever delimiter is more frequent in that line.
+use std::{fs::File, io::Read};
+
+use camino::Utf8Path;
+
+/// Bytes read from the head of the file.
+const SNIFF_LEN: usize = 8192;
+
+/// Supported observation file formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FileFormat {
+    Mpc80Col,
+    AdesXml,
+    AdesPsv,
+    AdesJson,
+    Csv,
+    Psv,
+    Parquet,
+}
+
+impl FileFormat {
+    /// Every format, in detection order.
+    pub(crate) const ALL: [FileFormat; 7] = [
+        FileFormat::Parquet,
+        FileFormat::AdesXml,
+        FileFormat::AdesJson,
+        FileFormat::Mpc80Col,
+        FileFormat::AdesPsv,
+        FileFormat::Psv,
+        FileFormat::Csv,
+    ];
+
+    /// Human-readable name, used in error messages.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            FileFormat::Mpc80Col => "MPC 80-column",
+            FileFormat::AdesXml => "ADES XML",
+            FileFormat::AdesPsv => "ADES PSV",
+            FileFormat::AdesJson => "ADES JSON",
+            FileFormat::Csv => "CSV with header",
+            FileFormat::Psv => "PSV with header",
+            FileFormat::Parquet => "Parquet",
+        }
+    }
+
+    fn from_extension(path: &Utf8Path) -> Option<Self> {
+        match path.extension()?.to_ascii_lowercase().as_str() {
+            "obs" | "mpc" => Some(FileFormat::Mpc80Col),
+            "xml" => Some(FileFormat::AdesXml),
+            "json" => Some(FileFormat::AdesJson),
+            "csv" => Some(FileFormat::Csv),
+            "psv" => Some(FileFormat::Psv),
+            "parquet" | "pq" => Some(FileFormat::Parquet),
+            _ => None,
+        }
+    }
+}
+
+/// `true` for a line of an MPC 80-column record (year in columns 16–19).
+fn is_mpc80_line(line: &str) -> bool {
+    line.len() == 80 && line.is_ascii() && line.as_bytes()[15..19].iter().all(u8::is_ascii_digit)
+}
+
+/// `true` for the version line opening an ADES PSV document (`# version=2017`).
+fn is_ades_version_line(line: &str) -> bool {
+    line.strip_prefix('#')
+        .is_some_and(|l| l.trim_start().starts_with("version="))
+}
+
+/// Detect the format of an observation file.
+///
+/// Arguments
+/// -----------------
+/// * `path` – File to inspect; only its first bytes are read.
+///
+/// Return
+/// ----------
+/// * The detected format, `None` when neither the content nor the extension match a
+///   supported format, or the I/O error raised while reading.
+pub(crate) fn sniff(path: &Utf8Path) -> std::io::Result<Option<FileFormat>> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    File::open(path)?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)?;
+
+    if head.starts_with(b"PAR1") {
+        return Ok(Some(FileFormat::Parquet));
+    }
+
+    let text = String::from_utf8_lossy(&head);
+    let text = text.trim_start_matches('\u{feff}');
+    if text.trim_start().starts_with('<') {
+        return Ok(text.contains("<ades").then_some(FileFormat::AdesXml));
+    }
+    if text.trim_start().starts_with('{') {
+        return Ok(text.contains("\"ades\"").then_some(FileFormat::AdesJson));
+    }
+
+    let mut lines: Vec<&str> = text
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    if head.len() == SNIFF_LEN {
+        // The last line may be truncated.
+        lines.pop();
+    }
+
+    if !lines.is_empty() && lines.iter().all(|l| is_mpc80_line(l)) {
+        return Ok(Some(FileFormat::Mpc80Col));
+    }
+    if lines.first().is_some_and(|l| is_ades_version_line(l)) {
+        return Ok(Some(FileFormat::AdesPsv));
+    }
+    if let Some(header) = lines
+        .iter()
+        .find(|l| !l.starts_with('#') && !l.starts_with('!'))
+    {
+        if header.split('|').any(|field| field.trim() == "obsTime") {
+            return Ok(Some(FileFormat::AdesPsv));
+        }
+        let pipes = header.matches('|').count();
+        let commas = header.matches(',').count();
+        if pipes > commas {
+            return Ok(Some(FileFormat::Psv));
+        }
+        if commas > 0 {
+            return Ok(Some(FileFormat::Csv));
+        }
+    }
+
+    Ok(FileFormat::from_extension(path))
+}
@@ -0,0 +1,215 @@
+//! Goodness-of-fit statistics of an orbit against its observations.
+//!
+//! The engine ranks candidate orbits with a dimensionless RMS (residuals normalized
+//! by the astrometric uncertainties). [`FitStatistics`] complements it with values in
+//! physical units (arcseconds), a breakdown by coordinate, and the χ² of the fit.
+//!
+//! Conventions
+//! -----------------
+//! * Residuals are `observed − computed`, evaluated over the whole arc.
+//! * The RA residual is projected on the sky: `ΔRA · cos(dec)`, with `ΔRA` wrapped to `[-π, π]`.
+//! * `χ² = Σ (ΔRA·cos(dec) / σ_ra)² + (Δdec / σ_dec)²`, i.e. the quantity used by the engine.
+//! * `reduced_χ² = χ² / (2·n − 6)` (six fitted orbital parameters); `NaN` when `n ≤ 3`.
+//!
+//! See also
+//! ------------
+//! * [`crate::observations::Observations::residuals`] – Per-observation residuals.
+//! * [`crate::iod_gauss::GaussResult`] – Carries the statistics of the fit that produced it.
+use outfit::{
+    constants::{DPI, RADSEC},
+    observations::Observation,
+    GaussResult as RsGaussResult, Outfit, OutfitError,
+};
+use pyo3::{prelude::*, types::PyDict};
+use std::f64::consts::PI;
+
+use crate::iod_gauss::GaussResult;
+use crate::{propagation, time_scales, units};
+
+/// Fit statistics of an orbit over an arc, in physical units.
+#[pyclass(module = "py_outfit", frozen)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FitStatistics {
+    /// Total RMS of the on-sky residuals (arcsec), over both coordinates.
+    #[pyo3(get)]
+    pub rms_arcsec: f64,
+    /// RMS of the RA residuals projected on the sky, `ΔRA·cos(dec)` (arcsec).
+    #[pyo3(get)]
+    pub rms_ra_arcsec: f64,
+    /// RMS of the declination residuals (arcsec).
+    #[pyo3(get)]
+    pub rms_dec_arcsec: f64,
+    /// Sum of the squared normalized residuals (both coordinates).
+    #[pyo3(get)]
+    pub chi2: f64,
+    /// `chi2 / (2·n_points − 6)`; `NaN` when the fit has no degree of freedom.
+    #[pyo3(get)]
+    pub reduced_chi2: f64,
+    /// Number of observations used.
+    #[pyo3(get)]
+    pub n_points: usize,
+}
+
+#[pymethods]
+impl FitStatistics {
+    /// Convert the statistics to a Python dict (one key per attribute).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `include_units`: Add a `"units"` block mapping each key to its unit (default: `False`).
+    #[pyo3(signature = (include_units=false), text_signature = "(self, include_units=False)")]
+    pub fn to_dict<'py>(
+        &self,
+        py: Python<'py>,
+        include_units: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let d = PyDict::new(py);
+        d.set_item("rms_arcsec", self.rms_arcsec)?;
+        d.set_item("rms_ra_arcsec", self.rms_ra_arcsec)?;
+        d.set_item("rms_dec_arcsec", self.rms_dec_arcsec)?;
+        d.set_item("chi2", self.chi2)?;
+        d.set_item("reduced_chi2", self.reduced_chi2)?;
+        d.set_item("n_points", self.n_points)?;
+        if include_units {
+            d.set_item("units", units::units_block(py, &units::FIT_FIELDS, false)?)?;
+        }
+        Ok(d)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FitStatistics(rms_arcsec={:.4}, rms_ra_arcsec={:.4}, rms_dec_arcsec={:.4}, chi2={:.4}, reduced_chi2={:.4}, n_points={})",
+            self.rms_arcsec,
+            self.rms_ra_arcsec,
+            self.rms_dec_arcsec,
+            self.chi2,
+            self.reduced_chi2,
+            self.n_points
+        )
+    }
+}
+
+impl FitStatistics {
+    /// Rebuild statistics from the keys of `to_dict` (extra keys are ignored).
+    ///
+    /// Return
+    /// ----------
+    /// * The statistics, or `KeyError` naming `context` and the missing key.
+    pub(crate) fn from_mapping(mapping: &Bound<'_, PyAny>, context: &str) -> PyResult<Self> {
+        let number = |key: &str| units::field(mapping, context, key)?.extract::<f64>();
+        Ok(Self {
+            rms_arcsec: number("rms_arcsec")?,
+            rms_ra_arcsec: number("rms_ra_arcsec")?,
+            rms_dec_arcsec: number("rms_dec_arcsec")?,
+            chi2: number("chi2")?,
+            reduced_chi2: number("reduced_chi2")?,
+            n_points: units::count(&units::field(mapping, context, "n_points")?, "n_points")?,
+        })
+    }
+}
+
+/// Residual of one observation against an orbit.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Residual {
+    /// `ΔRA · cos(dec)` in radians.
+    pub(crate) ra: f64,
+    /// `Δdec` in radians.
+    pub(crate) dec: f64,
+    /// Normalized squared residual (contribution to χ²).
+    pub(crate) chi2: f64,
+}
+
+/// Compute the residuals of every observation of `obs` against `orbit`.
+///
+/// Arguments
+/// -----------------
+/// * `obs` – Observations (uncertainties as used by the fit).
+/// * `state` – Global Outfit environment.
+/// * `orbit` – Orbit to evaluate, with its reference epoch in TDB.
+///
+/// Return
+/// ----------
+/// * One [`Residual`] per observation, in input order, or the propagation error.
+///   The orbit is moved to the TT axis of the observations before propagation.
+pub(crate) fn residuals(
+    obs: &[Observation],
+    state: &Outfit,
+    orbit: &RsGaussResult,
+) -> Result<Vec<Residual>, OutfitError> {
+    let elements = time_scales::orbit_to_tt(orbit)
+        .get_orbit()
+        .to_equinoctial()?;
+    obs.iter()
+        .map(|o| {
+            let (ra, dec) = o.compute_apparent_position(state, &elements)?;
+            let mut d_ra = (o.ra - ra) % DPI;
+            if d_ra > PI {
+                d_ra -= DPI;
+            } else if d_ra < -PI {
+                d_ra += DPI;
+            }
+            let ra = d_ra * o.dec.cos();
+            let dec = o.dec - dec;
+            Ok(Residual {
+                ra,
+                dec,
+                chi2: (ra / o.error_ra).powi(2) + (dec / o.error_dec).powi(2),
+            })
+        })
+        .collect()
+}
+
+impl FitStatistics {
+    /// Aggregate residuals into fit statistics.
+    pub(crate) fn from_residuals(res: &[Residual]) -> Self {
+        let n = res.len();
+        let nf = n as f64;
+        let sum_ra: f64 = res.iter().map(|r| r.ra * r.ra).sum();
+        let sum_dec: f64 = res.iter().map(|r| r.dec * r.dec).sum();
+        let chi2: f64 = res.iter().map(|r| r.chi2).sum();
+        let dof = 2 * n as i64 - 6;
+
+        Self {
+            rms_arcsec: ((sum_ra + sum_dec) / (2.0 * nf)).sqrt() / RADSEC,
+            rms_ra_arcsec: (sum_ra / nf).sqrt() / RADSEC,
+            rms_dec_arcsec: (sum_dec / nf).sqrt() / RADSEC,
+            chi2,
+            reduced_chi2: if dof > 0 { chi2 / dof as f64 } else { f64::NAN },
+            n_points: n,
+        }
+    }
+
+    /// Compute the statistics of `orbit` over `obs`.
+    pub(crate) fn compute(
+        obs: &[Observation],
+        state: &Outfit,
+        orbit: &RsGaussResult,
+    ) -> Result<Self, OutfitError> {
+        Ok(Self::from_residuals(&residuals(obs, state, orbit)?))
+    }
+}
+
+/// Wrap an engine orbit into its Python type, with the fit statistics over `obs` attached.
+///
+/// The reference epoch is relabelled from the core's TT axis to TDB (see
+/// [`crate::time_scales`]). Statistics are left unset when the orbit cannot be
+/// propagated over the arc (e.g. unbound orbits, which the propagator does not
+/// support yet).
+pub(crate) fn with_fit_statistics(
+    obs: &[Observation],
+    state: &Outfit,
+    orbit: RsGaussResult,
+) -> GaussResult {
+    let orbit = time_scales::orbit_to_tdb(&orbit);
+    let fit = FitStatistics::compute(obs, state, &orbit).ok();
+    let fit_epoch = Some(propagation::reference_epoch(orbit.get_orbit()));
+    GaussResult {
+        inner: orbit,
+        fit,
+        costs: None,
+        fit_epoch,
+        subsample_indices: None,
+        params_index: None,
+        iod_path: None,
+    }
+}
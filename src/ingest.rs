@@ -0,0 +1,1788 @@
+//! Ingestion-time checks applied to freshly parsed observations.
+//!
+//! Some catalogues encode an unknown astrometric uncertainty as `0` or a negative
+//! value. Left as-is, these entries produce infinite weights (or NaNs) deep in the
+//! fit. Every `TrajectorySet` ingestion entry point therefore runs the new
+//! observations through a [`BadSigma`] policy before they are stored.
+//!
+//! Observations are also checked against the time range of the environment
+//! ([`check_time_range`]), so that an epoch the ephemeris cannot serve is reported at
+//! ingestion rather than as a panic in the middle of a batch. The NumPy loaders also
+//! check their columns row by row before ingesting them ([`check_rows`]): a NaN or an
+//! out-of-range coordinate is reported with its row instead of failing the fit of its
+//! trajectory.
+//!
+//! Datasets already reduced to the geocentre are ingested with `topocentric=False`:
+//! their rows are attributed to the geocentric counterpart of the observer
+//! ([`observing_site`]), so that neither the IOD nor the residuals apply the
+//! topocentric correction a second time.
+//!
+//! The NumPy loaders take their uncertainties as one value for the whole batch or one
+//! value per observation ([`Sigma`]), applied before the policy runs. The observations are
+//! streamed from the borrowed columns into trajectories allocated at their final size
+//! ([`Rows`]), converting degrees on the fly: no intermediate batch or converted copy of
+//! the columns is held, and the set does not regrow. Trajectory ids are integers (up to 64 bits) or strings
+//! ([`TrajectoryIds`]); the core only takes `u32` ids, so the others are numbered for the
+//! ingestion and given back to the trajectories afterwards ([`IdNumbering`]). Epochs are
+//! numbers (MJD or JD), `datetime64` values or ISO-8601 strings ([`Epochs`]), converted to
+//! MJD (TT) before the core sees them. Numeric columns of any real dtype or memory layout
+//! are accepted ([`Reals`]): contiguous `float64` arrays are read in place, anything else
+//! is converted once, in Rust. Records given one mapping per observation are gathered
+//! into the same columns first ([`RecordColumns`]); streamed inputs are read one chunk of
+//! arrays at a time ([`Chunk`]). Any of these arguments may be a `numpy.ma.MaskedArray`
+//! ([`Masked`]): masked rows, and on request rows holding non-finite values, are dropped
+//! from every column before the rows are checked ([`DroppedRows`]). Large batches can be
+//! grouped into trajectories on several threads ([`Workers`], [`Rows::ingest_on`]), with
+//! the same result as the serial build.
+//!
+//! Every trajectory of a set is kept in canonical order ([`canonical_cmp`]: time, then
+//! RA, Dec and site): observations ingested out of order are sorted ([`sort_by_epoch`])
+//! and appends merge the new observations into place, so the IOD and the arc-based
+//! methods can rely on the order of a stored trajectory, and two pipelines holding the
+//! same observations export them in the same order.
+//!
+//! See also
+//! ------------
+//! * [`crate::trajectories::TrajectorySet`] – Ingestion entry points exposing `bad_sigma`.
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hash},
+    sync::Arc,
+};
+
+use numpy::{
+    datetime::{units, Datetime, Unit},
+    PyReadonlyArray1, PyUntypedArrayMethods,
+};
+use outfit::{
+    constants::RADSEC,
+    conversion::arcsec_to_rad,
+    observations::Observation,
+    trajectories::{batch_reader::ObservationBatch, trajectory_file::TrajectoryFile},
+    ObjectNumber, Observations, Observer, Outfit, OutfitError, TrajectorySet,
+};
+use pyo3::{
+    exceptions::{PyKeyError, PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyBool, PyInt, PyList, PyMapping, PyString, PyTuple},
+};
+use rayon::prelude::*;
+
+use crate::{
+    observatories::MpcSites,
+    time_scales::{self, EpochFormat, EpochScale},
+    trajectories::object_number_from_u64,
+    IntoPyResult,
+};
+
+/// Real-valued array argument of the NumPy loaders (coordinates, numeric epochs, per-row
+/// uncertainties): any integer or floating dtype, any memory layout.
+pub enum Reals<'py> {
+    /// Contiguous `float64` array, read in place.
+    InPlace(PyReadonlyArray1<'py, f64>),
+    /// Any other dtype or a strided view, converted to `f64` in a single copy.
+    Converted(Vec<f64>),
+}
+
+impl Reals<'_> {
+    /// The values as a contiguous `f64` slice.
+    pub fn as_slice(&self) -> PyResult<&[f64]> {
+        match self {
+            Reals::InPlace(a) => Ok(a.as_slice()?),
+            Reals::Converted(v) => Ok(v),
+        }
+    }
+}
+
+impl<'py> FromPyObject<'py> for Reals<'py> {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(a) = ob.extract::<PyReadonlyArray1<'py, f64>>() {
+            return Ok(if a.is_contiguous() {
+                Reals::InPlace(a)
+            } else {
+                Reals::Converted(a.as_array().to_vec())
+            });
+        }
+        macro_rules! converted {
+            ($($t:ty),*) => {$(
+                if let Ok(a) = ob.extract::<PyReadonlyArray1<'py, $t>>() {
+                    return Ok(Reals::Converted(a.as_array().iter().map(|&v| v as f64).collect()));
+                }
+            )*};
+        }
+        converted!(f32, i64, i32, i16, i8, u64, u32, u16, u8);
+        Err(PyTypeError::new_err(format!(
+            "expected a 1-D array of real numbers, got {}",
+            describe_array(ob)
+        )))
+    }
+}
+
+/// `dtype` and dimension of an array argument, or its type for anything else.
+fn describe_array(ob: &Bound<'_, PyAny>) -> String {
+    let attr = |name: &str| ob.getattr(name).and_then(|v| v.str()).ok();
+    match (attr("dtype"), attr("ndim")) {
+        (Some(dtype), Some(ndim)) => format!("a {ndim}-D array of dtype {dtype}"),
+        _ => ob
+            .get_type()
+            .name()
+            .map_or_else(|_| "an unsupported object".to_string(), |n| n.to_string()),
+    }
+}
+
+/// Uncertainty argument of the NumPy loaders: a `float` applied to every observation,
+/// or an array with one value per observation.
+#[derive(FromPyObject)]
+pub enum Sigma<'py> {
+    /// One value per observation (tried first, so that a one-element array is not
+    /// taken for a scalar).
+    PerRow(Reals<'py>),
+    /// One value for the whole batch.
+    Uniform(f64),
+}
+
+impl Sigma<'_> {
+    /// Value given to the core batch: the uniform value, NaN for per-row values (written
+    /// afterwards).
+    pub fn uniform(&self) -> f64 {
+        match self {
+            Sigma::PerRow(_) => f64::NAN,
+            Sigma::Uniform(v) => *v,
+        }
+    }
+
+    /// Per-row values, `None` for a uniform value.
+    pub fn per_row(&self) -> PyResult<Option<&[f64]>> {
+        match self {
+            Sigma::PerRow(a) => Ok(Some(a.as_slice()?)),
+            Sigma::Uniform(_) => Ok(None),
+        }
+    }
+}
+
+/// NumPy loader argument that may be a `numpy.ma.MaskedArray`: the value read from the
+/// array with its masked entries filled (see [`MaskFill`]), and the mask.
+///
+/// Plain arrays and other inputs are read as `T` without a mask. Dereferences to `T`.
+pub struct Masked<T> {
+    pub value: T,
+    /// `true` for the masked rows; `None` when nothing is masked.
+    pub mask: Option<Vec<bool>>,
+}
+
+impl<T> Masked<T> {
+    /// Mask of the rows, `None` when nothing is masked.
+    pub fn mask(&self) -> Option<&[bool]> {
+        self.mask.as_deref()
+    }
+}
+
+impl<'py> Masked<Sigma<'py>> {
+    /// The uncertainties of the rows kept by [`DroppedRows`]; all of them for `None`.
+    pub fn take_rows(self, rows: Option<&[usize]>) -> PyResult<Sigma<'py>> {
+        let kept = match (rows, self.per_row()?) {
+            (Some(rows), Some(values)) => rows.iter().map(|&k| values[k]).collect(),
+            _ => return Ok(self.value),
+        };
+        Ok(Sigma::PerRow(Reals::Converted(kept)))
+    }
+}
+
+impl<T> From<T> for Masked<T> {
+    fn from(value: T) -> Self {
+        Masked { value, mask: None }
+    }
+}
+
+impl<T> std::ops::Deref for Masked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// How the masked entries of a `MaskedArray` are filled before the array is read as a
+/// loader argument: with a value the argument reads without error, since the data under
+/// the mask can be anything (a negative id, a `NaT`).
+pub trait MaskFill {
+    /// `array` with its masked entries filled.
+    fn filled<'py>(array: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>>;
+}
+
+/// `dtype.kind` of an array argument.
+fn dtype_kind(array: &Bound<'_, PyAny>) -> PyResult<String> {
+    array.getattr("dtype")?.getattr("kind")?.extract()
+}
+
+/// Real values: NaN, the missing value of the loaders.
+fn filled_nan<'py>(array: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    array
+        .call_method1("astype", ("float64",))?
+        .call_method1("filled", (f64::NAN,))
+}
+
+impl MaskFill for Reals<'_> {
+    fn filled<'py>(array: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        filled_nan(array)
+    }
+}
+
+impl MaskFill for Sigma<'_> {
+    fn filled<'py>(array: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        filled_nan(array)
+    }
+}
+
+impl MaskFill for Epochs<'_> {
+    fn filled<'py>(array: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        match dtype_kind(array)?.as_str() {
+            "M" => {
+                let nat = array
+                    .py()
+                    .import("numpy")?
+                    .call_method1("datetime64", ("NaT",))?;
+                array.call_method1("filled", (nat,))
+            }
+            "U" | "S" | "O" => array.call_method1("filled", ("",)),
+            _ => filled_nan(array),
+        }
+    }
+}
+
+impl MaskFill for TrajectoryIds<'_> {
+    fn filled<'py>(array: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        match dtype_kind(array)?.as_str() {
+            "U" | "S" => array.call_method1("filled", ("",)),
+            // Object ids are filled with a value of the type of the unmasked ones.
+            "O" => {
+                let first = array
+                    .call_method0("compressed")?
+                    .try_iter()?
+                    .next()
+                    .transpose()?;
+                match first {
+                    Some(id) if id.is_instance_of::<PyString>() => {
+                        array.call_method1("filled", ("",))
+                    }
+                    _ => array.call_method1("filled", (0,)),
+                }
+            }
+            _ => array.call_method1("filled", (0,)),
+        }
+    }
+}
+
+impl<'py, T: FromPyObject<'py> + MaskFill> FromPyObject<'py> for Masked<T> {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ma = ob.py().import("numpy.ma")?;
+        if !ob.is_instance(&ma.getattr("MaskedArray")?)? {
+            return Ok(ob.extract::<T>()?.into());
+        }
+        let mask = ma.call_method1("getmaskarray", (ob,))?;
+        let mask: PyReadonlyArray1<'_, bool> = mask.extract()?;
+        let mask = mask.as_array().to_vec();
+        Ok(Masked {
+            value: T::filled(ob)?.extract()?,
+            mask: mask.contains(&true).then_some(mask),
+        })
+    }
+}
+
+/// Rows of a NumPy batch left out of the ingestion: masked rows (any column) and, with
+/// `drop_invalid=True`, rows holding a non-finite value (see [`invalid_columns`]).
+pub struct DroppedRows {
+    kept: Vec<usize>,
+    per_id: HashMap<u32, usize>,
+    without_id: usize,
+}
+
+impl DroppedRows {
+    /// Rows to drop from a batch.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `ids` – Numbered trajectory id of every row.
+    /// * `id_mask` – Mask of `trajectory_id`: such rows have no trajectory.
+    /// * `masks` – Masks of the other columns.
+    /// * `finite` – Columns whose non-finite values drop their row.
+    ///
+    /// Return
+    /// ----------
+    /// * `None` when every row is kept.
+    pub fn find(
+        ids: &[u32],
+        id_mask: Option<&[bool]>,
+        masks: &[Option<&[bool]>],
+        finite: &[&[f64]],
+    ) -> Option<Self> {
+        let masked = |mask: Option<&[bool]>, row: usize| mask.is_some_and(|m| m[row]);
+        let mut dropped = DroppedRows {
+            kept: Vec::with_capacity(ids.len()),
+            per_id: HashMap::new(),
+            without_id: 0,
+        };
+        for (row, &id) in ids.iter().enumerate() {
+            if masked(id_mask, row) {
+                dropped.without_id += 1;
+            } else if masks.iter().any(|&m| masked(m, row))
+                || finite.iter().any(|column| !column[row].is_finite())
+            {
+                *dropped.per_id.entry(id).or_default() += 1;
+            } else {
+                dropped.kept.push(row);
+            }
+        }
+        (dropped.kept.len() < ids.len()).then_some(dropped)
+    }
+
+    /// Rows kept, in input order.
+    pub fn rows(&self) -> &[usize] {
+        &self.kept
+    }
+
+    /// Number of rows dropped per trajectory, keyed by the original ids; rows whose id
+    /// is masked are counted under `None`.
+    pub fn counts(&self, numbering: &IdNumbering) -> HashMap<Option<ObjectNumber>, usize> {
+        let mut counts: HashMap<Option<ObjectNumber>, usize> = self
+            .per_id
+            .iter()
+            .map(|(&id, &n)| (Some(numbering.key(id)), n))
+            .collect();
+        if self.without_id > 0 {
+            counts.insert(None, self.without_id);
+        }
+        counts
+    }
+}
+
+/// Columns whose non-finite values `drop_invalid=True` drops: the coordinates, the epochs
+/// and, with `bad_sigma="error"`, the per-row uncertainties (the other policies
+/// substitute them instead).
+pub fn invalid_columns<'a>(
+    ra: &'a [f64],
+    dec: &'a [f64],
+    mjd: &'a [f64],
+    sigmas: [&'a Sigma<'_>; 2],
+    policy: BadSigma,
+) -> PyResult<Vec<&'a [f64]>> {
+    let mut columns = vec![ra, dec, mjd];
+    if policy == BadSigma::Error {
+        for sigma in sigmas {
+            columns.extend(sigma.per_row()?);
+        }
+    }
+    Ok(columns)
+}
+
+/// RA and DEC uncertainties of every row of a batch.
+pub type RowSigmas<'a> = (Cow<'a, [f64]>, Cow<'a, [f64]>);
+
+/// Per-row uncertainties of a batch of `n` rows, `None` when both are uniform.
+///
+/// A uniform value is repeated when the other uncertainty is given per row; lengths are
+/// checked by the callers.
+pub fn row_sigmas<'a>(
+    ra: &'a Sigma<'_>,
+    dec: &'a Sigma<'_>,
+    n: usize,
+) -> PyResult<Option<RowSigmas<'a>>> {
+    if let (Sigma::Uniform(_), Sigma::Uniform(_)) = (ra, dec) {
+        return Ok(None);
+    }
+    let expand = |s: &'a Sigma<'_>| -> PyResult<Cow<'a, [f64]>> {
+        Ok(match s.per_row()? {
+            Some(values) => Cow::Borrowed(values),
+            None => Cow::Owned(vec![s.uniform(); n]),
+        })
+    };
+    Ok(Some((expand(ra)?, expand(dec)?)))
+}
+
+/// `NaT` rows listed in the message of a `datetime64` epoch array.
+const MAX_REPORTED_ROWS: usize = 10;
+
+/// `mjd_tt` argument of the NumPy loaders: numeric epochs, `datetime64` timestamps (as
+/// read from Parquet or pandas), or ISO-8601 timestamps such as the `obsTime` of alert
+/// streams (list, NumPy string or object array).
+#[derive(FromPyObject)]
+pub enum Epochs<'py> {
+    /// Real array of MJD (or JD) epochs, read in place when contiguous `float64`.
+    Numbers(Reals<'py>),
+    /// `datetime64[ns]` array.
+    Nanoseconds(PyReadonlyArray1<'py, Datetime<units::Nanoseconds>>),
+    /// `datetime64[us]` array.
+    Microseconds(PyReadonlyArray1<'py, Datetime<units::Microseconds>>),
+    /// Timestamp strings, parsed in Rust.
+    Iso(Vec<String>),
+}
+
+impl Epochs<'_> {
+    /// Epochs as MJD (TT), converted from `epoch_format` and `time_scale`.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `epoch_format`: `"mjd"` or `"jd"` for numeric epochs; timestamps (strings or
+    ///   `datetime64`) only take `"mjd"` (the default).
+    /// * `time_scale`: scale of the epochs, TT by default for numbers and UTC for
+    ///   timestamps.
+    ///
+    /// Return
+    /// ----------
+    /// * The MJD (TT) epochs, borrowed when already given as such, or a `ValueError`
+    ///   naming the first row that cannot be converted (every `NaT` row, up to
+    ///   `MAX_REPORTED_ROWS`, for `datetime64`).
+    pub fn to_mjd_tt(
+        &self,
+        epoch_format: &str,
+        time_scale: Option<&str>,
+    ) -> PyResult<Cow<'_, [f64]>> {
+        self.to_mjd_tt_masked(epoch_format, time_scale, None, false)
+    }
+
+    /// [`Self::to_mjd_tt`] for epochs with missing rows, which read as NaN instead of
+    /// being converted.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `epoch_format`, `time_scale`: see [`Self::to_mjd_tt`].
+    /// * `masked`: rows masked in a `MaskedArray`.
+    /// * `nat_missing`: `true` to read `NaT` timestamps as NaN rather than rejecting them
+    ///   (`drop_invalid=True`).
+    pub fn to_mjd_tt_masked(
+        &self,
+        epoch_format: &str,
+        time_scale: Option<&str>,
+        masked: Option<&[bool]>,
+        nat_missing: bool,
+    ) -> PyResult<Cow<'_, [f64]>> {
+        let masked = |row: usize| masked.is_some_and(|m| m.get(row) == Some(&true));
+        match self {
+            Epochs::Numbers(mjd) => time_scales::epochs_to_mjd_tt(
+                mjd.as_slice()?,
+                epoch_format,
+                time_scale.unwrap_or("TT"),
+            ),
+            Epochs::Nanoseconds(ticks) => {
+                let scale = timestamp_scale(epoch_format, time_scale)?;
+                let missing = |row| nat_missing || masked(row);
+                ticks_to_mjd_tt(ticks.as_slice()?, 1_000_000_000, scale, missing).map(Cow::Owned)
+            }
+            Epochs::Microseconds(ticks) => {
+                let scale = timestamp_scale(epoch_format, time_scale)?;
+                let missing = |row| nat_missing || masked(row);
+                ticks_to_mjd_tt(ticks.as_slice()?, 1_000_000, scale, missing).map(Cow::Owned)
+            }
+            Epochs::Iso(stamps) => {
+                let scale = timestamp_scale(epoch_format, time_scale)?;
+                stamps
+                    .iter()
+                    .enumerate()
+                    .map(|(row, stamp)| {
+                        if masked(row) {
+                            return Ok(f64::NAN);
+                        }
+                        time_scales::iso_to_mjd_tt(stamp, scale).map_err(|why| {
+                            PyValueError::new_err(format!(
+                                "mjd[{row}] = {stamp:?} is not a valid ISO-8601 timestamp: {why}"
+                            ))
+                        })
+                    })
+                    .collect::<PyResult<Vec<f64>>>()
+                    .map(Cow::Owned)
+            }
+        }
+    }
+}
+
+/// Time scale of timestamp epochs (UTC unless given), which have no JD form.
+fn timestamp_scale(epoch_format: &str, time_scale: Option<&str>) -> PyResult<EpochScale> {
+    let scale = EpochScale::parse(time_scale.unwrap_or("UTC"))?;
+    if EpochFormat::parse(epoch_format)? != EpochFormat::Mjd {
+        return Err(PyValueError::new_err(
+            "epoch_format only applies to numeric epochs, not to timestamps",
+        ));
+    }
+    Ok(scale)
+}
+
+/// MJD (TT) of `datetime64` values of a given resolution; `NaT` rows are an error unless
+/// `missing` (NaN).
+fn ticks_to_mjd_tt<U: Unit>(
+    ticks: &[Datetime<U>],
+    ticks_per_second: i64,
+    scale: EpochScale,
+    missing: impl Fn(usize) -> bool,
+) -> PyResult<Vec<f64>> {
+    let is_nat = |t: Datetime<U>| i64::from(t) == i64::MIN;
+    let nat: Vec<usize> = ticks
+        .iter()
+        .enumerate()
+        .filter(|&(row, &t)| is_nat(t) && !missing(row))
+        .map(|(row, _)| row)
+        .collect();
+    if !nat.is_empty() {
+        let shown: Vec<String> = nat
+            .iter()
+            .take(MAX_REPORTED_ROWS)
+            .map(usize::to_string)
+            .collect();
+        let more = match nat.len().saturating_sub(MAX_REPORTED_ROWS) {
+            0 => String::new(),
+            n => format!(" (and {n} more)"),
+        };
+        return Err(PyValueError::new_err(format!(
+            "mjd has {} NaT value(s), at rows {}{more}",
+            nat.len(),
+            shown.join(", ")
+        )));
+    }
+    ticks
+        .iter()
+        .enumerate()
+        .map(|(row, &t)| {
+            if is_nat(t) {
+                return Ok(f64::NAN);
+            }
+            time_scales::unix_ticks_to_mjd_tt(i64::from(t), ticks_per_second, scale)
+                .map_err(|why| PyValueError::new_err(format!("mjd[{row}]: {why}")))
+        })
+        .collect()
+}
+
+/// `trajectory_id` argument of the NumPy loaders: integer ids, or string ids such as
+/// survey designations (list, NumPy string or object array).
+pub enum TrajectoryIds<'py> {
+    /// `uint32` array, read in place when contiguous.
+    Numbers(PyReadonlyArray1<'py, u32>),
+    /// `uint64` array (64-bit survey ids).
+    Wide(PyReadonlyArray1<'py, u64>),
+    /// Any other integer array, non-negative values only.
+    Signed(SignedIds),
+    /// Python ints of an object array (as pandas stores ids) or a list, checked to be
+    /// non-negative and to fit in 64 bits.
+    Objects(Vec<u64>),
+    /// String ids, kept as the keys of the set.
+    Names(Vec<String>),
+}
+
+impl<'py> FromPyObject<'py> for TrajectoryIds<'py> {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(ids) = ob.extract() {
+            return Ok(TrajectoryIds::Numbers(ids));
+        }
+        if let Ok(ids) = ob.extract() {
+            return Ok(TrajectoryIds::Wide(ids));
+        }
+        if let Ok(ids) = ob.extract() {
+            return Ok(TrajectoryIds::Signed(ids));
+        }
+        let kind = dtype_kind(ob).ok();
+        match kind.as_deref() {
+            Some("U" | "S") => Ok(TrajectoryIds::Names(ob.extract()?)),
+            Some("O") => object_ids(ob),
+            Some(_) => Err(PyTypeError::new_err(format!(
+                "expected integer or string ids, got {}",
+                describe_array(ob)
+            ))),
+            None if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() => {
+                object_ids(ob)
+            }
+            None => Err(PyTypeError::new_err(format!(
+                "expected an array or a list of integer or string ids, got {}",
+                describe_array(ob)
+            ))),
+        }
+    }
+}
+
+/// Ids held as Python objects: all `int` (the integer path) or all `str` (the string
+/// path).
+///
+/// Return
+/// ----------
+/// * The ids, or an error naming the first offending element: `ValueError` for a
+///   negative or wider than 64-bit `int`, `TypeError` for an element of another type
+///   (`None`, `float`, `bool`, …) or one whose type differs from the first element's.
+fn object_ids<'py>(ob: &Bound<'py, PyAny>) -> PyResult<TrajectoryIds<'py>> {
+    let describe = |row: usize, item: &Bound<'_, PyAny>| -> String {
+        let value = item.repr().map_or_else(|_| "?".into(), |r| r.to_string());
+        let kind = item
+            .get_type()
+            .name()
+            .map_or_else(|_| "?".into(), |n| n.to_string());
+        format!("trajectory_id[{row}] = {value} ({kind})")
+    };
+    let (mut ints, mut names) = (Vec::new(), Vec::new());
+    for (row, item) in ob.try_iter()?.enumerate() {
+        let item = item?;
+        let is_int = item.is_instance_of::<PyInt>() && !item.is_instance_of::<PyBool>();
+        let is_str = item.is_instance_of::<PyString>();
+        if (is_int && !names.is_empty()) || (is_str && !ints.is_empty()) {
+            let first = if ints.is_empty() { "str" } else { "int" };
+            return Err(PyTypeError::new_err(format!(
+                "{}: ids must be all int or all str, as the first one ({first})",
+                describe(row, &item)
+            )));
+        }
+        if is_int {
+            let id: i128 = item.extract().map_err(|_| {
+                PyValueError::new_err(format!("{} does not fit in 64 bits", describe(row, &item)))
+            })?;
+            ints.push(u64::try_from(id).map_err(|_| {
+                PyValueError::new_err(match id < 0 {
+                    true => format!("trajectory_id[{row}] = {id} is negative"),
+                    false => format!("{} does not fit in 64 bits", describe(row, &item)),
+                })
+            })?);
+        } else if is_str {
+            names.push(item.extract()?);
+        } else {
+            return Err(PyTypeError::new_err(format!(
+                "{}: expected int or str trajectory ids",
+                describe(row, &item)
+            )));
+        }
+    }
+    Ok(match ints.is_empty() {
+        true => TrajectoryIds::Names(names),
+        false => TrajectoryIds::Objects(ints),
+    })
+}
+
+impl TrajectoryIds<'_> {
+    /// Ids handed to the core ingestion, which only takes `u32`: a `uint32` array itself,
+    /// 64-bit ids that all fit in `u32` narrowed, otherwise the ids numbered in order of
+    /// first appearance. The numbering gives the original ids back with
+    /// [`IdNumbering::restore`].
+    ///
+    /// Return
+    /// ----------
+    /// * The `u32` ids and their numbering, or a `ValueError` naming the first negative
+    ///   id.
+    pub fn numbered(&self) -> PyResult<(Cow<'_, [u32]>, IdNumbering)> {
+        let mut numbering = IdNumbering::default();
+        let ids = match self {
+            TrajectoryIds::Numbers(ids) => match ids.as_slice() {
+                Ok(ids) => Cow::Borrowed(ids),
+                Err(_) => Cow::Owned(ids.as_array().to_vec()),
+            },
+            TrajectoryIds::Wide(ids) => Cow::Owned(match ids.as_slice() {
+                Ok(ids) => numbering.number_wide(ids),
+                Err(_) => numbering.number_wide(&ids.as_array().to_vec()),
+            }),
+            TrajectoryIds::Signed(SignedIds(ids)) => {
+                if let Some((row, id)) = ids.iter().enumerate().find(|(_, &id)| id < 0) {
+                    return Err(PyValueError::new_err(format!(
+                        "trajectory_id[{row}] = {id} is negative"
+                    )));
+                }
+                let ids: Vec<u64> = ids.iter().map(|&id| id as u64).collect();
+                Cow::Owned(numbering.number_wide(&ids))
+            }
+            TrajectoryIds::Objects(ids) => Cow::Owned(numbering.number_wide(ids)),
+            TrajectoryIds::Names(names) => {
+                Cow::Owned(names.iter().map(|name| numbering.number(name)).collect())
+            }
+        };
+        Ok((ids, numbering))
+    }
+}
+
+impl TrajectoryIds<'static> {
+    /// Ids read as text (CSV files): integers when every id is a non-negative integer
+    /// (leading zeros dropped), strings otherwise.
+    pub fn from_text(ids: Vec<String>) -> Self {
+        match ids
+            .iter()
+            .map(|id| id.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(numbers) if numbers.iter().all(|&id| id >= 0) => {
+                TrajectoryIds::Signed(SignedIds(numbers))
+            }
+            _ => TrajectoryIds::Names(ids),
+        }
+    }
+}
+
+/// Signed or narrow integer ids (`int64` down to `int8`, `uint16`, `uint8`), widened to
+/// `i64` in a single copy whatever the memory layout.
+pub struct SignedIds(Vec<i64>);
+
+impl<'py> FromPyObject<'py> for SignedIds {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        macro_rules! widened {
+            ($($t:ty),*) => {$(
+                if let Ok(a) = ob.extract::<PyReadonlyArray1<'py, $t>>() {
+                    return Ok(SignedIds(a.as_array().iter().map(|&v| v as i64).collect()));
+                }
+            )*};
+        }
+        widened!(i64, i32, i16, i8, u16, u8);
+        Err(PyTypeError::new_err(format!(
+            "expected a 1-D integer array, got {}",
+            describe_array(ob)
+        )))
+    }
+}
+
+/// Columns of `TrajectorySet.from_records`, accumulated from one mapping per observation
+/// and handed to the NumPy loader.
+pub struct RecordColumns {
+    /// `trajectory_id` of every record, all integers or all strings.
+    pub ids: TrajectoryIds<'static>,
+    pub ra_deg: Vec<f64>,
+    pub dec_deg: Vec<f64>,
+    pub sigma_arcsec: Vec<f64>,
+    pub mjd_tt: Vec<f64>,
+    /// Optional `mag` of every record (NaN where absent), `None` when no record has one.
+    pub mag: Option<Vec<f64>>,
+    /// Optional `band` of every record, `None` when no record has one.
+    pub band: Option<Vec<Option<String>>>,
+}
+
+impl RecordColumns {
+    /// Read `records`, an iterable of mappings with the keys `trajectory_id`, `ra_deg`,
+    /// `dec_deg`, `sigma_arcsec` and `mjd_tt` (and optionally `mag`, `band`).
+    ///
+    /// Return
+    /// ----------
+    /// * The columns, or an error naming the index of the first invalid record:
+    ///   `KeyError` for a missing key, `TypeError` for a record that is not a mapping, a
+    ///   value of the wrong type, or ids mixing integers and strings.
+    pub fn collect(records: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let (mut ints, mut names) = (Vec::new(), Vec::new());
+        let (mut ra_deg, mut dec_deg, mut sigma_arcsec, mut mjd_tt) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let (mut mag, mut band) = (Vec::new(), Vec::new());
+        for (i, record) in records.try_iter()?.enumerate() {
+            let record = record?;
+            let record = record.cast::<PyMapping>().map_err(|_| {
+                PyTypeError::new_err(format!(
+                    "record {i} is not a mapping, got {}",
+                    type_name(&record)
+                ))
+            })?;
+            let get = |key: &str| -> PyResult<Option<Bound<'_, PyAny>>> {
+                match record.get_item(key) {
+                    Ok(value) => Ok(Some(value)),
+                    Err(err) if err.is_instance_of::<PyKeyError>(record.py()) => Ok(None),
+                    Err(err) => Err(err),
+                }
+            };
+            let required = |key: &str| {
+                get(key)?.ok_or_else(|| PyKeyError::new_err(format!("record {i} has no '{key}'")))
+            };
+            // Optional keys read as absent when missing or `None`.
+            let optional = |key: &str| Ok::<_, PyErr>(get(key)?.filter(|v| !v.is_none()));
+            let real = |key: &str| -> PyResult<f64> {
+                let value = required(key)?;
+                value
+                    .extract()
+                    .map_err(|_| wrong_type(i, key, &value, "a real number"))
+            };
+
+            let id = required("trajectory_id")?;
+            match (id.extract::<i64>(), id.extract::<String>()) {
+                (Ok(id), _) if names.is_empty() => ints.push(id),
+                (_, Ok(name)) if ints.is_empty() => names.push(name),
+                _ => {
+                    let expected = match (ints.is_empty(), names.is_empty()) {
+                        (false, _) => "an int, like the previous ids",
+                        (_, false) => "a str, like the previous ids",
+                        _ => "an int or a str",
+                    };
+                    return Err(wrong_type(i, "trajectory_id", &id, expected));
+                }
+            }
+            ra_deg.push(real("ra_deg")?);
+            dec_deg.push(real("dec_deg")?);
+            sigma_arcsec.push(real("sigma_arcsec")?);
+            mjd_tt.push(real("mjd_tt")?);
+            mag.push(match optional("mag")? {
+                Some(_) => real("mag")?,
+                None => f64::NAN,
+            });
+            band.push(match optional("band")? {
+                Some(value) => Some(
+                    value
+                        .extract::<String>()
+                        .map_err(|_| wrong_type(i, "band", &value, "a str"))?,
+                ),
+                None => None,
+            });
+        }
+        Ok(RecordColumns {
+            ids: if ints.is_empty() {
+                TrajectoryIds::Names(names)
+            } else {
+                TrajectoryIds::Signed(SignedIds(ints))
+            },
+            ra_deg,
+            dec_deg,
+            sigma_arcsec,
+            mjd_tt,
+            mag: mag.iter().any(|m| !m.is_nan()).then_some(mag),
+            band: band.iter().any(Option::is_some).then_some(band),
+        })
+    }
+}
+
+/// Fields of a chunk of `TrajectorySet.from_chunks`, in tuple order; the last two are
+/// optional.
+const CHUNK_FIELDS: [&str; 8] = [
+    "trajectory_id",
+    "ra_deg",
+    "dec_deg",
+    "error_ra_arcsec",
+    "error_dec_arcsec",
+    "mjd_tt",
+    "mag",
+    "band",
+];
+
+/// One chunk of `TrajectorySet.from_chunks`: the arguments of the degrees loader for a
+/// slice of the rows.
+pub struct Chunk<'py> {
+    pub trajectory_id: Masked<TrajectoryIds<'py>>,
+    pub ra_deg: Masked<Reals<'py>>,
+    pub dec_deg: Masked<Reals<'py>>,
+    pub error_ra_arcsec: Masked<Sigma<'py>>,
+    pub error_dec_arcsec: Masked<Sigma<'py>>,
+    pub mjd_tt: Masked<Epochs<'py>>,
+    pub mag: Option<Masked<Reals<'py>>>,
+    pub band: Option<Vec<Option<String>>>,
+}
+
+impl<'py> Chunk<'py> {
+    /// Read chunk `i`: a tuple (or list) of arrays in the order of `CHUNK_FIELDS`, or a
+    /// mapping keyed by those names.
+    ///
+    /// Return
+    /// ----------
+    /// * The chunk, or an error prefixed with `chunk {i}`: `KeyError` / `ValueError` for a
+    ///   missing field, `TypeError` for a chunk of another type or a field that cannot be
+    ///   read as its loader argument.
+    pub fn extract(i: usize, chunk: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let mut fields: Vec<Option<Bound<'py, PyAny>>> = vec![None; CHUNK_FIELDS.len()];
+        if let Ok(map) = chunk.cast::<PyMapping>() {
+            for (field, name) in fields.iter_mut().zip(CHUNK_FIELDS) {
+                match map.get_item(name) {
+                    Ok(value) => *field = Some(value),
+                    Err(err) if err.is_instance_of::<PyKeyError>(chunk.py()) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+            if let Some(k) = fields[..6].iter().position(Option::is_none) {
+                return Err(PyKeyError::new_err(format!(
+                    "chunk {i} has no '{}'",
+                    CHUNK_FIELDS[k]
+                )));
+            }
+        } else if chunk.is_instance_of::<PyTuple>() || chunk.is_instance_of::<PyList>() {
+            let n = chunk.len()?;
+            if !(6..=CHUNK_FIELDS.len()).contains(&n) {
+                return Err(PyValueError::new_err(format!(
+                    "chunk {i} has {n} arrays, expected 6 to 8 ({})",
+                    CHUNK_FIELDS.join(", ")
+                )));
+            }
+            for (k, field) in fields.iter_mut().take(n).enumerate() {
+                *field = Some(chunk.get_item(k)?);
+            }
+        } else {
+            return Err(PyTypeError::new_err(format!(
+                "chunk {i} must be a tuple or a dict of arrays, got {}",
+                type_name(chunk)
+            )));
+        }
+
+        Ok(Chunk {
+            trajectory_id: required_field(i, &fields, 0)?,
+            ra_deg: required_field(i, &fields, 1)?,
+            dec_deg: required_field(i, &fields, 2)?,
+            error_ra_arcsec: required_field(i, &fields, 3)?,
+            error_dec_arcsec: required_field(i, &fields, 4)?,
+            mjd_tt: required_field(i, &fields, 5)?,
+            mag: field(i, &fields, 6)?,
+            band: field(i, &fields, 7)?,
+        })
+    }
+}
+
+/// Field `k` of chunk `i` (see `CHUNK_FIELDS`), `None` when absent or `None`.
+fn field<'py, T: FromPyObject<'py>>(
+    i: usize,
+    fields: &[Option<Bound<'py, PyAny>>],
+    k: usize,
+) -> PyResult<Option<T>> {
+    let Some(value) = fields[k].as_ref().filter(|v| !v.is_none()) else {
+        return Ok(None);
+    };
+    value.extract().map(Some).map_err(|err| {
+        PyTypeError::new_err(format!(
+            "chunk {i}: '{}': {}",
+            CHUNK_FIELDS[k],
+            err.value(value.py())
+        ))
+    })
+}
+
+/// Field `k` of chunk `i`, which must not be `None`.
+fn required_field<'py, T: FromPyObject<'py>>(
+    i: usize,
+    fields: &[Option<Bound<'py, PyAny>>],
+    k: usize,
+) -> PyResult<T> {
+    field(i, fields, k)?
+        .ok_or_else(|| PyTypeError::new_err(format!("chunk {i}: '{}' is None", CHUNK_FIELDS[k])))
+}
+
+/// `TypeError` for the value of `key` in record `i`.
+fn wrong_type(i: usize, key: &str, value: &Bound<'_, PyAny>, expected: &str) -> PyErr {
+    PyTypeError::new_err(format!(
+        "record {i}: '{key}' must be {expected}, got {}",
+        type_name(value)
+    ))
+}
+
+/// Python type name of `value`, for error messages.
+fn type_name(value: &Bound<'_, PyAny>) -> String {
+    value
+        .get_type()
+        .name()
+        .map_or_else(|_| "?".to_string(), |name| name.to_string())
+}
+
+/// Trajectory ids the core ingestion cannot take as they are (strings, 64-bit integers),
+/// numbered in order of first appearance.
+#[derive(Default)]
+pub struct IdNumbering {
+    keys: Vec<ObjectNumber>,
+    numbers: HashMap<String, u32>,
+}
+
+impl IdNumbering {
+    /// Number of the string id `name`, assigned on its first appearance.
+    pub fn number(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.numbers.get(name) {
+            return id;
+        }
+        let id = self.keys.len() as u32;
+        self.numbers.insert(name.to_string(), id);
+        self.keys.push(ObjectNumber::String(name.to_string()));
+        id
+    }
+
+    /// Numbers of 64-bit integer ids: the ids themselves when they all fit in `u32`.
+    ///
+    /// Larger ids are keyed by `object_number_from_u64` once restored.
+    pub fn number_wide(&mut self, ids: &[u64]) -> Vec<u32> {
+        if let Ok(narrow) = ids.iter().map(|&id| u32::try_from(id)).collect() {
+            return narrow;
+        }
+        let mut numbers: HashMap<u64, u32> = HashMap::new();
+        ids.iter()
+            .map(|&id| {
+                *numbers.entry(id).or_insert_with(|| {
+                    self.keys.push(object_number_from_u64(id));
+                    (self.keys.len() - 1) as u32
+                })
+            })
+            .collect()
+    }
+
+    /// Rename the trajectories of `set` numbered by [`Self::number`] or
+    /// [`Self::number_wide`] to their original ids; `set` is returned unchanged when no
+    /// id was numbered.
+    pub fn restore(&self, set: TrajectorySet) -> TrajectorySet {
+        if self.keys.is_empty() {
+            return set;
+        }
+        set.into_iter()
+            .map(|(key, obs)| match key {
+                ObjectNumber::Int(id) => (self.key(id), obs),
+                other => (other, obs),
+            })
+            .collect()
+    }
+
+    /// Original id of the trajectory numbered `id`.
+    pub fn key(&self, id: u32) -> ObjectNumber {
+        self.keys
+            .get(id as usize)
+            .cloned()
+            .unwrap_or(ObjectNumber::Int(id))
+    }
+}
+
+/// Observing site of every row of a multi-site batch (`observer_codes` of
+/// `TrajectorySet.from_numpy_multi_site`).
+pub enum SiteRows {
+    /// Positions in a list of `Observer` objects.
+    Indices(Vec<i64>),
+    /// MPC observatory codes.
+    Codes(Vec<String>),
+}
+
+impl SiteRows {
+    /// Read an integer array (any integer dtype) or a sequence of code strings.
+    pub fn extract(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let kind: Option<String> = obj
+            .getattr("dtype")
+            .and_then(|d| d.getattr("kind"))
+            .and_then(|k| k.extract())
+            .ok();
+        if matches!(kind.as_deref(), Some("i" | "u")) {
+            let values = obj.call_method1("astype", ("int64",))?;
+            let values: PyReadonlyArray1<'_, i64> = values.extract()?;
+            return Ok(SiteRows::Indices(values.as_array().to_vec()));
+        }
+        if let Ok(codes) = obj.extract::<Vec<String>>() {
+            return Ok(SiteRows::Codes(codes));
+        }
+        if let Ok(indices) = obj.extract::<Vec<i64>>() {
+            return Ok(SiteRows::Indices(indices));
+        }
+        Err(PyTypeError::new_err(
+            "observer_codes must be an integer array indexing `observers` or a sequence of \
+             MPC code strings",
+        ))
+    }
+
+    /// The sites of the rows kept by [`DroppedRows`]; itself for `None`.
+    pub fn take_rows(self, rows: Option<&[usize]>) -> Self {
+        let Some(rows) = rows else {
+            return self;
+        };
+        match self {
+            SiteRows::Indices(v) => SiteRows::Indices(rows.iter().map(|&k| v[k]).collect()),
+            SiteRows::Codes(v) => SiteRows::Codes(rows.iter().map(|&k| v[k].clone()).collect()),
+        }
+    }
+
+    /// Number of rows.
+    pub fn n_rows(&self) -> usize {
+        match self {
+            SiteRows::Indices(v) => v.len(),
+            SiteRows::Codes(v) => v.len(),
+        }
+    }
+
+    /// Rows grouped by site, in order of first appearance.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Environment resolving the MPC codes.
+    /// * `sites` – MPC sites of `env`, recording the code each site is resolved with.
+    /// * `observers` – Sites indexed by integer rows; required by (and only allowed with)
+    ///   [`SiteRows::Indices`].
+    /// * `topocentric` – `false` for astrometry already reduced to the geocentre, see
+    ///   [`observing_site`].
+    ///
+    /// Return
+    /// ----------
+    /// * One `(site, rows)` pair per distinct index or code. Raises `ValueError` for an
+    ///   index out of range or an unknown code, naming the first offending row.
+    pub fn groups(
+        &self,
+        env: &Outfit,
+        sites: &MpcSites,
+        observers: Option<&[Arc<Observer>]>,
+        topocentric: bool,
+    ) -> PyResult<Vec<(Arc<Observer>, Vec<usize>)>> {
+        let groups = match (self, observers) {
+            (SiteRows::Indices(indices), Some(observers)) => {
+                if let Some((row, i)) = indices
+                    .iter()
+                    .enumerate()
+                    .find(|(_, &i)| usize::try_from(i).map_or(true, |i| i >= observers.len()))
+                {
+                    return Err(PyValueError::new_err(format!(
+                        "observer_codes[{row}] = {i} is out of range for {} observers",
+                        observers.len()
+                    )));
+                }
+                group_rows(indices.iter())
+                    .into_iter()
+                    .map(|(&i, rows)| (observers[i as usize].clone(), rows))
+                    .collect::<Vec<_>>()
+            }
+            (SiteRows::Indices(_), None) => {
+                return Err(PyValueError::new_err(
+                    "integer observer_codes index the `observers` list, which is missing",
+                ))
+            }
+            (SiteRows::Codes(codes), None) => {
+                let known = sites.table(env);
+                if let Some((row, code)) = codes
+                    .iter()
+                    .enumerate()
+                    .find(|(_, c)| !known.contains_key(*c))
+                {
+                    return Err(PyValueError::new_err(format!(
+                        "observer_codes[{row}]: unknown observatory code {code:?}"
+                    )));
+                }
+                group_rows(codes.iter())
+                    .into_iter()
+                    .filter_map(|(code, rows)| Some((sites.resolve(env, code)?, rows)))
+                    .collect()
+            }
+            (SiteRows::Codes(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "`observers` is only used with integer observer_codes",
+                ))
+            }
+        };
+        groups
+            .into_iter()
+            .map(|(site, rows)| Ok((observing_site(&site, topocentric)?, rows)))
+            .collect()
+    }
+}
+
+/// Positions of every distinct key, in order of first appearance.
+fn group_rows<K: Hash + Eq + Copy>(keys: impl Iterator<Item = K>) -> Vec<(K, Vec<usize>)> {
+    let mut slots: HashMap<K, usize> = HashMap::new();
+    let mut groups: Vec<(K, Vec<usize>)> = Vec::new();
+    for (row, key) in keys.enumerate() {
+        match slots.get(&key) {
+            Some(&slot) => groups[slot].1.push(row),
+            None => {
+                slots.insert(key, groups.len());
+                groups.push((key, vec![row]));
+            }
+        }
+    }
+    groups
+}
+
+/// Policy for non-positive (or non-finite) uncertainties found at ingestion.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BadSigma {
+    /// Reject the ingestion with a `ValueError`.
+    Error,
+    /// Substitute the observer's error-model accuracy.
+    Model,
+    /// Substitute a fixed value (radians).
+    Value(f64),
+}
+
+impl BadSigma {
+    /// Parse the Python-facing `bad_sigma` / `bad_sigma_value` pair.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `policy` – `"error"`, `"model"` or `"value"`.
+    /// * `value` – Replacement uncertainty, required by (and only allowed with) `"value"`.
+    /// * `to_rad` – Factor converting `value` to radians (unit of the ingestion call).
+    ///
+    /// Return
+    /// ----------
+    /// * The parsed policy, or `ValueError` on an unknown policy / missing or invalid value.
+    pub fn parse(policy: &str, value: Option<f64>, to_rad: f64) -> PyResult<Self> {
+        match (policy, value) {
+            ("error", None) => Ok(BadSigma::Error),
+            ("model", None) => Ok(BadSigma::Model),
+            ("value", Some(v)) if v.is_finite() && v > 0.0 => Ok(BadSigma::Value(v * to_rad)),
+            ("value", Some(v)) => Err(PyValueError::new_err(format!(
+                "bad_sigma_value must be a positive finite float, got {v}"
+            ))),
+            ("value", None) => Err(PyValueError::new_err(
+                "bad_sigma='value' requires bad_sigma_value",
+            )),
+            ("error" | "model", Some(_)) => Err(PyValueError::new_err(
+                "bad_sigma_value is only used with bad_sigma='value'",
+            )),
+            (other, _) => Err(PyValueError::new_err(format!(
+                "bad_sigma must be 'error', 'model' or 'value', got {other:?}"
+            ))),
+        }
+    }
+}
+
+/// `true` when an uncertainty cannot be used as a weight.
+fn is_bad(sigma: f64) -> bool {
+    !(sigma.is_finite() && sigma > 0.0)
+}
+
+/// Apply `policy` to every observation of `set`.
+///
+/// Arguments
+/// -----------------
+/// * `set` – Freshly ingested observations, patched in place.
+/// * `env` – Environment used to resolve observers (for [`BadSigma::Model`]).
+/// * `policy` – What to do with non-positive or non-finite uncertainties.
+///
+/// Return
+/// ----------
+/// * The number of observations that had at least one uncertainty replaced, or a
+///   `ValueError` (with `set` left untouched) when the policy is [`BadSigma::Error`],
+///   or when [`BadSigma::Model`] meets an observer without error-model accuracy.
+pub fn apply_sigma_policy(
+    set: &mut TrajectorySet,
+    env: &Outfit,
+    policy: BadSigma,
+) -> PyResult<usize> {
+    let bad: Vec<_> = set
+        .iter()
+        .flat_map(|(k, obs)| obs.iter().map(move |o| (k, o)))
+        .filter(|(_, o)| is_bad(o.error_ra) || is_bad(o.error_dec))
+        .collect();
+
+    if bad.is_empty() {
+        return Ok(0);
+    }
+
+    match policy {
+        BadSigma::Error => {
+            let (k, o) = bad[0];
+            return Err(PyValueError::new_err(format!(
+                "{} observation(s) have non-positive or non-finite uncertainties \
+                 (first: trajectory {k}, mjd {}, sigma_ra={}, sigma_dec={}); \
+                 use bad_sigma='model' or bad_sigma='value' to substitute them",
+                bad.len(),
+                o.time,
+                o.error_ra,
+                o.error_dec
+            )));
+        }
+        BadSigma::Model => {
+            if let Some((k, o)) = bad.iter().find(|(_, o)| {
+                let site = o.get_observer(env);
+                site.ra_accuracy.is_none() || site.dec_accuracy.is_none()
+            }) {
+                return Err(PyValueError::new_err(format!(
+                    "bad_sigma='model': observer '{}' (trajectory {k}, mjd {}) has no \
+                     error-model accuracy",
+                    o.get_observer(env).name.as_deref().unwrap_or("Unnamed"),
+                    o.time
+                )));
+            }
+        }
+        BadSigma::Value(_) => {}
+    }
+
+    let mut patched = 0;
+    for obs in set.values_mut() {
+        for o in obs.iter_mut() {
+            let (bad_ra, bad_dec) = (is_bad(o.error_ra), is_bad(o.error_dec));
+            if !(bad_ra || bad_dec) {
+                continue;
+            }
+            let (ra, dec) = match policy {
+                BadSigma::Value(v) => (v, v),
+                _ => {
+                    let site = o.get_observer(env);
+                    (
+                        site.ra_accuracy.map_or(f64::NAN, |v| v.into_inner()),
+                        site.dec_accuracy.map_or(f64::NAN, |v| v.into_inner()),
+                    )
+                }
+            };
+            if bad_ra {
+                o.error_ra = ra;
+            }
+            if bad_dec {
+                o.error_dec = dec;
+            }
+            patched += 1;
+        }
+    }
+    Ok(patched)
+}
+
+/// Offending rows listed per column in the message of [`check_rows`].
+const MAX_INVALID_ROWS: usize = 20;
+
+/// MJD of 1800-01-01 and 2200-01-01, the epochs accepted by [`check_rows`].
+const PLAUSIBLE_MJD: (f64, f64) = (-21504.0, 124593.0);
+
+/// Quantity held by a column of [`check_rows`], which sets its valid range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RowCheck {
+    /// Right ascension, in degrees (`true`) or radians.
+    Ra(bool),
+    /// Declination, in degrees (`true`) or radians.
+    Dec(bool),
+    /// Epoch, MJD (TT).
+    Epoch,
+    /// 1-σ uncertainty.
+    Sigma,
+}
+
+impl RowCheck {
+    fn accepts(self, v: f64) -> bool {
+        let turn = |degrees: bool| {
+            if degrees {
+                360.0
+            } else {
+                std::f64::consts::TAU
+            }
+        };
+        match self {
+            RowCheck::Ra(degrees) => (0.0..turn(degrees)).contains(&v),
+            RowCheck::Dec(degrees) => (-turn(degrees) / 4.0..=turn(degrees) / 4.0).contains(&v),
+            RowCheck::Epoch => (PLAUSIBLE_MJD.0..PLAUSIBLE_MJD.1).contains(&v),
+            RowCheck::Sigma => v.is_finite() && v > 0.0,
+        }
+    }
+
+    /// Why a rejected value is invalid.
+    fn problem(self) -> &'static str {
+        match self {
+            RowCheck::Ra(true) => "not finite or outside [0, 360)",
+            RowCheck::Ra(false) => "not finite or outside [0, 2π)",
+            RowCheck::Dec(true) => "not finite or outside [-90, 90]",
+            RowCheck::Dec(false) => "not finite or outside [-π/2, π/2]",
+            RowCheck::Epoch => "not finite or outside the years 1800-2200",
+            RowCheck::Sigma => "non-positive or non-finite (see bad_sigma)",
+        }
+    }
+}
+
+/// Reject the rows of a batch holding values that cannot be observations.
+///
+/// NaN and infinities are outside every range, so they are reported with the column
+/// they appear in.
+///
+/// Arguments
+/// -----------------
+/// * `columns` – Name (as in the Python call), values and quantity of every checked
+///   column, all of the same length.
+///
+/// Return
+/// ----------
+/// * `Ok(())`, or a `ValueError` with one line per failing column: its name, what is
+///   wrong with its values and the offending row indices (the first `MAX_INVALID_ROWS`).
+pub fn check_rows(columns: &[(&str, &[f64], RowCheck)]) -> PyResult<()> {
+    let failures: Vec<String> = columns
+        .iter()
+        .filter_map(|&(name, values, check)| {
+            let rows: Vec<usize> = values
+                .iter()
+                .enumerate()
+                .filter(|(_, &v)| !check.accepts(v))
+                .map(|(row, _)| row)
+                .collect();
+            if rows.is_empty() {
+                return None;
+            }
+            let shown: Vec<String> = rows
+                .iter()
+                .take(MAX_INVALID_ROWS)
+                .map(usize::to_string)
+                .collect();
+            let more = match rows.len().saturating_sub(MAX_INVALID_ROWS) {
+                0 => String::new(),
+                n => format!(" (and {n} more)"),
+            };
+            Some(format!(
+                "  {name}: {} value(s) {}, at rows {}{more}",
+                rows.len(),
+                check.problem(),
+                shown.join(", ")
+            ))
+        })
+        .collect();
+    if failures.is_empty() {
+        return Ok(());
+    }
+    Err(PyValueError::new_err(format!(
+        "invalid input rows (pass validate=False to skip this check):\n{}",
+        failures.join("\n")
+    )))
+}
+
+/// [`check_rows`] on the columns of a NumPy batch.
+///
+/// Arguments
+/// -----------------
+/// * `ra`, `dec`, `sigmas` – Argument names and values, in degrees / arcseconds when
+///   `degrees`, radians otherwise.
+/// * `mjd` – MJD (TT) epochs, converted from the `mjd_tt` argument.
+/// * `policy` – `bad_sigma` policy: per-row uncertainties are only checked with
+///   [`BadSigma::Error`], the other policies substitute the invalid ones.
+pub fn check_batch(
+    ra: (&str, &[f64]),
+    dec: (&str, &[f64]),
+    mjd: &[f64],
+    sigmas: [(&str, &Sigma<'_>); 2],
+    degrees: bool,
+    policy: BadSigma,
+) -> PyResult<()> {
+    let mut columns = vec![
+        (ra.0, ra.1, RowCheck::Ra(degrees)),
+        (dec.0, dec.1, RowCheck::Dec(degrees)),
+        ("mjd_tt", mjd, RowCheck::Epoch),
+    ];
+    if policy == BadSigma::Error {
+        for (name, sigma) in sigmas {
+            if let Some(values) = sigma.per_row()? {
+                columns.push((name, values, RowCheck::Sigma));
+            }
+        }
+    }
+    check_rows(&columns)
+}
+
+/// Reject observations whose epoch lies outside the environment's ephemeris range.
+///
+/// Arguments
+/// -----------------
+/// * `set` – Freshly ingested observations.
+/// * `range` – `(mjd_start, mjd_end)` accepted by the environment (TDB).
+///
+/// Return
+/// ----------
+/// * `Ok(())`, or a `ValueError` giving the number of offending observations, the
+///   first one, and the valid range.
+///
+/// Notes
+/// ----------
+/// * Epochs are MJD (TT); the TT−TDB offset (below 2 ms) is ignored.
+pub fn check_time_range(set: &TrajectorySet, range: (f64, f64)) -> PyResult<()> {
+    let (start, end) = range;
+    let mut outside = set
+        .iter()
+        .flat_map(|(k, obs)| obs.iter().map(move |o| (k, o.time)))
+        .filter(|(_, t)| !(start..=end).contains(t));
+    let Some((k, t)) = outside.next() else {
+        return Ok(());
+    };
+    Err(PyValueError::new_err(format!(
+        "{} observation(s) fall outside the ephemeris time range of the environment \
+         [{start:.6}, {end:.6}] (MJD TDB) (first: trajectory {k}, mjd {t}); \
+         use a kernel or span covering these epochs",
+        1 + outside.count()
+    )))
+}
+
+/// Site to attribute the rows of an ingestion batch to.
+///
+/// Arguments
+/// -----------------
+/// * `observer` – Site passed to the ingestion call.
+/// * `topocentric` – `false` for astrometry already reduced to the geocentre.
+///
+/// Return
+/// ----------
+/// * `observer` itself when `topocentric` (or already at the geocentre), otherwise its geocentric counterpart: same
+///   longitude, name (suffixed with ` (geocentric)`) and accuracies, with zero parallax
+///   constants. It is registered as a site of its own in the environment, so that
+///   topocentric and geocentric rows of one set keep their own geometry.
+pub fn observing_site(observer: &Arc<Observer>, topocentric: bool) -> PyResult<Arc<Observer>> {
+    if topocentric || (observer.rho_cos_phi == 0.0 && observer.rho_sin_phi == 0.0) {
+        return Ok(observer.clone());
+    }
+    let name = match &observer.name {
+        Some(name) => format!("{name} (geocentric)"),
+        None => "Geocentric".to_string(),
+    };
+    let site = Observer::from_parallax(
+        observer.longitude.into_inner(),
+        0.0,
+        0.0,
+        Some(name),
+        observer.ra_accuracy.map(|a| a.into_inner()),
+        observer.dec_accuracy.map(|a| a.into_inner()),
+    )
+    .into_py()?;
+    Ok(Arc::new(site))
+}
+
+/// Distinct epochs whose site positions are computed per call to the core by
+/// [`Rows::ingest`].
+pub const EPOCH_CHUNK: usize = 1 << 10;
+
+/// `ahash` state of the transient maps of an ingestion.
+type FastHash = BuildHasherDefault<ahash::AHasher>;
+
+/// Observation of each distinct epoch of a batch (by bit pattern), site positions
+/// computed and angles left at zero.
+type Templates = HashMap<u64, Observation, FastHash>;
+
+/// Threads of a parallel ingestion (`parallel=True`, `n_threads=`).
+pub enum Workers {
+    /// The global rayon pool.
+    Global,
+    /// A dedicated pool of `n_threads` threads.
+    Pool(rayon::ThreadPool),
+}
+
+impl Workers {
+    /// Workers from the loader arguments, `None` for the serial build.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `parallel` – Build on several threads.
+    /// * `n_threads` – Number of threads (default: the size of the global rayon pool).
+    ///
+    /// Return
+    /// ----------
+    /// * `ValueError` for `n_threads=0` or `n_threads` without `parallel`.
+    pub fn new(parallel: bool, n_threads: Option<usize>) -> PyResult<Option<Self>> {
+        match (parallel, n_threads) {
+            (false, None) => Ok(None),
+            (false, Some(_)) => Err(PyValueError::new_err("n_threads requires parallel=True")),
+            (true, None) => Ok(Some(Workers::Global)),
+            (true, Some(0)) => Err(PyValueError::new_err("n_threads must be positive")),
+            (true, Some(n)) => rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .map(|pool| Some(Workers::Pool(pool)))
+                .map_err(|e| PyValueError::new_err(format!("cannot start {n} threads: {e}"))),
+        }
+    }
+
+    fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        match self {
+            Workers::Global => f(),
+            Workers::Pool(pool) => pool.install(f),
+        }
+    }
+}
+
+/// Worker of `id` among `workers`: a multiplicative hash spreads consecutive and strided
+/// ids evenly.
+fn bucket(id: u32, workers: usize) -> usize {
+    ((u64::from(id.wrapping_mul(0x9E37_79B9)) * workers as u64) >> 32) as usize
+}
+
+/// Template observations of `epochs` observed from `site`.
+///
+/// The core computes the positions of [`EPOCH_CHUNK`] epochs at a time, each as a
+/// one-observation trajectory, so that its own buffers stay small whatever the number of
+/// distinct epochs.
+fn templates(
+    env: &mut Outfit,
+    site: Arc<Observer>,
+    epochs: Vec<f64>,
+) -> Result<Templates, OutfitError> {
+    let mut templates = Templates::with_capacity_and_hasher(epochs.len(), FastHash::default());
+    let slots: Vec<u32> = (0..EPOCH_CHUNK as u32).collect();
+    let zeros = [0.0; EPOCH_CHUNK];
+    for chunk in epochs.chunks(EPOCH_CHUNK) {
+        let n = chunk.len();
+        let batch = ObservationBatch::from_radians_borrowed(
+            &slots[..n],
+            &zeros[..n],
+            &zeros[..n],
+            0.0,
+            0.0,
+            chunk,
+        );
+        let set = TrajectorySet::new_from_vec(env, &batch, site.clone())?;
+        templates.extend(set.into_values().map(|obs| (obs[0].time.to_bits(), obs[0])));
+    }
+    Ok(templates)
+}
+
+/// Columns of an ingestion batch, with angles in the unit of the loader.
+pub struct Rows<'a> {
+    /// Trajectory id of each row.
+    pub ids: &'a [u32],
+    /// Right ascension of each row.
+    pub ra: &'a [f64],
+    /// Declination of each row.
+    pub dec: &'a [f64],
+    /// Epoch of each row, MJD (TT).
+    pub mjd: &'a [f64],
+    /// Uniform `(ra, dec)` uncertainties, in arcseconds for degrees and radians otherwise.
+    pub errors: (f64, f64),
+    /// Per-row `(ra, dec)` uncertainties in the unit of `errors`, replacing them.
+    pub sigmas: Option<(&'a [f64], &'a [f64])>,
+    /// `true` when the angles are in degrees.
+    pub degrees: bool,
+}
+
+impl Rows<'_> {
+    /// Group the rows into trajectories observed from `site`.
+    ///
+    /// The observations are written straight from the borrowed columns into their
+    /// trajectory, converted on the fly when in degrees: the rows of each id are counted
+    /// first, so that the set and every trajectory are allocated once at their final
+    /// size, with no intermediate batch, no regrowth and no spare capacity. The site
+    /// positions are computed once per distinct epoch, as the core caches them.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Environment the observations are computed in.
+    /// * `site` – Observer of every row.
+    ///
+    /// Return
+    /// ----------
+    /// * The new trajectories, each in input order; the observations are those the core
+    ///   builds from the same rows (`TrajectorySet::new_from_vec`).
+    ///
+    /// Notes
+    /// ----------
+    /// * Besides the set, the call holds one template observation per distinct epoch
+    ///   and one row count per trajectory.
+    pub fn ingest(
+        &self,
+        env: &mut Outfit,
+        site: Arc<Observer>,
+    ) -> Result<TrajectorySet, OutfitError> {
+        let epochs: HashSet<u64, FastHash> = self.mjd.iter().map(|t| t.to_bits()).collect();
+        let templates = templates(env, site, epochs.into_iter().map(f64::from_bits).collect())?;
+        Ok(self.fill(&templates, |_| true))
+    }
+
+    /// [`Self::ingest`], on `workers` when given.
+    pub fn ingest_on(
+        &self,
+        env: &mut Outfit,
+        site: Arc<Observer>,
+        workers: Option<&Workers>,
+    ) -> Result<TrajectorySet, OutfitError> {
+        match workers {
+            None => self.ingest(env, site),
+            Some(workers) => workers.install(|| self.ingest_parallel(env, site)),
+        }
+    }
+
+    /// Group the rows into trajectories on the threads of the current rayon pool.
+    ///
+    /// The rows are partitioned by a hash of their trajectory id, each worker filling its
+    /// own trajectories as [`Self::ingest`] does, and the maps (disjoint by construction)
+    /// are merged. Observations and their order within a trajectory are identical to
+    /// those of [`Self::ingest`].
+    fn ingest_parallel(
+        &self,
+        env: &mut Outfit,
+        site: Arc<Observer>,
+    ) -> Result<TrajectorySet, OutfitError> {
+        let epochs: Vec<f64> = self
+            .mjd
+            .par_iter()
+            .fold(HashSet::<u64, FastHash>::default, |mut epochs, t| {
+                epochs.insert(t.to_bits());
+                epochs
+            })
+            .reduce(HashSet::default, |mut a, mut b| {
+                if a.len() < b.len() {
+                    std::mem::swap(&mut a, &mut b);
+                }
+                a.extend(b);
+                a
+            })
+            .into_iter()
+            .map(f64::from_bits)
+            .collect();
+        let templates = templates(env, site, epochs)?;
+
+        let workers = rayon::current_num_threads();
+        let parts: Vec<TrajectorySet> = (0..workers)
+            .into_par_iter()
+            .map(|worker| self.fill(&templates, |id| bucket(id, workers) == worker))
+            .collect();
+
+        let mut set = TrajectorySet::default();
+        set.reserve(parts.iter().map(|part| part.len()).sum());
+        for part in parts {
+            set.extend(part);
+        }
+        Ok(set)
+    }
+
+    /// Trajectories of the rows whose id passes `keep`, built from `templates`.
+    fn fill(&self, templates: &Templates, keep: impl Fn(u32) -> bool) -> TrajectorySet {
+        let mut counts: HashMap<u32, usize, FastHash> = HashMap::default();
+        for &id in self.ids.iter().filter(|&&id| keep(id)) {
+            *counts.entry(id).or_default() += 1;
+        }
+        let mut set = TrajectorySet::with_capacity_and_hasher(counts.len(), Default::default());
+
+        let (err_ra, err_dec) = match self.degrees {
+            true => (arcsec_to_rad(self.errors.0), arcsec_to_rad(self.errors.1)),
+            false => self.errors,
+        };
+        let to_rad = if self.degrees { RADSEC } else { 1.0 };
+        let angle = |x: f64| if self.degrees { x.to_radians() } else { x };
+        for (row, &id) in self.ids.iter().enumerate() {
+            if !keep(id) {
+                continue;
+            }
+            let mut o = templates[&self.mjd[row].to_bits()];
+            o.ra = angle(self.ra[row]);
+            o.dec = angle(self.dec[row]);
+            (o.error_ra, o.error_dec) = match self.sigmas {
+                Some((ra, dec)) => (ra[row] * to_rad, dec[row] * to_rad),
+                None => (err_ra, err_dec),
+            };
+            set.entry(ObjectNumber::Int(id))
+                .or_insert_with(|| Observations::with_capacity(counts[&id]))
+                .push(o);
+        }
+        set
+    }
+}
+
+/// Position of the first observation of `obs` earlier than the one before it.
+///
+/// Return
+/// ----------
+/// * `None` when `obs` is in time order (equal epochs allowed).
+pub fn first_unsorted(obs: &[Observation]) -> Option<usize> {
+    obs.windows(2)
+        .position(|w| w[1].time < w[0].time)
+        .map(|i| i + 1)
+}
+
+/// Canonical order of the observations of a trajectory.
+///
+/// Epoch first, then RA, Dec, the site and the uncertainties. The site is compared by
+/// its geocentric position at the epoch, which identifies it without the observer
+/// registry, so the order does not depend on the order in which sites were registered.
+/// Observations comparing equal hold the same values in every exported column.
+pub fn canonical_cmp(a: &Observation, b: &Observation) -> Ordering {
+    let site = |o: &Observation| o.get_observer_earth_position();
+    let (sa, sb) = (site(a), site(b));
+    a.time
+        .total_cmp(&b.time)
+        .then_with(|| a.ra.total_cmp(&b.ra))
+        .then_with(|| a.dec.total_cmp(&b.dec))
+        .then_with(|| {
+            sa.iter()
+                .zip(sb.iter())
+                .fold(Ordering::Equal, |o, (x, y)| o.then_with(|| x.total_cmp(y)))
+        })
+        .then_with(|| a.error_ra.total_cmp(&b.error_ra))
+        .then_with(|| a.error_dec.total_cmp(&b.error_dec))
+}
+
+/// Put one trajectory in canonical order ([`canonical_cmp`]).
+///
+/// Runs of already ordered observations are merged: appending an ordered batch to an
+/// ordered trajectory costs a linear merge.
+///
+/// Return
+/// ----------
+/// * `true` when the order of `obs` changed.
+pub fn canonicalize(obs: &mut Observations) -> bool {
+    if obs
+        .windows(2)
+        .all(|w| canonical_cmp(&w[0], &w[1]) != Ordering::Greater)
+    {
+        return false;
+    }
+    obs.sort_by(canonical_cmp);
+    true
+}
+
+/// Restore the time order of one trajectory, in canonical order ([`canonicalize`]).
+///
+/// Return
+/// ----------
+/// * `true` when `obs` was out of time order (observations sharing an epoch but out of
+///   canonical order are reordered without being reported).
+pub fn sort_by_epoch(obs: &mut Observations) -> bool {
+    let unsorted = first_unsorted(obs).is_some();
+    canonicalize(obs);
+    unsorted
+}
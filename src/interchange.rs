@@ -0,0 +1,491 @@
+//! DataFrame interchange protocol import.
+//!
+//! DataFrames of any library implementing `__dataframe__` (pandas, polars, vaex, cudf
+//! frames held in host memory, …) are read from the buffers the protocol exposes, on the
+//! Rust side, without going through the library's own NumPy conversion:
+//!
+//! * value columns may hold integers or floats of any width, widened to `f64`; nulls
+//!   become NaN;
+//! * identifier columns hold integers, strings, or categories of either; nulls are
+//!   rejected.
+//!
+//! Every null representation of the protocol is honoured: NaN, sentinel value, bit mask
+//! and byte mask. Columns split in several chunks are concatenated in order.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.from_dataframe` – Python entry point.
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyList},
+};
+
+use crate::ingest::TrajectoryIds;
+
+// `DtypeKind` of the protocol.
+const INT: i64 = 0;
+const UINT: i64 = 1;
+const FLOAT: i64 = 2;
+const BOOL: i64 = 20;
+const STRING: i64 = 21;
+const DATETIME: i64 = 22;
+const CATEGORICAL: i64 = 23;
+
+// `ColumnNullType` of the protocol.
+const USE_SENTINEL: i64 = 2;
+const USE_BITMASK: i64 = 3;
+const USE_BYTEMASK: i64 = 4;
+
+// DLPack device types whose memory the CPU can read: `kDLCPU` and `kDLCUDAHost`.
+const HOST_DEVICES: [i64; 2] = [1, 3];
+
+/// `(kind, bit width, format, endianness)` dtype of a column or buffer.
+struct Dtype {
+    kind: i64,
+    bits: usize,
+    endianness: String,
+}
+
+impl Dtype {
+    fn of(dtype: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let (kind, bits, _format, endianness): (i64, usize, String, String) = dtype.extract()?;
+        Ok(Dtype {
+            kind,
+            bits,
+            endianness,
+        })
+    }
+
+    /// Name of the dtype in error messages (`int64`, `string`, …).
+    fn describe(&self) -> String {
+        match self.kind {
+            INT => format!("int{}", self.bits),
+            UINT => format!("uint{}", self.bits),
+            FLOAT => format!("float{}", self.bits),
+            BOOL => "bool".into(),
+            STRING => "string".into(),
+            DATETIME => "datetime".into(),
+            CATEGORICAL => "categorical".into(),
+            other => format!("kind {other}"),
+        }
+    }
+}
+
+/// Buffer of a column: `bufsize` bytes from address `ptr`, in host memory.
+///
+/// The producer keeps the memory alive as long as the buffer object, held in `owner`.
+struct Buffer<'py> {
+    ptr: usize,
+    size: usize,
+    _owner: Bound<'py, PyAny>,
+}
+
+impl<'py> Buffer<'py> {
+    /// Checked buffer of column `name`, `ValueError` when it lives in device memory.
+    fn new(buffer: Bound<'py, PyAny>, name: &str) -> PyResult<Self> {
+        if buffer.hasattr("__dlpack_device__")? {
+            let (device, _): (i64, Option<i64>) =
+                buffer.call_method0("__dlpack_device__")?.extract()?;
+            if !HOST_DEVICES.contains(&device) {
+                return Err(PyValueError::new_err(format!(
+                    "column {name:?} lives in device memory (DLPack device {device}); copy \
+                     the DataFrame to host memory first"
+                )));
+            }
+        }
+        Ok(Buffer {
+            ptr: buffer.getattr("ptr")?.extract()?,
+            size: buffer.getattr("bufsize")?.extract()?,
+            _owner: buffer,
+        })
+    }
+
+    /// `len` values of type `T` from element `start`, `ValueError` when the buffer is
+    /// too short.
+    fn read<T: Copy>(&self, start: usize, len: usize, name: &str) -> PyResult<Vec<T>> {
+        let width = std::mem::size_of::<T>();
+        self.check(start * width, len * width, name)?;
+        let base = self.ptr + start * width;
+        Ok((0..len)
+            // SAFETY: the range is within the `bufsize` bytes the producer exposes and
+            // keeps alive with the buffer object; the reads tolerate any alignment.
+            .map(|k| unsafe { std::ptr::read_unaligned((base + k * width) as *const T) })
+            .collect())
+    }
+
+    /// `len` bits from bit `start`, least significant bit first (Arrow validity bitmaps).
+    fn bits(&self, start: usize, len: usize, name: &str) -> PyResult<Vec<bool>> {
+        let bytes = self.read::<u8>(start / 8, (start % 8 + len).div_ceil(8), name)?;
+        Ok((start..start + len)
+            .map(|bit| bytes[bit / 8 - start / 8] >> (bit % 8) & 1 == 1)
+            .collect())
+    }
+
+    fn check(&self, from: usize, len: usize, name: &str) -> PyResult<()> {
+        if (self.ptr == 0 && len > 0) || from + len > self.size {
+            return Err(PyValueError::new_err(format!(
+                "column {name:?}: buffer of {} bytes too short for the column",
+                self.size
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Values of a column, widened to 64 bits.
+enum Values {
+    Signed(Vec<i64>),
+    Unsigned(Vec<u64>),
+    Float(Vec<f64>),
+    Text(Vec<String>),
+}
+
+impl Values {
+    fn len(&self) -> usize {
+        match self {
+            Values::Signed(v) => v.len(),
+            Values::Unsigned(v) => v.len(),
+            Values::Float(v) => v.len(),
+            Values::Text(v) => v.len(),
+        }
+    }
+
+    /// Append the values of another chunk of the same column.
+    fn extend(&mut self, other: Values) {
+        match (self, other) {
+            (Values::Signed(a), Values::Signed(b)) => a.extend(b),
+            (Values::Unsigned(a), Values::Unsigned(b)) => a.extend(b),
+            (Values::Float(a), Values::Float(b)) => a.extend(b),
+            (Values::Text(a), Values::Text(b)) => a.extend(b),
+            _ => unreachable!("chunks of a column share its dtype"),
+        }
+    }
+}
+
+/// Column read from its buffers: values, rows holding a null, and the dtype name.
+struct Column {
+    values: Values,
+    nulls: Vec<usize>,
+    dtype: String,
+}
+
+/// DataFrame exposed through the interchange protocol.
+pub(crate) struct Frame<'py> {
+    frame: Bound<'py, PyAny>,
+}
+
+impl<'py> Frame<'py> {
+    /// Interchange object of `df`, `TypeError` when `df` does not implement
+    /// `__dataframe__`.
+    pub(crate) fn new(df: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if !df.hasattr("__dataframe__")? {
+            return Err(PyTypeError::new_err(format!(
+                "expected a DataFrame implementing the interchange protocol (__dataframe__), \
+                 got {}",
+                df.get_type().name()?
+            )));
+        }
+        let kwargs = PyDict::new(df.py());
+        kwargs.set_item("allow_copy", true)?;
+        Ok(Frame {
+            frame: df.call_method("__dataframe__", (), Some(&kwargs))?,
+        })
+    }
+
+    /// Names of the columns, as a list.
+    pub(crate) fn column_names(&self) -> PyResult<Bound<'py, PyList>> {
+        let names = self
+            .frame
+            .call_method0("column_names")?
+            .try_iter()?
+            .map(|name| name?.extract::<String>())
+            .collect::<PyResult<Vec<_>>>()?;
+        PyList::new(self.frame.py(), names)
+    }
+
+    /// Numeric column `name` as `f64` values, nulls becoming NaN.
+    pub(crate) fn f64_column(&self, name: &str) -> PyResult<Vec<f64>> {
+        let Column {
+            values,
+            nulls,
+            dtype,
+        } = self.column(name)?;
+        let mut values: Vec<f64> = match values {
+            Values::Signed(v) => v.into_iter().map(|v| v as f64).collect(),
+            Values::Unsigned(v) => v.into_iter().map(|v| v as f64).collect(),
+            Values::Float(v) => v,
+            Values::Text(_) => {
+                return Err(PyValueError::new_err(format!(
+                    "column {name:?} must be numeric, got dtype {dtype}"
+                )))
+            }
+        };
+        for row in nulls {
+            values[row] = f64::NAN;
+        }
+        Ok(values)
+    }
+
+    /// Identifier column `name`: integer ids (non-negative, at most 64 bits) or string
+    /// ids, `ValueError` on a null or negative id and on other dtypes.
+    pub(crate) fn ids(&self, name: &str) -> PyResult<TrajectoryIds<'static>> {
+        let Column {
+            values,
+            nulls,
+            dtype,
+        } = self.column(name)?;
+        if let Some(row) = nulls.first() {
+            return Err(PyValueError::new_err(format!(
+                "column {name:?}: the trajectory id of row {row} is null"
+            )));
+        }
+        match values {
+            Values::Unsigned(ids) => Ok(TrajectoryIds::Objects(ids)),
+            Values::Signed(ids) => ids
+                .into_iter()
+                .enumerate()
+                .map(|(row, id)| {
+                    u64::try_from(id).map_err(|_| {
+                        PyValueError::new_err(format!(
+                            "column {name:?}: trajectory id {id} of row {row} is negative"
+                        ))
+                    })
+                })
+                .collect::<PyResult<_>>()
+                .map(TrajectoryIds::Objects),
+            Values::Text(names) => Ok(TrajectoryIds::Names(names)),
+            Values::Float(_) => Err(PyValueError::new_err(format!(
+                "column {name:?} must hold integer or string trajectory ids, got dtype {dtype}"
+            ))),
+        }
+    }
+
+    /// Column `name`, its chunks concatenated.
+    fn column(&self, name: &str) -> PyResult<Column> {
+        let column = self.frame.call_method1("get_column_by_name", (name,))?;
+        let mut out: Option<Column> = None;
+        for chunk in column.call_method0("get_chunks")?.try_iter()? {
+            let chunk = read_chunk(&chunk?, name)?;
+            match out.as_mut() {
+                None => out = Some(chunk),
+                Some(out) => {
+                    let base = out.values.len();
+                    out.nulls
+                        .extend(chunk.nulls.into_iter().map(|row| base + row));
+                    out.values.extend(chunk.values);
+                }
+            }
+        }
+        match out {
+            Some(column) => Ok(column),
+            // A column without chunks is empty: read it as a single chunk.
+            None => read_chunk(&column, name),
+        }
+    }
+}
+
+/// Values and nulls of a single-chunk column; categories are resolved to their values.
+fn read_chunk(col: &Bound<'_, PyAny>, name: &str) -> PyResult<Column> {
+    let dtype = Dtype::of(&col.getattr("dtype")?)?;
+    let size: usize = col.call_method0("size")?.extract()?;
+    let offset: usize = col.getattr("offset")?.extract()?;
+    let buffers = col.call_method0("get_buffers")?;
+    let (data, data_dtype): (Bound<'_, PyAny>, Bound<'_, PyAny>) =
+        buffers.get_item("data")?.extract()?;
+    let data = Buffer::new(data, name)?;
+    let data_dtype = Dtype::of(&data_dtype)?;
+    if !matches!(data_dtype.endianness.as_str(), "=" | "|" | "<") {
+        return Err(PyValueError::new_err(format!(
+            "column {name:?}: big-endian buffers are not supported"
+        )));
+    }
+
+    let values = match dtype.kind {
+        INT | UINT | FLOAT | CATEGORICAL => numbers(&data, &data_dtype, offset, size, name)
+            .unwrap_or_else(|| Err(unsupported(name, &dtype)))?,
+        STRING => {
+            let (offsets, offsets_dtype): (Bound<'_, PyAny>, Bound<'_, PyAny>) =
+                buffers.get_item("offsets")?.extract()?;
+            let offsets = Buffer::new(offsets, name)?;
+            let bounds: Vec<usize> = match Dtype::of(&offsets_dtype)?.bits {
+                32 => offsets
+                    .read::<i32>(offset, size + 1, name)?
+                    .into_iter()
+                    .map(|o| o as usize)
+                    .collect(),
+                _ => offsets
+                    .read::<i64>(offset, size + 1, name)?
+                    .into_iter()
+                    .map(|o| o as usize)
+                    .collect(),
+            };
+            let text = bounds
+                .windows(2)
+                .map(|w| {
+                    let bytes = data.read::<u8>(w[0], w[1].saturating_sub(w[0]), name)?;
+                    String::from_utf8(bytes).map_err(|_| {
+                        PyValueError::new_err(format!("column {name:?} holds invalid UTF-8"))
+                    })
+                })
+                .collect::<PyResult<_>>()?;
+            Values::Text(text)
+        }
+        _ => return Err(unsupported(name, &dtype)),
+    };
+
+    let nulls = null_rows(col, &buffers, &values, offset, size, name)?;
+    let values = match dtype.kind {
+        CATEGORICAL => categories(col, values, &nulls, name)?,
+        _ => values,
+    };
+    Ok(Column {
+        values,
+        nulls,
+        dtype: dtype.describe(),
+    })
+}
+
+/// Numbers of a data buffer, widened to 64 bits; `None` for an unsupported width.
+fn numbers(
+    data: &Buffer<'_>,
+    dtype: &Dtype,
+    offset: usize,
+    size: usize,
+    name: &str,
+) -> Option<PyResult<Values>> {
+    macro_rules! widened {
+        ($t:ty, $variant:ident, $wide:ty) => {
+            data.read::<$t>(offset, size, name)
+                .map(|v| Values::$variant(v.into_iter().map(|v| v as $wide).collect()))
+        };
+    }
+    Some(match (dtype.kind, dtype.bits) {
+        (INT, 8) => widened!(i8, Signed, i64),
+        (INT, 16) => widened!(i16, Signed, i64),
+        (INT, 32) => widened!(i32, Signed, i64),
+        (INT, 64) => widened!(i64, Signed, i64),
+        (UINT, 8) => widened!(u8, Unsigned, u64),
+        (UINT, 16) => widened!(u16, Unsigned, u64),
+        (UINT, 32) => widened!(u32, Unsigned, u64),
+        (UINT, 64) => widened!(u64, Unsigned, u64),
+        (FLOAT, 32) => widened!(f32, Float, f64),
+        (FLOAT, 64) => widened!(f64, Float, f64),
+        _ => return None,
+    })
+}
+
+/// Rows of a chunk holding a null, read from the representation its `describe_null`
+/// announces.
+fn null_rows(
+    col: &Bound<'_, PyAny>,
+    buffers: &Bound<'_, PyAny>,
+    values: &Values,
+    offset: usize,
+    size: usize,
+    name: &str,
+) -> PyResult<Vec<usize>> {
+    let null_count: Option<usize> = col.getattr("null_count")?.extract().unwrap_or(None);
+    if null_count == Some(0) {
+        return Ok(Vec::new());
+    }
+    let (kind, value): (i64, Bound<'_, PyAny>) = col.getattr("describe_null")?.extract()?;
+    let flags: Vec<bool> = match kind {
+        USE_SENTINEL => match values {
+            Values::Signed(v) => {
+                let sentinel: i64 = value.extract()?;
+                v.iter().map(|&v| v == sentinel).collect()
+            }
+            Values::Unsigned(v) => {
+                let sentinel: u64 = value.extract()?;
+                v.iter().map(|&v| v == sentinel).collect()
+            }
+            Values::Float(v) => {
+                let sentinel: f64 = value.extract()?;
+                v.iter().map(|&v| v == sentinel).collect()
+            }
+            Values::Text(v) => {
+                let sentinel: String = value.extract()?;
+                v.iter().map(|v| *v == sentinel).collect()
+            }
+        },
+        USE_BITMASK | USE_BYTEMASK => {
+            let validity: Option<(Bound<'_, PyAny>, Bound<'_, PyAny>)> =
+                buffers.get_item("validity")?.extract()?;
+            let Some((mask, mask_dtype)) = validity else {
+                return Ok(Vec::new());
+            };
+            let mask = Buffer::new(mask, name)?;
+            let null_bit = value.extract::<i64>()? != 0;
+            let set = match Dtype::of(&mask_dtype)?.bits {
+                1 => mask.bits(offset, size, name)?,
+                _ => mask
+                    .read::<u8>(offset, size, name)?
+                    .into_iter()
+                    .map(|b| b != 0)
+                    .collect(),
+            };
+            set.into_iter().map(|bit| bit == null_bit).collect()
+        }
+        // Not nullable, or nulls stored as NaN (kept as such).
+        _ => return Ok(Vec::new()),
+    };
+    Ok(flags
+        .into_iter()
+        .enumerate()
+        .filter_map(|(row, null)| null.then_some(row))
+        .collect())
+}
+
+/// Values of a categorical chunk from its codes; null rows get the first category.
+fn categories(
+    col: &Bound<'_, PyAny>,
+    codes: Values,
+    nulls: &[usize],
+    name: &str,
+) -> PyResult<Values> {
+    let described = col.getattr("describe_categorical")?;
+    let categories = read_chunk(&described.get_item("categories")?, name)?.values;
+    let codes: Vec<i64> = match codes {
+        Values::Signed(c) => c,
+        Values::Unsigned(c) => c.into_iter().map(|c| c as i64).collect(),
+        _ => unreachable!("categorical codes are integers"),
+    };
+    let count = categories.len();
+    let index = |row: usize| -> PyResult<usize> {
+        if nulls.binary_search(&row).is_ok() {
+            return Ok(0);
+        }
+        usize::try_from(codes[row])
+            .ok()
+            .filter(|&code| code < count)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "column {name:?}: category code {} of row {row} is out of range",
+                    codes[row]
+                ))
+            })
+    };
+    macro_rules! decoded {
+        ($variant:ident, $cats:expr) => {
+            (0..codes.len())
+                .map(|row| Ok($cats.get(index(row)?).cloned().unwrap_or_default()))
+                .collect::<PyResult<_>>()
+                .map(Values::$variant)
+        };
+    }
+    match &categories {
+        Values::Signed(c) => decoded!(Signed, c),
+        Values::Unsigned(c) => decoded!(Unsigned, c),
+        Values::Float(c) => decoded!(Float, c),
+        Values::Text(c) => decoded!(Text, c),
+    }
+}
+
+/// `ValueError` for a column of a dtype the loaders cannot read.
+fn unsupported(name: &str, dtype: &Dtype) -> PyErr {
+    PyValueError::new_err(format!(
+        "column {name:?} has unsupported dtype {}",
+        dtype.describe()
+    ))
+}
@@ -0,0 +1,909 @@
+//! Binding-side driver for the Gauss IOD search loop.
+//!
+//! This module mirrors `ObservationIOD::estimate_best_orbit` from the Rust core,
+//! stage by stage, using only the public building blocks exposed by Outfit
+//! (triplet index generator, `GaussObs`, Monte-Carlo realizations, RMS scoring).
+//! Owning the loop on the binding side lets Python-facing features hook into
+//! the candidate ranking without forking the core crate. Runs that enable none of these
+//! features (see `needs_driver`) call the core unchanged.
+//!
+//! Pipeline
+//! -----------------
+//! 1. Batch RMS correction of the astrometric uncertainties.
+//! 2. Enumeration of time-feasible triplets and ranking (built-in weight or
+//!    user-provided `triplet_scorer`), keeping the best `max_triplets` in a bounded
+//!    heap as in the core.
+//! 3. Monte-Carlo realizations of each selected triplet.
+//! 4. Preliminary Gauss solution and RMS scoring over the selected arc.
+//! 5. Selection of the lowest-RMS candidate.
+//!
+//! Tie-breaking
+//! -----------------
+//! Candidates are explored in ranking order: ascending score, then earliest middle
+//! epoch, then lowest index tuple `(i, j, k)`. A candidate replaces the incumbent only
+//! with a strictly lower RMS, so among numerically identical RMS the first triplet in
+//! that order (and its first realization) wins. The sequential and parallel batch paths
+//! select the same solution for a trajectory, whatever the scheduling: both run this
+//! driver, or both run the core search when the driver is not needed.
+//!
+//! See also
+//! ------------
+//! * [`outfit::ObservationIOD::estimate_best_orbit`] – Reference implementation in the core.
+//! * [`crate::iod_params::IODParams`] – Python configuration carrying the optional hooks.
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+use nalgebra::{Matrix3, Vector3};
+use outfit::{
+    initial_orbit_determination::gauss::GaussObs,
+    observations::{
+        observations_ext::ObservationsExt, triplets_generator::TripletIndexGenerator,
+        triplets_iod::triplet_weight_with_inv, Observation,
+    },
+    GaussResult, ObjectNumber, ObservationIOD, Observations, Outfit, OutfitError,
+};
+use pyo3::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::{
+    costs::IodCosts,
+    differential_correction,
+    ephemeris::CoverageGap,
+    ingest,
+    iod_params::{IODParams, Subsample},
+    propagation,
+    rejections::{Reason, RejectionTally},
+    subsample, PyOutfit,
+};
+
+/// Failure of a single-trajectory IOD run driven by this module.
+///
+/// Variants
+/// -----------------
+/// * `Outfit` – Error raised by the Rust core (no feasible triplet, no viable orbit, …).
+/// * `TripletScorer` – The user `triplet_scorer` raised or returned a non-float value.
+/// * `Coverage` – Observation epochs too close to an ephemeris segment boundary.
+/// * `UnsortedEpochs` – The trajectory is not in time order (`validate=True`), see
+///   [`check_time_order`].
+#[derive(Debug)]
+pub(crate) enum IodError {
+    Outfit(OutfitError),
+    TripletScorer(String),
+    Coverage(CoverageGap),
+    UnsortedEpochs(String),
+}
+
+impl std::fmt::Display for IodError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IodError::Outfit(e) => write!(f, "{e}"),
+            IodError::TripletScorer(msg) => write!(f, "TripletScorerError: {msg}"),
+            IodError::Coverage(gap) => write!(f, "EphemerisCoverageError: {}", gap.message()),
+            IodError::UnsortedEpochs(msg) => write!(f, "UnsortedEpochsError: {msg}"),
+        }
+    }
+}
+
+impl From<OutfitError> for IodError {
+    fn from(e: OutfitError) -> Self {
+        IodError::Outfit(e)
+    }
+}
+
+impl IodError {
+    /// Short name of the error (`ResultRow.error_kind`): the Python exception class for
+    /// the errors raised by the bindings, the name of the core error variant otherwise
+    /// (e.g. `"SingularDirectionMatrix"`).
+    pub(crate) fn kind(&self) -> String {
+        match self {
+            IodError::Outfit(e) => format!("{e:?}")
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            IodError::TripletScorer(_) => "TripletScorerError".to_string(),
+            IodError::Coverage(_) => "EphemerisCoverageError".to_string(),
+            IodError::UnsortedEpochs(_) => "UnsortedEpochsError".to_string(),
+        }
+    }
+
+    /// Convert into the matching Python exception.
+    pub(crate) fn into_pyerr(self) -> PyErr {
+        match self {
+            IodError::Outfit(e) => pyo3::exceptions::PyRuntimeError::new_err(e.to_string()),
+            IodError::TripletScorer(msg) => crate::errors::TripletScorerError::new_err(msg),
+            IodError::Coverage(gap) => Python::attach(|py| gap.into_pyerr(py)),
+            IodError::UnsortedEpochs(msg) => crate::errors::UnsortedEpochsError::new_err(msg),
+        }
+    }
+}
+
+/// Check that `obs` is in time order before it is fitted.
+///
+/// Return
+/// ----------
+/// * `Ok(())`, or [`IodError::UnsortedEpochs`] naming the first observation earlier
+///   than the one before it.
+pub(crate) fn check_time_order(obs: &[Observation]) -> Result<(), IodError> {
+    match ingest::first_unsorted(obs) {
+        None => Ok(()),
+        Some(i) => Err(IodError::UnsortedEpochs(format!(
+            "observation {i} (MJD {}) precedes observation {} (MJD {})",
+            obs[i].time,
+            i - 1,
+            obs[i - 1].time
+        ))),
+    }
+}
+
+/// Per-trajectory outcome of the batch driver.
+pub(crate) type IodOutcome = Result<(GaussResult, f64), IodError>;
+
+/// Optional counters of the search of one trajectory.
+///
+/// Fields
+/// -----------------
+/// * `costs` – Work spent, with `IODParams.collect_costs`.
+/// * `rejections` – Why candidates were not retained, with
+///   `IODParams.collect_rejection_stats`.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Diagnostics {
+    pub(crate) costs: Option<IodCosts>,
+    pub(crate) rejections: Option<RejectionTally>,
+}
+
+/// Time-feasible triplet with its ranking score (lower is better).
+///
+/// Indices refer to positions in the time-sorted `Observations`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RankedTriplet {
+    pub(crate) score: f64,
+    pub(crate) idx: [usize; 3],
+}
+
+/// Entry of the bounded heap of [`rank_triplets`], ordered by score, then
+/// middle epoch, then index tuple, so that the heap top is the worst kept triplet.
+struct HeapEntry {
+    mid_time: f64,
+    triplet: RankedTriplet,
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.triplet
+            .score
+            .total_cmp(&other.triplet.score)
+            .then_with(|| self.mid_time.total_cmp(&other.mid_time))
+            .then_with(|| self.triplet.idx.cmp(&other.triplet.idx))
+    }
+}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapEntry {}
+
+/// Enumerate feasible triplets and keep the `max_triplets` best-ranked ones.
+///
+/// Arguments
+/// -----------------
+/// * `obs` – Observations of one trajectory (sorted in place by epoch).
+/// * `params` – IOD configuration (time constraints, subsampling and ranking hook).
+///
+/// Return
+/// ----------
+/// * The selected triplets sorted by ascending score, or the scorer failure. Equal
+///   scores are ordered by middle epoch, then by index tuple.
+///
+/// Notes
+/// ----------
+/// * When `params.triplet_scorer` is set, the callback is invoked with the GIL
+///   held, once per feasible triplet, as the enumeration proceeds. Non-finite scores
+///   discard the triplet, like the built-in weight.
+/// * Only the `max_triplets` best triplets are held, in a bounded heap: memory does not
+///   grow with the number of feasible triplets.
+pub(crate) fn rank_triplets(
+    obs: &mut Observations,
+    params: &IODParams,
+) -> Result<Vec<RankedTriplet>, IodError> {
+    let p = &params.inner;
+    if p.max_triplets == 0 {
+        return Ok(Vec::new());
+    }
+
+    let (index_gen, mapping) = match params.subsample {
+        Subsample::Leverage if obs.len() > p.max_obs_for_triplets => {
+            // Enumerate over the kept observations, then map back to the full arc.
+            let kept = subsample::leverage_indices(obs, p.max_obs_for_triplets);
+            let mut reduced: Observations = kept.iter().map(|&i| obs[i]).collect();
+            let gen = TripletIndexGenerator::from_observations(
+                &mut reduced,
+                p.dt_min,
+                p.dt_max_triplet,
+                usize::MAX,
+                usize::MAX,
+            );
+            let mapping = gen
+                .selected_original_indices()
+                .iter()
+                .map(|&r| kept[r])
+                .collect();
+            (gen, mapping)
+        }
+        _ => {
+            let gen = TripletIndexGenerator::from_observations(
+                obs,
+                p.dt_min,
+                p.dt_max_triplet,
+                p.max_obs_for_triplets,
+                usize::MAX,
+            );
+            let mapping = gen.selected_original_indices().to_vec();
+            (gen, mapping)
+        }
+    };
+    let times = index_gen.reduced_times().to_vec();
+    let feasible = index_gen.map(|(i, j, k)| ((i, j, k), [mapping[i], mapping[j], mapping[k]]));
+
+    // Bounded push: the heap keeps the `max_triplets` best triplets, worst on top.
+    let k_cap = p.max_triplets as usize;
+    let mut heap: BinaryHeap<HeapEntry> = BinaryHeap::new();
+    let mut push = |triplet: RankedTriplet| {
+        if !triplet.score.is_finite() {
+            return;
+        }
+        let entry = HeapEntry {
+            mid_time: obs[triplet.idx[1]].time,
+            triplet,
+        };
+        if heap.len() < k_cap {
+            heap.push(entry);
+        } else if heap.peek().is_some_and(|worst| entry < *worst) {
+            heap.pop();
+            heap.push(entry);
+        }
+    };
+
+    match params.triplet_scorer.as_ref() {
+        None => {
+            let inv_dtw = p.optimal_interval_time.recip();
+            for ((i, j, k), idx) in feasible {
+                push(RankedTriplet {
+                    score: triplet_weight_with_inv(times[i], times[j], times[k], inv_dtw),
+                    idx,
+                });
+            }
+        }
+        Some(scorer) => Python::attach(|py| {
+            for ((i, j, k), idx) in feasible {
+                let epochs = (times[i], times[j], times[k]);
+                let score = scorer
+                    .call1(py, (epochs, (idx[0], idx[1], idx[2])))
+                    .and_then(|v| v.extract::<f64>(py))
+                    .map_err(|e| IodError::TripletScorer(e.to_string()))?;
+                push(RankedTriplet { score, idx });
+            }
+            Ok::<(), IodError>(())
+        })?,
+    }
+
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|entry| entry.triplet)
+        .collect())
+}
+
+/// Materialize a `GaussObs` for a triplet of (sorted) observation indices.
+pub(crate) fn gauss_obs_for(obs: &Observations, idx: [usize; 3]) -> GaussObs {
+    let (o1, o2, o3) = (&obs[idx[0]], &obs[idx[1]], &obs[idx[2]]);
+    let observer_matrix = Matrix3::from_columns(&[
+        o1.get_observer_helio_position(),
+        o2.get_observer_helio_position(),
+        o3.get_observer_helio_position(),
+    ]);
+    GaussObs::with_observer_position(
+        Vector3::new(idx[0], idx[1], idx[2]),
+        Vector3::new(o1.ra, o2.ra, o3.ra),
+        Vector3::new(o1.dec, o2.dec, o3.dec),
+        Vector3::new(o1.time, o2.time, o3.time),
+        observer_matrix,
+    )
+}
+
+/// Whether a run needs this driver rather than the core search: a `triplet_scorer`,
+/// cost or rejection counters, or the `"leverage"` subsampling.
+pub(crate) fn needs_driver(params: &IODParams) -> bool {
+    params.triplet_scorer.is_some()
+        || params.collect_costs
+        || params.collect_rejection_stats
+        || params.subsample != Subsample::Uniform
+}
+
+/// Estimate the best orbit of a single trajectory.
+///
+/// Arguments
+/// -----------------
+/// * `obs` – Observations of the trajectory (sorted and error-corrected in place).
+/// * `env` – Global Outfit environment, locked only while the search uses it.
+/// * `rng` – Random source for the Monte-Carlo realizations.
+/// * `params` – Python-side IOD configuration (core parameters + hooks).
+///
+/// Return
+/// ----------
+/// * `(GaussResult, rms)` for the lowest-RMS candidate, or an [`IodError`]. Ties are
+///   broken as described in the module documentation.
+///
+/// Notes
+/// ----------
+/// * Runs `ObservationIOD::estimate_best_orbit` of the core when the driver is not
+///   needed (see [`needs_driver`]).
+/// * Call it without the GIL and without holding the engine: the `triplet_scorer`
+///   attaches to Python, with the engine released.
+///
+/// See also
+/// ------------
+/// * [`rank_triplets`] – Candidate enumeration and ranking.
+/// * [`estimate_best_orbit_costed`] – Same search, with cost and rejection counters.
+pub(crate) fn estimate_best_orbit(
+    obs: &mut Observations,
+    env: &PyOutfit,
+    rng: &mut impl Rng,
+    params: &IODParams,
+) -> IodOutcome {
+    if !needs_driver(params) {
+        let state = env.outfit_detached();
+        return Ok(obs.estimate_best_orbit(&state, &state.error_model, rng, &params.inner)?);
+    }
+    search_best_orbit(obs, env, rng, params, None, None)
+}
+
+/// Estimate the best orbit of a single trajectory, recording its cost when
+/// `params.collect_costs` is set and its rejections when
+/// `params.collect_rejection_stats` is set.
+///
+/// Return
+/// ----------
+/// * The outcome of [`estimate_best_orbit`] and the enabled [`Diagnostics`] of the
+///   search (also for failed trajectories). With both options disabled the search runs
+///   exactly as [`estimate_best_orbit`] and no counter is returned.
+pub(crate) fn estimate_best_orbit_costed(
+    obs: &mut Observations,
+    env: &PyOutfit,
+    rng: &mut impl Rng,
+    params: &IODParams,
+) -> (IodOutcome, Diagnostics) {
+    if !needs_driver(params) {
+        return (
+            estimate_best_orbit(obs, env, rng, params),
+            Diagnostics::default(),
+        );
+    }
+    let mut rejections = params.collect_rejection_stats.then(RejectionTally::default);
+    if !params.collect_costs {
+        let res = search_best_orbit(obs, env, rng, params, None, rejections.as_mut());
+        return (
+            res,
+            Diagnostics {
+                costs: None,
+                rejections,
+            },
+        );
+    }
+    let mut costs = IodCosts::default();
+    let start = Instant::now();
+    let res = search_best_orbit(obs, env, rng, params, Some(&mut costs), rejections.as_mut());
+    costs.wall_ms = start.elapsed().as_secs_f64() * 1e3;
+    (
+        res,
+        Diagnostics {
+            costs: Some(costs),
+            rejections,
+        },
+    )
+}
+
+/// Search loop shared by [`estimate_best_orbit`] and [`estimate_best_orbit_costed`].
+fn search_best_orbit(
+    obs: &mut Observations,
+    env: &PyOutfit,
+    rng: &mut impl Rng,
+    params: &IODParams,
+    mut costs: Option<&mut IodCosts>,
+    mut rejections: Option<&mut RejectionTally>,
+) -> IodOutcome {
+    let p = &params.inner;
+
+    // Stage 1: calibrate uncertainties for the whole batch.
+    obs.apply_batch_rms_correction(&env.outfit_detached().error_model, p.gap_max);
+
+    // Stage 2: enumerate and rank candidate triplets (the scorer attaches to Python, so
+    // the engine is not held).
+    let triplets = rank_triplets(obs, params)?;
+    if triplets.is_empty() {
+        if let Some(r) = rejections {
+            r.no_feasible_triplets = true;
+        }
+        let span = match (obs.first(), obs.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        };
+        return Err(OutfitError::NoFeasibleTriplets {
+            span,
+            n_obs: obs.len(),
+            dt_min: p.dt_min,
+            dt_max: p.dt_max_triplet,
+        }
+        .into());
+    }
+
+    let mut best_rms = f64::INFINITY;
+    let mut best_orbit: Option<GaussResult> = None;
+    let mut last_error: Option<OutfitError> = None;
+    let mut n_attempts: usize = 0;
+    let mut n_candidates: usize = 0;
+
+    // Stages 3-5: realizations, Gauss solution, RMS scoring.
+    let state = env.outfit_detached();
+    let mut aborted: Option<OutfitError> = None;
+    'search: for triplet in triplets {
+        if let Some(c) = costs.as_deref_mut() {
+            c.triplets_attempted += 1;
+        }
+        let gauss_obs = gauss_obs_for(obs, triplet.idx);
+        let (error_ra, error_dec) = obs.extract_errors(Vector3::from(triplet.idx));
+
+        for realization in gauss_obs.realizations_iter(
+            &error_ra,
+            &error_dec,
+            p.n_noise_realizations,
+            p.noise_scale,
+            rng,
+        ) {
+            n_attempts += 1;
+
+            let gauss_res = match realization.prelim_orbit(&state, p) {
+                Ok(res) => res,
+                Err(e) => {
+                    if let Some(r) = rejections.as_deref_mut() {
+                        r.reject(Reason::of(&e, Reason::RootFinding));
+                    }
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            if let Some(c) = costs.as_deref_mut() {
+                c.corrected_solutions += usize::from(gauss_res.is_corrected());
+            }
+
+            let equinoctial_elements = match gauss_res.get_orbit().to_equinoctial() {
+                Ok(elements) => elements,
+                Err(e) => {
+                    if let Some(r) = rejections.as_deref_mut() {
+                        r.reject(Reason::Physical);
+                    }
+                    aborted = Some(e);
+                    break 'search;
+                }
+            };
+
+            let rms = match obs.rms_orbit_error(
+                &state,
+                &realization,
+                &equinoctial_elements,
+                p.extf,
+                p.dtmax,
+                Some(best_rms),
+            ) {
+                Ok(v) if v.is_finite() => v,
+                Ok(v) => {
+                    if let Some(r) = rejections.as_deref_mut() {
+                        r.reject(Reason::RmsCut);
+                    }
+                    last_error = Some(OutfitError::NonFiniteScore(v));
+                    continue;
+                }
+                Err(e) => {
+                    if let Some(r) = rejections.as_deref_mut() {
+                        r.reject(Reason::of(&e, Reason::RmsCut));
+                    }
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            n_candidates += 1;
+
+            // Strict comparison: the earliest candidate in ranking order keeps a tie.
+            if rms < best_rms {
+                best_rms = rms;
+                best_orbit = Some(gauss_res);
+            }
+        }
+    }
+
+    if let Some(c) = costs {
+        c.realizations += n_attempts;
+    }
+    if let Some(e) = aborted {
+        if let Some(r) = rejections {
+            r.rejected[Reason::RmsCut as usize] += n_candidates;
+        }
+        return Err(e.into());
+    }
+    if let Some(r) = rejections {
+        // Scored candidates other than the retained one lost on RMS.
+        r.selected = usize::from(best_orbit.is_some());
+        r.rejected[Reason::RmsCut as usize] += n_candidates - r.selected;
+    }
+
+    match (best_orbit, last_error) {
+        (Some(orbit), _) => Ok((orbit, best_rms)),
+        (None, Some(cause)) => Err(OutfitError::NoViableOrbit {
+            cause: Box::new(cause),
+            attempts: n_attempts,
+        }
+        .into()),
+        (None, None) => Err(OutfitError::GaussNoRootsFound.into()),
+    }
+}
+
+/// Sequentially estimate orbits for every trajectory of a set.
+///
+/// Arguments
+/// -----------------
+/// * `set` – Trajectories to process (observations are mutated in place).
+/// * `env` – Global Outfit environment, locked one trajectory at a time.
+/// * `rng` – Shared random source, consumed in iteration order.
+/// * `params` – Python-side IOD configuration.
+/// * `should_cancel` – Polled every 20 ms, between trajectories and with the engine
+///   released; returning `true` stops the loop early.
+///
+/// Return
+/// ----------
+/// * One outcome per processed trajectory, with the [`Diagnostics`] enabled by
+///   `params`. Failures are isolated per object.
+///
+/// Notes
+/// ----------
+/// * Same loop as `TrajectoryFit::estimate_all_orbits_with_cancel` of the core, which
+///   would hold the engine while `should_cancel` attaches to Python.
+pub(crate) fn estimate_all_orbits_sequential<F>(
+    set: &mut outfit::TrajectorySet,
+    env: &PyOutfit,
+    rng: &mut impl Rng,
+    params: &IODParams,
+    mut should_cancel: F,
+) -> Vec<(ObjectNumber, IodOutcome, Diagnostics)>
+where
+    F: FnMut() -> bool,
+{
+    let interval = Duration::from_millis(20);
+    let mut last_poll = Instant::now();
+    let mut results = Vec::with_capacity(set.len());
+
+    for (obj, observations) in set.iter_mut() {
+        if last_poll.elapsed() >= interval {
+            if should_cancel() {
+                break;
+            }
+            last_poll = Instant::now();
+        }
+        let (res, diagnostics) = estimate_best_orbit_costed(observations, env, rng, params);
+        results.push((obj.clone(), res, diagnostics));
+    }
+    results
+}
+
+/// SplitMix64 finalizer (Steele et al., 2014), as used by the core to derive seeds.
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// Per-object seed derived from the base seed of a parallel run.
+///
+/// Same derivation as the core's parallel batch (fixed-key `ahash` of the object key,
+/// mixed through SplitMix64), so that seeded runs reproduce its Monte-Carlo draws.
+fn seed_for_object(base: u64, obj: &ObjectNumber) -> u64 {
+    let mut h = ahash::AHasher::default();
+    obj.hash(&mut h);
+    splitmix64(base ^ h.finish())
+}
+
+/// Estimate orbits for every trajectory of a set, in parallel.
+///
+/// Trajectories are split into batches of `params.batch_size`, processed in parallel
+/// (each batch sequentially), every one with its own RNG seeded from a single base
+/// seed drawn from `rng` and the object key. The outcome of a trajectory therefore
+/// depends neither on the scheduling nor on the iteration order of the set.
+///
+/// Arguments
+/// -----------------
+/// * `set` – Trajectories to process (observations are mutated in place).
+/// * `env` – Global Outfit environment.
+/// * `rng` – Source of the base seed (one draw).
+/// * `params` – Python-side IOD configuration.
+///
+/// Return
+/// ----------
+/// * One outcome per trajectory, with its rejections when
+///   `params.collect_rejection_stats` is set (costs are not collected on this path).
+///   Failures are isolated per object.
+///
+/// Notes
+/// ----------
+/// * Mirrors `TrajectoryFit::estimate_all_orbits_in_batches_parallel` of the core,
+///   with the candidate search (and tie-breaking) of [`estimate_best_orbit`]: the core
+///   search itself when the driver is not needed.
+/// * The sequential path draws from `rng` in iteration order instead: with noisy
+///   realizations both paths explore different candidates.
+pub(crate) fn estimate_all_orbits_parallel(
+    set: &mut outfit::TrajectorySet,
+    env: &PyOutfit,
+    rng: &mut impl Rng,
+    params: &IODParams,
+) -> Vec<(ObjectNumber, IodOutcome, Diagnostics)> {
+    let base_seed: u64 = rng.random();
+    let entries: Vec<(ObjectNumber, Observations)> = set.drain().collect();
+    let n_entries = entries.len();
+    let batch_size = params.inner.batch_size.max(1);
+
+    let batches: Vec<Vec<(ObjectNumber, IodOutcome, Diagnostics, Observations)>> = entries
+        .into_par_iter()
+        .chunks(batch_size)
+        .map(|batch| {
+            batch
+                .into_iter()
+                .map(|(obj, mut obs)| {
+                    let mut local = StdRng::seed_from_u64(seed_for_object(base_seed, &obj));
+                    let (res, diagnostics) =
+                        estimate_best_orbit_costed(&mut obs, env, &mut local, params);
+                    (obj, res, diagnostics, obs)
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(n_entries);
+    for (obj, res, diagnostics, obs) in batches.into_iter().flatten() {
+        set.insert(obj.clone(), obs);
+        results.push((obj, res, diagnostics));
+    }
+    results
+}
+
+/// Seed of fallback attempt `attempt` (1-based) for one object of a batch run.
+///
+/// Depends only on `base`, the attempt and the object key, so that a retry does not
+/// depend on the other trajectories, on the primary pass or on the scheduling.
+pub(crate) fn seed_for_attempt(base: u64, obj: &ObjectNumber, attempt: usize) -> u64 {
+    seed_for_object(splitmix64(base.wrapping_add(attempt as u64)), obj)
+}
+
+/// Retry the failed trajectories of a batch run with fallback configurations.
+///
+/// Each failed trajectory is fitted again from its original observations with
+/// `fallbacks[0]`, then `fallbacks[1]`, ... until one succeeds. Attempt `k` runs with
+/// its own RNG seeded by [`seed_for_attempt`]`(base_seed, obj, k)`, in parallel across
+/// trajectories only when `fallbacks[k - 1].do_parallel()`: a fallback with a
+/// `triplet_scorer` (sequential only) never runs on rayon workers, whatever the mode of
+/// the primary pass.
+///
+/// Arguments
+/// -----------------
+/// * `set` – Arcs as fitted by the primary pass; the arc of a successful retry
+///   replaces the primary one.
+/// * `originals` – Observations before the primary pass (before batch correction).
+/// * `results` – Outcomes of the primary pass.
+/// * `env` – Global Outfit environment.
+/// * `base_seed` – Base of the per-attempt seeds.
+/// * `fallbacks` – Relaxed configurations, tried in order.
+///
+/// Return
+/// ----------
+/// * The outcomes with the attempt that produced them: `0` for the primary pass, `k`
+///   for `fallbacks[k - 1]`. Trajectories failing every attempt keep the error of the
+///   last one.
+pub(crate) fn retry_with_fallbacks(
+    set: &mut outfit::TrajectorySet,
+    originals: &outfit::TrajectorySet,
+    results: Vec<(ObjectNumber, IodOutcome, Diagnostics)>,
+    env: &PyOutfit,
+    base_seed: u64,
+    fallbacks: &[&IODParams],
+) -> Vec<(ObjectNumber, IodOutcome, Diagnostics, usize)> {
+    type Entry = (ObjectNumber, IodOutcome, Diagnostics, usize);
+    let mut entries: Vec<Entry> = results
+        .into_iter()
+        .map(|(obj, res, diagnostics)| (obj, res, diagnostics, 0))
+        .collect();
+
+    for (k, params) in fallbacks.iter().enumerate() {
+        let attempt = k + 1;
+        // Refit a trajectory still failing; its new arc when the attempt succeeds.
+        let retry = |entry: &mut Entry| -> Option<Observations> {
+            let original = originals.get(&entry.0).filter(|_| entry.1.is_err())?;
+            let mut arc = original.clone();
+            let mut rng = StdRng::seed_from_u64(seed_for_attempt(base_seed, &entry.0, attempt));
+            let (res, diagnostics) = estimate_best_orbit_costed(&mut arc, env, &mut rng, params);
+            let succeeded = res.is_ok();
+            (entry.1, entry.2, entry.3) = (res, diagnostics, attempt);
+            succeeded.then_some(arc)
+        };
+        let arcs: Vec<Option<Observations>> = if params.do_parallel() {
+            entries.par_iter_mut().map(retry).collect()
+        } else {
+            entries.iter_mut().map(retry).collect()
+        };
+        for (entry, arc) in entries.iter().zip(arcs) {
+            if let Some(arc) = arc {
+                set.insert(entry.0.clone(), arc);
+            }
+        }
+    }
+    entries
+}
+
+/// Which strategy produced an entry of an incremental update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UpdatePath {
+    /// Existing orbit refined over the combined arc.
+    Incremental,
+    /// Refinement diverged (or failed); a fresh Gauss IOD was run on the combined arc.
+    Full,
+    /// Object absent from the previous results; fresh Gauss IOD.
+    New,
+    /// Object without new observations; previous entry carried over.
+    Unchanged,
+}
+
+impl UpdatePath {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            UpdatePath::Incremental => "incremental",
+            UpdatePath::Full => "full",
+            UpdatePath::New => "new",
+            UpdatePath::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// Which strategy produced an orbit of a batch run with initial guesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IodPath {
+    /// No guess for the trajectory; Gauss IOD.
+    Gauss,
+    /// Guess corrected over the whole arc, triplet search bypassed.
+    Guess,
+    /// Correction of the guess diverged; Gauss IOD.
+    Fallback,
+}
+
+impl IodPath {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            IodPath::Gauss => "gauss",
+            IodPath::Guess => "guess",
+            IodPath::Fallback => "fallback",
+        }
+    }
+}
+
+/// Fit a user-provided orbit to the whole arc of a trajectory.
+///
+/// The uncertainties are calibrated as in stage 1 of the Gauss search, the guess is
+/// propagated to the middle of the arc and corrected there with
+/// [`differential_correction::correct`].
+///
+/// Arguments
+/// -----------------
+/// * `obs` – Observations of the trajectory (error-corrected in place).
+/// * `state` – Global Outfit environment.
+/// * `params` – Python-side IOD configuration (`gap_max` and the physical bounds).
+/// * `guess` – Starting orbit, epoch on the TT axis.
+///
+/// Return
+/// ----------
+/// * The corrected orbit (keplerian elements) and its RMS over the arc, or `None` when
+///   the correction diverged: see [`differential_correction::correct`]; orbits beyond
+///   `max_ecc` / `max_perihelion_au` count as diverged too.
+pub(crate) fn correct_from_guess(
+    obs: &mut Observations,
+    state: &Outfit,
+    params: &IODParams,
+    guess: &GaussResult,
+) -> Option<(GaussResult, f64)> {
+    let p = &params.inner;
+    obs.apply_batch_rms_correction(&state.error_model, p.gap_max);
+
+    let (first, last) = obs
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), o| {
+            (lo.min(o.time), hi.max(o.time))
+        });
+    let start = propagation::propagate_elements(guess.get_orbit(), 0.5 * (first + last))
+        .and_then(|e| e.to_equinoctial())
+        .ok()?;
+    let (elements, rms) = differential_correction::correct(obs, state, &start)?;
+
+    let keplerian: outfit::KeplerianElements = (&elements).into();
+    let perihelion = keplerian.semi_major_axis * (1.0 - keplerian.eccentricity);
+    (keplerian.eccentricity <= p.max_ecc && perihelion <= p.max_perihelion_au).then_some((
+        GaussResult::CorrectedOrbit(outfit::OrbitalElements::Keplerian(keplerian)),
+        rms,
+    ))
+}
+
+/// Refine an existing orbit after new observations were appended to its arc.
+///
+/// The previous orbit seeds a differential correction over the combined arc (see
+/// [`correct_from_guess`]). When the correction diverges, or its RMS exceeds
+/// `divergence_factor × prior_rms`, a full Gauss IOD is run on the combined arc instead.
+///
+/// Arguments
+/// -----------------
+/// * `obs` – Combined arc (previous + new observations), mutated in place.
+/// * `env` – Global Outfit environment, not held during the full IOD.
+/// * `rng` – Random source for the Monte-Carlo realizations of the full IOD.
+/// * `params` – Python-side IOD configuration.
+/// * `previous` – Previous orbit (epoch on the TT axis) and its RMS.
+/// * `divergence_factor` – Tolerated RMS growth before falling back to a full IOD.
+///
+/// Return
+/// ----------
+/// * The updated `(GaussResult, rms)` and the path that produced it.
+///
+/// Notes
+/// ----------
+/// * The RMS is the one reported by the core search (`rms_orbit_error`), evaluated
+///   over the whole combined arc on the incremental path.
+pub(crate) fn refine_orbit(
+    obs: &mut Observations,
+    env: &PyOutfit,
+    rng: &mut impl Rng,
+    params: &IODParams,
+    previous: (&GaussResult, f64),
+    divergence_factor: f64,
+) -> (IodOutcome, UpdatePath) {
+    let p = &params.inner;
+    let (prev_orbit, prior_rms) = previous;
+
+    let mut snapshot = obs.clone();
+    let state = env.outfit_detached();
+    let refined = correct_from_guess(obs, &state, params, prev_orbit).and_then(|(orbit, _)| {
+        // A triplet spanning the arc makes the core evaluate every observation.
+        let n = obs.len();
+        let span = gauss_obs_for(obs, [0, n / 2, n - 1]);
+        let elements = orbit.get_orbit().to_equinoctial().ok()?;
+        let rms = obs
+            .rms_orbit_error(&state, &span, &elements, p.extf, p.dtmax, None)
+            .ok()?;
+        (rms.is_finite() && rms <= divergence_factor * prior_rms).then_some((orbit, rms))
+    });
+    drop(state);
+
+    match refined {
+        Some(res) => (Ok(res), UpdatePath::Incremental),
+        None => (
+            estimate_best_orbit(&mut snapshot, env, rng, params),
+            UpdatePath::Full,
+        ),
+    }
+}
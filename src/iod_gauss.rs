@@ -1,21 +1,58 @@
+use numpy::PyReadonlyArray1;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyType};
+use pyo3::types::{PyDict, PyList, PyType};
 
-use outfit::{GaussResult as RsGaussResult, OrbitalElements as RsOrbitalElements};
+use outfit::observations::Observation;
+use outfit::{
+    GaussResult as RsGaussResult, Observations, OrbitalElements as RsOrbitalElements, OutfitError,
+};
 
+use crate::costs::IodCosts;
+use crate::fit_statistics::FitStatistics;
+use crate::iod_engine::IodPath;
+use crate::iod_params::{IODParams, OutputEpoch};
 use crate::orbit_type::cometary::CometaryElements;
 use crate::orbit_type::equinoctial::EquinoctialElements;
-use crate::orbit_type::keplerian::KeplerianElements;
+use crate::orbit_type::keplerian::{keplerian_rows, KeplerianElements};
+use crate::propagation;
+use crate::subsample;
+use crate::units;
+use crate::IntoPyResult;
 
 /// Python wrapper for GaussResult.
+///
+/// Results produced by the IOD entry points also carry the [`FitStatistics`] of the
+/// orbit over the fitted arc (and its [`IodCosts`] when `collect_costs` is enabled);
+/// results built from element sets do not.
 #[pyclass]
+#[derive(Clone)]
 pub struct GaussResult {
     pub(crate) inner: RsGaussResult,
+    pub(crate) fit: Option<FitStatistics>,
+    pub(crate) costs: Option<IodCosts>,
+    /// Reference epoch (MJD TDB) of the orbit as fitted, before any `output_epoch` propagation.
+    pub(crate) fit_epoch: Option<f64>,
+    /// Positions in the time-sorted arc kept for triplet enumeration, when subsampled.
+    pub(crate) subsample_indices: Option<Vec<usize>>,
+    /// Parameter set that produced the orbit in a batch run with fallbacks
+    /// (0: primary `params`, `k`: `fallback_params[k - 1]`).
+    pub(crate) params_index: Option<usize>,
+    /// Strategy that produced the orbit in a batch run with initial guesses.
+    pub(crate) iod_path: Option<IodPath>,
 }
 
 impl From<RsGaussResult> for GaussResult {
     fn from(w: RsGaussResult) -> Self {
-        Self { inner: w }
+        Self {
+            inner: w,
+            fit: None,
+            costs: None,
+            fit_epoch: None,
+            subsample_indices: None,
+            params_index: None,
+            iod_path: None,
+        }
     }
 }
 impl AsRef<RsGaussResult> for GaussResult {
@@ -24,6 +61,82 @@ impl AsRef<RsGaussResult> for GaussResult {
     }
 }
 
+/// Top-level keys accepted by `GaussResult.from_dict`.
+const DICT_KEYS: [&str; 8] = [
+    "stage",
+    "type",
+    "elements",
+    "fit_statistics",
+    "costs",
+    "fit_epoch",
+    "units",
+    "rms",
+];
+
+/// Value of `key` in `mapping`, `None` when absent, `None` or `NaN` (missing cell).
+fn present<'py>(mapping: &Bound<'py, PyAny>, key: &str) -> Option<Bound<'py, PyAny>> {
+    mapping
+        .get_item(key)
+        .ok()
+        .filter(|v| !v.is_none() && !v.extract::<f64>().is_ok_and(f64::is_nan))
+}
+
+/// String value of a label column.
+fn label(value: &Bound<'_, PyAny>, key: &str) -> PyResult<String> {
+    value
+        .extract()
+        .map_err(|_| PyValueError::new_err(format!("{key:?} must be a string, got {value}")))
+}
+
+/// Wrap elements in the result stage named by `stage`.
+fn staged(stage: &str, elems: RsOrbitalElements) -> PyResult<RsGaussResult> {
+    match stage {
+        "preliminary" => Ok(RsGaussResult::PrelimOrbit(elems)),
+        "corrected" => Ok(RsGaussResult::CorrectedOrbit(elems)),
+        other => Err(PyValueError::new_err(format!(
+            "unknown stage {other:?} (expected 'preliminary' or 'corrected')"
+        ))),
+    }
+}
+
+impl GaussResult {
+    /// Copy of the result with its orbit two-body propagated to `epoch` (MJD TDB).
+    ///
+    /// Fit statistics, costs and `fit_epoch` are carried over unchanged.
+    pub(crate) fn propagated(&self, epoch: f64) -> Result<Self, OutfitError> {
+        Ok(Self {
+            inner: propagation::propagate_orbit(&self.inner, epoch)?,
+            ..self.clone()
+        })
+    }
+
+    /// Quote the orbit at the epoch selected by `IODParams.output_epoch` for `obs`.
+    ///
+    /// The result is returned unchanged when `output_epoch` is `None`.
+    pub(crate) fn at_output_epoch(
+        self,
+        obs: &[Observation],
+        output_epoch: Option<OutputEpoch>,
+    ) -> Result<Self, OutfitError> {
+        match output_epoch.and_then(|e| e.resolve(obs)) {
+            Some(epoch) => self.propagated(epoch),
+            None => Ok(self),
+        }
+    }
+
+    /// Record the observations of `obs` kept for triplet enumeration under `params`.
+    ///
+    /// `subsample_indices` stays `None` when the arc is not longer than
+    /// `max_obs_for_triplets`.
+    pub(crate) fn with_subsample(self, obs: &[Observation], params: &IODParams) -> Self {
+        let mut arc: Observations = obs.iter().copied().collect();
+        Self {
+            subsample_indices: subsample::kept_indices(&mut arc, params),
+            ..self
+        }
+    }
+}
+
 #[pymethods]
 impl GaussResult {
     /// Build a GaussResult from Keplerian elements.
@@ -53,10 +166,22 @@ impl GaussResult {
         if corrected.unwrap_or(false) {
             Self {
                 inner: RsGaussResult::CorrectedOrbit(elems),
+                fit: None,
+                costs: None,
+                fit_epoch: None,
+                subsample_indices: None,
+                params_index: None,
+                iod_path: None,
             }
         } else {
             Self {
                 inner: RsGaussResult::PrelimOrbit(elems),
+                fit: None,
+                costs: None,
+                fit_epoch: None,
+                subsample_indices: None,
+                params_index: None,
+                iod_path: None,
             }
         }
     }
@@ -88,10 +213,22 @@ impl GaussResult {
         if corrected.unwrap_or(false) {
             Self {
                 inner: RsGaussResult::CorrectedOrbit(elems),
+                fit: None,
+                costs: None,
+                fit_epoch: None,
+                subsample_indices: None,
+                params_index: None,
+                iod_path: None,
             }
         } else {
             Self {
                 inner: RsGaussResult::PrelimOrbit(elems),
+                fit: None,
+                costs: None,
+                fit_epoch: None,
+                subsample_indices: None,
+                params_index: None,
+                iod_path: None,
             }
         }
     }
@@ -123,14 +260,73 @@ impl GaussResult {
         if corrected.unwrap_or(false) {
             Self {
                 inner: RsGaussResult::CorrectedOrbit(elems),
+                fit: None,
+                costs: None,
+                fit_epoch: None,
+                subsample_indices: None,
+                params_index: None,
+                iod_path: None,
             }
         } else {
             Self {
                 inner: RsGaussResult::PrelimOrbit(elems),
+                fit: None,
+                costs: None,
+                fit_epoch: None,
+                subsample_indices: None,
+                params_index: None,
+                iod_path: None,
             }
         }
     }
 
+    /// Build many GaussResults from Keplerian element arrays.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `epoch`, `a`, `e`, `i`, `node`, `argp`, `m`: `np.ndarray[dtype=np.float64]` of
+    ///   equal length (MJD TDB, AU, radians).
+    /// * `corrected`: If `True`, builds corrected-stage results; otherwise preliminary (default: `False`).
+    ///
+    /// Return
+    /// ----------
+    /// * A list of `GaussResult`, one per row, built in a single Rust pass.
+    ///
+    /// See also
+    /// ------------
+    /// * [`from_keplerian`] – Single-object variant.
+    /// * `KeplerianElements.from_arrays` – Same input, as bare element sets.
+    #[classmethod]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(
+        signature = (epoch, a, e, i, node, argp, m, corrected=false),
+        text_signature = "(epoch, a, e, i, node, argp, m, corrected=False)"
+    )]
+    fn from_keplerian_arrays<'py>(
+        cls: &Bound<'py, PyType>,
+        epoch: PyReadonlyArray1<'py, f64>,
+        a: PyReadonlyArray1<'py, f64>,
+        e: PyReadonlyArray1<'py, f64>,
+        i: PyReadonlyArray1<'py, f64>,
+        node: PyReadonlyArray1<'py, f64>,
+        argp: PyReadonlyArray1<'py, f64>,
+        m: PyReadonlyArray1<'py, f64>,
+        corrected: bool,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let rows = keplerian_rows(&[epoch, a, e, i, node, argp, m])?;
+        PyList::new(
+            cls.py(),
+            rows.into_iter().map(|k| {
+                let elems = RsOrbitalElements::Keplerian(k);
+                GaussResult::from(if corrected {
+                    RsGaussResult::CorrectedOrbit(elems)
+                } else {
+                    RsGaussResult::PrelimOrbit(elems)
+                })
+            }),
+        )
+    }
+
     /// Whether the result includes the post-Gauss correction step.
     ///
     /// Return
@@ -241,20 +437,134 @@ impl GaussResult {
         }
     }
 
+    /// Fit statistics of the orbit over the fitted arc.
+    ///
+    /// Return
+    /// ----------
+    /// * A `FitStatistics` for results returned by the IOD entry points, `None` for
+    ///   results built from element sets (or when the orbit cannot be propagated).
+    #[getter]
+    fn fit_statistics(&self) -> Option<FitStatistics> {
+        self.fit
+    }
+
+    /// Cost counters of the IOD run that produced this result.
+    ///
+    /// Return
+    /// ----------
+    /// * An `IodCosts` when the run used `IODParams.collect_costs`, `None` otherwise.
+    #[getter]
+    fn costs(&self) -> Option<IodCosts> {
+        self.costs
+    }
+
+    /// Reference epoch of the orbit as fitted, in MJD (TDB).
+    ///
+    /// Return
+    /// ----------
+    /// * The fit epoch for results returned by the IOD entry points (it differs from the
+    ///   elements' `reference_epoch` when `IODParams.output_epoch` is set), `None` for
+    ///   results built from element sets.
+    #[getter]
+    fn fit_epoch(&self) -> Option<f64> {
+        self.fit_epoch
+    }
+
+    /// Observations kept for triplet enumeration on a subsampled arc.
+    ///
+    /// Return
+    /// ----------
+    /// * Increasing positions in the time-sorted arc, as selected by
+    ///   `IODParams.subsample`, when the arc was longer than `max_obs_for_triplets`;
+    ///   `None` otherwise and for results built from element sets.
+    #[getter]
+    fn subsample_indices(&self) -> Option<Vec<usize>> {
+        self.subsample_indices.clone()
+    }
+
+    /// Parameter set that produced this orbit in a batch run with `fallback_params`.
+    ///
+    /// Return
+    /// ----------
+    /// * `0` when the primary `params` succeeded, `k` when the orbit was obtained with
+    ///   `fallback_params[k - 1]`; `None` for runs without fallbacks and for results
+    ///   built from element sets.
+    #[getter]
+    fn params_index(&self) -> Option<usize> {
+        self.params_index
+    }
+
+    /// Strategy that produced this orbit in a batch run with `initial_guesses`.
+    ///
+    /// Return
+    /// ----------
+    /// * `"guess"` when the provided orbit was corrected over the whole arc,
+    ///   `"fallback"` when that correction diverged and the Gauss IOD ran instead,
+    ///   `"gauss"` for trajectories without a guess; `None` for runs without
+    ///   `initial_guesses` and for results built from element sets.
+    #[getter]
+    fn iod_path(&self) -> Option<&'static str> {
+        self.iod_path.map(IodPath::as_str)
+    }
+
+    /// Propagate the orbit to another epoch with the two-body model.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `epoch`: Target epoch in MJD (TDB).
+    ///
+    /// Return
+    /// ----------
+    /// * A new `GaussResult` of the same stage and element family at `epoch`, keeping the
+    ///   fit statistics, costs and `fit_epoch` of `self`.
+    ///
+    /// Notes
+    /// ----------
+    /// * No planetary perturbation is applied. Unbound (cometary) orbits are not
+    ///   supported and raise `RuntimeError`.
+    #[pyo3(text_signature = "(self, epoch)")]
+    fn propagate(&self, epoch: f64) -> PyResult<Self> {
+        self.propagated(epoch).into_py()
+    }
+
     /// Convert the result to a Python dict.
     ///
+    /// Arguments
+    /// -----------------
+    /// * `include_units`: Add `"units"` blocks mapping each numeric field to its unit
+    ///   (default: `False`, the cheap form used on hot paths).
+    /// * `degrees`: Export angles in degrees instead of radians (default: `False`).
+    ///   The units block is then always included, so the dict stays unambiguous.
+    ///
     /// Return
     /// ----------
     /// * A dict with keys:
     ///   * `"stage"`: `"preliminary"` | `"corrected"`
     ///   * `"type"`: `"keplerian"` | `"equinoctial"` | `"cometary"`
     ///   * `"elements"`: a nested dict of the concrete fields.
+    ///   * `"fit_statistics"`: `FitStatistics.to_dict()`, or `None` when unavailable.
+    ///   * `"costs"`: `IodCosts.to_dict()`, or `None` when costs were not collected.
+    ///   * `"fit_epoch"`: epoch of the fit, on the scale of `reference_epoch` (MJD TDB),
+    ///     or `None` for results built from element sets.
+    ///   * `"units"` (with units): unit of every `"elements"` field, e.g. `"AU"`,
+    ///     `"rad"` / `"deg"`, `"MJD_TDB"`, `""` for dimensionless values. The nested
+    ///     statistics and costs dicts carry their own block.
     ///
     /// See also
     /// ------------
     /// * [`keplerian`], [`equinoctial`], [`cometary`]
-    #[pyo3(text_signature = "(self)")]
-    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+    /// * `from_dict` – Inverse conversion.
+    #[pyo3(
+        signature = (include_units=false, degrees=false),
+        text_signature = "(self, include_units=False, degrees=False)"
+    )]
+    fn to_dict<'py>(
+        &self,
+        py: Python<'py>,
+        include_units: bool,
+        degrees: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let include_units = include_units || degrees;
         let d = PyDict::new(py);
         let (stage, elems) = match &self.inner {
             RsGaussResult::PrelimOrbit(e) => ("preliminary", e),
@@ -262,48 +572,186 @@ impl GaussResult {
         };
         d.set_item("stage", stage)?;
 
-        match elems {
-            RsOrbitalElements::Keplerian(k) => {
-                d.set_item("type", "keplerian")?;
-                let e = PyDict::new(py);
-                e.set_item("reference_epoch", k.reference_epoch)?;
-                e.set_item("semi_major_axis", k.semi_major_axis)?;
-                e.set_item("eccentricity", k.eccentricity)?;
-                e.set_item("inclination", k.inclination)?;
-                e.set_item("ascending_node_longitude", k.ascending_node_longitude)?;
-                e.set_item("periapsis_argument", k.periapsis_argument)?;
-                e.set_item("mean_anomaly", k.mean_anomaly)?;
-                d.set_item("elements", e)?;
-            }
-            RsOrbitalElements::Equinoctial(q) => {
-                d.set_item("type", "equinoctial")?;
-                let e = PyDict::new(py);
-                e.set_item("reference_epoch", q.reference_epoch)?;
-                e.set_item("semi_major_axis", q.semi_major_axis)?;
-                e.set_item("eccentricity_sin_lon", q.eccentricity_sin_lon)?;
-                e.set_item("eccentricity_cos_lon", q.eccentricity_cos_lon)?;
-                e.set_item("tan_half_incl_sin_node", q.tan_half_incl_sin_node)?;
-                e.set_item("tan_half_incl_cos_node", q.tan_half_incl_cos_node)?;
-                e.set_item("mean_longitude", q.mean_longitude)?;
-                d.set_item("elements", e)?;
-            }
-            RsOrbitalElements::Cometary(c) => {
-                d.set_item("type", "cometary")?;
-                let e = PyDict::new(py);
-                e.set_item("reference_epoch", c.reference_epoch)?;
-                e.set_item("perihelion_distance", c.perihelion_distance)?;
-                e.set_item("eccentricity", c.eccentricity)?;
-                e.set_item("inclination", c.inclination)?;
-                e.set_item("ascending_node_longitude", c.ascending_node_longitude)?;
-                e.set_item("periapsis_argument", c.periapsis_argument)?;
-                e.set_item("true_anomaly", c.true_anomaly)?;
-                d.set_item("elements", e)?;
-            }
+        let (kind, fields, values) = units::element_fields(elems);
+        d.set_item("type", kind)?;
+        let e = PyDict::new(py);
+        for ((name, q), v) in fields.iter().zip(values) {
+            e.set_item(*name, units::export(*q, v, degrees))?;
+        }
+        d.set_item("elements", e)?;
+
+        match &self.fit {
+            Some(fit) => d.set_item("fit_statistics", fit.to_dict(py, include_units)?)?,
+            None => d.set_item("fit_statistics", py.None())?,
+        }
+        match &self.costs {
+            Some(costs) => d.set_item("costs", costs.to_dict(py, include_units)?)?,
+            None => d.set_item("costs", py.None())?,
+        }
+        d.set_item("fit_epoch", self.fit_epoch)?;
+        if include_units {
+            d.set_item("units", units::units_block(py, fields, degrees)?)?;
         }
 
         Ok(d)
     }
 
+    /// Serialize the result to a JSON string.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `include_units`: Embed the `"units"` blocks (default: `True`).
+    /// * `degrees`: Export angles in degrees instead of radians (default: `False`).
+    /// * `indent`: Indentation passed to `json.dumps` (default: compact output).
+    ///
+    /// Return
+    /// ----------
+    /// * The JSON form of `to_dict(include_units, degrees)`.
+    ///
+    /// See also
+    /// ------------
+    /// * `from_json` – Inverse conversion.
+    #[pyo3(
+        signature = (include_units=true, degrees=false, indent=None),
+        text_signature = "(self, include_units=True, degrees=False, indent=None)"
+    )]
+    fn to_json(
+        &self,
+        py: Python<'_>,
+        include_units: bool,
+        degrees: bool,
+        indent: Option<usize>,
+    ) -> PyResult<String> {
+        units::dumps(&self.to_dict(py, include_units, degrees)?, indent)
+    }
+
+    /// Rebuild a result from the output of `to_dict`.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `data`: Mapping with `"stage"`, `"type"`, `"elements"` and optionally
+    ///   `"units"`, `"fit_statistics"`, `"costs"`, `"fit_epoch"` and `"rms"` (accepted
+    ///   for rows stored with their RMS, not kept). Without a units block, values are
+    ///   taken in radians / AU / MJD (TDB); angles declared `"deg"` are converted back
+    ///   to radians.
+    ///
+    /// Return
+    /// ----------
+    /// * A `GaussResult` holding the element set, fit statistics, costs and
+    ///   `"fit_epoch"` found in `data`.
+    ///
+    /// Notes
+    /// ----------
+    /// * `KeyError` when a required key or element field is missing (the message names
+    ///   it, and its parent for nested dicts), `ValueError` for an unknown key, stage or
+    ///   element type, or a unit other than the export's own.
+    #[classmethod]
+    #[pyo3(text_signature = "(data)")]
+    fn from_dict(_cls: &Bound<'_, PyType>, data: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let mut unknown = Vec::new();
+        for key in data.call_method0("keys")?.try_iter()? {
+            let key = key?;
+            if !key.extract::<&str>().is_ok_and(|k| DICT_KEYS.contains(&k)) {
+                unknown.push(key.repr()?.to_string());
+            }
+        }
+        if !unknown.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "unknown key(s) {} (expected {DICT_KEYS:?})",
+                unknown.join(", ")
+            )));
+        }
+
+        let kind = label(&units::field(data, "", "type")?, "type")?;
+        let units = present(data, "units");
+        let elems = units::elements_from_mapping(
+            &kind,
+            &units::field(data, "", "elements")?,
+            units.as_ref(),
+        )?;
+        if let Some(rms) = present(data, "rms") {
+            rms.extract::<f64>().map_err(|_| {
+                PyValueError::new_err(format!("\"rms\" must be a number, got {rms}"))
+            })?;
+        }
+
+        Ok(Self {
+            fit: present(data, "fit_statistics")
+                .map(|m| FitStatistics::from_mapping(&m, "fit_statistics"))
+                .transpose()?,
+            costs: present(data, "costs")
+                .map(|m| IodCosts::from_mapping(&m, "costs"))
+                .transpose()?,
+            fit_epoch: present(data, "fit_epoch")
+                .map(|v| v.extract())
+                .transpose()?,
+            ..staged(&label(&units::field(data, "", "stage")?, "stage")?, elems)?.into()
+        })
+    }
+
+    /// Rebuild a result from a row of the `OrbitResults` exports.
+    ///
+    /// Accepts one row of `OrbitResults.to_dict()` (or of a DataFrame / Parquet table
+    /// written from it, e.g. a `pandas.Series` or a `dict` from
+    /// `to_dict(orient="records")`): flat columns named as in
+    /// `py_outfit.schemas()["results"]`.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `row`: Mapping with `"stage"`, `"element_type"` and the element columns of
+    ///   that family; optionally `"fit_epoch"` and the fit statistics columns. Other
+    ///   columns (`object_id`, `rms`, the other families' elements) are ignored.
+    /// * `units`: Units block of the export (`to_dict(include_units=True)["units"]`),
+    ///   needed when angles were exported in degrees.
+    ///
+    /// Return
+    /// ----------
+    /// * A `GaussResult` with its elements, `fit_epoch` and fit statistics (when the
+    ///   row has them). Missing values may be `None` or `NaN`.
+    ///
+    /// Notes
+    /// ----------
+    /// * `KeyError` naming a missing column, `ValueError` for a failed trajectory (its
+    ///   `error` column is set), an unknown stage or element type, or an unsupported
+    ///   unit.
+    #[classmethod]
+    #[pyo3(signature = (row, units=None), text_signature = "(row, units=None)")]
+    fn from_row(
+        _cls: &Bound<'_, PyType>,
+        row: &Bound<'_, PyAny>,
+        units: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        if let Some(error) = present(row, "error") {
+            return Err(PyValueError::new_err(format!(
+                "the row is a failed trajectory: {error}"
+            )));
+        }
+        let kind = label(&units::field(row, "", "element_type")?, "element_type")?;
+        let elems = units::elements_from_mapping(&kind, row, units)?;
+        Ok(Self {
+            fit: present(row, "rms_arcsec")
+                .map(|_| FitStatistics::from_mapping(row, ""))
+                .transpose()?,
+            fit_epoch: present(row, "fit_epoch").map(|v| v.extract()).transpose()?,
+            ..staged(&label(&units::field(row, "", "stage")?, "stage")?, elems)?.into()
+        })
+    }
+
+    /// Rebuild a result from the output of `to_json`.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `s`: JSON document, see `from_dict` for the accepted content.
+    ///
+    /// Return
+    /// ----------
+    /// * A `GaussResult` holding the element set.
+    #[classmethod]
+    #[pyo3(text_signature = "(s)")]
+    fn from_json(cls: &Bound<'_, PyType>, s: &str) -> PyResult<Self> {
+        Self::from_dict(cls, &units::loads(cls.py(), s)?)
+    }
+
     /// Pretty string representation (`str(obj)` in Python).
     fn __str__(&self) -> String {
         format!("{}", self.inner)
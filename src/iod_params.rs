@@ -1,17 +1,112 @@
-use pyo3::{pyclass, pymethods, PyRefMut, PyResult};
+use outfit::observations::Observation;
+use pyo3::{exceptions::PyValueError, prelude::*, IntoPyObjectExt};
+
+use crate::{time_scales, IntoPyResult};
+
+/// Epoch at which the IOD entry points quote the returned elements.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum OutputEpoch {
+    /// Fixed MJD (TDB), shared by every trajectory.
+    Mjd(f64),
+    /// Epoch of the first observation of each trajectory.
+    FirstObs,
+    /// Epoch of the last observation of each trajectory.
+    LastObs,
+    /// Midpoint between the first and last observations of each trajectory.
+    Middle,
+}
+
+impl OutputEpoch {
+    fn parse(v: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(mjd) = v.extract::<f64>() {
+            if !mjd.is_finite() {
+                return Err(PyValueError::new_err("output_epoch must be a finite MJD"));
+            }
+            return Ok(OutputEpoch::Mjd(mjd));
+        }
+        match v.extract::<String>()?.as_str() {
+            "first_obs" => Ok(OutputEpoch::FirstObs),
+            "last_obs" => Ok(OutputEpoch::LastObs),
+            "middle" => Ok(OutputEpoch::Middle),
+            other => Err(PyValueError::new_err(format!(
+                "unknown output_epoch {other:?} (expected an MJD, 'first_obs', 'last_obs' or 'middle')"
+            ))),
+        }
+    }
+
+    fn to_object(self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match self {
+            OutputEpoch::Mjd(mjd) => mjd.into_py_any(py),
+            OutputEpoch::FirstObs => "first_obs".into_py_any(py),
+            OutputEpoch::LastObs => "last_obs".into_py_any(py),
+            OutputEpoch::Middle => "middle".into_py_any(py),
+        }
+    }
+
+    /// Target epoch (MJD TDB) for a trajectory, `None` when it has no observation.
+    pub(crate) fn resolve(self, obs: &[Observation]) -> Option<f64> {
+        if let OutputEpoch::Mjd(mjd) = self {
+            return Some(mjd);
+        }
+        let first = obs.iter().map(|o| o.time).reduce(f64::min)?;
+        let last = obs.iter().map(|o| o.time).reduce(f64::max)?;
+        let mjd_tt = match self {
+            OutputEpoch::FirstObs => first,
+            OutputEpoch::LastObs => last,
+            _ => 0.5 * (first + last),
+        };
+        Some(time_scales::tt_to_tdb(mjd_tt))
+    }
+}
+
+/// Thinning strategy of arcs longer than `max_obs_for_triplets`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Subsample {
+    /// Evenly spaced in index, endpoints kept (the core's selection).
+    Uniform,
+    /// Endpoints plus the most informative observations of a linear-motion fit.
+    Leverage,
+}
+
+impl Subsample {
+    fn parse(v: &str) -> PyResult<Self> {
+        match v {
+            "uniform" => Ok(Subsample::Uniform),
+            "leverage" => Ok(Subsample::Leverage),
+            other => Err(PyValueError::new_err(format!(
+                "unknown subsample strategy {other:?} (expected 'uniform' or 'leverage')"
+            ))),
+        }
+    }
 
-use crate::IntoPyResult;
+    fn name(self) -> &'static str {
+        match self {
+            Subsample::Uniform => "uniform",
+            Subsample::Leverage => "leverage",
+        }
+    }
+}
 
 #[pyclass]
 pub struct IODParams {
     pub(crate) inner: outfit::IODParams,
     do_parallel: bool,
+    pub(crate) triplet_scorer: Option<Py<PyAny>>,
+    pub(crate) collect_costs: bool,
+    pub(crate) collect_rejection_stats: bool,
+    pub(crate) output_epoch: Option<OutputEpoch>,
+    pub(crate) subsample: Subsample,
 }
 
 #[pyclass]
 pub struct IODParamsBuilder {
     pub(crate) inner: outfit::initial_orbit_determination::IODParamsBuilder,
     do_parallel: bool,
+    triplet_scorer: Option<Py<PyAny>>,
+    collect_costs: bool,
+    collect_rejection_stats: bool,
+    output_epoch: Option<OutputEpoch>,
+    subsample: Subsample,
 }
 
 impl Default for IODParams {
@@ -28,6 +123,11 @@ impl IODParams {
         Self {
             inner: outfit::IODParams::default(),
             do_parallel: false,
+            triplet_scorer: None,
+            collect_costs: false,
+            collect_rejection_stats: false,
+            output_epoch: None,
+            subsample: Subsample::Uniform,
         }
     }
 
@@ -46,6 +146,11 @@ impl IODParams {
         Ok(IODParamsBuilder {
             inner: outfit::IODParams::builder(),
             do_parallel: false,
+            triplet_scorer: None,
+            collect_costs: false,
+            collect_rejection_stats: false,
+            output_epoch: None,
+            subsample: Subsample::Uniform,
         })
     }
 
@@ -155,6 +260,33 @@ impl IODParams {
     pub fn do_parallel(&self) -> bool {
         self.do_parallel
     }
+
+    // Plugin hooks
+    #[getter]
+    pub fn triplet_scorer(&self, py: Python<'_>) -> Option<Py<PyAny>> {
+        self.triplet_scorer.as_ref().map(|f| f.clone_ref(py))
+    }
+
+    // Diagnostics
+    #[getter]
+    pub fn collect_costs(&self) -> bool {
+        self.collect_costs
+    }
+    #[getter]
+    pub fn collect_rejection_stats(&self) -> bool {
+        self.collect_rejection_stats
+    }
+
+    // Output
+    #[getter]
+    pub fn output_epoch(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        self.output_epoch.map(|e| e.to_object(py)).transpose()
+    }
+
+    #[getter]
+    pub fn subsample(&self) -> &'static str {
+        self.subsample.name()
+    }
 }
 
 #[pymethods]
@@ -165,6 +297,11 @@ impl IODParamsBuilder {
         Ok(Self {
             inner: outfit::initial_orbit_determination::IODParamsBuilder::new(),
             do_parallel: false,
+            triplet_scorer: None,
+            collect_costs: false,
+            collect_rejection_stats: false,
+            output_epoch: None,
+            subsample: Subsample::Uniform,
         })
     }
 
@@ -345,11 +482,105 @@ impl IODParamsBuilder {
         slf
     }
 
+    // --- Plugin hooks ---
+    /// Install a custom triplet ranking callback (`None` restores the built-in weight).
+    ///
+    /// The callable receives `(epochs, indices)`, two 3-tuples holding the MJD (TT)
+    /// epochs and the positions of the observations in the time-sorted trajectory,
+    /// and returns a float score (lower is tried first). Only the sequential and
+    /// single-trajectory paths support it.
+    #[pyo3(text_signature = "(scorer)")]
+    pub fn triplet_scorer<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        scorer: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        if let Some(f) = scorer.as_ref() {
+            if !f.is_callable() {
+                return Err(PyValueError::new_err("triplet_scorer must be callable"));
+            }
+        }
+        slf.triplet_scorer = scorer.map(Bound::unbind);
+        Ok(slf)
+    }
+
+    // --- Diagnostics ---
+    /// Record per-trajectory cost counters (`GaussResult.costs`, `OrbitResults.summary()`).
+    ///
+    /// Disabled by default; when disabled the search loop measures nothing. Only the
+    /// sequential and single-trajectory paths support it.
+    #[pyo3(text_signature = "(v)")]
+    pub fn collect_costs(mut slf: PyRefMut<'_, Self>, v: bool) -> PyRefMut<'_, Self> {
+        slf.collect_costs = v;
+        slf
+    }
+
+    /// Tally why candidate triplets were rejected (`OrbitResults.rejection_stats()`).
+    ///
+    /// Disabled by default; when disabled nothing is counted. Supported on every path,
+    /// parallel batches included.
+    #[pyo3(text_signature = "(v)")]
+    pub fn collect_rejection_stats(mut slf: PyRefMut<'_, Self>, v: bool) -> PyRefMut<'_, Self> {
+        slf.collect_rejection_stats = v;
+        slf
+    }
+
+    // --- Output ---
+    /// Epoch at which the returned elements are quoted (`None`: the fit epoch).
+    ///
+    /// A float is an MJD (TDB) shared by every trajectory; `"first_obs"`, `"last_obs"`
+    /// and `"middle"` pick the first or last observation epoch of each trajectory, or
+    /// the midpoint between them. Orbits are two-body propagated from their fit epoch,
+    /// which is kept as `GaussResult.fit_epoch`; fit statistics are those of the fit.
+    #[pyo3(text_signature = "(v)")]
+    pub fn output_epoch<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        v: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.output_epoch = v.as_ref().map(OutputEpoch::parse).transpose()?;
+        Ok(slf)
+    }
+
+    /// Thinning strategy of arcs longer than `max_obs_for_triplets`.
+    ///
+    /// `"uniform"` (default) keeps observations evenly spaced in index; `"leverage"`
+    /// keeps the endpoints and the observations carrying the most information in a
+    /// linear-motion fit of the arc. The kept observations are reported by
+    /// `GaussResult.subsample_indices`. Only the sequential and single-trajectory paths
+    /// support `"leverage"`.
+    #[pyo3(text_signature = "(v)")]
+    pub fn subsample<'py>(mut slf: PyRefMut<'py, Self>, v: &str) -> PyResult<PyRefMut<'py, Self>> {
+        slf.subsample = Subsample::parse(v)?;
+        Ok(slf)
+    }
+
     pub fn build(mut slf: PyRefMut<'_, Self>) -> PyResult<IODParams> {
+        if slf.do_parallel && slf.triplet_scorer.is_some() {
+            return Err(PyValueError::new_err(
+                "triplet_scorer is not supported with do_parallel(): Python callbacks \
+                 cannot be invoked from worker threads; use do_sequential()",
+            ));
+        }
+        if slf.do_parallel && slf.collect_costs {
+            return Err(PyValueError::new_err(
+                "collect_costs is not supported with do_parallel(): the parallel batch \
+                 runs in the core and does not report costs; use do_sequential()",
+            ));
+        }
+        if slf.do_parallel && slf.subsample == Subsample::Leverage {
+            return Err(PyValueError::new_err(
+                "subsample='leverage' is not supported with do_parallel(): the parallel \
+                 batch runs in the core, which only subsamples uniformly; use do_sequential()",
+            ));
+        }
         let inner = std::mem::take(&mut slf.inner).build().into_py()?;
         Ok(IODParams {
             inner,
             do_parallel: slf.do_parallel,
+            triplet_scorer: slf.triplet_scorer.take(),
+            collect_costs: slf.collect_costs,
+            collect_rejection_stats: slf.collect_rejection_stats,
+            output_epoch: slf.output_epoch,
+            subsample: slf.subsample,
         })
     }
 }
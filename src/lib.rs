@@ -107,16 +107,60 @@
 //! except RuntimeError as exc:
 //!     print("Failed to initialize environment:", exc)
 //! ```
+pub mod ades;
+pub mod ades_writer;
+pub mod arrow_ffi;
+pub mod builtin_ephemeris;
 pub mod constants;
+pub mod costs;
+pub mod csv_reader;
+pub mod dedup;
+pub mod differential_correction;
+pub mod ephemeris;
+pub mod errors;
+pub mod file_format;
+pub mod fit_statistics;
+pub mod ingest;
+pub mod interchange;
+pub mod iod_engine;
 pub mod iod_gauss;
 pub mod iod_params;
+pub mod memory;
+pub mod mpc80;
+pub mod mpc80_writer;
 pub mod observations;
+pub mod observatories;
 pub mod observer;
+pub mod orbfit;
 pub mod orbit_type;
+pub mod parquet_writer;
+pub mod photometry;
+pub mod prediction;
+pub mod propagation;
+pub mod records;
+pub mod rejections;
+pub mod results;
+pub mod schemas;
+pub mod sexagesimal;
+pub mod similarity;
+pub mod sqlite_writer;
+pub mod strict;
+pub mod subsample;
+pub mod time_scales;
 pub mod trajectories;
+pub mod units;
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError},
+};
 
 use outfit::Outfit;
-use pyo3::{exceptions::PyRuntimeError, prelude::*};
+use pyo3::{
+    exceptions::{PyKeyError, PyOSError, PyRuntimeError, PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyList},
+};
 
 use crate::{
     iod_gauss::GaussResult,
@@ -168,7 +212,23 @@ impl<T> IntoPyResult<T> for Result<T, outfit::outfit_errors::OutfitError> {
 /// * [`observer::Observer`] – Observatory handle used by `PyOutfit`.
 #[pyclass(module = "py_outfit")]
 pub struct PyOutfit {
-    inner: Outfit,
+    /// Core engine, shared with the clones made by [`PyOutfit::clone_shallow`] (see
+    /// [`PyOutfit::outfit`] and [`PyOutfit::inner_mut`]).
+    inner: Arc<RwLock<Outfit>>,
+    /// Sites registered through [`PyOutfit::add_observer`], with their handle, in
+    /// registration order.
+    user_observers: Vec<(u32, Arc<outfit::Observer>)>,
+    /// Handle given to the next registered site (handles are never reused).
+    next_handle: u32,
+    /// Epochs accepted by the environment, `(mjd_start, mjd_end)` in TDB: the kernel
+    /// coverage, narrowed by the requested span if any.
+    time_range: (f64, f64),
+    /// Whether observation epochs are checked against the ephemeris segments.
+    check_ephemeris_coverage: bool,
+    /// Continuous segments of `time_range`, mapped on first use.
+    segments: OnceLock<Vec<(f64, f64)>>,
+    /// MPC sites by code, shared with the clones like the engine.
+    mpc_sites: Arc<observatories::MpcSites>,
 }
 
 #[pymethods]
@@ -177,8 +237,17 @@ impl PyOutfit {
     ///
     /// Arguments
     /// -----------------
-    /// * `ephem` - Ephemerides selector (e.g. `"horizon:DE440"`).
-    /// * `error_model` - Astrometric error model (e.g. `"FCCT14"` or `"VFCC17"`).
+    /// * `ephem` - Ephemerides selector (e.g. `"horizon:DE440"`), optionally restricted
+    ///   to a range of years with a `[YYYY-YYYY]` suffix (e.g. `"horizon:DE440[2020-2030]"`).
+    ///   `"builtin:approx"` selects a compiled-in analytic ephemeris (1900–2100) that needs
+    ///   no kernel download; arcminute-level, for tests and CI, **not for science**.
+    /// * `error_model` - Astrometric error model (`"FCCT14"`, `"VFCC17"` or `"CBM10"`).
+    /// * `time_span` - Explicit restriction `(mjd_start, mjd_end)` (TDB); exclusive with
+    ///   the selector suffix.
+    /// * `strict` - Per-call override of the strict mode (`None`: the process-wide
+    ///   setting, see `py_outfit.set_strict`).
+    /// * `check_ephemeris_coverage` - Reject observation epochs that do not lie strictly
+    ///   inside a single ephemeris segment, at ingestion and estimation (default `True`).
     ///
     /// Return
     /// ----------
@@ -186,22 +255,188 @@ impl PyOutfit {
     ///
     /// Notes
     /// ----------
-    /// * Unknown `error_model` strings default to `FCCT14`.
+    /// * Unknown `error_model` strings default to `FCCT14` with a `FallbackWarning`
+    ///   (`StrictModeError` in strict mode).
     /// * All heavy computations remain in Rust; Python merely orchestrates flows.
+    /// * The ephemeris is loaded eagerly and checked for the bodies used by the IOD
+    ///   pipeline; an incomplete kernel raises `EphemerisIncompleteError`.
+    /// * `"builtin:approx"` provides the Earth–Moon barycenter only (see
+    ///   [`builtin_ephemeris`]); it is available on Linux and macOS.
+    /// * A restricted span does not shrink the download: the kernel formats are read
+    ///   whole by the core. The span must lie within the kernel coverage, and it bounds
+    ///   the epochs accepted at ingestion (see [`PyOutfit::ephemeris_time_range`]).
     ///
     /// See also
     /// ------------
     /// * [`Outfit::new`] – Builder in the Rust core.
     /// * [`iod_params::IODParams`] – IOD tuning parameters.
+    /// * [`PyOutfit::available_bodies`] – Bodies provided by the loaded kernel.
     #[new]
-    pub fn new(ephem: &str, error_model: &str) -> PyResult<Self> {
+    #[pyo3(signature = (
+        ephem, error_model, time_span=None, strict=None, check_ephemeris_coverage=true
+    ))]
+    pub fn new(
+        py: Python<'_>,
+        ephem: &str,
+        error_model: &str,
+        time_span: Option<(f64, f64)>,
+        strict: Option<bool>,
+        check_ephemeris_coverage: bool,
+    ) -> PyResult<Self> {
         let model = match error_model {
             "FCCT14" => outfit::error_models::ErrorModel::FCCT14,
             "VFCC17" => outfit::error_models::ErrorModel::VFCC17,
-            _ => outfit::error_models::ErrorModel::FCCT14,
+            "CBM10" => outfit::error_models::ErrorModel::CBM10,
+            other => {
+                strict::fallback(
+                    py,
+                    strict::Check::UnknownErrorModel,
+                    strict,
+                    &format!("unknown error model {other:?} (expected 'FCCT14', 'VFCC17' or 'CBM10'), using FCCT14"),
+                )?;
+                outfit::error_models::ErrorModel::FCCT14
+            }
+        };
+        let (selector, suffix_span) = ephemeris::split_selector(ephem)?;
+        let requested = match (suffix_span, time_span) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "give the ephemeris span either as a '[YYYY-YYYY]' suffix or as time_span, not both",
+                ))
+            }
+            (span, None) | (None, span) => span,
+        };
+        let inner = if builtin_ephemeris::is_builtin(selector)? {
+            py.detach(|| builtin_ephemeris::load(model))?
+        } else {
+            Outfit::new(selector, model).into_py()?
         };
-        let inner = Outfit::new(ephem, model).into_py()?;
-        Ok(Self { inner })
+        let jpl = py.detach(|| ephemeris::load(&inner)).into_py()?;
+        ephemeris::check_complete(py, jpl)?;
+
+        let (lo, hi) = py
+            .detach(|| ephemeris::coverage(jpl))
+            .ok_or_else(|| PyRuntimeError::new_err("the loaded ephemeris does not cover J2000"))?;
+        let time_range = match requested {
+            None => (lo, hi),
+            Some((start, end)) if start < end && start >= lo && end <= hi => (start, end),
+            Some((start, end)) => {
+                return Err(PyValueError::new_err(format!(
+                    "ephemeris span [{start}, {end}] (MJD TDB) is empty or not covered by \
+                     '{selector}', whose valid range is [{lo:.6}, {hi:.6}]"
+                )))
+            }
+        };
+
+        Ok(Self {
+            inner: Arc::new(RwLock::new(inner)),
+            user_observers: Vec::new(),
+            next_handle: 0,
+            time_range,
+            check_ephemeris_coverage,
+            segments: OnceLock::new(),
+            mpc_sites: Arc::default(),
+        })
+    }
+
+    /// List the bodies that can be interpolated from the loaded ephemeris.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * *(none)*
+    ///
+    /// Return
+    /// ----------
+    /// * A list of body names (e.g. `"Earth"`, `"Moon"`, `"Sun"` for Horizons files,
+    ///   `"Earth-Moon barycenter"` and friends for NAIF kernels).
+    ///
+    /// See also
+    /// ------------
+    /// * `ephemeris::available_bodies` – Probing strategy.
+    pub fn available_bodies(&self, py: Python<'_>) -> PyResult<Vec<&'static str>> {
+        let outfit = self.outfit(py);
+        let jpl = outfit.get_jpl_ephem().into_py()?;
+        Ok(ephemeris::available_bodies(jpl))
+    }
+
+    /// Epochs accepted by this environment.
+    ///
+    /// Return
+    /// ----------
+    /// * `(mjd_start, mjd_end)` in TDB: the coverage of the loaded kernel, or the
+    ///   restricted span given at construction. Ingesting observations outside this
+    ///   range raises `ValueError`.
+    pub fn ephemeris_time_range(&self) -> (f64, f64) {
+        self.time_range
+    }
+
+    /// Continuous segments of the ephemeris within [`PyOutfit::ephemeris_time_range`].
+    ///
+    /// Return
+    /// ----------
+    /// * A list of `(mjd_start, mjd_end)` in TDB, in time order. With
+    ///   `check_ephemeris_coverage`, observation epochs must lie at least one day inside
+    ///   one of them.
+    ///
+    /// Notes
+    /// ----------
+    /// * The kernel is scanned on the first call (every 16 days, transitions bisected),
+    ///   then the table is cached. Gaps shorter than the scan step may go unnoticed.
+    pub fn ephemeris_segments(&self, py: Python<'_>) -> Vec<(f64, f64)> {
+        py.detach(|| self.segments().to_vec())
+    }
+
+    /// Cheap copy of the environment, with its own observatory registry.
+    ///
+    /// The clone shares the core engine (loaded ephemeris, MPC table, error model) with
+    /// `self` for its whole life; the engine is never copied. The sites registered with
+    /// `add_observer`, `replace_observer` and `remove_observer` are private to each
+    /// side: mutations of one side are never seen by the other.
+    ///
+    /// Return
+    /// ----------
+    /// * A new `PyOutfit` with the same settings and user-registered sites (same handles).
+    ///
+    /// Notes
+    /// ----------
+    /// * The core indexes every site it sees (registrations, ingestion) in a table that
+    ///   only grows and is not visible from Python; clones share that index, which keeps
+    ///   the observations of either side valid in the other.
+    /// * Ingestion takes the engine exclusively: it waits for the calls running on the
+    ///   other clones (and holds them back) while it indexes the sites.
+    ///
+    /// See also
+    /// ------------
+    /// * [`PyOutfit::shares_engine_with`] – Whether two environments share it.
+    pub fn clone_shallow(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            user_observers: self.user_observers.clone(),
+            next_handle: self.next_handle,
+            time_range: self.time_range,
+            check_ephemeris_coverage: self.check_ephemeris_coverage,
+            segments: self.segments.clone(),
+            mpc_sites: Arc::clone(&self.mpc_sites),
+        }
+    }
+
+    /// Whether `self` and `other` use the same loaded ephemeris in memory.
+    ///
+    /// Return
+    /// ----------
+    /// * `True` for an environment and its `clone_shallow()` copies (the address of the
+    ///   loaded kernel is compared), `False` otherwise.
+    #[pyo3(text_signature = "($self, other)")]
+    pub fn shares_engine_with(&self, py: Python<'_>, other: &PyOutfit) -> bool {
+        if Arc::ptr_eq(&self.inner, &other.inner) {
+            return true;
+        }
+        // One engine locked at a time.
+        let kernel = |env: &PyOutfit| env.outfit(py).get_jpl_ephem().ok().map(std::ptr::from_ref);
+        match (kernel(self), kernel(other)) {
+            (Some(a), Some(b)) => std::ptr::eq(a, b),
+            _ => false,
+        }
     }
 
     /// Add an `Observer` to the current environment.
@@ -212,34 +447,273 @@ impl PyOutfit {
     ///
     /// Return
     /// ----------
-    /// * `Ok(())` on success.
+    /// * An integer handle identifying the registration, accepted by
+    ///   [`PyOutfit::user_observer`], [`PyOutfit::replace_observer`] and
+    ///   [`PyOutfit::remove_observer`]. Handles are never reused within an environment.
     ///
     /// See also
     /// ------------
     /// * [`observer::Observer`] – Construction and fields.
-    pub fn add_observer(&mut self, observer: &Observer) -> PyResult<()> {
-        self.inner.add_observer(observer.inner.clone());
-        Ok(())
+    pub fn add_observer(&mut self, py: Python<'_>, observer: &Observer) -> PyResult<u32> {
+        let handle = self.next_handle;
+        self.inner_mut(py).add_observer(observer.inner.clone());
+        self.user_observers.push((handle, observer.inner.clone()));
+        self.next_handle += 1;
+        Ok(handle)
+    }
+
+    /// Look up a site registered with [`PyOutfit::add_observer`].
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `handle_or_name` - Handle returned by `add_observer`, or the site name.
+    ///
+    /// Return
+    /// ----------
+    /// * The registered [`Observer`], or `KeyError` when no site matches (or a name
+    ///   matches several sites).
+    #[pyo3(text_signature = "($self, handle_or_name)")]
+    pub fn user_observer(&self, handle_or_name: &Bound<'_, PyAny>) -> PyResult<Observer> {
+        let i = self.user_index(handle_or_name)?;
+        Ok(Observer {
+            inner: self.user_observers[i].1.clone(),
+        })
+    }
+
+    /// Replace a site registered with [`PyOutfit::add_observer`], keeping its handle.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `handle_or_name` - Handle returned by `add_observer`, or the site name.
+    /// * `new_observer` - Site taking over the registration.
+    ///
+    /// Return
+    /// ----------
+    /// * The (unchanged) handle, or `KeyError` when no site matches.
+    ///
+    /// Notes
+    /// ----------
+    /// * Only future lookups and listings see the new site. Observations already
+    ///   ingested with the old site (e.g. in an existing `TrajectorySet`) keep referring
+    ///   to it: the core registry never forgets a site, so they stay valid.
+    #[pyo3(text_signature = "($self, handle_or_name, new_observer)")]
+    pub fn replace_observer(
+        &mut self,
+        py: Python<'_>,
+        handle_or_name: &Bound<'_, PyAny>,
+        new_observer: &Observer,
+    ) -> PyResult<u32> {
+        let i = self.user_index(handle_or_name)?;
+        self.inner_mut(py).add_observer(new_observer.inner.clone());
+        self.user_observers[i].1 = new_observer.inner.clone();
+        Ok(self.user_observers[i].0)
+    }
+
+    /// Unregister a site added with [`PyOutfit::add_observer`].
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `handle_or_name` - Handle returned by `add_observer`, or the site name.
+    ///
+    /// Return
+    /// ----------
+    /// * The removed [`Observer`], or `KeyError` when no site matches.
+    ///
+    /// Notes
+    /// ----------
+    /// * The site disappears from lookups and listings; its handle becomes invalid.
+    ///   Observations already ingested with it stay valid (see
+    ///   [`PyOutfit::replace_observer`]).
+    #[pyo3(text_signature = "($self, handle_or_name)")]
+    pub fn remove_observer(&mut self, handle_or_name: &Bound<'_, PyAny>) -> PyResult<Observer> {
+        let i = self.user_index(handle_or_name)?;
+        let (_, inner) = self.user_observers.remove(i);
+        Ok(Observer { inner })
+    }
+
+    /// Observers registered with [`PyOutfit::add_observer`], in registration order.
+    ///
+    /// Return
+    /// ----------
+    /// * A list of [`Observer`] handles (MPC sites are not included).
+    pub fn user_observatories(&self) -> Vec<Observer> {
+        self.user_observers
+            .iter()
+            .map(|(_, o)| Observer { inner: o.clone() })
+            .collect()
+    }
+
+    /// Write the sites registered with [`PyOutfit::add_observer`] to a JSON file.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `path` - Destination file (overwritten).
+    ///
+    /// Return
+    /// ----------
+    /// * The number of sites written, in registration order.
+    ///
+    /// Notes
+    /// ----------
+    /// * Each record holds the handle, name, longitude, parallax constants
+    ///   `rho_cos_phi`/`rho_sin_phi` (which define the site exactly), the geodetic
+    ///   latitude and elevation (informative) and the RA/DEC accuracy overrides.
+    #[pyo3(text_signature = "($self, path)")]
+    pub fn export_user_observers(&self, py: Python<'_>, path: PathBuf) -> PyResult<usize> {
+        let records = PyList::empty(py);
+        for (handle, site) in &self.user_observers {
+            records.append(observatories::site_record(py, *handle, site)?)?;
+        }
+        let doc = PyDict::new(py);
+        doc.set_item("format", observatories::USER_OBSERVERS_FORMAT)?;
+        doc.set_item("version", observatories::USER_OBSERVERS_VERSION)?;
+        doc.set_item("observers", &records)?;
+        std::fs::write(&path, units::dumps(&doc, Some(2))?)
+            .map_err(|e| PyOSError::new_err(format!("cannot write {}: {e}", path.display())))?;
+        Ok(records.len())
+    }
+
+    /// Register the sites of a file written by [`PyOutfit::export_user_observers`].
+    ///
+    /// Sites are matched to the already registered ones by name. A site identical to
+    /// a registered one is left alone; a site whose name is registered with other
+    /// parameters is a conflict.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `path` - JSON file to read.
+    /// * `merge` - Keep the registered site on conflicts (default `True`); with `False`
+    ///   the file wins and the registered site is replaced, keeping its handle.
+    ///
+    /// Return
+    /// ----------
+    /// * A report dict: `"added"` (new handles), `"unchanged"` (handles of identical
+    ///   sites), `"replaced"` (handles of replaced sites) and `"conflicts"` (names of
+    ///   the sites kept on conflict).
+    ///
+    /// Notes
+    /// ----------
+    /// * The whole file is validated before any registration: `ValueError` for a
+    ///   document of another format or version, or an invalid value, and `KeyError`
+    ///   naming the record and key of a missing field.
+    /// * Handles stored in the file are informative; new sites get fresh handles.
+    #[pyo3(signature = (path, merge=true), text_signature = "($self, path, merge=True)")]
+    pub fn import_user_observers<'py>(
+        &mut self,
+        py: Python<'py>,
+        path: PathBuf,
+        merge: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| PyOSError::new_err(format!("cannot read {}: {e}", path.display())))?;
+        let doc = units::loads(py, &text)?;
+        let field = |key: &str| {
+            doc.get_item(key)
+                .map_err(|_| PyKeyError::new_err(format!("missing key {key:?}")))
+        };
+        let format: String = field("format")?.extract().unwrap_or_default();
+        if format != observatories::USER_OBSERVERS_FORMAT {
+            return Err(PyValueError::new_err(format!(
+                "{} is not a user observer file (format {format:?}, expected {:?})",
+                path.display(),
+                observatories::USER_OBSERVERS_FORMAT
+            )));
+        }
+        let version: u32 = field("version")?.extract().unwrap_or(0);
+        if version != observatories::USER_OBSERVERS_VERSION {
+            return Err(PyValueError::new_err(format!(
+                "unsupported user observer file version {version} (expected {})",
+                observatories::USER_OBSERVERS_VERSION
+            )));
+        }
+        let records: Vec<Bound<'py, PyAny>> = field("observers")?
+            .extract()
+            .map_err(|_| PyValueError::new_err("\"observers\" must be a list"))?;
+        let sites = records
+            .iter()
+            .enumerate()
+            .map(|(i, r)| observatories::site_from_record(r, i).map(Arc::new))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let (mut added, mut unchanged, mut replaced, mut conflicts) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for site in sites {
+            let existing = site.name.as_ref().and_then(|name| {
+                self.user_observers
+                    .iter()
+                    .position(|(_, o)| o.name.as_ref() == Some(name))
+            });
+            match existing {
+                None => added.push(self.add_observer(py, &Observer { inner: site })?),
+                Some(i) if self.user_observers[i].1 == site => {
+                    unchanged.push(self.user_observers[i].0)
+                }
+                Some(_) if merge => conflicts.push(site.name.clone()),
+                Some(i) => {
+                    self.inner_mut(py).add_observer(site.clone());
+                    self.user_observers[i].1 = site;
+                    replaced.push(self.user_observers[i].0);
+                }
+            }
+        }
+
+        let report = PyDict::new(py);
+        report.set_item("added", added)?;
+        report.set_item("unchanged", unchanged)?;
+        report.set_item("replaced", replaced)?;
+        report.set_item("conflicts", conflicts)?;
+        Ok(report)
     }
 
     /// Render a human-readable list of currently known observatories.
     ///
+    /// User-added sites come first (registration order), followed by the MPC sites
+    /// sorted by code when the MPC table has been loaded.
+    ///
     /// Arguments
     /// -----------------
-    /// * *(none)*
+    /// * `only_user_added` - List only the sites added with `add_observer` (default `False`).
+    /// * `filter` - Case-insensitive substring matched against the MPC code and the site name.
+    /// * `limit` - Maximum number of sites to list; a trailing note reports how many were omitted.
     ///
     /// Return
     /// ----------
-    /// * A `String` with a formatted table or list of observatories.
+    /// * A `String` with one line per observatory.
+    ///
+    /// Notes
+    /// ----------
+    /// * Units are spelled in ASCII (angles suffixed with `deg`, elevation in `m`), so the
+    ///   listing prints regardless of the console encoding (site names are shown as registered).
+    /// * The MPC section only appears once the environment has resolved MPC codes (e.g.
+    ///   after `get_observer_from_mpc_code`, `nearest_observatories` or an ingestion
+    ///   reading observatory codes); listing does not access the network.
     ///
     /// Example
     /// -----------------
     /// ```python
     /// env = PyOutfit("horizon:DE440", "FCCT14")
-    /// print(env.show_observatories())
+    /// print(env.show_observatories(filter="palomar", limit=10))
     /// ```
-    pub fn show_observatories(&self) -> PyResult<String> {
-        Ok(format!("{}", self.inner.show_observatories()))
+    #[pyo3(
+        signature = (only_user_added=false, filter=None, limit=None),
+        text_signature = "($self, only_user_added=False, filter=None, limit=None)"
+    )]
+    pub fn show_observatories(
+        &self,
+        only_user_added: bool,
+        filter: Option<&str>,
+        limit: Option<usize>,
+    ) -> PyResult<String> {
+        let mpc: Vec<(String, Arc<outfit::Observer>)> = match self.mpc_sites.loaded() {
+            Some(table) if !only_user_added => table
+                .iter()
+                .map(|(code, obs)| (code.clone(), obs.clone()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let user: Vec<Arc<outfit::Observer>> =
+            self.user_observers.iter().map(|(_, o)| o.clone()).collect();
+        Ok(observatories::render(&user, &mpc, filter, limit))
     }
 
     /// Lookup an `Observer` from its MPC code.
@@ -250,16 +724,181 @@ impl PyOutfit {
     ///
     /// Return
     /// ----------
-    /// * An [`Observer`] handle usable with [`PyOutfit::add_observer`].
+    /// * An [`Observer`] handle usable with [`PyOutfit::add_observer`], or `ValueError`
+    ///   for an unknown code.
     ///
     /// See also
     /// ------------
     /// * [`observer::Observer`] – Python-visible wrapper for observatories.
-    pub fn get_observer_from_mpc_code(&self, code: &str) -> PyResult<Observer> {
-        Ok(Observer {
-            inner: self.inner.get_observer_from_mpc_code(&code.to_string()),
+    pub fn get_observer_from_mpc_code(&self, py: Python<'_>, code: &str) -> PyResult<Observer> {
+        let inner = py
+            .detach(|| self.mpc_sites.resolve(&self.outfit_detached(), code))
+            .ok_or_else(|| PyValueError::new_err(format!("unknown observatory code {code:?}")))?;
+        Ok(Observer { inner })
+    }
+
+    /// Find the MPC observatories closest to a geodetic position.
+    ///
+    /// Distances are great-circle distances on the mean Earth sphere (heights ignored),
+    /// computed from the geodetic coordinates recovered from the MPC parallax constants.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `latitude_deg` - Geodetic latitude in degrees, in `[-90, 90]`.
+    /// * `longitude_deg` - Longitude in degrees, east positive.
+    /// * `max_distance_km` - Search radius in km (default `50`).
+    /// * `limit` - Maximum number of sites returned (default `5`, `None` for all).
+    ///
+    /// Return
+    /// ----------
+    /// * A list of `(Observer, distance_km)`, nearest first.
+    ///
+    /// Notes
+    /// ----------
+    /// * The MPC table is loaded on first use (network access), as for
+    ///   `get_observer_from_mpc_code`.
+    /// * Sites without a ground position (geocentre, space telescopes) and sites added
+    ///   with `add_observer` are not considered.
+    #[pyo3(
+        signature = (latitude_deg, longitude_deg, max_distance_km=50.0, limit=Some(5)),
+        text_signature = "($self, latitude_deg, longitude_deg, max_distance_km=50.0, limit=5)"
+    )]
+    pub fn nearest_observatories(
+        &self,
+        py: Python<'_>,
+        latitude_deg: f64,
+        longitude_deg: f64,
+        max_distance_km: f64,
+        limit: Option<usize>,
+    ) -> PyResult<Vec<(Observer, f64)>> {
+        if !(-90.0..=90.0).contains(&latitude_deg) || !longitude_deg.is_finite() {
+            return Err(PyValueError::new_err(format!(
+                "invalid position (latitude_deg={latitude_deg}, longitude_deg={longitude_deg}); \
+                 latitude must lie in [-90, 90] and longitude be finite"
+            )));
+        }
+        if max_distance_km.is_nan() || max_distance_km < 0.0 {
+            return Err(PyValueError::new_err(format!(
+                "max_distance_km must be non-negative, got {max_distance_km}"
+            )));
+        }
+        let outfit = self.outfit(py);
+        let mpc: Vec<(String, Arc<outfit::Observer>)> = self
+            .mpc_sites
+            .table(&outfit)
+            .iter()
+            .map(|(code, obs)| (code.clone(), obs.clone()))
+            .collect();
+        Ok(
+            observatories::nearest(&mpc, latitude_deg, longitude_deg, max_distance_km, limit)
+                .into_iter()
+                .map(|(inner, d)| (Observer { inner }, d))
+                .collect(),
+        )
+    }
+}
+
+impl PyOutfit {
+    /// Shared access to the core engine, from a thread holding the GIL.
+    ///
+    /// When the engine is locked exclusively (ingestion on a clone), the GIL is released
+    /// while waiting: the other thread may need it to finish. A panic of the core while
+    /// the engine was locked leaves it usable: its site index only grows, so an
+    /// interrupted registration cannot corrupt it.
+    pub(crate) fn outfit(&self, py: Python<'_>) -> RwLockReadGuard<'_, Outfit> {
+        loop {
+            match self.inner.try_read() {
+                Ok(guard) => return guard,
+                Err(TryLockError::Poisoned(e)) => return e.into_inner(),
+                Err(TryLockError::WouldBlock) => py.detach(|| drop(self.inner.read())),
+            }
+        }
+    }
+
+    /// Shared access to the core engine, from code running without the GIL.
+    ///
+    /// Never hold the guard across a call that attaches to Python (triplet scorer,
+    /// signal checks), nor take it twice on one thread: a waiting writer blocks both.
+    pub(crate) fn outfit_detached(&self) -> RwLockReadGuard<'_, Outfit> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Exclusive access to the core engine, shared with the clones (never copied), from
+    /// a thread holding the GIL. Waits without the GIL, as [`PyOutfit::outfit`].
+    pub(crate) fn inner_mut(&mut self, py: Python<'_>) -> RwLockWriteGuard<'_, Outfit> {
+        loop {
+            match self.inner.try_write() {
+                Ok(guard) => return guard,
+                Err(TryLockError::Poisoned(e)) => return e.into_inner(),
+                Err(TryLockError::WouldBlock) => py.detach(|| drop(self.inner.write())),
+            }
+        }
+    }
+
+    /// Exclusive access to the core engine, from code running without the GIL.
+    pub(crate) fn inner_mut_detached(&mut self) -> RwLockWriteGuard<'_, Outfit> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// MPC sites by code, and the code of each site for the exports.
+    pub(crate) fn mpc_sites(&self) -> &observatories::MpcSites {
+        &self.mpc_sites
+    }
+
+    /// Segments of the ephemeris within `time_range`, mapped on first use. Call it
+    /// without the GIL and without holding the engine.
+    pub(crate) fn segments(&self) -> &[(f64, f64)] {
+        self.segments.get_or_init(|| {
+            let outfit = self.outfit_detached();
+            let jpl = outfit
+                .get_jpl_ephem()
+                .expect("ephemeris loaded at construction");
+            ephemeris::segments(jpl, self.time_range)
         })
     }
+
+    /// Epochs rejected by the ephemeris coverage check.
+    ///
+    /// Return
+    /// ----------
+    /// * `None` when the check is disabled or every epoch lies strictly inside a
+    ///   segment, the offending epochs otherwise.
+    pub(crate) fn coverage_gap(
+        &self,
+        epochs: impl IntoIterator<Item = f64>,
+    ) -> Option<ephemeris::CoverageGap> {
+        if !self.check_ephemeris_coverage {
+            return None;
+        }
+        ephemeris::CoverageGap::find(self.segments(), epochs)
+    }
+
+    /// Position in `user_observers` of the site designated by a handle or a name.
+    fn user_index(&self, key: &Bound<'_, PyAny>) -> PyResult<usize> {
+        if let Ok(handle) = key.extract::<u32>() {
+            return self
+                .user_observers
+                .iter()
+                .position(|(h, _)| *h == handle)
+                .ok_or_else(|| PyKeyError::new_err(format!("no observer with handle {handle}")));
+        }
+        let name: String = key.extract().map_err(|_| {
+            PyTypeError::new_err("expected an observer handle (int) or a site name (str)")
+        })?;
+        let mut hits = self
+            .user_observers
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, o))| o.name.as_deref() == Some(name.as_str()))
+            .map(|(i, _)| i);
+        match (hits.next(), hits.next()) {
+            (Some(i), None) => Ok(i),
+            (None, _) => Err(PyKeyError::new_err(format!("no observer named {name:?}"))),
+            (Some(_), Some(_)) => Err(PyKeyError::new_err(format!(
+                "several observers are named {name:?}; use the handle returned by add_observer"
+            ))),
+        }
+    }
 }
 
 /// Python module entry-point.
@@ -287,16 +926,32 @@ fn py_outfit(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // IOD configuration and trajectory handling.
     m.add_class::<iod_params::IODParams>()?;
     m.add_class::<trajectories::TrajectorySet>()?;
+    m.add_class::<trajectories::TrajectoryIdIterator>()?;
     m.add_class::<observations::Observations>()?;
 
     // Orbit results and element sets.
     m.add_class::<GaussResult>()?;
+    m.add_class::<fit_statistics::FitStatistics>()?;
+    m.add_class::<costs::IodCosts>()?;
+    m.add_class::<results::OrbitResults>()?;
+    m.add_class::<results::ResultRow>()?;
     m.add_class::<KeplerianElements>()?;
     m.add_class::<EquinoctialElements>()?;
     m.add_class::<CometaryElements>()?;
 
+    // Export schemas.
+    m.add_function(wrap_pyfunction!(schemas::schemas, m)?)?;
+    m.add_function(wrap_pyfunction!(strict::set_strict, m)?)?;
+    m.add_function(wrap_pyfunction!(strict::strict_checks, m)?)?;
+    m.add_function(wrap_pyfunction!(orbfit::read_orbfit_elements, m)?)?;
+    m.add_function(wrap_pyfunction!(sexagesimal::parse_sexagesimal, m)?)?;
+    m.add("SCHEMA_VERSION", schemas::SCHEMA_VERSION)?;
+
     // Constants (2π, AU, Gaussian k, etc.).
     constants::register_constants(m)?;
 
+    // Custom exception types.
+    errors::register_exceptions(m)?;
+
     Ok(())
 }
@@ -0,0 +1,111 @@
+//! Memory accounting of trajectory sets.
+//!
+//! The figures are computed from the sizes of the stored types and the capacities of
+//! the containers, not sampled from the allocator:
+//!
+//! * a trajectory set is a hash map from `ObjectNumber` to `Observations`, a small
+//!   vector holding up to six observations inline (inside the map slot) before
+//!   spilling to the heap;
+//! * every observation carries its astrometry, the 2-byte index of its site in the
+//!   environment's observer registry (the registry itself belongs to the environment),
+//!   and the observer positions prepared at ingestion (geocentric and heliocentric).
+//!
+//! The map's bucket count is derived from its capacity with the growth policy of the
+//! standard library's table (`hashbrown`), one control byte per bucket plus one group.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.memory_usage` / `TrajectorySet.shrink_to_fit` – Python entry points.
+use std::mem::size_of;
+
+use nalgebra::Vector3;
+use outfit::{observations::Observation, ObjectNumber, Observations};
+
+/// Control bytes appended to a hash table after its buckets (one SIMD group).
+const TABLE_GROUP_WIDTH: usize = 16;
+
+/// Byte breakdown of a trajectory set, see [`usage`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct MemoryUsage {
+    /// Astrometry of the stored observations (angles, uncertainties, epoch, padding).
+    pub observations: usize,
+    /// Observer positions prepared at ingestion.
+    pub prepared_geometry: usize,
+    /// Site indices into the environment's observer registry.
+    pub observer_interning: usize,
+    /// Allocated but unused observation slots of heap-allocated trajectories.
+    pub spare_capacity: usize,
+    /// Hash table, unused inline slots and string keys.
+    pub trajectory_overhead: usize,
+}
+
+impl MemoryUsage {
+    /// Sum of every category.
+    pub(crate) fn total(&self) -> usize {
+        self.observations
+            + self.prepared_geometry
+            + self.observer_interning
+            + self.spare_capacity
+            + self.trajectory_overhead
+    }
+}
+
+/// Number of buckets of a hash table of usable capacity `capacity`.
+fn table_buckets(capacity: usize) -> usize {
+    match capacity {
+        0 => 0,
+        c if c < 8 => c + 1,
+        c => c / 7 * 8,
+    }
+}
+
+/// Byte breakdown of `set`.
+///
+/// Arguments
+/// -----------------
+/// * `set` – Trajectories to account for.
+///
+/// Return
+/// ----------
+/// * The [`MemoryUsage`] of the set. Observations kept inline are counted in their
+///   categories and removed from the table overhead, so that the categories do not
+///   overlap.
+pub(crate) fn usage(set: &outfit::TrajectorySet) -> MemoryUsage {
+    let obs_size = size_of::<Observation>();
+    let geometry = 2 * size_of::<Vector3<f64>>();
+    let site = size_of::<u16>();
+
+    let buckets = table_buckets(set.capacity());
+    let slot = size_of::<(ObjectNumber, Observations)>();
+    let mut usage = MemoryUsage {
+        trajectory_overhead: buckets * (slot + 1) + if buckets > 0 { TABLE_GROUP_WIDTH } else { 0 },
+        ..MemoryUsage::default()
+    };
+
+    for (key, obs) in set {
+        let n = obs.len();
+        usage.observations += n * (obs_size - geometry - site);
+        usage.prepared_geometry += n * geometry;
+        usage.observer_interning += n * site;
+        if obs.spilled() {
+            usage.spare_capacity += (obs.capacity() - n) * obs_size;
+        } else {
+            usage.trajectory_overhead -= n * obs_size;
+        }
+        if let ObjectNumber::String(s) = key {
+            usage.trajectory_overhead += s.capacity();
+        }
+    }
+    usage
+}
+
+/// Release the over-allocated capacity of `set`.
+///
+/// Trajectories short enough to fit inline move back inside the table, the others get
+/// an exact-size buffer, then the table itself is shrunk to its length.
+pub(crate) fn shrink_to_fit(set: &mut outfit::TrajectorySet) {
+    for obs in set.values_mut() {
+        obs.shrink_to_fit();
+    }
+    set.shrink_to_fit();
+}
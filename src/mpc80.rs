@@ -0,0 +1,139 @@
+//! Reader for MPC 80-column observation files holding several objects.
+//!
+//! The core reader (`TrajectoryFile::new_from_80col`) takes the designation of the
+//! first line for the whole file and panics on the first malformed record. This module
+//! parses every line on its own, in parallel, and keeps the line number of each
+//! failure so that `TrajectorySet.from_mpc_80col` can report or skip it.
+//!
+//! Field layout (1-based columns)
+//! -----------------
+//! * `1–5` – packed number; `6–12` – packed provisional designation, used when the
+//!   number is blank.
+//! * `15` – note 2: second lines of satellite and roving records (`s`, `v`) and radar
+//!   records (`R`, `r`) carry no optical position and are skipped.
+//! * `16–32` – date `YYYY MM DD.dddddd` (UTC), converted to MJD (TT).
+//! * `33–44` – RA `HH MM SS.ddd`; `45–56` – Dec `sDD MM SS.dd`.
+//! * `78–80` – observatory code.
+//!
+//! Uncertainties follow the core reader: one unit of the last digit written for RA and
+//! Dec, floored by the accuracy of the site's error model, the RA one divided by
+//! `cos(dec)`.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.from_mpc_80col` – Python entry point.
+//! * [`crate::records`] – Attachment of the records to their sites.
+//! * [`crate::file_format`] – Detection of the format by `TrajectorySet.from_file`.
+use hifitime::{Epoch, TimeScale, Unit};
+use outfit::{
+    constants::RADH,
+    conversion::{parse_dec_to_deg, parse_ra_to_deg},
+};
+use rayon::prelude::*;
+
+use crate::records::{Malformed, Record};
+
+/// Parse one line.
+///
+/// Return
+/// ----------
+/// * `Ok(None)` for blank lines and records without an optical position, the record,
+///   or a description of the first invalid field.
+fn parse_line(number: usize, line: &str) -> Result<Option<Record>, String> {
+    if line.trim().is_empty() {
+        return Ok(None);
+    }
+    if !line.is_ascii() {
+        return Err("non-ASCII characters".into());
+    }
+    if line.len() < 80 {
+        return Err(format!("expected 80 columns, got {}", line.len()));
+    }
+    if matches!(line.as_bytes()[14], b's' | b'v' | b'R' | b'r') {
+        return Ok(None);
+    }
+    let field = |from: usize, to: usize| line[from..to].trim();
+
+    let designation = match field(0, 5) {
+        "" => field(5, 12),
+        number => number,
+    };
+    if designation.is_empty() {
+        return Err("missing designation (columns 1-12)".into());
+    }
+    let (ra, ra_step) = parse_ra_to_deg(field(32, 44))
+        .filter(|(ra, _)| (0.0..360.0).contains(ra))
+        .ok_or_else(|| format!("invalid RA {:?}", field(32, 44)))?;
+    let (dec, dec_step) = parse_dec_to_deg(field(44, 56))
+        .filter(|(dec, _)| dec.abs() <= 90.0)
+        .ok_or_else(|| format!("invalid Dec {:?}", field(44, 56)))?;
+    let mjd_tt =
+        mjd_tt(field(15, 32)).ok_or_else(|| format!("invalid date {:?}", field(15, 32)))?;
+    let code = field(77, 80);
+    if code.is_empty() {
+        return Err("missing observatory code (columns 78-80)".into());
+    }
+
+    Ok(Some(Record {
+        line: number,
+        designation: designation.to_string(),
+        code: code.to_string(),
+        mjd_tt,
+        ra: ra.to_radians(),
+        dec: dec.to_radians(),
+        // The parsers return the step in hours (RA) and degrees (Dec).
+        sigma_ra: ra_step * RADH,
+        sigma_dec: dec_step.to_radians(),
+        rejected: false,
+    }))
+}
+
+/// `YYYY MM DD.dddddd` (UTC) to MJD (TT), `None` for an invalid date.
+fn mjd_tt(date: &str) -> Option<f64> {
+    let mut parts = date.split_whitespace();
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let day: f64 = day.parse().ok()?;
+    if !(1.0..32.0).contains(&day) {
+        return None;
+    }
+    let midnight = Epoch::maybe_from_gregorian(
+        year.parse().ok()?,
+        month.parse().ok()?,
+        day as u8,
+        0,
+        0,
+        0,
+        0,
+        TimeScale::UTC,
+    )
+    .ok()?;
+    Some((midnight + day.fract() * Unit::Day).to_mjd_tt_days())
+}
+
+/// Parse a whole file.
+///
+/// Return
+/// ----------
+/// * The records in file order, and the `(line number, reason)` of malformed lines.
+pub(crate) fn parse(text: &str) -> (Vec<Record>, Malformed) {
+    let lines: Vec<&str> = text.lines().collect();
+    let parsed: Vec<Result<Option<Record>, (usize, String)>> = lines
+        .par_iter()
+        .enumerate()
+        .map(|(i, line)| parse_line(i + 1, line).map_err(|e| (i + 1, e)))
+        .collect();
+
+    let mut records = Vec::with_capacity(parsed.len());
+    let mut malformed = Vec::new();
+    for p in parsed {
+        match p {
+            Ok(Some(r)) => records.push(r),
+            Ok(None) => {}
+            Err(e) => malformed.push(e),
+        }
+    }
+    (records, malformed)
+}
@@ -0,0 +1,213 @@
+//! MPC 80-column export of observations.
+//!
+//! Every observation is formatted to one fixed-width record of the classic optical
+//! layout, the inverse of the reader ([`crate::mpc80`]):
+//!
+//! * `1–5` – number, for integer ids: zero-padded below 100000, packed above
+//!   (`A0000` for 100000, `~0000` for 620000, up to 15396335);
+//! * `6–12` – designation, for string ids (at most 7 characters, no whitespace);
+//! * `15` – note 2, always `C` (CCD);
+//! * `16–32` – date `YYYY MM DD.ddddd` (UTC), the day fraction rounded to 1e-5 day;
+//! * `33–44` – RA `HH MM SS.SS`, rounded to 0.01 s;
+//! * `45–56` – Dec `sDD MM SS.S`, rounded to 0.1″;
+//! * `78–80` – observatory code.
+//!
+//! Values are rounded once in units of their last digit, so that carries propagate
+//! (`23 59 59.996` is written `00 00 00.00`, a day fraction of `.999996` moves to the
+//! next day) and a negative declination rounded to zero is written `+00 00 00.0`.
+//! Magnitudes, bands, notes and references are left blank: they are not stored.
+//!
+//! See also
+//! ------------
+//! * `Observations.to_mpc_80col` / `TrajectorySet.to_mpc_80col` – Python entry points.
+//! * `TrajectorySet.from_mpc_80col` – Reader of the files written here.
+use std::{collections::HashMap, fmt::Write};
+
+use camino::Utf8Path;
+use hifitime::{Epoch, TimeScale};
+use outfit::{observations::Observation, ObjectNumber, Observer, Outfit};
+use pyo3::{
+    exceptions::{PyOSError, PyValueError},
+    prelude::*,
+};
+
+use crate::observatories::MpcSites;
+
+/// Largest number with a packed form (`~zzzz`).
+const MAX_PACKED: u32 = 620_000 + 62u32.pow(4) - 1;
+
+/// Digits of the packed numbers, in order of value.
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Columns `1–12` for the trajectory id `id`, `ValueError` when it has no 80-column
+/// form (see the module documentation).
+pub(crate) fn designation(id: &ObjectNumber) -> PyResult<String> {
+    match id {
+        ObjectNumber::Int(n) => {
+            let n = *n;
+            let packed = match n {
+                0..100_000 => format!("{n:05}"),
+                100_000..620_000 => {
+                    format!("{}{:04}", BASE62[(n / 10_000) as usize] as char, n % 10_000)
+                }
+                620_000..=MAX_PACKED => {
+                    let mut rest = n - 620_000;
+                    let mut digits = [b'0'; 4];
+                    for d in digits.iter_mut().rev() {
+                        *d = BASE62[(rest % 62) as usize];
+                        rest /= 62;
+                    }
+                    format!("~{}", std::str::from_utf8(&digits).expect("ASCII digits"))
+                }
+                _ => {
+                    return Err(PyValueError::new_err(format!(
+                        "trajectory id {n} exceeds the largest packed MPC number \
+                         ({MAX_PACKED})"
+                    )))
+                }
+            };
+            Ok(format!("{packed:<12}"))
+        }
+        ObjectNumber::String(s) => {
+            if s.is_empty() || s.len() > 7 || !s.chars().all(|c| c.is_ascii_graphic()) {
+                return Err(PyValueError::new_err(format!(
+                    "trajectory id {s:?} is not a valid 80-column designation \
+                     (1 to 7 ASCII characters without whitespace)"
+                )));
+            }
+            Ok(format!("     {s:<7}"))
+        }
+    }
+}
+
+/// Check an observatory code for columns `78–80`, `ValueError` otherwise.
+pub(crate) fn check_code(code: &str) -> PyResult<()> {
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(PyValueError::new_err(format!(
+            "invalid MPC observatory code {code:?} (expected 3 letters or digits)"
+        )));
+    }
+    Ok(())
+}
+
+/// Gregorian date of the modified Julian day `mjd` (proleptic calendar).
+fn civil_date(mjd: i64) -> (i64, u32, u32) {
+    // Days since 0000-03-01, counted in 400-year eras.
+    let z = mjd + 678_881;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Columns `16–32`: `YYYY MM DD.ddddd` (UTC) of an MJD (TT) epoch.
+fn date(mjd_tt: f64) -> String {
+    let mjd_utc = Epoch::from_mjd_in_time_scale(mjd_tt, TimeScale::TT).to_mjd_utc_days();
+    let units = (mjd_utc * 1e5).round() as i64;
+    let (year, month, day) = civil_date(units.div_euclid(100_000));
+    format!(
+        "{year:04} {month:02} {day:02}.{:05} ",
+        units.rem_euclid(100_000)
+    )
+}
+
+/// Columns `33–44`: `HH MM SS.SS`.
+fn ra(ra: f64) -> String {
+    let cs = ((ra.to_degrees() / 15.0 * 360_000.0).round() as i64).rem_euclid(24 * 360_000);
+    format!(
+        "{:02} {:02} {:02}.{:02} ",
+        cs / 360_000,
+        cs / 6000 % 60,
+        cs / 100 % 60,
+        cs % 100
+    )
+}
+
+/// Columns `45–56`: `sDD MM SS.S`.
+fn dec(dec: f64) -> String {
+    let ds = (dec.to_degrees().abs() * 36_000.0).round() as i64;
+    let sign = if dec < 0.0 && ds > 0 { '-' } else { '+' };
+    format!(
+        "{sign}{:02} {:02} {:02}.{} ",
+        ds / 36_000,
+        ds / 600 % 60,
+        ds / 10 % 60,
+        ds % 10
+    )
+}
+
+/// One 80-column record (without line terminator).
+///
+/// Arguments
+/// -----------------
+/// * `designation` – Columns `1–12`, see [`designation`].
+/// * `code` – Observatory code, see [`check_code`].
+/// * `o` – Observation to format.
+pub(crate) fn line(designation: &str, code: &str, o: &Observation) -> String {
+    let mut out = String::with_capacity(80);
+    let _ = write!(
+        out,
+        "{designation}  C{}{}{}{:21}{code}",
+        date(o.time),
+        ra(o.ra),
+        dec(o.dec),
+        ""
+    );
+    out
+}
+
+/// Write every observation of `set` to `path`, one record per line.
+///
+/// Arguments
+/// -----------------
+/// * `set` – Trajectories to write, by increasing id, their observations in stored
+///   (time) order.
+/// * `env` – Environment holding the observer registry of the observations.
+/// * `sites` – MPC sites of the environment, giving the code of each site.
+/// * `path` – Destination file, created or truncated.
+///
+/// Return
+/// ----------
+/// * The number of records written. Raises `ValueError` when an id has no 80-column
+///   form or a site has no MPC code, and `OSError` when the file cannot be written.
+pub(crate) fn write(
+    set: &outfit::TrajectorySet,
+    env: &Outfit,
+    sites: &MpcSites,
+    path: &Utf8Path,
+) -> PyResult<usize> {
+    let mut keys: Vec<&ObjectNumber> = set.keys().collect();
+    keys.sort();
+
+    let codes = sites.site_codes(env);
+    let mut resolved: HashMap<*const Observer, Option<&str>> = HashMap::new();
+    let mut out = String::new();
+    let mut n = 0;
+    for key in keys {
+        let id = designation(key)?;
+        for o in &set[key] {
+            let site = o.get_observer(env);
+            let code = resolved
+                .entry(site as *const Observer)
+                .or_insert_with(|| codes.get(site).map(String::as_str))
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "trajectory {key}: site {:?} has no MPC code (columns 78-80 \
+                         require one)",
+                        site.name.as_deref().unwrap_or("Unnamed")
+                    ))
+                })?;
+            out.push_str(&line(&id, code, o));
+            out.push('\n');
+            n += 1;
+        }
+    }
+    std::fs::write(path, out)
+        .map_err(|e| PyOSError::new_err(format!("cannot write {path}: {e}")))?;
+    Ok(n)
+}
@@ -1,18 +1,32 @@
 // imports à compléter en haut de ton fichier trajectories.rs
-use numpy::PyArray1;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+use numpy::{PyArray1, PyArray2, PyArrayMethods};
 use pyo3::{
-    exceptions::PyIndexError,
+    exceptions::{PyIndexError, PyValueError},
     prelude::*,
-    types::{PyIterator, PyList},
+    types::{PyDict, PyIterator, PyList, PyTuple},
 };
 
-use outfit::observations::display::ObservationsDisplayExt;
-use outfit::observations::observations_ext::ObservationIOD;
+use outfit::{
+    constants::RADSEC, observations::display::ObservationsDisplayExt, observers::helio_obs_pos,
+};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 
 use crate::{
-    iod_gauss::GaussResult as PyGaussResult, iod_params::IODParams, IntoPyResult, PyOutfit,
+    fit_statistics::{residuals, with_fit_statistics},
+    ingest, iod_engine,
+    iod_gauss::GaussResult as PyGaussResult,
+    iod_params::IODParams,
+    mpc80_writer,
+    observer::Observer,
+    photometry::PhotometryTable,
+    schemas, time_scales,
+    trajectories::py_to_object_number,
+    units, IntoPyResult, PyOutfit,
 };
 
 type ObsArrays<'py> = (
@@ -23,10 +37,129 @@ type ObsArrays<'py> = (
     Bound<'py, PyArray1<f64>>,
 );
 
+type ResidualArrays<'py> = (
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+);
+
+/// NumPy object array holding `values` (`None` where missing).
+fn object_array<'py>(py: Python<'py>, values: Vec<Option<String>>) -> PyResult<Bound<'py, PyAny>> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("dtype", "object")?;
+    py.import("numpy")?
+        .getattr("array")?
+        .call((values,), Some(&kwargs))
+}
+
+/// Columns of `obs` in the order of [`schemas::observations`], angles converted for export.
+pub(crate) fn observation_columns<'a>(
+    obs: impl IntoIterator<Item = &'a outfit::observations::Observation>,
+    degrees: bool,
+) -> Vec<Vec<f64>> {
+    let columns = schemas::observations();
+    let mut out = vec![Vec::new(); columns.len()];
+    for o in obs {
+        let row = [o.time, o.ra, o.dec, o.error_ra, o.error_dec];
+        for ((col, c), v) in out.iter_mut().zip(&columns).zip(row) {
+            col.push(match c.quantity {
+                Some(q) => units::export(q, v, degrees),
+                None => v,
+            });
+        }
+    }
+    out
+}
+
+/// Epochs kept by `filter_by_time`: `[mjd_min, mjd_max]`, open on the `None` sides.
+pub(crate) fn time_window(
+    mjd_min: Option<f64>,
+    mjd_max: Option<f64>,
+) -> PyResult<RangeInclusive<f64>> {
+    if mjd_min.is_some_and(f64::is_nan) || mjd_max.is_some_and(f64::is_nan) {
+        return Err(PyValueError::new_err("mjd_min and mjd_max must not be NaN"));
+    }
+    let window = mjd_min.unwrap_or(f64::NEG_INFINITY)..=mjd_max.unwrap_or(f64::INFINITY);
+    if window.start() > window.end() {
+        return Err(PyValueError::new_err(format!(
+            "mjd_min ({}) must not exceed mjd_max ({})",
+            window.start(),
+            window.end()
+        )));
+    }
+    Ok(window)
+}
+
+/// Whether both uncertainties of `o` are within `max_sigma` (radians), see
+/// `filter_by_uncertainty`; `ValueError` on a NaN or negative threshold (arcseconds).
+pub(crate) fn sigma_filter(
+    max_sigma_arcsec: f64,
+) -> PyResult<impl Fn(&outfit::observations::Observation) -> bool + Sync> {
+    if max_sigma_arcsec.is_nan() || max_sigma_arcsec < 0.0 {
+        return Err(PyValueError::new_err(format!(
+            "max_sigma_arcsec must be a non-negative number, got {max_sigma_arcsec}"
+        )));
+    }
+    let max_sigma = max_sigma_arcsec * RADSEC;
+    Ok(move |o: &outfit::observations::Observation| {
+        o.error_ra <= max_sigma && o.error_dec <= max_sigma
+    })
+}
+
 /// Read-only Python view over a single trajectory (owning clone of observations).
 #[pyclass]
 pub struct Observations {
     pub(crate) inner: outfit::Observations, // alias de Vec<Observation>
+    /// Photometry table of the set the view was taken from (see [`crate::photometry`]).
+    pub(crate) photometry: Arc<PhotometryTable>,
+}
+
+impl From<outfit::Observations> for Observations {
+    fn from(inner: outfit::Observations) -> Self {
+        Self {
+            inner,
+            photometry: Arc::default(),
+        }
+    }
+}
+
+impl Observations {
+    /// View over `inner` sharing the photometry table of its set.
+    pub(crate) fn with_photometry(
+        inner: outfit::Observations,
+        photometry: &Arc<PhotometryTable>,
+    ) -> Self {
+        Self {
+            inner,
+            photometry: photometry.clone(),
+        }
+    }
+
+    /// `(mjd_tt, ra, dec, sigma_ra, sigma_dec)` arrays in storage order.
+    fn astrometry_arrays<'py>(&self, py: Python<'py>) -> ObsArrays<'py> {
+        let n = self.inner.len();
+        let mut mjd = Vec::with_capacity(n);
+        let mut ra = Vec::with_capacity(n);
+        let mut dec = Vec::with_capacity(n);
+        let mut sra = Vec::with_capacity(n);
+        let mut sdec = Vec::with_capacity(n);
+
+        for o in &self.inner {
+            mjd.push(o.time);
+            ra.push(o.ra);
+            dec.push(o.dec);
+            sra.push(o.error_ra);
+            sdec.push(o.error_dec);
+        }
+
+        let mjd_a = PyArray1::from_vec(py, mjd);
+        let ra_a = PyArray1::from_vec(py, ra);
+        let dec_a = PyArray1::from_vec(py, dec);
+        let sra_a = PyArray1::from_vec(py, sra);
+        let sdec_a = PyArray1::from_vec(py, sdec);
+
+        (mjd_a, ra_a, dec_a, sra_a, sdec_a)
+    }
 }
 
 #[pymethods]
@@ -160,11 +293,12 @@ impl Observations {
         let sorted = sorted.unwrap_or(false);
         let sec_prec = sec_prec.unwrap_or(3);
 
+        let outfit = env.outfit(env.py());
         let disp = self
             .inner
             .show()
             .with_seconds_precision(sec_prec)
-            .with_env(&env.inner);
+            .with_env(&outfit);
 
         if sorted {
             format!("{}", disp.sorted())
@@ -202,12 +336,13 @@ impl Observations {
         let sec_prec = sec_prec.unwrap_or(3);
         let dist_prec = dist_prec.unwrap_or(6);
 
+        let outfit = env.outfit(env.py());
         let disp = self
             .inner
             .table_wide()
             .with_seconds_precision(sec_prec)
             .with_distance_precision(dist_prec)
-            .with_env(&env.inner);
+            .with_env(&outfit);
 
         if sorted {
             format!("{}", disp.sorted())
@@ -242,11 +377,12 @@ impl Observations {
         let sorted = sorted.unwrap_or(false);
         let sec_prec = sec_prec.unwrap_or(3);
 
+        let outfit = env.outfit(env.py());
         let disp = self
             .inner
             .table_iso()
             .with_seconds_precision(sec_prec)
-            .with_env(&env.inner);
+            .with_env(&outfit);
 
         if sorted {
             format!("{}", disp.sorted())
@@ -291,30 +427,162 @@ impl Observations {
         })
     }
 
-    /// Export arrays to NumPy (rad / days).
-    fn to_numpy<'py>(&self, py: Python<'py>) -> PyResult<ObsArrays<'py>> {
-        let n = self.inner.len();
-        let mut mjd = Vec::with_capacity(n);
-        let mut ra = Vec::with_capacity(n);
-        let mut dec = Vec::with_capacity(n);
-        let mut sra = Vec::with_capacity(n);
-        let mut sdec = Vec::with_capacity(n);
+    /// Put the observations in canonical order, in place.
+    ///
+    /// The canonical order is time ascending, ties broken by RA, then Dec, then site (see
+    /// [`crate::ingest::canonical_cmp`]). Trajectories of a `TrajectorySet` are kept in
+    /// this order by every mutating operation, so views taken from a set are already
+    /// canonical; the method restores the order of a view whose history is unknown.
+    ///
+    /// Return
+    /// ----------
+    /// * `True` when the order changed.
+    fn canonicalize(&mut self) -> bool {
+        ingest::canonicalize(&mut self.inner)
+    }
 
-        for o in &self.inner {
-            mjd.push(o.time);
-            ra.push(o.ra);
-            dec.push(o.dec);
-            sra.push(o.error_ra);
-            sdec.push(o.error_dec);
+    /// Observations of a time window, as a new view.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `mjd_min`, `mjd_max`: Bounds of the window (MJD TT, inclusive); `None` leaves the
+    ///   window open on that side.
+    ///
+    /// Return
+    /// ----------
+    /// * The observations within the window (possibly none), in storage order.
+    ///
+    /// Notes
+    /// ----------
+    /// * `ValueError` on a NaN bound or when `mjd_min > mjd_max`.
+    #[pyo3(
+        signature = (mjd_min=None, mjd_max=None),
+        text_signature = "($self, mjd_min=None, mjd_max=None)"
+    )]
+    fn filter_by_time(&self, mjd_min: Option<f64>, mjd_max: Option<f64>) -> PyResult<Self> {
+        let window = time_window(mjd_min, mjd_max)?;
+        let kept = self
+            .inner
+            .iter()
+            .filter(|o| window.contains(&o.time))
+            .cloned()
+            .collect();
+        Ok(Self::with_photometry(kept, &self.photometry))
+    }
+
+    /// Observations whose RA and Dec uncertainties are both within a threshold, as a new
+    /// view.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `max_sigma_arcsec`: Largest uncertainty kept, in arcseconds.
+    ///
+    /// Return
+    /// ----------
+    /// * The observations within the threshold (possibly none), in storage order.
+    ///
+    /// Notes
+    /// ----------
+    /// * `ValueError` on a NaN or negative threshold.
+    #[pyo3(text_signature = "($self, max_sigma_arcsec)")]
+    fn filter_by_uncertainty(&self, max_sigma_arcsec: f64) -> PyResult<Self> {
+        let keep = sigma_filter(max_sigma_arcsec)?;
+        let kept = self.inner.iter().filter(|o| keep(o)).cloned().collect();
+        Ok(Self::with_photometry(kept, &self.photometry))
+    }
+
+    /// Export arrays to NumPy (rad / days).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `photometry`: Append the `mag` and `band` arrays of [`Self::magnitudes`]
+    ///   (default: `False`).
+    ///
+    /// Return
+    /// ----------
+    /// * `(mjd_tt, ra, dec, sigma_ra, sigma_dec)`, followed by `(mag, band)` with
+    ///   `photometry=True`.
+    #[pyo3(signature = (photometry=false), text_signature = "($self, photometry=False)")]
+    fn to_numpy<'py>(&self, py: Python<'py>, photometry: bool) -> PyResult<Bound<'py, PyTuple>> {
+        let arrays = self.astrometry_arrays(py);
+        if !photometry {
+            return arrays.into_pyobject(py);
         }
+        let (mjd, ra, dec, sra, sdec) = arrays;
+        let (mag, band) = self.magnitudes(py)?;
+        (mjd, ra, dec, sra, sdec, mag, band).into_pyobject(py)
+    }
 
-        let mjd_a = PyArray1::from_vec(py, mjd);
-        let ra_a = PyArray1::from_vec(py, ra);
-        let dec_a = PyArray1::from_vec(py, dec);
-        let sra_a = PyArray1::from_vec(py, sra);
-        let sdec_a = PyArray1::from_vec(py, sdec);
+    /// Magnitude and photometric band of each observation, as given at ingestion
+    /// (`mag=` / `band=` of the NumPy loaders).
+    ///
+    /// Return
+    /// ----------
+    /// * `(mag, band)`: a `float64` array (NaN where unknown) and an object array of
+    ///   `str` (`None` where unknown), in storage order. Both are all-missing for
+    ///   observations ingested without photometry.
+    fn magnitudes<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyArray1<f64>>, Bound<'py, PyAny>)> {
+        let (mag, band) = self.photometry.columns(&self.inner);
+        Ok((PyArray1::from_vec(py, mag), object_array(py, band)?))
+    }
+
+    /// Whether each observation is flagged as rejected by its ADES file (`selAst`,
+    /// `exclude`), for sets read with `include_rejected=True`.
+    ///
+    /// Return
+    /// ----------
+    /// * A `bool` array in storage order, all `False` for observations read without
+    ///   flags.
+    fn rejected<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<bool>> {
+        let flags: Vec<bool> = self
+            .inner
+            .iter()
+            .map(|o| self.photometry.is_rejected(o))
+            .collect();
+        PyArray1::from_vec(py, flags)
+    }
 
-        Ok((mjd_a, ra_a, dec_a, sra_a, sdec_a))
+    /// Export the observations as a pandas `DataFrame`, one row per observation.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `degrees`: Angles in degrees instead of radians (default: `False`).
+    ///
+    /// Return
+    /// ----------
+    /// * A `DataFrame` with the columns of [`Self::to_dict`] (`mag` and `band` included
+    ///   when the observations carry photometry).
+    #[pyo3(signature = (degrees=false), text_signature = "($self, degrees=False)")]
+    fn to_pandas<'py>(&self, py: Python<'py>, degrees: bool) -> PyResult<Bound<'py, PyAny>> {
+        let columns = self.to_dict(py, false, degrees)?;
+        py.import("pandas")?.getattr("DataFrame")?.call1((columns,))
+    }
+    /// Whether the topocentric correction applies to each observation.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` : Global environment holding the observer registry.
+    ///
+    /// Return
+    /// ----------
+    /// * A boolean array in storage order: `False` for observations whose site is at the
+    ///   geocentre (ingested with `topocentric=False`, or geocentric sites such as MPC
+    ///   code `500`), `True` otherwise.
+    #[pyo3(text_signature = "($self, env)")]
+    fn topocentric<'py>(&self, py: Python<'py>, env: &PyOutfit) -> Bound<'py, PyArray1<bool>> {
+        let outfit = env.outfit(py);
+        let flags: Vec<bool> = self
+            .inner
+            .iter()
+            .map(|o| {
+                let site = o.get_observer(&outfit);
+                site.rho_cos_phi != 0.0 || site.rho_sin_phi != 0.0
+            })
+            .collect();
+        PyArray1::from_vec(py, flags)
     }
 
     /// Return a Python list of tuples `(mjd_tt, ra_rad, dec_rad, sigma_ra, sigma_dec)`.
@@ -328,6 +596,115 @@ impl Observations {
         Ok(out)
     }
 
+    /// Export the observations as a dict of columns.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `include_units`: Add a `"units"` block mapping each column to its unit (default: `False`).
+    /// * `degrees`: Export `ra`, `dec` and the uncertainties in degrees instead of radians
+    ///   (default: `False`). The units block is then always included.
+    ///
+    /// Return
+    /// ----------
+    /// * A dict with the list columns `"mjd_tt"`, `"ra"`, `"dec"`, `"sigma_ra"`,
+    ///   `"sigma_dec"`, then `"mag"` and `"band"` (`None` where unknown) when at least one
+    ///   observation carries photometry, and `"units"` when requested.
+    #[pyo3(
+        signature = (include_units=false, degrees=false),
+        text_signature = "(self, include_units=False, degrees=False)"
+    )]
+    fn to_dict<'py>(
+        &self,
+        py: Python<'py>,
+        include_units: bool,
+        degrees: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let columns = schemas::observations();
+        let d = PyDict::new(py);
+        for (c, values) in columns
+            .iter()
+            .zip(observation_columns(&self.inner, degrees))
+        {
+            d.set_item(c.name, values)?;
+        }
+        if self.photometry.covers(&self.inner) {
+            let (mag, band) = self.photometry.columns(&self.inner);
+            let mag: Vec<Option<f64>> = mag
+                .into_iter()
+                .map(|m| (!m.is_nan()).then_some(m))
+                .collect();
+            d.set_item("mag", mag)?;
+            d.set_item("band", band)?;
+        }
+        if include_units || degrees {
+            d.set_item(
+                "units",
+                units::units_block(py, &schemas::unit_fields(&columns), degrees)?,
+            )?;
+        }
+        Ok(d)
+    }
+
+    /// Serialize the observations to a JSON string.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `include_units`: Embed the `"units"` block (default: `True`).
+    /// * `degrees`: Export angles in degrees instead of radians (default: `False`).
+    /// * `indent`: Indentation passed to `json.dumps` (default: compact output).
+    ///
+    /// Return
+    /// ----------
+    /// * The JSON form of `to_dict(include_units, degrees)`.
+    #[pyo3(
+        signature = (include_units=true, degrees=false, indent=None),
+        text_signature = "(self, include_units=True, degrees=False, indent=None)"
+    )]
+    fn to_json(
+        &self,
+        py: Python<'_>,
+        include_units: bool,
+        degrees: bool,
+        indent: Option<usize>,
+    ) -> PyResult<String> {
+        units::dumps(&self.to_dict(py, include_units, degrees)?, indent)
+    }
+
+    /// Format the observations as MPC 80-column records.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `designation`: Identifier of the object: an integer is written as a number in
+    ///   columns 1–5 (packed from 100000), a string of at most 7 characters as is in
+    ///   columns 6–12.
+    /// * `observer`: MPC code of the observing site (columns 78–80).
+    ///
+    /// Return
+    /// ----------
+    /// * One 80-character line per observation, in storage order (no line terminator).
+    ///
+    /// Notes
+    /// ----------
+    /// * Dates are written in UTC as `YYYY MM DD.ddddd`, RA as `HH MM SS.SS` and Dec as
+    ///   `sDD MM SS.S`, each rounded to its last digit with carries propagated. Note 2
+    ///   is `C` (CCD); magnitude, band and reference columns are left blank.
+    /// * Raises `ValueError` for a designation without an 80-column form or an invalid
+    ///   observatory code.
+    #[pyo3(text_signature = "($self, designation, observer)")]
+    fn to_mpc_80col(
+        &self,
+        designation: &Bound<'_, PyAny>,
+        observer: &str,
+    ) -> PyResult<Vec<String>> {
+        let designation = mpc80_writer::designation(&py_to_object_number(designation)?)?;
+        mpc80_writer::check_code(observer)?;
+        Ok(self
+            .inner
+            .iter()
+            .map(|o| mpc80_writer::line(&designation, observer, o))
+            .collect())
+    }
+
     /// Estimate the best orbit for this single set of observations.
     ///
     /// Arguments
@@ -342,13 +719,18 @@ impl Observations {
     /// ----------
     /// (GaussResult, float)
     ///     The best preliminary or corrected orbit found by the engine and its RMS score
-    ///     evaluated over the selected arc. The RMS is expressed in radians.
+    ///     evaluated over the selected arc. The RMS is dimensionless: residuals are
+    ///     normalized by the astrometric uncertainties. The returned `GaussResult` carries
+    ///     `fit_statistics` (RMS in arcsec per coordinate, χ²) computed over the whole arc,
+    ///     and `costs` when `params.collect_costs` is enabled.
     ///
     /// Notes
     /// ----------
     /// The method mirrors the batch API used by `TrajectorySet.estimate_all_orbits` but operates
     /// on a single trajectory. Internally it applies batch RMS corrections, generates feasible
     /// triplets, samples noisy realizations, and returns the lowest-RMS candidate.
+    /// When `params.triplet_scorer` is set, it replaces the built-in triplet ranking and
+    /// any exception it raises is re-raised as `TripletScorerError`.
     #[pyo3(text_signature = "($self, env, params, seed=None)")]
     pub fn estimate_best_orbit(
         &mut self,
@@ -363,18 +745,154 @@ impl Observations {
             None => StdRng::from_os_rng(),
         };
 
-        // Heavy computation without the GIL
-        let res = py.detach(|| {
-            self.inner.estimate_best_orbit(
-                &env.inner,
-                &env.inner.error_model,
-                &mut rng,
-                &params.inner,
-            )
-        });
-
-        // Map OutfitError -> PyErr and convert result to Python wrappers
-        let (g, rms) = res.into_py()?;
-        Ok((PyGaussResult::from(g), rms))
+        // Heavy computation without the GIL (the triplet scorer re-acquires it when set)
+        let (res, diagnostics) =
+            py.detach(
+                || match env.coverage_gap(self.inner.iter().map(|o| o.time)) {
+                    Some(gap) => (
+                        Err(iod_engine::IodError::Coverage(gap)),
+                        iod_engine::Diagnostics::default(),
+                    ),
+                    None => iod_engine::estimate_best_orbit_costed(
+                        &mut self.inner,
+                        env,
+                        &mut rng,
+                        params,
+                    ),
+                },
+            );
+
+        // Map IodError -> PyErr and convert result to Python wrappers
+        let (g, rms) = res.map_err(iod_engine::IodError::into_pyerr)?;
+        let mut g = with_fit_statistics(&self.inner, &env.outfit(py), g)
+            .with_subsample(&self.inner, params)
+            .at_output_epoch(&self.inner, params.output_epoch)
+            .into_py()?;
+        g.costs = diagnostics.costs;
+        Ok((g, rms))
+    }
+
+    /// Residuals of this trajectory against an orbit, in arcseconds.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` : Global environment providing ephemerides and observers.
+    /// * `orbit` : Orbit to evaluate (e.g. the result of `estimate_best_orbit`).
+    ///
+    /// Returns
+    /// ----------
+    /// (numpy.ndarray, numpy.ndarray, numpy.ndarray)
+    ///     `(mjd_tt, ra_arcsec, dec_arcsec)` with one entry per observation, in storage order.
+    ///     Residuals are `observed − computed`; the RA residual is projected on the sky
+    ///     (`ΔRA · cos(dec)`), with `ΔRA` wrapped to `[-180°, 180°]`.
+    ///
+    /// Notes
+    /// ----------
+    /// These are the residuals aggregated by `GaussResult.fit_statistics`:
+    /// `rms_ra_arcsec = sqrt(mean(ra_arcsec²))`, `rms_dec_arcsec = sqrt(mean(dec_arcsec²))`
+    /// and `rms_arcsec² = (rms_ra_arcsec² + rms_dec_arcsec²) / 2`.
+    #[pyo3(text_signature = "($self, env, orbit)")]
+    pub fn residuals<'py>(
+        &self,
+        py: Python<'py>,
+        env: &PyOutfit,
+        orbit: &PyGaussResult,
+    ) -> PyResult<ResidualArrays<'py>> {
+        let res = py
+            .detach(|| residuals(&self.inner, &env.outfit_detached(), &orbit.inner))
+            .into_py()?;
+
+        let mjd: Vec<f64> = self.inner.iter().map(|o| o.time).collect();
+        let ra: Vec<f64> = res.iter().map(|r| r.ra / RADSEC).collect();
+        let dec: Vec<f64> = res.iter().map(|r| r.dec / RADSEC).collect();
+
+        Ok((
+            PyArray1::from_vec(py, mjd),
+            PyArray1::from_vec(py, ra),
+            PyArray1::from_vec(py, dec),
+        ))
+    }
+
+    /// Per-observation geometry used by the Gauss IOD, without the solver.
+    ///
+    /// Exposes the inputs the internal IOD builds its triplets from, so that other
+    /// methods (Gooding, double-r, …) can be prototyped on the same conventions.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` : Global environment providing ephemerides and Earth orientation.
+    /// * `observer` : Site to recompute the observer positions for. With `None` (default),
+    ///   the positions computed at ingestion for each observation's own site are returned,
+    ///   which are exactly those used by the IOD.
+    ///
+    /// Returns
+    /// ----------
+    /// dict[str, numpy.ndarray]
+    ///     * `"observer_position"`: `(n, 3)` heliocentric observer positions (AU).
+    ///     * `"los"`: `(n, 3)` unit line-of-sight vectors from RA/DEC.
+    ///     * `"mjd_tt"`: `(n,)` epochs on the TT axis, as used internally.
+    ///     * `"mjd_tdb"`: `(n,)` the same epochs in TDB.
+    ///
+    /// Notes
+    /// ----------
+    /// * Vectors are in the equatorial mean J2000 frame, rows in storage order.
+    /// * No light-time correction is applied: the Gauss solver shifts the reference epoch
+    ///   by `rho/c` once the topocentric distance is known.
+    #[pyo3(
+        signature = (env, observer=None),
+        text_signature = "($self, env, observer=None)"
+    )]
+    pub fn geometry<'py>(
+        &self,
+        py: Python<'py>,
+        env: &PyOutfit,
+        observer: Option<&Observer>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let n = self.inner.len();
+        let positions: Vec<Vector3<f64>> = match observer {
+            None => self
+                .inner
+                .iter()
+                .map(|o| o.get_observer_helio_position())
+                .collect(),
+            Some(site) => {
+                let times: Vec<f64> = self.inner.iter().map(|o| o.time).collect();
+                py.detach(|| {
+                    let outfit = env.outfit_detached();
+                    let sites = [site.inner.as_ref(); 3];
+                    let mut out = Vec::with_capacity(n);
+                    for chunk in times.chunks(3) {
+                        // Pad the last chunk by repeating its final epoch.
+                        let t = |k: usize| chunk[k.min(chunk.len() - 1)];
+                        let m = helio_obs_pos(sites, &Vector3::new(t(0), t(1), t(2)), &outfit)?;
+                        out.extend(m.column_iter().take(chunk.len()).map(|c| c.into_owned()));
+                    }
+                    Ok::<_, outfit::OutfitError>(out)
+                })
+                .into_py()?
+            }
+        };
+
+        let flat = |v: Vec<Vector3<f64>>| -> PyResult<Bound<'py, PyArray2<f64>>> {
+            let data: Vec<f64> = v.iter().flat_map(|p| [p.x, p.y, p.z]).collect();
+            PyArray1::from_vec(py, data).reshape([n, 3])
+        };
+        let los: Vec<Vector3<f64>> = self
+            .inner
+            .iter()
+            .map(|o| {
+                let cos_dec = o.dec.cos();
+                Vector3::new(o.ra.cos() * cos_dec, o.ra.sin() * cos_dec, o.dec.sin())
+            })
+            .collect();
+        let mjd_tt: Vec<f64> = self.inner.iter().map(|o| o.time).collect();
+        let mjd_tdb: Vec<f64> = mjd_tt.iter().map(|&t| time_scales::tt_to_tdb(t)).collect();
+
+        let d = PyDict::new(py);
+        d.set_item("observer_position", flat(positions)?)?;
+        d.set_item("los", flat(los)?)?;
+        d.set_item("mjd_tt", PyArray1::from_vec(py, mjd_tt))?;
+        d.set_item("mjd_tdb", PyArray1::from_vec(py, mjd_tdb))?;
+        Ok(d)
     }
 }
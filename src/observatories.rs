@@ -0,0 +1,405 @@
+//! Deterministic, filterable observatory listings.
+//!
+//! The core `Outfit` registry renders observatories in hash order and only through
+//! its `Display` implementation. This module builds the listing used by
+//! [`crate::PyOutfit::show_observatories`]: user-added sites first (registration
+//! order), then MPC sites sorted by code, optionally filtered and truncated.
+//!
+//! Numbers are formatted independently of the locale and units are spelled in ASCII
+//! (`deg` instead of `°`), so the listing prints on consoles with a non-UTF-8 locale
+//! (site names are shown as registered).
+//!
+//! The MPC sites are listed from a binding-side table ([`MpcSites`]), which also gives
+//! the exports the code of each site. The proximity search of
+//! [`crate::PyOutfit::nearest_observatories`] and the JSON records of
+//! `PyOutfit.export_user_observers` live here too.
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+use outfit::{Observer, Outfit};
+use pyo3::{
+    exceptions::{PyKeyError, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+
+use crate::ephemeris;
+
+/// One line of the listing.
+struct SiteRow<'a> {
+    code: Option<&'a str>,
+    observer: &'a Observer,
+}
+
+impl SiteRow<'_> {
+    fn name(&self) -> &str {
+        self.observer.name.as_deref().unwrap_or("Unnamed")
+    }
+
+    /// Case-insensitive substring match against code and name.
+    fn matches(&self, needle: &str) -> bool {
+        self.code.is_some_and(|c| c.to_lowercase().contains(needle))
+            || self.name().to_lowercase().contains(needle)
+    }
+
+    fn write_to(&self, out: &mut String) {
+        let (lat, height_m) = self.observer.geodetic_lat_height_wgs84();
+        let _ = match self.code {
+            Some(code) => write!(out, "  {code}  "),
+            None => write!(out, "  "),
+        };
+        let _ = writeln!(
+            out,
+            "{} (lon: {:.6} deg, lat: {:.6} deg, elev: {:.2} m)",
+            self.name(),
+            self.observer.longitude.into_inner(),
+            lat,
+            height_m
+        );
+    }
+}
+
+/// First characters of the MPC observatory codes: a code is one digit or capital
+/// letter followed by two digits (`"500"`, `"I41"`).
+const CODE_PREFIXES: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// MPC sites of an environment, by code, kept on the binding side.
+///
+/// The core resolves one code at a time (and panics on an unknown one), without
+/// listing its table. The table is enumerated once, by resolving every code allowed by
+/// the MPC code syntax; the code through which each site is resolved by the binding
+/// (ingestion, `get_observer_from_mpc_code`) is recorded for the exports.
+#[derive(Default)]
+pub struct MpcSites {
+    table: OnceLock<BTreeMap<String, Arc<Observer>>>,
+    /// Code each site was first resolved with.
+    resolved: Mutex<HashMap<Observer, String>>,
+}
+
+impl MpcSites {
+    /// MPC sites sorted by code, enumerated on first use.
+    ///
+    /// The core loads its MPC table on first use (network access).
+    pub(crate) fn table(&self, outfit: &Outfit) -> &BTreeMap<String, Arc<Observer>> {
+        self.table.get_or_init(|| {
+            // Geocentre: present in every MPC table, forces its lazy loading outside
+            // the probes, so that a failed download is reported as such.
+            outfit.get_observer_from_mpc_code(&"500".to_string());
+            CODE_PREFIXES
+                .iter()
+                .flat_map(|&c| (0..100).map(move |n| format!("{}{n:02}", c as char)))
+                .filter_map(|code| {
+                    let site = ephemeris::probe(|| outfit.get_observer_from_mpc_code(&code))?;
+                    Some((code, site))
+                })
+                .collect()
+        })
+    }
+
+    /// The table, when it has already been enumerated (no network access).
+    pub(crate) fn loaded(&self) -> Option<&BTreeMap<String, Arc<Observer>>> {
+        self.table.get()
+    }
+
+    /// Site of an MPC code, `None` for an unknown code.
+    ///
+    /// The code is recorded as the one of the site unless the site was resolved with
+    /// another code before.
+    pub(crate) fn resolve(&self, outfit: &Outfit, code: &str) -> Option<Arc<Observer>> {
+        let site = self.table(outfit).get(code)?;
+        self.record(site, code);
+        Some(site.clone())
+    }
+
+    /// Record `code` as the one of `site`, unless another code was recorded before.
+    fn record(&self, site: &Observer, code: &str) {
+        self.resolved
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(site.clone())
+            .or_insert_with(|| code.to_string());
+    }
+
+    /// MPC code of every site, keyed by site (reverse lookup for the exports).
+    ///
+    /// A site listed under several codes gets the code it was resolved with; when it
+    /// was never resolved by the binding (e.g. ingested by `new_from_mpc_80col`, which
+    /// resolves the codes in the core) it has no code.
+    pub(crate) fn site_codes(&self, outfit: &Outfit) -> HashMap<Observer, String> {
+        self.codes_in(self.table(outfit))
+    }
+
+    /// Reverse lookup of `table`, see [`MpcSites::site_codes`].
+    fn codes_in(&self, table: &BTreeMap<String, Arc<Observer>>) -> HashMap<Observer, String> {
+        let mut codes: HashMap<Observer, Option<String>> = HashMap::new();
+        for (code, site) in table {
+            codes
+                .entry(site.as_ref().clone())
+                .and_modify(|c| *c = None)
+                .or_insert_with(|| Some(code.clone()));
+        }
+        let resolved = self.resolved.lock().unwrap_or_else(PoisonError::into_inner);
+        codes
+            .into_iter()
+            .filter_map(|(site, code)| {
+                let code = resolved.get(&site).cloned().or(code)?;
+                Some((site, code))
+            })
+            .collect()
+    }
+}
+
+/// Render the observatory listing.
+///
+/// Arguments
+/// -----------------
+/// * `user` – Sites added through `PyOutfit.add_observer`, in registration order.
+/// * `mpc` – MPC sites as `(code, observer)`, sorted by code.
+/// * `filter` – Optional case-insensitive substring matched against code and name.
+/// * `limit` – Optional maximum number of sites to print.
+///
+/// Return
+/// ----------
+/// * The formatted listing, ending with a note when rows were truncated.
+pub(crate) fn render(
+    user: &[Arc<Observer>],
+    mpc: &[(String, Arc<Observer>)],
+    filter: Option<&str>,
+    limit: Option<usize>,
+) -> String {
+    let needle = filter.map(str::to_lowercase);
+    let keep = |row: &SiteRow<'_>| needle.as_deref().is_none_or(|n| row.matches(n));
+
+    let user_rows: Vec<SiteRow<'_>> = user
+        .iter()
+        .map(|o| SiteRow {
+            code: None,
+            observer: o,
+        })
+        .filter(keep)
+        .collect();
+    let mpc_rows: Vec<SiteRow<'_>> = mpc
+        .iter()
+        .map(|(c, o)| SiteRow {
+            code: Some(c),
+            observer: o,
+        })
+        .filter(keep)
+        .collect();
+
+    let mut out = String::new();
+    if user.is_empty() && mpc.is_empty() {
+        out.push_str("No observatories to show.\n");
+        return out;
+    }
+    if user_rows.is_empty() && mpc_rows.is_empty() {
+        let _ = writeln!(
+            out,
+            "No observatories match filter '{}'.",
+            filter.unwrap_or_default()
+        );
+        return out;
+    }
+
+    let total = user_rows.len() + mpc_rows.len();
+    let mut budget = limit.unwrap_or(usize::MAX);
+    for (title, rows) in [
+        ("User-defined observers:", &user_rows),
+        ("MPC observers:", &mpc_rows),
+    ] {
+        if rows.is_empty() || budget == 0 {
+            continue;
+        }
+        let _ = writeln!(out, "{title}");
+        for row in rows.iter().take(budget) {
+            row.write_to(&mut out);
+        }
+        budget = budget.saturating_sub(rows.len());
+    }
+
+    let shown = limit.map_or(total, |l| l.min(total));
+    if shown < total {
+        let _ = writeln!(
+            out,
+            "... {} more observatories (pass limit=None to show all)",
+            total - shown
+        );
+    }
+    out
+}
+
+/// Mean Earth radius (IUGG), in km, for great-circle distances.
+const MEAN_EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// Great-circle distance between two geodetic positions, in km (heights ignored).
+fn great_circle_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = phi2 - phi1;
+    let dlambda = (lon2 - lon1).to_radians();
+    let h = (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+    2.0 * MEAN_EARTH_RADIUS_KM * h.sqrt().min(1.0).asin()
+}
+
+/// Sites of `mpc` within `max_distance_km` of a geodetic position, nearest first.
+///
+/// MPC sites are stored as parallax constants `(ρ·cosφ, ρ·sinφ)`; their geodetic
+/// latitude is recovered on the WGS-84 ellipsoid. Sites without a ground position
+/// (geocentre, space telescopes, roving observers: both constants zero) are skipped.
+///
+/// Arguments
+/// -----------------
+/// * `mpc` – MPC sites as `(code, observer)`.
+/// * `latitude_deg`, `longitude_deg` – Geodetic position (degrees, east positive).
+/// * `max_distance_km` – Search radius.
+/// * `limit` – Optional maximum number of sites returned.
+///
+/// Return
+/// ----------
+/// * `(observer, distance_km)` pairs sorted by distance, ties broken by MPC code.
+pub(crate) fn nearest(
+    mpc: &[(String, Arc<Observer>)],
+    latitude_deg: f64,
+    longitude_deg: f64,
+    max_distance_km: f64,
+    limit: Option<usize>,
+) -> Vec<(Arc<Observer>, f64)> {
+    let mut hits: Vec<(&str, Arc<Observer>, f64)> = mpc
+        .iter()
+        .filter(|(_, o)| *o.rho_cos_phi != 0.0 || *o.rho_sin_phi != 0.0)
+        .filter_map(|(code, o)| {
+            let (lat, _) = o.geodetic_lat_height_wgs84();
+            let d = great_circle_km(latitude_deg, longitude_deg, lat, o.longitude.into_inner());
+            (d <= max_distance_km).then(|| (code.as_str(), o.clone(), d))
+        })
+        .collect();
+    hits.sort_by(|a, b| a.2.total_cmp(&b.2).then_with(|| a.0.cmp(b.0)));
+    hits.into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|(_, o, d)| (o, d))
+        .collect()
+}
+
+/// Tag of the user-observer JSON documents written by `PyOutfit.export_user_observers`.
+pub(crate) const USER_OBSERVERS_FORMAT: &str = "py_outfit.user_observers";
+/// Version of the user-observer JSON layout.
+pub(crate) const USER_OBSERVERS_VERSION: u32 = 1;
+
+/// JSON record of a user-registered site.
+///
+/// The parallax constants and the longitude define the site exactly; the geodetic
+/// latitude and elevation are informative (recomputed on the WGS-84 ellipsoid).
+pub(crate) fn site_record<'py>(
+    py: Python<'py>,
+    handle: u32,
+    site: &Observer,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (latitude, elevation_m) = site.geodetic_lat_height_wgs84();
+    let d = PyDict::new(py);
+    d.set_item("handle", handle)?;
+    d.set_item("name", site.name.as_deref())?;
+    d.set_item("longitude_deg", site.longitude.into_inner())?;
+    d.set_item("latitude_deg", latitude)?;
+    d.set_item("elevation_m", elevation_m)?;
+    d.set_item("rho_cos_phi", site.rho_cos_phi.into_inner())?;
+    d.set_item("rho_sin_phi", site.rho_sin_phi.into_inner())?;
+    d.set_item("ra_accuracy", site.ra_accuracy.map(|v| v.into_inner()))?;
+    d.set_item("dec_accuracy", site.dec_accuracy.map(|v| v.into_inner()))?;
+    Ok(d)
+}
+
+/// Rebuild a site from a record written by [`site_record`].
+///
+/// Arguments
+/// -----------------
+/// * `record` – Mapping with `longitude_deg`, `rho_cos_phi`, `rho_sin_phi` and,
+///   optionally, `name`, `ra_accuracy` and `dec_accuracy` (`None` when absent).
+/// * `position` – Position of the record in the file, for error messages.
+///
+/// Return
+/// ----------
+/// * The site, `KeyError` when a required key is missing, `ValueError` for a value of
+///   the wrong type or a non-finite number.
+pub(crate) fn site_from_record(record: &Bound<'_, PyAny>, position: usize) -> PyResult<Observer> {
+    let context = |key: &str| format!("observers[{position}][{key:?}]");
+    let required = |key: &str| -> PyResult<f64> {
+        let v = record
+            .get_item(key)
+            .map_err(|_| PyKeyError::new_err(format!("missing key {}", context(key))))?;
+        let x: f64 = v.extract().map_err(|_| {
+            PyValueError::new_err(format!("{} must be a number, got {v}", context(key)))
+        })?;
+        if !x.is_finite() {
+            return Err(PyValueError::new_err(format!(
+                "{} must be finite, got {x}",
+                context(key)
+            )));
+        }
+        Ok(x)
+    };
+    let optional = |key: &str| -> PyResult<Option<Bound<'_, PyAny>>> {
+        Ok(record.get_item(key).ok().filter(|v| !v.is_none()))
+    };
+    let accuracy = |key: &str| -> PyResult<Option<f64>> {
+        optional(key)?
+            .map(|v| {
+                v.extract::<f64>().map_err(|_| {
+                    PyValueError::new_err(format!("{} must be a number, got {v}", context(key)))
+                })
+            })
+            .transpose()
+    };
+    let name = optional("name")?
+        .map(|v| {
+            v.extract::<String>().map_err(|_| {
+                PyValueError::new_err(format!("{} must be a string, got {v}", context("name")))
+            })
+        })
+        .transpose()?;
+
+    Observer::from_parallax(
+        required("longitude_deg")?,
+        required("rho_cos_phi")?,
+        required("rho_sin_phi")?,
+        name,
+        accuracy("ra_accuracy")?,
+        accuracy("dec_accuracy")?,
+    )
+    .map_err(|e| PyValueError::new_err(format!("observers[{position}]: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site(name: &str, longitude: f64) -> Arc<Observer> {
+        Arc::new(
+            Observer::new(longitude, 20.7, 3000.0, Some(name.to_string()), None, None).unwrap(),
+        )
+    }
+
+    #[test]
+    fn site_listed_under_two_codes() {
+        let shared = site("Shared dome", 203.7);
+        let table: BTreeMap<String, Arc<Observer>> = [
+            ("F51".to_string(), shared.clone()),
+            ("F52".to_string(), site("Single dome", 203.8)),
+            ("T05".to_string(), shared.clone()),
+        ]
+        .into_iter()
+        .collect();
+        let sites = MpcSites::default();
+
+        // Ambiguous until resolved: neither code is guessed.
+        let codes = sites.codes_in(&table);
+        assert_eq!(codes.get(&*shared), None);
+        assert_eq!(codes.get(&*table["F52"]).map(String::as_str), Some("F52"));
+
+        // The code the site was first resolved with wins, whichever sorts first.
+        sites.record(&shared, "T05");
+        sites.record(&shared, "F51");
+        let codes = sites.codes_in(&table);
+        assert_eq!(codes.get(&*shared).map(String::as_str), Some("T05"));
+        assert_eq!(codes.len(), 2);
+    }
+}
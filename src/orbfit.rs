@@ -0,0 +1,405 @@
+//! OrbFit orbital element files (OEF2.0, `.eq0` / `.oel`).
+//!
+//! OrbFit exchanges orbits as multi-line records: a designation line, one element
+//! record (`KEP` or `EQU`), the epoch (`MJD … TDT`), then optional records (`MAG`,
+//! `LSP`, `COV`, `COR`, `NOR`, …). Comment lines start with `!`. A file may open with a
+//! `key = value` header closed by `END_OF_HEADER`.
+//!
+//! Conventions
+//! -----------------
+//! * `KEP`: `a` (AU), `e`, `i`, `Ω`, `ω`, `M` (degrees).
+//! * `EQU`: `a` (AU), `h = e·sin(ϖ)`, `k = e·cos(ϖ)`, `p = tan(i/2)·sin(Ω)`,
+//!   `q = tan(i/2)·cos(Ω)`, `λ` (degrees).
+//! * Epochs are MJD in TT (`TDT`); the element objects of the bindings hold TDB, the
+//!   `TDB − TT` offset (≤ 1.7 ms) is applied on both ways.
+//! * `COV` records hold the 21 upper-triangle terms of the covariance, row by row, in
+//!   the units of the element record. They are returned in the units of the bindings
+//!   (angles in radians).
+//! * Only the ecliptic mean J2000 frame (`refsys = ECLM J2000`, OrbFit's default) is
+//!   read, as used by the core.
+//!
+//! See also
+//! ------------
+//! * `KeplerianElements.to_orbfit_string`, `EquinoctialElements.to_orbfit_string` –
+//!   Writers.
+//! * [`read_orbfit_elements`] – Reader.
+use std::{collections::HashSet, path::PathBuf};
+
+use numpy::{PyArray1, PyArrayMethods};
+use outfit::{EquinoctialElements as RsEquinoctial, KeplerianElements as RsKeplerian};
+use pyo3::{
+    exceptions::{PyOSError, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+
+use crate::{
+    orbit_type::{equinoctial::EquinoctialElements, keplerian::KeplerianElements},
+    time_scales,
+};
+
+/// Header written in front of the records.
+const HEADER: &str = "format  = 'OEF2.0'       ! file format
+rectype = 'ML'           ! record type (1L/ML)
+refsys  = ECLM J2000     ! default reference system
+END_OF_HEADER
+";
+
+/// Reference system supported by the reader.
+const REFSYS: &str = "ECLM J2000";
+
+/// One degree in radians, scale of the angular covariance terms.
+const DEGREE: f64 = std::f64::consts::PI / 180.0;
+
+/// Number of terms of a `COV` block (upper triangle of a 6×6 matrix).
+const COV_TERMS: usize = 21;
+
+/// Element record of an object.
+pub(crate) enum Elements {
+    Keplerian(RsKeplerian),
+    Equinoctial(RsEquinoctial),
+}
+
+/// One object read from a file.
+pub(crate) struct Entry {
+    pub(crate) designation: String,
+    pub(crate) elements: Elements,
+    /// Covariance in the units of the bindings, when the record has a `COV` block.
+    pub(crate) covariance: Option<[[f64; 6]; 6]>,
+}
+
+/// Angle in degrees, in `[0, 360)`.
+fn degrees(angle: f64) -> f64 {
+    angle.to_degrees().rem_euclid(360.0)
+}
+
+/// Format one object as an OEF2.0 multi-line record.
+///
+/// Arguments
+/// -----------------
+/// * `designation` – Object name, without whitespace.
+/// * `header` – Prepend the file header, so that the text is a complete file.
+/// * `kind` – Element record keyword (`KEP`, `EQU`) and its comment line.
+/// * `values` – The six elements in the units of the record.
+/// * `epoch_tdb` – Reference epoch (MJD TDB).
+fn format_record(
+    designation: &str,
+    header: bool,
+    (kind, comment): (&str, &str),
+    values: [f64; 6],
+    epoch_tdb: f64,
+) -> PyResult<String> {
+    if designation.is_empty() || designation.contains(char::is_whitespace) {
+        return Err(PyValueError::new_err(format!(
+            "OrbFit designations cannot be empty or contain whitespace, got {designation:?}"
+        )));
+    }
+    let mut out = String::new();
+    if header {
+        out.push_str(HEADER);
+    }
+    out.push_str(&format!("{designation}\n! {comment}\n {kind} "));
+    for (i, v) in values.iter().enumerate() {
+        // Fortran list-directed reads accept any fixed notation; 15 decimals keep the
+        // full double precision of a and of the dimensionless elements.
+        if kind == "EQU" && i < 5 || kind == "KEP" && i < 2 {
+            out.push_str(&format!(" {v:>22.15}"));
+        } else {
+            out.push_str(&format!(" {v:>19.12}"));
+        }
+    }
+    out.push_str(&format!(
+        "\n MJD {:>20.9} TDT\n",
+        time_scales::tdb_to_tt(epoch_tdb)
+    ));
+    Ok(out)
+}
+
+/// OEF2.0 record of Keplerian elements (see [`format_record`]).
+pub(crate) fn keplerian_record(
+    k: &RsKeplerian,
+    designation: &str,
+    header: bool,
+) -> PyResult<String> {
+    format_record(
+        designation,
+        header,
+        (
+            "KEP",
+            "Keplerian elements: a, e, i, long. node, arg. peric., mean anomaly",
+        ),
+        [
+            k.semi_major_axis,
+            k.eccentricity,
+            degrees(k.inclination),
+            degrees(k.ascending_node_longitude),
+            degrees(k.periapsis_argument),
+            degrees(k.mean_anomaly),
+        ],
+        k.reference_epoch,
+    )
+}
+
+/// OEF2.0 record of equinoctial elements (see [`format_record`]).
+pub(crate) fn equinoctial_record(
+    q: &RsEquinoctial,
+    designation: &str,
+    header: bool,
+) -> PyResult<String> {
+    format_record(
+        designation,
+        header,
+        (
+            "EQU",
+            "Equinoctial elements: a, e*sin(LP), e*cos(LP), tan(i/2)*sin(LN), \
+             tan(i/2)*cos(LN), mean long.",
+        ),
+        [
+            q.semi_major_axis,
+            q.eccentricity_sin_lon,
+            q.eccentricity_cos_lon,
+            q.tan_half_incl_sin_node,
+            q.tan_half_incl_cos_node,
+            degrees(q.mean_longitude),
+        ],
+        q.reference_epoch,
+    )
+}
+
+/// Fortran real (`1.5`, `1.5E+00`, `1.5D+00`).
+fn real(token: &str) -> Option<f64> {
+    token.replace(['D', 'd'], "E").parse().ok()
+}
+
+/// Object being read, until its next designation line.
+struct Pending {
+    line: usize,
+    designation: String,
+    record: Option<(&'static str, [f64; 6])>,
+    epoch_tt: Option<f64>,
+    cov: Vec<f64>,
+}
+
+impl Pending {
+    fn finish(self) -> Result<Entry, String> {
+        let Pending {
+            line,
+            designation,
+            record,
+            epoch_tt,
+            cov,
+        } = self;
+        let at = |what: &str| format!("line {line}: object {designation:?} {what}");
+        let (kind, v) = record.ok_or_else(|| at("has no KEP or EQU record"))?;
+        let epoch = time_scales::tt_to_tdb(epoch_tt.ok_or_else(|| at("has no MJD epoch"))?);
+        let (elements, scale) = match kind {
+            "KEP" => (
+                Elements::Keplerian(RsKeplerian {
+                    reference_epoch: epoch,
+                    semi_major_axis: v[0],
+                    eccentricity: v[1],
+                    inclination: v[2].to_radians(),
+                    ascending_node_longitude: v[3].to_radians(),
+                    periapsis_argument: v[4].to_radians(),
+                    mean_anomaly: v[5].to_radians(),
+                }),
+                [1.0, 1.0, DEGREE, DEGREE, DEGREE, DEGREE],
+            ),
+            _ => (
+                Elements::Equinoctial(RsEquinoctial {
+                    reference_epoch: epoch,
+                    semi_major_axis: v[0],
+                    eccentricity_sin_lon: v[1],
+                    eccentricity_cos_lon: v[2],
+                    tan_half_incl_sin_node: v[3],
+                    tan_half_incl_cos_node: v[4],
+                    mean_longitude: v[5].to_radians(),
+                }),
+                [1.0, 1.0, 1.0, 1.0, 1.0, DEGREE],
+            ),
+        };
+        let covariance = match cov.len() {
+            0 => None,
+            COV_TERMS => {
+                let mut m = [[0.0; 6]; 6];
+                let mut terms = cov.iter();
+                for i in 0..6 {
+                    for j in i..6 {
+                        let c = terms.next().copied().unwrap_or(f64::NAN) * scale[i] * scale[j];
+                        (m[i][j], m[j][i]) = (c, c);
+                    }
+                }
+                Some(m)
+            }
+            n => return Err(at(&format!("has {n} COV terms, expected {COV_TERMS}"))),
+        };
+        Ok(Entry {
+            designation,
+            elements,
+            covariance,
+        })
+    }
+}
+
+/// Check the `key = value` lines of a header.
+fn check_header(lines: &[(usize, &str)]) -> Result<(), String> {
+    for &(number, line) in lines {
+        let content = line.split('!').next().unwrap_or_default();
+        let Some((key, value)) = content.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('\'');
+        match key.trim() {
+            "format" if !value.starts_with("OEF") => {
+                return Err(format!("line {number}: unsupported format {value:?}"));
+            }
+            "refsys" if value.split_whitespace().ne(REFSYS.split_whitespace()) => {
+                return Err(format!(
+                    "line {number}: unsupported reference system {value:?} (expected {REFSYS})"
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parse the objects of an OrbFit element file.
+///
+/// Return
+/// ----------
+/// * The objects in file order, or a description of the first invalid line or object.
+pub(crate) fn parse(text: &str) -> Result<Vec<Entry>, String> {
+    let lines: Vec<(usize, &str)> = text.lines().enumerate().map(|(i, l)| (i + 1, l)).collect();
+    let body = match lines.iter().position(|(_, l)| l.trim() == "END_OF_HEADER") {
+        Some(end) => {
+            check_header(&lines[..end])?;
+            &lines[end + 1..]
+        }
+        None => &lines[..],
+    };
+
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending: Option<Pending> = None;
+    for &(number, line) in body {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('!') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            if let Some(p) = pending.take() {
+                entries.push(p.finish()?);
+            }
+            if !seen.insert(trimmed.to_string()) {
+                return Err(format!("line {number}: duplicate designation {trimmed:?}"));
+            }
+            pending = Some(Pending {
+                line: number,
+                designation: trimmed.to_string(),
+                record: None,
+                epoch_tt: None,
+                cov: Vec::new(),
+            });
+            continue;
+        }
+        let p = pending
+            .as_mut()
+            .ok_or_else(|| format!("line {number}: record before any designation"))?;
+        let mut tokens = trimmed.split_whitespace();
+        let keyword = tokens.next().unwrap_or_default();
+        let numbers = |tokens: std::str::SplitWhitespace<'_>| -> Result<Vec<f64>, String> {
+            tokens
+                .map(|t| real(t).ok_or_else(|| format!("line {number}: invalid number {t:?}")))
+                .collect()
+        };
+        match keyword {
+            "KEP" | "EQU" => {
+                if p.record.is_some() {
+                    return Err(format!("line {number}: second element record"));
+                }
+                let values = numbers(tokens)?;
+                let values: [f64; 6] = values.try_into().map_err(|v: Vec<f64>| {
+                    format!("line {number}: {keyword} expects 6 values, got {}", v.len())
+                })?;
+                let kind = if keyword == "KEP" { "KEP" } else { "EQU" };
+                p.record = Some((kind, values));
+            }
+            "COM" | "CAR" | "COT" | "ATT" => {
+                return Err(format!(
+                    "line {number}: unsupported element record {keyword} (only KEP and EQU are read)"
+                ));
+            }
+            "MJD" => {
+                let value = tokens.next().and_then(real);
+                match (value, tokens.next()) {
+                    (Some(mjd), Some("TDT" | "TT")) => p.epoch_tt = Some(mjd),
+                    (Some(_), scale) => {
+                        return Err(format!(
+                            "line {number}: unsupported time scale {:?} (expected TDT)",
+                            scale.unwrap_or_default()
+                        ));
+                    }
+                    (None, _) => return Err(format!("line {number}: invalid MJD epoch")),
+                }
+            }
+            "COV" => p.cov.extend(numbers(tokens)?),
+            _ => {}
+        }
+    }
+    if let Some(p) = pending {
+        entries.push(p.finish()?);
+    }
+    Ok(entries)
+}
+
+/// Read the orbits of an OrbFit element file (`.eq0`, `.oel`).
+///
+/// Arguments
+/// -----------------
+/// * `path` – OEF file (`str` or `pathlib.Path`).
+/// * `covariance` – Also return the covariance of each object.
+///
+/// Return
+/// ----------
+/// * A dict mapping each designation (in file order) to its `KeplerianElements` or
+///   `EquinoctialElements`, or with `covariance=True` to `(elements, cov)`, `cov`
+///   being a `(6, 6)` array in the units of the element object (AU, radians) or `None`
+///   when the record has no `COV` block.
+///
+/// Notes
+/// ----------
+/// * `ValueError` (with the line number) for an unsupported format, reference
+///   system, time scale or element record (`COM`, `CAR`, …), a malformed record, a
+///   duplicate designation, or an object without elements or epoch.
+#[pyfunction]
+#[pyo3(signature = (path, covariance=false), text_signature = "(path, covariance=False)")]
+pub fn read_orbfit_elements(
+    py: Python<'_>,
+    path: PathBuf,
+    covariance: bool,
+) -> PyResult<Bound<'_, PyDict>> {
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| PyOSError::new_err(format!("cannot read {}: {e}", path.display())))?;
+    let entries = parse(&text).map_err(|e| {
+        PyValueError::new_err(format!("invalid OrbFit file {}: {e}", path.display()))
+    })?;
+
+    let out = PyDict::new(py);
+    for entry in entries {
+        let elements = match entry.elements {
+            Elements::Keplerian(k) => KeplerianElements::from(k).into_pyobject(py)?.into_any(),
+            Elements::Equinoctial(q) => EquinoctialElements::from(q).into_pyobject(py)?.into_any(),
+        };
+        if covariance {
+            let cov = entry
+                .covariance
+                .map(|m| PyArray1::from_iter(py, m.into_iter().flatten()).reshape([6, 6]))
+                .transpose()?;
+            out.set_item(entry.designation, (elements, cov))?;
+        } else {
+            out.set_item(entry.designation, elements)?;
+        }
+    }
+    Ok(out)
+}
@@ -1,13 +1,15 @@
-use pyo3::{pyclass, pymethods, PyResult};
+use std::f64::consts::{PI, TAU};
+
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyType};
 
 use outfit::{
-    CometaryElements as RsCometary, EquinoctialElements as RsEquinoctial,
+    constants::GAUSS_GRAV, CometaryElements as RsCometary, EquinoctialElements as RsEquinoctial,
     KeplerianElements as RsKeplerian,
 };
 
 use crate::{
     orbit_type::{equinoctial::EquinoctialElements, keplerian::KeplerianElements},
-    IntoPyResult,
+    time_scales, IntoPyResult,
 };
 
 /// Python wrapper for Cometary elements.
@@ -22,6 +24,168 @@ impl From<RsCometary> for CometaryElements {
     }
 }
 
+/// Eccentricity range around 1 where the inverse starts from Barker's equation.
+const NEAR_PARABOLIC: f64 = 1e-2;
+/// Iteration budget of the Kepler solvers and of the time-of-flight refinement.
+const MAX_ITERATIONS: usize = 100;
+
+/// `atan(√z)/√z` for `z > 0`, `atanh(√-z)/√-z` for `-1 < z < 0`, `1` at `0`.
+fn arctan_ratio(z: f64) -> f64 {
+    if z.abs() < 0.1 {
+        // Σ (-z)ⁿ / (2n + 1)
+        let (mut sum, mut power) = (1.0, 1.0);
+        for n in 1..20 {
+            power *= -z;
+            sum += power / (2 * n + 1) as f64;
+        }
+        sum
+    } else if z > 0.0 {
+        z.sqrt().atan() / z.sqrt()
+    } else {
+        (-z).sqrt().atanh() / (-z).sqrt()
+    }
+}
+
+/// Stumpff function `c₃(y)`: `(√y - sin √y) / y^{3/2}`, `(sinh √-y - √-y) / (-y)^{3/2}`
+/// for negative `y`, `1/6` at `0`.
+fn stumpff_c3(y: f64) -> f64 {
+    if y.abs() < 1.0 {
+        // Σ (-y)ⁿ / (2n + 3)!
+        let (mut sum, mut term) = (1.0 / 6.0, 1.0 / 6.0);
+        for n in 1..12 {
+            term *= -y / ((2 * n + 2) * (2 * n + 3)) as f64;
+            sum += term;
+        }
+        sum
+    } else if y > 0.0 {
+        let x = y.sqrt();
+        (x - x.sin()) / (y * x)
+    } else {
+        let x = (-y).sqrt();
+        (x.sinh() - x) / (-y * x)
+    }
+}
+
+/// Bound of the true anomaly: `π`, or the asymptote `acos(-1/e)` for `e > 1`.
+fn max_true_anomaly(e: f64) -> f64 {
+    if e > 1.0 {
+        (-1.0 / e).acos()
+    } else {
+        PI
+    }
+}
+
+/// Time from perihelion to the true anomaly `nu` (days), for any eccentricity.
+///
+/// With `D = tan(ν/2)` and `z = (1-e)/(1+e)·D²`, the eccentric (hyperbolic) anomaly is
+/// `2·√z·D·arctan_ratio(z)` and Kepler's equation reads
+/// `Δt = q^{3/2}/k · [2Dg/√(1+e) + 8eD³g³·c₃(4zg²)/(1+e)^{3/2}]` with `g = arctan_ratio(z)`:
+/// no division by `1 - e`, so the accuracy is uniform across `e = 1`, where it reduces to
+/// Barker's equation. `nu` must lie within [`max_true_anomaly`].
+fn time_from_perihelion(q: f64, e: f64, nu: f64) -> f64 {
+    let d = (nu / 2.0).tan();
+    let z = (1.0 - e) / (1.0 + e) * d * d;
+    let g = arctan_ratio(z);
+    let dg = d * g;
+    let kepler = 2.0 * dg / (1.0 + e).sqrt()
+        + 8.0 * e * dg.powi(3) * stumpff_c3(4.0 * z * g * g) / (1.0 + e).powf(1.5);
+    q.powf(1.5) / GAUSS_GRAV * kepler
+}
+
+/// Orbital period (days) of a bound orbit, `None` for `e ≥ 1`.
+fn period(q: f64, e: f64) -> Option<f64> {
+    (e < 1.0).then(|| TAU * (q / (1.0 - e)).powf(1.5) / GAUSS_GRAV)
+}
+
+/// True anomaly reached `dt` days after perihelion (before it for `dt < 0`).
+///
+/// The starting point comes from Barker's equation near `e = 1` and from the elliptic or
+/// hyperbolic Kepler equation otherwise; it is refined by Newton iterations on
+/// [`time_from_perihelion`] (`dΔt/dν = r²/h`), safeguarded by bisection inside
+/// `(-ν_max, ν_max)`. Bound orbits are first reduced to the passage closest to the
+/// epoch (`|dt| ≤ P/2`).
+fn true_anomaly_after_perihelion(q: f64, e: f64, dt: f64) -> f64 {
+    let dt = match period(q, e) {
+        Some(p) => dt - p * (dt / p).round(),
+        None => dt,
+    };
+    let nu_max = max_true_anomaly(e);
+    let guess = if (e - 1.0).abs() < NEAR_PARABOLIC {
+        // D + D³/3 = B (Barker), solved in closed form.
+        let b = GAUSS_GRAV * dt / (2.0 * q.powi(3)).sqrt();
+        let y = (1.5 * b.abs() + (2.25 * b * b + 1.0).sqrt()).cbrt();
+        2.0 * (y - 1.0 / y).copysign(b).atan()
+    } else if e < 1.0 {
+        let m = GAUSS_GRAV * ((1.0 - e) / q).powf(1.5) * dt;
+        let mut ecc = if e > 0.8 { PI.copysign(m) } else { m };
+        for _ in 0..MAX_ITERATIONS {
+            let step = (ecc - e * ecc.sin() - m) / (1.0 - e * ecc.cos());
+            ecc -= step;
+            if step.abs() < 1e-15 {
+                break;
+            }
+        }
+        2.0 * (((1.0 + e) / (1.0 - e)).sqrt() * (ecc / 2.0).tan()).atan()
+    } else {
+        let m = GAUSS_GRAV * ((e - 1.0) / q).powf(1.5) * dt;
+        let mut f = (m / e).asinh();
+        for _ in 0..MAX_ITERATIONS {
+            let step = (e * f.sinh() - f - m) / (e * f.cosh() - 1.0);
+            f -= step;
+            if step.abs() < 1e-15 * f.abs().max(1.0) {
+                break;
+            }
+        }
+        2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (f / 2.0).tanh()).atan()
+    };
+
+    let (mut lo, mut hi) = (-nu_max, nu_max);
+    let mut nu = if guess.is_finite() {
+        guess.clamp(lo, hi)
+    } else {
+        0.0
+    };
+    let h = GAUSS_GRAV * (q * (1.0 + e)).sqrt();
+    for _ in 0..MAX_ITERATIONS {
+        if nu <= lo || nu >= hi {
+            nu = 0.5 * (lo + hi);
+        }
+        let residual = time_from_perihelion(q, e, nu) - dt;
+        if residual == 0.0 {
+            break;
+        }
+        if residual > 0.0 {
+            hi = nu;
+        } else {
+            lo = nu;
+        }
+        let r = q * (1.0 + e) / (1.0 + e * nu.cos());
+        let next = nu - residual * h / (r * r);
+        let next = if next > lo && next < hi {
+            next
+        } else {
+            0.5 * (lo + hi)
+        };
+        let converged = (next - nu).abs() <= 4.0 * f64::EPSILON * nu.abs().max(1.0);
+        nu = next;
+        if converged {
+            break;
+        }
+    }
+    nu
+}
+
+/// `ValueError` unless `q > 0` and `e ≥ 0` are finite.
+fn check_conic(q: f64, e: f64) -> PyResult<()> {
+    if !(q.is_finite() && q > 0.0 && e.is_finite() && e >= 0.0) {
+        return Err(PyValueError::new_err(format!(
+            "invalid conic: perihelion distance {q} (expected > 0) and eccentricity {e} \
+             (expected >= 0)"
+        )));
+    }
+    Ok(())
+}
+
 #[pymethods]
 impl CometaryElements {
     /// Build a new Cometary element set.
@@ -69,10 +233,106 @@ impl CometaryElements {
         Self { inner }
     }
 
+    /// Build a cometary element set from the time of perihelion passage.
+    ///
+    /// The true anomaly at `reference_epoch` is computed from `t_p`: Barker's equation near
+    /// `e = 1`, the elliptic or hyperbolic Kepler equation otherwise, refined on a time of
+    /// flight free of the `1/(1 - e)` singularity (see `perihelion_time`).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `t_p`: Time of perihelion passage, MJD (TDB).
+    /// * `q`, `e`, `i`, `node`, `argp`: Perihelion distance (AU), eccentricity, inclination,
+    ///   Ω and ω (rad), as in the constructor.
+    /// * `reference_epoch`: Epoch of the element set, MJD (TDB).
+    ///
+    /// Return
+    /// ----------
+    /// * A new `CometaryElements`, with `true_anomaly` in `(-π, π]` (within the asymptotes
+    ///   for `e > 1`). Raises `ValueError` unless `q > 0` and `e ≥ 0`.
+    ///
+    /// See also
+    /// ------------
+    /// * [`perihelion_time`] – Inverse computation.
+    #[classmethod]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(text_signature = "(t_p, q, e, i, node, argp, reference_epoch)")]
+    fn from_perihelion_time(
+        _cls: &Bound<'_, PyType>,
+        t_p: f64,
+        q: f64,
+        e: f64,
+        i: f64,
+        node: f64,
+        argp: f64,
+        reference_epoch: f64,
+    ) -> PyResult<Self> {
+        check_conic(q, e)?;
+        let true_anomaly = true_anomaly_after_perihelion(q, e, reference_epoch - t_p);
+        Ok(Self::new(
+            reference_epoch,
+            q,
+            e,
+            i,
+            node,
+            argp,
+            true_anomaly,
+        ))
+    }
+
+    /// Time of perihelion passage T_p derived from (epoch, q, e, ν).
+    ///
+    /// The time of flight from perihelion is written with `D = tan(ν/2)` and Stumpff
+    /// series, so it is continuous across `e = 1` (where it reduces to Barker's equation)
+    /// instead of dividing by `1 - e`: the accuracy near parabolic orbits is that of
+    /// the elliptic and hyperbolic cases (~1e-9 day for q ~ 1 AU).
+    ///
+    /// Return
+    /// ----------
+    /// * MJD (TDB) of the perihelion passage. For `e < 1`, the passage closest to the
+    ///   reference epoch (within half a period). Raises `ValueError` for a true anomaly
+    ///   beyond the asymptotes of a hyperbolic orbit (`|ν| ≥ acos(-1/e)`) or an invalid
+    ///   `q` / `e`.
+    ///
+    /// See also
+    /// ------------
+    /// * [`from_perihelion_time`] – Build elements from T_p.
+    #[pyo3(text_signature = "(self)")]
+    fn perihelion_time(&self) -> PyResult<f64> {
+        let RsCometary {
+            reference_epoch,
+            perihelion_distance: q,
+            eccentricity: e,
+            true_anomaly,
+            ..
+        } = self.inner;
+        check_conic(q, e)?;
+        let nu = (true_anomaly + PI).rem_euclid(TAU) - PI;
+        let nu_max = max_true_anomaly(e);
+        if e > 1.0 && nu.abs() >= nu_max {
+            return Err(PyValueError::new_err(format!(
+                "true anomaly {true_anomaly} rad is beyond the asymptotes of the hyperbolic \
+                 orbit (|ν| < {nu_max} rad for e = {e})"
+            )));
+        }
+        Ok(reference_epoch - time_from_perihelion(q, e, nu))
+    }
+
+    /// Reference epoch (MJD, TDB).
     #[getter]
     fn reference_epoch(&self) -> f64 {
         self.inner.reference_epoch
     }
+    /// Reference epoch (MJD, TDB); same value as `reference_epoch`.
+    #[getter]
+    fn reference_epoch_tdb(&self) -> f64 {
+        self.inner.reference_epoch
+    }
+    /// Reference epoch converted to TT (MJD), the scale of observation epochs.
+    #[getter]
+    fn reference_epoch_tt(&self) -> f64 {
+        time_scales::tdb_to_tt(self.inner.reference_epoch)
+    }
     #[getter]
     fn perihelion_distance(&self) -> f64 {
         self.inner.perihelion_distance
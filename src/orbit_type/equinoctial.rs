@@ -1,8 +1,8 @@
 use outfit::{EquinoctialElements as RsEquinoctial, KeplerianElements as RsKeplerian};
 
-use pyo3::{pyclass, pymethods};
+use pyo3::{pyclass, pymethods, PyResult};
 
-use crate::orbit_type::keplerian::KeplerianElements;
+use crate::{orbfit, orbit_type::keplerian::KeplerianElements, time_scales};
 
 /// Python wrapper for Equinoctial elements.
 #[pyclass]
@@ -62,10 +62,21 @@ impl EquinoctialElements {
         Self { inner }
     }
 
+    /// Reference epoch (MJD, TDB).
     #[getter]
     fn reference_epoch(&self) -> f64 {
         self.inner.reference_epoch
     }
+    /// Reference epoch (MJD, TDB); same value as `reference_epoch`.
+    #[getter]
+    fn reference_epoch_tdb(&self) -> f64 {
+        self.inner.reference_epoch
+    }
+    /// Reference epoch converted to TT (MJD), the scale of observation epochs.
+    #[getter]
+    fn reference_epoch_tt(&self) -> f64 {
+        time_scales::tdb_to_tt(self.inner.reference_epoch)
+    }
     #[getter]
     fn semi_major_axis(&self) -> f64 {
         self.inner.semi_major_axis
@@ -111,6 +122,27 @@ impl EquinoctialElements {
         RsKeplerian::from(&self.inner).into()
     }
 
+    /// Format the elements as an OrbFit (OEF2.0) `EQU` record.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `designation`: Object name (no whitespace).
+    /// * `header`: Prepend the OEF2.0 header, making the text a complete `.eq0` file;
+    ///   `False` to append the record to an existing file.
+    ///
+    /// Return
+    /// ----------
+    /// * The designation line, the `EQU` record (AU, mean longitude in degrees) and the
+    ///   `MJD … TDT` epoch.
+    ///
+    /// See also
+    /// ------------
+    /// * `py_outfit.read_orbfit_elements` – Reader of the same format.
+    #[pyo3(signature = (designation, header=true), text_signature = "(self, designation, header=True)")]
+    fn to_orbfit_string(&self, designation: &str, header: bool) -> PyResult<String> {
+        orbfit::equinoctial_record(&self.inner, designation, header)
+    }
+
     /// Pretty string representation (`str(obj)` in Python).
     fn __str__(&self) -> String {
         format!("{}", self.inner)
@@ -1,7 +1,12 @@
+use numpy::PyReadonlyArray1;
 use outfit::{EquinoctialElements as RsEquinoctial, KeplerianElements as RsKeplerian};
-use pyo3::{pyclass, pymethods};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyList, PyType},
+};
 
-use crate::orbit_type::equinoctial::EquinoctialElements;
+use crate::{orbfit, orbit_type::equinoctial::EquinoctialElements, time_scales};
 
 /// Python wrapper for Keplerian elements.
 #[pyclass]
@@ -15,6 +20,41 @@ impl From<RsKeplerian> for KeplerianElements {
     }
 }
 
+/// Column arrays of Keplerian elements, in constructor order.
+pub(crate) type KeplerianColumns<'py> = [PyReadonlyArray1<'py, f64>; 7];
+
+/// Assemble Keplerian element sets from column arrays in one pass.
+///
+/// Arguments
+/// -----------------
+/// * `columns` – `(epoch, a, e, i, node, argp, m)` arrays, in the units of
+///   [`KeplerianElements::new`].
+///
+/// Return
+/// ----------
+/// * One element set per row, or `ValueError` when the lengths differ.
+pub(crate) fn keplerian_rows(columns: &KeplerianColumns<'_>) -> PyResult<Vec<RsKeplerian>> {
+    let cols = columns.iter().map(|c| c.as_array()).collect::<Vec<_>>();
+    let n = cols[0].len();
+    if cols.iter().any(|c| c.len() != n) {
+        let lens: Vec<usize> = cols.iter().map(|c| c.len()).collect();
+        return Err(PyValueError::new_err(format!(
+            "Length mismatch between element arrays (epoch, a, e, i, node, argp, m): {lens:?}"
+        )));
+    }
+    Ok((0..n)
+        .map(|k| RsKeplerian {
+            reference_epoch: cols[0][k],
+            semi_major_axis: cols[1][k],
+            eccentricity: cols[2][k],
+            inclination: cols[3][k],
+            ascending_node_longitude: cols[4][k],
+            periapsis_argument: cols[5][k],
+            mean_anomaly: cols[6][k],
+        })
+        .collect())
+}
+
 #[pymethods]
 impl KeplerianElements {
     /// Build a new Keplerian element set.
@@ -61,11 +101,52 @@ impl KeplerianElements {
         Self { inner }
     }
 
-    /// Reference epoch (MJD).
+    /// Build many Keplerian element sets from column arrays.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `epoch`, `a`, `e`, `i`, `node`, `argp`, `m`: `np.ndarray[dtype=np.float64]` of
+    ///   equal length, in the units of the constructor (MJD TDB, AU, radians).
+    ///
+    /// Return
+    /// ----------
+    /// * A list of `KeplerianElements`, one per row, built in a single Rust pass.
+    ///
+    /// See also
+    /// ------------
+    /// * `GaussResult.from_keplerian_arrays` – Same input, wrapped as results.
+    #[classmethod]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(text_signature = "(epoch, a, e, i, node, argp, m)")]
+    fn from_arrays<'py>(
+        cls: &Bound<'py, PyType>,
+        epoch: PyReadonlyArray1<'py, f64>,
+        a: PyReadonlyArray1<'py, f64>,
+        e: PyReadonlyArray1<'py, f64>,
+        i: PyReadonlyArray1<'py, f64>,
+        node: PyReadonlyArray1<'py, f64>,
+        argp: PyReadonlyArray1<'py, f64>,
+        m: PyReadonlyArray1<'py, f64>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let rows = keplerian_rows(&[epoch, a, e, i, node, argp, m])?;
+        PyList::new(cls.py(), rows.into_iter().map(KeplerianElements::from))
+    }
+
+    /// Reference epoch (MJD, TDB).
     #[getter]
     fn reference_epoch(&self) -> f64 {
         self.inner.reference_epoch
     }
+    /// Reference epoch (MJD, TDB); same value as `reference_epoch`.
+    #[getter]
+    fn reference_epoch_tdb(&self) -> f64 {
+        self.inner.reference_epoch
+    }
+    /// Reference epoch converted to TT (MJD), the scale of observation epochs.
+    #[getter]
+    fn reference_epoch_tt(&self) -> f64 {
+        time_scales::tdb_to_tt(self.inner.reference_epoch)
+    }
     /// Semi-major axis (AU).
     #[getter]
     fn semi_major_axis(&self) -> f64 {
@@ -117,6 +198,26 @@ impl KeplerianElements {
         RsEquinoctial::from(&self.inner).into()
     }
 
+    /// Format the elements as an OrbFit (OEF2.0) `KEP` record.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `designation`: Object name (no whitespace).
+    /// * `header`: Prepend the OEF2.0 header, making the text a complete `.oel` file;
+    ///   `False` to append the record to an existing file.
+    ///
+    /// Return
+    /// ----------
+    /// * The designation line, the `KEP` record (AU, degrees) and the `MJD … TDT` epoch.
+    ///
+    /// See also
+    /// ------------
+    /// * `py_outfit.read_orbfit_elements` – Reader of the same format.
+    #[pyo3(signature = (designation, header=true), text_signature = "(self, designation, header=True)")]
+    fn to_orbfit_string(&self, designation: &str, header: bool) -> PyResult<String> {
+        orbfit::keplerian_record(&self.inner, designation, header)
+    }
+
     /// Pretty string representation (`str(obj)` in Python).
     fn __str__(&self) -> String {
         format!("{}", self.inner)
@@ -0,0 +1,149 @@
+//! Parquet export of trajectory sets.
+//!
+//! A trajectory set is written as a single Parquet file with one row per observation,
+//! in the columns of `schemas::trajectory_set_file`:
+//!
+//! * `trajectory_id` – `UInt32` when every id is an integer, `Utf8` otherwise (integer
+//!   ids are then written as their decimal string);
+//! * `mjd_tt`, `ra`, `dec`, `sigma_ra`, `sigma_dec` – `Float64`, angles in radians;
+//! * `observer` – MPC code of the observing site, null for sites without one (user
+//!   defined observers).
+//!
+//! Trajectories are written by increasing id, their observations in stored (time)
+//! order. Each field carries its unit in its metadata (`"unit"`), and the file the
+//! schema version (`"py_outfit.schema_version"`), so that loaders can validate it.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.to_parquet` / `TrajectorySet.from_parquet` – Python entry points.
+use std::{collections::HashMap, fs::File, sync::Arc};
+
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt32Array};
+use arrow_schema::{DataType, Field, Schema};
+use camino::Utf8Path;
+use outfit::{ObjectNumber, Observer, Outfit};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use pyo3::{
+    exceptions::{PyOSError, PyValueError},
+    prelude::*,
+};
+
+use crate::{
+    observations::observation_columns,
+    observatories::MpcSites,
+    schemas::{self, SCHEMA_VERSION},
+};
+
+/// Parquet codec named by the `compression` argument of `TrajectorySet.to_parquet`.
+///
+/// Only the codecs compiled into the Parquet reader are offered, so that every file
+/// written can be read back by `TrajectorySet.from_parquet`.
+pub(crate) fn compression(name: &str) -> PyResult<Compression> {
+    match name {
+        "snappy" => Ok(Compression::SNAPPY),
+        "none" | "uncompressed" => Ok(Compression::UNCOMPRESSED),
+        "zstd" => Err(PyValueError::new_err(
+            "zstd compression is not available in this build (supported: 'snappy', 'none')",
+        )),
+        other => Err(PyValueError::new_err(format!(
+            "unknown compression {other:?} (expected 'snappy' or 'none')"
+        ))),
+    }
+}
+
+/// Arrow field of an exported column, with its unit in the metadata.
+fn field(column: &schemas::Column, data_type: DataType) -> Field {
+    let field = Field::new(column.name, data_type, column.nullable);
+    match column.quantity {
+        Some(q) => field.with_metadata(HashMap::from([(
+            "unit".to_string(),
+            q.unit(false).to_string(),
+        )])),
+        None => field,
+    }
+}
+
+/// Write `set` to `path` as one Parquet file.
+///
+/// Arguments
+/// -----------------
+/// * `set` – Trajectories to write.
+/// * `env` – Environment holding the observer registry of the observations.
+/// * `sites` – MPC sites of the environment, giving the code of each site.
+/// * `path` – Destination file, created or truncated.
+/// * `compression` – Codec of every column chunk, see [`compression`].
+///
+/// Return
+/// ----------
+/// * The number of rows written. Raises `OSError` when the file cannot be created or
+///   written.
+pub(crate) fn write(
+    set: &outfit::TrajectorySet,
+    env: &Outfit,
+    sites: &MpcSites,
+    path: &Utf8Path,
+    compression: Compression,
+) -> PyResult<usize> {
+    let columns = schemas::trajectory_set_file();
+    let (id_column, site_column) = (&columns[0], &columns[columns.len() - 1]);
+    let mut keys: Vec<&ObjectNumber> = set.keys().collect();
+    keys.sort();
+    let rows = || {
+        keys.iter()
+            .flat_map(|k| set[*k].iter().map(move |o| (*k, o)))
+    };
+
+    let int_ids = keys.iter().all(|k| matches!(k, ObjectNumber::Int(_)));
+    let (id_type, ids): (DataType, ArrayRef) = if int_ids {
+        let ids = rows().map(|(k, _)| match k {
+            ObjectNumber::Int(id) => *id,
+            ObjectNumber::String(_) => unreachable!(),
+        });
+        (DataType::UInt32, Arc::new(ids.collect::<UInt32Array>()))
+    } else {
+        let ids: StringArray = rows().map(|(k, _)| Some(k.to_string())).collect();
+        (DataType::Utf8, Arc::new(ids))
+    };
+
+    // Sites are resolved once per registry entry, not per row.
+    let codes = sites.site_codes(env);
+    let mut resolved: HashMap<*const Observer, Option<&str>> = HashMap::new();
+    let observers: StringArray = rows()
+        .map(|(_, o)| {
+            let site = o.get_observer(env);
+            *resolved
+                .entry(site as *const Observer)
+                .or_insert_with(|| codes.get(site).map(String::as_str))
+        })
+        .collect();
+
+    let mut fields = vec![field(id_column, id_type)];
+    let mut arrays = vec![ids];
+    let values = observation_columns(rows().map(|(_, o)| o), false);
+    for (c, v) in columns[1..columns.len() - 1].iter().zip(values) {
+        fields.push(field(c, DataType::Float64));
+        arrays.push(Arc::new(Float64Array::from(v)));
+    }
+    fields.push(field(site_column, DataType::Utf8));
+    arrays.push(Arc::new(observers));
+
+    let schema = Arc::new(Schema::new(fields).with_metadata(HashMap::from([(
+        "py_outfit.schema_version".to_string(),
+        SCHEMA_VERSION.to_string(),
+    )])));
+    let write_err = |e: parquet::errors::ParquetError| {
+        PyOSError::new_err(format!("cannot write {path} as Parquet: {e}"))
+    };
+    let batch = RecordBatch::try_new(schema.clone(), arrays)
+        .map_err(|e| PyValueError::new_err(format!("cannot build the record batch: {e}")))?;
+
+    let file =
+        File::create(path).map_err(|e| PyOSError::new_err(format!("cannot create {path}: {e}")))?;
+    let props = WriterProperties::builder()
+        .set_compression(compression)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, schema, Some(props)).map_err(write_err)?;
+    writer.write(&batch).map_err(write_err)?;
+    writer.close().map_err(write_err)?;
+    Ok(batch.num_rows())
+}
@@ -0,0 +1,188 @@
+//! Photometry carried alongside the astrometry of a trajectory set.
+//!
+//! The core observations only hold astrometry. The magnitude and photometric band of
+//! each detection, given at ingestion (`mag=`, `band=`), are kept by the bindings in a
+//! side table keyed by the content of the observation (epoch, RA, Dec and site
+//! position) rather than by its position in a trajectory. Sorting, merging, splitting or
+//! renaming trajectories leaves those keys unchanged, so the photometry follows the
+//! observations through every operation of a set without being re-indexed.
+//!
+//! The table is shared (`Arc`) between a set and the `Observations` views taken from it;
+//! sets that were never given photometry hold an empty table. It also records, with the
+//! same keys, the observations flagged as rejected in ADES files (`selAst`, `exclude`)
+//! and read with `include_rejected=True`.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.from_numpy_degrees` – Ingestion entry point taking `mag` / `band`.
+//! * `Observations.magnitudes` – Per-view accessor.
+//! * `Observations.rejected` – Per-view accessor of the ADES rejection flags.
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use outfit::{observations::Observation, ObjectNumber, TrajectorySet};
+
+/// Identity of an observation: bit patterns of its epoch, RA, Dec and geocentric site
+/// position (the uncertainties are left out, as the `bad_sigma` policy may rewrite them).
+type ObsKey = [u64; 6];
+
+fn obs_key(o: &Observation) -> ObsKey {
+    let site = o.get_observer_earth_position();
+    [
+        o.time.to_bits(),
+        o.ra.to_bits(),
+        o.dec.to_bits(),
+        site.x.to_bits(),
+        site.y.to_bits(),
+        site.z.to_bits(),
+    ]
+}
+
+/// Magnitude and photometric band of one detection.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Photometry {
+    /// Magnitude, NaN when only the band is known.
+    pub mag: f64,
+    /// Photometric band (e.g. `"g"`, `"r"`), shared between detections.
+    pub band: Option<Arc<str>>,
+}
+
+/// Photometry of the observations of a set, see the module documentation.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PhotometryTable {
+    rows: HashMap<ObsKey, Photometry>,
+    /// Observations flagged as rejected by their source file.
+    rejected: HashSet<ObsKey>,
+}
+
+impl PhotometryTable {
+    /// Whether no observation has photometry nor a rejection flag.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty() && self.rejected.is_empty()
+    }
+
+    /// Photometry of `o`, if any was given at ingestion.
+    pub fn get(&self, o: &Observation) -> Option<&Photometry> {
+        if self.rows.is_empty() {
+            return None;
+        }
+        self.rows.get(&obs_key(o))
+    }
+
+    /// Whether at least one of `obs` has photometry.
+    pub fn covers<'a>(&self, obs: impl IntoIterator<Item = &'a Observation>) -> bool {
+        !self.rows.is_empty() && obs.into_iter().any(|o| self.get(o).is_some())
+    }
+
+    /// Whether `o` was flagged as rejected by its source file.
+    pub fn is_rejected(&self, o: &Observation) -> bool {
+        !self.rejected.is_empty() && self.rejected.contains(&obs_key(o))
+    }
+
+    /// Flag the observations `obs` as rejected.
+    pub fn reject<'a>(&mut self, obs: impl IntoIterator<Item = &'a Observation>) {
+        self.rejected.extend(obs.into_iter().map(obs_key));
+    }
+
+    /// Add the entries of `other`; entries already present are kept.
+    pub fn merge(&mut self, other: &PhotometryTable) {
+        for (key, p) in &other.rows {
+            self.rows.entry(*key).or_insert_with(|| p.clone());
+        }
+        self.rejected.extend(&other.rejected);
+    }
+
+    /// Add the entries of `other` for the observations `obs` (a view taken from the set
+    /// owning `other`); entries already present are kept.
+    pub fn merge_for<'a>(
+        &mut self,
+        other: &PhotometryTable,
+        obs: impl IntoIterator<Item = &'a Observation>,
+    ) {
+        if other.is_empty() {
+            return;
+        }
+        for o in obs {
+            let key = obs_key(o);
+            if let Some(p) = other.rows.get(&key) {
+                self.rows.entry(key).or_insert_with(|| p.clone());
+            }
+            if other.rejected.contains(&key) {
+                self.rejected.insert(key);
+            }
+        }
+    }
+
+    /// Record the photometry of the rows of a freshly built set.
+    ///
+    /// Rows are matched to observations as the core ingestion stores them: the `k`-th row
+    /// of an id is the `k`-th observation of its trajectory, so this must run before the
+    /// trajectories are sorted or their ids restored.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `set` – Set built from the rows, keyed by `ids`.
+    /// * `ids` – `u32` id of every row.
+    /// * `mag` – Magnitude of every row (NaN when unknown), if given.
+    /// * `band` – Band of every row (`None` when unknown), if given.
+    pub fn record(
+        &mut self,
+        set: &TrajectorySet,
+        ids: &[u32],
+        mag: Option<&[f64]>,
+        band: Option<&[Option<String>]>,
+    ) {
+        if mag.is_none() && band.is_none() {
+            return;
+        }
+        let mut bands: HashMap<&str, Arc<str>> = HashMap::new();
+        let mut next: HashMap<u32, usize> = HashMap::new();
+        for (row, &id) in ids.iter().enumerate() {
+            let k = next.entry(id).or_default();
+            let o = set.get(&ObjectNumber::Int(id)).and_then(|obs| obs.get(*k));
+            *k += 1;
+            let mag = mag.map_or(f64::NAN, |m| m[row]);
+            let band = band
+                .and_then(|b| b[row].as_deref())
+                .map(|name| bands.entry(name).or_insert_with(|| Arc::from(name)).clone());
+            if let Some(o) = o.filter(|_| !mag.is_nan() || band.is_some()) {
+                self.rows.insert(obs_key(o), Photometry { mag, band });
+            }
+        }
+    }
+
+    /// Flag the rejected rows of a freshly built set, matched to its observations as in
+    /// [`Self::record`].
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `set` – Set built from the rows, keyed by `ids`.
+    /// * `ids` – `u32` id of every row.
+    /// * `rejected` – Whether every row is flagged as rejected.
+    pub fn record_rejected(&mut self, set: &TrajectorySet, ids: &[u32], rejected: &[bool]) {
+        let mut next: HashMap<u32, usize> = HashMap::new();
+        for (&id, &flagged) in ids.iter().zip(rejected) {
+            let k = next.entry(id).or_default();
+            let o = set.get(&ObjectNumber::Int(id)).and_then(|obs| obs.get(*k));
+            *k += 1;
+            if let Some(o) = o.filter(|_| flagged) {
+                self.rejected.insert(obs_key(o));
+            }
+        }
+    }
+
+    /// Magnitude (NaN when unknown) and band of each of `obs`, in order.
+    pub fn columns<'a>(
+        &self,
+        obs: impl IntoIterator<Item = &'a Observation>,
+    ) -> (Vec<f64>, Vec<Option<String>>) {
+        obs.into_iter()
+            .map(|o| match self.get(o) {
+                Some(p) => (p.mag, p.band.as_deref().map(str::to_string)),
+                None => (f64::NAN, None),
+            })
+            .unzip()
+    }
+}
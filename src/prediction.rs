@@ -0,0 +1,156 @@
+//! Bulk prediction of apparent positions for many orbits at shared epochs.
+//!
+//! The observer's geocentric position is computed once per epoch, then every orbit is
+//! evaluated at every epoch with the core's apparent-position model (two-body
+//! propagation, aberration correction), in parallel over the orbits.
+//!
+//! Conventions
+//! -----------------
+//! * Epochs are MJD on the TT axis, as for observations.
+//! * Rates are central differences over `RATE_STEP_DAYS` (one minute); the RA rate is projected on
+//!   the sky, `dRA/dt · cos(dec)`.
+//! * A prediction that cannot be made is `NaN`: the whole row when the orbit cannot be
+//!   propagated (e.g. unbound orbits), the whole column when the epoch is not covered by
+//!   the ephemeris.
+//!
+//! See also
+//! ------------
+//! * `OrbitResults.predict_all` – Python entry point.
+//! * [`crate::fit_statistics`] – Residuals computed with the same model.
+use std::f64::consts::PI;
+
+use hifitime::{Epoch, TimeScale};
+use outfit::{
+    constants::DPI, observations::Observation, GaussResult as RsGaussResult, Observer, Outfit,
+    OutfitError,
+};
+use rayon::prelude::*;
+
+use crate::{ephemeris::CoverageGap, time_scales};
+
+/// Half-width of the central difference used for the rates (days).
+pub(crate) const RATE_STEP_DAYS: f64 = 1.0 / 1440.0;
+
+/// Predicted positions of `n` orbits at `m` epochs, row-major `(n, m)`, in radians.
+pub(crate) struct Grid {
+    pub(crate) ra: Vec<f64>,
+    pub(crate) dec: Vec<f64>,
+    /// `dRA/dt · cos(dec)` (rad/day).
+    pub(crate) ra_rate: Vec<f64>,
+    /// `dDec/dt` (rad/day).
+    pub(crate) dec_rate: Vec<f64>,
+    /// Rows with at least one `NaN`, with the first error met.
+    pub(crate) failures: Vec<(usize, String)>,
+}
+
+/// Placeholder observation at `time` seen from `site`, carrying its geocentric position.
+fn probe(state: &Outfit, site: &Observer, time: f64) -> Result<Observation, OutfitError> {
+    let epoch = Epoch::from_mjd_in_time_scale(time, TimeScale::TT);
+    let (geo, _) = site.pvobs(&epoch, state.get_ut1_provider())?;
+    let helio = site.helio_position(state, &epoch, &geo)?;
+    Ok(Observation::with_positions(
+        0, 0.0, 0.0, 0.0, 0.0, time, geo, helio,
+    ))
+}
+
+/// Apparent position and on-sky rates of one orbit from the three probes of an epoch.
+fn predict_one(
+    state: &Outfit,
+    orbit: &outfit::EquinoctialElements,
+    probes: &[Observation; 3],
+) -> Result<[f64; 4], OutfitError> {
+    let (ra, dec) = probes[1].compute_apparent_position(state, orbit)?;
+    let (ra_lo, dec_lo) = probes[0].compute_apparent_position(state, orbit)?;
+    let (ra_hi, dec_hi) = probes[2].compute_apparent_position(state, orbit)?;
+    let mut d_ra = (ra_hi - ra_lo) % DPI;
+    if d_ra > PI {
+        d_ra -= DPI;
+    } else if d_ra < -PI {
+        d_ra += DPI;
+    }
+    let span = 2.0 * RATE_STEP_DAYS;
+    Ok([ra, dec, d_ra * dec.cos() / span, (dec_hi - dec_lo) / span])
+}
+
+/// Predict the apparent positions of `orbits` seen from `site` at `epochs`.
+///
+/// Arguments
+/// -----------------
+/// * `state` – Global Outfit environment.
+/// * `segments` – Continuous segments of the ephemeris (see `PyOutfit::segments`).
+/// * `site` – Observing site.
+/// * `orbits` – Orbits to evaluate, with their reference epoch in TDB.
+/// * `epochs` – Prediction epochs (MJD TT).
+///
+/// Return
+/// ----------
+/// * The [`Grid`] of predictions, one row per orbit in input order.
+pub(crate) fn predict(
+    state: &Outfit,
+    segments: &[(f64, f64)],
+    site: &Observer,
+    orbits: &[&RsGaussResult],
+    epochs: &[f64],
+) -> Grid {
+    // Observer positions, once per epoch; uncovered epochs stay unset.
+    let probes: Vec<Result<[Observation; 3], String>> = epochs
+        .par_iter()
+        .map(|&t| {
+            let window = [t - RATE_STEP_DAYS, t, t + RATE_STEP_DAYS];
+            if let Some(gap) = CoverageGap::find(segments, window) {
+                return Err(gap.message());
+            }
+            let [lo, mid, hi] = window.map(|s| probe(state, site, s).map_err(|e| e.to_string()));
+            Ok([lo?, mid?, hi?])
+        })
+        .collect();
+
+    let rows: Vec<(Vec<[f64; 4]>, Option<String>)> = orbits
+        .par_iter()
+        .map(|orbit| {
+            let elements = time_scales::orbit_to_tt(orbit).get_orbit().to_equinoctial();
+            let mut first_error = None;
+            let mut record = |e: String| {
+                first_error.get_or_insert(e);
+                [f64::NAN; 4]
+            };
+            let row = match elements {
+                Ok(elements) => probes
+                    .iter()
+                    .map(|p| match p {
+                        Ok(p) => predict_one(state, &elements, p)
+                            .unwrap_or_else(|e| record(e.to_string())),
+                        Err(msg) => record(msg.clone()),
+                    })
+                    .collect(),
+                Err(e) => {
+                    let row = vec![[f64::NAN; 4]; epochs.len()];
+                    record(e.to_string());
+                    row
+                }
+            };
+            (row, first_error)
+        })
+        .collect();
+
+    let size = orbits.len() * epochs.len();
+    let mut grid = Grid {
+        ra: Vec::with_capacity(size),
+        dec: Vec::with_capacity(size),
+        ra_rate: Vec::with_capacity(size),
+        dec_rate: Vec::with_capacity(size),
+        failures: Vec::new(),
+    };
+    for (i, (row, error)) in rows.into_iter().enumerate() {
+        for [ra, dec, ra_rate, dec_rate] in row {
+            grid.ra.push(ra);
+            grid.dec.push(dec);
+            grid.ra_rate.push(ra_rate);
+            grid.dec_rate.push(dec_rate);
+        }
+        if let Some(e) = error {
+            grid.failures.push((i, e));
+        }
+    }
+    grid
+}
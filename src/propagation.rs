@@ -0,0 +1,63 @@
+//! Two-body propagation of element sets.
+//!
+//! Orbits are moved along their osculating conic with the core's two-body solver
+//! (`EquinoctialElements::solve_two_body_problem`); the propagated state is converted
+//! back to elements with `OrbitalElements::from_orbital_state`, then to the family of
+//! the input. No planetary perturbation is applied.
+//!
+//! Unbound (cometary, `e ≥ 1`) orbits are not supported by the core solver and are
+//! reported as errors.
+//!
+//! See also
+//! ------------
+//! * [`crate::iod_params::IODParams`] – `output_epoch` quotes IOD results at a chosen epoch.
+use outfit::{GaussResult as RsGaussResult, OrbitalElements as RsOrbitalElements, OutfitError};
+
+/// Reference epoch of an element set.
+pub(crate) fn reference_epoch(elements: &RsOrbitalElements) -> f64 {
+    match elements {
+        RsOrbitalElements::Keplerian(k) => k.reference_epoch,
+        RsOrbitalElements::Equinoctial(q) => q.reference_epoch,
+        RsOrbitalElements::Cometary(c) => c.reference_epoch,
+    }
+}
+
+/// Propagate an element set to `epoch` with the two-body model.
+///
+/// Arguments
+/// -----------------
+/// * `elements` – Element set to propagate.
+/// * `epoch` – Target epoch, on the time scale of `elements.reference_epoch`.
+///
+/// Return
+/// ----------
+/// * The element set of the same family at `epoch`, or the core error for unbound orbits.
+pub(crate) fn propagate_elements(
+    elements: &RsOrbitalElements,
+    epoch: f64,
+) -> Result<RsOrbitalElements, OutfitError> {
+    let equinoctial = elements.to_equinoctial()?;
+    let (position, velocity, _) =
+        equinoctial.solve_two_body_problem(equinoctial.reference_epoch, epoch, false)?;
+    let state = RsOrbitalElements::from_orbital_state(&position, &velocity, epoch);
+    Ok(match elements {
+        RsOrbitalElements::Keplerian(_) => RsOrbitalElements::Keplerian(state.to_keplerian()?),
+        RsOrbitalElements::Equinoctial(_) => {
+            RsOrbitalElements::Equinoctial(state.to_equinoctial()?)
+        }
+        RsOrbitalElements::Cometary(_) => state,
+    })
+}
+
+/// Propagate the orbit of a result to `epoch`, keeping its stage.
+pub(crate) fn propagate_orbit(
+    orbit: &RsGaussResult,
+    epoch: f64,
+) -> Result<RsGaussResult, OutfitError> {
+    Ok(match orbit {
+        RsGaussResult::PrelimOrbit(e) => RsGaussResult::PrelimOrbit(propagate_elements(e, epoch)?),
+        RsGaussResult::CorrectedOrbit(e) => {
+            RsGaussResult::CorrectedOrbit(propagate_elements(e, epoch)?)
+        }
+    })
+}
@@ -0,0 +1,154 @@
+//! Observations read from text formats, before they are attached to their sites.
+//!
+//! MPC 80-column and ADES files give every observation its own observatory code. Their
+//! readers (`mpc80`, `ades`) turn the file into `Record`s; this module checks the codes
+//! against the MPC table of the environment and builds the trajectories one site at a
+//! time with the core batch ingestion, keyed by designation and in file order.
+//!
+//! See also
+//! ------------
+//! * `TrajectorySet.from_mpc_80col`, `from_ades_xml`, `from_ades_psv`, `from_ades_json` –
+//!   Python entry points.
+use std::collections::{HashMap, HashSet};
+
+use outfit::{
+    constants::RADSEC,
+    observations::Observation,
+    trajectories::{batch_reader::ObservationBatch, trajectory_file::TrajectoryFile},
+    ObjectNumber, Outfit, OutfitError, TrajectorySet,
+};
+
+use crate::observatories::MpcSites;
+
+/// `(line number, reason)` of the records that could not be read.
+pub(crate) type Malformed = Vec<(usize, String)>;
+
+/// One observation read from a file.
+pub(crate) struct Record {
+    /// Line number in the file (1-based).
+    pub(crate) line: usize,
+    /// Trajectory identifier (packed designation, tracklet name, ...).
+    pub(crate) designation: String,
+    /// Observatory code.
+    pub(crate) code: String,
+    pub(crate) mjd_tt: f64,
+    /// RA and Dec (radians).
+    pub(crate) ra: f64,
+    pub(crate) dec: f64,
+    /// On-sky 1-σ uncertainties (radians) of `RA·cos(dec)` and Dec, `NaN` when unknown.
+    pub(crate) sigma_ra: f64,
+    pub(crate) sigma_dec: f64,
+    /// Flagged by the file as not to be used for orbit fitting (ADES `selAst`, `exclude`).
+    pub(crate) rejected: bool,
+}
+
+/// Split off the records whose observatory code is not in the MPC table of `state`.
+///
+/// The code of every known record is recorded in `sites` as the one of its site.
+///
+/// Return
+/// ----------
+/// * The records with a known site, and the `(line number, reason)` of the others.
+pub(crate) fn known_sites(
+    sites: &MpcSites,
+    state: &Outfit,
+    records: Vec<Record>,
+) -> (Vec<Record>, Malformed) {
+    let codes: HashSet<&str> = records.iter().map(|r| r.code.as_str()).collect();
+    let known: HashSet<String> = codes
+        .into_iter()
+        .filter(|code| sites.resolve(state, code).is_some())
+        .map(str::to_string)
+        .collect();
+    let (records, unknown): (Vec<Record>, Vec<Record>) =
+        records.into_iter().partition(|r| known.contains(&r.code));
+    let unknown = unknown
+        .into_iter()
+        .map(|r| (r.line, format!("unknown observatory code {:?}", r.code)))
+        .collect();
+    (records, unknown)
+}
+
+/// Build the trajectories of `records`, keyed by designation, in file order.
+///
+/// Observations are computed one site at a time; every code must be in the MPC table
+/// (see [`known_sites`]).
+///
+/// Arguments
+/// -----------------
+/// * `state` – Global Outfit state.
+/// * `records` – Records with known sites.
+/// * `site_floor` – Floor the uncertainties by the accuracy of the site's error model
+///   (MPC 80-column convention).
+///
+/// Return
+/// ----------
+/// * The trajectories, with the RA uncertainty on the RA coordinate
+///   (`sigma_ra / cos(dec)`), and the observations of the records flagged as rejected.
+pub(crate) fn build(
+    state: &mut Outfit,
+    records: &[Record],
+    site_floor: bool,
+) -> Result<(TrajectorySet, Vec<Observation>), OutfitError> {
+    let mut designations: Vec<&str> = Vec::new();
+    let mut ids: HashMap<&str, u32> = HashMap::new();
+    let mut by_site: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (k, r) in records.iter().enumerate() {
+        ids.entry(&r.designation).or_insert_with(|| {
+            designations.push(&r.designation);
+            designations.len() as u32 - 1
+        });
+        by_site.entry(&r.code).or_default().push(k);
+    }
+
+    let mut trajectories: Vec<Vec<(usize, Observation)>> = vec![Vec::new(); designations.len()];
+    let mut rejected = Vec::new();
+    for (code, rows) in by_site {
+        let site = state.get_observer_from_mpc_code(&code.to_string());
+        let tid: Vec<u32> = rows
+            .iter()
+            .map(|&k| ids[records[k].designation.as_str()])
+            .collect();
+        let column =
+            |f: fn(&Record) -> f64| rows.iter().map(|&k| f(&records[k])).collect::<Vec<_>>();
+        let (ra, dec, mjd) = (column(|r| r.ra), column(|r| r.dec), column(|r| r.mjd_tt));
+        let batch =
+            ObservationBatch::from_radians_borrowed(&tid, &ra, &dec, f64::NAN, f64::NAN, &mjd);
+        let built = TrajectorySet::new_from_vec(state, &batch, site.clone())?;
+
+        let floor = |accuracy: Option<f64>| match accuracy {
+            Some(v) if site_floor => v * RADSEC,
+            _ => f64::NAN,
+        };
+        let ra_floor = floor(site.ra_accuracy.map(|v| v.into_inner()));
+        let dec_floor = floor(site.dec_accuracy.map(|v| v.into_inner()));
+        for (key, observations) in built {
+            let ObjectNumber::Int(id) = key else {
+                unreachable!("batches are keyed by integer identifiers")
+            };
+            // The core keeps the batch order within a trajectory.
+            let rows_of_id = rows.iter().zip(&tid).filter(|(_, &t)| t == id);
+            for ((&k, _), mut obs) in rows_of_id.zip(observations) {
+                let r = &records[k];
+                let cos_dec = r.dec.cos();
+                // `max` ignores a NaN operand: no floor, or an unknown uncertainty.
+                obs.error_ra = r.sigma_ra.max(ra_floor) / cos_dec;
+                obs.error_dec = r.sigma_dec.max(dec_floor);
+                if r.rejected {
+                    rejected.push(obs);
+                }
+                trajectories[id as usize].push((k, obs));
+            }
+        }
+    }
+
+    let mut set = TrajectorySet::default();
+    for (designation, mut observations) in designations.into_iter().zip(trajectories) {
+        observations.sort_by_key(|(k, _)| *k);
+        set.insert(
+            ObjectNumber::String(designation.to_string()),
+            observations.into_iter().map(|(_, obs)| obs).collect(),
+        );
+    }
+    Ok((set, rejected))
+}
@@ -0,0 +1,155 @@
+//! Batch-level tally of the rejected IOD candidates.
+//!
+//! When `IODParams.collect_rejection_stats` is enabled, the search loop of
+//! [`crate::iod_engine`] records, for every trajectory, why each noise realization of
+//! its candidate triplets was not retained. The counters are plain per-trajectory
+//! locals (a worker thread only touches the ones of the trajectory it processes),
+//! merged once the batch is complete by `OrbitResults.rejection_stats()`; with the
+//! option disabled nothing is counted.
+//!
+//! Reasons
+//! -----------------
+//! * `spacing` – The lines of sight of the triplet are degenerate (singular direction
+//!   matrix), typically observations too close in time or along a great circle.
+//! * `root_finding` – The Gauss polynomial has no admissible root (root solver failure,
+//!   spurious root, no real positive root inside the heliocentric distance window).
+//! * `physical` – The solution cannot be propagated over the arc (hyperbolic orbit,
+//!   Kepler solver failure).
+//! * `rms_cut` – The RMS over the arc could not be computed or was not finite, or the
+//!   candidate was scored but not retained (not the lowest RMS of the trajectory).
+//!
+//! Every realization is counted once: either under one reason or as the selected
+//! solution, so the rejections and the selections sum to the realizations solved.
+//!
+//! See also
+//! ------------
+//! * [`crate::costs`] – Per-trajectory cost counters, collected the same way.
+use outfit::{ObjectNumber, OutfitError};
+use pyo3::{
+    prelude::*,
+    types::{PyDict, PyList},
+};
+
+use crate::trajectories::object_number_to_py;
+
+/// Example trajectory ids reported per reason.
+const MAX_EXAMPLES: usize = 5;
+
+/// Why a realization was not retained, see the module documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Reason {
+    Spacing,
+    RootFinding,
+    Physical,
+    RmsCut,
+}
+
+impl Reason {
+    pub(crate) const ALL: [Reason; 4] = [
+        Reason::Spacing,
+        Reason::RootFinding,
+        Reason::Physical,
+        Reason::RmsCut,
+    ];
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Reason::Spacing => "spacing",
+            Reason::RootFinding => "root_finding",
+            Reason::Physical => "physical",
+            Reason::RmsCut => "rms_cut",
+        }
+    }
+
+    /// Reason of an error raised while solving or scoring a realization; errors without
+    /// a specific reason fall back to `stage`, the reason of the step that raised them.
+    pub(crate) fn of(e: &OutfitError, stage: Reason) -> Self {
+        match e {
+            OutfitError::SingularDirectionMatrix => Reason::Spacing,
+            OutfitError::PolynomialRootFindingFailed
+            | OutfitError::SpuriousRootDetected
+            | OutfitError::GaussNoRootsFound
+            | OutfitError::RootFindingError(_) => Reason::RootFinding,
+            OutfitError::InvalidOrbit(_) | OutfitError::VelocityCorrectionError(_) => {
+                Reason::Physical
+            }
+            _ => stage,
+        }
+    }
+}
+
+/// Rejections of the IOD search of one trajectory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct RejectionTally {
+    /// Rejected realizations, indexed by [`Reason`].
+    pub(crate) rejected: [usize; 4],
+    /// Realizations retained as the solution (`0` or `1`).
+    pub(crate) selected: usize,
+    /// No triplet satisfies the time constraints: nothing was attempted.
+    pub(crate) no_feasible_triplets: bool,
+}
+
+impl RejectionTally {
+    pub(crate) fn reject(&mut self, reason: Reason) {
+        self.rejected[reason as usize] += 1;
+    }
+}
+
+/// Merge per-trajectory tallies into the dict returned by `OrbitResults.rejection_stats()`.
+///
+/// Arguments
+/// -----------------
+/// * `tallies` – Tallies of the trajectories that collected them, in id order.
+///
+/// Return
+/// ----------
+/// * `None` when `tallies` is empty, otherwise a dict with keys:
+///   * `"attempts"`: realizations solved over the batch;
+///   * `"selected"`: realizations retained as a solution;
+///   * `"rejected"`: reason → count (the counts and `"selected"` sum to `"attempts"`);
+///   * `"examples"`: reason → up to five trajectory ids with at least one rejection for
+///     that reason, smallest ids first;
+///   * `"no_feasible_triplets"`: trajectories without any time-feasible triplet;
+///   * `"n_collected"`: number of trajectories contributing.
+pub(crate) fn stats<'a, 'py>(
+    py: Python<'py>,
+    tallies: impl Iterator<Item = (&'a ObjectNumber, &'a RejectionTally)>,
+) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let mut total = RejectionTally::default();
+    let mut no_feasible = 0;
+    let mut n_collected = 0;
+    let examples: [Bound<'py, PyList>; 4] = std::array::from_fn(|_| PyList::empty(py));
+
+    for (key, tally) in tallies {
+        n_collected += 1;
+        total.selected += tally.selected;
+        no_feasible += usize::from(tally.no_feasible_triplets);
+        for (i, &n) in tally.rejected.iter().enumerate() {
+            total.rejected[i] += n;
+            if n > 0 && examples[i].len() < MAX_EXAMPLES {
+                examples[i].append(object_number_to_py(py, key)?)?;
+            }
+        }
+    }
+    if n_collected == 0 {
+        return Ok(None);
+    }
+
+    let rejected = PyDict::new(py);
+    let by_reason = PyDict::new(py);
+    for reason in Reason::ALL {
+        rejected.set_item(reason.as_str(), total.rejected[reason as usize])?;
+        by_reason.set_item(reason.as_str(), &examples[reason as usize])?;
+    }
+    let d = PyDict::new(py);
+    d.set_item(
+        "attempts",
+        total.rejected.iter().sum::<usize>() + total.selected,
+    )?;
+    d.set_item("selected", total.selected)?;
+    d.set_item("rejected", rejected)?;
+    d.set_item("examples", by_reason)?;
+    d.set_item("no_feasible_triplets", no_feasible)?;
+    d.set_item("n_collected", n_collected)?;
+    Ok(Some(d))
+}
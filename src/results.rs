@@ -0,0 +1,980 @@
+//! Batch IOD results container.
+//!
+//! [`OrbitResults`] keeps, for every trajectory of a batch run, the IOD outcome
+//! (orbit + RMS, or the error message) together with the observations that were
+//! fitted. Keeping the arc alongside the orbit enables follow-up workflows such as
+//! incremental updates when new observations arrive.
+//!
+//! See also
+//! ------------
+//! * [`crate::trajectories::TrajectorySet::estimate_orbits`] – Producer of `OrbitResults`.
+//! * `iod_engine::refine_orbit` – Incremental refinement used by `update_with`.
+use std::{collections::BTreeMap, path::PathBuf};
+
+use numpy::{PyArray1, PyArrayMethods};
+use outfit::{
+    constants::RADSEC, observations::Observation, GaussResult as RsGaussResult, ObjectNumber,
+};
+use pyo3::{
+    exceptions::{PyImportError, PyKeyError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyIterator, PyList},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    costs::IodCosts,
+    fit_statistics::with_fit_statistics,
+    iod_engine::{self, IodError, UpdatePath},
+    iod_gauss::GaussResult,
+    iod_params::IODParams,
+    observations::Observations,
+    observer::Observer,
+    prediction,
+    rejections::{self, RejectionTally},
+    schemas::{self, Dtype},
+    similarity::{self, Criterion, OrbitShape},
+    sqlite_writer::{self, IfExists},
+    time_scales,
+    trajectories::{object_number_to_py, py_to_object_number, TrajectorySet},
+    units::{self, Quantity},
+    PyOutfit,
+};
+
+/// One trajectory of a batch IOD run.
+///
+/// Fields
+/// -----------------
+/// * `outcome` – Orbit (with its fit statistics) and RMS on success, error message otherwise.
+/// * `error_kind` – Short name of the error of a failure, see `IodError::kind`.
+/// * `observations` – Arc used for the fit, as provided (before uncertainty inflation).
+/// * `costs` – Cost counters of the run (successful or not), when `collect_costs` was set.
+/// * `rejections` – Rejected candidates of the run, when `collect_rejection_stats` was set.
+/// * `weights` – Weights of the observations in the accepted fit, see [`fit_weights`]
+///   (`None` for failures).
+#[derive(Clone)]
+pub(crate) struct OrbitEntry {
+    pub(crate) outcome: Result<(GaussResult, f64), String>,
+    pub(crate) error_kind: Option<String>,
+    pub(crate) observations: outfit::Observations,
+    pub(crate) costs: Option<IodCosts>,
+    pub(crate) rejections: Option<RejectionTally>,
+    pub(crate) weights: Option<Weights>,
+}
+
+/// Per-observation fit weights `(ra, dec)`, in arcsec⁻².
+pub(crate) type Weights = (Vec<f64>, Vec<f64>);
+
+type WeightArrays<'py> = (Bound<'py, PyArray1<f64>>, Bound<'py, PyArray1<f64>>);
+
+/// Weights of the observations of a fitted arc in the χ² of the fit.
+///
+/// Each coordinate of an observation weighs `1/σ²`, `σ` being its uncertainty as
+/// calibrated by the fit: the ingested value (explicit, or substituted by the
+/// `bad_sigma` policy from the site accuracy) after the batch RMS correction of the
+/// error model.
+///
+/// Arguments
+/// -----------------
+/// * `arc` – Observations as fitted (uncertainties after the batch correction).
+///
+/// Return
+/// ----------
+/// * The RA and Dec weights in arcsec⁻², aligned with `arc`.
+pub(crate) fn fit_weights(arc: &[Observation]) -> Weights {
+    let weight = |sigma: f64| (RADSEC / sigma).powi(2);
+    arc.iter()
+        .map(|o| (weight(o.error_ra), weight(o.error_dec)))
+        .unzip()
+}
+
+/// Minimum, median and maximum of the weights of both coordinates.
+fn weight_summary((ra, dec): &Weights) -> Option<[f64; 3]> {
+    let mut all: Vec<f64> = ra.iter().chain(dec).copied().collect();
+    if all.is_empty() {
+        return None;
+    }
+    all.sort_by(f64::total_cmp);
+    let n = all.len();
+    let median = if n % 2 == 1 {
+        all[n / 2]
+    } else {
+        0.5 * (all[n / 2 - 1] + all[n / 2])
+    };
+    Some([all[0], median, all[n - 1]])
+}
+
+/// Results of a batch Gauss IOD run, keyed by trajectory identifier.
+///
+/// Behaves like a read-only mapping `id -> (GaussResult, rms)` restricted to the
+/// successful fits; failures are available through `OrbitResults.errors()`.
+#[pyclass(module = "py_outfit")]
+#[derive(Clone, Default)]
+pub struct OrbitResults {
+    pub(crate) entries: BTreeMap<ObjectNumber, OrbitEntry>,
+}
+
+#[pymethods]
+impl OrbitResults {
+    fn __repr__(&self) -> String {
+        let n_ok = self.entries.values().filter(|e| e.outcome.is_ok()).count();
+        format!(
+            "OrbitResults(n_ok={}, n_err={})",
+            n_ok,
+            self.entries.len() - n_ok
+        )
+    }
+
+    /// Number of trajectories (successful or not).
+    fn __len__(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// `key in results` support (successful or failed trajectories).
+    fn __contains__(&self, key: &Bound<'_, PyAny>) -> PyResult<bool> {
+        Ok(self.entries.contains_key(&py_to_object_number(key)?))
+    }
+
+    /// Subscript access: `results[key] -> (GaussResult, rms)`.
+    ///
+    /// Raises `KeyError` for unknown keys and for failed trajectories (the message
+    /// then carries the IOD error).
+    fn __getitem__(&self, key: &Bound<'_, PyAny>) -> PyResult<(GaussResult, f64)> {
+        let k = py_to_object_number(key)?;
+        match self.entries.get(&k).map(|e| &e.outcome) {
+            Some(Ok((g, rms))) => Ok((g.clone(), *rms)),
+            Some(Err(msg)) => Err(PyKeyError::new_err(format!("IOD failed for {k}: {msg}"))),
+            None => Err(PyKeyError::new_err(format!("Key not found: {k:?}"))),
+        }
+    }
+
+    /// All trajectory identifiers, sorted.
+    fn keys<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let out = PyList::empty(py);
+        for k in self.entries.keys() {
+            out.append(object_number_to_py(py, k)?)?;
+        }
+        Ok(out)
+    }
+
+    /// Successful fits as `dict[id, (GaussResult, rms)]` (same layout as `estimate_all_orbits`).
+    fn ok<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let d = PyDict::new(py);
+        for (k, e) in &self.entries {
+            if let Ok((g, rms)) = &e.outcome {
+                d.set_item(object_number_to_py(py, k)?, (g.clone(), *rms))?;
+            }
+        }
+        Ok(d)
+    }
+
+    /// Failures as `dict[id, str]` (same layout as `estimate_all_orbits`).
+    fn errors<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let d = PyDict::new(py);
+        for (k, e) in &self.entries {
+            if let Err(msg) = &e.outcome {
+                d.set_item(object_number_to_py(py, k)?, msg)?;
+            }
+        }
+        Ok(d)
+    }
+
+    /// Iterate over every trajectory as a [`ResultRow`], in identifier order.
+    ///
+    /// Successes and failures share one type, so loops read
+    /// `for row in results.rows(): if row.ok: ...`. Rows are views on this object: the
+    /// orbit, error and metadata are only read when the attribute is accessed.
+    fn rows<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyIterator>> {
+        let py = slf.py();
+        let results: Py<Self> = slf.clone().unbind();
+        let list = PyList::empty(py);
+        for key in slf.borrow().entries.keys() {
+            let row = ResultRow {
+                results: results.clone_ref(py),
+                key: key.clone(),
+            };
+            list.append(Py::new(py, row)?)?;
+        }
+        PyIterator::from_object(&list)
+    }
+
+    /// Observations fitted for `key` (before uncertainty inflation).
+    fn observations(&self, key: &Bound<'_, PyAny>) -> PyResult<Observations> {
+        let k = py_to_object_number(key)?;
+        self.entries
+            .get(&k)
+            .map(|e| Observations::from(e.observations.clone()))
+            .ok_or_else(|| PyKeyError::new_err(format!("Key not found: {k:?}")))
+    }
+
+    /// Weights of the observations of `key` in its accepted fit.
+    ///
+    /// Each coordinate weighs `1/σ²` in the χ² of the fit, `σ` being the uncertainty
+    /// the fit used: as ingested (or substituted by the `bad_sigma` policy), then
+    /// inflated by the batch RMS correction of the error model.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `key`: Trajectory identifier.
+    ///
+    /// Return
+    /// ----------
+    /// * `(weight_ra, weight_dec)` in arcsec⁻², aligned with
+    ///   [`OrbitResults::observations`]. Raises `KeyError` for unknown keys and for
+    ///   failed trajectories.
+    fn effective_weights<'py>(
+        &self,
+        py: Python<'py>,
+        key: &Bound<'_, PyAny>,
+    ) -> PyResult<WeightArrays<'py>> {
+        let k = py_to_object_number(key)?;
+        let entry = self
+            .entries
+            .get(&k)
+            .ok_or_else(|| PyKeyError::new_err(format!("Key not found: {k:?}")))?;
+        match (&entry.outcome, &entry.weights) {
+            (Ok(_), Some((ra, dec))) => {
+                Ok((PyArray1::from_slice(py, ra), PyArray1::from_slice(py, dec)))
+            }
+            (Err(msg), _) => Err(PyKeyError::new_err(format!("IOD failed for {k}: {msg}"))),
+            (Ok(_), None) => Err(PyKeyError::new_err(format!(
+                "no fit weights recorded for {k}"
+            ))),
+        }
+    }
+
+    /// Cost counters per trajectory, as `dict[id, IodCosts]`.
+    ///
+    /// Only trajectories processed with `IODParams.collect_costs` appear, failed ones
+    /// included.
+    fn costs<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let d = PyDict::new(py);
+        for (k, e) in &self.entries {
+            if let Some(c) = e.costs {
+                d.set_item(object_number_to_py(py, k)?, c)?;
+            }
+        }
+        Ok(d)
+    }
+
+    /// Aggregate view of the batch.
+    ///
+    /// Return
+    /// ----------
+    /// * A dict with keys:
+    ///   * `"n_trajectories"`, `"n_ok"`, `"n_err"`: entry counts.
+    ///   * `"costs"`: `IodCosts.to_dict()` of the counters summed over every entry that
+    ///     carries costs (failures included), or `None` when no entry does.
+    ///   * `"n_costed"`: number of entries contributing to `"costs"`.
+    fn summary<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let n_ok = self.entries.values().filter(|e| e.outcome.is_ok()).count();
+        let mut total: Option<IodCosts> = None;
+        let mut n_costed = 0;
+        for c in self.entries.values().filter_map(|e| e.costs) {
+            *total.get_or_insert_with(IodCosts::default) += c;
+            n_costed += 1;
+        }
+
+        let d = PyDict::new(py);
+        d.set_item("n_trajectories", self.entries.len())?;
+        d.set_item("n_ok", n_ok)?;
+        d.set_item("n_err", self.entries.len() - n_ok)?;
+        match total {
+            Some(t) => d.set_item("costs", t.to_dict(py, false)?)?,
+            None => d.set_item("costs", py.None())?,
+        }
+        d.set_item("n_costed", n_costed)?;
+        Ok(d)
+    }
+
+    /// Why candidate triplets were rejected, aggregated over the batch.
+    ///
+    /// Every noise realization solved by the search is counted once, either under the
+    /// reason it was rejected for or as the selected solution of its trajectory.
+    ///
+    /// Return
+    /// ----------
+    /// * `None` when no entry was processed with `IODParams.collect_rejection_stats`,
+    ///   otherwise a dict with keys:
+    ///   * `"attempts"`: realizations solved over the batch;
+    ///   * `"selected"`: realizations retained as a solution (one per successful fit);
+    ///   * `"rejected"`: `{"spacing", "root_finding", "physical", "rms_cut"}` → count;
+    ///     the counts and `"selected"` sum to `"attempts"`;
+    ///   * `"examples"`: reason → up to five trajectory ids with at least one rejection
+    ///     for that reason, in id order;
+    ///   * `"no_feasible_triplets"`: trajectories without any time-feasible triplet
+    ///     (no realization attempted);
+    ///   * `"n_collected"`: number of entries contributing.
+    ///
+    /// Notes
+    /// ----------
+    /// * With fallback parameter sets, a trajectory contributes the tally of the attempt
+    ///   that produced its entry, as for `costs()`.
+    /// * Trajectories fitted from an initial guess or by `update_with` do not contribute.
+    fn rejection_stats<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        rejections::stats(
+            py,
+            self.entries
+                .iter()
+                .filter_map(|(k, e)| e.rejections.as_ref().map(|r| (k, r))),
+        )
+    }
+
+    /// Export the batch as a columnar dict, one row per trajectory.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `include_units`: Add a `"units"` block mapping each numeric column to its unit
+    ///   (default: `False`).
+    /// * `degrees`: Export angles in degrees instead of radians (default: `False`).
+    ///   The units block is then always included.
+    ///
+    /// Return
+    /// ----------
+    /// * A dict of equally long lists, ready for `pandas.DataFrame(...)`, with the
+    ///   columns of `py_outfit.schemas()["results"]` in that order. Rows are sorted by
+    ///   identifier; failed trajectories only fill `object_id` and `error`, and element
+    ///   columns of the other families are `None`.
+    ///
+    /// See also
+    /// ------------
+    /// * [`crate::schemas::schemas`] – Column names, dtypes, units and nullability.
+    #[pyo3(
+        signature = (include_units=false, degrees=false),
+        text_signature = "($self, include_units=False, degrees=False)"
+    )]
+    fn to_dict<'py>(
+        &self,
+        py: Python<'py>,
+        include_units: bool,
+        degrees: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let columns = schemas::results();
+        let mut rows = Vec::with_capacity(self.entries.len());
+        for (k, e) in &self.entries {
+            let row = PyDict::new(py);
+            row.set_item("object_id", object_number_to_py(py, k)?)?;
+            match &e.outcome {
+                Ok((g, rms)) => {
+                    let (stage, elems) = match &g.inner {
+                        RsGaussResult::PrelimOrbit(el) => ("preliminary", el),
+                        RsGaussResult::CorrectedOrbit(el) => ("corrected", el),
+                    };
+                    let (kind, fields, values) = units::element_fields(elems);
+                    row.set_item("stage", stage)?;
+                    row.set_item("element_type", kind)?;
+                    row.set_item("rms", *rms)?;
+                    row.set_item("fit_epoch", g.fit_epoch)?;
+                    if let Some(fit) = &g.fit {
+                        row.update(fit.to_dict(py, false)?.as_mapping())?;
+                    }
+                    if let Some(summary) = e.weights.as_ref().and_then(weight_summary) {
+                        for ((name, _), v) in units::WEIGHT_FIELDS.iter().zip(summary) {
+                            row.set_item(*name, v)?;
+                        }
+                    }
+                    for ((name, q), v) in fields.iter().zip(values) {
+                        row.set_item(*name, units::export(*q, v, degrees))?;
+                    }
+                }
+                Err(msg) => row.set_item("error", msg)?,
+            }
+            rows.push(row);
+        }
+
+        let d = schemas::table_from_rows(py, &columns, &rows)?;
+        if include_units || degrees {
+            d.set_item(
+                "units",
+                units::units_block(py, &schemas::unit_fields(&columns), degrees)?,
+            )?;
+        }
+        Ok(d)
+    }
+
+    /// Export the batch as an `astropy.table.QTable`, one row per trajectory.
+    ///
+    /// The columns are those of [`OrbitResults::to_dict`] (same names and order as
+    /// `py_outfit.schemas()["results"]`), converted at the boundary:
+    ///
+    /// * epochs (`fit_epoch`, `reference_epoch`) → `astropy.time.Time` in TDB;
+    /// * distances, angles and arcsecond fields → `Quantity` columns (AU, deg or rad, arcsec);
+    /// * dimensionless numbers → plain float columns;
+    /// * `n_points`, `stage`, `element_type` and `error` → `MaskedColumn`s.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `degrees`: Angle columns in degrees (default: `True`) instead of radians.
+    ///
+    /// Return
+    /// ----------
+    /// * A `QTable`. Missing numbers are `NaN` (masked for epochs), missing labels are masked.
+    ///
+    /// Notes
+    /// ----------
+    /// * astropy is imported on call; `ImportError` is raised when it is not installed.
+    #[pyo3(signature = (degrees=true), text_signature = "($self, degrees=True)")]
+    fn to_qtable<'py>(&self, py: Python<'py>, degrees: bool) -> PyResult<Bound<'py, PyAny>> {
+        let table_mod = import_astropy(py, "astropy.table")?;
+        let u = import_astropy(py, "astropy.units")?;
+        let time = import_astropy(py, "astropy.time")?.getattr("Time")?;
+        let masked_column = table_mod.getattr("MaskedColumn")?;
+
+        let data = self.to_dict(py, false, degrees)?;
+        let table = table_mod.getattr("QTable")?.call0()?;
+        for c in schemas::results() {
+            let values = data
+                .get_item(c.name)?
+                .ok_or_else(|| PyKeyError::new_err(c.name))?;
+            let values: Vec<Bound<'py, PyAny>> = values.extract()?;
+            let mask: Vec<bool> = values.iter().map(|v| v.is_none()).collect();
+
+            let column = match (c.dtype, c.quantity) {
+                (Dtype::Float64, Some(q)) => {
+                    let numbers = values
+                        .iter()
+                        .map(|v| Ok(v.extract::<Option<f64>>()?.unwrap_or(f64::NAN)))
+                        .collect::<PyResult<Vec<f64>>>()?;
+                    let numbers = PyArray1::from_vec(py, numbers);
+                    match q {
+                        Quantity::EpochTdb => {
+                            let kwargs = PyDict::new(py);
+                            kwargs.set_item("format", "mjd")?;
+                            kwargs.set_item("scale", "tdb")?;
+                            let masked = py
+                                .import("numpy.ma")?
+                                .call_method1("masked_invalid", (numbers,))?;
+                            time.call((masked,), Some(&kwargs))?
+                        }
+                        _ => match q.unit(degrees) {
+                            "" => numbers.into_any(),
+                            unit => u.getattr("Quantity")?.call1((numbers, unit))?,
+                        },
+                    }
+                }
+                (Dtype::Key, _) => {
+                    let kwargs = PyDict::new(py);
+                    kwargs.set_item("dtype", "object")?;
+                    table_mod
+                        .getattr("Column")?
+                        .call((values,), Some(&kwargs))?
+                }
+                (Dtype::Int64, _) => {
+                    let numbers = values
+                        .iter()
+                        .map(|v| Ok(v.extract::<Option<i64>>()?.unwrap_or(0)))
+                        .collect::<PyResult<Vec<i64>>>()?;
+                    masked_column.call1((PyArray1::from_vec(py, numbers), mask))?
+                }
+                _ => {
+                    let labels = values
+                        .iter()
+                        .map(|v| Ok(v.extract::<Option<String>>()?.unwrap_or_default()))
+                        .collect::<PyResult<Vec<String>>>()?;
+                    masked_column.call1((labels, mask))?
+                }
+            };
+            table.set_item(c.name, column)?;
+        }
+        Ok(table)
+    }
+
+    /// Write the batch to a table of a SQLite database, one row per trajectory.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `path`: Database file (`str` or `pathlib.Path`), created if needed.
+    /// * `table`: Destination table (default: `"orbits"`).
+    /// * `if_exists`: `"fail"` (default, raise `ValueError`), `"replace"` (drop the
+    ///   table first) or `"append"` (insert into the table, whose columns must match)
+    ///   when the table already exists.
+    /// * `degrees`: Write angles in degrees instead of radians (default: `False`).
+    ///
+    /// Return
+    /// ----------
+    /// * The number of rows written.
+    ///
+    /// Notes
+    /// ----------
+    /// * The columns are those of [`OrbitResults::to_dict`], typed `REAL`, `INTEGER`
+    ///   (`n_points`) or `TEXT`; `object_id` is `INTEGER` when every id is an integer,
+    ///   `TEXT` otherwise, and indexed. Missing values are `NULL`.
+    /// * Rows are inserted without the GIL inside one transaction, rolled back on error;
+    ///   database errors raise `OSError`.
+    ///
+    /// See also
+    /// ------------
+    /// * [`crate::sqlite_writer`] – Table layout and transaction handling.
+    #[pyo3(
+        signature = (path, table="orbits", if_exists="fail", degrees=false),
+        text_signature = "($self, path, table='orbits', if_exists='fail', degrees=False)"
+    )]
+    fn to_sqlite(
+        &self,
+        py: Python<'_>,
+        path: PathBuf,
+        table: &str,
+        if_exists: &str,
+        degrees: bool,
+    ) -> PyResult<usize> {
+        let if_exists = IfExists::parse(if_exists)?;
+        let columns = schemas::results();
+        let data = self.to_dict(py, false, degrees)?;
+        let values = columns
+            .iter()
+            .map(|c| {
+                let values = data
+                    .get_item(c.name)?
+                    .ok_or_else(|| PyKeyError::new_err(c.name))?;
+                sqlite_writer::py_values(values.downcast::<PyList>()?, c)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let int_keys = self
+            .entries
+            .keys()
+            .all(|k| matches!(k, ObjectNumber::Int(_)));
+        sqlite_writer::write(py, &path, table, if_exists, &columns, int_keys, values)
+    }
+
+    /// Predict the apparent position of every successful orbit at shared epochs.
+    ///
+    /// Observer positions are computed once per epoch, then the orbits are evaluated in
+    /// parallel, without the GIL, with the model used for the residuals (two-body
+    /// propagation, aberration correction).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Global environment providing ephemerides and Earth orientation.
+    /// * `observer` – Observing site.
+    /// * `epochs_mjd_tt` – Prediction epochs (MJD TT).
+    /// * `path` – When set, the predictions are also written to this Parquet file (with
+    ///   `pyarrow`), one row per `(object_id, mjd_tt)`.
+    ///
+    /// Return
+    /// ----------
+    /// * A dict of NumPy arrays, rows in the order of `object_id`:
+    ///   * `"object_id"`: `(n,)` identifiers of the successful fits (object array);
+    ///   * `"mjd_tt"`: `(m,)` the prediction epochs;
+    ///   * `"ra"`, `"dec"`: `(n, m)` apparent position (deg);
+    ///   * `"ra_rate"`, `"dec_rate"`: `(n, m)` rates (deg/day), `dRA/dt · cos(dec)` for RA;
+    ///   * `"failed_rows"`: rows holding `NaN` predictions, with `"errors"` the first
+    ///     error of each.
+    ///
+    /// Notes
+    /// ----------
+    /// * A prediction that cannot be made is `NaN` rather than an error: a whole row for
+    ///   orbits that cannot be propagated (unbound orbits), a whole column for epochs
+    ///   the ephemeris does not cover.
+    /// * Rates are central differences over two minutes.
+    ///
+    /// See also
+    /// ------------
+    /// * [`crate::prediction`] – Prediction model and failure handling.
+    #[pyo3(
+        signature = (env, observer, epochs_mjd_tt, path=None),
+        text_signature = "($self, env, observer, epochs_mjd_tt, path=None)"
+    )]
+    fn predict_all<'py>(
+        &self,
+        py: Python<'py>,
+        env: &PyOutfit,
+        observer: &Observer,
+        epochs_mjd_tt: Vec<f64>,
+        path: Option<PathBuf>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        if let Some(t) = epochs_mjd_tt.iter().find(|t| !t.is_finite()) {
+            return Err(PyValueError::new_err(format!(
+                "epochs_mjd_tt must be finite (got {t})"
+            )));
+        }
+        let (keys, orbits): (Vec<&ObjectNumber>, Vec<&RsGaussResult>) = self
+            .entries
+            .iter()
+            .filter_map(|(k, e)| e.outcome.as_ref().ok().map(|(g, _)| (k, &g.inner)))
+            .unzip();
+        let grid = py.detach(|| {
+            let segments = env.segments();
+            prediction::predict(
+                &env.outfit_detached(),
+                segments,
+                &observer.inner,
+                &orbits,
+                &epochs_mjd_tt,
+            )
+        });
+
+        let np = py.import("numpy")?;
+        let (n, m) = (keys.len(), epochs_mjd_tt.len());
+        let ids = PyList::empty(py);
+        for k in &keys {
+            ids.append(object_number_to_py(py, k)?)?;
+        }
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("dtype", "object")?;
+        let ids = np.call_method("array", (ids,), Some(&kwargs))?;
+        let grid_deg = |v: Vec<f64>| {
+            let deg: Vec<f64> = v.into_iter().map(f64::to_degrees).collect();
+            PyArray1::from_vec(py, deg).reshape([n, m])
+        };
+        let (failed_rows, errors): (Vec<usize>, Vec<String>) = grid.failures.into_iter().unzip();
+
+        let d = PyDict::new(py);
+        d.set_item("object_id", &ids)?;
+        d.set_item("mjd_tt", PyArray1::from_vec(py, epochs_mjd_tt))?;
+        d.set_item("ra", grid_deg(grid.ra)?)?;
+        d.set_item("dec", grid_deg(grid.dec)?)?;
+        d.set_item("ra_rate", grid_deg(grid.ra_rate)?)?;
+        d.set_item("dec_rate", grid_deg(grid.dec_rate)?)?;
+        d.set_item("failed_rows", PyArray1::from_vec(py, failed_rows))?;
+        d.set_item("errors", errors)?;
+
+        if let Some(path) = path {
+            // Long layout: one row per (object, epoch).
+            let columns = PyDict::new(py);
+            columns.set_item("object_id", np.call_method1("repeat", (&ids, m))?)?;
+            columns.set_item(
+                "mjd_tt",
+                np.call_method1("tile", (d.get_item("mjd_tt")?, n))?,
+            )?;
+            for name in ["ra", "dec", "ra_rate", "dec_rate"] {
+                let values = d.get_item(name)?.ok_or_else(|| PyKeyError::new_err(name))?;
+                columns.set_item(name, values.call_method0("ravel")?)?;
+            }
+            let table = py
+                .import("pyarrow")?
+                .getattr("Table")?
+                .call_method1("from_pydict", (columns,))?;
+            py.import("pyarrow.parquet")?
+                .call_method1("write_table", (table, path))?;
+        }
+        Ok(d)
+    }
+
+    /// Find trajectories whose orbits look like the same object.
+    ///
+    /// Pairwise D-criteria are evaluated among the successful fits; orbits are
+    /// pre-binned on `(q, e, i)` so that only pairs that can pass the threshold are
+    /// compared. Trajectories linked by a chain of pairs with `D ≤ d_max` form a cluster.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `d_max` – Similarity threshold (default `0.1`).
+    /// * `kind` – `"SH"` (Southworth–Hawkins, default) or `"D"` (Drummond).
+    ///
+    /// Return
+    /// ----------
+    /// * A list of clusters (lists of identifiers, sorted), largest first. Trajectories
+    ///   without any similar orbit are not listed.
+    ///
+    /// See also
+    /// ------------
+    /// * [`crate::similarity`] – Criteria and binning.
+    #[pyo3(
+        signature = (d_max=0.1, kind="SH"),
+        text_signature = "($self, d_max=0.1, kind='SH')"
+    )]
+    fn find_duplicates<'py>(
+        &self,
+        py: Python<'py>,
+        d_max: f64,
+        kind: &str,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let criterion = Criterion::parse(kind)?;
+        if !(d_max.is_finite() && d_max >= 0.0) {
+            return Err(PyValueError::new_err(format!(
+                "d_max must be a non-negative finite float, got {d_max}"
+            )));
+        }
+
+        let (keys, shapes): (Vec<&ObjectNumber>, Vec<OrbitShape>) = self
+            .entries
+            .iter()
+            .filter_map(|(k, e)| {
+                let (g, _) = e.outcome.as_ref().ok()?;
+                let shape = OrbitShape::from_elements(g.inner.get_orbit()).ok()?;
+                Some((k, shape))
+            })
+            .unzip();
+
+        let clusters = py.detach(|| similarity::cluster(&shapes, criterion, d_max));
+
+        let out = PyList::empty(py);
+        for members in clusters {
+            let ids = PyList::empty(py);
+            for k in members {
+                ids.append(object_number_to_py(py, keys[k])?)?;
+            }
+            out.append(ids)?;
+        }
+        Ok(out)
+    }
+
+    /// Update orbits with newly arrived observations.
+    ///
+    /// For identifiers present in both `self` and `new_observations`, the new points are
+    /// appended to the stored arc and the existing orbit is refined: it seeds a
+    /// differential correction over the combined arc. When the correction diverges, its
+    /// RMS exceeds `divergence_factor × previous RMS`, or the previous entry was a
+    /// failure, a full Gauss IOD is run on the combined arc instead.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Global Outfit environment.
+    /// * `new_observations` – `TrajectorySet` holding only the new observations.
+    /// * `params` – IOD configuration (used by both paths).
+    /// * `seed` – Optional RNG seed for deterministic runs.
+    /// * `divergence_factor` – Tolerated RMS growth on the incremental path (default 3.0).
+    ///
+    /// Return
+    /// ----------
+    /// * `(OrbitResults, dict[id, str])`: the updated results (self is left untouched) and
+    ///   the path taken per identifier: `"incremental"`, `"full"`, `"new"` or `"unchanged"`.
+    ///   Updated entries carry no cost counters nor rejection tallies, even with
+    ///   `params.collect_costs` / `params.collect_rejection_stats`.
+    ///
+    /// See also
+    /// ------------
+    /// * `iod_engine::refine_orbit` – Incremental refinement strategy.
+    #[pyo3(
+        signature = (env, new_observations, params, seed=None, divergence_factor=3.0),
+        text_signature = "($self, env, new_observations, params, seed=None, divergence_factor=3.0)"
+    )]
+    pub fn update_with<'py>(
+        &self,
+        py: Python<'py>,
+        env: &PyOutfit,
+        new_observations: &TrajectorySet,
+        params: &IODParams,
+        seed: Option<u64>,
+        divergence_factor: f64,
+    ) -> PyResult<(OrbitResults, Bound<'py, PyDict>)> {
+        let mut rng: StdRng = match seed {
+            Some(s) => StdRng::seed_from_u64(s),
+            None => StdRng::from_os_rng(),
+        };
+
+        let mut updated = self.clone();
+        let mut report: Vec<(ObjectNumber, UpdatePath)> = Vec::new();
+
+        py.detach(|| {
+            let mut keys: Vec<&ObjectNumber> = new_observations.inner.keys().collect();
+            keys.sort();
+            for key in keys {
+                let fresh = &new_observations.inner[key];
+                let entry = updated.entries.get(key);
+
+                let mut combined = entry.map(|e| e.observations.clone()).unwrap_or_default();
+                combined.extend(fresh.iter().cloned());
+                let stored = combined.clone();
+
+                let gap = env.coverage_gap(combined.iter().map(|o| o.time));
+                let (outcome, path) = match (gap, entry.map(|e| &e.outcome)) {
+                    (Some(gap), entry) => (
+                        Err(IodError::Coverage(gap)),
+                        if entry.is_some() {
+                            UpdatePath::Full
+                        } else {
+                            UpdatePath::New
+                        },
+                    ),
+                    (None, Some(Ok((g, rms)))) => iod_engine::refine_orbit(
+                        &mut combined,
+                        env,
+                        &mut rng,
+                        params,
+                        (&time_scales::orbit_to_tt(&g.inner), *rms),
+                        divergence_factor,
+                    ),
+                    (None, Some(Err(_))) => (
+                        iod_engine::estimate_best_orbit(&mut combined, env, &mut rng, params),
+                        UpdatePath::Full,
+                    ),
+                    (None, None) => (
+                        iod_engine::estimate_best_orbit(&mut combined, env, &mut rng, params),
+                        UpdatePath::New,
+                    ),
+                };
+
+                let outcome = outcome.and_then(|(g, rms)| {
+                    let g = with_fit_statistics(&combined, &env.outfit_detached(), g)
+                        .with_subsample(&combined, params)
+                        .at_output_epoch(&combined, params.output_epoch)?;
+                    Ok((g, rms))
+                });
+                let error_kind = outcome.as_ref().err().map(IodError::kind);
+                let outcome = outcome.map_err(|e| e.to_string());
+                let weights = outcome.is_ok().then(|| fit_weights(&combined));
+                updated.entries.insert(
+                    key.clone(),
+                    OrbitEntry {
+                        outcome,
+                        error_kind,
+                        observations: stored,
+                        costs: None,
+                        rejections: None,
+                        weights,
+                    },
+                );
+                report.push((key.clone(), path));
+            }
+        });
+
+        let paths = PyDict::new(py);
+        for key in self.entries.keys() {
+            if !new_observations.inner.contains_key(key) {
+                paths.set_item(
+                    object_number_to_py(py, key)?,
+                    UpdatePath::Unchanged.as_str(),
+                )?;
+            }
+        }
+        for (key, path) in report {
+            paths.set_item(object_number_to_py(py, &key)?, path.as_str())?;
+        }
+
+        Ok((updated, paths))
+    }
+}
+
+impl OrbitResults {
+    /// Row of the `TrajectorySet.estimate_orbits_sweep` comparison table.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `config` – Position of the configuration in the sweep.
+    ///
+    /// Return
+    /// ----------
+    /// * A dict with `config`, `n_trajectories`, `n_ok`, `success_rate` and `median_rms`
+    ///   (over the successful fits, `NaN` when there is none).
+    pub(crate) fn comparison_row<'py>(
+        &self,
+        py: Python<'py>,
+        config: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let mut rms: Vec<f64> = self
+            .entries
+            .values()
+            .filter_map(|e| e.outcome.as_ref().ok().map(|(_, r)| *r))
+            .collect();
+        rms.sort_by(f64::total_cmp);
+        let median = match rms.len() {
+            0 => f64::NAN,
+            n if n % 2 == 1 => rms[n / 2],
+            n => 0.5 * (rms[n / 2 - 1] + rms[n / 2]),
+        };
+        let n = self.entries.len();
+
+        let d = PyDict::new(py);
+        d.set_item("config", config)?;
+        d.set_item("n_trajectories", n)?;
+        d.set_item("n_ok", rms.len())?;
+        d.set_item(
+            "success_rate",
+            if n == 0 {
+                f64::NAN
+            } else {
+                rms.len() as f64 / n as f64
+            },
+        )?;
+        d.set_item("median_rms", median)?;
+        Ok(d)
+    }
+}
+
+/// One trajectory of an [`OrbitResults`], as yielded by `OrbitResults.rows()`.
+///
+/// A view on the results (which are immutable): it only holds the results object and
+/// the identifier, and reads the entry when an attribute is accessed.
+#[pyclass(module = "py_outfit", frozen)]
+pub struct ResultRow {
+    results: Py<OrbitResults>,
+    key: ObjectNumber,
+}
+
+impl ResultRow {
+    fn with_entry<R>(&self, py: Python<'_>, f: impl FnOnce(&OrbitEntry) -> R) -> PyResult<R> {
+        let results = self.results.borrow(py);
+        results
+            .entries
+            .get(&self.key)
+            .map(f)
+            .ok_or_else(|| PyKeyError::new_err(format!("Key not found: {:?}", self.key)))
+    }
+}
+
+#[pymethods]
+impl ResultRow {
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        self.with_entry(py, |e| match &e.outcome {
+            Ok((_, rms)) => format!("ResultRow(id={}, ok=True, rms={rms})", self.key),
+            Err(_) => format!(
+                "ResultRow(id={}, ok=False, error_kind={:?})",
+                self.key,
+                e.error_kind.as_deref().unwrap_or_default()
+            ),
+        })
+    }
+
+    /// Trajectory identifier (`int` or `str`).
+    #[getter]
+    fn id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        object_number_to_py(py, &self.key)
+    }
+
+    /// `True` when the IOD produced an orbit.
+    #[getter]
+    fn ok(&self, py: Python<'_>) -> PyResult<bool> {
+        self.with_entry(py, |e| e.outcome.is_ok())
+    }
+
+    /// Orbit of a success, `None` for a failure.
+    #[getter]
+    fn orbit(&self, py: Python<'_>) -> PyResult<Option<GaussResult>> {
+        self.with_entry(py, |e| e.outcome.as_ref().ok().map(|(g, _)| g.clone()))
+    }
+
+    /// RMS of the normalized residuals of a success, `None` for a failure.
+    #[getter]
+    fn rms(&self, py: Python<'_>) -> PyResult<Option<f64>> {
+        self.with_entry(py, |e| e.outcome.as_ref().ok().map(|(_, rms)| *rms))
+    }
+
+    /// Short name of the error of a failure (e.g. `"EphemerisCoverageError"`,
+    /// `"SingularDirectionMatrix"`), `None` for a success.
+    #[getter]
+    fn error_kind(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        self.with_entry(py, |e| e.error_kind.clone())
+    }
+
+    /// Error message of a failure (as in `OrbitResults.errors()`), `None` for a success.
+    #[getter]
+    fn error_message(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        self.with_entry(py, |e| e.outcome.as_ref().err().cloned())
+    }
+
+    /// Number of observations of the fitted arc.
+    #[getter]
+    fn n_obs(&self, py: Python<'_>) -> PyResult<usize> {
+        self.with_entry(py, |e| e.observations.len())
+    }
+
+    /// Cost counters of the run, `None` unless `IODParams.collect_costs` was set.
+    #[getter]
+    fn costs(&self, py: Python<'_>) -> PyResult<Option<IodCosts>> {
+        self.with_entry(py, |e| e.costs)
+    }
+}
+
+/// Import an astropy module, pointing to the missing dependency when it is absent.
+fn import_astropy<'py>(py: Python<'py>, module: &str) -> PyResult<Bound<'py, PyModule>> {
+    py.import(module).map_err(|e| {
+        if e.is_instance_of::<PyImportError>(py) {
+            PyImportError::new_err(format!(
+                "OrbitResults.to_qtable requires astropy ({e}); install it with `pip install astropy`"
+            ))
+        } else {
+            e
+        }
+    })
+}
@@ -0,0 +1,215 @@
+//! Machine-readable description of the tabular exports.
+//!
+//! Each columnar export (`OrbitResults.to_dict`, `Observations.to_dict`,
+//! `TrajectorySet.to_dict`, `TrajectorySet.to_parquet`, the SQLite exports) builds its
+//! keys from the column lists of this module, and `py_outfit.schemas()` publishes the
+//! same lists: the description cannot drift from the data. Database loaders can create
+//! their tables ahead of time from it and validate Parquet/DataFrame files written from
+//! the exports.
+//!
+//! Versioning
+//! -----------------
+//! [`SCHEMA_VERSION`] is bumped on any change of a column list (name, order, dtype,
+//! unit or nullability).
+//!
+//! See also
+//! ------------
+//! * [`crate::units`] – Field tables and unit strings shared with the dict/JSON exports.
+use pyo3::{
+    prelude::*,
+    types::{PyDict, PyList},
+};
+
+use crate::units::{self, Quantity};
+
+/// Version of the export schemas, bumped on any change of a column list.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Value type of an exported column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Dtype {
+    Float64,
+    Int64,
+    String,
+    /// Trajectory identifier: `int` or `str`, as the keys of the containers.
+    Key,
+}
+
+impl Dtype {
+    /// NumPy / pandas name of the type (`"object"` for mixed identifiers).
+    fn name(self) -> &'static str {
+        match self {
+            Dtype::Float64 => "float64",
+            Dtype::Int64 => "int64",
+            Dtype::String => "string",
+            Dtype::Key => "object",
+        }
+    }
+}
+
+/// One column of a tabular export.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Column {
+    pub(crate) name: &'static str,
+    pub(crate) dtype: Dtype,
+    /// Physical quantity of numeric columns (`None` for labels and identifiers).
+    pub(crate) quantity: Option<Quantity>,
+    /// Whether the column may hold `None`.
+    pub(crate) nullable: bool,
+}
+
+impl Column {
+    const fn label(name: &'static str, dtype: Dtype, nullable: bool) -> Self {
+        Self {
+            name,
+            dtype,
+            quantity: None,
+            nullable,
+        }
+    }
+
+    const fn number(name: &'static str, quantity: Quantity, nullable: bool) -> Self {
+        Self {
+            name,
+            dtype: Dtype::Float64,
+            quantity: Some(quantity),
+            nullable,
+        }
+    }
+}
+
+/// `(name, quantity)` pairs of the numeric columns, for [`units::units_block`].
+pub(crate) fn unit_fields(columns: &[Column]) -> Vec<(&'static str, Quantity)> {
+    columns
+        .iter()
+        .filter_map(|c| c.quantity.map(|q| (c.name, q)))
+        .collect()
+}
+
+/// Columns of `Observations.to_dict`, in the order of `Observations.to_numpy`.
+pub(crate) fn observations() -> Vec<Column> {
+    units::OBSERVATION_FIELDS
+        .iter()
+        .map(|(name, q)| Column::number(name, *q, false))
+        .collect()
+}
+
+/// Columns of `TrajectorySet.to_dict`: one row per observation, keyed by trajectory.
+pub(crate) fn trajectory_set() -> Vec<Column> {
+    let mut cols = vec![Column::label("trajectory_id", Dtype::Key, false)];
+    cols.extend(observations());
+    cols
+}
+
+/// Columns of the files written by `TrajectorySet.to_parquet`: those of
+/// [`trajectory_set`] (angles in radians) and the MPC code of the observing site, null
+/// for sites without one.
+pub(crate) fn trajectory_set_file() -> Vec<Column> {
+    let mut cols = trajectory_set();
+    cols.push(Column::label("observer", Dtype::String, true));
+    cols
+}
+
+/// Columns of `OrbitResults.to_dict`: one row per trajectory.
+///
+/// Element columns are the union of the three families (first occurrence order:
+/// Keplerian, equinoctial, cometary); a row fills those of its own family. Failed
+/// trajectories only fill `object_id` and `error`.
+pub(crate) fn results() -> Vec<Column> {
+    let mut cols = vec![
+        Column::label("object_id", Dtype::Key, false),
+        Column::label("stage", Dtype::String, true),
+        Column::label("element_type", Dtype::String, true),
+        Column::number("rms", Quantity::Ratio, true),
+        Column::number("fit_epoch", Quantity::EpochTdb, true),
+        Column::label("error", Dtype::String, true),
+    ];
+    for (name, q) in units::FIT_FIELDS {
+        cols.push(match name {
+            "n_points" => Column {
+                dtype: Dtype::Int64,
+                ..Column::number(name, q, true)
+            },
+            _ => Column::number(name, q, true),
+        });
+    }
+    for (name, q) in units::WEIGHT_FIELDS {
+        cols.push(Column::number(name, q, true));
+    }
+    let families = [
+        &units::KEPLERIAN_FIELDS,
+        &units::EQUINOCTIAL_FIELDS,
+        &units::COMETARY_FIELDS,
+    ];
+    for (name, q) in families.into_iter().flatten() {
+        if !cols.iter().any(|c| c.name == *name) {
+            cols.push(Column::number(name, *q, true));
+        }
+    }
+    cols
+}
+
+/// Columnar dict built from row dicts: one list per column, in schema order.
+///
+/// Row keys outside `columns` are ignored; columns absent from a row get `None`.
+pub(crate) fn table_from_rows<'py>(
+    py: Python<'py>,
+    columns: &[Column],
+    rows: &[Bound<'py, PyDict>],
+) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new(py);
+    for c in columns {
+        let values = PyList::empty(py);
+        for row in rows {
+            match row.get_item(c.name)? {
+                Some(v) => values.append(v)?,
+                None => values.append(py.None())?,
+            }
+        }
+        d.set_item(c.name, values)?;
+    }
+    Ok(d)
+}
+
+fn describe<'py>(
+    py: Python<'py>,
+    columns: &[Column],
+    degrees: bool,
+) -> PyResult<Bound<'py, PyList>> {
+    let out = PyList::empty(py);
+    for c in columns {
+        let d = PyDict::new(py);
+        d.set_item("name", c.name)?;
+        d.set_item("dtype", c.dtype.name())?;
+        d.set_item("unit", c.quantity.map(|q| q.unit(degrees)))?;
+        d.set_item("nullable", c.nullable)?;
+        out.append(d)?;
+    }
+    Ok(out)
+}
+
+/// Describe the columnar exports.
+///
+/// Arguments
+/// -----------------
+/// * `degrees`: Describe the exports produced with `degrees=True` (angle units `"deg"`).
+///
+/// Return
+/// ----------
+/// * A dict with `"schema_version"` (int) and, for each export (`"results"`,
+///   `"observations"`, `"trajectory_set"`, `"trajectory_set_parquet"`), the ordered list of its columns as dicts
+///   `{"name", "dtype", "unit", "nullable"}`. `unit` is `None` for non-numeric columns.
+#[pyfunction]
+#[pyo3(signature = (degrees=false), text_signature = "(degrees=False)")]
+pub fn schemas(py: Python<'_>, degrees: bool) -> PyResult<Bound<'_, PyDict>> {
+    let d = PyDict::new(py);
+    d.set_item("schema_version", SCHEMA_VERSION)?;
+    d.set_item("results", describe(py, &results(), degrees)?)?;
+    d.set_item("observations", describe(py, &observations(), degrees)?)?;
+    d.set_item("trajectory_set", describe(py, &trajectory_set(), degrees)?)?;
+    d.set_item(
+        "trajectory_set_parquet",
+        describe(py, &trajectory_set_file(), false)?,
+    )?;
+    Ok(d)
+}
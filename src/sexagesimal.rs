@@ -0,0 +1,165 @@
+//! Sexagesimal right ascension and declination strings.
+//!
+//! Historical astrometry is often tabulated as `"12 34 56.78"` / `"-05 43 21.0"`.
+//! [`parse_sexagesimal`] converts such columns to radians, so that they can be handed to
+//! `TrajectorySet.from_numpy_radians`.
+//!
+//! Conventions
+//! -----------------
+//! * Right ascension is read as `h m s` (hours, `0 ≤ h < 24`), declination as `±d m s`
+//!   (degrees, `|d| ≤ 90`).
+//! * Fields are separated by colons or whitespace; the seconds may be omitted, in which
+//!   case the minutes may carry a fraction (`"12 34.5"`). Only the last field may be
+//!   fractional.
+//! * The sign may be followed by whitespace (`"- 05 43 21"`) and applies to the whole
+//!   angle, so that `"-00 30 00"` is half a degree south. A right ascension only takes
+//!   `+`.
+use numpy::PyArray1;
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::PyString,
+};
+
+/// `(ra, dec)` arrays in radians.
+type RadianArrays<'py> = (Bound<'py, PyArray1<f64>>, Bound<'py, PyArray1<f64>>);
+
+/// Hours or degrees of a sexagesimal string, `Err` with the reason when malformed.
+///
+/// Arguments
+/// -----------------
+/// * `text` – The string, e.g. `"-05:43:21.0"`.
+/// * `signed` – Whether a `-` sign is accepted (declinations).
+/// * `limit` – Upper bound of the value (24 hours, 90 degrees).
+/// * `inclusive` – Whether `limit` itself is valid (90°, not 24h).
+fn sexagesimal(text: &str, signed: bool, limit: f64, inclusive: bool) -> Result<f64, String> {
+    let text = text.trim();
+    let (negative, rest) = match text.strip_prefix('-') {
+        Some(rest) if signed => (true, rest),
+        Some(_) => return Err("a right ascension cannot be negative".into()),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let fields: Vec<&str> = if rest.contains(':') {
+        rest.trim_start().split(':').map(str::trim).collect()
+    } else {
+        rest.split_whitespace().collect()
+    };
+    if fields.is_empty() || fields.iter().any(|f| f.is_empty()) {
+        return Err("empty field".into());
+    }
+    if !(2..=3).contains(&fields.len()) {
+        return Err(format!(
+            "expected 2 or 3 fields (with or without seconds), got {}",
+            fields.len()
+        ));
+    }
+
+    let first = if signed { "degrees" } else { "hours" };
+    let names = [first, "minutes", "seconds"];
+    let last = fields.len() - 1;
+    let mut value = 0.0;
+    for (k, field) in fields.iter().enumerate() {
+        let number = match k == last {
+            true => field
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite() && *v >= 0.0),
+            false if field.bytes().all(|b| b.is_ascii_digit()) => field.parse::<f64>().ok(),
+            false => None,
+        };
+        let number = number.ok_or_else(|| match k == last {
+            true => format!("{} {field:?} is not a non-negative number", names[k]),
+            false => format!("{} {field:?} is not an integer", names[k]),
+        })?;
+        if k > 0 && number >= 60.0 {
+            return Err(format!("{} {field:?} must be below 60", names[k]));
+        }
+        value += number / 60f64.powi(k as i32);
+    }
+    if value > limit || (value == limit && !inclusive) {
+        return Err(match signed {
+            true => format!("declination beyond {limit} degrees"),
+            false => format!("right ascension beyond {limit} hours"),
+        });
+    }
+    Ok(if negative { -value } else { value })
+}
+
+/// Strings of `values`, `TypeError` naming the first element that is not a `str`.
+fn strings(values: &Bound<'_, PyAny>, name: &str) -> PyResult<Vec<String>> {
+    if values.is_instance_of::<PyString>() {
+        return Err(PyTypeError::new_err(format!(
+            "{name} must be a list or an array of strings, got a single str"
+        )));
+    }
+    values
+        .try_iter()?
+        .enumerate()
+        .map(|(row, item)| {
+            let item = item?;
+            item.extract::<String>().map_err(|_| {
+                PyTypeError::new_err(format!(
+                    "{name}[{row}] = {} ({}): expected a str",
+                    item.repr().map_or_else(|_| "?".into(), |r| r.to_string()),
+                    item.get_type()
+                        .name()
+                        .map_or_else(|_| "?".into(), |n| n.to_string())
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Convert sexagesimal right ascensions and declinations to radians.
+///
+/// Arguments
+/// -----------------
+/// * `ra_strings` – Right ascensions `h m s` (list or NumPy string / object array).
+/// * `dec_strings` – Declinations `±d m s`, same length.
+///
+/// Return
+/// ----------
+/// * `(ra, dec)`: `float64` arrays in radians.
+///
+/// Notes
+/// ----------
+/// * `ValueError` naming the row and the string on the first malformed or out-of-range
+///   value (see the module conventions), and when the lengths differ; `TypeError` on an
+///   element that is not a `str`.
+#[pyfunction]
+#[pyo3(text_signature = "(ra_strings, dec_strings)")]
+pub fn parse_sexagesimal<'py>(
+    py: Python<'py>,
+    ra_strings: &Bound<'py, PyAny>,
+    dec_strings: &Bound<'py, PyAny>,
+) -> PyResult<RadianArrays<'py>> {
+    let ra = strings(ra_strings, "ra_strings")?;
+    let dec = strings(dec_strings, "dec_strings")?;
+    if ra.len() != dec.len() {
+        return Err(PyValueError::new_err(format!(
+            "ra_strings and dec_strings have different lengths ({} and {})",
+            ra.len(),
+            dec.len()
+        )));
+    }
+    let convert = |values: &[String], name: &str, signed: bool, limit: f64| {
+        values
+            .iter()
+            .enumerate()
+            .map(|(row, text)| {
+                sexagesimal(text, signed, limit, signed)
+                    .map(|v| (v * if signed { 1.0 } else { 15.0 }).to_radians())
+                    .map_err(|why| format!("{name}[{row}] = {text:?}: {why}"))
+            })
+            .collect::<Result<Vec<f64>, String>>()
+    };
+    let (ra, dec) = py
+        .detach(|| {
+            Ok::<_, String>((
+                convert(&ra, "ra_strings", false, 24.0)?,
+                convert(&dec, "dec_strings", true, 90.0)?,
+            ))
+        })
+        .map_err(PyValueError::new_err)?;
+    Ok((PyArray1::from_vec(py, ra), PyArray1::from_vec(py, dec)))
+}
@@ -0,0 +1,236 @@
+//! Orbit similarity (D-criteria) and duplicate-object clustering.
+//!
+//! Two tracklets of the same object that were not linked upstream yield two orbits
+//! with nearly identical shapes. This module compares orbits with the classic
+//! D-criteria and groups the similar ones.
+//!
+//! Criteria
+//! -----------------
+//! * `"SH"` – Southworth & Hawkins (1963), on `(q, e, i, Ω, ω)`.
+//! * `"D"` – Drummond (1981), the normalized variant based on the perihelion direction.
+//!
+//! Binning
+//! -----------------
+//! Each criterion bounds some coordinate differences by `D` itself (e.g. `|Δq| ≤ D_SH`).
+//! Orbits are hashed on a grid whose cell widths follow those bounds, so that any pair
+//! with `D ≤ d_max` lies in neighbouring cells; only these pairs are evaluated.
+//!
+//! See also
+//! ------------
+//! * `OrbitResults.find_duplicates` – Python entry point.
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use outfit::{OrbitalElements, OutfitError};
+use pyo3::{exceptions::PyValueError, PyResult};
+
+/// Elements entering the D-criteria (angles in radians, `q` in AU).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct OrbitShape {
+    q: f64,
+    e: f64,
+    i: f64,
+    node: f64,
+    peri: f64,
+}
+
+impl OrbitShape {
+    /// Extract `(q, e, i, Ω, ω)` from any element family.
+    pub(crate) fn from_elements(elements: &OrbitalElements) -> Result<Self, OutfitError> {
+        Ok(match elements {
+            OrbitalElements::Cometary(c) => Self {
+                q: c.perihelion_distance,
+                e: c.eccentricity,
+                i: c.inclination,
+                node: c.ascending_node_longitude,
+                peri: c.periapsis_argument,
+            },
+            other => {
+                let k = other.to_keplerian()?;
+                Self {
+                    q: k.semi_major_axis * (1.0 - k.eccentricity),
+                    e: k.eccentricity,
+                    i: k.inclination,
+                    node: k.ascending_node_longitude,
+                    peri: k.periapsis_argument,
+                }
+            }
+        })
+    }
+}
+
+/// Supported D-criteria.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Criterion {
+    SouthworthHawkins,
+    Drummond,
+}
+
+impl Criterion {
+    /// Parse the Python-facing `kind` argument (`"SH"` or `"D"`, case-insensitive).
+    pub(crate) fn parse(kind: &str) -> PyResult<Self> {
+        match kind.to_ascii_uppercase().as_str() {
+            "SH" => Ok(Criterion::SouthworthHawkins),
+            "D" => Ok(Criterion::Drummond),
+            _ => Err(PyValueError::new_err(format!(
+                "kind must be 'SH' (Southworth-Hawkins) or 'D' (Drummond), got {kind:?}"
+            ))),
+        }
+    }
+
+    /// Distance between two orbits.
+    pub(crate) fn distance(self, a: &OrbitShape, b: &OrbitShape) -> f64 {
+        match self {
+            Criterion::SouthworthHawkins => d_sh(a, b),
+            Criterion::Drummond => d_drummond(a, b),
+        }
+    }
+
+    /// Grid coordinates of an orbit for a search radius `d_max`.
+    ///
+    /// Pairs with `D ≤ d_max` differ by at most one cell along every axis. Axes whose
+    /// bound degenerates (infinite width) collapse to a single cell.
+    fn cell(self, o: &OrbitShape, d_max: f64) -> [i64; 3] {
+        let idx = |x: f64, width: f64| {
+            if width.is_finite() && width > 0.0 && x.is_finite() {
+                (x / width).floor() as i64
+            } else {
+                0
+            }
+        };
+        match self {
+            // |Δq| ≤ D, |Δe| ≤ D, 2·sin(|Δi|/2) ≤ D.
+            Criterion::SouthworthHawkins => {
+                let w_i = if d_max < 2.0 {
+                    2.0 * (d_max / 2.0).asin()
+                } else {
+                    f64::INFINITY
+                };
+                [idx(o.q, d_max), idx(o.e, d_max), idx(o.i, w_i)]
+            }
+            // |Δq|/(q1+q2) ≤ D  ⇔  |Δ ln q| ≤ ln((1+D)/(1−D));  |Δi| ≤ I₂₁ ≤ π·D.
+            Criterion::Drummond => {
+                let w_q = if d_max < 1.0 {
+                    ((1.0 + d_max) / (1.0 - d_max)).ln()
+                } else {
+                    f64::INFINITY
+                };
+                [idx(o.q.ln(), w_q), 0, idx(o.i, PI * d_max)]
+            }
+        }
+    }
+}
+
+/// Mutual inclination `I₂₁` of two orbital planes.
+fn mutual_inclination(a: &OrbitShape, b: &OrbitShape) -> f64 {
+    let cos_i = a.i.cos() * b.i.cos() + a.i.sin() * b.i.sin() * (b.node - a.node).cos();
+    cos_i.clamp(-1.0, 1.0).acos()
+}
+
+/// Southworth & Hawkins D-criterion.
+fn d_sh(a: &OrbitShape, b: &OrbitShape) -> f64 {
+    let d_node = b.node - a.node;
+    let two_sin_half_i = ((2.0 * ((b.i - a.i) / 2.0).sin()).powi(2)
+        + a.i.sin() * b.i.sin() * (2.0 * (d_node / 2.0).sin()).powi(2))
+    .sqrt();
+
+    // Difference of the perihelion longitudes measured from the mutual node.
+    let half_i21 = (two_sin_half_i / 2.0).clamp(-1.0, 1.0).asin();
+    let mut ratio = ((b.i + a.i) / 2.0).cos() * (d_node / 2.0).sin() / half_i21.cos();
+    ratio = ratio.clamp(-1.0, 1.0);
+    let sign = if d_node.abs() > PI { -1.0 } else { 1.0 };
+    let pi21 = b.peri - a.peri + sign * 2.0 * ratio.asin();
+
+    let mean_e = (a.e + b.e) / 2.0;
+    ((b.e - a.e).powi(2)
+        + (b.q - a.q).powi(2)
+        + two_sin_half_i.powi(2)
+        + (mean_e * 2.0 * (pi21 / 2.0).sin()).powi(2))
+    .sqrt()
+}
+
+/// Ecliptic longitude and latitude of the perihelion direction.
+fn perihelion_direction(o: &OrbitShape) -> (f64, f64) {
+    let lambda = o.node + (o.i.cos() * o.peri.sin()).atan2(o.peri.cos());
+    let beta = (o.i.sin() * o.peri.sin()).clamp(-1.0, 1.0).asin();
+    (lambda, beta)
+}
+
+/// Drummond D-criterion.
+fn d_drummond(a: &OrbitShape, b: &OrbitShape) -> f64 {
+    let (l1, b1) = perihelion_direction(a);
+    let (l2, b2) = perihelion_direction(b);
+    let theta = (b1.cos() * b2.cos() * (l2 - l1).cos() + b1.sin() * b2.sin())
+        .clamp(-1.0, 1.0)
+        .acos();
+    let ratio = |x: f64, y: f64| if x + y > 0.0 { (y - x) / (y + x) } else { 0.0 };
+
+    let mean_e = (a.e + b.e) / 2.0;
+    (ratio(a.e, b.e).powi(2)
+        + ratio(a.q, b.q).powi(2)
+        + (mutual_inclination(a, b) / PI).powi(2)
+        + (mean_e * theta / PI).powi(2))
+    .sqrt()
+}
+
+/// Group orbits linked by chains of pairs with `D ≤ d_max`.
+///
+/// Arguments
+/// -----------------
+/// * `shapes` – Orbits to compare.
+/// * `criterion` – D-criterion to use.
+/// * `d_max` – Similarity threshold (inclusive).
+///
+/// Return
+/// ----------
+/// * Clusters of at least two orbits, as sorted indices into `shapes`, ordered by
+///   decreasing size then by first index.
+pub(crate) fn cluster(shapes: &[OrbitShape], criterion: Criterion, d_max: f64) -> Vec<Vec<usize>> {
+    let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    for (k, o) in shapes.iter().enumerate() {
+        grid.entry(criterion.cell(o, d_max)).or_default().push(k);
+    }
+
+    let mut parent: Vec<usize> = (0..shapes.len()).collect();
+    fn root(parent: &mut [usize], mut k: usize) -> usize {
+        while parent[k] != k {
+            parent[k] = parent[parent[k]];
+            k = parent[k];
+        }
+        k
+    }
+
+    for (cell, members) in &grid {
+        for dq in -1..=1 {
+            for de in -1..=1 {
+                for di in -1..=1 {
+                    let other = [cell[0] + dq, cell[1] + de, cell[2] + di];
+                    let Some(neighbours) = grid.get(&other) else {
+                        continue;
+                    };
+                    for &a in members {
+                        // Each unordered pair is visited once.
+                        for &b in neighbours.iter().filter(|&&b| b > a) {
+                            if criterion.distance(&shapes[a], &shapes[b]) <= d_max {
+                                let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                                parent[ra.max(rb)] = ra.min(rb);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for k in 0..shapes.len() {
+        let r = root(&mut parent, k);
+        groups.entry(r).or_default().push(k);
+    }
+    let mut clusters: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+    for g in &mut clusters {
+        g.sort_unstable();
+    }
+    clusters.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+    clusters
+}
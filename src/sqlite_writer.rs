@@ -0,0 +1,241 @@
+//! SQLite export of the tabular results.
+//!
+//! `OrbitResults.to_sqlite` and `TrajectorySet.observations_to_sqlite` write the
+//! columns of their columnar exports (`schemas::results`,
+//! `schemas::trajectory_set_file`) to one table of a SQLite database, created if
+//! needed:
+//!
+//! * `Float64` columns are `REAL`, `Int64` columns `INTEGER`, labels `TEXT`;
+//!   identifiers are `INTEGER` when every id is an integer and `TEXT` otherwise.
+//!   Non-nullable columns are `NOT NULL`; missing values are `NULL`.
+//! * The identifier column is indexed (`"{table}_{column}"`).
+//! * The values are converted on the Rust side, then written with `rusqlite` (SQLite
+//!   compiled into the extension) without the GIL, through one prepared statement
+//!   inside a single transaction: a failed write leaves the database as it was.
+//!
+//! Neither pandas nor the `sqlite3` module of the standard library is used.
+//!
+//! See also
+//! ------------
+//! * [`crate::schemas`] – Column lists shared with the dict and Parquet exports.
+use std::path::Path;
+
+use outfit::constants::ObjectNumber;
+use pyo3::{
+    exceptions::{PyOSError, PyValueError},
+    prelude::*,
+    types::PyList,
+};
+use rusqlite::{params_from_iter, types::Value, Connection};
+
+use crate::schemas::{Column, Dtype};
+
+/// Behaviour when the destination table already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IfExists {
+    /// Raise `ValueError`.
+    Fail,
+    /// Drop the table and create it again.
+    Replace,
+    /// Insert the rows into the table, whose columns must match.
+    Append,
+}
+
+impl IfExists {
+    pub(crate) fn parse(value: &str) -> PyResult<Self> {
+        match value {
+            "fail" => Ok(IfExists::Fail),
+            "replace" => Ok(IfExists::Replace),
+            "append" => Ok(IfExists::Append),
+            other => Err(PyValueError::new_err(format!(
+                "invalid if_exists {other:?} (expected 'fail', 'replace' or 'append')"
+            ))),
+        }
+    }
+}
+
+/// Failure of [`write`], turned into a Python exception once the GIL is held again.
+enum WriteError {
+    /// The table exists and `if_exists="fail"`.
+    Exists(String),
+    /// The table exists with columns other than those of the export.
+    Columns(String, Vec<String>, Vec<String>),
+    /// Error of the database.
+    Sqlite(rusqlite::Error),
+}
+
+impl From<rusqlite::Error> for WriteError {
+    fn from(e: rusqlite::Error) -> Self {
+        WriteError::Sqlite(e)
+    }
+}
+
+impl WriteError {
+    fn into_py(self, path: &Path) -> PyErr {
+        match self {
+            WriteError::Exists(table) => PyValueError::new_err(format!(
+                "table {table:?} already exists (use if_exists='replace' or 'append')"
+            )),
+            WriteError::Columns(table, existing, names) => PyValueError::new_err(format!(
+                "cannot append to table {table:?}: its columns {existing:?} differ from \
+                 those of the export {names:?}"
+            )),
+            WriteError::Sqlite(e) => PyOSError::new_err(format!(
+                "cannot write the SQLite database {}: {e}",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// SQL value of a trajectory or object identifier.
+pub(crate) fn key_value(key: &ObjectNumber) -> Value {
+    match key {
+        ObjectNumber::Int(n) => Value::Integer(i64::from(*n)),
+        ObjectNumber::String(s) => Value::Text(s.clone()),
+    }
+}
+
+/// SQL values of a column of Python objects (`None` for missing values).
+///
+/// Arguments
+/// -----------------
+/// * `values` – Values of `column`, as exported by `to_dict`.
+/// * `column` – Column description, giving the expected type.
+pub(crate) fn py_values(values: &Bound<'_, PyList>, column: &Column) -> PyResult<Vec<Value>> {
+    values
+        .iter()
+        .map(|v| {
+            if v.is_none() {
+                return Ok(Value::Null);
+            }
+            Ok(match column.dtype {
+                Dtype::Float64 => Value::Real(v.extract()?),
+                Dtype::Int64 => Value::Integer(v.extract()?),
+                Dtype::String => Value::Text(v.extract()?),
+                Dtype::Key => match v.extract::<i64>() {
+                    Ok(n) => Value::Integer(n),
+                    Err(_) => Value::Text(v.extract()?),
+                },
+            })
+        })
+        .collect()
+}
+
+/// SQL identifier, double-quoted.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Declared type of `column`; `int_keys` selects `INTEGER` identifiers.
+fn sql_type(column: &Column, int_keys: bool) -> &'static str {
+    match column.dtype {
+        Dtype::Float64 => "REAL",
+        Dtype::Int64 => "INTEGER",
+        Dtype::String => "TEXT",
+        Dtype::Key if int_keys => "INTEGER",
+        Dtype::Key => "TEXT",
+    }
+}
+
+/// Create the table (per `if_exists`) and its index, then insert the rows.
+fn fill(
+    conn: &Connection,
+    table: &str,
+    if_exists: IfExists,
+    columns: &[Column],
+    int_keys: bool,
+    values: &[Vec<Value>],
+) -> Result<(), WriteError> {
+    let quoted = quote(table);
+    let existing: Vec<String> = conn
+        .prepare(&format!("PRAGMA table_info({quoted})"))?
+        .query_map([], |row| row.get(1))?
+        .collect::<Result<_, _>>()?;
+    let names: Vec<String> = columns.iter().map(|c| c.name.to_string()).collect();
+
+    let create = match (existing.is_empty(), if_exists) {
+        (true, _) => true,
+        (false, IfExists::Fail) => return Err(WriteError::Exists(table.to_string())),
+        (false, IfExists::Replace) => {
+            conn.execute(&format!("DROP TABLE {quoted}"), [])?;
+            true
+        }
+        (false, IfExists::Append) => {
+            if existing != names {
+                return Err(WriteError::Columns(table.to_string(), existing, names));
+            }
+            false
+        }
+    };
+    if create {
+        let defs: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let not_null = if c.nullable { "" } else { " NOT NULL" };
+                format!("{} {}{not_null}", quote(c.name), sql_type(c, int_keys))
+            })
+            .collect();
+        conn.execute(&format!("CREATE TABLE {quoted} ({})", defs.join(", ")), [])?;
+    }
+    conn.execute(
+        &format!(
+            "CREATE INDEX IF NOT EXISTS {} ON {quoted} ({})",
+            quote(&format!("{table}_{}", columns[0].name)),
+            quote(columns[0].name)
+        ),
+        [],
+    )?;
+
+    let mut insert = conn.prepare(&format!(
+        "INSERT INTO {quoted} VALUES ({})",
+        vec!["?"; columns.len()].join(", ")
+    ))?;
+    let n_rows = values.first().map_or(0, Vec::len);
+    for row in 0..n_rows {
+        insert.execute(params_from_iter(values.iter().map(|column| &column[row])))?;
+    }
+    Ok(())
+}
+
+/// Write rows to `table` of the SQLite database at `path`, in one transaction.
+///
+/// Arguments
+/// -----------------
+/// * `py` – Python token; the GIL is released while the database is written.
+/// * `path` – Database file, created if needed.
+/// * `table` – Destination table.
+/// * `if_exists` – Behaviour when the table exists, see [`IfExists`].
+/// * `columns` – Columns of the table, the first one (the identifier) indexed.
+/// * `int_keys` – Whether every identifier is an integer.
+/// * `values` – One vector of values per column, in `columns` order.
+///
+/// Return
+/// ----------
+/// * The number of rows written. Raises `ValueError` for an empty table name, an
+///   existing table with `if_exists="fail"` or with other columns on `"append"`, and
+///   `OSError` for errors of the database. The transaction is rolled back on any
+///   error.
+pub(crate) fn write(
+    py: Python<'_>,
+    path: &Path,
+    table: &str,
+    if_exists: IfExists,
+    columns: &[Column],
+    int_keys: bool,
+    values: Vec<Vec<Value>>,
+) -> PyResult<usize> {
+    if table.is_empty() {
+        return Err(PyValueError::new_err("table name must not be empty"));
+    }
+    let n_rows = values.first().map_or(0, Vec::len);
+    py.detach(|| {
+        let mut conn = Connection::open(path)?;
+        let tx = conn.transaction()?;
+        // Dropping the transaction on an error rolls it back.
+        fill(&tx, table, if_exists, columns, int_keys, &values)?;
+        tx.commit()?;
+        Ok(n_rows)
+    })
+    .map_err(|e: WriteError| e.into_py(path))
+}
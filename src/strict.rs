@@ -0,0 +1,145 @@
+//! Strict mode: documented fallbacks promoted to errors.
+//!
+//! By default the bindings recover from a few dubious inputs with a documented
+//! fallback, and report it with a `FallbackWarning`. Production pipelines may prefer
+//! hard failures: with `py_outfit.set_strict(True)`, or `strict=True` on a call, each of
+//! these conditions raises `StrictModeError` instead.
+//!
+//! The conditions are listed in `Check` and published by `py_outfit.strict_checks()`.
+//!
+//! See also
+//! ------------
+//! * [`crate::errors::StrictModeError`], [`crate::errors::FallbackWarning`] – Raised types.
+use std::ffi::CString;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use pyo3::{prelude::*, types::PyDict};
+
+use crate::errors::{FallbackWarning, StrictModeError};
+
+/// Process-wide strict flag, set by `py_outfit.set_strict`.
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+/// Fallback conditions subject to the strict mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Check {
+    /// `PyOutfit(..., error_model=...)` with an unknown model name.
+    UnknownErrorModel,
+    /// Uncertainties substituted by the `bad_sigma="model"` / `"value"` policies.
+    PatchedSigma,
+    /// Trajectories ingested out of time order.
+    UnsortedEpochs,
+    /// Malformed lines or records of a text file skipped with `on_error="skip"`.
+    SkippedLines,
+    /// Ids absent from the set left out of `TrajectorySet.subset`.
+    MissingIds,
+}
+
+impl Check {
+    /// Every check, in documentation order.
+    pub(crate) const ALL: [Check; 5] = [
+        Check::UnknownErrorModel,
+        Check::PatchedSigma,
+        Check::UnsortedEpochs,
+        Check::SkippedLines,
+        Check::MissingIds,
+    ];
+
+    /// Identifier of the check, used in messages and by `strict_checks()`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Check::UnknownErrorModel => "unknown_error_model",
+            Check::PatchedSigma => "patched_sigma",
+            Check::UnsortedEpochs => "unsorted_epochs",
+            Check::SkippedLines => "skipped_lines",
+            Check::MissingIds => "missing_ids",
+        }
+    }
+
+    /// Permissive behaviour of the check.
+    fn fallback(self) -> &'static str {
+        match self {
+            Check::UnknownErrorModel => "an unknown error model name falls back to FCCT14",
+            Check::PatchedSigma => {
+                "non-positive or non-finite uncertainties are substituted by the \
+                 bad_sigma='model' / 'value' policies"
+            }
+            Check::UnsortedEpochs => {
+                "trajectories ingested out of time order are sorted at ingestion"
+            }
+            Check::SkippedLines => {
+                "malformed lines of MPC 80-column files and invalid ADES records are skipped \
+                 with on_error='skip'"
+            }
+            Check::MissingIds => "ids absent from the set are left out of TrajectorySet.subset()",
+        }
+    }
+}
+
+/// Whether strict mode applies, given an optional per-call override.
+pub(crate) fn is_strict(strict: Option<bool>) -> bool {
+    strict.unwrap_or_else(|| STRICT.load(Ordering::Relaxed))
+}
+
+/// Report a fallback: `FallbackWarning` in permissive mode, `StrictModeError` in strict mode.
+///
+/// Arguments
+/// -----------------
+/// * `check` – Condition met.
+/// * `strict` – Per-call override (`None`: the process-wide setting).
+/// * `message` – What happened, prefixed with the check name in the report.
+///
+/// Return
+/// ----------
+/// * `Ok(())` once the warning is issued (or turned into an exception by the warning
+///   filters), `StrictModeError` in strict mode.
+pub(crate) fn fallback(
+    py: Python<'_>,
+    check: Check,
+    strict: Option<bool>,
+    message: &str,
+) -> PyResult<()> {
+    let message = format!("[{}] {message}", check.name());
+    if is_strict(strict) {
+        return Err(StrictModeError::new_err(message));
+    }
+    let category = py.get_type::<FallbackWarning>();
+    let message = CString::new(message).expect("fallback messages contain no NUL byte");
+    PyErr::warn(py, &category, &message, 1)
+}
+
+/// Enable or disable strict mode for the whole process.
+///
+/// Arguments
+/// -----------------
+/// * `enabled`: `True` turns every fallback of `strict_checks()` into a
+///   `StrictModeError`; `False` (the default state) only warns.
+///
+/// Return
+/// ----------
+/// * The previous setting.
+///
+/// Notes
+/// ----------
+/// * Calls taking a `strict` argument override the setting when it is not `None`.
+#[pyfunction]
+#[pyo3(text_signature = "(enabled)")]
+pub fn set_strict(enabled: bool) -> bool {
+    STRICT.swap(enabled, Ordering::Relaxed)
+}
+
+/// Behaviours affected by strict mode.
+///
+/// Return
+/// ----------
+/// * A dict mapping each check name to its permissive fallback. In permissive mode
+///   the fallback is applied and reported with `FallbackWarning`; in strict mode the
+///   call raises `StrictModeError`.
+#[pyfunction]
+pub fn strict_checks(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let d = PyDict::new(py);
+    for check in Check::ALL {
+        d.set_item(check.name(), check.fallback())?;
+    }
+    Ok(d)
+}
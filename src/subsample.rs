@@ -0,0 +1,173 @@
+//! Selection of the observations eligible for triplet enumeration on dense arcs.
+//!
+//! Arcs longer than `max_obs_for_triplets` are thinned before the Gauss triplets are
+//! enumerated. Two strategies are available (`IODParamsBuilder.subsample`):
+//!
+//! * `"uniform"` (default) – the core's selection, evenly spaced in index with both
+//!   endpoints kept;
+//! * `"leverage"` – the endpoints plus the observations carrying the most information
+//!   in a linear-motion fit of the arc (see `leverage_indices`).
+//!
+//! Both are deterministic. Indices refer to the time-sorted arc.
+//!
+//! See also
+//! ------------
+//! * `iod_engine::rank_triplets` – Consumer of the selection.
+//! * [`crate::iod_gauss::GaussResult`] – `subsample_indices` exposes it on results.
+use outfit::{observations::triplets_generator::TripletIndexGenerator, Observations};
+
+use crate::iod_params::{IODParams, Subsample};
+
+/// Sort an arc by epoch, as the core does before enumerating triplets.
+pub(crate) fn sort_by_epoch(obs: &mut Observations) {
+    obs.sort_by(|a, b| a.time.total_cmp(&b.time));
+}
+
+/// Observations kept for triplet enumeration.
+///
+/// Arguments
+/// -----------------
+/// * `obs` – Arc of one trajectory (sorted in place by epoch).
+/// * `params` – IOD configuration (`max_obs_for_triplets`, `subsample`).
+///
+/// Return
+/// ----------
+/// * The kept positions in the sorted arc, increasing, or `None` when the arc is not
+///   longer than `max_obs_for_triplets` (every observation is eligible).
+pub(crate) fn kept_indices(obs: &mut Observations, params: &IODParams) -> Option<Vec<usize>> {
+    let p = &params.inner;
+    if obs.len() <= p.max_obs_for_triplets {
+        sort_by_epoch(obs);
+        return None;
+    }
+    Some(match params.subsample {
+        Subsample::Uniform => TripletIndexGenerator::from_observations(
+            obs,
+            p.dt_min,
+            p.dt_max_triplet,
+            p.max_obs_for_triplets,
+            0,
+        )
+        .selected_original_indices()
+        .to_vec(),
+        Subsample::Leverage => leverage_indices(obs, p.max_obs_for_triplets),
+    })
+}
+
+/// Weighted straight-line fit `v ≈ β₀ + β₁·τ` of one coordinate.
+///
+/// Return
+/// ----------
+/// * Per observation, its leverage (diagonal of the hat matrix, summing to 2) and its
+///   weighted squared residual.
+fn line_fit(tau: &[f64], v: &[f64], w: &[f64]) -> Vec<(f64, f64)> {
+    let (mut s0, mut s1, mut s2, mut b0, mut b1) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for ((&t, &x), &wi) in tau.iter().zip(v).zip(w) {
+        s0 += wi;
+        s1 += wi * t;
+        s2 += wi * t * t;
+        b0 += wi * x;
+        b1 += wi * x * t;
+    }
+    let det = s0 * s2 - s1 * s1;
+    if det <= f64::EPSILON * s0 * s2 {
+        // All epochs equal: a constant fit.
+        let mean = b0 / s0;
+        return w
+            .iter()
+            .zip(v)
+            .map(|(&wi, &x)| (wi / s0, wi * (x - mean).powi(2)))
+            .collect();
+    }
+    // Inverse of the information matrix [[s0, s1], [s1, s2]].
+    let (i00, i01, i11) = (s2 / det, -s1 / det, s0 / det);
+    let (beta0, beta1) = (i00 * b0 + i01 * b1, i01 * b0 + i11 * b1);
+    tau.iter()
+        .zip(v)
+        .zip(w)
+        .map(|((&t, &x), &wi)| {
+            let h = wi * (i00 + 2.0 * i01 * t + i11 * t * t);
+            (h, wi * (x - beta0 - beta1 * t).powi(2))
+        })
+        .collect()
+}
+
+/// Leverage-based thinning of an arc.
+///
+/// Each observation is scored by its contribution to a linear-motion fit of the arc on
+/// the tangent plane (RA·cos δ and δ as straight lines in time, weighted by the
+/// astrometric uncertainties): its share of the fit information (hat-matrix leverage,
+/// high at the ends of the arc) plus its share of the χ² (high for the points far from
+/// the great-circle motion, where the curvature shows). The endpoints are always kept,
+/// then the highest scores, ties broken by position.
+///
+/// Arguments
+/// -----------------
+/// * `obs` – Arc of one trajectory (sorted in place by epoch).
+/// * `max_keep` – Number of observations to keep.
+///
+/// Return
+/// ----------
+/// * The kept positions in the sorted arc, increasing.
+pub(crate) fn leverage_indices(obs: &mut Observations, max_keep: usize) -> Vec<usize> {
+    sort_by_epoch(obs);
+    let n = obs.len();
+    if n <= max_keep {
+        return (0..n).collect();
+    }
+    if max_keep <= 2 {
+        return [0, n - 1].into_iter().take(max_keep).collect();
+    }
+
+    let t_mean = obs.iter().map(|o| o.time).sum::<f64>() / n as f64;
+    let (ra0, dec0) = (obs[n / 2].ra, obs[n / 2].dec);
+    let cos_dec0 = dec0.cos();
+    let weight = |sigma: f64| {
+        if sigma.is_finite() && sigma > 0.0 {
+            sigma.powi(-2)
+        } else {
+            1.0
+        }
+    };
+
+    let tau: Vec<f64> = obs.iter().map(|o| o.time - t_mean).collect();
+    let x: Vec<f64> = obs
+        .iter()
+        .map(|o| {
+            let d = (o.ra - ra0).rem_euclid(std::f64::consts::TAU);
+            let d = if d > std::f64::consts::PI {
+                d - std::f64::consts::TAU
+            } else {
+                d
+            };
+            d * cos_dec0
+        })
+        .collect();
+    let y: Vec<f64> = obs.iter().map(|o| o.dec - dec0).collect();
+    let wx: Vec<f64> = obs.iter().map(|o| weight(o.error_ra * cos_dec0)).collect();
+    let wy: Vec<f64> = obs.iter().map(|o| weight(o.error_dec)).collect();
+
+    let fit_x = line_fit(&tau, &x, &wx);
+    let fit_y = line_fit(&tau, &y, &wy);
+    let chi2: f64 = fit_x.iter().chain(&fit_y).map(|(_, r)| r).sum();
+
+    let mut scored: Vec<(usize, f64)> = (1..n - 1)
+        .map(|i| {
+            let info = (fit_x[i].0 + fit_y[i].0) / 4.0;
+            let misfit = if chi2 > 0.0 {
+                (fit_x[i].1 + fit_y[i].1) / chi2
+            } else {
+                0.0
+            };
+            (i, info + misfit)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut kept: Vec<usize> = [0, n - 1]
+        .into_iter()
+        .chain(scored.into_iter().take(max_keep - 2).map(|(i, _)| i))
+        .collect();
+    kept.sort_unstable();
+    kept
+}
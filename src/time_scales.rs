@@ -0,0 +1,466 @@
+//! TT ↔ TDB conversions at the boundary between the bindings and the core.
+//!
+//! Observation epochs are MJD in Terrestrial Time (TT); element reference epochs are
+//! MJD in Barycentric Dynamical Time (TDB). The two scales differ by a periodic term
+//! of at most ~1.7 ms.
+//!
+//! The Outfit core works on a single time axis, the one of the observations: the
+//! Gauss solver derives the reference epoch from TT observation times (light-time
+//! corrected), and propagation evaluates orbits at TT observation epochs. The bindings
+//! therefore convert at the boundary:
+//!
+//! * orbits leaving the core are relabelled to TDB (`orbit_to_tdb`);
+//! * orbits handed back to the core (residuals, refinement) are converted to TT first
+//!   (`orbit_to_tt`).
+//!
+//! Conventions
+//! -----------------
+//! * `TDB − TT` follows the series of Fairhead & Bretagnon (1990) truncated as in
+//!   USNO Circular 179 (accuracy ~10 µs between 1600 and 2200).
+//! * The geocentric series is used; topocentric terms (≤ 2 µs) are neglected.
+//!
+//! Observation epochs may also be handed to the NumPy loaders in UTC or TAI
+//! (`EpochScale`), and as Julian Dates rather than MJD (`EpochFormat`); they are
+//! converted to MJD (TT) once, at ingestion (`epochs_to_mjd_tt`). Timestamps given as
+//! ISO-8601 strings or NumPy `datetime64` values are converted here too
+//! (`iso_to_mjd_tt`, `unix_ticks_to_mjd_tt`).
+use std::borrow::Cow;
+
+use hifitime::Epoch;
+use outfit::{GaussResult as RsGaussResult, OrbitalElements as RsOrbitalElements};
+use pyo3::{exceptions::PyValueError, PyResult};
+
+/// Julian Date of J2000 expressed as MJD.
+const MJD_J2000: f64 = 51544.5;
+
+/// `TDB − TT` in seconds at a given MJD (either scale: the argument difference is
+/// far below the series accuracy).
+pub(crate) fn tdb_minus_tt_seconds(mjd: f64) -> f64 {
+    let t = (mjd - MJD_J2000) / 36525.0;
+    0.001657 * (628.3076 * t + 6.2401).sin()
+        + 0.000022 * (575.3385 * t + 4.2970).sin()
+        + 0.000014 * (1256.6152 * t + 6.1969).sin()
+        + 0.000005 * (606.9777 * t + 4.0212).sin()
+        + 0.000005 * (52.9691 * t + 0.4444).sin()
+        + 0.000002 * (21.3299 * t + 5.5431).sin()
+        + 0.000010 * t * (628.3076 * t + 4.2490).sin()
+}
+
+/// Convert an MJD from TT to TDB.
+pub(crate) fn tt_to_tdb(mjd_tt: f64) -> f64 {
+    mjd_tt + tdb_minus_tt_seconds(mjd_tt) / 86400.0
+}
+
+/// Convert an MJD from TDB to TT.
+pub(crate) fn tdb_to_tt(mjd_tdb: f64) -> f64 {
+    mjd_tdb - tdb_minus_tt_seconds(mjd_tdb) / 86400.0
+}
+
+/// `TT − TAI` in seconds.
+const TT_MINUS_TAI_S: f64 = 32.184;
+
+/// First day of UTC with whole leap seconds (1972-01-01), as MJD.
+const MJD_UTC_LEAP_SECONDS: f64 = 41317.0;
+
+/// Time scale of the epochs given to the NumPy loaders (`time_scale=`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EpochScale {
+    Tt,
+    Tai,
+    Utc,
+}
+
+impl EpochScale {
+    /// Parse `"TT"`, `"TAI"` or `"UTC"`.
+    pub(crate) fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "TT" => Ok(EpochScale::Tt),
+            "TAI" => Ok(EpochScale::Tai),
+            "UTC" => Ok(EpochScale::Utc),
+            other => Err(PyValueError::new_err(format!(
+                "time_scale must be 'TT', 'TAI' or 'UTC', got {other:?}"
+            ))),
+        }
+    }
+
+    /// Convert MJD epochs of this scale to TT (borrowed as-is for TT).
+    ///
+    /// Return
+    /// ----------
+    /// * The TT epochs. Non-finite epochs are passed through, for the ingestion checks
+    ///   to report. A `ValueError` names the first UTC epoch before 1972, see
+    ///   [`utc_to_tt`].
+    pub(crate) fn to_tt(self, mjd: &[f64]) -> PyResult<Cow<'_, [f64]>> {
+        match self {
+            EpochScale::Tt => Ok(Cow::Borrowed(mjd)),
+            EpochScale::Tai => Ok(Cow::Owned(
+                mjd.iter().map(|t| t + TT_MINUS_TAI_S / 86400.0).collect(),
+            )),
+            EpochScale::Utc => mjd
+                .iter()
+                .enumerate()
+                .map(|(row, &t)| {
+                    utc_to_tt(t).ok_or_else(|| {
+                        PyValueError::new_err(format!(
+                            "mjd[{row}] = {t} is before 1972-01-01 (MJD 41317): UTC epochs are \
+                             only converted from the introduction of leap seconds; give \
+                             earlier epochs in TT or TAI"
+                        ))
+                    })
+                })
+                .collect::<PyResult<Vec<f64>>>()
+                .map(Cow::Owned),
+        }
+    }
+}
+
+/// `JD − MJD` in days.
+const JD_MINUS_MJD: f64 = 2_400_000.5;
+
+/// Boundary between plausible MJD and JD values: JD 2 000 000 falls in the 8th century
+/// and MJD 2 000 000 in the 73rd, so no real epoch is ambiguous.
+const JD_MJD_BOUNDARY: f64 = 2_000_000.0;
+
+/// Day count of the epochs given to the NumPy loaders (`epoch_format=`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EpochFormat {
+    Mjd,
+    Jd,
+}
+
+impl EpochFormat {
+    /// Parse `"mjd"` or `"jd"`.
+    pub(crate) fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "mjd" => Ok(EpochFormat::Mjd),
+            "jd" => Ok(EpochFormat::Jd),
+            other => Err(PyValueError::new_err(format!(
+                "epoch_format must be 'mjd' or 'jd', got {other:?}"
+            ))),
+        }
+    }
+
+    /// Convert epochs of this format to MJD (borrowed as-is for MJD).
+    ///
+    /// Return
+    /// ----------
+    /// * The MJD epochs. Non-finite epochs are passed through, for the ingestion checks
+    ///   to report. A `ValueError` names the first epoch on the wrong side of
+    ///   2 000 000 days: a JD given as MJD (or the reverse) would otherwise be shifted
+    ///   by 2.4 million days without any error.
+    pub(crate) fn to_mjd(self, epochs: &[f64]) -> PyResult<Cow<'_, [f64]>> {
+        let misread = |row: usize, t: f64| {
+            let (claimed, looks_like, hint) = match self {
+                EpochFormat::Mjd => ("an MJD", "a Julian Date", "epoch_format='jd'"),
+                EpochFormat::Jd => ("a Julian Date", "an MJD", "epoch_format='mjd'"),
+            };
+            PyValueError::new_err(format!(
+                "mjd[{row}] = {t} was given as {claimed} but looks like {looks_like} \
+                 (MJD = JD - 2400000.5); pass {hint}"
+            ))
+        };
+        let wrong = |t: f64| match self {
+            EpochFormat::Mjd => t.is_finite() && t > JD_MJD_BOUNDARY,
+            EpochFormat::Jd => t.is_finite() && t < JD_MJD_BOUNDARY,
+        };
+        if let Some((row, &t)) = epochs.iter().enumerate().find(|(_, &t)| wrong(t)) {
+            return Err(misread(row, t));
+        }
+        Ok(match self {
+            EpochFormat::Mjd => Cow::Borrowed(epochs),
+            EpochFormat::Jd => Cow::Owned(epochs.iter().map(|t| t - JD_MINUS_MJD).collect()),
+        })
+    }
+}
+
+/// Convert the epochs given to a NumPy loader to MJD (TT).
+///
+/// Arguments
+/// -----------------
+/// * `epochs`: epochs as given by the caller.
+/// * `epoch_format`: `"mjd"` or `"jd"`, see [`EpochFormat`].
+/// * `time_scale`: `"TT"`, `"TAI"` or `"UTC"`, see [`EpochScale`].
+///
+/// Return
+/// ----------
+/// * The MJD (TT) epochs, borrowed from `epochs` when already MJD (TT).
+pub(crate) fn epochs_to_mjd_tt<'a>(
+    epochs: &'a [f64],
+    epoch_format: &str,
+    time_scale: &str,
+) -> PyResult<Cow<'a, [f64]>> {
+    let scale = EpochScale::parse(time_scale)?;
+    let mjd = EpochFormat::parse(epoch_format)?.to_mjd(epochs)?;
+    Ok(match scale.to_tt(&mjd)? {
+        Cow::Borrowed(_) => mjd,
+        Cow::Owned(tt) => Cow::Owned(tt),
+    })
+}
+
+/// Convert an MJD from UTC to TT.
+///
+/// Follows the quasi-MJD convention of SOFA (and astropy): the fraction of a day
+/// counts that day's actual length, 86 401 s on a day ending with a leap second, so
+/// every instant of that day (23:59:60 included) has its own MJD. `TAI − UTC` comes
+/// from the leap-second table of `hifitime`.
+///
+/// Return
+/// ----------
+/// * The TT epoch, `mjd_utc` itself when not finite, or `None` before 1972, when
+///   `TAI − UTC` drifted continuously instead of stepping by whole seconds.
+pub(crate) fn utc_to_tt(mjd_utc: f64) -> Option<f64> {
+    if !mjd_utc.is_finite() {
+        return Some(mjd_utc);
+    }
+    if mjd_utc < MJD_UTC_LEAP_SECONDS {
+        return None;
+    }
+    let day = mjd_utc.floor();
+    let tai_minus_utc = |mjd: f64| Epoch::from_mjd_utc(mjd).leap_seconds(true);
+    let (start, end) = (tai_minus_utc(day)?, tai_minus_utc(day + 1.0)?);
+    let seconds = (mjd_utc - day) * (86400.0 + end - start);
+    Some(day + (seconds + start + TT_MINUS_TAI_S) / 86400.0)
+}
+
+/// MJD of 1970-01-01.
+const MJD_UNIX_EPOCH: i64 = 40587;
+
+/// Convert an ISO-8601 timestamp to MJD (TT).
+///
+/// Accepted forms are `YYYY-MM-DD`, optionally followed by `T` (or a space) and
+/// `hh:mm[:ss[.fff…]]`, then optionally `Z` or a UTC offset `±hh[:mm]`. The wall-clock
+/// time is on `scale`, shifted by the offset; a leap second (`23:59:60` UTC) is accepted
+/// on the days that have one.
+///
+/// Arguments
+/// -----------------
+/// * `text`: the timestamp.
+/// * `scale`: time scale of the wall-clock time (UTC for alert streams).
+///
+/// Return
+/// ----------
+/// * The MJD (TT) epoch, or the reason the timestamp was rejected.
+pub(crate) fn iso_to_mjd_tt(text: &str, scale: EpochScale) -> Result<f64, String> {
+    let stamp = IsoStamp::parse(text.trim())?;
+    // Whole seconds before the offset is applied; a leap second is added back after, so
+    // that `00:59:60+01:00` lands at the end of the previous UTC day.
+    let leap = stamp.second >= 60.0;
+    let whole = stamp.hour * 3600 + stamp.minute * 60 + if leap { 59 } else { 0 };
+    let shifted = whole - stamp.offset_minutes * 60;
+    let day = stamp.mjd + shifted.div_euclid(86400);
+    let mut seconds = shifted.rem_euclid(86400) as f64;
+    seconds += if leap {
+        stamp.second - 59.0
+    } else {
+        stamp.second
+    };
+    if leap && shifted.rem_euclid(86400) != 86399 {
+        return Err("no leap second at this time".to_string());
+    }
+    wall_clock_to_mjd_tt(day, seconds, scale)
+}
+
+/// Convert a tick count since 1970-01-01 (`datetime64`, which has no leap seconds) to
+/// MJD (TT).
+///
+/// Arguments
+/// -----------------
+/// * `count`: number of ticks since 1970-01-01T00:00:00 on `scale`.
+/// * `ticks_per_second`: resolution of `count` (`1_000_000_000` for nanoseconds).
+/// * `scale`: time scale of the wall-clock time.
+///
+/// Return
+/// ----------
+/// * The MJD (TT) epoch, or the reason it was rejected (UTC before 1972). The day and
+///   the seconds within it are split in integers, so the only rounding is the final
+///   one to `f64` (~0.5 µs at current epochs).
+pub(crate) fn unix_ticks_to_mjd_tt(
+    count: i64,
+    ticks_per_second: i64,
+    scale: EpochScale,
+) -> Result<f64, String> {
+    let ticks_per_day = ticks_per_second * 86400;
+    let day = MJD_UNIX_EPOCH + count.div_euclid(ticks_per_day);
+    let seconds = count.rem_euclid(ticks_per_day) as f64 / ticks_per_second as f64;
+    wall_clock_to_mjd_tt(day, seconds, scale)
+}
+
+/// MJD (TT) of `seconds` into the day `day` (MJD) on `scale`; `seconds` reaches 86 400
+/// only during a UTC leap second.
+fn wall_clock_to_mjd_tt(day: i64, seconds: f64, scale: EpochScale) -> Result<f64, String> {
+    let leap = seconds >= 86400.0;
+    let day_f = day as f64;
+    match scale {
+        EpochScale::Tt | EpochScale::Tai if leap => Err("second 60 only exists in UTC".to_string()),
+        EpochScale::Tt => Ok(day_f + seconds / 86400.0),
+        EpochScale::Tai => Ok(day_f + (seconds + TT_MINUS_TAI_S) / 86400.0),
+        EpochScale::Utc => {
+            if day_f < MJD_UTC_LEAP_SECONDS {
+                return Err("UTC epochs before 1972-01-01 are not supported".to_string());
+            }
+            let tai_minus_utc = |mjd: f64| Epoch::from_mjd_utc(mjd).leap_seconds(true);
+            let (start, end) = tai_minus_utc(day_f)
+                .zip(tai_minus_utc(day_f + 1.0))
+                .ok_or_else(|| "no leap-second data for this date".to_string())?;
+            if leap && end - start < 1.0 {
+                return Err("no leap second at this time".to_string());
+            }
+            Ok(day_f + (seconds + start + TT_MINUS_TAI_S) / 86400.0)
+        }
+    }
+}
+
+/// Fields of a parsed ISO-8601 timestamp, before the offset is applied.
+struct IsoStamp {
+    /// MJD of the calendar date.
+    mjd: i64,
+    hour: i64,
+    minute: i64,
+    /// Seconds with their fraction, up to (but excluding) 61.
+    second: f64,
+    /// Offset of the wall-clock time ahead of UTC, in minutes.
+    offset_minutes: i64,
+}
+
+impl IsoStamp {
+    fn parse(text: &str) -> Result<Self, String> {
+        let cut = text.len().min(10);
+        if !text.is_char_boundary(cut) {
+            return Err(format!("invalid date in {text:?}"));
+        }
+        let (date, rest) = text.split_at(cut);
+        let mjd = parse_date(date)?;
+        let mut stamp = IsoStamp {
+            mjd,
+            hour: 0,
+            minute: 0,
+            second: 0.0,
+            offset_minutes: 0,
+        };
+        let Some(rest) = rest.strip_prefix(['T', 't', ' ']) else {
+            return if rest.is_empty() {
+                Ok(stamp)
+            } else {
+                Err(format!("expected 'T' after the date, got {rest:?}"))
+            };
+        };
+        let (clock, zone) = match rest.find(['Z', 'z', '+', '-']) {
+            Some(at) => rest.split_at(at),
+            None => (rest, ""),
+        };
+        let mut fields = clock.split(':');
+        stamp.hour = field(fields.next(), "hour", 2, 0..=23)?;
+        stamp.minute = field(fields.next(), "minute", 2, 0..=59)?;
+        if let Some(sec) = fields.next() {
+            let (whole, frac) = sec.split_once('.').unwrap_or((sec, "0"));
+            field(Some(whole), "second", 2, 0..=60)?;
+            if frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(format!("invalid second {sec:?}"));
+            }
+            stamp.second = sec.parse().map_err(|_| format!("invalid second {sec:?}"))?;
+        }
+        if fields.next().is_some() {
+            return Err(format!("invalid time {clock:?}"));
+        }
+        stamp.offset_minutes = parse_offset(zone)?;
+        Ok(stamp)
+    }
+}
+
+/// MJD of a `YYYY-MM-DD` date (proleptic Gregorian calendar).
+fn parse_date(date: &str) -> Result<i64, String> {
+    let invalid = || format!("invalid date {date:?}, expected YYYY-MM-DD");
+    let mut parts = date.split('-');
+    let (Some(y), Some(m), Some(d), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let year = field(Some(y), "year", 4, 0..=9999).map_err(|_| invalid())?;
+    let month = field(Some(m), "month", 2, 1..=12).map_err(|_| invalid())?;
+    let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    let day = field(Some(d), "day", 2, 1..=days_in_month).map_err(|_| invalid())?;
+    // Days since 1970-01-01 (H. Hinnant's `days_from_civil`).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Ok(era * 146097 + doe - 719468 + MJD_UNIX_EPOCH)
+}
+
+/// Minutes ahead of UTC of an empty zone, `Z` or `±hh[[:]mm]`.
+fn parse_offset(zone: &str) -> Result<i64, String> {
+    let sign = match zone.chars().next() {
+        None => return Ok(0),
+        Some('Z' | 'z') if zone.len() == 1 => return Ok(0),
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Err(format!("invalid UTC offset {zone:?}")),
+    };
+    let digits = &zone[1..];
+    let (h, m) = match digits.len() {
+        2 => (digits, "00"),
+        4 => digits.split_at(2),
+        5 if digits.as_bytes()[2] == b':' => (&digits[..2], &digits[3..]),
+        _ => return Err(format!("invalid UTC offset {zone:?}")),
+    };
+    let invalid = |_| format!("invalid UTC offset {zone:?}");
+    let hours = field(Some(h), "offset hour", 2, 0..=23).map_err(invalid)?;
+    let minutes = field(Some(m), "offset minute", 2, 0..=59).map_err(invalid)?;
+    Ok(sign * (hours * 60 + minutes))
+}
+
+/// Fixed-width, all-digit field within `range`.
+fn field(
+    text: Option<&str>,
+    name: &str,
+    width: usize,
+    range: std::ops::RangeInclusive<i64>,
+) -> Result<i64, String> {
+    let text = text.ok_or_else(|| format!("missing {name}"))?;
+    if text.len() != width || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("invalid {name} {text:?}"));
+    }
+    let value: i64 = text
+        .parse()
+        .map_err(|_| format!("invalid {name} {text:?}"))?;
+    if !range.contains(&value) {
+        return Err(format!("{name} {value} out of range"));
+    }
+    Ok(value)
+}
+
+/// Copy of `elements` with its reference epoch mapped through `f`.
+fn map_epoch(elements: &RsOrbitalElements, f: impl Fn(f64) -> f64) -> RsOrbitalElements {
+    let mut out = elements.clone();
+    match &mut out {
+        RsOrbitalElements::Keplerian(k) => k.reference_epoch = f(k.reference_epoch),
+        RsOrbitalElements::Equinoctial(q) => q.reference_epoch = f(q.reference_epoch),
+        RsOrbitalElements::Cometary(c) => c.reference_epoch = f(c.reference_epoch),
+    }
+    out
+}
+
+fn map_orbit(orbit: &RsGaussResult, f: impl Fn(f64) -> f64) -> RsGaussResult {
+    match orbit {
+        RsGaussResult::PrelimOrbit(e) => RsGaussResult::PrelimOrbit(map_epoch(e, f)),
+        RsGaussResult::CorrectedOrbit(e) => RsGaussResult::CorrectedOrbit(map_epoch(e, f)),
+    }
+}
+
+/// Relabel an orbit produced by the core (epoch on the TT axis) to a TDB epoch.
+pub(crate) fn orbit_to_tdb(orbit: &RsGaussResult) -> RsGaussResult {
+    map_orbit(orbit, tt_to_tdb)
+}
+
+/// Convert an orbit exposed by the bindings (TDB epoch) to the core's TT axis.
+pub(crate) fn orbit_to_tt(orbit: &RsGaussResult) -> RsGaussResult {
+    map_orbit(orbit, tdb_to_tt)
+}
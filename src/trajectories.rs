@@ -1,30 +1,83 @@
+use std::borrow::Cow;
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use arrow_array::RecordBatch;
+use arrow_schema::ArrowError;
+
 use camino::Utf8PathBuf;
-use numpy::PyReadonlyArray1;
+use numpy::{PyArray1, PyReadonlyArray1};
 use outfit::{
+    constants::RADSEC,
     trajectories::{
         batch_reader::ObservationBatch, trajectory_file::TrajectoryFile,
         trajectory_fit::TrajectoryFit,
     },
-    FullOrbitResult, ObjectNumber,
+    ObjectNumber,
+};
+use parquet::{
+    arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ProjectionMask},
+    errors::ParquetError,
 };
 use pyo3::{
-    exceptions::{PyKeyError, PyValueError},
+    exceptions::{PyKeyError, PyOSError, PyRuntimeError, PyTypeError, PyValueError},
     prelude::*,
-    types::{PyDict, PyIterator, PyList},
+    types::{IntoPyDict, PyDict, PyList},
 };
 
 use rand::rngs::StdRng;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
 
 use crate::{
-    iod_gauss::GaussResult, iod_params::IODParams, observations::Observations, observer::Observer,
-    IntoPyResult, PyOutfit,
+    ades, ades_writer, arrow_ffi, csv_reader,
+    dedup::{self, Dedup, Tolerance},
+    ephemeris::CoverageGap,
+    file_format::{self, FileFormat},
+    fit_statistics::with_fit_statistics,
+    ingest::{
+        self, apply_sigma_policy, check_time_range, BadSigma, Chunk, DroppedRows, Epochs,
+        IdNumbering, Masked, Reals, RecordColumns, Sigma, SiteRows, TrajectoryIds,
+    },
+    interchange,
+    iod_engine::{self, Diagnostics, IodError, IodOutcome, IodPath},
+    iod_gauss::GaussResult,
+    iod_params::IODParams,
+    memory, mpc80, mpc80_writer,
+    observations::{self, observation_columns, Observations},
+    observatories::MpcSites,
+    observer::Observer,
+    orbit_type::keplerian::KeplerianElements,
+    parquet_writer,
+    photometry::PhotometryTable,
+    records,
+    results::{fit_weights, OrbitEntry, OrbitResults},
+    schemas,
+    sqlite_writer::{self, IfExists},
+    strict::{self, Check},
+    time_scales, units, IntoPyResult, PyOutfit,
 };
 
 use pyo3::types::{PyInt, PyString};
 use pyo3::IntoPyObject;
+use rusqlite::types::Value;
+
+/// Malformed records listed in the messages of the text readers (`from_mpc_80col`, ...),
+/// and conflicting ids in those of `merge`.
+const MAX_REPORTED_LINES: usize = 10;
+
+/// Bins of `epoch_histogram` above which `bin_days` is reported as too small.
+const MAX_HISTOGRAM_BINS: usize = 10_000_000;
+
+/// IOD outcome with the orbit wrapped for Python (fit statistics attached).
+type FitOutcome = Result<(GaussResult, f64), IodError>;
+
+/// One trajectory of a batch run: outcome and the counters enabled by the parameters.
+type RunEntry = (ObjectNumber, FitOutcome, Diagnostics);
+
+/// Bin edges and counts of `epoch_histogram`.
+type Histogram<'py> = (Bound<'py, PyArray1<f64>>, Bound<'py, PyArray1<i64>>);
 
 /// Python wrapper for `TrajectorySet`.
 ///
@@ -35,6 +88,54 @@ use pyo3::IntoPyObject;
 #[pyclass]
 pub struct TrajectorySet {
     pub(crate) inner: outfit::TrajectorySet,
+    /// Observations whose uncertainties were substituted at ingestion (`bad_sigma`).
+    pub(crate) patched_sigmas: usize,
+    /// Magnitude and band of the observations given them at ingestion, shared with the
+    /// views (see [`crate::photometry`]).
+    pub(crate) photometry: Arc<PhotometryTable>,
+    /// Observations dropped as duplicates (`dedup=`, `deduplicate`), per trajectory.
+    pub(crate) duplicates_removed: HashMap<ObjectNumber, usize>,
+    /// Rows dropped at ingestion as masked or invalid (`drop_invalid=`), per trajectory;
+    /// `None` for rows whose id is masked.
+    pub(crate) invalid_rows: HashMap<Option<ObjectNumber>, usize>,
+    /// Environment the observations were ingested with, which resolves their sites at
+    /// export (`to_parquet`); shared with the sets derived from this one.
+    pub(crate) env: Option<Arc<Py<PyOutfit>>>,
+}
+
+impl From<outfit::TrajectorySet> for TrajectorySet {
+    fn from(inner: outfit::TrajectorySet) -> Self {
+        Self {
+            inner,
+            patched_sigmas: 0,
+            photometry: Arc::default(),
+            duplicates_removed: HashMap::new(),
+            invalid_rows: HashMap::new(),
+            env: None,
+        }
+    }
+}
+
+/// Environment argument of the loaders: the borrowed `PyOutfit` together with its Python
+/// handle, which the loaded set records to resolve its sites at export.
+pub struct LoaderEnv<'py> {
+    handle: Bound<'py, PyOutfit>,
+    env: PyRefMut<'py, PyOutfit>,
+}
+
+impl<'py> LoaderEnv<'py> {
+    /// The borrowed environment, and the handle to record in the loaded set.
+    fn split(&mut self) -> (&mut PyOutfit, &Bound<'py, PyOutfit>) {
+        (&mut self.env, &self.handle)
+    }
+}
+
+impl<'py> FromPyObject<'py> for LoaderEnv<'py> {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let handle = ob.cast::<PyOutfit>()?.clone();
+        let env = handle.try_borrow_mut()?;
+        Ok(Self { handle, env })
+    }
 }
 
 #[pymethods]
@@ -49,13 +150,17 @@ impl TrajectorySet {
         self.inner.len()
     }
 
-    /// `key in ts` support.
+    /// `key in ts` support: a hash lookup of the id, no `Observations` is built.
     fn __contains__(&self, key: &Bound<'_, PyAny>) -> PyResult<bool> {
-        let k = py_to_object_number(key)?;
-        Ok(self.inner.contains_key(&k))
+        Ok(lookup_key(key)?.is_some_and(|k| self.inner.contains_key(&k)))
     }
 
-    /// Subscript access: `ts[key] -> Trajectory`.
+    /// Subscript access: `ts[key] -> Observations`.
+    ///
+    /// Return
+    /// ----------
+    /// * The `Observations` view of trajectory `key` (an `int` or `str` id); `KeyError`
+    ///   holding `key` when the set has no such trajectory.
     ///
     /// See also
     /// ------------
@@ -63,15 +168,41 @@ impl TrajectorySet {
     /// * [`values`] – Trajectory list.
     /// * [`items`] – Pairs `(key, Trajectory)`.
     fn __getitem__(&self, py: Python<'_>, key: &Bound<'_, PyAny>) -> PyResult<Py<Observations>> {
-        let k = py_to_object_number(key)?;
-        match self.inner.get(&k) {
+        match lookup_key(key)?.and_then(|k| self.inner.get(&k)) {
             Some(obs_list) => Py::new(
                 py,
-                Observations {
-                    inner: obs_list.clone(),
-                },
+                Observations::with_photometry(obs_list.clone(), &self.photometry),
             ),
-            None => Err(PyKeyError::new_err(format!("Key not found: {k:?}"))),
+            None => Err(PyKeyError::new_err(key.clone().unbind())),
+        }
+    }
+
+    /// Non-throwing lookup (like `dict.get`): `ts.get(key, default=None)`.
+    ///
+    /// Return
+    /// ----------
+    /// * The `Observations` view of trajectory `key` (an `int` or `str` id), or `default`
+    ///   when the set has no such trajectory.
+    ///
+    /// See also
+    /// ------------
+    /// * [`__getitem__`] – Lookup raising `KeyError`.
+    #[pyo3(signature = (key, default=None))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        key: &Bound<'py, PyAny>,
+        default: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        match lookup_key(key)?.and_then(|k| self.inner.get(&k)) {
+            Some(obs_list) => Ok(Some(
+                Bound::new(
+                    py,
+                    Observations::with_photometry(obs_list.clone(), &self.photometry),
+                )?
+                .into_any(),
+            )),
+            None => Ok(default),
         }
     }
 
@@ -84,39 +215,61 @@ impl TrajectorySet {
         Ok(out)
     }
 
+    /// Trajectory identifiers, in the order of [`keys`], without touching the observations.
+    ///
+    /// Return
+    /// ----------
+    /// * A `uint64` NumPy array when every id is an integer (64-bit ids included), the
+    ///   list of [`keys`] (`int` and `str`) otherwise.
+    fn ids<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        id_column(py, self.inner.keys())
+    }
+
     /// Return the list of `Trajectory` (like `dict.values()`).
+    ///
+    /// Notes
+    /// ----------
+    /// * Every trajectory is copied into its `Observations`; see [`__iter__`] to walk a
+    ///   large set one trajectory at a time.
     fn values<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
         let out = PyList::empty(py);
         for v in self.inner.values() {
-            out.append(Py::new(py, Observations { inner: v.clone() })?)?;
+            out.append(Py::new(
+                py,
+                Observations::with_photometry(v.clone(), &self.photometry),
+            )?)?;
         }
         Ok(out)
     }
 
     /// Return list of `(key, Trajectory)` pairs (like `dict.items()`).
+    ///
+    /// Notes
+    /// ----------
+    /// * Every trajectory is copied into its `Observations`, as in [`values`].
     fn items<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
         let out = PyList::empty(py);
         for (k, v) in &self.inner {
             let py_k = object_number_to_py(py, k)?;
-            let tr = Py::new(py, Observations { inner: v.clone() })?;
+            let tr = Py::new(
+                py,
+                Observations::with_photometry(v.clone(), &self.photometry),
+            )?;
             let tup = (py_k, tr).into_pyobject(py)?;
             out.append(tup)?;
         }
         Ok(out)
     }
 
-    /// Iterate over keys (like a dict).
-    fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<PyIterator>> {
-        Python::attach(|py| {
-            let list = PyList::empty(py);
-            for k in slf.inner.keys() {
-                let py_k = object_number_to_py(py, k)?;
-                list.append(py_k)?;
-            }
-
-            let it_bound = PyIterator::from_object(list.as_any())?;
-            Ok(it_bound.unbind())
-        })
+    /// Iterate over keys (like a dict), see [`TrajectoryIdIterator`].
+    fn __iter__(slf: &Bound<'_, Self>) -> PyResult<Py<TrajectoryIdIterator>> {
+        let set = slf.borrow();
+        let iter = TrajectoryIdIterator {
+            keys: set.inner.keys().cloned().collect::<Vec<_>>().into_iter(),
+            len: set.inner.len(),
+            set: slf.clone().unbind(),
+        };
+        Py::new(slf.py(), iter)
     }
 
     fn total_observations(&self) -> usize {
@@ -127,397 +280,5211 @@ impl TrajectorySet {
         self.inner.number_of_trajectories()
     }
 
-    fn get_traj_stat(&self) -> String {
-        if let Some(stat) = self.inner.obs_count_stats() {
-            format!("{:#}", stat)
-        } else {
-            "No trajectories available.".to_string()
-        }
+    /// Number of observations whose uncertainties were substituted at ingestion.
+    ///
+    /// Non-zero only for sets ingested with `bad_sigma="model"` or `bad_sigma="value"`;
+    /// accumulates over `add_from_*` calls.
+    #[getter]
+    fn patched_sigma_count(&self) -> usize {
+        self.patched_sigmas
     }
 
-    /// Build a `TrajectorySet` by reading a **MPC 80-column** file.
-    ///
-    /// This mirrors `TrajectoryFile::new_from_80col`. Internally it delegates parsing
-    /// and observation construction to the Rust implementation.
+    /// Number of observations dropped as duplicates, per trajectory.
     ///
-    /// Arguments
-    /// -----------------
-    /// * `env` – Global Outfit state (ephemerides, observers/EOP registry).
-    /// * `path` – File path (`str` or `pathlib.Path`) to a MPC 80-column text file.
+    /// Filled by the loaders called with `dedup=` and by `deduplicate`; accumulates over
+    /// calls and through `extend`.
     ///
     /// Return
     /// ----------
-    /// * A new `PyTrajectorySet` populated from the file.
+    /// * A `dict` mapping the id of every trajectory that had duplicates to the number of
+    ///   observations removed from it.
+    #[getter]
+    fn duplicates_removed<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        counts_to_dict(py, &self.duplicates_removed)
+    }
+
+    /// Number of rows dropped at ingestion, per trajectory: masked rows of
+    /// `numpy.ma.MaskedArray` inputs and, with `drop_invalid=True`, rows holding NaN or
+    /// infinite values.
     ///
-    /// Notes
+    /// Filled by the NumPy loaders and `from_chunks`; accumulates over calls and through
+    /// `extend`.
+    ///
+    /// Return
     /// ----------
-    /// * This call may **panic** on parse errors (same semantics as the Rust API).
-    #[staticmethod]
-    pub fn new_from_mpc_80col(
-        py: Python<'_>,
-        env: &mut PyOutfit,
-        path: &Bound<'_, PyAny>,
-    ) -> PyResult<TrajectorySet> {
-        let p = py_path_to_utf8(py, path)?;
-        let ts = py.detach(|| outfit::TrajectorySet::new_from_80col(&mut env.inner, &p));
-        Ok(TrajectorySet { inner: ts })
+    /// * A `dict` mapping the id of every trajectory that lost rows to the number of rows
+    ///   dropped; rows whose `trajectory_id` is masked are counted under `None`.
+    #[getter]
+    fn invalid_rows_dropped<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let mut keys: Vec<&Option<ObjectNumber>> = self.invalid_rows.keys().collect();
+        keys.sort();
+        let out = PyDict::new(py);
+        for key in keys {
+            let id = match key {
+                Some(key) => object_number_to_py(py, key)?,
+                None => py.None().into_bound(py),
+            };
+            out.set_item(id, self.invalid_rows[key])?;
+        }
+        Ok(out)
     }
 
-    /// Append observations from a **MPC 80-column** file into this set.
+    /// Drop the duplicate observations of every trajectory.
     ///
-    /// This mirrors `TrajectoryFile::add_from_80col`.
+    /// Two observations of a trajectory are duplicates when their epochs differ by at
+    /// most `time_tol` days and their positions by at most `sep_mas` milliarcseconds (see
+    /// [`crate::dedup`]); the first of a group of duplicates is kept.
     ///
     /// Arguments
     /// -----------------
-    /// * `env` – Global Outfit state (ephemerides, observers/EOP registry).
-    /// * `path` – File path (`str` or `pathlib.Path`) to a MPC 80-column text file.
+    /// * `time_tol`: epoch tolerance in days (default `1e-7`, ~9 ms).
+    /// * `sep_mas`: position tolerance in milliarcseconds (default `1.0`).
     ///
     /// Return
     /// ----------
-    /// * `None`. The internal map is updated in place.
+    /// * A `dict` mapping the id of every trajectory that had duplicates to the number of
+    ///   observations removed by this call; the counts are also added to
+    ///   `duplicates_removed`.
     ///
     /// Notes
     /// ----------
-    /// * **No de-duplication** is performed; do not ingest the same file twice if duplicates are undesirable.
-    /// * This call may **panic** on parse errors (same semantics as the Rust API).
-    pub fn add_from_mpc_80col(
+    /// * Raises `ValueError` for a negative or non-finite tolerance.
+    #[pyo3(
+        signature = (time_tol=dedup::DEFAULT_TIME_TOL, sep_mas=dedup::DEFAULT_SEP_MAS),
+        text_signature = "($self, time_tol=1e-7, sep_mas=1.0)"
+    )]
+    fn deduplicate<'py>(
         &mut self,
-        py: Python<'_>,
-        env: &mut PyOutfit,
-        path: &Bound<'_, PyAny>,
-    ) -> PyResult<()> {
-        let p = py_path_to_utf8(py, path)?;
-        py.detach(|| self.inner.add_from_80col(&mut env.inner, &p));
-        Ok(())
+        py: Python<'py>,
+        time_tol: f64,
+        sep_mas: f64,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let tol = Tolerance::new(time_tol, sep_mas)?;
+        let removed = py.detach(|| dedup::dedup_set(&mut self.inner, &tol));
+        let out = counts_to_dict(py, &removed)?;
+        self.count_duplicates(removed);
+        Ok(out)
     }
 
-    /// Build a `TrajectorySet` by reading an **ADES** file (MPC XML/JSON).
+    /// Find the detections placed in more than one trajectory.
+    ///
+    /// Two observations of different trajectories are the same detection when their
+    /// epochs differ by at most `time_tol_days`, their positions by at most
+    /// `pos_tol_arcsec`, and their sites are the same (geocentric positions within 1 km).
+    /// The search uses a hash of epoch and position cells (see [`crate::dedup`]), so it
+    /// scales linearly with the number of observations.
     ///
     /// Arguments
     /// -----------------
-    /// * `env` – Global Outfit state (ephemerides, observers/EOP registry).
-    /// * `path` – ADES file path (`str` or `pathlib.Path`).
-    /// * `error_ra_arcsec` – Optional 1-σ RA uncertainty applied to all rows without explicit σ.
-    /// * `error_dec_arcsec` – Optional 1-σ DEC uncertainty applied to all rows without explicit σ.
+    /// * `time_tol_days`: epoch tolerance in days (default `1e-6`, ~86 ms).
+    /// * `pos_tol_arcsec`: position tolerance in arcseconds (default `0.05`).
     ///
     /// Return
     /// ----------
-    /// * A new `PyTrajectorySet` populated from the ADES file.
+    /// * A list of `(traj_id_a, traj_id_b, n_shared)` tuples, one per pair of trajectories
+    ///   sharing detections, with `traj_id_a` before `traj_id_b` in sorted id order;
+    ///   `n_shared` counts the matching pairs of observations. Sorted by ids.
     ///
     /// Notes
     /// ----------
-    /// * The underlying parser defines the error-handling policy (it may log or panic on invalid data).
-    /// * **No de-duplication** is performed across multiple ingestions.
-    #[staticmethod]
-    pub fn new_from_ades(
-        py: Python<'_>,
-        env: &mut PyOutfit,
-        path: &Bound<'_, PyAny>,
-        error_ra_arcsec: Option<f64>,
-        error_dec_arcsec: Option<f64>,
-    ) -> PyResult<TrajectorySet> {
-        let p = py_path_to_utf8(py, path)?;
-        let ts = py.detach(|| {
-            outfit::TrajectorySet::new_from_ades(
-                &mut env.inner,
-                &p,
-                error_ra_arcsec,
-                error_dec_arcsec,
-            )
-        });
-        Ok(TrajectorySet { inner: ts })
+    /// * Raises `ValueError` for a negative or non-finite tolerance.
+    /// * Duplicates within a single trajectory are not reported; see [`deduplicate`].
+    #[pyo3(
+        signature = (
+            time_tol_days=dedup::DEFAULT_SHARED_TIME_TOL,
+            pos_tol_arcsec=dedup::DEFAULT_SHARED_POS_TOL,
+        ),
+        text_signature = "($self, time_tol_days=1e-6, pos_tol_arcsec=0.05)"
+    )]
+    fn find_shared_observations<'py>(
+        &self,
+        py: Python<'py>,
+        time_tol_days: f64,
+        pos_tol_arcsec: f64,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let tol = Tolerance::shared(time_tol_days, pos_tol_arcsec)?;
+        let (keys, pairs) = py.detach(|| self.shared_pairs(&tol));
+        let mut counts: Vec<((usize, usize), usize)> = Vec::new();
+        for ((a, _), (b, _)) in pairs {
+            match counts.last_mut() {
+                Some((last, n)) if *last == (a, b) => *n += 1,
+                _ => counts.push(((a, b), 1)),
+            }
+        }
+        let out = PyList::empty(py);
+        for ((a, b), n) in counts {
+            let a = object_number_to_py(py, keys[a])?;
+            let b = object_number_to_py(py, keys[b])?;
+            out.append((a, b, n))?;
+        }
+        Ok(out)
     }
 
-    /// Append observations from an **ADES** file (MPC XML/JSON) into this set.
+    /// Remove the detections placed in more than one trajectory, in place.
+    ///
+    /// Shared detections are found as in [`find_shared_observations`]. Trajectories left
+    /// without observations are removed.
     ///
     /// Arguments
     /// -----------------
-    /// * `env` – Global Outfit state (ephemerides, observers/EOP registry).
-    /// * `path` – ADES file path (`str` or `pathlib.Path`).
-    /// * `error_ra_arcsec` – Optional 1-σ RA uncertainty applied to all rows without explicit σ.
-    /// * `error_dec_arcsec` – Optional 1-σ DEC uncertainty applied to all rows without explicit σ.
+    /// * `keep`: which copy of a shared detection to keep: `"first"` (default) keeps it in
+    ///   the trajectory with the smaller id and removes it from the others, `"last"` keeps
+    ///   it in the trajectory with the larger id, `"none"` removes every copy.
+    /// * `time_tol_days`: epoch tolerance in days (default `1e-6`).
+    /// * `pos_tol_arcsec`: position tolerance in arcseconds (default `0.05`).
     ///
     /// Return
     /// ----------
-    /// * `None`. The internal map is updated in place.
+    /// * A `dict` mapping the id of every trajectory that lost observations to the number
+    ///   of observations removed.
     ///
     /// Notes
     /// ----------
-    /// * The underlying parser defines the error-handling policy (it may log or panic on invalid data).
-    /// * **No de-duplication** is performed; avoid re-ingesting the same file twice.
-    pub fn add_from_ades(
+    /// * Raises `ValueError` for an unknown `keep` or a negative or non-finite tolerance.
+    /// * With `"first"` and `"last"`, a detection shared by three trajectories or more is
+    ///   removed from every trajectory but the kept one.
+    #[pyo3(
+        signature = (
+            keep="first",
+            time_tol_days=dedup::DEFAULT_SHARED_TIME_TOL,
+            pos_tol_arcsec=dedup::DEFAULT_SHARED_POS_TOL,
+        ),
+        text_signature = "($self, keep='first', time_tol_days=1e-6, pos_tol_arcsec=0.05)"
+    )]
+    fn remove_shared<'py>(
         &mut self,
-        py: Python<'_>,
-        env: &mut PyOutfit,
-        path: &Bound<'_, PyAny>,
-        error_ra_arcsec: Option<f64>,
-        error_dec_arcsec: Option<f64>,
-    ) -> PyResult<()> {
-        let p = py_path_to_utf8(py, path)?;
-        py.detach(|| {
-            self.inner
-                .add_from_ades(&mut env.inner, &p, error_ra_arcsec, error_dec_arcsec)
-        });
-        Ok(())
+        py: Python<'py>,
+        keep: &str,
+        time_tol_days: f64,
+        pos_tol_arcsec: f64,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let (first, last) = match keep {
+            "first" => (false, true),
+            "last" => (true, false),
+            "none" => (true, true),
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "keep must be 'first', 'last' or 'none', got {other:?}"
+                )))
+            }
+        };
+        let tol = Tolerance::shared(time_tol_days, pos_tol_arcsec)?;
+        let (keys, pairs) = py.detach(|| self.shared_pairs(&tol));
+        let mut drop: HashMap<ObjectNumber, HashSet<usize>> = HashMap::new();
+        for ((a, i), (b, j)) in pairs {
+            if first {
+                drop.entry(keys[a].clone()).or_default().insert(i);
+            }
+            if last {
+                drop.entry(keys[b].clone()).or_default().insert(j);
+            }
+        }
+        let mut removed = HashMap::new();
+        for (key, indices) in drop {
+            let Entry::Occupied(mut entry) = self.inner.entry(key) else {
+                continue;
+            };
+            let obs = entry.get_mut();
+            let mut index = 0;
+            obs.retain(|_| {
+                index += 1;
+                !indices.contains(&(index - 1))
+            });
+            let empty = obs.is_empty();
+            removed.insert(entry.key().clone(), indices.len());
+            if empty {
+                entry.remove();
+            }
+        }
+        counts_to_dict(py, &removed)
     }
 
-    /// Build a `TrajectorySet` from NumPy arrays already expressed in **radians** and **MJD (TT)**.
-    ///
-    /// Solve the ingestion path using a zero-copy `ObservationBatch::from_radians_borrowed`
-    /// and then grouping observations into trajectories via `TrajectorySet::new_from_vec`.
+    /// Drop the observations flagged as rejected by their ADES file.
     ///
-    /// Arguments
-    /// -----------------
-    /// * `trajectory_id`: `np.ndarray[dtype=np.uint32]` — one ID per observation.
-    /// * `ra`: `np.ndarray[dtype=np.float64]` — right ascension in **radians**.
-    /// * `dec`: `np.ndarray[dtype=np.float64]` — declination in **radians**.
-    /// * `error_ra_rad`: `float` — 1-σ RA uncertainty (**radians**) applied uniformly to the batch.
-    /// * `error_dec_rad`: `float` — 1-σ DEC uncertainty (**radians**) applied uniformly to the batch.
-    /// * `mjd_tt`: `np.ndarray[dtype=np.float64]` — epochs in **MJD (TT)** (days).
-    /// * `observer`: `PyObserver` — single observer for the whole batch.
+    /// Only sets read with `include_rejected=True` hold such observations; trajectories
+    /// left without observations are removed.
     ///
     /// Return
     /// ----------
-    /// * A new `PyTrajectorySet` populated from the provided inputs.
+    /// * A `dict` mapping the id of every trajectory that had rejected observations to
+    ///   the number of observations removed.
+    fn drop_rejected<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let flags = &self.photometry;
+        let mut removed = HashMap::new();
+        self.inner.retain(|key, obs| {
+            let before = obs.len();
+            obs.retain(|o| !flags.is_rejected(o));
+            if obs.len() < before {
+                removed.insert(key.clone(), before - obs.len());
+            }
+            !obs.is_empty()
+        });
+        counts_to_dict(py, &removed)
+    }
+
+    /// Memory held by the set, in bytes, per category.
     ///
-    /// Panics
+    /// Computed from the sizes of the stored types and the capacities of the containers
+    /// (see [`crate::memory`]), not estimated from the number of observations.
+    ///
+    /// Return
     /// ----------
-    /// * Never panics; returns `ValueError` on length mismatches.
+    /// * A dict with the keys `observations` (astrometry), `prepared_geometry` (observer
+    ///   positions computed at ingestion), `observer_interning` (site indices),
+    ///   `spare_capacity` (over-allocation), `trajectory_overhead` (hash table, string
+    ///   keys) and their sum `total`.
     ///
-    /// See also
+    /// Notes
     /// ----------
-    /// * [`Self::from_numpy_degrees`] – Degrees/arcsec variant with conversions.
-    #[allow(clippy::too_many_arguments)]
-    #[staticmethod]
-    pub fn from_numpy_radians(
-        py: Python<'_>,
-        pyoutfit: &mut PyOutfit,
-        trajectory_id: PyReadonlyArray1<u32>,
-        ra: PyReadonlyArray1<f64>,
-        dec: PyReadonlyArray1<f64>,
-        error_ra_rad: f64,
-        error_dec_rad: f64,
-        mjd_tt: PyReadonlyArray1<f64>,
-        observer: &Observer,
-    ) -> PyResult<TrajectorySet> {
-        // Borrow NumPy memory as Rust slices (lifetime bound to `py`/this function).
-        let tid = trajectory_id.as_slice()?;
-        let ra_rad = ra.as_slice()?;
-        let dec_rad = dec.as_slice()?;
-        let t_mjd = mjd_tt.as_slice()?;
-
-        // Length checks (clear Python-side errors instead of debug-assert).
-        let n = tid.len();
-        if ra_rad.len() != n || dec_rad.len() != n || t_mjd.len() != n {
-            return Err(PyValueError::new_err(format!(
-                "Length mismatch: trajectory_id={}, ra={}, dec={}, mjd={}",
-                n,
-                ra_rad.len(),
-                dec_rad.len(),
-                t_mjd.len()
-            )));
-        }
+    /// * The observer registry is owned by the environment and not counted here.
+    fn memory_usage<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let usage = memory::usage(&self.inner);
+        let out = PyDict::new(py);
+        out.set_item("observations", usage.observations)?;
+        out.set_item("prepared_geometry", usage.prepared_geometry)?;
+        out.set_item("observer_interning", usage.observer_interning)?;
+        out.set_item("spare_capacity", usage.spare_capacity)?;
+        out.set_item("trajectory_overhead", usage.trajectory_overhead)?;
+        out.set_item("total", usage.total())?;
+        Ok(out)
+    }
 
-        // Build zero-copy batch (Cow::Borrowed) and immediately consume it into a TrajectorySet.
-        let batch = ObservationBatch::from_radians_borrowed(
-            tid,
-            ra_rad,
-            dec_rad,
-            error_ra_rad,
-            error_dec_rad,
-            t_mjd,
-        );
+    /// Release the capacity over-allocated during ingestion.
+    ///
+    /// Return
+    /// ----------
+    /// * The number of bytes released, as accounted by `memory_usage()["total"]`.
+    ///
+    /// Notes
+    /// ----------
+    /// * The observations are left untouched; only the containers are reallocated.
+    fn shrink_to_fit(&mut self) -> usize {
+        let before = memory::usage(&self.inner).total();
+        memory::shrink_to_fit(&mut self.inner);
+        before.saturating_sub(memory::usage(&self.inner).total())
+    }
 
-        // Heavy work without the GIL (ephemerides, positions, etc.).
-        let observer_arc: Arc<outfit::Observer> = observer.inner.clone();
-        let ts_res = py.detach(|| {
-            outfit::TrajectorySet::new_from_vec(&mut pyoutfit.inner, &batch, observer_arc)
+    /// Append the trajectories of another set to this one.
+    ///
+    /// Observations of a trajectory id present in both sets are merged in canonical
+    /// order (see [`crate::ingest::canonical_cmp`]); each observation keeps the site it was ingested
+    /// with, so sets ingested with `topocentric=True` and `topocentric=False` can be
+    /// combined.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `other`: Set to append; left unchanged.
+    #[pyo3(text_signature = "($self, other)")]
+    fn extend(&mut self, other: &TrajectorySet) {
+        self.absorb(TrajectorySet {
+            inner: other.inner.clone(),
+            patched_sigmas: other.patched_sigmas,
+            photometry: other.photometry.clone(),
+            duplicates_removed: other.duplicates_removed.clone(),
+            invalid_rows: other.invalid_rows.clone(),
+            env: other.env.clone(),
         });
-
-        ts_res.map(|ts| TrajectorySet { inner: ts }).into_py()
     }
 
-    /// Build a `TrajectorySet` from NumPy arrays in **degrees** (RA/DEC), **arcseconds** (uncertainties),
-    /// and **MJD (TT)** for epochs.
+    /// Move the trajectories of another set into this one.
     ///
-    /// Internally converts to radians once via `ObservationBatch::from_degrees_owned`,
-    /// then groups observations with `TrajectorySet::new_from_vec`.
+    /// Unlike [`extend`], the observations are moved rather than copied, and `other` is
+    /// left empty (its ingestion counters and photometry included).
     ///
     /// Arguments
     /// -----------------
-    /// * `trajectory_id`: `np.ndarray[dtype=np.uint32]` — one ID per observation.
-    /// * `ra_deg`: `np.ndarray[dtype=np.float64]` — right ascension in **degrees**.
-    /// * `dec_deg`: `np.ndarray[dtype=np.float64]` — declination in **degrees**.
-    /// * `error_ra_arcsec`: `float` — 1-σ RA uncertainty (**arcseconds**).
-    /// * `error_dec_arcsec`: `float` — 1-σ DEC uncertainty (**arcseconds**).
-    /// * `mjd_tt`: `np.ndarray[dtype=np.float64]` — epochs in **MJD (TT)** (days).
-    /// * `observer`: `PyObserver` — single observer for the whole batch.
-    ///
-    /// Return
-    /// ----------
-    /// * A new `PyTrajectorySet` populated from the provided inputs.
+    /// * `other`: Set to empty into this one (e.g. a per-night set built by a worker).
+    /// * `on_conflict`: `"concat"` (default) merges the observations of an id present in
+    ///   both sets in canonical order, as [`extend`]; `"error"` raises `ValueError` naming
+    ///   the shared ids, before anything is moved.
     ///
-    /// Panics
+    /// Notes
     /// ----------
-    /// * Never panics; returns `ValueError` on length mismatches.
-    ///
-    /// See also
-    /// ------------
-    /// * [`Self::from_numpy_radians`] – Zero-copy variant for radian inputs.
-    #[allow(clippy::too_many_arguments)]
-    #[staticmethod]
-    pub fn from_numpy_degrees(
+    /// * Ids are compared as stored: the int `5` and the string `"5"` are distinct
+    ///   trajectories, while 64-bit ints match whichever loader produced them.
+    /// * Merging a set into itself raises `ValueError`.
+    #[pyo3(signature = (other, on_conflict="concat"), text_signature = "($self, other, on_conflict='concat')")]
+    fn merge(
+        &mut self,
         py: Python<'_>,
-        pyoutfit: &mut PyOutfit,
-        trajectory_id: PyReadonlyArray1<u32>,
-        ra_deg: PyReadonlyArray1<f64>,
-        dec_deg: PyReadonlyArray1<f64>,
-        error_ra_arcsec: f64,
-        error_dec_arcsec: f64,
-        mjd_tt: PyReadonlyArray1<f64>,
-        observer: &Observer,
-    ) -> PyResult<TrajectorySet> {
-        let tid = trajectory_id.as_slice()?;
-        let ra_d = ra_deg.as_slice()?;
-        let dec_d = dec_deg.as_slice()?;
-        let t_mjd = mjd_tt.as_slice()?;
-
-        let n = tid.len();
-        if ra_d.len() != n || dec_d.len() != n || t_mjd.len() != n {
-            return Err(PyValueError::new_err(format!(
-                "Length mismatch: trajectory_id={}, ra_deg={}, dec_deg={}, mjd={}",
-                n,
-                ra_d.len(),
-                dec_d.len(),
-                t_mjd.len()
-            )));
+        other: &Bound<'_, TrajectorySet>,
+        on_conflict: &str,
+    ) -> PyResult<()> {
+        let concat = concat_conflicts(on_conflict)?;
+        let mut other = other
+            .try_borrow_mut()
+            .map_err(|_| PyValueError::new_err("cannot merge a TrajectorySet into itself"))?;
+        if !concat {
+            let shared: Vec<&ObjectNumber> = other
+                .inner
+                .keys()
+                .filter(|k| self.inner.contains_key(k))
+                .collect();
+            if !shared.is_empty() {
+                let shown = shared
+                    .iter()
+                    .take(MAX_REPORTED_LINES)
+                    .map(|k| key_repr(py, k))
+                    .collect::<PyResult<Vec<String>>>()?;
+                let more = match shared.len().saturating_sub(MAX_REPORTED_LINES) {
+                    0 => String::new(),
+                    n => format!(" (and {n} more)"),
+                };
+                return Err(PyValueError::new_err(format!(
+                    "{} trajectory id(s) present in both sets: {}{more}; use \
+                     on_conflict='concat' to merge their observations",
+                    shared.len(),
+                    shown.join(", ")
+                )));
+            }
         }
-
-        // Build owned/converted batch once.
-        let batch = ObservationBatch::from_degrees_owned(
-            tid,
-            ra_d,
-            dec_d,
-            error_ra_arcsec,
-            error_dec_arcsec,
-            t_mjd,
+        let taken = std::mem::replace(
+            &mut *other,
+            TrajectorySet::from(outfit::TrajectorySet::default()),
         );
+        py.detach(|| self.absorb(taken));
+        Ok(())
+    }
 
-        let observer_arc: Arc<outfit::Observer> = observer.inner.clone();
-        let ts_res = py.detach(|| {
-            outfit::TrajectorySet::new_from_vec(&mut pyoutfit.inner, &batch, observer_arc)
-        });
-
-        ts_res.map(|ts| TrajectorySet { inner: ts }).into_py()
+    /// Rename trajectories in place.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `mapping`: `dict[old_id, new_id]` (`int` and/or `str` ids). Ids may be exchanged
+    ///   (`{1: 2, 2: 1}`).
+    ///
+    /// Notes
+    /// ----------
+    /// * The mapping is checked before anything is renamed: an `old_id` missing from the
+    ///   set raises `KeyError`, a `new_id` already held by a trajectory that is not renamed
+    ///   or given to several trajectories raises `ValueError`.
+    /// * `duplicates_removed` and `invalid_rows_dropped` follow the new ids, as do the
+    ///   results of the IODs run afterwards.
+    #[pyo3(text_signature = "($self, mapping)")]
+    fn remap_ids(&mut self, py: Python<'_>, mapping: &Bound<'_, PyDict>) -> PyResult<()> {
+        let mut renames = Vec::with_capacity(mapping.len());
+        for (old, new) in mapping.iter() {
+            let old_key = lookup_key(&old)?
+                .filter(|k| self.inner.contains_key(k))
+                .ok_or_else(|| PyKeyError::new_err(old.unbind()))?;
+            renames.push((old_key, py_to_object_number(&new)?));
+        }
+        self.rename(py, renames)
     }
 
-    /// Estimate the best orbit for **all trajectories** in this set.
+    /// Add `offset` to every integer trajectory id, in place (e.g. to keep the ids of
+    /// several campaigns apart before a `merge`).
     ///
-    /// Runs Gauss-based initial orbit determination for each trajectory, using
-    /// the provided `Outfit` state and `IODParams`. A RNG is created locally:
-    /// - when `seed` is provided, a deterministic `StdRng::seed_from_u64(seed)` is used;
-    /// - otherwise, `StdRng::from_entropy()` is used.
+    /// Arguments
+    /// -----------------
+    /// * `offset`: Added to the `int` ids (64-bit ones included), possibly negative;
+    ///   `str` ids are left unchanged.
+    ///
+    /// Notes
+    /// ----------
+    /// * `ValueError`, before anything is renamed, when an id would become negative or
+    ///   exceed 64 bits, or collide with a `str` id of the set; see [`remap_ids`].
+    #[pyo3(text_signature = "($self, offset)")]
+    fn offset_ids(&mut self, py: Python<'_>, offset: i64) -> PyResult<()> {
+        let mut renames = Vec::new();
+        for key in self.inner.keys() {
+            let id = match key {
+                ObjectNumber::Int(id) => u64::from(*id),
+                ObjectNumber::String(s) => match wide_id(s) {
+                    Some(id) => id,
+                    None => continue,
+                },
+            };
+            let shifted = id.checked_add_signed(offset).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "offset {offset} takes trajectory id {id} out of the 64-bit unsigned range"
+                ))
+            })?;
+            renames.push((key.clone(), object_number_from_u64(shifted)));
+        }
+        self.rename(py, renames)
+    }
+
+    /// New set holding copies of the trajectories `ids`.
     ///
     /// Arguments
     /// -----------------
-    /// * `env`: Global Outfit state (ephemeris, EOP, error model).
-    /// * `params`: IOD configuration parameters.
-    /// * `seed`: Optional seed for deterministic RNG (u64). If `None`, a random seed is used.
+    /// * `ids`: Iterable of `int` / `str` ids (a list, a NumPy integer array, ...);
+    ///   repeated ids are selected once.
+    /// * `strict`: Missing ids are left out with a `FallbackWarning` naming them
+    ///   (`missing_ids` check), or raise `StrictModeError` in strict mode; `None` follows
+    ///   `py_outfit.set_strict`.
     ///
     /// Return
     /// ----------
-    /// * A `dict[int, PyGaussResult]` mapping each `trajectory_id` to its orbit-estimation result.
+    /// * A `TrajectorySet` with the selected trajectories; `self` is left untouched.
     ///
-    /// See also
-    /// ------------
-    /// * [`TrajectorySet::from_numpy_radians`] – Build set from radian inputs.
+    /// Notes
+    /// ----------
+    /// * Only the selected observations are copied; the photometry table is shared.
+    ///   `duplicates_removed` and `invalid_rows_dropped` keep the entries of the selected
+    ///   ids, `patched_sigma_count` (not tracked per trajectory) starts at 0.
+    #[pyo3(signature = (ids, strict=None), text_signature = "($self, ids, strict=None)")]
+    fn subset(
+        &self,
+        py: Python<'_>,
+        ids: &Bound<'_, PyAny>,
+        strict: Option<bool>,
+    ) -> PyResult<TrajectorySet> {
+        if ids.is_instance_of::<PyString>() {
+            return Err(PyTypeError::new_err(
+                "ids must be an iterable of trajectory ids, got a single str",
+            ));
+        }
+        let mut keys = Vec::new();
+        let mut missing = Vec::new();
+        for id in ids.try_iter()? {
+            let id = id?;
+            match lookup_key(&id)?.filter(|k| self.inner.contains_key(k)) {
+                Some(key) => keys.push(key),
+                None => missing.push(id),
+            }
+        }
+        if !missing.is_empty() {
+            let shown = missing
+                .iter()
+                .take(MAX_REPORTED_LINES)
+                .map(|id| Ok(id.repr()?.to_string()))
+                .collect::<PyResult<Vec<String>>>()?;
+            let more = match missing.len().saturating_sub(MAX_REPORTED_LINES) {
+                0 => String::new(),
+                n => format!(" (and {n} more)"),
+            };
+            strict::fallback(
+                py,
+                Check::MissingIds,
+                strict,
+                &format!(
+                    "{} id(s) not in the set left out of the subset: {}{more}",
+                    missing.len(),
+                    shown.join(", ")
+                ),
+            )?;
+        }
+        Ok(py.detach(|| self.selected(keys)))
+    }
+
+    /// New set holding copies of `n` trajectories drawn at random, without replacement.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `n`: Number of trajectories, at most `len(self)`.
+    /// * `seed`: Optional seed (u64): the same seed draws the same ids from the same set,
+    ///   whatever the order of the set. If `None`, a random seed is used.
+    ///
+    /// Return
+    /// ----------
+    /// * A `TrajectorySet`, as with [`subset`]; `self` is left untouched.
+    #[pyo3(signature = (n, seed=None), text_signature = "($self, n, seed=None)")]
+    fn sample(&self, py: Python<'_>, n: usize, seed: Option<u64>) -> PyResult<TrajectorySet> {
+        if n > self.inner.len() {
+            return Err(PyValueError::new_err(format!(
+                "cannot sample {n} trajectories from a set of {}",
+                self.inner.len()
+            )));
+        }
+        let mut rng: StdRng = match seed {
+            Some(s) => StdRng::seed_from_u64(s),
+            None => StdRng::from_os_rng(),
+        };
+        Ok(py.detach(|| {
+            let mut keys: Vec<ObjectNumber> = self.inner.keys().cloned().collect();
+            keys.sort();
+            // Partial Fisher-Yates shuffle: the first `n` keys are the sample.
+            for i in 0..n {
+                let j = rng.random_range(i..keys.len());
+                keys.swap(i, j);
+            }
+            keys.truncate(n);
+            self.selected(keys)
+        }))
+    }
+
+    /// Keep the trajectories whose number of observations lies in `[min_obs, max_obs]`.
+    ///
+    /// Gauss IOD needs at least 3 observations, while very long trajectories make the
+    /// triplet selection slow; `get_traj_stat()` summarizes the distribution of counts.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `min_obs`: Smallest number of observations kept (default 3).
+    /// * `max_obs`: Largest number kept; `None` (default) for no upper bound.
+    /// * `inplace`: Remove the other trajectories from this set instead of copying the
+    ///   kept ones into a new set (as [`subset`]).
+    ///
+    /// Return
+    /// ----------
+    /// * `(TrajectorySet, list[id])`: the filtered set (`self` when `inplace`) and the
+    ///   sorted ids of the trajectories left out.
+    #[pyo3(
+        signature = (min_obs=3, max_obs=None, inplace=false),
+        text_signature = "($self, min_obs=3, max_obs=None, inplace=False)"
+    )]
+    fn filter_by_obs_count<'py>(
+        slf: &Bound<'py, Self>,
+        min_obs: usize,
+        max_obs: Option<usize>,
+        inplace: bool,
+    ) -> PyResult<(Bound<'py, TrajectorySet>, Bound<'py, PyList>)> {
+        let py = slf.py();
+        if let Some(max_obs) = max_obs.filter(|&max_obs| max_obs < min_obs) {
+            return Err(PyValueError::new_err(format!(
+                "max_obs ({max_obs}) must be at least min_obs ({min_obs})"
+            )));
+        }
+        let max_obs = max_obs.unwrap_or(usize::MAX);
+        let set = slf.borrow();
+        let (mut kept, mut dropped) = (Vec::new(), Vec::<ObjectNumber>::new());
+        for (key, obs) in &set.inner {
+            match (min_obs..=max_obs).contains(&obs.len()) {
+                true => kept.push(key.clone()),
+                false => dropped.push(key.clone()),
+            }
+        }
+        dropped.sort();
+
+        let dropped_ids = PyList::empty(py);
+        for key in &dropped {
+            dropped_ids.append(object_number_to_py(py, key)?)?;
+        }
+        if !inplace {
+            let set = &*set;
+            let filtered = py.detach(|| set.selected(kept));
+            return Ok((Bound::new(py, filtered)?, dropped_ids));
+        }
+        drop(set);
+        let mut set = slf.borrow_mut();
+        for key in dropped {
+            set.duplicates_removed.remove(&key);
+            set.invalid_rows.remove(&Some(key.clone()));
+            set.inner.remove(&key);
+        }
+        Ok((slf.clone(), dropped_ids))
+    }
+
+    /// New set keeping, in every trajectory, the observations of a time window.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `mjd_min`, `mjd_max`: Bounds of the window (MJD TT, inclusive); `None` leaves the
+    ///   window open on that side.
+    /// * `drop_empty`: Leave out the trajectories with fewer than `min_obs` observations
+    ///   in the window (default `True`); with `False` every trajectory is kept, possibly
+    ///   empty.
+    /// * `min_obs`: Threshold of `drop_empty` (default 1: only empty trajectories).
+    ///
+    /// Return
+    /// ----------
+    /// * A `TrajectorySet` (empty when no trajectory qualifies); `self` is left untouched.
+    ///
+    /// Notes
+    /// ----------
+    /// * `ValueError` on a NaN bound or when `mjd_min > mjd_max`.
+    #[pyo3(
+        signature = (mjd_min=None, mjd_max=None, drop_empty=true, min_obs=1),
+        text_signature = "($self, mjd_min=None, mjd_max=None, drop_empty=True, min_obs=1)"
+    )]
+    fn filter_by_time(
+        &self,
+        py: Python<'_>,
+        mjd_min: Option<f64>,
+        mjd_max: Option<f64>,
+        drop_empty: bool,
+        min_obs: usize,
+    ) -> PyResult<TrajectorySet> {
+        let window = observations::time_window(mjd_min, mjd_max)?;
+        let min_obs = if drop_empty { min_obs } else { 0 };
+        Ok(py.detach(|| {
+            let inner = self
+                .inner
+                .iter()
+                .filter_map(|(key, obs)| {
+                    let kept: outfit::Observations = obs
+                        .iter()
+                        .filter(|o| window.contains(&o.time))
+                        .cloned()
+                        .collect();
+                    (kept.len() >= min_obs).then(|| (key.clone(), kept))
+                })
+                .collect();
+            self.derived(inner)
+        }))
+    }
+
+    /// Remove, in place, the observations whose RA or Dec uncertainty exceeds a threshold,
+    /// then the trajectories left with too few observations.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `max_sigma_arcsec`: Largest uncertainty kept, in arcseconds.
+    /// * `drop_trajectories_below`: Trajectories with fewer observations than this once
+    ///   filtered are removed, whether they lost observations or not (default 3, the
+    ///   minimum of a Gauss IOD); 0 keeps them all, possibly empty.
+    ///
+    /// Return
+    /// ----------
+    /// * A dict: `observations_dropped` (every observation removed from the set, those of
+    ///   the dropped trajectories included), `trajectories_dropped` and the sorted
+    ///   `dropped_ids`.
+    ///
+    /// Notes
+    /// ----------
+    /// * `ValueError` on a NaN or negative threshold, before anything is removed.
+    #[pyo3(
+        signature = (max_sigma_arcsec, drop_trajectories_below=3),
+        text_signature = "($self, max_sigma_arcsec, drop_trajectories_below=3)"
+    )]
+    fn filter_by_uncertainty<'py>(
+        &mut self,
+        py: Python<'py>,
+        max_sigma_arcsec: f64,
+        drop_trajectories_below: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let keep = observations::sigma_filter(max_sigma_arcsec)?;
+        let (observations_dropped, mut dropped) = py.detach(|| {
+            let mut removed = 0;
+            let mut dropped = Vec::new();
+            self.inner.retain(|key, obs| {
+                let before = obs.len();
+                obs.retain(|o| keep(o));
+                removed += before - obs.len();
+                let short = obs.len() < drop_trajectories_below;
+                if short {
+                    removed += obs.len();
+                    dropped.push(key.clone());
+                }
+                !short
+            });
+            (removed, dropped)
+        });
+        dropped.sort();
+        for key in &dropped {
+            self.duplicates_removed.remove(key);
+            self.invalid_rows.remove(&Some(key.clone()));
+        }
+
+        let d = PyDict::new(py);
+        d.set_item("observations_dropped", observations_dropped)?;
+        d.set_item("trajectories_dropped", dropped.len())?;
+        let ids = PyList::empty(py);
+        for key in &dropped {
+            ids.append(object_number_to_py(py, key)?)?;
+        }
+        d.set_item("dropped_ids", ids)?;
+        Ok(d)
+    }
+
+    /// Split every trajectory at an epoch into two new sets.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `mjd`: Epoch of the cut (MJD TT): observations strictly before it go to the first
+    ///   set, the others to the second.
+    ///
+    /// Return
+    /// ----------
+    /// * `(before, after)`: new `TrajectorySet`s; a trajectory lying entirely on one side
+    ///   of the cut only appears in that set. `self` is left untouched.
+    #[pyo3(text_signature = "($self, mjd)")]
+    fn split_at(&self, py: Python<'_>, mjd: f64) -> PyResult<(TrajectorySet, TrajectorySet)> {
+        if mjd.is_nan() {
+            return Err(PyValueError::new_err("mjd must not be NaN"));
+        }
+        Ok(py.detach(|| self.split_with(|obs| obs.partition_point(|o| o.time < mjd))))
+    }
+
+    /// Split every trajectory into its earliest observations and the rest (fit and
+    /// holdout sets).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `frac`: Fraction kept for fitting, in `[0, 1]`.
+    /// * `by`: `"time"` (default) keeps the observations within the first `frac` of the
+    ///   time span of each trajectory; `"count"` keeps its first `ceil(frac * n)`
+    ///   observations.
+    ///
+    /// Return
+    /// ----------
+    /// * `(fit, holdout)`: new `TrajectorySet`s, as with [`split_at`].
+    #[pyo3(signature = (frac, by="time"), text_signature = "($self, frac, by='time')")]
+    fn split_fraction(
+        &self,
+        py: Python<'_>,
+        frac: f64,
+        by: &str,
+    ) -> PyResult<(TrajectorySet, TrajectorySet)> {
+        if !(0.0..=1.0).contains(&frac) {
+            return Err(PyValueError::new_err(format!(
+                "frac must lie in [0, 1], got {frac}"
+            )));
+        }
+        let by_time = match by {
+            "time" => true,
+            "count" => false,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "by must be 'time' or 'count', got {other:?}"
+                )))
+            }
+        };
+        Ok(py.detach(|| {
+            self.split_with(|obs| match obs.first().zip(obs.last()) {
+                _ if frac == 0.0 => 0,
+                Some((first, last)) if by_time => {
+                    let end = first.time + frac * (last.time - first.time);
+                    obs.partition_point(|o| o.time <= end)
+                }
+                _ => (frac * obs.len() as f64).ceil() as usize,
+            })
+        }))
+    }
+
+    /// Sort the observations of every trajectory by epoch.
+    ///
+    /// Every construction path and mutator already stores the trajectories in canonical
+    /// order (epoch, then RA, Dec and site, see [`crate::ingest::canonical_cmp`]), so this
+    /// only reorders a set whose invariant was broken; pipelines can call it as an
+    /// explicit guarantee before a batch IOD.
+    ///
+    /// Return
+    /// ----------
+    /// * The number of trajectories that were out of time order.
+    fn sort_observations(&mut self, py: Python<'_>) -> usize {
+        py.detach(|| {
+            self.inner
+                .values_mut()
+                .map(ingest::sort_by_epoch)
+                .filter(|&sorted| sorted)
+                .count()
+        })
+    }
+
+    /// Whether every trajectory is stored in time order.
+    ///
+    /// Every construction path and mutator keeps this invariant (see [`crate::ingest`]);
+    /// the method scans the observations to verify it.
+    fn is_time_sorted(&self) -> bool {
+        self.inner
+            .values()
+            .all(|obs| ingest::first_unsorted(obs).is_none())
+    }
+
+    fn get_traj_stat(&self) -> String {
+        if let Some(stat) = self.inner.obs_count_stats() {
+            format!("{:#}", stat)
+        } else {
+            "No trajectories available.".to_string()
+        }
+    }
+
+    /// Statistics of [`get_traj_stat`] as a dict, for pipelines.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `histogram`: Add a `"histogram"` entry mapping each number of observations to the
+    ///   number of trajectories having it (default `False`).
+    ///
+    /// Return
+    /// ----------
+    /// * A dict with `min`, `p25`, `median`, `p95` and `max` (ints, nearest-rank
+    ///   percentiles as in `get_traj_stat`, `None` for an empty set), `mean` (float,
+    ///   NaN for an empty set), `total` (observations) and `n_trajectories`.
+    #[pyo3(signature = (histogram=false), text_signature = "($self, histogram=False)")]
+    fn obs_count_stats_dict<'py>(
+        &self,
+        py: Python<'py>,
+        histogram: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let stats = self.inner.obs_count_stats();
+        let total = self.inner.total_observations();
+        let n = self.inner.len();
+        let d = PyDict::new(py);
+        d.set_item("min", stats.as_ref().map(|s| s.min))?;
+        d.set_item("p25", stats.as_ref().map(|s| s.p25))?;
+        d.set_item("median", stats.as_ref().map(|s| s.median))?;
+        d.set_item("p95", stats.as_ref().map(|s| s.p95))?;
+        d.set_item("max", stats.as_ref().map(|s| s.max))?;
+        d.set_item("mean", total as f64 / n as f64)?;
+        d.set_item("total", total)?;
+        d.set_item("n_trajectories", n)?;
+        if histogram {
+            let mut counts: Vec<(usize, usize)> = self
+                .inner
+                .values()
+                .fold(HashMap::new(), |mut acc, obs| {
+                    *acc.entry(obs.len()).or_default() += 1;
+                    acc
+                })
+                .into_iter()
+                .collect();
+            counts.sort_unstable();
+            d.set_item("histogram", counts.into_py_dict(py)?)?;
+        }
+        Ok(d)
+    }
+
+    /// Time coverage of the whole set.
+    ///
+    /// Return
+    /// ----------
+    /// * `(mjd_min, mjd_max, span_days)` over every observation (MJD TT); NaNs for a set
+    ///   without observations.
+    fn time_span(&self, py: Python<'_>) -> (f64, f64, f64) {
+        match py.detach(|| self.epoch_range()) {
+            Some((min, max)) => (min, max, max - min),
+            None => (f64::NAN, f64::NAN, f64::NAN),
+        }
+    }
+
+    /// Histogram of the epochs of every observation.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `bin_days`: Width of the bins, in days (default 1). The edges are multiples of
+    ///   `bin_days` (whole MJDs by default).
+    ///
+    /// Return
+    /// ----------
+    /// * `(edges, counts)`: `float64` bin edges (MJD TT, one more than the bins) and
+    ///   `int64` counts; the last bin includes its right edge. Both are empty for a set
+    ///   without observations.
+    ///
+    /// Notes
+    /// ----------
+    /// * `ValueError` when `bin_days` is not a positive finite number, or would make more
+    ///   than 10 million bins.
+    #[pyo3(signature = (bin_days=1.0), text_signature = "($self, bin_days=1.0)")]
+    fn epoch_histogram<'py>(&self, py: Python<'py>, bin_days: f64) -> PyResult<Histogram<'py>> {
+        if !(bin_days.is_finite() && bin_days > 0.0) {
+            return Err(PyValueError::new_err(format!(
+                "bin_days must be a positive finite number, got {bin_days}"
+            )));
+        }
+        let Some((min, max)) = py.detach(|| self.epoch_range()) else {
+            return Ok((
+                PyArray1::from_vec(py, vec![]),
+                PyArray1::from_vec(py, vec![]),
+            ));
+        };
+        let start = (min / bin_days).floor();
+        let bins = ((max / bin_days).floor() - start) as usize + 1;
+        if bins > MAX_HISTOGRAM_BINS {
+            return Err(PyValueError::new_err(format!(
+                "bin_days = {bin_days} makes {bins} bins over [{min}, {max}] (at most \
+                 {MAX_HISTOGRAM_BINS}); use wider bins"
+            )));
+        }
+        let counts = py.detach(|| {
+            let mut counts = vec![0i64; bins];
+            for o in self.inner.values().flatten() {
+                let bin = ((o.time / bin_days).floor() - start) as usize;
+                counts[bin.min(bins - 1)] += 1;
+            }
+            counts
+        });
+        let edges = (0..=bins).map(|k| (start + k as f64) * bin_days);
+        Ok((
+            PyArray1::from_iter(py, edges),
+            PyArray1::from_vec(py, counts),
+        ))
+    }
+
+    /// Arc statistics of every trajectory, as columns.
+    ///
+    /// Computed with the GIL released, to pre-select the trajectories worth an IOD
+    /// attempt without iterating in Python.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Environment the set was ingested with (resolves the observing sites).
+    ///
+    /// Return
+    /// ----------
+    /// * A dict of NumPy arrays, one entry per trajectory sorted by id: `trajectory_id`
+    ///   (as [`ids`]), `first_mjd`, `last_mjd` and `arc_days` (MJD TT, days), `n_obs`,
+    ///   `n_nights` and `n_observers`.
+    ///
+    /// Notes
+    /// ----------
+    /// * A night runs from local mean noon to the next one at the observing site (from its
+    ///   longitude), so the detections of one night count once even across 0h UTC; nights
+    ///   at different sites are told apart by their date only.
+    /// * Empty trajectories have NaN epochs and arc, and zero counts.
+    #[pyo3(text_signature = "($self, env)")]
+    fn arc_statistics<'py>(&self, py: Python<'py>, env: &PyOutfit) -> PyResult<Bound<'py, PyDict>> {
+        let mut keys: Vec<&ObjectNumber> = self.inner.keys().collect();
+        keys.sort();
+        let stats: Vec<ArcStats> = py.detach(|| {
+            let outfit = env.outfit_detached();
+            keys.par_iter()
+                .map(|k| ArcStats::of(&self.inner[*k], &outfit))
+                .collect()
+        });
+
+        let d = PyDict::new(py);
+        d.set_item("trajectory_id", id_column(py, keys.iter().copied())?)?;
+        let float = |f: fn(&ArcStats) -> f64| PyArray1::from_iter(py, stats.iter().map(f));
+        let count =
+            |f: fn(&ArcStats) -> usize| PyArray1::from_iter(py, stats.iter().map(|s| f(s) as i64));
+        d.set_item("first_mjd", float(|s| s.first))?;
+        d.set_item("last_mjd", float(|s| s.last))?;
+        d.set_item("arc_days", float(|s| s.last - s.first))?;
+        d.set_item("n_obs", count(|s| s.n_obs))?;
+        d.set_item("n_nights", count(|s| s.n_nights))?;
+        d.set_item("n_observers", count(|s| s.n_observers))?;
+        Ok(d)
+    }
+
+    /// Build a `TrajectorySet` by reading a **MPC 80-column** file.
+    ///
+    /// This mirrors `TrajectoryFile::new_from_80col`. Internally it delegates parsing
+    /// and observation construction to the Rust implementation.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Global Outfit state (ephemerides, observers/EOP registry).
+    /// * `path` – File path (`str` or `pathlib.Path`) to a MPC 80-column text file.
+    ///
+    /// Return
+    /// ----------
+    /// * A new `PyTrajectorySet` populated from the file.
+    ///
+    /// Notes
+    /// ----------
+    /// * This call may **panic** on parse errors (same semantics as the Rust API).
+    /// * `bad_sigma` / `bad_sigma_value` (arcsec): policy for non-positive uncertainties,
+    ///   see [`TrajectorySet::from_numpy_degrees`].
+    /// * `strict`: per-call strict mode, see [`TrajectorySet::from_numpy_degrees`].
+    #[staticmethod]
+    #[pyo3(signature = (env, path, bad_sigma="error", bad_sigma_value=None, strict=None))]
+    pub fn new_from_mpc_80col(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let p = py_path_to_utf8(py, path)?;
+        let ts =
+            py.detach(|| outfit::TrajectorySet::new_from_80col(&mut env.inner_mut_detached(), &p));
+        TrajectorySet::checked(py, ts, env, handle, policy, strict, false)
+    }
+
+    /// Append observations from a **MPC 80-column** file into this set.
+    ///
+    /// This mirrors `TrajectoryFile::add_from_80col`.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Global Outfit state (ephemerides, observers/EOP registry).
+    /// * `path` – File path (`str` or `pathlib.Path`) to a MPC 80-column text file.
+    ///
+    /// Return
+    /// ----------
+    /// * `None`. The internal map is updated in place.
+    ///
+    /// Notes
+    /// ----------
+    /// * **No de-duplication** is performed; do not ingest the same file twice if duplicates are undesirable.
+    /// * This call may **panic** on parse errors (same semantics as the Rust API).
+    /// * `bad_sigma` / `bad_sigma_value` (arcsec): policy for non-positive uncertainties,
+    ///   see [`TrajectorySet::from_numpy_degrees`]. On rejection the set is left unchanged.
+    /// * `strict`: per-call strict mode, see [`TrajectorySet::from_numpy_degrees`].
+    #[pyo3(signature = (env, path, bad_sigma="error", bad_sigma_value=None, strict=None))]
+    pub fn add_from_mpc_80col(
+        &mut self,
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+    ) -> PyResult<()> {
+        let (env, handle) = env.split();
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let p = py_path_to_utf8(py, path)?;
+        let ts =
+            py.detach(|| outfit::TrajectorySet::new_from_80col(&mut env.inner_mut_detached(), &p));
+        self.absorb(TrajectorySet::checked(
+            py, ts, env, handle, policy, strict, false,
+        )?);
+        Ok(())
+    }
+
+    /// Build a `TrajectorySet` from a **MPC 80-column** file holding any number of objects.
+    ///
+    /// Unlike [`Self::new_from_mpc_80col`], every line is parsed on its own: observations
+    /// are grouped by designation and each one is attributed to the site of its own
+    /// observatory code. Parsing runs in parallel, without the GIL.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Global Outfit state; observatory codes are resolved in its MPC table.
+    /// * `path` – File path (`str` or `pathlib.Path`).
+    /// * `on_error` – `"raise"` (default): malformed lines raise `ValueError`; `"skip"`:
+    ///   they are dropped and reported with a `FallbackWarning` (`skipped_lines` check).
+    /// * `bad_sigma` / `bad_sigma_value` (arcsec): policy for non-positive uncertainties,
+    ///   see [`TrajectorySet::from_numpy_degrees`].
+    /// * `strict`: per-call strict mode, see [`TrajectorySet::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet` keyed by packed designation (`"00433"`, `"K24A00A"`), the
+    ///   observations of each object in time order (file order on equal epochs).
+    ///
+    /// Notes
+    /// ----------
+    /// * Malformed lines (short, invalid date / RA / Dec, missing designation, unknown
+    ///   observatory code) are reported with their 1-based line numbers (the first
+    ///   `MAX_REPORTED_LINES` in the message).
+    /// * Blank lines, second lines of satellite and roving records and radar records are
+    ///   ignored.
+    /// * Uncertainties are derived from the precision of the RA and Dec fields, floored
+    ///   by the site accuracy of the error model, as in the core reader.
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, path, on_error="raise", bad_sigma="error", bad_sigma_value=None, strict=None
+    ))]
+    pub fn from_mpc_80col(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        on_error: &str,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let skip = skip_malformed(on_error)?;
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let p = py_path_to_utf8(py, path)?;
+        let text = read_text(py, &p)?;
+
+        let (records, malformed) = py.detach(|| {
+            let (records, mut malformed) = mpc80::parse(&text);
+            let (records, unknown) =
+                records::known_sites(env.mpc_sites(), &env.outfit_detached(), records);
+            malformed.extend(unknown);
+            (records, malformed)
+        });
+        report_malformed(py, &p, "line", "line", malformed, skip, strict)?;
+
+        let built = py.detach(|| records::build(&mut env.inner_mut_detached(), &records, true));
+        TrajectorySet::checked(py, built.into_py()?.0, env, handle, policy, strict, false)
+    }
+
+    /// Build a `TrajectorySet` from an **ADES XML** file.
+    ///
+    /// Every `<optical>` record (flat list or `<obsBlock>` structure) becomes an
+    /// observation: `obsTime` (UTC) is converted to MJD (TT), `ra` / `dec` from degrees,
+    /// `rmsRA` / `rmsDec` from arcseconds. The site is the record's `stn` (or the
+    /// `mpcCode` of its block), resolved in the MPC table of `env`. Parsing runs without
+    /// the GIL.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Global Outfit state.
+    /// * `path` – File path (`str` or `pathlib.Path`).
+    /// * `error_ra_arcsec`, `error_dec_arcsec` – Uncertainties of the records without
+    ///   `rmsRA` / `rmsDec` (on-sky, arcseconds). `None` leaves them unknown and defers
+    ///   to `bad_sigma`.
+    /// * `group_by` – Identifier fields naming the trajectory of a record, the first one
+    ///   present wins (default: `trkSub`, `provID`, `permID`).
+    /// * `on_error` – `"raise"` or `"skip"` invalid records, see
+    ///   [`Self::from_mpc_80col`].
+    /// * `bad_sigma`, `bad_sigma_value` (arcsec), `strict`: see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `include_rejected` – Keep the records flagged as rejected (`selAst` of `D` /
+    ///   `d`, or an `exclude` field), readable afterwards with `Observations.rejected()`
+    ///   and removed with `drop_rejected()`. By default they are dropped.
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet` keyed by identifier (always a string), the observations
+    ///   of each trajectory in document order.
+    ///
+    /// Notes
+    /// ----------
+    /// * Unlike [`Self::new_from_ades`], which forwards the values to the core as they
+    ///   are written, angles, uncertainties and epochs are converted to the units and
+    ///   time scale of the bindings.
+    /// * The RA uncertainty stored is that of the RA coordinate, `rmsRA / cos(dec)`.
+    /// * Invalid records (missing identifier, field, unknown station, values out of
+    ///   range) are reported with the line of their opening tag; an XML syntax error
+    ///   raises `ValueError` whatever `on_error`.
+    #[staticmethod]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        env, path, error_ra_arcsec=None, error_dec_arcsec=None, group_by=None,
+        on_error="raise", bad_sigma="error", bad_sigma_value=None, strict=None,
+        include_rejected=false
+    ))]
+    pub fn from_ades_xml(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        error_ra_arcsec: Option<f64>,
+        error_dec_arcsec: Option<f64>,
+        group_by: Option<Vec<String>>,
+        on_error: &str,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        include_rejected: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let skip = skip_malformed(on_error)?;
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let opts = ades_options(group_by, error_ra_arcsec, error_dec_arcsec)?;
+        let p = py_path_to_utf8(py, path)?;
+        let text = read_text(py, &p)?;
+
+        let parsed = py
+            .detach(|| ades::parse_xml(&text, &opts))
+            .map_err(|e| PyValueError::new_err(format!("invalid ADES XML in {p}: {e}")))?;
+        ades_set(
+            py,
+            env,
+            handle,
+            &p,
+            parsed,
+            "line",
+            skip,
+            policy,
+            strict,
+            include_rejected,
+        )
+    }
+
+    /// Build a `TrajectorySet` from an **ADES PSV** (pipe-separated values) file.
+    ///
+    /// Same field mapping, units and options as [`Self::from_ades_xml`]. Each block of
+    /// the file starts with its `#` / `!` header (`! mpcCode` under `# observatory`
+    /// giving the station of rows without `stn`); the first other line of a block is
+    /// detected as its field header. Padded columns are accepted.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * See [`Self::from_ades_xml`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet` keyed by identifier (always a string), the observations
+    ///   of each trajectory in time order (file order on equal epochs).
+    ///
+    /// Notes
+    /// ----------
+    /// * Invalid rows (wrong number of fields, missing identifier or field, unknown
+    ///   station, values out of range) are reported with their line number; a block
+    ///   whose field header lacks `obsTime` raises `ValueError` whatever `on_error`.
+    #[staticmethod]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        env, path, error_ra_arcsec=None, error_dec_arcsec=None, group_by=None,
+        on_error="raise", bad_sigma="error", bad_sigma_value=None, strict=None,
+        include_rejected=false
+    ))]
+    pub fn from_ades_psv(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        error_ra_arcsec: Option<f64>,
+        error_dec_arcsec: Option<f64>,
+        group_by: Option<Vec<String>>,
+        on_error: &str,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        include_rejected: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let skip = skip_malformed(on_error)?;
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let opts = ades_options(group_by, error_ra_arcsec, error_dec_arcsec)?;
+        let p = py_path_to_utf8(py, path)?;
+        let text = read_text(py, &p)?;
+
+        let parsed = py
+            .detach(|| ades::parse_psv(&text, &opts))
+            .map_err(|e| PyValueError::new_err(format!("invalid ADES PSV in {p}: {e}")))?;
+        ades_set(
+            py,
+            env,
+            handle,
+            &p,
+            parsed,
+            "line",
+            skip,
+            policy,
+            strict,
+            include_rejected,
+        )
+    }
+
+    /// Build a `TrajectorySet` from an **ADES JSON** file.
+    ///
+    /// Same field mapping, units and options as [`Self::from_ades_xml`]. The optical
+    /// records are collected from `optical` lists at any depth, in document order; the
+    /// `obsContext.observatory.mpcCode` of an `obsBlock` gives the station of its records
+    /// without `stn`. Values may be JSON strings or numbers.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * See [`Self::from_ades_xml`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet` keyed by identifier (always a string), the observations
+    ///   of each trajectory in document order.
+    ///
+    /// Notes
+    /// ----------
+    /// * Invalid records are reported with their rank (1-based) among the optical
+    ///   records of the document; a JSON syntax error raises `ValueError` whatever
+    ///   `on_error`.
+    #[staticmethod]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        env, path, error_ra_arcsec=None, error_dec_arcsec=None, group_by=None,
+        on_error="raise", bad_sigma="error", bad_sigma_value=None, strict=None,
+        include_rejected=false
+    ))]
+    pub fn from_ades_json(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        error_ra_arcsec: Option<f64>,
+        error_dec_arcsec: Option<f64>,
+        group_by: Option<Vec<String>>,
+        on_error: &str,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        include_rejected: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let skip = skip_malformed(on_error)?;
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let opts = ades_options(group_by, error_ra_arcsec, error_dec_arcsec)?;
+        let p = py_path_to_utf8(py, path)?;
+        let text = read_text(py, &p)?;
+
+        let doc = units::loads(py, &text)
+            .map_err(|e| PyValueError::new_err(format!("invalid ADES JSON in {p}: {e}")))?;
+        let parsed = ades::parse_json(&doc, &opts)?;
+        ades_set(
+            py,
+            env,
+            handle,
+            &p,
+            parsed,
+            "record",
+            skip,
+            policy,
+            strict,
+            include_rejected,
+        )
+    }
+
+    /// Build a `TrajectorySet` by reading an **ADES** file (MPC XML/JSON).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Global Outfit state (ephemerides, observers/EOP registry).
+    /// * `path` – ADES file path (`str` or `pathlib.Path`).
+    /// * `error_ra_arcsec` – Optional 1-σ RA uncertainty applied to all rows without explicit σ.
+    /// * `error_dec_arcsec` – Optional 1-σ DEC uncertainty applied to all rows without explicit σ.
+    ///
+    /// Return
+    /// ----------
+    /// * A new `PyTrajectorySet` populated from the ADES file.
+    ///
+    /// Notes
+    /// ----------
+    /// * The underlying parser defines the error-handling policy (it may log or panic on invalid data).
+    /// * **No de-duplication** is performed across multiple ingestions.
+    /// * `bad_sigma` / `bad_sigma_value` (arcsec): policy for non-positive uncertainties,
+    ///   see [`TrajectorySet::from_numpy_degrees`].
+    /// * `strict`: per-call strict mode, see [`TrajectorySet::from_numpy_degrees`].
+    #[staticmethod]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        env, path, error_ra_arcsec=None, error_dec_arcsec=None, bad_sigma="error",
+        bad_sigma_value=None, strict=None
+    ))]
+    pub fn new_from_ades(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        error_ra_arcsec: Option<f64>,
+        error_dec_arcsec: Option<f64>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let p = py_path_to_utf8(py, path)?;
+        let ts = py.detach(|| {
+            outfit::TrajectorySet::new_from_ades(
+                &mut env.inner_mut_detached(),
+                &p,
+                error_ra_arcsec,
+                error_dec_arcsec,
+            )
+        });
+        TrajectorySet::checked(py, ts, env, handle, policy, strict, false)
+    }
+
+    /// Append observations from an **ADES** file (MPC XML/JSON) into this set.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Global Outfit state (ephemerides, observers/EOP registry).
+    /// * `path` – ADES file path (`str` or `pathlib.Path`).
+    /// * `error_ra_arcsec` – Optional 1-σ RA uncertainty applied to all rows without explicit σ.
+    /// * `error_dec_arcsec` – Optional 1-σ DEC uncertainty applied to all rows without explicit σ.
+    ///
+    /// Return
+    /// ----------
+    /// * `None`. The internal map is updated in place.
+    ///
+    /// Notes
+    /// ----------
+    /// * The underlying parser defines the error-handling policy (it may log or panic on invalid data).
+    /// * **No de-duplication** is performed; avoid re-ingesting the same file twice.
+    /// * `bad_sigma` / `bad_sigma_value` (arcsec): policy for non-positive uncertainties,
+    ///   see [`TrajectorySet::from_numpy_degrees`]. On rejection the set is left unchanged.
+    /// * `strict`: per-call strict mode, see [`TrajectorySet::from_numpy_degrees`].
+    #[pyo3(signature = (
+        env, path, error_ra_arcsec=None, error_dec_arcsec=None, bad_sigma="error",
+        bad_sigma_value=None, strict=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_from_ades(
+        &mut self,
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        error_ra_arcsec: Option<f64>,
+        error_dec_arcsec: Option<f64>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+    ) -> PyResult<()> {
+        let (env, handle) = env.split();
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let p = py_path_to_utf8(py, path)?;
+        let ts = py.detach(|| {
+            outfit::TrajectorySet::new_from_ades(
+                &mut env.inner_mut_detached(),
+                &p,
+                error_ra_arcsec,
+                error_dec_arcsec,
+            )
+        });
+        self.absorb(TrajectorySet::checked(
+            py, ts, env, handle, policy, strict, false,
+        )?);
+        Ok(())
+    }
+
+    /// Build a `TrajectorySet` from NumPy arrays already expressed in **radians** and **MJD (TT)**.
+    ///
+    /// The observations are streamed from the borrowed NumPy memory into trajectories
+    /// allocated at their final size (see `ingest::Rows`), without an intermediate batch.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `trajectory_id`: integer (any integer dtype) or string ids — one ID
+    ///   per observation, see [`Self::from_numpy_degrees`].
+    /// * `ra`: `np.ndarray` — right ascension in **radians**.
+    /// * `dec`: `np.ndarray` — declination in **radians**.
+    /// * `error_ra_rad`: `float | np.ndarray` — 1-σ RA uncertainty
+    ///   (**radians**): one value for the whole batch, or one per observation.
+    /// * `error_dec_rad`: `float | np.ndarray` — 1-σ DEC uncertainty
+    ///   (**radians**), as `error_ra_rad`.
+    /// * `mjd_tt`: `np.ndarray` — epochs in **MJD** (days) on the
+    ///   `time_scale` scale (TT by default), or JD with `epoch_format="jd"`; or
+    ///   `datetime64` / ISO-8601 timestamps, see [`Self::from_numpy_degrees`].
+    /// * `observer`: `PyObserver` — single observer for the whole batch.
+    /// * `bad_sigma`: policy for non-positive uncertainties, see [`Self::from_numpy_degrees`].
+    /// * `bad_sigma_value`: replacement uncertainty (**radians**) for `bad_sigma="value"`.
+    /// * `strict`: per-call strict mode, see [`Self::from_numpy_degrees`].
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `time_scale`: scale of `mjd_tt`, see [`Self::from_numpy_degrees`].
+    /// * `epoch_format`: `"mjd"` or `"jd"`, see [`Self::from_numpy_degrees`].
+    /// * `mag`, `band`: optional photometry of every row, see [`Self::from_numpy_degrees`].
+    /// * `dedup`: drop duplicate observations, see [`Self::from_numpy_degrees`].
+    /// * `validate`: check the rows before ingesting them, see [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `drop_invalid`: drop masked rows and rows with NaN or infinite values, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `parallel`, `n_threads`: group the rows into trajectories on several threads, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `PyTrajectorySet` populated from the provided inputs.
+    ///
+    /// Panics
+    /// ----------
+    /// * Never panics; returns `ValueError` on length mismatches, uncertainty arrays
+    ///   included, and (with `validate`) listing the rows holding invalid values.
+    ///
+    /// See also
+    /// ----------
+    /// * [`Self::from_numpy_degrees`] – Degrees/arcsec variant with conversions.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        pyoutfit, trajectory_id, ra, dec, error_ra_rad, error_dec_rad, mjd_tt, observer,
+        bad_sigma="error", bad_sigma_value=None, strict=None, topocentric=true,
+        time_scale=None, epoch_format="mjd", mag=None, band=None, dedup=Dedup(None),
+        validate=true, sort=false, drop_invalid=false, parallel=false, n_threads=None
+    ))]
+    pub fn from_numpy_radians(
+        py: Python<'_>,
+        mut pyoutfit: LoaderEnv<'_>,
+        trajectory_id: Masked<TrajectoryIds<'_>>,
+        ra: Masked<Reals<'_>>,
+        dec: Masked<Reals<'_>>,
+        error_ra_rad: Masked<Sigma<'_>>,
+        error_dec_rad: Masked<Sigma<'_>>,
+        mjd_tt: Masked<Epochs<'_>>,
+        observer: &Observer,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        time_scale: Option<&str>,
+        epoch_format: &str,
+        mag: Option<Masked<Reals<'_>>>,
+        band: Option<Vec<Option<String>>>,
+        dedup: Dedup,
+        validate: bool,
+        sort: bool,
+        drop_invalid: bool,
+        parallel: bool,
+        n_threads: Option<usize>,
+    ) -> PyResult<TrajectorySet> {
+        let (pyoutfit, handle) = pyoutfit.split();
+        Self::from_numpy_columns(
+            py,
+            pyoutfit,
+            handle,
+            NumpyColumns {
+                names: ["ra", "dec", "error_ra_rad", "error_dec_rad"],
+                trajectory_id,
+                ra,
+                dec,
+                error_ra: error_ra_rad,
+                error_dec: error_dec_rad,
+                mjd_tt,
+                mag,
+                band,
+            },
+            false,
+            BatchSites::Single(observer),
+            NumpyOptions {
+                bad_sigma,
+                bad_sigma_value,
+                strict,
+                topocentric,
+                time_scale,
+                epoch_format,
+                dedup,
+                validate,
+                sort,
+                drop_invalid,
+                parallel,
+                n_threads,
+            },
+        )
+    }
+
+    /// Build a `TrajectorySet` from NumPy arrays in **degrees** (RA/DEC), **arcseconds** (uncertainties),
+    /// and **MJD (TT)** for epochs.
+    ///
+    /// The angles are converted to radians as the observations are streamed into the set,
+    /// so no converted copy of the columns is allocated (see `ingest::Rows`).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `trajectory_id`: one ID per observation: `np.ndarray` of non-negative integers
+    ///   (any integer dtype), or string ids (`list[str]`, NumPy string array) such as
+    ///   survey designations. An object array (as pandas stores ids) or a list holds
+    ///   either Python `int`s, read as integer ids, or `str`s; any other element, or one
+    ///   not of the type of the first, raises an error naming it. The ids are the keys of
+    ///   the set and of the results of `estimate_all_orbits`; 64-bit ids come back as
+    ///   full Python `int`s.
+    /// * `ra_deg`: `np.ndarray` — right ascension in **degrees**.
+    /// * `dec_deg`: `np.ndarray` — declination in **degrees**.
+    /// * `error_ra_arcsec`: `float | np.ndarray` — 1-σ RA uncertainty
+    ///   (**arcseconds**): one value for the whole batch, or one per observation
+    ///   (heteroscedastic astrometry).
+    /// * `error_dec_arcsec`: `float | np.ndarray` — 1-σ DEC uncertainty
+    ///   (**arcseconds**), as `error_ra_arcsec`.
+    /// * `mjd_tt`: `np.ndarray` — epochs in **MJD** (days) on the
+    ///   `time_scale` scale (TT by default), or JD with `epoch_format="jd"`; or
+    ///   `datetime64` / ISO-8601 timestamps, see [`Self::from_numpy_degrees`].
+    /// * `observer`: `PyObserver` — single observer for the whole batch.
+    /// * `bad_sigma`: policy for non-positive or non-finite uncertainties (default `"error"`):
+    ///   * `"error"` – raise `ValueError`;
+    ///   * `"model"` – substitute the observer's error-model accuracy;
+    ///   * `"value"` – substitute `bad_sigma_value`.
+    /// * `bad_sigma_value`: replacement uncertainty (**arcseconds**) for `bad_sigma="value"`.
+    /// * `strict`: override of the process-wide strict mode (`None`: `py_outfit.set_strict`).
+    ///   Substituted uncertainties and trajectories given out of time order are reported
+    ///   with `FallbackWarning`, or rejected with `StrictModeError` in strict mode.
+    /// * `topocentric`: `True` (default) applies the parallax of `observer`. `False` is for
+    ///   astrometry already reduced to the geocentre: the rows are attributed to the
+    ///   geocentric counterpart of `observer` (same name suffixed with ` (geocentric)`, same
+    ///   accuracies), which the IOD, residuals and predictions then use as observing site.
+    ///   Sets ingested with either value can be combined with `extend`.
+    /// * `time_scale`: scale of `mjd_tt`: `"TT"`, `"TAI"` or `"UTC"`; TT by default for
+    ///   numeric epochs and UTC for timestamps. UTC and TAI
+    ///   epochs are converted to TT once, in Rust, with the leap-second table of
+    ///   `hifitime`. UTC MJDs follow the SOFA / astropy convention (a day ending with a
+    ///   leap second lasts 86 401 s); UTC epochs before 1972 raise `ValueError`.
+    /// * `epoch_format`: `"mjd"` (default) or `"jd"`. Julian Dates are converted with
+    ///   `MJD = JD − 2400000.5` before the time scale. Epochs above 2 000 000 given as MJD
+    ///   (or below given as JD) raise `ValueError`: they are the other format by mistake.
+    /// * `mag`: optional magnitude of every row (NaN where unknown), any real dtype.
+    /// * `band`: optional photometric band of every row (`None` where unknown).
+    ///   Both are kept alongside the observations (through sorting, `extend`,
+    ///   `split_on_gaps`, `from_observations`) and read back with
+    ///   `Observations.magnitudes()` / `to_numpy(photometry=True)`.
+    /// * `dedup`: `False` (default) keeps every row. `True` drops the duplicate
+    ///   observations of each trajectory: epochs within 1e-7 day (~9 ms) and positions
+    ///   within 1 mas of an earlier observation; a `(time_tol, sep_mas)` pair sets other
+    ///   tolerances (days, milliarcseconds). The number of rows removed per trajectory is
+    ///   reported by `duplicates_removed`; see also `deduplicate`.
+    /// * `validate`: `True` (default) checks every row before the ingestion: RA in
+    ///   [0, 360) and Dec in [-90, 90] (radians: [0, 2π) and [-π/2, π/2]), epochs between
+    ///   the years 1800 and 2200 and, with `bad_sigma="error"`, positive per-row
+    ///   uncertainties, all finite. Failures raise `ValueError` naming each failing column
+    ///   and its rows (the first 20). `False` skips the check for data known to be clean.
+    /// * `sort`: `False` (default) reports the trajectories given out of time order
+    ///   (`FallbackWarning`, `StrictModeError` in strict mode) before sorting them.
+    ///   `True` declares the rows unordered, as out of a `groupby`: every trajectory is
+    ///   sorted by epoch without a report, strict mode included.
+    /// * `drop_invalid`: `False` (default) ingests every unmasked row. `True` also drops
+    ///   the rows holding NaN or infinite values (`NaT` for `datetime64` epochs) in the
+    ///   coordinates, the epochs or, with `bad_sigma="error"`, the per-row uncertainties,
+    ///   as survey pipelines mark bad detections. Independently of it, the arguments
+    ///   accept `numpy.ma.MaskedArray` inputs, whose masked rows are always dropped (a
+    ///   masked `mag` only marks an unknown magnitude). The remaining rows stay aligned
+    ///   across the columns; the number of rows dropped per trajectory is reported by
+    ///   `invalid_rows_dropped`.
+    /// * `parallel`: `False` (default) groups the rows into trajectories on the calling
+    ///   thread. `True` partitions the rows by a hash of their trajectory id across rayon
+    ///   workers, each building its own trajectories, and merges them; the site positions
+    ///   are still computed once per distinct epoch. The set is identical to the serial
+    ///   build, observation order within each trajectory included.
+    /// * `n_threads`: number of worker threads with `parallel=True` (default: the global
+    ///   rayon pool, one thread per core); `ValueError` if zero or given without `parallel`.
+    ///
+    /// Return
+    /// ----------
+    /// * A new `PyTrajectorySet` populated from the provided inputs. The number of
+    ///   observations whose uncertainties were substituted is available as
+    ///   `patched_sigma_count`; `Observations` report the substituted values.
+    ///
+    /// Panics
+    /// ----------
+    /// * Never panics; returns `ValueError` on length mismatches, uncertainty arrays
+    ///   included, and (with `validate`) listing the rows holding invalid values.
+    ///
+    /// Notes
+    /// ----------
+    /// * `mjd_tt` may instead be a list or NumPy array of ISO-8601 timestamps, such as the
+    ///   `obsTime` of alert streams: `YYYY-MM-DD[Thh:mm[:ss[.fff…]]]`, optionally followed
+    ///   by `Z` or a UTC offset `±hh[:mm]`. They are parsed in Rust and read as UTC unless
+    ///   `time_scale` says otherwise; `23:59:60` is accepted on leap-second days. The first
+    ///   unparsable string raises `ValueError` with its row and value.
+    /// * `mjd_tt` may also be a `datetime64[ns]` or `datetime64[us]` array, as read from
+    ///   Parquet or pandas. The values are read as UTC (unless `time_scale` says
+    ///   otherwise) and converted exactly up to the final `f64` MJD (~0.5 µs); `NaT`
+    ///   entries raise `ValueError` listing their rows.
+    /// * Numeric arrays (ids, coordinates, epochs, per-row uncertainties) may have any
+    ///   integer or floating dtype and any memory layout: a contiguous `float64` (`uint32`
+    ///   for ids) array is read in place, anything else is converted once, in Rust. Other
+    ///   dtypes raise `TypeError` naming the argument.
+    ///
+    /// See also
+    /// ------------
+    /// * [`Self::from_numpy_radians`] – Zero-copy variant for radian inputs.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        pyoutfit, trajectory_id, ra_deg, dec_deg, error_ra_arcsec, error_dec_arcsec, mjd_tt,
+        observer, bad_sigma="error", bad_sigma_value=None, strict=None, topocentric=true,
+        time_scale=None, epoch_format="mjd", mag=None, band=None, dedup=Dedup(None),
+        validate=true, sort=false, drop_invalid=false, parallel=false, n_threads=None
+    ))]
+    pub fn from_numpy_degrees(
+        py: Python<'_>,
+        mut pyoutfit: LoaderEnv<'_>,
+        trajectory_id: Masked<TrajectoryIds<'_>>,
+        ra_deg: Masked<Reals<'_>>,
+        dec_deg: Masked<Reals<'_>>,
+        error_ra_arcsec: Masked<Sigma<'_>>,
+        error_dec_arcsec: Masked<Sigma<'_>>,
+        mjd_tt: Masked<Epochs<'_>>,
+        observer: &Observer,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        time_scale: Option<&str>,
+        epoch_format: &str,
+        mag: Option<Masked<Reals<'_>>>,
+        band: Option<Vec<Option<String>>>,
+        dedup: Dedup,
+        validate: bool,
+        sort: bool,
+        drop_invalid: bool,
+        parallel: bool,
+        n_threads: Option<usize>,
+    ) -> PyResult<TrajectorySet> {
+        let (pyoutfit, handle) = pyoutfit.split();
+        Self::from_numpy_columns(
+            py,
+            pyoutfit,
+            handle,
+            NumpyColumns {
+                names: DEGREE_COLUMNS,
+                trajectory_id,
+                ra: ra_deg,
+                dec: dec_deg,
+                error_ra: error_ra_arcsec,
+                error_dec: error_dec_arcsec,
+                mjd_tt,
+                mag,
+                band,
+            },
+            true,
+            BatchSites::Single(observer),
+            NumpyOptions {
+                bad_sigma,
+                bad_sigma_value,
+                strict,
+                topocentric,
+                time_scale,
+                epoch_format,
+                dedup,
+                validate,
+                sort,
+                drop_invalid,
+                parallel,
+                n_threads,
+            },
+        )
+    }
+
+    /// Build a `TrajectorySet` from NumPy arrays whose rows come from several sites.
+    ///
+    /// Same inputs as [`Self::from_numpy_degrees`] / [`Self::from_numpy_radians`], with the
+    /// single observer replaced by one site per row, so that a linked trajectory mixing
+    /// observations of several surveys uses the topocentric position of each.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `trajectory_id`: integer (any integer dtype) or string ids — one ID
+    ///   per observation, see [`Self::from_numpy_degrees`].
+    /// * `ra`, `dec`: `np.ndarray` — coordinates in the unit of `units`.
+    /// * `error_ra`, `error_dec`: `float | np.ndarray` — 1-σ
+    ///   uncertainties (arcseconds for `"degrees"`, radians for `"radians"`), uniform or
+    ///   per observation.
+    /// * `mjd_tt`: `np.ndarray` — epochs in **MJD** (days) on the
+    ///   `time_scale` scale (TT by default), or JD with `epoch_format="jd"`; or
+    ///   `datetime64` / ISO-8601 timestamps, see [`Self::from_numpy_degrees`].
+    /// * `observer_codes`: site of every row, either an integer array (any integer dtype)
+    ///   of positions in `observers`, or a sequence of MPC code strings.
+    /// * `observers`: `list[Observer]` indexed by integer `observer_codes`.
+    /// * `units`: `"degrees"` (default) or `"radians"`.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`]
+    ///   (`bad_sigma="model"` uses the accuracy of the site of each row).
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`]; applies to every site.
+    /// * `time_scale`: scale of `mjd_tt`, see [`Self::from_numpy_degrees`].
+    /// * `epoch_format`: `"mjd"` or `"jd"`, see [`Self::from_numpy_degrees`].
+    /// * `mag`, `band`: optional photometry of every row, see [`Self::from_numpy_degrees`].
+    /// * `dedup`: drop duplicate observations, see [`Self::from_numpy_degrees`].
+    /// * `validate`: check the rows before ingesting them, see [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `drop_invalid`: drop masked rows and rows with NaN or infinite values, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `parallel`, `n_threads`: group the rows into trajectories on several threads, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`; each observation keeps the site of its row.
+    ///
+    /// Notes
+    /// ----------
+    /// * Raises `ValueError` on length mismatches (`observer_codes` included), for an index
+    ///   out of range of `observers`, an unknown MPC code, integer codes without
+    ///   `observers` or code strings with them, and `TypeError` for any other
+    ///   `observer_codes`.
+    /// * Rows are ingested one site at a time; the trajectories are merged afterwards in
+    ///   canonical order, before `dedup` runs (duplicates reported by two sites are found).
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        pyoutfit, trajectory_id, ra, dec, error_ra, error_dec, mjd_tt, observer_codes,
+        observers=None, units="degrees", bad_sigma="error", bad_sigma_value=None, strict=None,
+        topocentric=true, time_scale=None, epoch_format="mjd", mag=None, band=None,
+        dedup=Dedup(None), validate=true, sort=false, drop_invalid=false, parallel=false,
+        n_threads=None
+    ))]
+    pub fn from_numpy_multi_site(
+        py: Python<'_>,
+        mut pyoutfit: LoaderEnv<'_>,
+        trajectory_id: Masked<TrajectoryIds<'_>>,
+        ra: Masked<Reals<'_>>,
+        dec: Masked<Reals<'_>>,
+        error_ra: Masked<Sigma<'_>>,
+        error_dec: Masked<Sigma<'_>>,
+        mjd_tt: Masked<Epochs<'_>>,
+        observer_codes: &Bound<'_, PyAny>,
+        observers: Option<Vec<PyRef<'_, Observer>>>,
+        units: &str,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        time_scale: Option<&str>,
+        epoch_format: &str,
+        mag: Option<Masked<Reals<'_>>>,
+        band: Option<Vec<Option<String>>>,
+        dedup: Dedup,
+        validate: bool,
+        sort: bool,
+        drop_invalid: bool,
+        parallel: bool,
+        n_threads: Option<usize>,
+    ) -> PyResult<TrajectorySet> {
+        let (pyoutfit, handle) = pyoutfit.split();
+        let degrees = degrees_units(units)?;
+        let sites = BatchSites::PerRow(SiteRows::extract(observer_codes)?, observers);
+        Self::from_numpy_columns(
+            py,
+            pyoutfit,
+            handle,
+            NumpyColumns {
+                names: ["ra", "dec", "error_ra", "error_dec"],
+                trajectory_id,
+                ra,
+                dec,
+                error_ra,
+                error_dec,
+                mjd_tt,
+                mag,
+                band,
+            },
+            degrees,
+            sites,
+            NumpyOptions {
+                bad_sigma,
+                bad_sigma_value,
+                strict,
+                topocentric,
+                time_scale,
+                epoch_format,
+                dedup,
+                validate,
+                sort,
+                drop_invalid,
+                parallel,
+                n_threads,
+            },
+        )
+    }
+
+    /// Build a `TrajectorySet` from NumPy arrays in **radians** whose rows select their
+    /// site in a list of observers.
+    ///
+    /// Integer-indexed form of [`Self::from_numpy_multi_site`] for heterogeneous batches
+    /// of custom sites: no code is looked up per row, and the indices are checked against
+    /// `observers` before the epochs are converted or the rows validated.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `trajectory_id`, `ra`, `dec`, `error_ra`, `error_dec`, `mjd_tt`: as for
+    ///   [`Self::from_numpy_radians`].
+    /// * `observers`: `list[Observer]` — sites of the batch.
+    /// * `observer_idx`: `np.ndarray[uint16]` — position in `observers` of the site of
+    ///   each row.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`, `topocentric`, `time_scale`,
+    ///   `epoch_format`, `mag`, `band`, `dedup`, `validate`, `sort`, `drop_invalid`,
+    ///   `parallel`, `n_threads`: see [`Self::from_numpy_multi_site`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`; each observation keeps the `Observer` selected by its row.
+    ///
+    /// Notes
+    /// ----------
+    /// * Raises `ValueError` for a length mismatch between `ra` and `observer_idx` or an
+    ///   index out of range of `observers` (naming the first offending row), before any
+    ///   other work, and for the checks of
+    ///   [`Self::from_numpy_multi_site`]; `TypeError` if `observer_idx` is not a `uint16`
+    ///   array.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        pyoutfit, trajectory_id, ra, dec, error_ra, error_dec, mjd_tt, observers, observer_idx,
+        bad_sigma="error", bad_sigma_value=None, strict=None, topocentric=true,
+        time_scale=None, epoch_format="mjd", mag=None, band=None, dedup=Dedup(None),
+        validate=true, sort=false, drop_invalid=false, parallel=false, n_threads=None
+    ))]
+    pub fn from_numpy_radians_indexed(
+        py: Python<'_>,
+        mut pyoutfit: LoaderEnv<'_>,
+        trajectory_id: Masked<TrajectoryIds<'_>>,
+        ra: Masked<Reals<'_>>,
+        dec: Masked<Reals<'_>>,
+        error_ra: Masked<Sigma<'_>>,
+        error_dec: Masked<Sigma<'_>>,
+        mjd_tt: Masked<Epochs<'_>>,
+        observers: Vec<PyRef<'_, Observer>>,
+        observer_idx: PyReadonlyArray1<'_, u16>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        time_scale: Option<&str>,
+        epoch_format: &str,
+        mag: Option<Masked<Reals<'_>>>,
+        band: Option<Vec<Option<String>>>,
+        dedup: Dedup,
+        validate: bool,
+        sort: bool,
+        drop_invalid: bool,
+        parallel: bool,
+        n_threads: Option<usize>,
+    ) -> PyResult<TrajectorySet> {
+        let (pyoutfit, handle) = pyoutfit.split();
+        let (n, n_idx) = (ra.as_slice()?.len(), observer_idx.as_array().len());
+        if n_idx != n {
+            return Err(PyValueError::new_err(format!(
+                "Length mismatch: ra={n}, observer_idx={n_idx}"
+            )));
+        }
+        let n_sites = observers.len();
+        if let Some((row, i)) = observer_idx
+            .as_array()
+            .iter()
+            .enumerate()
+            .find(|(_, &i)| usize::from(i) >= n_sites)
+        {
+            return Err(PyValueError::new_err(format!(
+                "observer_idx[{row}] = {i} is out of range for {n_sites} observers"
+            )));
+        }
+        let sites = BatchSites::PerRow(SiteRows::extract(observer_idx.as_any())?, Some(observers));
+        Self::from_numpy_columns(
+            py,
+            pyoutfit,
+            handle,
+            NumpyColumns {
+                names: ["ra", "dec", "error_ra", "error_dec"],
+                trajectory_id,
+                ra,
+                dec,
+                error_ra,
+                error_dec,
+                mjd_tt,
+                mag,
+                band,
+            },
+            false,
+            sites,
+            NumpyOptions {
+                bad_sigma,
+                bad_sigma_value,
+                strict,
+                topocentric,
+                time_scale,
+                epoch_format,
+                dedup,
+                validate,
+                sort,
+                drop_invalid,
+                parallel,
+                n_threads,
+            },
+        )
+    }
+
+    /// Build a `TrajectorySet` from a list of records, one dict per observation.
+    ///
+    /// Convenience loader for interactive sessions and tests, where building NumPy arrays
+    /// is overkill. The records are validated and accumulated into columns, then go
+    /// through [`Self::from_numpy_degrees`].
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Environment used for the ingestion.
+    /// * `records`: iterable of mappings with the keys `trajectory_id` (`int` or `str`,
+    ///   the same type for every record), `ra_deg`, `dec_deg` (**degrees**),
+    ///   `sigma_arcsec` (1-σ uncertainty on both axes, **arcseconds**) and `mjd_tt`
+    ///   (**MJD**, on the `time_scale` scale), and optionally `mag` and `band`.
+    /// * `observer`: single observer for the whole batch.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`, `topocentric`, `time_scale`,
+    ///   `epoch_format`: see [`Self::from_numpy_degrees`].
+    /// * `dedup`: drop duplicate observations, see [`Self::from_numpy_degrees`].
+    /// * `validate`: check the rows before ingesting them, see [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`, as built by [`Self::from_numpy_degrees`].
+    ///
+    /// Notes
+    /// ----------
+    /// * Errors name the index of the first invalid record: `KeyError` for a missing key,
+    ///   `TypeError` for a record that is not a mapping, a value of the wrong type or ids
+    ///   mixing `int` and `str`. Values out of range are reported by record index
+    ///   (`validate`).
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, records, observer, bad_sigma="error", bad_sigma_value=None, strict=None,
+        topocentric=true, time_scale=None, epoch_format="mjd", dedup=Dedup(None),
+        validate=true, sort=false
+    ))]
+    pub fn from_records(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        records: &Bound<'_, PyAny>,
+        observer: &Observer,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        time_scale: Option<&str>,
+        epoch_format: &str,
+        dedup: Dedup,
+        validate: bool,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let columns = RecordColumns::collect(records)?;
+        Self::from_numpy_columns(
+            py,
+            env,
+            handle,
+            NumpyColumns {
+                names: DEGREE_COLUMNS,
+                trajectory_id: columns.ids.into(),
+                ra: Reals::Converted(columns.ra_deg).into(),
+                dec: Reals::Converted(columns.dec_deg).into(),
+                error_ra: Sigma::PerRow(Reals::Converted(columns.sigma_arcsec.clone())).into(),
+                error_dec: Sigma::PerRow(Reals::Converted(columns.sigma_arcsec)).into(),
+                mjd_tt: Epochs::Numbers(Reals::Converted(columns.mjd_tt)).into(),
+                mag: columns.mag.map(|mag| Reals::Converted(mag).into()),
+                band: columns.band,
+            },
+            true,
+            BatchSites::Single(observer),
+            NumpyOptions {
+                bad_sigma,
+                bad_sigma_value,
+                strict,
+                topocentric,
+                time_scale,
+                epoch_format,
+                dedup,
+                validate,
+                sort,
+                drop_invalid: false,
+                parallel: false,
+                n_threads: None,
+            },
+        )
+    }
+
+    /// Build a `TrajectorySet` from an iterator of array chunks, one chunk at a time.
+    ///
+    /// Streaming counterpart of [`Self::from_numpy_degrees`] for batches too large to be
+    /// held in memory at once: each chunk is ingested (without the GIL) and merged into
+    /// the set before the next one is requested, so only one chunk of input arrays is
+    /// alive at any time.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Environment used for the ingestion.
+    /// * `chunks`: iterable of chunks, each a tuple
+    ///   `(trajectory_id, ra_deg, dec_deg, error_ra_arcsec, error_dec_arcsec, mjd_tt[, mag[, band]])`
+    ///   or a dict with those keys, holding the arguments of
+    ///   [`Self::from_numpy_degrees`] for a slice of the rows.
+    /// * `observer`: single observer for the whole batch.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`, `topocentric`, `time_scale`,
+    ///   `epoch_format`: see [`Self::from_numpy_degrees`], applied to every chunk.
+    /// * `dedup`: drop duplicate observations, see [`Self::from_numpy_degrees`]; applied
+    ///   once the chunks are merged, so duplicates spread over two chunks are found.
+    /// * `validate`: check the rows before ingesting them, see [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `drop_invalid`: drop masked rows and rows with NaN or infinite values, see
+    ///   [`Self::from_numpy_degrees`]; applied to every chunk.
+    /// * `parallel`, `n_threads`: group the rows of each chunk on several threads, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * The set built from the concatenated chunks: observations of an id spread over
+    ///   several chunks are merged in canonical order, so the result does not depend on
+    ///   the chunk boundaries.
+    ///
+    /// Notes
+    /// ----------
+    /// * Errors are prefixed with the index of the chunk they come from. The time-order
+    ///   check (`FallbackWarning` / `StrictModeError`) runs within each chunk; rows out of
+    ///   order across two chunks are sorted silently.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, chunks, observer, bad_sigma="error", bad_sigma_value=None, strict=None,
+        topocentric=true, time_scale=None, epoch_format="mjd", dedup=Dedup(None),
+        validate=true, sort=false, drop_invalid=false, parallel=false, n_threads=None
+    ))]
+    pub fn from_chunks(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        chunks: &Bound<'_, PyAny>,
+        observer: &Observer,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        time_scale: Option<&str>,
+        epoch_format: &str,
+        dedup: Dedup,
+        validate: bool,
+        sort: bool,
+        drop_invalid: bool,
+        parallel: bool,
+        n_threads: Option<usize>,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let mut out = TrajectorySet::from(outfit::TrajectorySet::default());
+        for (i, chunk) in chunks.try_iter()?.enumerate() {
+            let chunk = Chunk::extract(i, &chunk?)?;
+            let columns = NumpyColumns {
+                names: DEGREE_COLUMNS,
+                trajectory_id: chunk.trajectory_id,
+                ra: chunk.ra_deg,
+                dec: chunk.dec_deg,
+                error_ra: chunk.error_ra_arcsec,
+                error_dec: chunk.error_dec_arcsec,
+                mjd_tt: chunk.mjd_tt,
+                mag: chunk.mag,
+                band: chunk.band,
+            };
+            let options = NumpyOptions {
+                bad_sigma,
+                bad_sigma_value,
+                strict,
+                topocentric,
+                time_scale,
+                epoch_format,
+                dedup: Dedup(None),
+                validate,
+                sort,
+                drop_invalid,
+                parallel,
+                n_threads,
+            };
+            let sites = BatchSites::Single(observer);
+            let set = Self::from_numpy_columns(py, env, handle, columns, true, sites, options)
+                .map_err(|err| {
+                    let prefixed =
+                        PyErr::from_type(err.get_type(py), format!("chunk {i}: {}", err.value(py)));
+                    prefixed.set_cause(py, Some(err));
+                    prefixed
+                })?;
+            py.detach(|| out.absorb(set));
+        }
+        Ok(out.deduplicated(dedup))
+    }
+
+    /// Build a `TrajectorySet` from a mapping of trajectory ids to `Observations`.
+    ///
+    /// Brings trajectories extracted from a set (`ts[key]`, `values()`, result views) and
+    /// edited in Python back into a set for batch estimation, without going through NumPy
+    /// arrays.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Environment the observations were ingested with.
+    /// * `mapping`: `dict[int | str, Observations]` — one trajectory per key.
+    /// * `observer`: `None` (default) keeps the site of every observation. Otherwise every
+    ///   observation is attributed to `observer`, its positions recomputed as by
+    ///   [`Self::from_numpy_radians`] (the site is interned once for the whole mapping).
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`];
+    ///   `bad_sigma_value` is in **radians**, the unit of `Observations`.
+    /// * `topocentric`: `False` attributes the rows to the geocentric counterpart of
+    ///   `observer`, see [`Self::from_numpy_degrees`]. Ignored without `observer`.
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet` with the keys of `mapping`.
+    ///
+    /// Notes
+    /// ----------
+    /// * Every trajectory goes through the checks of the other loaders: epochs within the
+    ///   ephemeris range, time order (`FallbackWarning` / `StrictModeError`) and the
+    ///   `bad_sigma` policy. Raises `ValueError` for an empty trajectory or two keys naming
+    ///   the same id, `TypeError` when a value is not an `Observations`.
+    /// * Without `observer`, the observations keep the site index and positions computed by
+    ///   the environment that produced them; mixing environments is not detected.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, mapping, observer=None, bad_sigma="error", bad_sigma_value=None, strict=None,
+        topocentric=true, sort=false
+    ))]
+    pub fn from_observations(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        mapping: &Bound<'_, PyDict>,
+        observer: Option<&Observer>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, 1.0)?;
+
+        let mut inner = outfit::TrajectorySet::default();
+        let mut photometry = PhotometryTable::default();
+        for (key, value) in mapping.iter() {
+            let obj = py_to_object_number(&key)?;
+            let obs = value.cast::<Observations>().map_err(|_| {
+                PyTypeError::new_err(format!(
+                    "mapping[{key}] must be Observations, not {}",
+                    value
+                        .get_type()
+                        .name()
+                        .map_or_else(|_| "?".into(), |n| n.to_string())
+                ))
+            })?;
+            let view = obs.borrow();
+            photometry.merge_for(&view.photometry, &view.inner);
+            let obs = view.inner.clone();
+            if obs.is_empty() {
+                return Err(PyValueError::new_err(format!(
+                    "trajectory {key} has no observations"
+                )));
+            }
+            if inner.insert(obj, obs).is_some() {
+                return Err(PyValueError::new_err(format!(
+                    "duplicate trajectory id {key} in mapping"
+                )));
+            }
+        }
+
+        if let Some(observer) = observer {
+            // Rows of every trajectory, numbered for the core ingestion.
+            let keys: Vec<ObjectNumber> = inner.keys().cloned().collect();
+            let rows = || {
+                keys.iter()
+                    .enumerate()
+                    .flat_map(|(i, k)| inner[k].iter().map(move |o| (i as u32, o)))
+            };
+            let tid: Vec<u32> = rows().map(|(i, _)| i).collect();
+            let column = |f: fn(&outfit::observations::Observation) -> f64| -> Vec<f64> {
+                rows().map(|(_, o)| f(o)).collect()
+            };
+            let (ra, dec, mjd) = (column(|o| o.ra), column(|o| o.dec), column(|o| o.time));
+            let (sigma_ra, sigma_dec) = (column(|o| o.error_ra), column(|o| o.error_dec));
+            let batch =
+                ObservationBatch::from_radians_borrowed(&tid, &ra, &dec, f64::NAN, f64::NAN, &mjd);
+            let site = ingest::observing_site(&observer.inner, topocentric)?;
+            let mut numbered = py
+                .detach(|| {
+                    outfit::TrajectorySet::new_from_vec(&mut env.inner_mut_detached(), &batch, site)
+                })
+                .into_py()?;
+            assign_row_sigmas(&mut numbered, &tid, &sigma_ra, &sigma_dec, 1.0);
+            // The site moved, so the photometry and the rejection flags are matched to the
+            // rebuilt observations.
+            if !photometry.is_empty() {
+                let (mag, band) = photometry.columns(rows().map(|(_, o)| o));
+                let rejected: Vec<bool> = rows().map(|(_, o)| photometry.is_rejected(o)).collect();
+                photometry = PhotometryTable::default();
+                photometry.record(&numbered, &tid, Some(&mag), Some(&band));
+                photometry.record_rejected(&numbered, &tid, &rejected);
+            }
+            inner = numbered
+                .into_iter()
+                .map(|(key, obs)| match key {
+                    ObjectNumber::Int(i) => (keys[i as usize].clone(), obs),
+                    other => (other, obs),
+                })
+                .collect();
+        }
+
+        TrajectorySet::checked(py, inner, env, handle, policy, strict, sort)
+            .map(|set| set.with_photometry(photometry))
+    }
+
+    /// Build a `TrajectorySet` from an Arrow stream, consuming one record batch at a time.
+    ///
+    /// Accepts any object implementing the Arrow PyCapsule stream interface
+    /// (`__arrow_c_stream__`): `pyarrow.RecordBatchReader`, `pyarrow.Table`, ADBC cursors,
+    /// Polars frames, … Each record batch goes through the same ingestion path as
+    /// [`Self::from_numpy_degrees`] / [`Self::from_numpy_radians`] (checks and `bad_sigma`
+    /// policy included) and is merged into the result before the next one is read, so only
+    /// one batch is materialised at a time.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `readable`: Arrow stream source (imported with `pyarrow`).
+    /// * `observer`: Single observer for every row. With `None`, each row is attributed to
+    ///   the site whose MPC code is in the `observer` column.
+    /// * `columns`: Mapping from the fields `tid`, `mjd`, `ra`, `dec`, `observer` to column
+    ///   names; unmapped fields use their own name (as the pandas accessor `Schema`).
+    /// * `units`: `"degrees"` (RA/DEC in degrees, uncertainties in arcseconds, default) or
+    ///   `"radians"`.
+    /// * `error_ra`, `error_dec`: Uniform 1-σ uncertainties in the unit of `units`. `None`
+    ///   leaves them unknown and defers to `bad_sigma` (e.g. `"model"`).
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`].
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`. Rows of one trajectory spread over several batches are
+    ///   concatenated in stream order.
+    ///
+    /// Notes
+    /// ----------
+    /// * `tid` must be an integer column of non-negative ids, 64-bit ones included, and
+    ///   `mjd` holds epochs in MJD (TT), as for the NumPy loaders.
+    /// * Raises `TypeError` when `readable` does not implement `__arrow_c_stream__`, and
+    ///   `KeyError` when a mapped column is missing from a batch.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        pyoutfit, readable, observer=None, columns=None, units="degrees", error_ra=None,
+        error_dec=None, bad_sigma="error", bad_sigma_value=None, strict=None, topocentric=true,
+        sort=false
+    ))]
+    pub fn from_arrow_ipc(
+        py: Python<'_>,
+        mut pyoutfit: LoaderEnv<'_>,
+        readable: &Bound<'_, PyAny>,
+        observer: Option<&Observer>,
+        columns: Option<&Bound<'_, PyDict>>,
+        units: &str,
+        error_ra: Option<f64>,
+        error_dec: Option<f64>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (pyoutfit, handle) = pyoutfit.split();
+        let degrees = degrees_units(units)?;
+        let policy = BadSigma::parse(
+            bad_sigma,
+            bad_sigma_value,
+            if degrees { RADSEC } else { 1.0 },
+        )?;
+        let [tid_col, mjd_col, ra_col, dec_col, site_col, ..] = arrow_column_names(columns)?;
+
+        if !readable.hasattr("__arrow_c_stream__")? {
+            return Err(PyTypeError::new_err(
+                "readable must implement the Arrow PyCapsule stream interface (__arrow_c_stream__)",
+            ));
+        }
+        let reader = py
+            .import("pyarrow")?
+            .getattr("RecordBatchReader")?
+            .call_method1("from_stream", (readable,))?;
+
+        let err_ra = error_ra.unwrap_or(f64::NAN);
+        let err_dec = error_dec.unwrap_or(f64::NAN);
+        let mut sites: HashMap<String, Arc<outfit::Observer>> = HashMap::new();
+        let mut out = TrajectorySet::from(outfit::TrajectorySet::default());
+
+        for batch in reader.try_iter()? {
+            let batch = batch?;
+            let (tid, numbering) = arrow_ids(&batch, &tid_col)?;
+            let mjd = arrow_f64(&batch, &mjd_col)?;
+            let ra = arrow_f64(&batch, &ra_col)?;
+            let dec = arrow_f64(&batch, &dec_col)?;
+
+            // Rows of the batch grouped by observing site, in order of first appearance.
+            let groups: Vec<(Arc<outfit::Observer>, Vec<usize>)> = match observer {
+                Some(obs) => vec![(
+                    ingest::observing_site(&obs.inner, topocentric)?,
+                    (0..tid.len()).collect(),
+                )],
+                None => {
+                    let codes: Vec<String> = arrow_column(&batch, &site_col)?
+                        .call_method0("to_pylist")?
+                        .extract()?;
+                    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+                    for (row, code) in codes.into_iter().enumerate() {
+                        match groups.iter_mut().find(|(c, _)| *c == code) {
+                            Some((_, rows)) => rows.push(row),
+                            None => groups.push((code, vec![row])),
+                        }
+                    }
+                    groups
+                        .into_iter()
+                        .map(|(code, rows)| {
+                            let site = sites.entry(code).or_insert_with_key(|c| {
+                                pyoutfit.outfit(py).get_observer_from_mpc_code(c)
+                            });
+                            Ok((ingest::observing_site(site, topocentric)?, rows))
+                        })
+                        .collect::<PyResult<_>>()?
+                }
+            };
+
+            for (site, rows) in groups {
+                let pick = |v: &[f64]| rows.iter().map(|&k| v[k]).collect::<Vec<f64>>();
+                let ids: Vec<u32> = rows.iter().map(|&k| tid[k]).collect();
+                let (ra, dec, mjd) = (pick(&ra), pick(&dec), pick(&mjd));
+                let batch = ingest::Rows {
+                    ids: &ids,
+                    ra: &ra,
+                    dec: &dec,
+                    mjd: &mjd,
+                    errors: (err_ra, err_dec),
+                    sigmas: None,
+                    degrees,
+                };
+                let ts_res = py.detach(|| batch.ingest(&mut pyoutfit.inner_mut_detached(), site));
+                out.absorb(TrajectorySet::checked(
+                    py,
+                    numbering.restore(ts_res.into_py()?),
+                    pyoutfit,
+                    handle,
+                    policy,
+                    strict,
+                    sort,
+                )?);
+            }
+            py.check_signals()?;
+        }
+        Ok(out)
+    }
+
+    /// Build a `TrajectorySet` from Arrow record batches through the C Data Interface.
+    ///
+    /// The batches are imported with the Arrow PyCapsule protocol and their columns read
+    /// from the Arrow buffers on the Rust side: no Python-level column extraction, and no
+    /// dependency on `pyarrow` for the producer. `UInt32` ids and radian `Float64`
+    /// columns are handed to the ingestion without copy.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `record_batch`: Object implementing `__arrow_c_array__` (a record batch) or
+    ///   `__arrow_c_stream__` (a table with several chunks, a reader, …; consumed one
+    ///   batch at a time).
+    /// * `observer`: Single observer for every row.
+    /// * `columns`: Mapping from the fields `tid`, `mjd`, `ra`, `dec`, `sigma_ra`,
+    ///   `sigma_dec` to column names, as in [`Self::from_arrow_ipc`].
+    /// * `units`: `"degrees"` (RA/DEC in degrees, uncertainties in arcseconds, default) or
+    ///   `"radians"`.
+    /// * `error_ra`, `error_dec`: Uniform 1-σ uncertainties in the unit of `units`. With
+    ///   `None` for both, the per-row `sigma_ra` / `sigma_dec` columns are read when
+    ///   present; the uncertainties are otherwise unknown and defer to `bad_sigma`.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`].
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`. Rows of one trajectory spread over several batches are
+    ///   concatenated in stream order.
+    ///
+    /// Notes
+    /// ----------
+    /// * `tid` must be a `UInt32`, `UInt64`, `Int32` or `Int64` column of non-negative ids,
+    ///   `mjd`, `ra` and `dec` `Float64` columns; other types raise `TypeError`, null
+    ///   values `ValueError`, and a missing column `KeyError`.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, record_batch, observer, columns=None, units="degrees", error_ra=None,
+        error_dec=None, bad_sigma="error", bad_sigma_value=None, strict=None, topocentric=true,
+        sort=false
+    ))]
+    pub fn from_arrow(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        record_batch: &Bound<'_, PyAny>,
+        observer: &Observer,
+        columns: Option<&Bound<'_, PyDict>>,
+        units: &str,
+        error_ra: Option<f64>,
+        error_dec: Option<f64>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let degrees = degrees_units(units)?;
+        let policy = BadSigma::parse(
+            bad_sigma,
+            bad_sigma_value,
+            if degrees { RADSEC } else { 1.0 },
+        )?;
+        let batches = arrow_ffi::import(record_batch)?;
+        TrajectorySet::from_record_batches(
+            py,
+            env,
+            handle,
+            batches,
+            Some(observer),
+            RecordBatchOptions {
+                columns: arrow_column_names(columns)?,
+                degrees,
+                flexible_ids: false,
+                errors: record_batch_errors(error_ra, error_dec),
+                policy,
+                strict,
+                topocentric,
+                sort,
+            },
+        )
+    }
+
+    /// Build a `TrajectorySet` from a Parquet file, decoded in Rust one record batch at a
+    /// time.
+    ///
+    /// The file is read row group after row group with the Arrow Parquet reader, only the
+    /// mapped columns being decoded. Each record batch goes through the same ingestion as
+    /// [`Self::from_arrow`] and is merged into the result before the next one is decoded,
+    /// so the memory held by the reader stays bounded by a row group, whatever the size of
+    /// the file.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `path`: File path (`str` or `pathlib.Path`).
+    /// * `observer`: Single observer for every row. With `None`, each row is attributed to
+    ///   the site whose MPC code is in the `observer` column (e.g.
+    ///   `columns={"observer": "stn"}` for ADES-style files).
+    /// * `columns`: Mapping from the fields `tid`, `mjd`, `ra`, `dec`, `observer`,
+    ///   `sigma_ra`, `sigma_dec` to column names, as in [`Self::from_arrow_ipc`].
+    /// * `units`: `"degrees"` (RA/DEC in degrees, uncertainties in arcseconds, default) or
+    ///   `"radians"`.
+    /// * `error_ra`, `error_dec`: Uniform 1-σ uncertainties in the unit of `units`. With
+    ///   `None` for both, the per-row `sigma_ra` / `sigma_dec` columns are read when
+    ///   present; the uncertainties are otherwise unknown and defer to `bad_sigma`.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`].
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `batch_size`: Rows per decoded record batch (default: 65 536).
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`. Rows of one trajectory spread over several batches are
+    ///   merged in time order.
+    ///
+    /// Notes
+    /// ----------
+    /// * `tid` must be an integer column of non-negative ids (pandas writes `int64`) or a
+    ///   string column (string trajectory ids), `mjd`, `ra`, `dec` and the
+    ///   uncertainties `Float64` columns, and the `observer` column strings (plain or
+    ///   dictionary-encoded); other types raise `TypeError`, null values `ValueError`, and
+    ///   a missing column `KeyError`.
+    /// * Files written by `TrajectorySet.to_parquet` read back with
+    ///   `columns={"tid": "trajectory_id", "mjd": "mjd_tt"}` and `units="radians"`.
+    /// * Uncompressed and Snappy-compressed files (the `pyarrow` default) are supported;
+    ///   other codecs raise `ValueError`, as do files that are not valid Parquet. A file
+    ///   that cannot be opened raises `OSError`.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, path, observer=None, columns=None, units="degrees", error_ra=None,
+        error_dec=None, bad_sigma="error", bad_sigma_value=None, strict=None, topocentric=true,
+        batch_size=65536, sort=false
+    ))]
+    pub fn from_parquet(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        observer: Option<&Observer>,
+        columns: Option<&Bound<'_, PyDict>>,
+        units: &str,
+        error_ra: Option<f64>,
+        error_dec: Option<f64>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        batch_size: usize,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let degrees = degrees_units(units)?;
+        let policy = BadSigma::parse(
+            bad_sigma,
+            bad_sigma_value,
+            if degrees { RADSEC } else { 1.0 },
+        )?;
+        if batch_size == 0 {
+            return Err(PyValueError::new_err("batch_size must be positive"));
+        }
+        let names = arrow_column_names(columns)?;
+        let errors = record_batch_errors(error_ra, error_dec);
+        let path = py_path_to_utf8(py, path)?;
+        let reader = parquet_reader(&path, &names, observer.is_none(), errors.is_none())?
+            .with_batch_size(batch_size)
+            .build()
+            .map_err(|e| parquet_err(&path, e))?;
+
+        TrajectorySet::from_record_batches(
+            py,
+            env,
+            handle,
+            reader,
+            observer,
+            RecordBatchOptions {
+                columns: names,
+                degrees,
+                flexible_ids: true,
+                errors,
+                policy,
+                strict,
+                topocentric,
+                sort,
+            },
+        )
+    }
+
+    /// Build a `TrajectorySet` from a CSV file, parsed in Rust.
+    ///
+    /// The file is read and split into rows without the GIL nor pandas; gzip-compressed
+    /// files (detected from their magic bytes) are decompressed on the fly. The selected
+    /// columns then go through the same ingestion as [`Self::from_numpy_degrees`] /
+    /// [`Self::from_numpy_radians`].
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `path`: File path (`str` or `pathlib.Path`), plain or gzip-compressed.
+    /// * `observer`: Single observer for every row.
+    /// * `columns`: Mapping from the fields `id`, `time`, `ra`, `dec`, `sigma_ra`,
+    ///   `sigma_dec` to a header name (`str`) or a 0-based column index (`int`). Defaults
+    ///   to the names `trajectory_id`, `mjd_tt`, `ra`, `dec`, `sigma_ra`, `sigma_dec`.
+    /// * `units`: `"degrees"` (RA/DEC in degrees, uncertainties in arcseconds, default) or
+    ///   `"radians"`.
+    /// * `delimiter`: Field delimiter, a single ASCII character (default `","`).
+    /// * `header`: Whether the first row names the columns (default `True`). Without
+    ///   header, every field must be selected by index.
+    /// * `error_ra`, `error_dec`: Uniform 1-σ uncertainties in the unit of `units`. With
+    ///   `None` for both, the `sigma_ra` / `sigma_dec` columns are read when present (or
+    ///   mapped); the uncertainties are otherwise unknown and defer to `bad_sigma`.
+    /// * `on_error`: `"raise"` (default) or `"skip"` rows that cannot be read.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`].
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `dedup`: drop duplicate observations, see [`Self::from_numpy_degrees`].
+    /// * `validate`: check the rows before ingesting them, see [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`, keyed by integer ids when every id of the file is a
+    ///   non-negative integer, by string ids otherwise.
+    ///
+    /// Notes
+    /// ----------
+    /// * Fields follow RFC 4180 quoting (`"a,b"`, `"say ""hi"""`, line breaks inside
+    ///   quotes) and are stripped of surrounding blanks; blank lines and lines starting
+    ///   with `#` are skipped. A row spanning several lines is reported with its first.
+    /// * Rows with a missing field, an empty id or a value that is not a number are
+    ///   reported with their line number: `ValueError` with `on_error="raise"`, a
+    ///   `FallbackWarning` (`StrictModeError` in strict mode) with `"skip"`.
+    /// * A named column missing from the header raises `KeyError`; an unterminated quoted
+    ///   field or invalid gzip data `ValueError`; a file that cannot be read `OSError`.
+    /// * Epochs are MJD (TT) numbers.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, path, observer, columns=None, units="degrees", delimiter=",", header=true,
+        error_ra=None, error_dec=None, on_error="raise", bad_sigma="error",
+        bad_sigma_value=None, strict=None, topocentric=true, dedup=Dedup(None),
+        validate=true, sort=false
+    ))]
+    pub fn from_csv(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        path: &Bound<'_, PyAny>,
+        observer: &Observer,
+        columns: Option<&Bound<'_, PyDict>>,
+        units: &str,
+        delimiter: &str,
+        header: bool,
+        error_ra: Option<f64>,
+        error_dec: Option<f64>,
+        on_error: &str,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        dedup: Dedup,
+        validate: bool,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let degrees = degrees_units(units)?;
+        let skip = skip_malformed(on_error)?;
+        let errors = record_batch_errors(error_ra, error_dec);
+        let opts = csv_options(columns, delimiter, header, errors.is_none())?;
+        let p = py_path_to_utf8(py, path)?;
+        let (table, malformed) = py.detach(|| read_csv(&p, &opts))?;
+        report_malformed(py, &p, "row", "line", malformed, skip, strict)?;
+        csv_set(
+            py,
+            env,
+            handle,
+            table,
+            errors,
+            degrees,
+            observer,
+            bad_sigma,
+            bad_sigma_value,
+            strict,
+            topocentric,
+            dedup,
+            validate,
+            sort,
+        )
+    }
+
+    /// Build a `TrajectorySet` from a pandas `DataFrame` with one row per observation.
+    ///
+    /// The mapped columns are checked and converted (any integer dtype for the ids, or an
+    /// `object` column of `int` or `str` ids, any numeric dtype for the values), then
+    /// ingested through the same path as
+    /// [`Self::from_numpy_degrees`] / [`Self::from_numpy_radians`].
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `df`: Source `DataFrame`.
+    /// * `observer`: Single observer for every row.
+    /// * `ra_col`, `dec_col`, `time_col`, `id_col`: Names of the RA, DEC, epoch (MJD, TT)
+    ///   and trajectory id columns.
+    /// * `units`: `"degrees"` (RA/DEC in degrees, uncertainties in arcseconds, default) or
+    ///   `"radians"`.
+    /// * `error_ra`, `error_dec`: Uniform 1-σ uncertainties in the unit of `units`, given
+    ///   together.
+    /// * `sigma_ra_col`, `sigma_dec_col`: Per-row uncertainty columns, used when no
+    ///   uniform uncertainties are given and the `DataFrame` has them.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`].
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`.
+    ///
+    /// Notes
+    /// ----------
+    /// * Raises `ValueError` listing every missing column, and when a column has the
+    ///   wrong dtype or an integer id is outside the `uint32` range. The ids of an
+    ///   `object` column are read as the `trajectory_id` of [`Self::from_numpy_degrees`]:
+    ///   errors name the first element that is neither an `int` nor a `str`, or not of
+    ///   the type of the others.
+    /// * Without uniform uncertainties nor uncertainty columns, the uncertainties are
+    ///   unknown and `bad_sigma` decides (e.g. `"model"`).
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, df, observer, ra_col="ra", dec_col="dec", time_col="mjd_tt",
+        id_col="trajectory_id", units="degrees", error_ra=None, error_dec=None,
+        sigma_ra_col="sigma_ra", sigma_dec_col="sigma_dec", bad_sigma="error",
+        bad_sigma_value=None, strict=None, topocentric=true, sort=false
+    ))]
+    pub fn from_pandas(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        df: &Bound<'_, PyAny>,
+        observer: &Observer,
+        ra_col: &str,
+        dec_col: &str,
+        time_col: &str,
+        id_col: &str,
+        units: &str,
+        error_ra: Option<f64>,
+        error_dec: Option<f64>,
+        sigma_ra_col: &str,
+        sigma_dec_col: &str,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let degrees = degrees_units(units)?;
+        let scale = if degrees { RADSEC } else { 1.0 };
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, scale)?;
+        let uniform = uniform_errors(error_ra, error_dec)?;
+
+        let columns = df.getattr("columns")?;
+        let has = |name: &str| columns.contains(name);
+        let per_row = uniform.is_none() && (has(sigma_ra_col)? || has(sigma_dec_col)?);
+        let mut required = vec![
+            (id_col, "id_col"),
+            (time_col, "time_col"),
+            (ra_col, "ra_col"),
+            (dec_col, "dec_col"),
+        ];
+        if per_row {
+            required.extend([
+                (sigma_ra_col, "sigma_ra_col"),
+                (sigma_dec_col, "sigma_dec_col"),
+            ]);
+        }
+        check_frame_columns(&columns, &required)?;
+
+        let (tid, numbering) = frame_ids(df, id_col)?;
+        let mjd = frame_f64(df, time_col)?;
+        let ra = frame_f64(df, ra_col)?;
+        let dec = frame_f64(df, dec_col)?;
+
+        let (err_ra, err_dec) = uniform.unwrap_or((f64::NAN, f64::NAN));
+        let sigmas = match per_row {
+            true => Some((frame_f64(df, sigma_ra_col)?, frame_f64(df, sigma_dec_col)?)),
+            false => None,
+        };
+        let batch = ingest::Rows {
+            ids: &tid,
+            ra: &ra,
+            dec: &dec,
+            mjd: &mjd,
+            errors: (err_ra, err_dec),
+            sigmas: sigmas.as_ref().map(|(ra, dec)| (&ra[..], &dec[..])),
+            degrees,
+        };
+        let site = ingest::observing_site(&observer.inner, topocentric)?;
+        let inner = py
+            .detach(|| batch.ingest(&mut env.inner_mut_detached(), site))
+            .into_py()?;
+
+        TrajectorySet::checked(
+            py,
+            numbering.restore(inner),
+            env,
+            handle,
+            policy,
+            strict,
+            sort,
+        )
+    }
+
+    /// Build a `TrajectorySet` from any `DataFrame` implementing the interchange protocol
+    /// (`__dataframe__`): pandas, polars, vaex, cudf frames held in host memory, …
+    ///
+    /// The mapped columns are read from the buffers the protocol exposes, on the Rust
+    /// side, then ingested through the same path as [`Self::from_pandas`]. Values may be
+    /// integers or floats of any width, null values becoming NaN; ids may be integers,
+    /// strings, or categories of either.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `df`: Source `DataFrame`, any object with a `__dataframe__` method.
+    /// * `observer`: Single observer for every row.
+    /// * `ra_col`, `dec_col`, `time_col`, `id_col`, `units`, `error_ra`, `error_dec`,
+    ///   `sigma_ra_col`, `sigma_dec_col`: see [`Self::from_pandas`].
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`].
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`, equal to the one [`Self::from_pandas`] builds from the same
+    ///   columns.
+    ///
+    /// Notes
+    /// ----------
+    /// * Raises `TypeError` when `df` does not implement `__dataframe__`, and `ValueError`
+    ///   listing every missing column, when a column has an unsupported dtype (booleans,
+    ///   dates, floating ids, …) or lives in device memory, and on a null or negative id.
+    /// * Null uncertainties are NaN, so that `bad_sigma` decides.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, df, observer, ra_col="ra", dec_col="dec", time_col="mjd_tt",
+        id_col="trajectory_id", units="degrees", error_ra=None, error_dec=None,
+        sigma_ra_col="sigma_ra", sigma_dec_col="sigma_dec", bad_sigma="error",
+        bad_sigma_value=None, strict=None, topocentric=true, sort=false
+    ))]
+    pub fn from_dataframe(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        df: &Bound<'_, PyAny>,
+        observer: &Observer,
+        ra_col: &str,
+        dec_col: &str,
+        time_col: &str,
+        id_col: &str,
+        units: &str,
+        error_ra: Option<f64>,
+        error_dec: Option<f64>,
+        sigma_ra_col: &str,
+        sigma_dec_col: &str,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let degrees = degrees_units(units)?;
+        let scale = if degrees { RADSEC } else { 1.0 };
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, scale)?;
+        let uniform = uniform_errors(error_ra, error_dec)?;
+
+        let frame = interchange::Frame::new(df)?;
+        let columns = frame.column_names()?;
+        let has = |name: &str| columns.contains(name);
+        let per_row = uniform.is_none() && (has(sigma_ra_col)? || has(sigma_dec_col)?);
+        let mut required = vec![
+            (id_col, "id_col"),
+            (time_col, "time_col"),
+            (ra_col, "ra_col"),
+            (dec_col, "dec_col"),
+        ];
+        if per_row {
+            required.extend([
+                (sigma_ra_col, "sigma_ra_col"),
+                (sigma_dec_col, "sigma_dec_col"),
+            ]);
+        }
+        check_frame_columns(columns.as_any(), &required)?;
+
+        let ids = frame.ids(id_col)?;
+        let (tid, numbering) = ids.numbered()?;
+        let mjd = frame.f64_column(time_col)?;
+        let ra = frame.f64_column(ra_col)?;
+        let dec = frame.f64_column(dec_col)?;
+
+        let (err_ra, err_dec) = uniform.unwrap_or((f64::NAN, f64::NAN));
+        let sigmas = match per_row {
+            true => Some((
+                frame.f64_column(sigma_ra_col)?,
+                frame.f64_column(sigma_dec_col)?,
+            )),
+            false => None,
+        };
+        let batch = ingest::Rows {
+            ids: &tid,
+            ra: &ra,
+            dec: &dec,
+            mjd: &mjd,
+            errors: (err_ra, err_dec),
+            sigmas: sigmas.as_ref().map(|(ra, dec)| (&ra[..], &dec[..])),
+            degrees,
+        };
+        let site = ingest::observing_site(&observer.inner, topocentric)?;
+        let inner = py
+            .detach(|| batch.ingest(&mut env.inner_mut_detached(), site))
+            .into_py()?;
+
+        TrajectorySet::checked(
+            py,
+            numbering.restore(inner),
+            env,
+            handle,
+            policy,
+            strict,
+            sort,
+        )
+    }
+
+    /// Build a `TrajectorySet` from a Fink / ZTF tracklet table.
+    ///
+    /// The schema of Fink exports is fixed: `objectId` (string ids), `ra` / `dec`
+    /// (degrees), `jd` (Julian Date, UTC), `sigmara` / `sigmadec` (arcseconds) and `fid`
+    /// (ZTF filter id). Epochs are converted to MJD (TT), every row is attributed to the
+    /// ZTF site (MPC code `I41`), and the filter of each detection is kept as its band
+    /// (`1`: `"g"`, `2`: `"r"`, `3`: `"i"`), with the `magpsf` magnitude when the table
+    /// has that column. The table is read as in [`Self::from_dataframe`].
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `df`: Source `DataFrame` (pandas, polars, or any other implementing
+    ///   `__dataframe__`).
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`]
+    ///   (`bad_sigma_value` in arcseconds).
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet` keyed by `objectId`, with the photometry of every row.
+    ///
+    /// Notes
+    /// ----------
+    /// * Raises `ValueError` listing every missing column of the schema, on an unknown
+    ///   filter id, an epoch before 1972 or that is not a Julian Date, and as
+    ///   [`Self::from_dataframe`] for the column dtypes and null ids.
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, df, bad_sigma="error", bad_sigma_value=None, strict=None, sort=false
+    ))]
+    pub fn from_fink(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        df: &Bound<'_, PyAny>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let frame = interchange::Frame::new(df)?;
+        let columns = frame.column_names()?;
+        check_frame_columns(columns.as_any(), &FINK_COLUMNS)?;
+
+        let ids = frame.ids("objectId")?;
+        let (tid, numbering) = ids.numbered()?;
+        let jd = frame.f64_column("jd")?;
+        let mjd = time_scales::epochs_to_mjd_tt(&jd, "jd", "UTC")?;
+        let ra = frame.f64_column("ra")?;
+        let dec = frame.f64_column("dec")?;
+        let sigma_ra = frame.f64_column("sigmara")?;
+        let sigma_dec = frame.f64_column("sigmadec")?;
+        let band = frame
+            .f64_column("fid")?
+            .into_iter()
+            .enumerate()
+            .map(|(row, fid)| ztf_band(row, fid))
+            .collect::<PyResult<Vec<_>>>()?;
+        let mag = match columns.contains("magpsf")? {
+            true => Some(frame.f64_column("magpsf")?),
+            false => None,
+        };
+
+        let batch = ingest::Rows {
+            ids: &tid,
+            ra: &ra,
+            dec: &dec,
+            mjd: &mjd,
+            errors: (f64::NAN, f64::NAN),
+            sigmas: Some((&sigma_ra, &sigma_dec)),
+            degrees: true,
+        };
+        let ztf = env
+            .outfit(py)
+            .get_observer_from_mpc_code(&"I41".to_string());
+        let site = ingest::observing_site(&ztf, true)?;
+        let inner = py
+            .detach(|| batch.ingest(&mut env.inner_mut_detached(), site))
+            .into_py()?;
+        let mut photometry = PhotometryTable::default();
+        photometry.record(&inner, &tid, mag.as_deref(), Some(&band));
+
+        TrajectorySet::checked(
+            py,
+            numbering.restore(inner),
+            env,
+            handle,
+            policy,
+            strict,
+            sort,
+        )
+        .map(|set| set.with_photometry(photometry))
+    }
+
+    /// Build a `TrajectorySet` from a polars `DataFrame` without copying its columns.
+    ///
+    /// Non-null, single-chunk `Float64` and `UInt32` columns are read in place: their Arrow
+    /// buffers are exposed as read-only NumPy views and borrowed as slices by the NumPy
+    /// loaders' ingestion ([`ingest::Rows::ingest`]), so that no intermediate copy of the
+    /// table is made. Other columns (nullable, chunked, other numeric dtypes) are cast and
+    /// rechunked, costing one copy of that column; ids of other integer dtypes keep theirs,
+    /// so that `UInt64` / `Int64` survey ids are accepted as by the NumPy loaders. The rows
+    /// then go through the checks and options of [`Self::from_numpy_radians`] /
+    /// [`Self::from_numpy_degrees`].
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `df`: Source `polars.DataFrame`.
+    /// * `observer`: Single observer for every row.
+    /// * `ra_col`, `dec_col`, `time_col`, `id_col`: Names of the RA, DEC, epoch (MJD, TT)
+    ///   and trajectory id columns.
+    /// * `units`: `"radians"` (default, zero-copy) or `"degrees"` (RA/DEC in degrees,
+    ///   uncertainties in arcseconds, converted once to radians).
+    /// * `error_ra`, `error_dec`: Uniform 1-σ uncertainties in the unit of `units`, given
+    ///   together. `None` leaves them unknown and defers to `bad_sigma`.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`].
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `sort`, `dedup`, `validate`, `drop_invalid`: see [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`, equal to the one [`Self::from_numpy_radians`] builds from
+    ///   the same columns.
+    ///
+    /// Notes
+    /// ----------
+    /// * Raises `ValueError` listing every missing column, and when a column has the
+    ///   wrong dtype, an id is null or negative.
+    /// * Null values of the `Float64` columns read as NaN: `validate` rejects their rows,
+    ///   `drop_invalid` drops them.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, df, observer, ra_col="ra", dec_col="dec", time_col="mjd_tt",
+        id_col="trajectory_id", units="radians", error_ra=None, error_dec=None,
+        bad_sigma="error", bad_sigma_value=None, strict=None, topocentric=true, sort=false,
+        dedup=Dedup(None), validate=true, drop_invalid=false
+    ))]
+    pub fn from_polars(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        df: &Bound<'_, PyAny>,
+        observer: &Observer,
+        ra_col: &str,
+        dec_col: &str,
+        time_col: &str,
+        id_col: &str,
+        units: &str,
+        error_ra: Option<f64>,
+        error_dec: Option<f64>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        sort: bool,
+        dedup: Dedup,
+        validate: bool,
+        drop_invalid: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let degrees = degrees_units(units)?;
+        let (err_ra, err_dec) =
+            uniform_errors(error_ra, error_dec)?.unwrap_or((f64::NAN, f64::NAN));
+        check_frame_columns(
+            &df.getattr("columns")?,
+            &[
+                (id_col, "id_col"),
+                (time_col, "time_col"),
+                (ra_col, "ra_col"),
+                (dec_col, "dec_col"),
+            ],
+        )?;
+
+        let polars = py.import("polars")?;
+        let column = |name: &str| polars_column(&polars, df, name);
+        let columns = NumpyColumns {
+            names: ["ra_col", "dec_col", "error_ra", "error_dec"],
+            trajectory_id: polars_ids(df, id_col)?.extract::<TrajectoryIds>()?.into(),
+            ra: column(ra_col)?.extract::<Reals>()?.into(),
+            dec: column(dec_col)?.extract::<Reals>()?.into(),
+            error_ra: Sigma::Uniform(err_ra).into(),
+            error_dec: Sigma::Uniform(err_dec).into(),
+            mjd_tt: Epochs::Numbers(column(time_col)?.extract()?).into(),
+            mag: None,
+            band: None,
+        };
+        let options = NumpyOptions {
+            bad_sigma,
+            bad_sigma_value,
+            strict,
+            topocentric,
+            time_scale: None,
+            epoch_format: "mjd",
+            dedup,
+            validate,
+            sort,
+            drop_invalid,
+            parallel: false,
+            n_threads: None,
+        };
+        let sites = BatchSites::Single(observer);
+        Self::from_numpy_columns(py, env, handle, columns, degrees, sites, options)
+    }
+
+    /// Build a `TrajectorySet` from an astropy `Table`, honouring the column units.
+    ///
+    /// Angle columns are converted to radians from their attached unit; the time column
+    /// may be an `astropy.time.Time` in any scale (converted to TT) or plain MJD (TT)
+    /// values. The converted columns go through [`Self::from_numpy_radians`]'s path.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `table`: Source `astropy.table.Table` or `QTable`.
+    /// * `observer`: Single observer for every row.
+    /// * `ra_col`, `dec_col`, `time_col`, `id_col`: Names of the RA, DEC, epoch and
+    ///   trajectory id columns.
+    /// * `units`: `"degrees"` or `"radians"`, for RA/DEC columns without a unit. With a
+    ///   unit attached, it must agree with it.
+    /// * `error_ra`, `error_dec`: Uniform 1-σ uncertainties, given together: angle
+    ///   `Quantity` objects or floats in arcseconds. `None` defers to `bad_sigma`.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`]
+    ///   (`bad_sigma_value` in arcseconds).
+    /// * `topocentric`: `False` for astrometry already reduced to the geocentre, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`.
+    ///
+    /// Notes
+    /// ----------
+    /// * Raises `ValueError` listing every missing column, when an angle column has a
+    ///   non-angular unit, no unit and no `units`, or a unit contradicting `units`, when
+    ///   the time column has a non-temporal unit, and on masked values or ids outside the
+    ///   `uint32` range.
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, table, observer, ra_col="ra", dec_col="dec", time_col="time",
+        id_col="trajectory_id", units=None, error_ra=None, error_dec=None,
+        bad_sigma="error", bad_sigma_value=None, strict=None, topocentric=true, sort=false
+    ))]
+    pub fn from_astropy_table(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        table: &Bound<'_, PyAny>,
+        observer: &Observer,
+        ra_col: &str,
+        dec_col: &str,
+        time_col: &str,
+        id_col: &str,
+        units: Option<&str>,
+        error_ra: Option<&Bound<'_, PyAny>>,
+        error_dec: Option<&Bound<'_, PyAny>>,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        topocentric: bool,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let degrees = units.map(degrees_units).transpose()?;
+        let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+        let u = py.import("astropy.units")?;
+        let arcsec = |e: Option<&Bound<'_, PyAny>>| -> PyResult<Option<f64>> {
+            e.map(|e| match e.hasattr("unit")? {
+                true => e
+                    .call_method1("to_value", (u.getattr("arcsec")?,))?
+                    .extract(),
+                false => e.extract(),
+            })
+            .transpose()
+        };
+        let (err_ra, err_dec) = uniform_errors(arcsec(error_ra)?, arcsec(error_dec)?)?
+            .map_or((f64::NAN, f64::NAN), |(ra, dec)| {
+                (ra / RADSEC, dec / RADSEC)
+            });
+        check_frame_columns(
+            &table.getattr("colnames")?,
+            &[
+                (id_col, "id_col"),
+                (time_col, "time_col"),
+                (ra_col, "ra_col"),
+                (dec_col, "dec_col"),
+            ],
+        )?;
+
+        let np = py.import("numpy")?;
+        let tid = {
+            let col = unmasked_column(&np, table, id_col)?;
+            let values = np.call_method1("asarray", (col,))?;
+            let kind: String = values.getattr("dtype")?.getattr("kind")?.extract()?;
+            if kind != "i" && kind != "u" {
+                return Err(PyValueError::new_err(format!(
+                    "column {id_col:?} must hold integer trajectory ids"
+                )));
+            }
+            u32_ids(&values, id_col)?
+        };
+        let mjd = astropy_mjd_tt(&np, &u, table, time_col)?;
+        let ra = astropy_radians(&np, &u, table, ra_col, degrees)?;
+        let dec = astropy_radians(&np, &u, table, dec_col, degrees)?;
+
+        let batch = ingest::Rows {
+            ids: &tid,
+            ra: &ra,
+            dec: &dec,
+            mjd: &mjd,
+            errors: (err_ra, err_dec),
+            sigmas: None,
+            degrees: false,
+        };
+        let site = ingest::observing_site(&observer.inner, topocentric)?;
+        let ts_res = py.detach(|| batch.ingest(&mut env.inner_mut_detached(), site));
+
+        TrajectorySet::checked(py, ts_res.into_py()?, env, handle, policy, strict, sort)
+    }
+
+    /// Build a `TrajectorySet` from a file, detecting its format.
+    ///
+    /// The format is sniffed from the content (Parquet magic bytes, ADES XML root tag,
+    /// ADES JSON `"ades"` member, ADES PSV version line or field header, fixed 80-column
+    /// records, delimiter of the header line), the extension only being used when the
+    /// content is inconclusive. The file is then read by the dedicated loader, with
+    /// `kwargs` forwarded to it:
+    ///
+    /// * MPC 80-column → [`Self::new_from_mpc_80col`];
+    /// * ADES XML → [`Self::new_from_ades`];
+    /// * ADES PSV → [`Self::from_ades_psv`];
+    /// * ADES JSON → [`Self::from_ades_json`];
+    /// * CSV / PSV with a header line (`#` comments skipped) → [`Self::from_csv`], with
+    ///   the `"|"` delimiter for PSV and the `tid` / `mjd` / `ra` / `dec` columns by
+    ///   default;
+    /// * Parquet → [`Self::from_parquet`] (decoded in Rust, row group by row group).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `path`: File path (`str` or `pathlib.Path`).
+    /// * `observer`: Site of tabular files: required for CSV and PSV, optional for
+    ///   Parquet (see [`Self::from_parquet`]).
+    /// * `kwargs`: Keyword arguments of the selected loader (e.g. `columns`, `error_ra`,
+    ///   `bad_sigma`).
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`.
+    ///
+    /// Notes
+    /// ----------
+    /// * Raises `ValueError` listing the considered formats when detection fails, when
+    ///   `observer` is given for a format carrying its own observatory codes, and when it
+    ///   is missing for CSV / PSV.
+    #[staticmethod]
+    #[pyo3(signature = (env, path, observer=None, **kwargs))]
+    pub fn from_file<'py>(
+        py: Python<'py>,
+        env: &Bound<'py, PyOutfit>,
+        path: &Bound<'py, PyAny>,
+        observer: Option<&Bound<'py, Observer>>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let p = py_path_to_utf8(py, path)?;
+        let format = file_format::sniff(&p)?.ok_or_else(|| {
+            let considered: Vec<&str> = FileFormat::ALL.iter().map(|f| f.name()).collect();
+            PyValueError::new_err(format!(
+                "could not detect the format of {p} (considered: {})",
+                considered.join(", ")
+            ))
+        })?;
+        let cls = py.get_type::<TrajectorySet>();
+
+        match format {
+            FileFormat::Mpc80Col
+            | FileFormat::AdesXml
+            | FileFormat::AdesPsv
+            | FileFormat::AdesJson => {
+                if observer.is_some() {
+                    return Err(PyValueError::new_err(format!(
+                        "observer is not used for {} files: they carry their own observatory codes",
+                        format.name()
+                    )));
+                }
+                let loader = match format {
+                    FileFormat::Mpc80Col => "new_from_mpc_80col",
+                    FileFormat::AdesPsv => "from_ades_psv",
+                    FileFormat::AdesJson => "from_ades_json",
+                    _ => "new_from_ades",
+                };
+                cls.call_method(loader, (env, path), kwargs)
+            }
+            FileFormat::Csv | FileFormat::Psv => {
+                let observer = observer.ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "observer is required for {} files: from_csv reads every row from one site",
+                        format.name()
+                    ))
+                })?;
+                let forwarded = match kwargs {
+                    Some(k) => k.copy()?,
+                    None => PyDict::new(py),
+                };
+                // Column names of `from_arrow_ipc`, which these files were first read with.
+                let columns = PyDict::new(py);
+                columns.set_item("id", "tid")?;
+                columns.set_item("time", "mjd")?;
+                if let Some(given) = forwarded.get_item("columns")? {
+                    columns.update(given.cast::<PyDict>()?.as_mapping())?;
+                }
+                forwarded.set_item("columns", columns)?;
+                if format == FileFormat::Psv && !forwarded.contains("delimiter")? {
+                    forwarded.set_item("delimiter", "|")?;
+                }
+                cls.call_method("from_csv", (env, path, observer), Some(&forwarded))
+            }
+            FileFormat::Parquet => {
+                let forwarded = match kwargs {
+                    Some(k) => k.copy()?,
+                    None => PyDict::new(py),
+                };
+                if let Some(obs) = observer {
+                    forwarded.set_item("observer", obs)?;
+                }
+                cls.call_method("from_parquet", (env, path), Some(&forwarded))
+            }
+        }
+    }
+
+    /// Build one `TrajectorySet` from several observation files of the same format.
+    ///
+    /// The files are read and parsed in Rust with the GIL released for the whole loop,
+    /// concurrently on the rayon pool with `parallel`, then merged: observations with the
+    /// same trajectory id in several files (e.g. one file per night) form one trajectory,
+    /// in time order. The same detection found in two files is kept once with `dedup`.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global environment (ephemerides, observers, error model).
+    /// * `paths`: File paths (`str` or `pathlib.Path`).
+    /// * `format`: `"ades_xml"` (default), `"parquet"` or `"csv"`.
+    /// * `observer`: Single observer for every row of tabular files: required for CSV,
+    ///   optional for Parquet (see [`Self::from_parquet`]). ADES records carry their own
+    ///   observatory codes and refuse it.
+    /// * `columns`: Column mapping of tabular files, as in [`Self::from_parquet`] /
+    ///   [`Self::from_csv`].
+    /// * `units`: Units of tabular files, `"degrees"` (default) or `"radians"`.
+    /// * `error_ra`, `error_dec`: Uniform 1-σ uncertainties of the rows without their own,
+    ///   in arcseconds for ADES files and in the unit of `units` otherwise.
+    /// * `on_error`: `"raise"` or `"skip"` invalid records of ADES and CSV files, see
+    ///   [`Self::from_mpc_80col`]; they are reported file by file.
+    /// * `bad_sigma`, `bad_sigma_value`, `strict`: see [`Self::from_numpy_degrees`].
+    /// * `dedup`: drop duplicate observations, see [`Self::from_numpy_degrees`] (default:
+    ///   `True`, with the default tolerances).
+    /// * `parallel`: Parse the files concurrently (default: `True`).
+    /// * `sort`: sort trajectories out of time order without a report, see
+    ///   [`Self::from_numpy_degrees`].
+    /// * `include_rejected`: keep the ADES records flagged as rejected, see
+    ///   [`Self::from_ades_xml`].
+    ///
+    /// Return
+    /// ----------
+    /// * A new `TrajectorySet`, keyed as by the loader of a single file
+    ///   ([`Self::from_ades_xml`], [`Self::from_parquet`], [`Self::from_csv`]).
+    ///
+    /// Notes
+    /// ----------
+    /// * A file that cannot be read or parsed raises (`OSError`, `ValueError`) before
+    ///   anything is ingested.
+    /// * Parquet files are decoded whole before ingestion, so the record batches of every
+    ///   file are held at once; [`Self::from_parquet`] streams a single file row group by
+    ///   row group.
+    /// * Trajectories split over files given out of chronological order are sorted with
+    ///   a `FallbackWarning`, as any unordered input (see `sort`).
+    #[allow(clippy::too_many_arguments)]
+    #[staticmethod]
+    #[pyo3(signature = (
+        env, paths, format="ades_xml", observer=None, columns=None, units="degrees",
+        error_ra=None, error_dec=None, on_error="raise", bad_sigma="error",
+        bad_sigma_value=None, strict=None, dedup=Dedup(Some(Tolerance::default())),
+        parallel=true, sort=false, include_rejected=false
+    ))]
+    pub fn from_files(
+        py: Python<'_>,
+        mut env: LoaderEnv<'_>,
+        paths: Vec<Bound<'_, PyAny>>,
+        format: &str,
+        observer: Option<&Observer>,
+        columns: Option<&Bound<'_, PyDict>>,
+        units: &str,
+        error_ra: Option<f64>,
+        error_dec: Option<f64>,
+        on_error: &str,
+        bad_sigma: &str,
+        bad_sigma_value: Option<f64>,
+        strict: Option<bool>,
+        dedup: Dedup,
+        parallel: bool,
+        sort: bool,
+        include_rejected: bool,
+    ) -> PyResult<TrajectorySet> {
+        let (env, handle) = env.split();
+        let skip = skip_malformed(on_error)?;
+        let paths = paths
+            .iter()
+            .map(|p| py_path_to_utf8(py, p))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        match format {
+            "ades_xml" => {
+                if observer.is_some() {
+                    return Err(PyValueError::new_err(
+                        "observer is not used for ADES XML files: they carry their own \
+                         observatory codes",
+                    ));
+                }
+                let policy = BadSigma::parse(bad_sigma, bad_sigma_value, RADSEC)?;
+                let opts = ades_options(None, error_ra, error_dec)?;
+                let parsed = py.detach(|| {
+                    let state = env.outfit_detached();
+                    let files = read_files(&paths, parallel, |p| {
+                        let text = std::fs::read_to_string(p)
+                            .map_err(|e| PyOSError::new_err(format!("cannot read {p}: {e}")))?;
+                        ades::parse_xml(&text, &opts).map_err(|e| {
+                            PyValueError::new_err(format!("invalid ADES XML in {p}: {e}"))
+                        })
+                    })?;
+                    let known = files.into_iter().map(|(records, mut malformed)| {
+                        let (records, unknown) =
+                            records::known_sites(env.mpc_sites(), &state, records);
+                        malformed.extend(unknown);
+                        (records, malformed)
+                    });
+                    PyResult::Ok(known.collect::<Vec<_>>())
+                })?;
+
+                let mut all = Vec::new();
+                for (p, (records, malformed)) in paths.iter().zip(parsed) {
+                    report_malformed(py, p, "record", "line", malformed, skip, strict)?;
+                    all.extend(records);
+                }
+                if !include_rejected {
+                    all.retain(|r| !r.rejected);
+                }
+                let built =
+                    py.detach(|| records::build(&mut env.inner_mut_detached(), &all, false));
+                rejected_set(py, built.into_py()?, env, handle, policy, strict, sort)
+                    .map(|set| set.deduplicated(dedup))
+            }
+            "parquet" => {
+                let degrees = degrees_units(units)?;
+                let policy = BadSigma::parse(
+                    bad_sigma,
+                    bad_sigma_value,
+                    if degrees { RADSEC } else { 1.0 },
+                )?;
+                let names = arrow_column_names(columns)?;
+                let errors = record_batch_errors(error_ra, error_dec);
+                let sites = observer.is_none();
+                let batches = py.detach(|| {
+                    read_files(&paths, parallel, |p| {
+                        // The batch size of `from_parquet`.
+                        let reader = parquet_reader(p, &names, sites, errors.is_none())?
+                            .with_batch_size(65536)
+                            .build()
+                            .map_err(|e| parquet_err(p, e))?;
+                        reader.collect::<Result<Vec<_>, _>>().map_err(|e| {
+                            PyValueError::new_err(format!("cannot read {p} as Parquet: {e}"))
+                        })
+                    })
+                })?;
+
+                TrajectorySet::from_record_batches(
+                    py,
+                    env,
+                    handle,
+                    batches.into_iter().flatten().map(Ok),
+                    observer,
+                    RecordBatchOptions {
+                        columns: names,
+                        degrees,
+                        flexible_ids: true,
+                        errors,
+                        policy,
+                        strict,
+                        topocentric: true,
+                        sort,
+                    },
+                )
+                .map(|set| set.deduplicated(dedup))
+            }
+            "csv" => {
+                let observer = observer
+                    .ok_or_else(|| PyValueError::new_err("observer is required for CSV files"))?;
+                let degrees = degrees_units(units)?;
+                let errors = record_batch_errors(error_ra, error_dec);
+                let opts = csv_options(columns, ",", true, errors.is_none())?;
+                let parsed = py.detach(|| read_files(&paths, parallel, |p| read_csv(p, &opts)))?;
+
+                let mut table = csv_reader::Table::default();
+                for (p, (rows, malformed)) in paths.iter().zip(parsed) {
+                    report_malformed(py, p, "row", "line", malformed, skip, strict)?;
+                    table.append(rows);
+                }
+                csv_set(
+                    py,
+                    env,
+                    handle,
+                    table,
+                    errors,
+                    degrees,
+                    observer,
+                    bad_sigma,
+                    bad_sigma_value,
+                    strict,
+                    true,
+                    dedup,
+                    true,
+                    sort,
+                )
+            }
+            other => Err(PyValueError::new_err(format!(
+                "format must be 'ades_xml', 'parquet' or 'csv', got {other:?}"
+            ))),
+        }
+    }
+
+    /// Export every observation as a columnar dict, one row per observation.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `include_units`: Add a `"units"` block mapping each numeric column to its unit
+    ///   (default: `False`).
+    /// * `degrees`: Export angles in degrees instead of radians (default: `False`).
+    ///   The units block is then always included.
+    ///
+    /// Return
+    /// ----------
+    /// * A dict of equally long lists with the columns of
+    ///   `py_outfit.schemas()["trajectory_set"]`: `trajectory_id` followed by the columns
+    ///   of `Observations.to_dict`. Trajectories are sorted by identifier, observations
+    ///   keep their stored order.
+    #[pyo3(
+        signature = (include_units=false, degrees=false),
+        text_signature = "($self, include_units=False, degrees=False)"
+    )]
+    fn to_dict<'py>(
+        &self,
+        py: Python<'py>,
+        include_units: bool,
+        degrees: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let columns = schemas::trajectory_set();
+        let mut keys: Vec<&ObjectNumber> = self.inner.keys().collect();
+        keys.sort();
+
+        let ids = PyList::empty(py);
+        for k in &keys {
+            let id = object_number_to_py(py, k)?;
+            for _ in 0..self.inner[*k].len() {
+                ids.append(&id)?;
+            }
+        }
+        let values = observation_columns(keys.iter().flat_map(|k| self.inner[*k].iter()), degrees);
+
+        let d = PyDict::new(py);
+        d.set_item(columns[0].name, ids)?;
+        for (c, v) in columns[1..].iter().zip(values) {
+            d.set_item(c.name, v)?;
+        }
+        if include_units || degrees {
+            d.set_item(
+                "units",
+                units::units_block(py, &schemas::unit_fields(&columns), degrees)?,
+            )?;
+        }
+        Ok(d)
+    }
+
+    /// Write every observation to a Parquet file, one row per observation.
+    ///
+    /// The columns are those of `schemas()["trajectory_set_parquet"]`: `trajectory_id`,
+    /// `mjd_tt`, `ra`, `dec`, `sigma_ra`, `sigma_dec` (angles in radians) and the MPC code
+    /// of the observing site in `observer`. Arrays are built and the file is written with
+    /// the GIL released.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `path`: Destination file (`str` or `pathlib.Path`), created or truncated.
+    /// * `compression`: `"snappy"` (default) or `"none"`.
+    /// * `env`: Environment resolving the observing sites (default: the one the set was
+    ///   ingested with).
+    ///
+    /// Return
+    /// ----------
+    /// * The number of rows written.
+    ///
+    /// Notes
+    /// ----------
+    /// * `trajectory_id` is `uint32` when every id is an integer and a string column
+    ///   otherwise (integer ids then written as strings). Trajectories are written by
+    ///   increasing id, their observations in time order.
+    /// * Sites without an MPC code (user-defined observers) have a null `observer`.
+    /// * The file reads back with [`Self::from_parquet`], using
+    ///   `columns={"tid": "trajectory_id", "mjd": "mjd_tt"}` and `units="radians"`.
+    /// * `"zstd"` is not available in this build and raises `ValueError`, as do unknown
+    ///   codecs; a file that cannot be created or written raises `OSError`.
+    /// * Without `env=`, a set that records no environment (e.g. `from_chunks` over no
+    ///   chunks) raises `ValueError`.
+    #[pyo3(signature = (path, compression="snappy", env=None))]
+    fn to_parquet(
+        &self,
+        py: Python<'_>,
+        path: &Bound<'_, PyAny>,
+        compression: &str,
+        env: Option<PyRef<'_, PyOutfit>>,
+    ) -> PyResult<usize> {
+        let compression = parquet_writer::compression(compression)?;
+        let path = py_path_to_utf8(py, path)?;
+        let env = match env {
+            Some(env) => env,
+            None => self.recorded_env(py)?,
+        };
+        let env: &PyOutfit = &env;
+        py.detach(|| {
+            let outfit = env.outfit_detached();
+            parquet_writer::write(&self.inner, &outfit, env.mpc_sites(), &path, compression)
+        })
+    }
+
+    /// Write every observation to an ADES XML file, for submission to the MPC.
+    ///
+    /// Observations are grouped by station into `<obsBlock>` elements sharing the header
+    /// `obs_context`, with one `<optical>` record per observation (inverse of the field
+    /// mapping of [`Self::from_ades_xml`]). The document is built and written with the GIL
+    /// released.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Environment the set was ingested with (resolves the station codes).
+    /// * `path`: Destination file (`str` or `pathlib.Path`), created or truncated.
+    /// * `obs_context`: Header of the blocks: `observers`, `measurers` (a name or a list
+    ///   of names), `telescope` (dict with `design`, `aperture`, `detector` and optionally
+    ///   `name`, `fRatio`, `filter`, `arraySize`, `pixelScale`), and optionally
+    ///   `submitter` (a name or `{"name", "institution"}`, default: the first measurer)
+    ///   and `comment` (a line or a list of lines).
+    /// * `id_field`: ADES field holding the trajectory id: `"trkSub"` (default),
+    ///   `"provID"` or `"permID"`.
+    /// * `mode`: Observation mode of the records (default `"CCD"`).
+    /// * `ast_cat`: Astrometric catalogue of the records (`astCat`, required for MPC
+    ///   submissions), omitted with `None`.
+    ///
+    /// Return
+    /// ----------
+    /// * The number of `<optical>` records written.
+    ///
+    /// Notes
+    /// ----------
+    /// * `obsTime` is written in UTC with millisecond precision, `ra` / `dec` in degrees
+    ///   (9 decimals), `rmsRA` (on-sky, `σ_RA·cos(dec)`) and `rmsDec` in arcseconds
+    ///   (4 decimals, omitted for unknown uncertainties).
+    /// * Raises `ValueError` when an observation's site has no MPC code, when an id is
+    ///   not a valid value of `id_field` (`trkSub`: 1 to 8 letters or digits), or on an
+    ///   invalid `obs_context` (`KeyError` for a missing required key), and `OSError`
+    ///   when the file cannot be written.
+    #[pyo3(signature = (env, path, obs_context, id_field="trkSub", mode="CCD", ast_cat=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn to_ades_xml(
+        &self,
+        py: Python<'_>,
+        env: &PyOutfit,
+        path: &Bound<'_, PyAny>,
+        obs_context: &Bound<'_, PyDict>,
+        id_field: &str,
+        mode: &str,
+        ast_cat: Option<&str>,
+    ) -> PyResult<usize> {
+        let context = ades_writer::ObsContext::from_dict(obs_context)?;
+        let opts = ades_writer::Options::new(id_field, mode, ast_cat)?;
+        let path = py_path_to_utf8(py, path)?;
+        py.detach(|| {
+            let (text, n) = ades_writer::document(
+                &self.inner,
+                &env.outfit_detached(),
+                env.mpc_sites(),
+                &context,
+                &opts,
+            )?;
+            std::fs::write(&path, text)
+                .map_err(|e| PyOSError::new_err(format!("cannot write {path}: {e}")))?;
+            Ok(n)
+        })
+    }
+
+    /// Write every observation to an MPC 80-column file, one record per line.
+    ///
+    /// Records are formatted as by `Observations.to_mpc_80col`, with the trajectory id
+    /// as designation and the MPC code of each observation's site; the file is written
+    /// with the GIL released.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Environment the set was ingested with (resolves the observatory codes).
+    /// * `path`: Destination file (`str` or `pathlib.Path`), created or truncated.
+    ///
+    /// Return
+    /// ----------
+    /// * The number of records written.
+    ///
+    /// Notes
+    /// ----------
+    /// * Trajectories are written by increasing id, their observations in time order.
+    ///   Integer ids are numbers (columns 1–5), string ids designations of at most 7
+    ///   characters (columns 6–12).
+    /// * The file reads back with [`Self::from_mpc_80col`], ids then being strings
+    ///   (zero-padded or packed numbers for integer ids).
+    /// * Raises `ValueError` when an id has no 80-column form or an observation's site
+    ///   has no MPC code, and `OSError` when the file cannot be written.
+    #[pyo3(text_signature = "($self, env, path)")]
+    fn to_mpc_80col(
+        &self,
+        py: Python<'_>,
+        env: &PyOutfit,
+        path: &Bound<'_, PyAny>,
+    ) -> PyResult<usize> {
+        let path = py_path_to_utf8(py, path)?;
+        py.detach(|| {
+            mpc80_writer::write(&self.inner, &env.outfit_detached(), env.mpc_sites(), &path)
+        })
+    }
+
+    /// Write every observation to a table of a SQLite database, one row per observation.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Environment the set was ingested with (resolves the observing sites).
+    /// * `path`: Database file (`str` or `pathlib.Path`), created if needed.
+    /// * `table`: Destination table (default: `"observations"`).
+    /// * `if_exists`: `"fail"` (default, raise `ValueError`), `"replace"` (drop the
+    ///   table first) or `"append"` (insert into the table, whose columns must match)
+    ///   when the table already exists.
+    /// * `degrees`: Write angles in degrees instead of radians (default: `False`).
+    ///
+    /// Return
+    /// ----------
+    /// * The number of rows written.
+    ///
+    /// Notes
+    /// ----------
+    /// * The columns are those of [`Self::to_parquet`] (`trajectory_id`, `mjd_tt`,
+    ///   `ra`, `dec`, `sigma_ra`, `sigma_dec`, `observer`): `trajectory_id` is `INTEGER`
+    ///   when every id is an integer, `TEXT` otherwise, and indexed; `observer` is the
+    ///   MPC code of the site, `NULL` for sites without one.
+    /// * Trajectories are written by increasing id, their observations in time order.
+    ///   Rows are inserted without the GIL inside one transaction, rolled back on error;
+    ///   database errors raise `OSError`.
+    #[pyo3(
+        signature = (env, path, table="observations", if_exists="fail", degrees=false),
+        text_signature = "($self, env, path, table='observations', if_exists='fail', degrees=False)"
+    )]
+    fn observations_to_sqlite(
+        &self,
+        py: Python<'_>,
+        env: &PyOutfit,
+        path: PathBuf,
+        table: &str,
+        if_exists: &str,
+        degrees: bool,
+    ) -> PyResult<usize> {
+        let if_exists = IfExists::parse(if_exists)?;
+        let mut keys: Vec<&ObjectNumber> = self.inner.keys().collect();
+        keys.sort();
+        let rows: Vec<(&ObjectNumber, &outfit::observations::Observation)> = keys
+            .iter()
+            .flat_map(|k| self.inner[*k].iter().map(move |o| (*k, o)))
+            .collect();
+        let int_keys = keys.iter().all(|k| matches!(k, ObjectNumber::Int(_)));
+
+        // Sites are resolved once per registry entry, not per row.
+        let outfit = env.outfit(py);
+        let codes = env.mpc_sites().site_codes(&outfit);
+        let mut resolved: HashMap<*const outfit::Observer, Value> = HashMap::new();
+        let mut values = vec![rows
+            .iter()
+            .map(|(k, _)| sqlite_writer::key_value(k))
+            .collect()];
+        for column in observation_columns(rows.iter().map(|(_, o)| *o), degrees) {
+            values.push(column.into_iter().map(Value::Real).collect());
+        }
+        values.push(
+            rows.iter()
+                .map(|(_, o)| {
+                    let site = o.get_observer(&outfit);
+                    resolved
+                        .entry(site as *const outfit::Observer)
+                        .or_insert_with(|| {
+                            codes
+                                .get(site)
+                                .map_or(Value::Null, |c| Value::Text(c.clone()))
+                        })
+                        .clone()
+                })
+                .collect(),
+        );
+        drop(outfit);
+        sqlite_writer::write(
+            py,
+            &path,
+            table,
+            if_exists,
+            &schemas::trajectory_set_file(),
+            int_keys,
+            values,
+        )
+    }
+
+    /// Split trajectories at time gaps larger than `max_gap_days`.
+    ///
+    /// Long identifier-merged arcs may hold several apparitions separated by months,
+    /// while Gauss IOD works best on a single one. Every trajectory containing a gap
+    /// (between consecutive epochs) strictly larger than the threshold is replaced by
+    /// one trajectory per segment, with derived string identifiers `"{id}_a"`,
+    /// `"{id}_b"`, … (`"{id}_aa"` after `"{id}_z"`).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `max_gap_days`: Largest tolerated gap between consecutive epochs, in days (default 30).
+    ///
+    /// Return
+    /// ----------
+    /// * A `dict[old_id, list[str]]` mapping each split trajectory to its new identifiers,
+    ///   in time order. Trajectories without such a gap are left untouched and omitted.
+    ///
+    /// Notes
+    /// ----------
+    /// * Derived identifiers never collide with existing ones: a suffix already in use is
+    ///   skipped.
+    /// * The observations of each segment are stored in time order.
+    #[pyo3(signature = (max_gap_days=30.0), text_signature = "($self, max_gap_days=30.0)")]
+    pub fn split_on_gaps<'py>(
+        &mut self,
+        py: Python<'py>,
+        max_gap_days: f64,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        if !(max_gap_days.is_finite() && max_gap_days > 0.0) {
+            return Err(PyValueError::new_err(format!(
+                "max_gap_days must be a positive finite number, got {max_gap_days}"
+            )));
+        }
+
+        let mut keys: Vec<ObjectNumber> = self.inner.keys().cloned().collect();
+        keys.sort();
+
+        let mapping = PyDict::new(py);
+        for key in keys {
+            let obs = &self.inner[&key];
+            let mut order: Vec<usize> = (0..obs.len()).collect();
+            order.sort_by(|&a, &b| obs[a].time.total_cmp(&obs[b].time));
+
+            let mut segments: Vec<outfit::Observations> = vec![Default::default()];
+            for (pos, &i) in order.iter().enumerate() {
+                if pos > 0 && obs[i].time - obs[order[pos - 1]].time > max_gap_days {
+                    segments.push(Default::default());
+                }
+                segments.last_mut().unwrap().push(obs[i]);
+            }
+            if segments.len() < 2 {
+                continue;
+            }
+
+            self.inner.remove(&key);
+            let mut new_ids = Vec::with_capacity(segments.len());
+            let mut n = 0;
+            for segment in segments {
+                let id = loop {
+                    let candidate = ObjectNumber::String(format!("{key}_{}", gap_suffix(n)));
+                    n += 1;
+                    if !self.inner.contains_key(&candidate) {
+                        break candidate;
+                    }
+                };
+                new_ids.push(id.to_string());
+                self.inner.insert(id, segment);
+            }
+            mapping.set_item(object_number_to_py(py, &key)?, new_ids)?;
+        }
+        Ok(mapping)
+    }
+
+    /// Estimate the best orbit for **all trajectories** in this set.
+    ///
+    /// Runs Gauss-based initial orbit determination for each trajectory, using
+    /// the provided `Outfit` state and `IODParams`. A RNG is created locally:
+    /// - when `seed` is provided, a deterministic `StdRng::seed_from_u64(seed)` is used;
+    /// - otherwise, `StdRng::from_entropy()` is used.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global Outfit state (ephemeris, EOP, error model).
+    /// * `params`: IOD configuration parameters.
+    /// * `seed`: Optional seed for deterministic RNG (u64). If `None`, a random seed is used.
+    /// * `fallback_params`: Relaxed configurations, tried in order on the trajectories that
+    ///   fail under `params`.
+    /// * `initial_guesses`: Optional `dict[trajectory_id, KeplerianElements]`. These
+    ///   trajectories skip the triplet search: their guess is corrected over the whole arc,
+    ///   and the Gauss IOD only runs when that correction diverges.
+    /// * `validate`: Check that every trajectory is in time order before fitting it.
+    ///   Trajectories breaking the invariant are not fitted and get an
+    ///   `UnsortedEpochsError:` entry in the error map.
+    ///
+    /// Return
+    /// ----------
+    /// * A `dict[int, PyGaussResult]` mapping each `trajectory_id` to its orbit-estimation result.
+    ///
+    /// Notes
+    /// ----------
+    /// * A failing `params.triplet_scorer` only aborts the affected trajectory; its error
+    ///   message is prefixed with `TripletScorerError:` in the error map.
+    /// * Ties between candidates of identical RMS are broken the same way on the
+    ///   sequential and parallel paths (see [`crate::iod_engine`]).
+    /// * A retry restarts from the observations as ingested, with an RNG seeded from
+    ///   `seed`, the trajectory and the attempt index only. `GaussResult.params_index`
+    ///   tells which configuration produced each orbit; a trajectory failing every
+    ///   attempt reports the error of its last one. Each fallback runs in its own
+    ///   `do_parallel()` mode, whatever the mode of `params`.
+    /// * With `initial_guesses`, `GaussResult.iod_path` records the strategy of every
+    ///   orbit (`"guess"`, `"fallback"` or `"gauss"`). Guesses for trajectories absent
+    ///   from the set raise `KeyError`.
+    ///
+    /// See also
+    /// ------------
+    /// * [`TrajectorySet::from_numpy_radians`] – Build set from radian inputs.
     /// * [`TrajectorySet::from_numpy_degrees`] – Build set with degree→radian conversion.
     /// * [`IODParams`] – Initial orbit determination configuration.
     /// * [`GaussResult`] – Result wrapper for the Gauss IOD.
+    #[pyo3(
+        signature = (
+            env, params, seed=None, fallback_params=Vec::new(), initial_guesses=None,
+            validate=false
+        ),
+        text_signature = "($self, env, params, seed=None, fallback_params=(), initial_guesses=None, validate=False)"
+    )]
+    #[allow(clippy::too_many_arguments)]
     pub fn estimate_all_orbits(
         &mut self,
         py: Python<'_>,
         env: &PyOutfit,
         params: &IODParams,
         seed: Option<u64>,
-    ) -> PyResult<(Py<PyDict>, Py<PyDict>)> {
+        fallback_params: Vec<PyRef<'_, IODParams>>,
+        initial_guesses: Option<&Bound<'_, PyDict>>,
+        validate: bool,
+    ) -> PyResult<(Py<PyDict>, Py<PyDict>)> {
+        let fallbacks: Vec<&IODParams> = fallback_params.iter().map(|p| &**p).collect();
+        let guesses = self.initial_guesses(initial_guesses)?;
+        let invalid = if validate {
+            Self::take_unsorted(&mut self.inner)
+        } else {
+            Vec::new()
+        };
+        let results = Self::run_iod(
+            &mut self.inner,
+            py,
+            env,
+            params,
+            &fallbacks,
+            guesses.as_ref(),
+            seed,
+        );
+
+        // Python dicts (bound to current GIL).
+        let ok: Bound<'_, PyDict> = PyDict::new(py);
+        let err: Bound<'_, PyDict> = PyDict::new(py);
+
+        for (obj, res, _) in results {
+            let py_key = object_number_to_py(py, &obj)?; // Bound<'py, PyAny>
+
+            match res {
+                Ok((py_g, rms)) => {
+                    let py_rms = rms.into_pyobject(py)?;
+
+                    let tuple = (py_g, py_rms).into_pyobject(py)?;
+
+                    ok.set_item(py_key, tuple)?;
+                }
+                Err(e) => {
+                    err.set_item(py_key, e.to_string())?;
+                }
+            }
+        }
+        for (obj, obs, e) in invalid {
+            err.set_item(object_number_to_py(py, &obj)?, e.to_string())?;
+            self.inner.insert(obj, obs);
+        }
+
+        Ok((ok.unbind(), err.unbind()))
+    }
+
+    /// Estimate the best orbit for all trajectories and keep the fitted arcs.
+    ///
+    /// Same computation as [`TrajectorySet::estimate_all_orbits`], returned as an
+    /// [`OrbitResults`] container that stores, for each trajectory, the outcome and the
+    /// observations that were fitted (needed e.g. by `OrbitResults.update_with`).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global Outfit state (ephemeris, EOP, error model).
+    /// * `params`: IOD configuration parameters.
+    /// * `seed`: Optional seed for deterministic RNG (u64). If `None`, a random seed is used.
+    /// * `fallback_params`: Relaxed configurations retried on failures, as in
+    ///   [`TrajectorySet::estimate_all_orbits`].
+    /// * `initial_guesses`: Orbits corrected instead of searching triplets, as in
+    ///   [`TrajectorySet::estimate_all_orbits`].
+    ///
+    /// Return
+    /// ----------
+    /// * An `OrbitResults` with one entry per processed trajectory.
+    ///
+    /// See also
+    /// ------------
+    /// * [`OrbitResults`] – Results container.
+    #[pyo3(
+        signature = (env, params, seed=None, fallback_params=Vec::new(), initial_guesses=None),
+        text_signature = "($self, env, params, seed=None, fallback_params=(), initial_guesses=None)"
+    )]
+    pub fn estimate_orbits(
+        &mut self,
+        py: Python<'_>,
+        env: &PyOutfit,
+        params: &IODParams,
+        seed: Option<u64>,
+        fallback_params: Vec<PyRef<'_, IODParams>>,
+        initial_guesses: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<OrbitResults> {
+        let arcs = self.inner.clone();
+        let fallbacks: Vec<&IODParams> = fallback_params.iter().map(|p| &**p).collect();
+        let guesses = self.initial_guesses(initial_guesses)?;
+        let results = Self::run_iod(
+            &mut self.inner,
+            py,
+            env,
+            params,
+            &fallbacks,
+            guesses.as_ref(),
+            seed,
+        );
+        Ok(Self::orbit_results(&arcs, &self.inner, results))
+    }
+
+    /// Run the batch IOD once per parameter set.
+    ///
+    /// Each configuration is fitted on a fresh copy of the trajectories, so that the
+    /// configurations do not influence each other (the in-place uncertainty calibration
+    /// of one run is not seen by the next), and `self` is left untouched.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global Outfit state (ephemeris, EOP, error model).
+    /// * `params_list`: IOD configurations to compare.
+    /// * `seed`: Optional seed (u64). Every configuration is seeded with it, so that
+    ///   entry `k` equals `estimate_orbits(env, params_list[k], seed)` on a copy of this set.
+    ///
+    /// Return
+    /// ----------
+    /// * `(results, table)`: one `OrbitResults` per configuration, aligned with
+    ///   `params_list`, and a comparison table with one dict per configuration
+    ///   (`config`, `n_trajectories`, `n_ok`, `success_rate`, `median_rms`).
+    ///
+    /// Notes
+    /// ----------
+    /// * Configurations run one after the other; within one configuration the
+    ///   trajectories run sequentially or in parallel according to its `do_parallel`.
+    /// * `median_rms` is `NaN` for a configuration without any successful fit.
+    ///
+    /// See also
+    /// ------------
+    /// * [`TrajectorySet::estimate_orbits`] – Single-configuration run.
+    #[pyo3(
+        signature = (env, params_list, seed=None),
+        text_signature = "($self, env, params_list, seed=None)"
+    )]
+    pub fn estimate_orbits_sweep<'py>(
+        &self,
+        py: Python<'py>,
+        env: &PyOutfit,
+        params_list: Vec<PyRef<'py, IODParams>>,
+        seed: Option<u64>,
+    ) -> PyResult<(Vec<OrbitResults>, Bound<'py, PyList>)> {
+        // One OS seed shared by all configurations when none is given.
+        let seed = seed.unwrap_or_else(|| StdRng::from_os_rng().random());
+
+        let mut runs = Vec::with_capacity(params_list.len());
+        let table = PyList::empty(py);
+        for (k, params) in params_list.iter().enumerate() {
+            let mut set = self.inner.clone();
+            let results = Self::run_iod(&mut set, py, env, params, &[], None, Some(seed));
+            let run = Self::orbit_results(&self.inner, &set, results);
+            table.append(run.comparison_row(py, k)?)?;
+            runs.push(run);
+            py.check_signals()?;
+        }
+        Ok((runs, table))
+    }
+
+    /// Fit every trajectory on growing arcs, one per checkpoint epoch.
+    ///
+    /// For each trajectory and each checkpoint, the IOD runs on the observations strictly
+    /// before the checkpoint. Truncated arcs are copied from the stored trajectories one
+    /// at a time, so `self` is left untouched and no intermediate set is built.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env`: Global Outfit state (ephemeris, EOP, error model).
+    /// * `params`: IOD configuration parameters.
+    /// * `checkpoints_mjd`: Checkpoint epochs (MJD, TT), in any order.
+    /// * `seed`: Optional seed for deterministic RNG (u64). If `None`, a random seed is used.
+    ///
+    /// Return
+    /// ----------
+    /// * `(ok, errors)`: two dicts keyed by `(trajectory_id, checkpoint)`. `ok` maps to
+    ///   `(GaussResult, rms)`, `errors` to the error message of the failed fits.
+    ///
+    /// Notes
+    /// ----------
+    /// * Checkpoints leaving fewer than 3 observations are skipped: they appear in neither dict.
+    /// * Fits run sequentially, trajectory by trajectory and checkpoint by checkpoint, with
+    ///   a single RNG. A checkpoint after the last observation of a one-trajectory set
+    ///   therefore reproduces `estimate_all_orbits(env, params, seed)` with the sequential engine.
+    /// * `Ctrl-C` interrupts the loop; the fits completed so far are returned.
+    ///
+    /// See also
+    /// ------------
+    /// * [`TrajectorySet::estimate_all_orbits`] – Full-arc run.
+    #[pyo3(
+        signature = (env, params, checkpoints_mjd, seed=None),
+        text_signature = "($self, env, params, checkpoints_mjd, seed=None)"
+    )]
+    pub fn estimate_arc_growth<'py>(
+        &self,
+        py: Python<'py>,
+        env: &PyOutfit,
+        params: &IODParams,
+        checkpoints_mjd: Vec<f64>,
+        seed: Option<u64>,
+    ) -> PyResult<(Bound<'py, PyDict>, Bound<'py, PyDict>)> {
+        let mut rng: StdRng = match seed {
+            Some(s) => StdRng::seed_from_u64(s),
+            None => StdRng::from_os_rng(),
+        };
+        let should_cancel = || Python::attach(|py| py.check_signals().is_err());
+
+        // The engine is locked by each fit, never while checking signals.
+        let results = py.detach(|| {
+            let mut results = Vec::new();
+            'outer: for (obj, observations) in self.inner.iter() {
+                for &checkpoint in &checkpoints_mjd {
+                    if should_cancel() {
+                        break 'outer;
+                    }
+                    let mut arc: outfit::Observations = observations
+                        .iter()
+                        .filter(|o| o.time < checkpoint)
+                        .cloned()
+                        .collect();
+                    if arc.len() < 3 {
+                        continue;
+                    }
+                    if let Some(gap) = env.coverage_gap(arc.iter().map(|o| o.time)) {
+                        results.push((obj.clone(), checkpoint, Err(IodError::Coverage(gap))));
+                        continue;
+                    }
+                    let res = iod_engine::estimate_best_orbit(&mut arc, env, &mut rng, params)
+                        .and_then(|(g, rms)| {
+                            let g = with_fit_statistics(&arc, &env.outfit_detached(), g)
+                                .with_subsample(&arc, params)
+                                .at_output_epoch(&arc, params.output_epoch)?;
+                            Ok((g, rms))
+                        });
+                    results.push((obj.clone(), checkpoint, res));
+                }
+            }
+            results
+        });
+
+        let ok = PyDict::new(py);
+        let err = PyDict::new(py);
+        for (obj, checkpoint, res) in results {
+            let key = (object_number_to_py(py, &obj)?, checkpoint);
+            match res {
+                Ok((g, rms)) => ok.set_item(key, (g, rms))?,
+                Err(e) => err.set_item(key, e.to_string())?,
+            }
+        }
+        Ok((ok, err))
+    }
+}
+
+/// Iterator over the ids of a [`TrajectorySet`], as returned by `iter(ts)`.
+///
+/// The identifiers are snapshotted on the Rust side when the iteration starts and turned
+/// into Python objects one at a time, so that iterating over a large set does not
+/// allocate every key up front.
+///
+/// Notes
+/// ----------
+/// * Like a `dict`, a set whose size changes during the iteration raises `RuntimeError`
+///   on the next step.
+#[pyclass(module = "py_outfit")]
+pub struct TrajectoryIdIterator {
+    set: Py<TrajectorySet>,
+    keys: std::vec::IntoIter<ObjectNumber>,
+    /// Size of the set when the iteration started.
+    len: usize,
+}
+
+#[pymethods]
+impl TrajectoryIdIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        if self.set.borrow(py).inner.len() != self.len {
+            return Err(PyRuntimeError::new_err(
+                "TrajectorySet changed size during iteration",
+            ));
+        }
+        self.keys
+            .next()
+            .map(|k| object_number_to_py(py, &k))
+            .transpose()
+    }
+
+    /// Number of ids left (`operator.length_hint`).
+    fn __length_hint__(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+impl TrajectorySet {
+    /// Wrap freshly ingested observations after checking their epochs against the
+    /// ephemeris range, sorting them by epoch and applying the `bad_sigma` policy.
+    ///
+    /// Substituted uncertainties and trajectories out of time order are reported as
+    /// fallbacks (see [`crate::strict`]); the latter are sorted silently with `sort`, for
+    /// inputs the caller knows to be unordered.
+    fn checked(
+        py: Python<'_>,
+        mut inner: outfit::TrajectorySet,
+        env: &PyOutfit,
+        handle: &Bound<'_, PyOutfit>,
+        policy: BadSigma,
+        strict: Option<bool>,
+        sort: bool,
+    ) -> PyResult<TrajectorySet> {
+        check_time_range(&inner, env.ephemeris_time_range())?;
+        let epochs = || inner.values().flat_map(|obs| obs.iter().map(|o| o.time));
+        if let Some(gap) = py.detach(|| env.coverage_gap(epochs())) {
+            return Err(gap.into_pyerr(py));
+        }
+        let unsorted = inner
+            .values_mut()
+            .map(ingest::sort_by_epoch)
+            .filter(|&sorted| sorted)
+            .count();
+        if unsorted > 0 && !sort {
+            strict::fallback(
+                py,
+                Check::UnsortedEpochs,
+                strict,
+                &format!("{unsorted} trajectory(ies) were not in time order and have been sorted"),
+            )?;
+        }
+        let patched_sigmas = apply_sigma_policy(&mut inner, &env.outfit(py), policy)?;
+        if patched_sigmas > 0 {
+            strict::fallback(
+                py,
+                Check::PatchedSigma,
+                strict,
+                &format!(
+                    "{patched_sigmas} observation(s) had non-positive or non-finite \
+                     uncertainties substituted by the bad_sigma policy"
+                ),
+            )?;
+        }
+        Ok(TrajectorySet {
+            inner,
+            patched_sigmas,
+            photometry: Arc::default(),
+            duplicates_removed: HashMap::new(),
+            invalid_rows: HashMap::new(),
+            env: Some(Arc::new(handle.clone().unbind())),
+        })
+    }
+
+    /// Ingest the columns of a NumPy loader (`from_numpy_radians`, `from_numpy_degrees`,
+    /// `from_numpy_multi_site`, ...).
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Environment used for the ingestion.
+    /// * `columns` – Rows of the batch.
+    /// * `degrees` – RA/DEC in degrees and uncertainties in arcseconds, converted to
+    ///   radians as the rows are streamed into the set; radians otherwise.
+    /// * `sites` – Observing site of the rows.
+    /// * `options` – Arguments shared by the loaders, see [`Self::from_numpy_degrees`].
+    ///
+    /// Rows are ingested one site at a time; with several sites the trajectories are
+    /// merged afterwards in canonical order, before `dedup` runs.
+    fn from_numpy_columns(
+        py: Python<'_>,
+        env: &mut PyOutfit,
+        handle: &Bound<'_, PyOutfit>,
+        columns: NumpyColumns<'_>,
+        degrees: bool,
+        sites: BatchSites<'_, '_>,
+        options: NumpyOptions<'_>,
+    ) -> PyResult<TrajectorySet> {
+        let NumpyColumns {
+            names: [ra_name, dec_name, error_ra_name, error_dec_name],
+            trajectory_id,
+            ra,
+            dec,
+            error_ra,
+            error_dec,
+            mjd_tt,
+            mag,
+            band,
+        } = columns;
+        let to_rad = if degrees { RADSEC } else { 1.0 };
+        let policy = BadSigma::parse(options.bad_sigma, options.bad_sigma_value, to_rad)?;
+        let workers = ingest::Workers::new(options.parallel, options.n_threads)?;
+
+        // Borrow NumPy memory as Rust slices (lifetime bound to `py`/this function).
+        let (tid, numbering) = trajectory_id.numbered()?;
+        let tid = &*tid;
+        let (ra_col, dec_col) = (ra.as_slice()?, dec.as_slice()?);
+        let t_mjd = mjd_tt.to_mjd_tt_masked(
+            options.epoch_format,
+            options.time_scale,
+            mjd_tt.mask(),
+            options.drop_invalid,
+        )?;
+        let t_mjd = &*t_mjd;
+        let mag = mag.as_ref().map(|mag| mag.as_slice()).transpose()?;
+        let band = band.as_deref();
+
+        // Length checks (clear Python-side errors instead of debug-assert).
+        let n = tid.len();
+        let mut lengths = vec![
+            (ra_name, ra_col.len()),
+            (dec_name, dec_col.len()),
+            ("mjd", t_mjd.len()),
+        ];
+        if let BatchSites::PerRow(codes, _) = &sites {
+            lengths.push(("observer_codes", codes.n_rows()));
+        }
+        lengths.push(("mag", mag.map_or(n, <[f64]>::len)));
+        lengths.push(("band", band.map_or(n, <[Option<String>]>::len)));
+        check_lengths(
+            n,
+            &lengths,
+            &[(error_ra_name, &error_ra), (error_dec_name, &error_dec)],
+        )?;
+
+        let finite = if options.drop_invalid {
+            ingest::invalid_columns(ra_col, dec_col, t_mjd, [&error_ra, &error_dec], policy)?
+        } else {
+            Vec::new()
+        };
+        let masks = [
+            ra.mask(),
+            dec.mask(),
+            mjd_tt.mask(),
+            error_ra.mask(),
+            error_dec.mask(),
+        ];
+        let dropped = DroppedRows::find(tid, trajectory_id.mask(), &masks, &finite);
+        let kept = dropped.as_ref().map(DroppedRows::rows);
+        let (tid, ra, dec, t_mjd) = (
+            take_rows(tid, kept),
+            take_rows(ra_col, kept),
+            take_rows(dec_col, kept),
+            take_rows(t_mjd, kept),
+        );
+        let (tid, ra, dec, t_mjd) = (&*tid, &*ra, &*dec, &*t_mjd);
+        let error_ra = error_ra.take_rows(kept)?;
+        let error_dec = error_dec.take_rows(kept)?;
+        let mag = mag.map(|mag| take_rows(mag, kept));
+        let band = band.map(|band| take_rows(band, kept));
+        let (mag, band) = (mag.as_deref(), band.as_deref());
+        let n = tid.len();
+
+        let groups = sites.groups(&env.outfit(py), env.mpc_sites(), kept, options.topocentric)?;
+        if options.validate {
+            ingest::check_batch(
+                (ra_name, ra),
+                (dec_name, dec),
+                t_mjd,
+                [(error_ra_name, &error_ra), (error_dec_name, &error_dec)],
+                degrees,
+                policy,
+            )?;
+        }
+
+        // Zero-copy rows (borrowed slices) when the batch has a single site.
+        let sigmas = ingest::row_sigmas(&error_ra, &error_dec, n)?;
+        let single_site = groups.len() == 1;
+        let mut out: Option<TrajectorySet> = None;
+        for (site, rows) in groups {
+            let rows = (!single_site).then_some(rows.as_slice());
+            let (ids, ra, dec, mjd) = (
+                take_rows(tid, rows),
+                take_rows(ra, rows),
+                take_rows(dec, rows),
+                take_rows(t_mjd, rows),
+            );
+            let sigmas = sigmas
+                .as_ref()
+                .map(|(ra, dec)| (take_rows(ra, rows), take_rows(dec, rows)));
+            let batch = ingest::Rows {
+                ids: &ids,
+                ra: &ra,
+                dec: &dec,
+                mjd: &mjd,
+                errors: (error_ra.uniform(), error_dec.uniform()),
+                sigmas: sigmas.as_ref().map(|(ra, dec)| (&**ra, &**dec)),
+                degrees,
+            };
+
+            // Heavy work without the GIL (ephemerides, positions, etc.).
+            let inner = py
+                .detach(|| batch.ingest_on(&mut env.inner_mut_detached(), site, workers.as_ref()))
+                .into_py()?;
+            let mut photometry = PhotometryTable::default();
+            let mag = mag.map(|mag| take_rows(mag, rows));
+            let band = band.map(|band| take_rows(band, rows));
+            photometry.record(&inner, &ids, mag.as_deref(), band.as_deref());
+            let inner = numbering.restore(inner);
+            let set = TrajectorySet::checked(
+                py,
+                inner,
+                env,
+                handle,
+                policy,
+                options.strict,
+                options.sort,
+            )?
+            .with_photometry(photometry);
+            match &mut out {
+                Some(out) => out.absorb(set),
+                None => out = Some(set),
+            }
+        }
+        let out = out.unwrap_or_else(|| TrajectorySet::from(outfit::TrajectorySet::default()));
+        let dropped = dropped.map(|d| d.counts(&numbering)).unwrap_or_default();
+        Ok(out.with_invalid_rows(dropped).deduplicated(options.dedup))
+    }
+
+    /// Ingest record batches decoded on the Rust side ([`Self::from_arrow`],
+    /// [`Self::from_parquet`]).
+    ///
+    /// Every batch is ingested and merged before the next one is read. Rows go to
+    /// `observer` or, when `None`, to the site whose MPC code is in the `observer` column.
+    /// String ids (with `flexible_ids`) and 64-bit ids outside the `uint32` range are
+    /// numbered in order of first appearance for the core ingestion, then restored as the
+    /// keys of the set of each batch.
+    fn from_record_batches(
+        py: Python<'_>,
+        env: &mut PyOutfit,
+        handle: &Bound<'_, PyOutfit>,
+        batches: impl Iterator<Item = Result<RecordBatch, ArrowError>>,
+        observer: Option<&Observer>,
+        options: RecordBatchOptions,
+    ) -> PyResult<TrajectorySet> {
+        let RecordBatchOptions {
+            columns,
+            degrees,
+            flexible_ids,
+            errors,
+            policy,
+            strict,
+            topocentric,
+            sort,
+        } = options;
+        let [tid_col, mjd_col, ra_col, dec_col, site_col, sigma_ra_col, sigma_dec_col] = columns;
+        let (err_ra, err_dec) = errors.unwrap_or((f64::NAN, f64::NAN));
+        let mut sites: HashMap<String, Arc<outfit::Observer>> = HashMap::new();
+        let mut out = TrajectorySet::from(outfit::TrajectorySet::default());
+
+        for batch in batches {
+            let batch = batch.map_err(arrow_ffi::arrow_err)?;
+            let mut numbering = IdNumbering::default();
+            let tid = if flexible_ids && arrow_ffi::is_string(&batch, &tid_col) {
+                let ids = arrow_ffi::str_column(&batch, &tid_col)?
+                    .into_iter()
+                    .map(|name| numbering.number(name))
+                    .collect();
+                Cow::Owned(ids)
+            } else {
+                arrow_ffi::id_column(&batch, &tid_col, &mut numbering)?
+            };
+            let mjd = arrow_ffi::f64_column(&batch, &mjd_col)?;
+            let ra = arrow_ffi::f64_column(&batch, &ra_col)?;
+            let dec = arrow_ffi::f64_column(&batch, &dec_col)?;
+            // Per-row uncertainties, when no uniform ones are given.
+            let has = |name: &str| batch.column_by_name(name).is_some();
+            let sigmas = match errors {
+                None if has(&sigma_ra_col) || has(&sigma_dec_col) => Some((
+                    arrow_ffi::f64_column(&batch, &sigma_ra_col)?,
+                    arrow_ffi::f64_column(&batch, &sigma_dec_col)?,
+                )),
+                _ => None,
+            };
+
+            // Rows of the batch grouped by observing site (all of them for `observer`).
+            let groups: Vec<(Arc<outfit::Observer>, Option<Vec<usize>>)> = match observer {
+                Some(obs) => vec![(ingest::observing_site(&obs.inner, topocentric)?, None)],
+                None => arrow_ffi::string_groups(&batch, &site_col)?
+                    .into_iter()
+                    .map(|(code, rows)| {
+                        let site = sites
+                            .entry(code)
+                            .or_insert_with_key(|c| env.outfit(py).get_observer_from_mpc_code(c));
+                        Ok((ingest::observing_site(site, topocentric)?, Some(rows)))
+                    })
+                    .collect::<PyResult<_>>()?,
+            };
+
+            for (site, rows) in groups {
+                let rows = rows.as_deref();
+                let (ids, ra, dec, mjd) = (
+                    take_rows(&tid, rows),
+                    take_rows(ra, rows),
+                    take_rows(dec, rows),
+                    take_rows(mjd, rows),
+                );
+                let sigmas = sigmas.map(|(ra, dec)| (take_rows(ra, rows), take_rows(dec, rows)));
+                let batch = ingest::Rows {
+                    ids: &ids,
+                    ra: &ra,
+                    dec: &dec,
+                    mjd: &mjd,
+                    errors: (err_ra, err_dec),
+                    sigmas: sigmas.as_ref().map(|(ra, dec)| (&**ra, &**dec)),
+                    degrees,
+                };
+                let inner = py
+                    .detach(|| batch.ingest(&mut env.inner_mut_detached(), site))
+                    .into_py()?;
+                let inner = numbering.restore(inner);
+                out.absorb(TrajectorySet::checked(
+                    py, inner, env, handle, policy, strict, sort,
+                )?);
+            }
+            py.check_signals()?;
+        }
+        Ok(out)
+    }
+
+    /// The set with `photometry` as its photometry table.
+    fn with_photometry(mut self, photometry: PhotometryTable) -> Self {
+        self.photometry = Arc::new(photometry);
+        self
+    }
+
+    /// The set without its duplicate observations when `dedup` asks for it.
+    fn deduplicated(mut self, dedup: Dedup) -> Self {
+        if let Some(tol) = dedup.0 {
+            let removed = dedup::dedup_set(&mut self.inner, &tol);
+            self.count_duplicates(removed);
+        }
+        self
+    }
+
+    /// The set with `dropped` added to the rows dropped at ingestion (`drop_invalid=`).
+    fn with_invalid_rows(mut self, dropped: HashMap<Option<ObjectNumber>, usize>) -> Self {
+        self.count_invalid_rows(dropped);
+        self
+    }
+
+    /// Add `dropped` to the per-trajectory counts of `invalid_rows_dropped`.
+    fn count_invalid_rows(&mut self, dropped: HashMap<Option<ObjectNumber>, usize>) {
+        for (key, n) in dropped {
+            *self.invalid_rows.entry(key).or_default() += n;
+        }
+    }
+
+    /// Add `removed` to the per-trajectory counts of `duplicates_removed`.
+    /// Observations shared by several trajectories (see [`dedup::shared_observations`]),
+    /// with the sorted ids their trajectory indices refer to.
+    fn shared_pairs(
+        &self,
+        tol: &Tolerance,
+    ) -> (Vec<&ObjectNumber>, Vec<(dedup::ObsIndex, dedup::ObsIndex)>) {
+        let mut keys: Vec<&ObjectNumber> = self.inner.keys().collect();
+        keys.sort();
+        let trajectories: Vec<&outfit::Observations> =
+            keys.iter().map(|k| &self.inner[*k]).collect();
+        let pairs = dedup::shared_observations(&trajectories, tol);
+        (keys, pairs)
+    }
+
+    fn count_duplicates(&mut self, removed: HashMap<ObjectNumber, usize>) {
+        for (key, n) in removed {
+            *self.duplicates_removed.entry(key).or_default() += n;
+        }
+    }
+
+    /// Rename the trajectories `renames` (`(old, new)`, every `old` in the set) after
+    /// checking that the new ids are unique, with the per-trajectory counters.
+    fn rename(
+        &mut self,
+        py: Python<'_>,
+        renames: Vec<(ObjectNumber, ObjectNumber)>,
+    ) -> PyResult<()> {
+        let mut renamed = HashSet::with_capacity(renames.len());
+        for (old, _) in &renames {
+            if !renamed.insert(old) {
+                return Err(PyValueError::new_err(format!(
+                    "trajectory {} is renamed twice",
+                    key_repr(py, old)?
+                )));
+            }
+        }
+        let mut targets = HashSet::with_capacity(renames.len());
+        for (_, new) in &renames {
+            if !targets.insert(new) {
+                return Err(PyValueError::new_err(format!(
+                    "several trajectories renamed to {}",
+                    key_repr(py, new)?
+                )));
+            }
+            if self.inner.contains_key(new) && !renamed.contains(new) {
+                return Err(PyValueError::new_err(format!(
+                    "cannot rename to {}: the id is held by a trajectory that is not renamed",
+                    key_repr(py, new)?
+                )));
+            }
+        }
+
+        let moved: Vec<_> = renames
+            .into_iter()
+            .map(|(old, new)| {
+                let obs = self.inner.remove(&old).expect("renamed ids are in the set");
+                let duplicates = self.duplicates_removed.remove(&old);
+                let invalid = self.invalid_rows.remove(&Some(old));
+                (new, obs, duplicates, invalid)
+            })
+            .collect();
+        for (new, obs, duplicates, invalid) in moved {
+            if let Some(n) = duplicates {
+                self.duplicates_removed.insert(new.clone(), n);
+            }
+            if let Some(n) = invalid {
+                self.invalid_rows.insert(Some(new.clone()), n);
+            }
+            self.inner.insert(new, obs);
+        }
+        Ok(())
+    }
+
+    /// Earliest and latest epochs of the set, `None` without observations.
+    fn epoch_range(&self) -> Option<(f64, f64)> {
+        self.inner
+            .values()
+            .flatten()
+            .fold(None, |range, o| match range {
+                None => Some((o.time, o.time)),
+                Some((min, max)) => Some((o.time.min(min), o.time.max(max))),
+            })
+    }
+
+    /// Copy of the trajectories `keys` (all present in the set), see [`Self::derived`].
+    fn selected(&self, keys: Vec<ObjectNumber>) -> TrajectorySet {
+        self.derived(
+            keys.into_iter()
+                .map(|key| {
+                    let obs = self.inner[&key].clone();
+                    (key, obs)
+                })
+                .collect(),
+        )
+    }
+
+    /// Borrow the environment recorded at ingestion (see [`TrajectorySet::env`]).
+    fn recorded_env<'py>(&self, py: Python<'py>) -> PyResult<PyRef<'py, PyOutfit>> {
+        let env = self
+            .env
+            .as_ref()
+            .ok_or_else(|| PyValueError::new_err("this set records no environment; pass env="))?;
+        Ok(env.bind(py).try_borrow()?)
+    }
+
+    /// Set holding `inner`, trajectories taken from this set (`subset`, `sample`,
+    /// `filter_by_time`, ...), with their counters and the shared photometry.
+    fn derived(&self, inner: outfit::TrajectorySet) -> TrajectorySet {
+        let mut set = TrajectorySet::from(inner);
+        set.photometry = self.photometry.clone();
+        set.env = self.env.clone();
+        for key in set.inner.keys() {
+            if let Some(&n) = self.duplicates_removed.get(key) {
+                set.duplicates_removed.insert(key.clone(), n);
+            }
+            if let Some(&n) = self.invalid_rows.get(&Some(key.clone())) {
+                set.invalid_rows.insert(Some(key.clone()), n);
+            }
+        }
+        set
+    }
+
+    /// Cut every trajectory (in time order) at the index given by `at`, into two sets
+    /// holding the non-empty heads and tails (`split_at`, `split_fraction`).
+    fn split_with(
+        &self,
+        at: impl Fn(&outfit::Observations) -> usize,
+    ) -> (TrajectorySet, TrajectorySet) {
+        let mut heads = outfit::TrajectorySet::default();
+        let mut tails = outfit::TrajectorySet::default();
+        for (key, obs) in &self.inner {
+            let (head, tail) = obs.split_at(at(obs).min(obs.len()));
+            if !head.is_empty() {
+                heads.insert(key.clone(), head.to_vec().into());
+            }
+            if !tail.is_empty() {
+                tails.insert(key.clone(), tail.to_vec().into());
+            }
+        }
+        (self.derived(heads), self.derived(tails))
+    }
+
+    /// Append the trajectories of `other` (observations of shared keys are merged in
+    /// canonical order).
+    fn absorb(&mut self, other: TrajectorySet) {
+        for (key, obs) in other.inner {
+            match self.inner.entry(key) {
+                Entry::Vacant(slot) => {
+                    ingest::canonicalize(slot.insert(obs));
+                }
+                Entry::Occupied(mut slot) => {
+                    let merged = slot.get_mut();
+                    merged.extend(obs);
+                    ingest::canonicalize(merged);
+                }
+            }
+        }
+        self.patched_sigmas += other.patched_sigmas;
+        self.count_duplicates(other.duplicates_removed);
+        self.count_invalid_rows(other.invalid_rows);
+        if self.env.is_none() {
+            self.env = other.env;
+        }
+        if self.photometry.is_empty() {
+            self.photometry = other.photometry;
+        } else if !other.photometry.is_empty() {
+            Arc::make_mut(&mut self.photometry).merge(&other.photometry);
+        }
+    }
+
+    /// Remove the trajectories out of time order from `set`, with the error reported for
+    /// each (`validate=True`).
+    fn take_unsorted(
+        set: &mut outfit::TrajectorySet,
+    ) -> Vec<(ObjectNumber, outfit::Observations, IodError)> {
+        let unsorted: Vec<(ObjectNumber, IodError)> = set
+            .iter()
+            .filter_map(|(obj, obs)| {
+                iod_engine::check_time_order(obs)
+                    .err()
+                    .map(|e| (obj.clone(), e))
+            })
+            .collect();
+        unsorted
+            .into_iter()
+            .filter_map(|(obj, e)| set.remove_entry(&obj).map(|(obj, obs)| (obj, obs, e)))
+            .collect()
+    }
+
+    /// Initial guesses of `estimate_all_orbits`, on the core's TT axis.
+    fn initial_guesses(
+        &self,
+        guesses: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Option<HashMap<ObjectNumber, outfit::GaussResult>>> {
+        let Some(guesses) = guesses else {
+            return Ok(None);
+        };
+        let mut out = HashMap::with_capacity(guesses.len());
+        for (key, value) in guesses.iter() {
+            let obj = py_to_object_number(&key)?;
+            if !self.inner.contains_key(&obj) {
+                return Err(PyKeyError::new_err(format!(
+                    "initial guess for unknown trajectory {key}"
+                )));
+            }
+            let elements: KeplerianElements = value.extract().map_err(|_| {
+                PyTypeError::new_err(format!(
+                    "initial guess for trajectory {key} must be a KeplerianElements"
+                ))
+            })?;
+            let orbit = outfit::GaussResult::PrelimOrbit(outfit::OrbitalElements::Keplerian(
+                elements.inner,
+            ));
+            out.insert(obj, time_scales::orbit_to_tt(&orbit));
+        }
+        Ok(Some(out))
+    }
+
+    /// Pair batch outcomes with the arcs they were fitted on (`arcs` as provided, `fitted`
+    /// as left by the run, with calibrated uncertainties).
+    fn orbit_results(
+        arcs: &outfit::TrajectorySet,
+        fitted: &outfit::TrajectorySet,
+        results: Vec<RunEntry>,
+    ) -> OrbitResults {
+        let entries = results
+            .into_iter()
+            .map(|(obj, res, diagnostics)| {
+                let observations = arcs.get(&obj).cloned().unwrap_or_default();
+                let weights = res
+                    .is_ok()
+                    .then(|| fit_weights(fitted.get(&obj).map_or(&[], |o| o.as_slice())));
+                let error_kind = res.as_ref().err().map(IodError::kind);
+                let outcome = res.map_err(|e| e.to_string());
+                (
+                    obj,
+                    OrbitEntry {
+                        outcome,
+                        error_kind,
+                        observations,
+                        costs: diagnostics.costs,
+                        rejections: diagnostics.rejections,
+                        weights,
+                    },
+                )
+            })
+            .collect();
+        OrbitResults { entries }
+    }
+
+    /// Run the batch IOD on `set` (sequential or parallel, depending on `params`).
+    ///
+    /// The RNG is seeded from `seed` when provided, from the OS otherwise. The
+    /// sequential path polls Python signals so that `Ctrl-C` interrupts the loop.
+    /// Successful orbits come back wrapped, with their fit statistics (and costs, when
+    /// `params.collect_costs` is set) attached.
+    ///
+    /// Trajectories failing under `params` are retried with each of `fallbacks` in
+    /// turn (see [`iod_engine::retry_with_fallbacks`]); with fallbacks, every orbit
+    /// records the index of the parameter set that produced it.
+    ///
+    /// Trajectories of `guesses` are first fitted from their guess
+    /// ([`iod_engine::correct_from_guess`]); only those whose correction diverged go
+    /// through the Gauss IOD. With guesses, every orbit records its [`IodPath`].
+    fn run_iod(
+        set: &mut outfit::TrajectorySet,
+        py: Python<'_>,
+        env: &PyOutfit,
+        params: &IODParams,
+        fallbacks: &[&IODParams],
+        guesses: Option<&HashMap<ObjectNumber, outfit::GaussResult>>,
+        seed: Option<u64>,
+    ) -> Vec<RunEntry> {
         // Build RNG (deterministic if a seed is provided).
-        let mut rng: StdRng = match seed {
-            Some(s) => StdRng::seed_from_u64(s),
-            None => StdRng::from_os_rng(),
-        };
+        let seed = seed.unwrap_or_else(|| StdRng::from_os_rng().random());
+        let mut rng = StdRng::seed_from_u64(seed);
 
         // Cancellation callback: returns true when a KeyboardInterrupt occurred.
         // We briefly acquire the GIL to check Python's signal state.
         let mut should_cancel = || Python::attach(|py| py.check_signals().is_err());
 
-        // Run the heavy computation without the GIL.
-        let results: FullOrbitResult = py.detach(|| {
-            if params.do_parallel() {
-                self.inner.estimate_all_orbits_in_batches_parallel(
-                    &env.inner,
-                    &mut rng,
-                    &params.inner,
-                )
+        // Run the heavy computation without the GIL. The engine is locked per step and
+        // released by the IOD before it attaches to Python (scorer, signal checks).
+        py.detach(|| {
+            // Trajectories failing the ephemeris coverage check are set aside.
+            let uncovered: Vec<(ObjectNumber, CoverageGap)> = set
+                .iter()
+                .filter_map(|(obj, obs)| {
+                    env.coverage_gap(obs.iter().map(|o| o.time))
+                        .map(|gap| (obj.clone(), gap))
+                })
+                .collect();
+            let set_aside: Vec<(ObjectNumber, outfit::Observations)> = uncovered
+                .iter()
+                .filter_map(|(obj, _)| set.remove_entry(obj))
+                .collect();
+
+            // Trajectories with a guess: correction over the whole arc, no triplet search.
+            let mut diverged: HashSet<ObjectNumber> = HashSet::new();
+            let guessed: Vec<(
+                ObjectNumber,
+                outfit::Observations,
+                (outfit::GaussResult, f64),
+            )> = match guesses {
+                Some(guesses) => {
+                    let outfit = env.outfit_detached();
+                    let arcs: Vec<(ObjectNumber, outfit::Observations)> = guesses
+                        .keys()
+                        .filter_map(|obj| set.remove_entry(obj))
+                        .collect();
+                    let correct = |(obj, obs): (ObjectNumber, outfit::Observations)| {
+                        let mut arc = obs.clone();
+                        let fit = iod_engine::correct_from_guess(
+                            &mut arc,
+                            &outfit,
+                            params,
+                            &guesses[&obj],
+                        );
+                        (obj, obs, arc, fit)
+                    };
+                    let outcomes: Vec<_> = if params.do_parallel() {
+                        arcs.into_par_iter().map(correct).collect()
+                    } else {
+                        arcs.into_iter().map(correct).collect()
+                    };
+                    outcomes
+                        .into_iter()
+                        .filter_map(|(obj, obs, arc, fit)| match fit {
+                            Some(fit) => Some((obj, arc, fit)),
+                            None => {
+                                // Diverged: the Gauss IOD runs on the arc as ingested.
+                                set.insert(obj.clone(), obs);
+                                diverged.insert(obj);
+                                None
+                            }
+                        })
+                        .collect()
+                }
+                None => Vec::new(),
+            };
+
+            // Retries restart from the observations as ingested.
+            let originals = (!fallbacks.is_empty()).then(|| set.clone());
+
+            let results: Vec<(ObjectNumber, IodOutcome, Diagnostics)> = if params.do_parallel() {
+                iod_engine::estimate_all_orbits_parallel(set, env, &mut rng, params)
             } else {
-                self.inner.estimate_all_orbits_with_cancel(
-                    &env.inner,
+                iod_engine::estimate_all_orbits_sequential(
+                    set,
+                    env,
                     &mut rng,
-                    &params.inner,
+                    params,
                     &mut should_cancel,
                 )
-            }
-        });
+            };
 
-        // Python dicts (bound to current GIL).
-        let ok: Bound<'_, PyDict> = PyDict::new(py);
-        let err: Bound<'_, PyDict> = PyDict::new(py);
+            let results = match &originals {
+                Some(originals) => {
+                    iod_engine::retry_with_fallbacks(set, originals, results, env, seed, fallbacks)
+                }
+                None => results
+                    .into_iter()
+                    .map(|(obj, res, diagnostics)| (obj, res, diagnostics, 0))
+                    .collect(),
+            };
 
-        for (obj, res) in results {
-            let py_key = object_number_to_py(py, &obj)?; // Bound<'py, PyAny>
+            // Statistics over the arcs as fitted (uncertainties after batch correction).
+            let outfit = env.outfit_detached();
+            let fitted: Vec<RunEntry> = results
+                .into_iter()
+                .map(|(obj, res, diagnostics, attempt)| {
+                    let used = if attempt == 0 {
+                        params
+                    } else {
+                        fallbacks[attempt - 1]
+                    };
+                    let res = res.and_then(|(g, rms)| {
+                        let arc = set.get(&obj).map(|o| o.as_slice()).unwrap_or(&[]);
+                        let mut g = with_fit_statistics(arc, &outfit, g)
+                            .with_subsample(arc, used)
+                            .at_output_epoch(arc, used.output_epoch)?;
+                        g.costs = diagnostics.costs;
+                        g.params_index = originals.as_ref().map(|_| attempt);
+                        g.iod_path = guesses.map(|_| {
+                            if diverged.contains(&obj) {
+                                IodPath::Fallback
+                            } else {
+                                IodPath::Gauss
+                            }
+                        });
+                        Ok((g, rms))
+                    });
+                    (obj, res, diagnostics)
+                })
+                .collect();
+            let corrected: Vec<RunEntry> = guessed
+                .into_iter()
+                .map(|(obj, arc, (g, rms))| {
+                    let res = with_fit_statistics(&arc, &outfit, g)
+                        .at_output_epoch(&arc, params.output_epoch)
+                        .map(|mut g| {
+                            g.params_index = originals.as_ref().map(|_| 0);
+                            g.iod_path = Some(IodPath::Guess);
+                            (g, rms)
+                        })
+                        .map_err(IodError::from);
+                    set.insert(obj.clone(), arc);
+                    (obj, res, Diagnostics::default())
+                })
+                .collect();
 
-            match res {
-                Ok((g, rms)) => {
-                    let py_g: GaussResult = g.into();
-                    let py_rms = rms.into_pyobject(py)?;
+            set.extend(set_aside);
+            fitted
+                .into_iter()
+                .chain(corrected)
+                .chain(
+                    uncovered.into_iter().map(|(obj, gap)| {
+                        (obj, Err(IodError::Coverage(gap)), Diagnostics::default())
+                    }),
+                )
+                .collect()
+        })
+    }
+}
 
-                    let tuple = (py_g, py_rms).into_pyobject(py)?;
+/// Arc of one trajectory, see `TrajectorySet.arc_statistics`.
+struct ArcStats {
+    first: f64,
+    last: f64,
+    n_obs: usize,
+    n_nights: usize,
+    n_observers: usize,
+}
 
-                    ok.set_item(py_key, tuple)?;
-                }
-                Err(e) => {
-                    err.set_item(py_key, e.to_string())?;
-                }
-            }
+impl ArcStats {
+    fn of(obs: &outfit::Observations, env: &outfit::Outfit) -> Self {
+        let mut nights = HashSet::new();
+        let mut sites = HashSet::new();
+        let (mut first, mut last) = (f64::NAN, f64::NAN);
+        for o in obs {
+            first = first.min(o.time);
+            last = last.max(o.time);
+            let site = o.get_observer(env);
+            // Local mean solar date, changing at local noon (longitude in [-180, 180)).
+            let longitude = (site.longitude.into_inner() + 180.0).rem_euclid(360.0) - 180.0;
+            let local = o.time + longitude / 360.0 - 0.5;
+            nights.insert(local.floor() as i64);
+            sites.insert(std::ptr::from_ref(site));
         }
+        Self {
+            first,
+            last,
+            n_obs: obs.len(),
+            n_nights: nights.len(),
+            n_observers: sites.len(),
+        }
+    }
+}
 
-        Ok((ok.unbind(), err.unbind()))
+/// `repr` of the Python form of `key`, for messages.
+fn key_repr(py: Python<'_>, key: &ObjectNumber) -> PyResult<String> {
+    Ok(object_number_to_py(py, key)?.repr()?.to_string())
+}
+
+/// Ids `keys` as a column: a `uint64` array when every id is an integer (64-bit ids
+/// included), the list of their Python forms otherwise.
+fn id_column<'py, 'a>(
+    py: Python<'py>,
+    keys: impl Iterator<Item = &'a ObjectNumber> + Clone,
+) -> PyResult<Bound<'py, PyAny>> {
+    let ints: Option<Vec<u64>> = keys
+        .clone()
+        .map(|k| match k {
+            ObjectNumber::Int(id) => Some(u64::from(*id)),
+            ObjectNumber::String(s) => wide_id(s),
+        })
+        .collect();
+    match ints {
+        Some(ints) => Ok(PyArray1::from_vec(py, ints).into_any()),
+        None => {
+            let out = PyList::empty(py);
+            for k in keys {
+                out.append(object_number_to_py(py, k)?)?;
+            }
+            Ok(out.into_any())
+        }
     }
 }
 
-fn object_number_to_py<'py>(py: Python<'py>, key: &ObjectNumber) -> PyResult<Bound<'py, PyAny>> {
+/// Python form of a key: `int` for integer ids (64-bit ones included, see [`wide_id`]),
+/// `str` otherwise.
+pub(crate) fn object_number_to_py<'py>(
+    py: Python<'py>,
+    key: &ObjectNumber,
+) -> PyResult<Bound<'py, PyAny>> {
     match key {
         ObjectNumber::Int(n) => {
             let b: Bound<'py, PyInt> = (*n as u64).into_pyobject(py)?;
             Ok(b.into_any()) // upcast PyInt -> PyAny
         }
-        ObjectNumber::String(s) => {
-            let b: Bound<'py, PyString> = s.as_str().into_pyobject(py)?;
-            Ok(b.into_any())
+        ObjectNumber::String(s) => match wide_id(s) {
+            Some(id) => {
+                let b: Bound<'py, PyInt> = id.into_pyobject(py)?;
+                Ok(b.into_any())
+            }
+            None => {
+                let b: Bound<'py, PyString> = s.as_str().into_pyobject(py)?;
+                Ok(b.into_any())
+            }
+        },
+    }
+}
+
+/// `{id: count}` dict of per-trajectory counts, keys sorted.
+fn counts_to_dict<'py>(
+    py: Python<'py>,
+    counts: &HashMap<ObjectNumber, usize>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut keys: Vec<&ObjectNumber> = counts.keys().collect();
+    keys.sort();
+    let out = PyDict::new(py);
+    for key in keys {
+        out.set_item(object_number_to_py(py, key)?, counts[key])?;
+    }
+    Ok(out)
+}
+
+/// Suffix of the `n`-th segment produced by `split_on_gaps`: `a`…`z`, `aa`, `ab`, …
+fn gap_suffix(mut n: usize) -> String {
+    let mut out = Vec::new();
+    loop {
+        out.push(b'a' + (n % 26) as u8);
+        if n < 26 {
+            break;
         }
+        n = n / 26 - 1;
     }
+    out.reverse();
+    String::from_utf8(out).expect("ASCII suffix")
 }
 
 // -----------------------------------------------------------------------------
 // Helpers: Python key -> ObjectNumber
 // -----------------------------------------------------------------------------
 
-fn py_to_object_number(key: &Bound<'_, PyAny>) -> PyResult<ObjectNumber> {
+pub(crate) fn py_to_object_number(key: &Bound<'_, PyAny>) -> PyResult<ObjectNumber> {
     if let Ok(i) = key.extract::<u64>() {
-        let v = u32::try_from(i).map_err(|_| {
-            PyValueError::new_err(format!("Integer key too large for ObjectNumber::Int: {i}"))
-        })?;
-        return Ok(ObjectNumber::Int(v));
+        return Ok(object_number_from_u64(i));
     }
     // sinon str
     if let Ok(s) = key.extract::<String>() {
@@ -528,9 +5495,973 @@ fn py_to_object_number(key: &Bound<'_, PyAny>) -> PyResult<ObjectNumber> {
     ))
 }
 
+/// Key looked up by `ts[key]` / `key in ts`: `None` for an integer no trajectory can have
+/// (negative, or wider than 64 bits), so that it is reported as missing. NumPy integer
+/// scalars count as integers.
+fn lookup_key(key: &Bound<'_, PyAny>) -> PyResult<Option<ObjectNumber>> {
+    let integer = key.is_instance_of::<PyInt>() || key.extract::<i64>().is_ok();
+    if integer && key.extract::<u64>().is_err() {
+        return Ok(None);
+    }
+    py_to_object_number(key).map(Some)
+}
+
+/// Key of the integer id `id`.
+///
+/// `ObjectNumber::Int` holds a `u32`: larger ids (64-bit survey ids such as ZTF / Rubin
+/// `diaObjectId`) are stored as their decimal string and reported back to Python as
+/// `int` ([`wide_id`]).
+pub(crate) fn object_number_from_u64(id: u64) -> ObjectNumber {
+    match u32::try_from(id) {
+        Ok(id) => ObjectNumber::Int(id),
+        Err(_) => ObjectNumber::String(id.to_string()),
+    }
+}
+
+/// Integer id above the `u32` range stored in a string key by
+/// [`object_number_from_u64`]: canonical decimal digits (no sign, no leading zero).
+pub(crate) fn wide_id(key: &str) -> Option<u64> {
+    if key.starts_with('0') || !key.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    key.parse::<u64>()
+        .ok()
+        .filter(|&id| id > u64::from(u32::MAX))
+}
+
+// -----------------------------------------------------------------------------
+// Helpers: Arrow record batches
+// -----------------------------------------------------------------------------
+
+/// Column names of the `tid`, `mjd`, `ra`, `dec`, `observer`, `sigma_ra` and
+/// `sigma_dec` fields of an Arrow stream.
+fn arrow_column_names(columns: Option<&Bound<'_, PyDict>>) -> PyResult<[String; 7]> {
+    let mut names = [
+        "tid",
+        "mjd",
+        "ra",
+        "dec",
+        "observer",
+        "sigma_ra",
+        "sigma_dec",
+    ]
+    .map(String::from);
+    if let Some(columns) = columns {
+        for (field, column) in columns.iter() {
+            let field: String = field.extract()?;
+            let slot = names.iter().position(|n| *n == field).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "unknown column field {field:?} (expected 'tid', 'mjd', 'ra', 'dec', \
+                         'observer', 'sigma_ra' or 'sigma_dec')"
+                ))
+            })?;
+            names[slot] = column.extract()?;
+        }
+    }
+    Ok(names)
+}
+
+/// Reading options of `from_csv`.
+///
+/// Arguments
+/// -----------------
+/// * `columns` – `{field: name or index}` overrides of the default column names.
+/// * `delimiter` – Field delimiter, one ASCII character.
+/// * `header` – Whether the first row names the columns.
+/// * `read_sigmas` – Read the per-row uncertainty columns (no uniform uncertainties).
+fn csv_options(
+    columns: Option<&Bound<'_, PyDict>>,
+    delimiter: &str,
+    header: bool,
+    read_sigmas: bool,
+) -> PyResult<csv_reader::Options> {
+    const FIELDS: [&str; 6] = ["id", "time", "ra", "dec", "sigma_ra", "sigma_dec"];
+    const DEFAULTS: [&str; 6] = [
+        "trajectory_id",
+        "mjd_tt",
+        "ra",
+        "dec",
+        "sigma_ra",
+        "sigma_dec",
+    ];
+    let delimiter = match delimiter.as_bytes() {
+        [b] if b.is_ascii() && !matches!(b, b'"' | b'#' | b'\n' | b'\r') => *b,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "delimiter must be a single ASCII character other than '\"', '#' and line \
+                 breaks, got {delimiter:?}"
+            )))
+        }
+    };
+    let mut refs = DEFAULTS.map(|name| csv_reader::ColumnRef::Name(name.to_string()));
+    let mut mapped = [false; 6];
+    if let Some(columns) = columns {
+        for (field, column) in columns.iter() {
+            let field: String = field.extract()?;
+            let slot = FIELDS.iter().position(|f| *f == field).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "unknown column field {field:?} (expected 'id', 'time', 'ra', 'dec', \
+                     'sigma_ra' or 'sigma_dec')"
+                ))
+            })?;
+            refs[slot] = if let Ok(index) = column.extract::<usize>() {
+                csv_reader::ColumnRef::Index(index)
+            } else if let Ok(name) = column.extract::<String>() {
+                csv_reader::ColumnRef::Name(name)
+            } else {
+                return Err(PyTypeError::new_err(format!(
+                    "columns[{field:?}] must be a column name or a 0-based index, got {column}"
+                )));
+            };
+            mapped[slot] = true;
+        }
+    }
+    let [id, time, ra, dec, sigma_ra, sigma_dec] = refs;
+    Ok(csv_reader::Options {
+        delimiter,
+        header,
+        columns: [id, time, ra, dec],
+        sigmas: read_sigmas.then(|| ([sigma_ra, sigma_dec], mapped[4] || mapped[5])),
+    })
+}
+
+/// Argument names of the columns of `TrajectorySet.from_numpy_degrees`, see
+/// [`NumpyColumns`].
+const DEGREE_COLUMNS: [&str; 4] = ["ra_deg", "dec_deg", "error_ra_arcsec", "error_dec_arcsec"];
+
+/// Columns of a NumPy batch, as given to the `from_numpy_*` loaders.
+///
+/// Fields
+/// -----------------
+/// * `names` – Argument names of `ra`, `dec`, `error_ra` and `error_dec`, which the
+///   length and validation errors report.
+/// * `trajectory_id`, `ra`, `dec`, `error_ra`, `error_dec`, `mjd_tt`, `mag`, `band` –
+///   Columns of the rows, see `TrajectorySet.from_numpy_degrees`.
+struct NumpyColumns<'py> {
+    names: [&'static str; 4],
+    trajectory_id: Masked<TrajectoryIds<'py>>,
+    ra: Masked<Reals<'py>>,
+    dec: Masked<Reals<'py>>,
+    error_ra: Masked<Sigma<'py>>,
+    error_dec: Masked<Sigma<'py>>,
+    mjd_tt: Masked<Epochs<'py>>,
+    mag: Option<Masked<Reals<'py>>>,
+    band: Option<Vec<Option<String>>>,
+}
+
+/// Observing sites of the rows of a NumPy batch.
+enum BatchSites<'a, 'py> {
+    /// One observer for every row (`from_numpy_radians`, `from_numpy_degrees`).
+    Single(&'a Observer),
+    /// One site per row (`from_numpy_multi_site`): `observer_codes`, and the `observers`
+    /// indexed by integer codes.
+    PerRow(SiteRows, Option<Vec<PyRef<'py, Observer>>>),
+}
+
+impl BatchSites<'_, '_> {
+    /// Rows kept by [`DroppedRows`] grouped by site, in order of first appearance; a
+    /// single group covers every row.
+    ///
+    /// Arguments
+    /// -----------------
+    /// * `env` – Environment resolving the MPC codes.
+    /// * `mpc_sites` – MPC sites of `env`.
+    /// * `kept` – Rows kept out of the batch, all of them for `None`.
+    /// * `topocentric` – `false` for astrometry already reduced to the geocentre, see
+    ///   [`ingest::observing_site`].
+    fn groups(
+        self,
+        env: &outfit::Outfit,
+        mpc_sites: &MpcSites,
+        kept: Option<&[usize]>,
+        topocentric: bool,
+    ) -> PyResult<Vec<(Arc<outfit::Observer>, Vec<usize>)>> {
+        match self {
+            BatchSites::Single(observer) => Ok(vec![(
+                ingest::observing_site(&observer.inner, topocentric)?,
+                Vec::new(),
+            )]),
+            BatchSites::PerRow(codes, observers) => {
+                let observers: Option<Vec<Arc<outfit::Observer>>> =
+                    observers.map(|list| list.iter().map(|o| o.inner.clone()).collect());
+                codes
+                    .take_rows(kept)
+                    .groups(env, mpc_sites, observers.as_deref(), topocentric)
+            }
+        }
+    }
+}
+
+/// Ingestion options shared by the NumPy loaders, see `TrajectorySet.from_numpy_degrees`.
+///
+/// `bad_sigma_value` is in the unit of the uncertainties of the batch (arcseconds for
+/// degrees, radians otherwise).
+struct NumpyOptions<'a> {
+    bad_sigma: &'a str,
+    bad_sigma_value: Option<f64>,
+    strict: Option<bool>,
+    topocentric: bool,
+    time_scale: Option<&'a str>,
+    epoch_format: &'a str,
+    dedup: Dedup,
+    validate: bool,
+    sort: bool,
+    drop_invalid: bool,
+    parallel: bool,
+    n_threads: Option<usize>,
+}
+
+/// Ingestion options of the record batch loaders (`from_arrow`, `from_parquet`).
+///
+/// Fields
+/// -----------------
+/// * `columns` – Column names of `tid`, `mjd`, `ra`, `dec`, `observer`, `sigma_ra` and
+///   `sigma_dec`.
+/// * `degrees` – RA/DEC in degrees (uncertainties in arcseconds) instead of radians.
+/// * `flexible_ids` – Accept string ids besides the integer ones.
+/// * `errors` – Uniform RA/DEC uncertainties; with `None`, the `sigma_ra` / `sigma_dec`
+///   columns are read when present, the uncertainties are unknown (NaN) otherwise.
+/// * `policy`, `strict`, `topocentric` – As for the other loaders.
+struct RecordBatchOptions {
+    columns: [String; 7],
+    degrees: bool,
+    flexible_ids: bool,
+    errors: Option<(f64, f64)>,
+    policy: BadSigma,
+    strict: Option<bool>,
+    topocentric: bool,
+    sort: bool,
+}
+
+/// Uniform uncertainties of the record batch loaders: `None` when neither is given (the
+/// per-row columns are then read when present), NaN for the one left out otherwise.
+fn record_batch_errors(error_ra: Option<f64>, error_dec: Option<f64>) -> Option<(f64, f64)> {
+    (error_ra.is_some() || error_dec.is_some())
+        .then(|| (error_ra.unwrap_or(f64::NAN), error_dec.unwrap_or(f64::NAN)))
+}
+
+/// Parquet reader of the mapped columns of `path`, before its batch size is set.
+///
+/// Arguments
+/// -----------------
+/// * `names` – Column names, as returned by [`arrow_column_names`].
+/// * `sites` – Read the `observer` column (no single observer given).
+/// * `sigmas` – Read the `sigma_ra` / `sigma_dec` columns (no uniform uncertainties).
+///
+/// Missing columns are ignored by the projection (the uncertainty columns are optional).
+fn parquet_reader(
+    path: &Utf8PathBuf,
+    names: &[String; 7],
+    sites: bool,
+    sigmas: bool,
+) -> PyResult<ParquetRecordBatchReaderBuilder<std::fs::File>> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| PyOSError::new_err(format!("cannot open {path}: {e}")))?;
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new(file).map_err(|e| parquet_err(path, e))?;
+    let wanted = names.iter().enumerate().filter(|&(i, _)| match i {
+        4 => sites,
+        5 | 6 => sigmas,
+        _ => true,
+    });
+    let mask = ProjectionMask::columns(
+        builder.parquet_schema(),
+        wanted.map(|(_, name)| name.as_str()),
+    );
+    Ok(builder.with_projection(mask))
+}
+
+fn parquet_err(path: &Utf8PathBuf, e: ParquetError) -> PyErr {
+    PyValueError::new_err(format!("cannot read {path} as Parquet: {e}"))
+}
+
+/// Read and parse a CSV file (gzip-compressed or not), without touching Python objects.
+fn read_csv(
+    path: &Utf8PathBuf,
+    opts: &csv_reader::Options,
+) -> PyResult<(csv_reader::Table, records::Malformed)> {
+    let bytes =
+        std::fs::read(path).map_err(|e| PyOSError::new_err(format!("cannot read {path}: {e}")))?;
+    let bytes = match csv_reader::is_gzip(&bytes) {
+        true => csv_reader::gunzip(&bytes)
+            .map_err(|e| PyValueError::new_err(format!("cannot decompress {path}: {e}")))?,
+        false => bytes,
+    };
+    let text = String::from_utf8(bytes)
+        .map_err(|e| PyValueError::new_err(format!("{path} is not UTF-8 text: {e}")))?;
+    csv_reader::parse(&text, opts).map_err(|e| match e {
+        csv_reader::CsvError::MissingColumn(column, header) => PyKeyError::new_err(format!(
+            "column {column} not in the header of {path} ({})",
+            header.join(", ")
+        )),
+        csv_reader::CsvError::Syntax(line, why) => {
+            PyValueError::new_err(format!("invalid CSV in {path}, line {line}: {why}"))
+        }
+    })
+}
+
+/// Ingest the rows of a CSV table as [`TrajectorySet::from_numpy_degrees`] (or
+/// `from_numpy_radians`) would.
+///
+/// The RA / Dec uncertainties are `errors` when given, the per-row columns when read,
+/// unknown otherwise.
+#[allow(clippy::too_many_arguments)]
+fn csv_set(
+    py: Python<'_>,
+    env: &mut PyOutfit,
+    handle: &Bound<'_, PyOutfit>,
+    table: csv_reader::Table,
+    errors: Option<(f64, f64)>,
+    degrees: bool,
+    observer: &Observer,
+    bad_sigma: &str,
+    bad_sigma_value: Option<f64>,
+    strict: Option<bool>,
+    topocentric: bool,
+    dedup: Dedup,
+    validate: bool,
+    sort: bool,
+) -> PyResult<TrajectorySet> {
+    let sigma = |uniform: Option<f64>, per_row: Option<Vec<f64>>| match (uniform, per_row) {
+        (Some(v), _) => Sigma::Uniform(v),
+        (None, Some(rows)) => Sigma::PerRow(Reals::Converted(rows)),
+        (None, None) => Sigma::Uniform(f64::NAN),
+    };
+    let (sigma_ra, sigma_dec) = match table.sigmas {
+        Some((ra, dec)) => (Some(ra), Some(dec)),
+        None => (None, None),
+    };
+    let columns = NumpyColumns {
+        names: if degrees {
+            DEGREE_COLUMNS
+        } else {
+            ["ra", "dec", "error_ra_rad", "error_dec_rad"]
+        },
+        trajectory_id: TrajectoryIds::from_text(table.ids).into(),
+        ra: Reals::Converted(table.ra).into(),
+        dec: Reals::Converted(table.dec).into(),
+        error_ra: sigma(errors.map(|e| e.0), sigma_ra).into(),
+        error_dec: sigma(errors.map(|e| e.1), sigma_dec).into(),
+        mjd_tt: Epochs::Numbers(Reals::Converted(table.time)).into(),
+        mag: None,
+        band: None,
+    };
+    let options = NumpyOptions {
+        bad_sigma,
+        bad_sigma_value,
+        strict,
+        topocentric,
+        time_scale: None,
+        epoch_format: "mjd",
+        dedup,
+        validate,
+        sort,
+        drop_invalid: false,
+        parallel: false,
+        n_threads: None,
+    };
+    let sites = BatchSites::Single(observer);
+    TrajectorySet::from_numpy_columns(py, env, handle, columns, degrees, sites, options)
+}
+
+/// Apply `read` to every path, on the rayon pool when `parallel`.
+///
+/// Return
+/// ----------
+/// * The results in the order of `paths`, or the error of a file that failed (the first
+///   one in sequential mode).
+fn read_files<T: Send>(
+    paths: &[Utf8PathBuf],
+    parallel: bool,
+    read: impl Fn(&Utf8PathBuf) -> PyResult<T> + Sync,
+) -> PyResult<Vec<T>> {
+    if parallel {
+        paths.par_iter().map(&read).collect()
+    } else {
+        paths.iter().map(read).collect()
+    }
+}
+
+/// Check the lengths of the NumPy loader arguments against the `n` trajectory ids,
+/// `ValueError` listing every length otherwise.
+///
+/// Arguments
+/// -----------------
+/// * `n` – Number of trajectory ids (rows).
+/// * `arrays` – `(argument, length)` of the other arrays.
+/// * `sigmas` – Uncertainty arguments; only those given per row are checked.
+fn check_lengths(
+    n: usize,
+    arrays: &[(&str, usize)],
+    sigmas: &[(&str, &Sigma<'_>)],
+) -> PyResult<()> {
+    let mut lengths = arrays.to_vec();
+    for (name, sigma) in sigmas {
+        if let Some(values) = sigma.per_row()? {
+            lengths.push((name, values.len()));
+        }
+    }
+    if lengths.iter().all(|(_, len)| *len == n) {
+        return Ok(());
+    }
+    let listed: Vec<String> = lengths
+        .iter()
+        .map(|(name, len)| format!("{name}={len}"))
+        .collect();
+    Err(PyValueError::new_err(format!(
+        "Length mismatch: trajectory_id={n}, {}",
+        listed.join(", ")
+    )))
+}
+
+/// Overwrite the uncertainties of freshly ingested trajectories with per-row values.
+///
+/// The core ingestion appends the observations of a trajectory in row order, so the
+/// `k`-th row of id `i` is the `k`-th observation of trajectory `i`. Values are converted
+/// to radians with `to_rad`.
+fn assign_row_sigmas(
+    set: &mut outfit::TrajectorySet,
+    ids: &[u32],
+    sigma_ra: &[f64],
+    sigma_dec: &[f64],
+    to_rad: f64,
+) {
+    let mut next: HashMap<u32, usize> = HashMap::new();
+    for (row, &id) in ids.iter().enumerate() {
+        let k = next.entry(id).or_default();
+        if let Some(o) = set
+            .get_mut(&ObjectNumber::Int(id))
+            .and_then(|obs| obs.get_mut(*k))
+        {
+            o.error_ra = sigma_ra[row] * to_rad;
+            o.error_dec = sigma_dec[row] * to_rad;
+        }
+        *k += 1;
+    }
+}
+
+/// Values of `column` at `rows`, or the whole column (borrowed) for `None`.
+fn take_rows<'a, T: Clone>(column: &'a [T], rows: Option<&[usize]>) -> Cow<'a, [T]> {
+    match rows {
+        Some(rows) => Cow::Owned(rows.iter().map(|&k| column[k].clone()).collect()),
+        None => Cow::Borrowed(column),
+    }
+}
+
+/// Column `name` of a record batch, `KeyError` when absent.
+fn arrow_column<'py>(batch: &Bound<'py, PyAny>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+    let present: bool = batch
+        .getattr("schema")?
+        .call_method1("get_field_index", (name,))?
+        .extract::<i64>()?
+        >= 0;
+    if !present {
+        return Err(PyKeyError::new_err(format!(
+            "column {name:?} not found in the Arrow stream"
+        )));
+    }
+    batch.call_method1("column", (name,))
+}
+
+/// Floating-point column of a record batch as `f64` values (nulls become NaN).
+fn arrow_f64(batch: &Bound<'_, PyAny>, name: &str) -> PyResult<Vec<f64>> {
+    let kwargs = PyDict::new(batch.py());
+    kwargs.set_item("zero_copy_only", false)?;
+    let values = arrow_column(batch, name)?
+        .call_method("to_numpy", (), Some(&kwargs))?
+        .call_method1("astype", ("float64",))?;
+    Ok(values
+        .extract::<PyReadonlyArray1<f64>>()?
+        .as_slice()?
+        .to_vec())
+}
+
+/// Integer identifier column of a record batch as `u32` values, 64-bit ids numbered as
+/// by the NumPy loaders ([`TrajectoryIds::numbered`]).
+fn arrow_ids(batch: &Bound<'_, PyAny>, name: &str) -> PyResult<(Vec<u32>, IdNumbering)> {
+    let kwargs = PyDict::new(batch.py());
+    kwargs.set_item("zero_copy_only", false)?;
+    let values = arrow_column(batch, name)?.call_method("to_numpy", (), Some(&kwargs))?;
+    let kind: String = values.getattr("dtype")?.getattr("kind")?.extract()?;
+    if kind != "i" && kind != "u" {
+        return Err(PyValueError::new_err(format!(
+            "column {name:?} must hold non-null integer trajectory ids"
+        )));
+    }
+    let ids: TrajectoryIds = values.extract()?;
+    let (ids, numbering) = ids.numbered()?;
+    Ok((ids.into_owned(), numbering))
+}
+
+/// Numeric column of a `DataFrame` as `f64` values (missing values become NaN).
+fn frame_f64(df: &Bound<'_, PyAny>, name: &str) -> PyResult<Vec<f64>> {
+    let series = df.get_item(name)?;
+    let dtype = series.getattr("dtype")?;
+    let kind: String = dtype.getattr("kind")?.extract()?;
+    if !matches!(kind.as_str(), "f" | "i" | "u") {
+        return Err(PyValueError::new_err(format!(
+            "column {name:?} must be numeric, got dtype {}",
+            dtype.str()?
+        )));
+    }
+    let kwargs = PyDict::new(df.py());
+    kwargs.set_item("dtype", "float64")?;
+    kwargs.set_item("na_value", f64::NAN)?;
+    let values = series.call_method("to_numpy", (), Some(&kwargs))?;
+    Ok(values
+        .extract::<PyReadonlyArray1<f64>>()?
+        .as_slice()?
+        .to_vec())
+}
+
+/// `ValueError` listing the `(column, argument)` pairs of `required` missing from
+/// `columns` (the columns of a pandas or polars `DataFrame`).
+fn check_frame_columns(columns: &Bound<'_, PyAny>, required: &[(&str, &str)]) -> PyResult<()> {
+    let mut missing = Vec::new();
+    for (name, arg) in required {
+        if !columns.contains(*name)? {
+            missing.push(format!("'{name}' ({arg})"));
+        }
+    }
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let available = columns
+        .try_iter()?
+        .map(|c| Ok(format!("'{}'", c?.str()?)))
+        .collect::<PyResult<Vec<_>>>()?;
+    Err(PyValueError::new_err(format!(
+        "missing DataFrame column(s): {}; available: {}",
+        missing.join(", "),
+        available.join(", ")
+    )))
+}
+
+/// Columns of a Fink tracklet table, with their role (`TrajectorySet.from_fink`).
+const FINK_COLUMNS: [(&str, &str); 7] = [
+    ("objectId", "trajectory id"),
+    ("jd", "epoch"),
+    ("ra", "right ascension"),
+    ("dec", "declination"),
+    ("sigmara", "RA uncertainty"),
+    ("sigmadec", "DEC uncertainty"),
+    ("fid", "ZTF filter"),
+];
+
+/// Band of the ZTF filter id `fid` of row `row`, `None` when null.
+fn ztf_band(row: usize, fid: f64) -> PyResult<Option<String>> {
+    match fid {
+        f if f.is_nan() => Ok(None),
+        1.0 => Ok(Some("g".into())),
+        2.0 => Ok(Some("r".into())),
+        3.0 => Ok(Some("i".into())),
+        other => Err(PyValueError::new_err(format!(
+            "column \"fid\": unknown ZTF filter id {other} at row {row} (expected 1, 2 or 3)"
+        ))),
+    }
+}
+
+/// Uniform uncertainties of the `DataFrame` loaders, `None` when neither is given.
+fn uniform_errors(error_ra: Option<f64>, error_dec: Option<f64>) -> PyResult<Option<(f64, f64)>> {
+    match (error_ra, error_dec) {
+        (Some(ra), Some(dec)) => Ok(Some((ra, dec))),
+        (None, None) => Ok(None),
+        _ => Err(PyValueError::new_err(
+            "error_ra and error_dec must be given together",
+        )),
+    }
+}
+
+/// `Float64` column `name` of a polars `DataFrame` as a NumPy array.
+///
+/// A non-null `Float64` column is exposed with [`polars_numpy`]. Other numeric columns
+/// are cast, nulls becoming NaN, and copied once.
+fn polars_column<'py>(
+    polars: &Bound<'py, PyAny>,
+    df: &Bound<'py, PyAny>,
+    name: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let series = df.call_method1("get_column", (name,))?;
+    let current = series.getattr("dtype")?;
+    let nulls: usize = series.call_method0("null_count")?.extract()?;
+    if current.str()?.to_str()? == "Float64" && nulls == 0 {
+        return polars_numpy(&series);
+    }
+    let numeric: bool = current.call_method0("is_numeric")?.extract()?;
+    if !numeric {
+        return Err(PyValueError::new_err(format!(
+            "column {name:?} must be numeric, got dtype {}",
+            current.str()?
+        )));
+    }
+    series
+        .call_method1("cast", (polars.getattr("Float64")?,))?
+        .call_method1("fill_null", (f64::NAN,))?
+        .call_method0("rechunk")?
+        .call_method0("to_numpy")
+}
+
+/// Trajectory id column `name` of a polars `DataFrame` as a NumPy integer array.
+///
+/// Any non-null integer column is exposed with [`polars_numpy`] in its own dtype, so
+/// that `UInt64` / `Int64` survey ids reach [`TrajectoryIds`] unchanged (negative ids are
+/// rejected there).
+fn polars_ids<'py>(df: &Bound<'py, PyAny>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+    let series = df.call_method1("get_column", (name,))?;
+    let current = series.getattr("dtype")?;
+    let integer: bool = current.call_method0("is_integer")?.extract()?;
+    let nulls: usize = series.call_method0("null_count")?.extract()?;
+    if !integer || nulls > 0 {
+        return Err(PyValueError::new_err(format!(
+            "column {name:?} must hold non-null integer trajectory ids, got dtype {}",
+            current.str()?
+        )));
+    }
+    polars_numpy(&series)
+}
+
+/// Non-null polars `series` as a NumPy array: a read-only view of its Arrow buffer (no
+/// copy) when it is a single chunk, rechunked and copied once otherwise.
+fn polars_numpy<'py>(series: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let chunks: usize = series.call_method0("n_chunks")?.extract()?;
+    if chunks > 1 {
+        return series.call_method0("rechunk")?.call_method0("to_numpy");
+    }
+    let kwargs = PyDict::new(series.py());
+    kwargs.set_item("allow_copy", false)?;
+    series.call_method("to_numpy", (), Some(&kwargs))
+}
+
+/// Column `name` of an astropy table, `ValueError` when some of its values are masked.
+fn unmasked_column<'py>(
+    np: &Bound<'py, PyModule>,
+    table: &Bound<'py, PyAny>,
+    name: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let col = table.get_item(name)?;
+    if let Ok(mask) = col.getattr("mask") {
+        if np.call_method1("any", (mask,))?.is_truthy()? {
+            return Err(PyValueError::new_err(format!(
+                "column {name:?} has masked values"
+            )));
+        }
+    }
+    Ok(col)
+}
+
+/// `float64` values of `values` (a column or a NumPy array) as a vector.
+fn f64_vec(np: &Bound<'_, PyModule>, values: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    let kwargs = PyDict::new(np.py());
+    kwargs.set_item("dtype", "float64")?;
+    let values = np.call_method("ascontiguousarray", (values,), Some(&kwargs))?;
+    Ok(values
+        .extract::<PyReadonlyArray1<f64>>()?
+        .as_slice()?
+        .to_vec())
+}
+
+/// Angle column of an astropy table in radians.
+///
+/// The attached unit is used when present (`degrees` must then agree with it);
+/// unitless columns are read in the unit given by `degrees`.
+fn astropy_radians(
+    np: &Bound<'_, PyModule>,
+    u: &Bound<'_, PyModule>,
+    table: &Bound<'_, PyAny>,
+    name: &str,
+    degrees: Option<bool>,
+) -> PyResult<Vec<f64>> {
+    let col = unmasked_column(np, table, name)?;
+    let unit = col.getattr("unit")?;
+    let (rad, deg) = (u.getattr("rad")?, u.getattr("deg")?);
+    if unit.is_none() {
+        let Some(degrees) = degrees else {
+            return Err(PyValueError::new_err(format!(
+                "column {name:?} has no unit: attach one (e.g. table[{name:?}].unit = 'deg') \
+                 or pass units='degrees' or units='radians'"
+            )));
+        };
+        let values = np.call_method1("asarray", (col,))?;
+        let values = if degrees {
+            np.call_method1("deg2rad", (values,))?
+        } else {
+            values
+        };
+        return f64_vec(np, &values);
+    }
+    if !unit.call_method1("is_equivalent", (&rad,))?.is_truthy()? {
+        return Err(PyValueError::new_err(format!(
+            "column {name:?} has unit '{}', which is not an angle",
+            unit.str()?
+        )));
+    }
+    if let Some(degrees) = degrees {
+        let stated = if degrees { &deg } else { &rad };
+        if !unit.eq(stated)? {
+            return Err(PyValueError::new_err(format!(
+                "column {name:?} is in '{}' but units='{}' was given",
+                unit.str()?,
+                if degrees { "degrees" } else { "radians" }
+            )));
+        }
+    }
+    let values = u
+        .getattr("Quantity")?
+        .call1((col,))?
+        .call_method1("to_value", (rad,))?;
+    f64_vec(np, &values)
+}
+
+/// Epoch column of an astropy table as MJD (TT).
+///
+/// `Time` columns are converted to the TT scale; other columns hold MJD (TT) values,
+/// in days or with a time unit attached.
+fn astropy_mjd_tt(
+    np: &Bound<'_, PyModule>,
+    u: &Bound<'_, PyModule>,
+    table: &Bound<'_, PyAny>,
+    name: &str,
+) -> PyResult<Vec<f64>> {
+    let col = unmasked_column(np, table, name)?;
+    let time = np.py().import("astropy.time")?.getattr("Time")?;
+    if col.is_instance(&time)? {
+        return f64_vec(np, &col.getattr("tt")?.getattr("mjd")?);
+    }
+    let unit = col.getattr("unit")?;
+    if unit.is_none() {
+        return f64_vec(np, &np.call_method1("asarray", (col,))?);
+    }
+    let day = u.getattr("day")?;
+    if !unit.call_method1("is_equivalent", (&day,))?.is_truthy()? {
+        return Err(PyValueError::new_err(format!(
+            "column {name:?} has unit '{}': expected an astropy Time or MJD (TT) values",
+            unit.str()?
+        )));
+    }
+    let values = u
+        .getattr("Quantity")?
+        .call1((col,))?
+        .call_method1("to_value", (day,))?;
+    f64_vec(np, &values)
+}
+
+/// Identifier column of a `DataFrame` as `u32` values: an integer column as is, an `object`
+/// column of `int` or `str` ids numbered as the `trajectory_id` of the NumPy loaders.
+fn frame_ids(df: &Bound<'_, PyAny>, name: &str) -> PyResult<(Vec<u32>, IdNumbering)> {
+    let series = df.get_item(name)?;
+    let dtype = series.getattr("dtype")?;
+    let kind: String = dtype.getattr("kind")?.extract()?;
+    if kind == "O" {
+        let values = series.call_method0("to_numpy")?;
+        let ids = values
+            .extract::<TrajectoryIds>()
+            .and_then(|ids| ids.numbered().map(|(ids, n)| (ids.into_owned(), n)));
+        return ids.map_err(|err| {
+            let prefixed = PyErr::from_type(
+                err.get_type(df.py()),
+                format!("column {name:?}: {}", err.value(df.py())),
+            );
+            prefixed.set_cause(df.py(), Some(err));
+            prefixed
+        });
+    }
+    let nulls: bool = series
+        .call_method0("isna")?
+        .call_method0("any")?
+        .extract()?;
+    if !matches!(kind.as_str(), "i" | "u") || nulls {
+        return Err(PyValueError::new_err(format!(
+            "column {name:?} must hold non-null integer trajectory ids, got dtype {}",
+            dtype.str()?
+        )));
+    }
+    Ok((
+        u32_ids(&series.call_method1("to_numpy", ("int64",))?, name)?,
+        IdNumbering::default(),
+    ))
+}
+
+/// Integer ids of a NumPy array checked against the `uint32` range.
+fn u32_ids(values: &Bound<'_, PyAny>, name: &str) -> PyResult<Vec<u32>> {
+    let values = values.call_method1("astype", ("int64",))?;
+    values
+        .extract::<PyReadonlyArray1<i64>>()?
+        .as_slice()?
+        .iter()
+        .map(|&v| {
+            u32::try_from(v).map_err(|_| {
+                PyValueError::new_err(format!(
+                    "trajectory id {v} in column {name:?} is outside the uint32 range"
+                ))
+            })
+        })
+        .collect()
+}
+
 /// Convert a Python path-like (str or pathlib.Path) to Utf8PathBuf.
 ///
 /// This calls `os.fspath(obj)` to be fully path-protocol compliant.
+/// Parse the `units` argument of the tabular loaders: `true` for degrees.
+fn degrees_units(units: &str) -> PyResult<bool> {
+    match units {
+        "degrees" => Ok(true),
+        "radians" => Ok(false),
+        other => Err(PyValueError::new_err(format!(
+            "units must be 'degrees' or 'radians', got {other:?}"
+        ))),
+    }
+}
+
+/// Parse the `on_error` argument of the text readers: `true` to skip malformed records.
+fn skip_malformed(on_error: &str) -> PyResult<bool> {
+    match on_error {
+        "raise" => Ok(false),
+        "skip" => Ok(true),
+        other => Err(PyValueError::new_err(format!(
+            "on_error must be 'raise' or 'skip', got {other:?}"
+        ))),
+    }
+}
+
+/// Parse the `on_conflict` argument of `merge`: `true` to concatenate shared ids.
+fn concat_conflicts(on_conflict: &str) -> PyResult<bool> {
+    match on_conflict {
+        "concat" => Ok(true),
+        "error" => Ok(false),
+        other => Err(PyValueError::new_err(format!(
+            "on_conflict must be 'concat' or 'error', got {other:?}"
+        ))),
+    }
+}
+
+/// Read a text file without the GIL, `OSError` on failure.
+fn read_text(py: Python<'_>, path: &Utf8PathBuf) -> PyResult<String> {
+    py.detach(|| std::fs::read_to_string(path))
+        .map_err(|e| PyOSError::new_err(format!("cannot read {path}: {e}")))
+}
+
+/// Raise or report the malformed records of a text file.
+///
+/// Arguments
+/// -----------------
+/// * `what` – Unit named in the message (`"line"`, `"record"`).
+/// * `at` – Kind of position of the entries (`"line"`, or `"record"` for a rank).
+/// * `malformed` – `(position, reason)` pairs, in any order.
+/// * `skip` – `on_error="skip"`: report with the `skipped_lines` fallback instead of
+///   raising `ValueError`.
+fn report_malformed(
+    py: Python<'_>,
+    path: &Utf8PathBuf,
+    what: &str,
+    at: &str,
+    mut malformed: records::Malformed,
+    skip: bool,
+    strict: Option<bool>,
+) -> PyResult<()> {
+    if malformed.is_empty() {
+        return Ok(());
+    }
+    malformed.sort();
+    let shown: Vec<String> = malformed
+        .iter()
+        .take(MAX_REPORTED_LINES)
+        .map(|(pos, reason)| format!("{at} {pos}: {reason}"))
+        .collect();
+    let more = match malformed.len().saturating_sub(MAX_REPORTED_LINES) {
+        0 => String::new(),
+        n => format!(" (and {n} more)"),
+    };
+    let summary = format!(
+        "{} malformed {what}(s) in {path}: {}{more}",
+        malformed.len(),
+        shown.join("; ")
+    );
+    if !skip {
+        return Err(PyValueError::new_err(format!(
+            "{summary}; use on_error='skip' to ignore them"
+        )));
+    }
+    strict::fallback(
+        py,
+        Check::SkippedLines,
+        strict,
+        &format!("skipped {summary}"),
+    )
+}
+
+/// Attach parsed ADES records to their sites and build the checked set.
+///
+/// Records with an unknown station join the malformed ones, reported as `"record"`s
+/// located by `at` (see [`report_malformed`]). Records flagged as rejected are dropped,
+/// or kept and flagged in the set with `include_rejected`.
+#[allow(clippy::too_many_arguments)]
+fn ades_set(
+    py: Python<'_>,
+    env: &mut PyOutfit,
+    handle: &Bound<'_, PyOutfit>,
+    path: &Utf8PathBuf,
+    (mut records, mut malformed): (Vec<records::Record>, records::Malformed),
+    at: &str,
+    skip: bool,
+    policy: BadSigma,
+    strict: Option<bool>,
+    include_rejected: bool,
+) -> PyResult<TrajectorySet> {
+    if !include_rejected {
+        records.retain(|r| !r.rejected);
+    }
+    let (records, unknown) =
+        py.detach(|| records::known_sites(env.mpc_sites(), &env.outfit_detached(), records));
+    malformed.extend(unknown);
+    report_malformed(py, path, "record", at, malformed, skip, strict)?;
+
+    let built = py.detach(|| records::build(&mut env.inner_mut_detached(), &records, false));
+    rejected_set(py, built.into_py()?, env, handle, policy, strict, false)
+}
+
+/// Checked set of ADES records, its rejected observations flagged (see [`ades_set`]).
+fn rejected_set(
+    py: Python<'_>,
+    (inner, rejected): (
+        outfit::TrajectorySet,
+        Vec<outfit::observations::Observation>,
+    ),
+    env: &PyOutfit,
+    handle: &Bound<'_, PyOutfit>,
+    policy: BadSigma,
+    strict: Option<bool>,
+    sort: bool,
+) -> PyResult<TrajectorySet> {
+    let mut flags = PhotometryTable::default();
+    flags.reject(&rejected);
+    TrajectorySet::checked(py, inner, env, handle, policy, strict, sort)
+        .map(|set| set.with_photometry(flags))
+}
+
+/// Reading options of the ADES loaders (uncertainties given in arcseconds).
+fn ades_options(
+    group_by: Option<Vec<String>>,
+    error_ra_arcsec: Option<f64>,
+    error_dec_arcsec: Option<f64>,
+) -> PyResult<ades::Options> {
+    let group_by = group_by.unwrap_or_else(|| {
+        ades::DEFAULT_GROUP_BY
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    });
+    if group_by.is_empty() {
+        return Err(PyValueError::new_err(
+            "group_by must name at least one field",
+        ));
+    }
+    Ok(ades::Options {
+        group_by,
+        default_sigma_ra: error_ra_arcsec.map_or(f64::NAN, |s| s * RADSEC),
+        default_sigma_dec: error_dec_arcsec.map_or(f64::NAN, |s| s * RADSEC),
+    })
+}
+
 fn py_path_to_utf8(py: Python<'_>, pathlike: &Bound<'_, PyAny>) -> PyResult<Utf8PathBuf> {
     let os = py.import("os")?;
     let fspath = os.getattr("fspath")?;
@@ -0,0 +1,342 @@
+//! Unit metadata of the dict/JSON exports.
+//!
+//! Exports carry bare floats. With `include_units=True` they also carry a `"units"`
+//! block mapping each field to a unit string, so that a file read months later is
+//! unambiguous. Unit strings are parsable by `astropy.units.Unit` (`""` is
+//! dimensionless); epochs are tagged with their time scale (`"MJD_TDB"`, `"MJD_TT"`).
+//!
+//! Every export taking a `degrees` flag converts its angles consistently, and its
+//! units block then says `"deg"`. Parsers honour the block: angles declared in degrees
+//! are converted back to radians, any other mismatch is rejected.
+//!
+//! See also
+//! ------------
+//! * [`crate::iod_gauss::GaussResult`] – `to_dict` / `to_json` / `from_dict` / `from_json`.
+use outfit::{
+    CometaryElements as RsCometary, EquinoctialElements as RsEquinoctial,
+    KeplerianElements as RsKeplerian, OrbitalElements as RsOrbitalElements,
+};
+use pyo3::{
+    exceptions::{PyKeyError, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+
+/// Physical quantity of an exported field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Quantity {
+    /// Epoch in MJD (TDB).
+    EpochTdb,
+    /// Epoch in MJD (TT).
+    EpochTt,
+    /// Distance in AU.
+    Distance,
+    /// Angle, exported in radians or degrees.
+    Angle,
+    /// Angle always exported in arcseconds.
+    Arcsec,
+    /// Dimensionless number.
+    Ratio,
+    /// Duration in milliseconds.
+    Millis,
+    /// Weight of a normalized residual, `1/σ²` with `σ` in arcseconds.
+    InvArcsec2,
+}
+
+impl Quantity {
+    /// Unit string of the quantity.
+    pub(crate) fn unit(self, degrees: bool) -> &'static str {
+        match self {
+            Quantity::EpochTdb => "MJD_TDB",
+            Quantity::EpochTt => "MJD_TT",
+            Quantity::Distance => "AU",
+            Quantity::Angle if degrees => "deg",
+            Quantity::Angle => "rad",
+            Quantity::Arcsec => "arcsec",
+            Quantity::Ratio => "",
+            Quantity::Millis => "ms",
+            Quantity::InvArcsec2 => "arcsec^-2",
+        }
+    }
+
+    /// Convert an exported value back to the internal unit, given the declared unit.
+    fn to_internal(self, field: &str, value: f64, unit: &str) -> PyResult<f64> {
+        match (self, unit) {
+            (Quantity::Angle, "deg") => Ok(value.to_radians()),
+            _ if unit == self.unit(false) => Ok(value),
+            _ => Err(PyValueError::new_err(format!(
+                "units[{field:?}] = {unit:?} is not supported (expected {:?}{})",
+                self.unit(false),
+                if self == Quantity::Angle {
+                    " or \"deg\""
+                } else {
+                    ""
+                }
+            ))),
+        }
+    }
+}
+
+/// Convert an internal value for export.
+pub(crate) fn export(q: Quantity, value: f64, degrees: bool) -> f64 {
+    if degrees && q == Quantity::Angle {
+        value.to_degrees()
+    } else {
+        value
+    }
+}
+
+/// Build a `"units"` block from `(field, quantity)` pairs.
+pub(crate) fn units_block<'py>(
+    py: Python<'py>,
+    fields: &[(&str, Quantity)],
+    degrees: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new(py);
+    for (name, q) in fields {
+        d.set_item(*name, q.unit(degrees))?;
+    }
+    Ok(d)
+}
+
+/// Field names and quantities of the three element families, in export order.
+pub(crate) const KEPLERIAN_FIELDS: [(&str, Quantity); 7] = [
+    ("reference_epoch", Quantity::EpochTdb),
+    ("semi_major_axis", Quantity::Distance),
+    ("eccentricity", Quantity::Ratio),
+    ("inclination", Quantity::Angle),
+    ("ascending_node_longitude", Quantity::Angle),
+    ("periapsis_argument", Quantity::Angle),
+    ("mean_anomaly", Quantity::Angle),
+];
+
+pub(crate) const EQUINOCTIAL_FIELDS: [(&str, Quantity); 7] = [
+    ("reference_epoch", Quantity::EpochTdb),
+    ("semi_major_axis", Quantity::Distance),
+    ("eccentricity_sin_lon", Quantity::Ratio),
+    ("eccentricity_cos_lon", Quantity::Ratio),
+    ("tan_half_incl_sin_node", Quantity::Ratio),
+    ("tan_half_incl_cos_node", Quantity::Ratio),
+    ("mean_longitude", Quantity::Angle),
+];
+
+pub(crate) const COMETARY_FIELDS: [(&str, Quantity); 7] = [
+    ("reference_epoch", Quantity::EpochTdb),
+    ("perihelion_distance", Quantity::Distance),
+    ("eccentricity", Quantity::Ratio),
+    ("inclination", Quantity::Angle),
+    ("ascending_node_longitude", Quantity::Angle),
+    ("periapsis_argument", Quantity::Angle),
+    ("true_anomaly", Quantity::Angle),
+];
+
+/// Columns of `Observations.to_dict`.
+pub(crate) const OBSERVATION_FIELDS: [(&str, Quantity); 5] = [
+    ("mjd_tt", Quantity::EpochTt),
+    ("ra", Quantity::Angle),
+    ("dec", Quantity::Angle),
+    ("sigma_ra", Quantity::Angle),
+    ("sigma_dec", Quantity::Angle),
+];
+
+/// Keys of `FitStatistics.to_dict`.
+pub(crate) const FIT_FIELDS: [(&str, Quantity); 6] = [
+    ("rms_arcsec", Quantity::Arcsec),
+    ("rms_ra_arcsec", Quantity::Arcsec),
+    ("rms_dec_arcsec", Quantity::Arcsec),
+    ("chi2", Quantity::Ratio),
+    ("reduced_chi2", Quantity::Ratio),
+    ("n_points", Quantity::Ratio),
+];
+
+/// Summary of the fit weights in `OrbitResults.to_dict` (over both coordinates).
+pub(crate) const WEIGHT_FIELDS: [(&str, Quantity); 3] = [
+    ("weight_min", Quantity::InvArcsec2),
+    ("weight_median", Quantity::InvArcsec2),
+    ("weight_max", Quantity::InvArcsec2),
+];
+
+/// Keys of `IodCosts.to_dict`.
+pub(crate) const COST_FIELDS: [(&str, Quantity); 4] = [
+    ("wall_ms", Quantity::Millis),
+    ("triplets_attempted", Quantity::Ratio),
+    ("realizations", Quantity::Ratio),
+    ("corrected_solutions", Quantity::Ratio),
+];
+
+/// Serialize a dict export with Python's `json` module.
+pub(crate) fn dumps(obj: &Bound<'_, PyDict>, indent: Option<usize>) -> PyResult<String> {
+    let py = obj.py();
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("indent", indent)?;
+    py.import("json")?
+        .call_method("dumps", (obj,), Some(&kwargs))?
+        .extract()
+}
+
+/// Value of `key` in an exported mapping.
+///
+/// Return
+/// ----------
+/// * The value, or `KeyError` naming `context` (e.g. `"fit_statistics"`) and the key.
+pub(crate) fn field<'py>(
+    mapping: &Bound<'py, PyAny>,
+    context: &str,
+    key: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    mapping.get_item(key).map_err(|_| {
+        PyKeyError::new_err(if context.is_empty() {
+            format!("missing key {key:?}")
+        } else {
+            format!("missing key {key:?} in {context:?}")
+        })
+    })
+}
+
+/// Count stored in an export: an `int`, or an integral float as read back from a
+/// DataFrame column.
+pub(crate) fn count(value: &Bound<'_, PyAny>, key: &str) -> PyResult<usize> {
+    if let Ok(n) = value.extract::<usize>() {
+        return Ok(n);
+    }
+    match value.extract::<f64>() {
+        Ok(x) if x >= 0.0 && x.fract() == 0.0 && x <= usize::MAX as f64 => Ok(x as usize),
+        _ => Err(PyValueError::new_err(format!(
+            "{key:?} must be a non-negative integer, got {value}"
+        ))),
+    }
+}
+
+/// Parse a JSON document with Python's `json` module.
+pub(crate) fn loads<'py>(py: Python<'py>, s: &str) -> PyResult<Bound<'py, PyAny>> {
+    py.import("json")?.call_method1("loads", (s,))
+}
+
+/// Family tag, field table and values (internal units) of an element set.
+pub(crate) fn element_fields(
+    elems: &RsOrbitalElements,
+) -> (
+    &'static str,
+    &'static [(&'static str, Quantity); 7],
+    [f64; 7],
+) {
+    match elems {
+        RsOrbitalElements::Keplerian(k) => (
+            "keplerian",
+            &KEPLERIAN_FIELDS,
+            [
+                k.reference_epoch,
+                k.semi_major_axis,
+                k.eccentricity,
+                k.inclination,
+                k.ascending_node_longitude,
+                k.periapsis_argument,
+                k.mean_anomaly,
+            ],
+        ),
+        RsOrbitalElements::Equinoctial(q) => (
+            "equinoctial",
+            &EQUINOCTIAL_FIELDS,
+            [
+                q.reference_epoch,
+                q.semi_major_axis,
+                q.eccentricity_sin_lon,
+                q.eccentricity_cos_lon,
+                q.tan_half_incl_sin_node,
+                q.tan_half_incl_cos_node,
+                q.mean_longitude,
+            ],
+        ),
+        RsOrbitalElements::Cometary(c) => (
+            "cometary",
+            &COMETARY_FIELDS,
+            [
+                c.reference_epoch,
+                c.perihelion_distance,
+                c.eccentricity,
+                c.inclination,
+                c.ascending_node_longitude,
+                c.periapsis_argument,
+                c.true_anomaly,
+            ],
+        ),
+    }
+}
+
+/// Rebuild an element set from its family tag and an `elements` mapping.
+///
+/// Arguments
+/// -----------------
+/// * `kind` – `"keplerian"`, `"equinoctial"` or `"cometary"`.
+/// * `elements` – Mapping holding the seven fields of the family.
+/// * `units` – Optional `"units"` block; fields it omits are taken in internal units.
+///
+/// Return
+/// ----------
+/// * The element set in internal units, `KeyError` naming a missing field, or
+///   `ValueError` for an unknown family or an unsupported unit.
+pub(crate) fn elements_from_mapping(
+    kind: &str,
+    elements: &Bound<'_, PyAny>,
+    units: Option<&Bound<'_, PyAny>>,
+) -> PyResult<RsOrbitalElements> {
+    let fields = match kind {
+        "keplerian" => &KEPLERIAN_FIELDS,
+        "equinoctial" => &EQUINOCTIAL_FIELDS,
+        "cometary" => &COMETARY_FIELDS,
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "unknown element type {other:?} (expected 'keplerian', 'equinoctial' or 'cometary')"
+            )))
+        }
+    };
+
+    let mut v = [0.0; 7];
+    for (slot, (name, q)) in v.iter_mut().zip(fields.iter()) {
+        let raw: f64 = elements
+            .get_item(*name)
+            .map_err(|_| PyKeyError::new_err(format!("missing element field {name:?}")))?
+            .extract()?;
+        let unit: Option<String> = match units {
+            Some(u) => match u.get_item(*name) {
+                Ok(x) => Some(x.extract()?),
+                Err(_) => None,
+            },
+            None => None,
+        };
+        *slot = match unit {
+            Some(unit) => q.to_internal(name, raw, &unit)?,
+            None => raw,
+        };
+    }
+
+    Ok(match kind {
+        "keplerian" => RsOrbitalElements::Keplerian(RsKeplerian {
+            reference_epoch: v[0],
+            semi_major_axis: v[1],
+            eccentricity: v[2],
+            inclination: v[3],
+            ascending_node_longitude: v[4],
+            periapsis_argument: v[5],
+            mean_anomaly: v[6],
+        }),
+        "equinoctial" => RsOrbitalElements::Equinoctial(RsEquinoctial {
+            reference_epoch: v[0],
+            semi_major_axis: v[1],
+            eccentricity_sin_lon: v[2],
+            eccentricity_cos_lon: v[3],
+            tan_half_incl_sin_node: v[4],
+            tan_half_incl_cos_node: v[5],
+            mean_longitude: v[6],
+        }),
+        _ => RsOrbitalElements::Cometary(RsCometary {
+            reference_epoch: v[0],
+            perihelion_distance: v[1],
+            eccentricity: v[2],
+            inclination: v[3],
+            ascending_node_longitude: v[4],
+            periapsis_argument: v[5],
+            true_anomaly: v[6],
+        }),
+    })
+}
@@ -1,4 +1,6 @@
 from collections import Counter
+import math
+import time
 import numpy as np
 from astropy.time import Time
 import pandas as pd
@@ -10,6 +12,50 @@ from py_outfit import Observer
 from py_outfit import TrajectorySet
 
 
+# ----------------------------------------------------------------------
+# Benchmarks: timings only reported, run with --benchmark
+# ----------------------------------------------------------------------
+
+
+def pytest_addoption(parser):
+    parser.addoption(
+        "--benchmark", action="store_true", help="run the tests marked benchmark"
+    )
+
+
+def pytest_configure(config):
+    config.addinivalue_line(
+        "markers", "benchmark: timing comparison, skipped unless --benchmark is given"
+    )
+
+
+def pytest_collection_modifyitems(config, items):
+    if config.getoption("--benchmark"):
+        return
+    skip = pytest.mark.skip(reason="benchmark, run with --benchmark")
+    for item in items:
+        if "benchmark" in item.keywords:
+            item.add_marker(skip)
+
+
+@pytest.fixture
+def best_of():
+    """
+    Fixture returning `best_of(f, repeat=3)`: the best wall-clock time of `repeat`
+    calls of `f`, in seconds. Benchmarks report it; they never assert on it.
+    """
+
+    def best_of(f, repeat=3):
+        best = math.inf
+        for _ in range(repeat):
+            t0 = time.perf_counter()
+            f()
+            best = min(best, time.perf_counter() - t0)
+        return best
+
+    return best_of
+
+
 @pytest.fixture
 def pyoutfit_env() -> Iterator[PyOutfit]:
     """
@@ -110,3 +110,35 @@ def test_multiple_partial_chains_are_independent():
     b = IODParams.builder().max_triplets(200).build()
     assert a.max_triplets == 100
     assert b.max_triplets == 200
+
+
+@pytest.mark.parametrize("v", [None, 60000.0, "first_obs", "last_obs", "middle"])
+def test_output_epoch_is_settable(v):
+    params = IODParams.builder().output_epoch(v).build()
+    assert params.output_epoch == v
+
+
+@pytest.mark.parametrize("v", ["start", float("nan")])
+def test_output_epoch_rejects_unknown_values(v):
+    with pytest.raises(ValueError):
+        IODParams.builder().output_epoch(v)
+
+
+def test_subsample_defaults_to_uniform():
+    assert IODParams().subsample == "uniform"
+    assert IODParams.builder().build().subsample == "uniform"
+
+
+@pytest.mark.parametrize("v", ["uniform", "leverage"])
+def test_subsample_is_settable(v):
+    assert IODParams.builder().subsample(v).build().subsample == v
+
+
+def test_subsample_rejects_unknown_strategy():
+    with pytest.raises(ValueError):
+        IODParams.builder().subsample("random")
+
+
+def test_subsample_leverage_rejected_in_parallel_mode():
+    with pytest.raises(ValueError):
+        IODParams.builder().subsample("leverage").do_parallel().build()
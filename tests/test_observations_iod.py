@@ -1,4 +1,6 @@
+import json
 import math
+import threading
 from typing import Tuple
 
 import numpy as np
@@ -87,3 +89,538 @@ def test_single_observations_estimate_is_deterministic_with_seed(
     # should be fixed for outfit 3.0.0 with issue #41
     # assert r1 == pytest.approx(r2, rel=1e-14, abs=1e-14)
     _compare_orbit_dicts_approx(g1.to_dict(), g2.to_dict(), rtol=1e-14, atol=1e-14)
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_triplet_scorer_forces_selected_triplet(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    """
+    A scorer that only accepts one triplet must make the Gauss stage solve exactly
+    that triplet: the orbit epoch then sits at the middle observation of the triplet.
+    """
+    traj_set, counts = small_traj_set
+    key = next(k for k, n in counts.items() if n >= 4)
+
+    # First pass: record every feasible triplet offered to the scorer.
+    seen = []
+
+    def recorder(epochs, indices):
+        seen.append((tuple(epochs), tuple(indices)))
+        return 0.0
+
+    params = (
+        IODParams.builder()
+        .n_noise_realizations(0)
+        .triplet_scorer(recorder)
+        .build()
+    )
+    traj_set[key].estimate_best_orbit(pyoutfit_env, params, seed=1)
+    assert len(seen) > 1
+    for epochs, indices in seen:
+        assert epochs[0] < epochs[1] < epochs[2]
+        assert indices[0] < indices[1] < indices[2]
+
+    # Second pass: only the last enumerated triplet gets a finite score.
+    target_epochs, target_indices = seen[-1]
+    used = []
+
+    def forcing(epochs, indices):
+        if tuple(indices) == target_indices:
+            used.append(tuple(indices))
+            return 0.0
+        return math.inf
+
+    params = (
+        IODParams.builder()
+        .n_noise_realizations(0)
+        .max_triplets(1)
+        .triplet_scorer(forcing)
+        .build()
+    )
+    assert params.triplet_scorer is forcing
+
+    g, rms = traj_set[key].estimate_best_orbit(pyoutfit_env, params, seed=1)
+    assert used == [target_indices]
+    assert math.isfinite(rms)
+    epoch = g.to_dict()["elements"]["reference_epoch"]
+    assert epoch == pytest.approx(target_epochs[1], abs=0.05)
+
+
+def test_triplet_scorer_exception_aborts_only_that_trajectory(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    """Scorer failures are isolated per trajectory and surfaced with a dedicated kind."""
+    traj_set, counts = small_traj_set
+    bad_key = next(iter(counts))
+
+    def scorer(epochs, indices):
+        if len(traj_set[bad_key]) and epochs[0] == traj_set[bad_key][0][0]:
+            raise ValueError("boom")
+        return 0.0
+
+    params = IODParams.builder().n_noise_realizations(0).triplet_scorer(scorer).build()
+
+    with pytest.raises(pf.TripletScorerError, match="boom"):
+        traj_set[bad_key].estimate_best_orbit(pyoutfit_env, params, seed=3)
+
+    ok, err = traj_set.estimate_all_orbits(pyoutfit_env, params, seed=3)
+    assert bad_key in err
+    assert err[bad_key].startswith("TripletScorerError:")
+    assert len(ok) + len(err) == len(counts)
+
+
+def test_triplet_scorer_rejected_in_parallel_mode():
+    with pytest.raises(ValueError, match="triplet_scorer"):
+        IODParams.builder().triplet_scorer(lambda e, i: 0.0).do_parallel().build()
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_triplet_scorer_fallback_runs_sequentially_after_parallel_params(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    """
+    A fallback runs in its own mode: after a parallel primary pass, a sequential
+    fallback with a scorer is called on the calling thread, never on rayon workers.
+    """
+    traj_set, counts = small_traj_set
+    threads = set()
+
+    def scorer(epochs, indices):
+        threads.add(threading.get_ident())
+        return 0.0
+
+    strict = IODParams.builder().n_noise_realizations(0).max_ecc(1e-4).do_parallel().build()
+    relaxed = IODParams.builder().n_noise_realizations(0).triplet_scorer(scorer).build()
+
+    ok, err = traj_set.estimate_all_orbits(
+        pyoutfit_env, strict, seed=5, fallback_params=[relaxed]
+    )
+    assert len(ok) + len(err) == len(counts)
+    assert any(g.params_index == 1 for g, _ in ok.values())
+    assert threads == {threading.get_ident()}
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_rms_ties_resolve_identically_in_sequential_and_parallel_runs(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    traj_data: Tuple[np.ndarray, np.ndarray, np.ndarray, np.ndarray],
+):
+    """
+    Every observation is duplicated, so that triplets differing only by which copy
+    they use have the same score and produce numerically identical RMS. The
+    documented tie-break must pick the same solution on both batch paths.
+    """
+    tid, ra_deg, dec_deg, mjd_tt = (np.repeat(a, 2) for a in traj_data)
+
+    def symmetric_set() -> TrajectorySet:
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+        )
+
+    def params(builder) -> IODParams:
+        return builder.n_noise_realizations(0).max_triplets(50).batch_size(1).build()
+
+    ok_seq, err_seq = symmetric_set().estimate_all_orbits(
+        pyoutfit_env, params(IODParams.builder().do_sequential()), seed=42
+    )
+    ok_par, err_par = symmetric_set().estimate_all_orbits(
+        pyoutfit_env, params(IODParams.builder().do_parallel()), seed=42
+    )
+
+    assert err_seq == err_par == {}
+    assert set(ok_seq) == set(ok_par) == set(tid.tolist())
+    for key, (g_seq, rms_seq) in ok_seq.items():
+        g_par, rms_par = ok_par[key]
+        assert rms_seq == rms_par
+        assert g_seq.to_dict() == g_par.to_dict()
+
+        # The single-trajectory estimator ranks the candidates the same way.
+        g_one, rms_one = symmetric_set()[key].estimate_best_orbit(
+            pyoutfit_env, params(IODParams.builder()), seed=7
+        )
+        assert rms_one == pytest.approx(rms_seq, rel=1e-12, abs=1e-12)
+        _compare_orbit_dicts_approx(g_one.to_dict(), g_seq.to_dict())
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_fit_statistics_match_residuals(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    """FitStatistics aggregates the residual API and combines into the reported total."""
+    traj_set, counts = small_traj_set
+    key = next(k for k, n in counts.items() if n >= 4)
+    obs = traj_set[key]
+
+    params = IODParams.builder().n_noise_realizations(0).max_triplets(50).build()
+    g, rms = obs.estimate_best_orbit(pyoutfit_env, params, seed=123)
+    assert isinstance(rms, float)
+
+    stats = g.fit_statistics
+    assert isinstance(stats, pf.FitStatistics)
+
+    mjd, ra_res, dec_res = obs.residuals(pyoutfit_env, g)
+    assert len(mjd) == len(ra_res) == len(dec_res) == len(obs) == stats.n_points
+
+    rms_ra = math.sqrt(np.mean(ra_res**2))
+    rms_dec = math.sqrt(np.mean(dec_res**2))
+    assert stats.rms_ra_arcsec == pytest.approx(rms_ra, rel=1e-9)
+    assert stats.rms_dec_arcsec == pytest.approx(rms_dec, rel=1e-9)
+    assert stats.rms_arcsec == pytest.approx(
+        math.sqrt((rms_ra**2 + rms_dec**2) / 2.0), rel=1e-9
+    )
+
+    assert stats.chi2 > 0.0
+    assert stats.reduced_chi2 == pytest.approx(stats.chi2 / (2 * stats.n_points - 6))
+
+    d = g.to_dict()["fit_statistics"]
+    assert d == stats.to_dict()
+
+
+def test_fit_statistics_absent_on_user_built_result():
+    k = pf.KeplerianElements(
+        reference_epoch=60000.0,
+        semi_major_axis=2.5,
+        eccentricity=0.1,
+        inclination=0.2,
+        ascending_node_longitude=0.3,
+        periapsis_argument=0.4,
+        mean_anomaly=0.5,
+    )
+    g = pf.GaussResult.from_keplerian(k)
+    assert g.fit_statistics is None
+    assert g.to_dict()["fit_statistics"] is None
+
+
+def test_observations_dict_units_both_systems(
+    small_traj_set: Tuple[TrajectorySet, dict]
+):
+    """Observation exports convert every angle column together and label them."""
+    traj_set, _ = small_traj_set
+    obs = traj_set[next(iter(traj_set.keys()))]
+    mjd, ra, dec, sra, sdec = obs.to_numpy()
+
+    d = obs.to_dict()
+    assert "units" not in d
+    np.testing.assert_allclose(d["ra"], ra)
+
+    d_deg = obs.to_dict(degrees=True)
+    assert d_deg["units"] == {
+        "mjd_tt": "MJD_TT",
+        "ra": "deg",
+        "dec": "deg",
+        "sigma_ra": "deg",
+        "sigma_dec": "deg",
+    }
+    np.testing.assert_allclose(d_deg["mjd_tt"], mjd)
+    for col, ref in (("ra", ra), ("dec", dec), ("sigma_ra", sra), ("sigma_dec", sdec)):
+        np.testing.assert_allclose(np.radians(d_deg[col]), ref, rtol=1e-14)
+
+    parsed = json.loads(obs.to_json())
+    assert parsed["units"]["ra"] == "rad"
+    np.testing.assert_allclose(parsed["dec"], dec)
+
+
+def test_gauss_result_json_round_trip_with_statistics_units(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    traj_set, counts = small_traj_set
+    key = next(k for k, n in counts.items() if n >= 4)
+    params = IODParams.builder().n_noise_realizations(0).max_triplets(50).build()
+    g, _ = traj_set[key].estimate_best_orbit(pyoutfit_env, params, seed=123)
+
+    d = g.to_dict(include_units=True)
+    assert d["fit_statistics"]["units"]["rms_arcsec"] == "arcsec"
+
+    for degrees in (False, True):
+        back = pf.GaussResult.from_json(g.to_json(degrees=degrees))
+        _compare_orbit_dicts_approx(back.to_dict(), g.to_dict(), rtol=1e-12, atol=1e-12)
+
+
+def _naive_gauss(pos: np.ndarray, los: np.ndarray, t: np.ndarray):
+    """
+    Textbook Gauss method (Curtis, Algorithm 5.5) on a triplet, heliocentric.
+
+    Returns one `(r2, v2)` state per admissible root of the 8th-degree polynomial.
+    """
+    mu = pf.GAUSS_GRAV**2
+    tau1, tau3 = t[0] - t[1], t[2] - t[1]
+    tau = tau3 - tau1
+    p1, p2, p3 = np.cross(los[1], los[2]), np.cross(los[0], los[2]), np.cross(los[0], los[1])
+    d0 = los[0] @ p1
+    d = np.array([[pos[i] @ p for p in (p1, p2, p3)] for i in range(3)])
+
+    a_ = (-d[0, 1] * tau3 / tau + d[1, 1] + d[2, 1] * tau1 / tau) / d0
+    b_ = (d[0, 1] * (tau3**2 - tau**2) * tau3 / tau + d[2, 1] * (tau**2 - tau1**2) * tau1 / tau) / (
+        6.0 * d0
+    )
+    e_ = pos[1] @ los[1]
+    r2sq = pos[1] @ pos[1]
+    coeffs = [1, 0, -(a_**2 + 2 * a_ * e_ + r2sq), 0, 0, -2 * mu * b_ * (a_ + e_), 0, 0, -(mu**2) * b_**2]
+
+    states = []
+    for root in np.roots(coeffs):
+        if abs(root.imag) > 1e-12 or root.real <= 0:
+            continue
+        r2 = root.real
+        rho2 = a_ + mu * b_ / r2**3
+        if rho2 <= 0:
+            continue
+        rho1 = (
+            (6 * (d[2, 0] * tau1 / tau3 + d[1, 0] * tau / tau3) * r2**3
+             + mu * d[2, 0] * (tau**2 - tau1**2) * tau1 / tau3)
+            / (6 * r2**3 + mu * (tau**2 - tau3**2))
+            - d[0, 0]
+        ) / d0
+        rho3 = (
+            (6 * (d[0, 2] * tau3 / tau1 - d[1, 2] * tau / tau1) * r2**3
+             + mu * d[0, 2] * (tau**2 - tau3**2) * tau3 / tau1)
+            / (6 * r2**3 + mu * (tau**2 - tau1**2))
+            - d[2, 2]
+        ) / d0
+        r = [pos[0] + rho1 * los[0], pos[1] + rho2 * los[1], pos[2] + rho3 * los[2]]
+        f1 = 1 - 0.5 * mu * tau1**2 / r2**3
+        f3 = 1 - 0.5 * mu * tau3**2 / r2**3
+        g1 = tau1 - mu * tau1**3 / (6 * r2**3)
+        g3 = tau3 - mu * tau3**3 / (6 * r2**3)
+        v2 = (-f3 * r[0] + f1 * r[2]) / (f1 * g3 - f3 * g1)
+        states.append((r[1], v2))
+    return states
+
+
+def _semi_major_axis_and_eccentricity(r: np.ndarray, v: np.ndarray):
+    mu = pf.GAUSS_GRAV**2
+    rn = np.linalg.norm(r)
+    a = 1.0 / (2.0 / rn - (v @ v) / mu)
+    e_vec = ((v @ v) / mu - 1.0 / rn) * r - (r @ v) / mu * v
+    return a, np.linalg.norm(e_vec)
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_geometry_shapes_and_unit_line_of_sight(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict], ZTF_observatory: Observer
+):
+    traj_set, counts = small_traj_set
+    key = next(iter(counts))
+    obs = traj_set[key]
+    n = counts[key]
+
+    geo = obs.geometry(pyoutfit_env)
+    assert geo["observer_position"].shape == (n, 3)
+    assert geo["los"].shape == (n, 3)
+    np.testing.assert_allclose(np.linalg.norm(geo["los"], axis=1), 1.0, rtol=1e-14)
+    # Observers are ~1 AU from the Sun.
+    assert np.all(np.abs(np.linalg.norm(geo["observer_position"], axis=1) - 1.0) < 0.02)
+
+    mjd_tt = obs.to_numpy()[0]
+    np.testing.assert_array_equal(geo["mjd_tt"], mjd_tt)
+    assert np.all(np.abs(geo["mjd_tdb"] - mjd_tt) * 86400.0 < 2e-3)
+
+    # Recomputing for the ingestion site reproduces the stored positions.
+    again = obs.geometry(pyoutfit_env, ZTF_observatory)
+    np.testing.assert_allclose(
+        again["observer_position"], geo["observer_position"], rtol=0, atol=1e-12
+    )
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_geometry_feeds_naive_gauss(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer, traj_data
+):
+    """A textbook Gauss solver on `geometry()` reproduces the crate's orbit on a clean triplet."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    rows = np.flatnonzero(tid == 0)[[0, 4, 5]]
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        np.ascontiguousarray(tid[rows]),
+        np.ascontiguousarray(ra_deg[rows]),
+        np.ascontiguousarray(dec_deg[rows]),
+        0.5,
+        0.5,
+        np.ascontiguousarray(mjd_tt[rows]),
+        ZTF_observatory,
+    )
+    obs = ts[0]
+    params = IODParams.builder().n_noise_realizations(0).build()
+    g, _ = obs.estimate_best_orbit(pyoutfit_env, params, seed=1)
+    if g.keplerian() is not None:
+        a_ref, e_ref = g.keplerian().semi_major_axis, g.keplerian().eccentricity
+    else:
+        q = g.equinoctial()
+        a_ref = q.semi_major_axis
+        e_ref = math.hypot(q.eccentricity_sin_lon, q.eccentricity_cos_lon)
+
+    geo = obs.geometry(pyoutfit_env)
+    states = _naive_gauss(geo["observer_position"], geo["los"], geo["mjd_tt"])
+    assert states, "no admissible root"
+    candidates = [_semi_major_axis_and_eccentricity(r, v) for r, v in states]
+    best = min(candidates, key=lambda ae: abs(ae[0] - a_ref))
+    assert best[0] == pytest.approx(a_ref, rel=5e-2)
+    assert best[1] == pytest.approx(e_ref, abs=5e-2)
+
+
+def _reference_epoch(g) -> float:
+    return g.to_dict()["elements"]["reference_epoch"]
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_output_epoch_propagates_and_is_reversible(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    traj_set, counts = small_traj_set
+    key = next(k for k, n in counts.items() if n >= 4)
+    obs = traj_set[key]
+
+    base = IODParams.builder().n_noise_realizations(0).max_triplets(50)
+    g_fit, rms_fit = obs.estimate_best_orbit(pyoutfit_env, base.build(), seed=123)
+    fit_epoch = _reference_epoch(g_fit)
+    assert g_fit.fit_epoch == fit_epoch
+
+    target = fit_epoch + 200.0
+    params = base.output_epoch(target).build()
+    g, rms = obs.estimate_best_orbit(pyoutfit_env, params, seed=123)
+
+    assert _reference_epoch(g) == pytest.approx(target, abs=1e-12)
+    assert g.fit_epoch == pytest.approx(fit_epoch, abs=1e-12)
+    assert g.to_dict()["fit_epoch"] == g.fit_epoch
+    assert rms == pytest.approx(rms_fit)
+    assert g.fit_statistics.to_dict() == pytest.approx(g_fit.fit_statistics.to_dict())
+    assert g.to_dict()["elements"] != pytest.approx(g_fit.to_dict()["elements"])
+
+    # The propagated orbit describes the same motion: same residuals, and
+    # propagating back restores the fitted elements.
+    _, ra_fit, dec_fit = obs.residuals(pyoutfit_env, g_fit)
+    _, ra, dec = obs.residuals(pyoutfit_env, g)
+    np.testing.assert_allclose(ra, ra_fit, atol=1e-6)
+    np.testing.assert_allclose(dec, dec_fit, atol=1e-6)
+
+    back = g.propagate(g.fit_epoch)
+    _compare_orbit_dicts_approx(back.to_dict(), g_fit.to_dict(), rtol=1e-9, atol=1e-9)
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+@pytest.mark.parametrize("choice", ["first_obs", "last_obs", "middle"])
+def test_output_epoch_relative_choices_in_batch(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict], choice: str
+):
+    traj_set, _ = small_traj_set
+    params = (
+        IODParams.builder()
+        .n_noise_realizations(0)
+        .max_triplets(50)
+        .output_epoch(choice)
+        .build()
+    )
+    ok, _ = traj_set.estimate_all_orbits(pyoutfit_env, params, seed=7)
+    assert ok
+
+    for key, (g, _) in ok.items():
+        mjd = traj_set[key].to_dict()["mjd_tt"]
+        expected = {
+            "first_obs": min(mjd),
+            "last_obs": max(mjd),
+            "middle": 0.5 * (min(mjd) + max(mjd)),
+        }[choice]
+        # TDB − TT stays below 2 ms.
+        assert _reference_epoch(g) == pytest.approx(expected, abs=1e-7)
+        assert g.fit_epoch is not None
+        assert g.propagate(g.fit_epoch).to_dict()["elements"]["reference_epoch"] == (
+            pytest.approx(g.fit_epoch)
+        )
+
+
+def test_fit_epoch_absent_on_user_built_result_and_round_trips():
+    k = pf.KeplerianElements(
+        reference_epoch=60000.0,
+        semi_major_axis=2.5,
+        eccentricity=0.1,
+        inclination=0.2,
+        ascending_node_longitude=0.3,
+        periapsis_argument=0.4,
+        mean_anomaly=0.5,
+    )
+    g = pf.GaussResult.from_keplerian(k)
+    assert g.fit_epoch is None
+
+    moved = g.propagate(60100.0)
+    e = moved.to_dict()["elements"]
+    n = pf.GAUSS_GRAV / 2.5**1.5
+    assert e["reference_epoch"] == pytest.approx(60100.0)
+    assert e["mean_anomaly"] == pytest.approx(0.5 + 100.0 * n, rel=1e-12)
+    assert e["semi_major_axis"] == pytest.approx(2.5, rel=1e-12)
+
+    d = dict(g.to_dict(), fit_epoch=59990.0)
+    assert pf.GaussResult.from_dict(d).fit_epoch == 59990.0
+
+
+def _dense_arc(env, observer, orbit, mjd):
+    """Observations of `orbit` at `mjd`, built from the exact residual-free positions."""
+    n = mjd.size
+    ra0 = np.full(n, 30.0)
+    dec0 = np.full(n, 20.0)
+    probe = TrajectorySet.from_numpy_degrees(
+        env, np.zeros(n, dtype=np.uint32), ra0, dec0, 0.5, 0.5, mjd, observer
+    )
+    epochs, dra, ddec = probe[0].residuals(env, orbit)
+    dec = dec0 - ddec / 3600.0
+    ra = np.mod(ra0 - dra / 3600.0 / np.cos(np.radians(dec0)), 360.0)
+    return TrajectorySet.from_numpy_degrees(
+        env, np.zeros(n, dtype=np.uint32), ra, dec, 0.5, 0.5, epochs, observer
+    )[0]
+
+
+def _element_error(g, truth) -> float:
+    """Relative semi-major axis error plus absolute eccentricity and inclination errors."""
+    epoch = _reference_epoch(truth)
+    k = g.propagate(epoch).keplerian()
+    t = truth.keplerian()
+    return (
+        abs(k.semi_major_axis - t.semi_major_axis) / t.semi_major_axis
+        + abs(k.eccentricity - t.eccentricity)
+        + abs(k.inclination - t.inclination)
+    )
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_leverage_subsample_is_no_worse_than_uniform(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    small_traj_set: Tuple[TrajectorySet, dict],
+):
+    traj_set, _ = small_traj_set
+    base = IODParams.builder().n_noise_realizations(0).max_triplets(50)
+    ok, _ = traj_set.estimate_all_orbits(pyoutfit_env, base.build(), seed=42)
+    truth = next(iter(ok.values()))[0]
+
+    # Dense arc: three observations a night, every night for a month.
+    start = _reference_epoch(truth)
+    mjd = np.concatenate([start + d + np.array([0.0, 0.02, 0.04]) for d in range(30)])
+    obs = _dense_arc(pyoutfit_env, ZTF_observatory, truth, mjd)
+
+    n_keep = 12
+    errors = {}
+    for strategy in ("uniform", "leverage"):
+        params = base.max_obs_for_triplets(n_keep).subsample(strategy).build()
+        g, _ = obs.estimate_best_orbit(pyoutfit_env, params, seed=42)
+        kept = g.subsample_indices
+        assert len(kept) == n_keep
+        assert kept[0] == 0 and kept[-1] == mjd.size - 1
+        assert kept == sorted(set(kept))
+        errors[strategy] = _element_error(g, truth)
+
+        # Deterministic selection.
+        again, _ = obs.estimate_best_orbit(pyoutfit_env, params, seed=7)
+        assert again.subsample_indices == kept
+
+    assert errors["leverage"] <= errors["uniform"] * (1.0 + 1e-6) + 1e-9
+
+
+def test_subsample_indices_absent_on_short_arcs(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    traj_set, counts = small_traj_set
+    key = next(k for k, n in counts.items() if n >= 4)
+    params = IODParams.builder().n_noise_realizations(0).subsample("leverage").build()
+    g, _ = traj_set[key].estimate_best_orbit(pyoutfit_env, params, seed=1)
+    assert g.subsample_indices is None
@@ -0,0 +1,146 @@
+import math
+from pathlib import Path
+
+import numpy as np
+import pytest
+
+from py_outfit import EquinoctialElements, KeplerianElements, read_orbfit_elements
+
+REFERENCE = Path(__file__).parent / "data" / "orbfit_reference.eq0"
+
+
+def _fields(elements) -> list[float]:
+    if isinstance(elements, KeplerianElements):
+        names = ("semi_major_axis", "eccentricity", "inclination", "ascending_node_longitude",
+                 "periapsis_argument", "mean_anomaly")
+    else:
+        names = ("semi_major_axis", "eccentricity_sin_lon", "eccentricity_cos_lon",
+                 "tan_half_incl_sin_node", "tan_half_incl_cos_node", "mean_longitude")
+    return [getattr(elements, n) for n in names]
+
+
+def test_read_orbfit_reference_file():
+    """
+    EQU and KEP records of an OEF2.0 file, converted to the units of the bindings.
+    """
+    orbits = read_orbfit_elements(REFERENCE)
+    assert list(orbits) == ["433", "1"]
+
+    eros = orbits["433"]
+    assert isinstance(eros, EquinoctialElements)
+    assert eros.semi_major_axis == 1.458120998474684
+    assert eros.eccentricity_sin_lon == -0.102212887373291
+    assert eros.tan_half_incl_cos_node == 0.052691042230453
+    assert eros.mean_longitude == pytest.approx(math.radians(293.962622580116081), rel=1e-15)
+    # MJD TDT in the file, TDB on the element object.
+    assert eros.reference_epoch_tt == pytest.approx(59600.0, abs=1e-9)
+    assert abs(eros.reference_epoch - 59600.0) * 86400.0 < 2e-3
+
+    ceres = orbits["1"]
+    assert isinstance(ceres, KeplerianElements)
+    assert ceres.semi_major_axis == 2.7660965924362
+    assert ceres.inclination == pytest.approx(math.radians(10.5878745880466), rel=1e-15)
+    assert ceres.mean_anomaly == pytest.approx(math.radians(291.3776143098014), rel=1e-15)
+
+
+def _records(keyword: str) -> list[float]:
+    """Values of the `keyword` records of the reference file (only 433 has them)."""
+    values = []
+    for line in REFERENCE.read_text().splitlines():
+        tokens = line.split()
+        if tokens and tokens[0] == keyword:
+            values.extend(float(t) for t in tokens[1:])
+    return values
+
+
+def test_read_orbfit_covariance():
+    orbits = read_orbfit_elements(REFERENCE, covariance=True)
+    eros, cov = orbits["433"]
+    assert isinstance(eros, EquinoctialElements)
+    assert cov.shape == (6, 6) and cov.dtype == np.float64
+    np.testing.assert_array_equal(cov, cov.T)
+
+    # Back to the units of the file (the mean longitude in degrees).
+    scale = np.array([1.0, 1.0, 1.0, 1.0, 1.0, math.radians(1.0)])
+    cov_file = cov / np.outer(scale, scale)
+    upper = np.triu_indices(6)
+    np.testing.assert_allclose(cov_file[upper], _records("COV"), rtol=1e-14)
+
+    # Consistent with the RMS and COR records written next to it.
+    sigma = np.sqrt(np.diag(cov_file))
+    np.testing.assert_allclose(sigma, _records("RMS"), rtol=1e-5)
+    cor = cov_file / np.outer(sigma, sigma)
+    np.testing.assert_allclose(cor[upper], _records("COR"), rtol=0, atol=1e-12)
+
+    # A covariance: positive definite, correlations within [-1, 1].
+    assert np.all(np.abs(cor) <= 1.0 + 1e-12)
+    assert np.linalg.eigvalsh(cor).min() > 0.0
+
+    ceres, none = orbits["1"]
+    assert isinstance(ceres, KeplerianElements)
+    assert none is None
+
+
+def test_orbfit_string_round_trip(tmp_path: Path):
+    """
+    Records written by `to_orbfit_string` read back to the same elements, as a single
+    file or appended to the reference file.
+    """
+    orbits = read_orbfit_elements(REFERENCE)
+    path = tmp_path / "out.eq0"
+    path.write_text(
+        orbits["433"].to_orbfit_string("433")
+        + orbits["1"].to_orbfit_string("1", header=False)
+        + orbits["1"].to_equinoctial().to_orbfit_string("2015AB", header=False)
+    )
+    text = path.read_text()
+    assert text.startswith("format  = 'OEF2.0'")
+    assert text.count("END_OF_HEADER") == 1
+    assert " EQU " in text and " KEP " in text and " TDT\n" in text
+
+    back = read_orbfit_elements(path)
+    assert list(back) == ["433", "1", "2015AB"]
+    for name in ("433", "1"):
+        assert type(back[name]) is type(orbits[name])
+        np.testing.assert_allclose(_fields(back[name]), _fields(orbits[name]), rtol=1e-13)
+        assert back[name].reference_epoch == pytest.approx(orbits[name].reference_epoch, abs=1e-9)
+    np.testing.assert_allclose(
+        _fields(back["2015AB"]), _fields(orbits["1"].to_equinoctial()), rtol=1e-13
+    )
+
+    k = KeplerianElements(60000.0, 1.5, 0.1, 0.2, -0.5, 7.0, 1.0)
+    (k2,) = read_orbfit_elements(_write(tmp_path, k.to_orbfit_string("x"))).values()
+    # Angles are written in [0, 360) degrees.
+    assert k2.ascending_node_longitude == pytest.approx(-0.5 + 2 * math.pi, rel=1e-13)
+    assert k2.periapsis_argument == pytest.approx(7.0 - 2 * math.pi, rel=1e-13)
+
+    with pytest.raises(ValueError, match="whitespace"):
+        k.to_orbfit_string("2015 AB")
+
+
+def _write(tmp_path: Path, text: str) -> Path:
+    path = tmp_path / "case.eq0"
+    path.write_text(text)
+    return path
+
+
+def test_read_orbfit_rejects_unsupported_content(tmp_path: Path):
+    header = "format  = 'OEF2.0'\nrefsys  = ECLM J2000\nEND_OF_HEADER\n"
+    kep = " KEP 1.5 0.1 10.0 20.0 30.0 40.0\n"
+    epoch = " MJD 60000.0 TDT\n"
+
+    cases = {
+        "unsupported element record COM": header + "c1\n COM 1.0 0.9 10.0 20.0 30.0 60000.0\n" + epoch,
+        "unsupported reference system": header.replace("ECLM", "EQUM") + "x\n" + kep + epoch,
+        "unsupported time scale": header + "x\n" + kep + " MJD 60000.0 UTC\n",
+        'line 4: object "x" has no MJD epoch': header + "x\n" + kep,
+        "KEP expects 6 values, got 5": header + "x\n KEP 1.5 0.1 10.0 20.0 30.0\n" + epoch,
+        "duplicate designation": header + "x\n" + kep + epoch + "x\n" + kep + epoch,
+        "has 3 COV terms": header + "x\n" + kep + epoch + " COV 1.0 2.0 3.0\n",
+    }
+    for message, text in cases.items():
+        with pytest.raises(ValueError, match=message):
+            read_orbfit_elements(_write(tmp_path, text))
+
+    with pytest.raises(OSError):
+        read_orbfit_elements(tmp_path / "missing.eq0")
@@ -0,0 +1,909 @@
+import math
+import sys
+from typing import Tuple
+
+import numpy as np
+import pytest
+
+from py_outfit import (
+    GaussResult,
+    IODParams,
+    KeplerianElements,
+    Observer,
+    OrbitResults,
+    PyOutfit,
+    RADSEC,
+    ResultRow,
+    TrajectorySet,
+)
+
+
+def _split_set(env: PyOutfit, observer: Observer, traj_data, mjd_cut: float):
+    """Build (old, new, full) TrajectorySets, splitting observations at `mjd_cut`."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+
+    def build(mask):
+        return TrajectorySet.from_numpy_degrees(
+            env,
+            np.ascontiguousarray(tid[mask]),
+            np.ascontiguousarray(ra_deg[mask]),
+            np.ascontiguousarray(dec_deg[mask]),
+            0.5,
+            0.5,
+            np.ascontiguousarray(mjd_tt[mask]),
+            observer,
+        )
+
+    old = mjd_tt < mjd_cut
+    return build(old), build(~old), build(np.ones_like(old))
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_estimate_orbits_matches_dict_api(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    traj_set, counts = small_traj_set
+    params = IODParams.builder().n_noise_realizations(0).build()
+
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=7)
+    assert isinstance(results, OrbitResults)
+    assert len(results) == len(counts)
+    assert sorted(results.keys()) == sorted(counts)
+
+    ok = results.ok()
+    err = results.errors()
+    assert len(ok) + len(err) == len(counts)
+    for key, (g, rms) in ok.items():
+        assert key in results
+        assert results[key][1] == pytest.approx(rms)
+        assert len(results.observations(key)) == counts[key]
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_update_with_refines_and_reports_paths(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer, traj_data
+):
+    """
+    Adding the late observations incrementally must give orbits fitting the combined
+    arcs at least as well as a full re-fit.
+    """
+    mjd_cut = 58793.5
+    old_set, new_set, full_set = _split_set(
+        pyoutfit_env, ZTF_observatory, traj_data, mjd_cut
+    )
+    params = IODParams.builder().n_noise_realizations(5).build()
+
+    previous = old_set.estimate_orbits(pyoutfit_env, params, seed=11)
+
+    updated, paths = previous.update_with(pyoutfit_env, new_set, params, seed=11)
+    refit = full_set.estimate_orbits(pyoutfit_env, params, seed=11)
+
+    # Every object is reported, and objects without new points are carried over.
+    assert set(paths) == set(previous.keys()) | set(new_set.keys())
+    for key in previous.keys():
+        if key not in new_set:
+            assert paths[key] == "unchanged"
+    assert set(paths.values()) <= {"incremental", "full", "new", "unchanged"}
+
+    # The original results are left untouched; the update sees the combined arcs.
+    for key in new_set.keys():
+        assert len(updated.observations(key)) == len(full_set[key])
+        if key in previous:
+            assert len(previous.observations(key)) < len(full_set[key])
+
+    # The incremental path fits the whole combined arc: its RMS is the one of its fit
+    # statistics, and no worse over that arc than the orbit of a full re-fit.
+    for key, path in paths.items():
+        if path != "incremental" or key not in refit.ok():
+            continue
+        g_inc, rms_inc = updated[key]
+        g_full, _ = refit[key]
+        stats_inc, stats_full = g_inc.fit_statistics, g_full.fit_statistics
+        assert math.isfinite(rms_inc)
+        assert rms_inc == pytest.approx(
+            math.sqrt(stats_inc.chi2 / (2 * stats_inc.n_points)), rel=1e-3
+        )
+        assert stats_inc.chi2 <= 1.01 * stats_full.chi2
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_collect_costs_single_triplet_and_summary(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    traj_data,
+    small_traj_set: Tuple[TrajectorySet, dict],
+):
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    params = IODParams.builder().n_noise_realizations(2).collect_costs(True).build()
+    assert params.collect_costs
+
+    # Three observations of object 0 spread over ~12 days: a single feasible triplet.
+    idx = np.flatnonzero(tid == 0)[[0, 3, 5]]
+    three = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        np.ascontiguousarray(tid[idx]),
+        np.ascontiguousarray(ra_deg[idx]),
+        np.ascontiguousarray(dec_deg[idx]),
+        0.5,
+        0.5,
+        np.ascontiguousarray(mjd_tt[idx]),
+        ZTF_observatory,
+    )
+    single = three.estimate_orbits(pyoutfit_env, params, seed=3)
+    costs = single.costs()[0]
+    assert costs.triplets_attempted == 1
+    assert costs.realizations == 3  # unperturbed triplet + 2 noisy copies
+    assert 0 <= costs.corrected_solutions <= costs.realizations
+    assert costs.wall_ms >= 0.0
+    if 0 in single.ok():
+        assert single[0][0].costs == costs
+
+    # Totals over a batch are the sums of the per-trajectory counters.
+    full, _ = small_traj_set
+    results = full.estimate_orbits(pyoutfit_env, params, seed=3)
+    per_traj = results.costs()
+    summary = results.summary()
+    assert set(per_traj) == set(results.keys())
+    assert summary["n_trajectories"] == len(results)
+    assert summary["n_ok"] + summary["n_err"] == len(results)
+    assert summary["n_costed"] == len(results)
+    for name in ("triplets_attempted", "realizations", "corrected_solutions"):
+        assert summary["costs"][name] == sum(getattr(c, name) for c in per_traj.values())
+    assert summary["costs"]["wall_ms"] == pytest.approx(
+        sum(c.wall_ms for c in per_traj.values())
+    )
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_costs_absent_by_default(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    traj_set, _ = small_traj_set
+    params = IODParams.builder().n_noise_realizations(0).build()
+    assert not params.collect_costs
+
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=7)
+    assert results.costs() == {}
+    assert results.summary()["costs"] is None
+    for g, _ in results.ok().values():
+        assert g.costs is None
+        assert g.to_dict()["costs"] is None
+
+    with pytest.raises(ValueError, match="collect_costs"):
+        IODParams.builder().collect_costs(True).do_parallel().build()
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_rejection_stats_sum_to_attempts(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    traj_set, _ = small_traj_set
+    reasons = {"spacing", "root_finding", "physical", "rms_cut"}
+
+    default = traj_set.estimate_orbits(
+        pyoutfit_env, IODParams.builder().n_noise_realizations(2).build(), seed=5
+    )
+    assert default.rejection_stats() is None
+
+    params = (
+        IODParams.builder()
+        .n_noise_realizations(2)
+        .collect_costs(True)
+        .collect_rejection_stats(True)
+        .build()
+    )
+    assert params.collect_rejection_stats
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=5)
+    stats = results.rejection_stats()
+    assert set(stats["rejected"]) == reasons and set(stats["examples"]) == reasons
+    assert stats["n_collected"] == len(results)
+    assert stats["selected"] == len(results.ok())
+    # Every realization is either rejected for one reason or selected.
+    assert sum(stats["rejected"].values()) + stats["selected"] == stats["attempts"]
+    assert stats["attempts"] == results.summary()["costs"]["realizations"]
+    for reason, ids in stats["examples"].items():
+        assert len(ids) <= 5 and ids == sorted(ids)
+        assert set(ids) <= set(results.keys())
+        assert bool(ids) == (stats["rejected"][reason] > 0)
+
+    # The parallel path merges the per-trajectory counters of its workers.
+    parallel = (
+        IODParams.builder()
+        .n_noise_realizations(2)
+        .collect_rejection_stats(True)
+        .do_parallel()
+        .build()
+    )
+    par = traj_set.estimate_orbits(pyoutfit_env, parallel, seed=5).rejection_stats()
+    assert par["n_collected"] == len(results)
+    assert sum(par["rejected"].values()) + par["selected"] == par["attempts"]
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_estimate_orbits_sweep_aligned_reproducible_and_ordered(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    traj_set, counts = small_traj_set
+    coarse = IODParams.builder().n_noise_realizations(0).build()
+    fine = IODParams.builder().n_noise_realizations(20).build()
+
+    runs, table = traj_set.estimate_orbits_sweep(pyoutfit_env, [coarse, fine], seed=5)
+    assert len(runs) == len(table) == 2
+    assert [row["config"] for row in table] == [0, 1]
+    for run, row in zip(runs, table):
+        assert isinstance(run, OrbitResults)
+        assert row["n_trajectories"] == len(counts)
+        assert row["n_ok"] == len(run.ok())
+        assert row["success_rate"] == pytest.approx(row["n_ok"] / len(counts))
+
+    # Each configuration is reproducible on its own (same seed, fresh copy of the set).
+    alone = traj_set.estimate_orbits_sweep(pyoutfit_env, [fine], seed=5)[0][0]
+    assert sorted(alone.ok()) == sorted(runs[1].ok())
+    for key, (_, rms) in alone.ok().items():
+        assert runs[1][key][1] == pytest.approx(rms)
+
+    # The unperturbed triplets are part of every realization set: more noise
+    # realizations can only lower each trajectory's best RMS.
+    for key, (_, rms) in runs[0].ok().items():
+        assert key in runs[1]
+        assert runs[1][key][1] <= rms + 1e-12
+    assert table[1]["success_rate"] >= table[0]["success_rate"]
+    for run, row in zip(runs, table):
+        assert row["median_rms"] == pytest.approx(
+            float(np.median([rms for _, rms in run.ok().values()]))
+        )
+    if sorted(runs[0].ok()) == sorted(runs[1].ok()):
+        assert table[1]["median_rms"] <= table[0]["median_rms"] + 1e-12
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_fallback_params_retry_failures_and_attribute_orbits(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer, traj_data
+):
+    """
+    Trajectories rejected by a near-circular `max_ecc` cut are recovered by the
+    relaxed fallback, which is reported through `GaussResult.params_index`.
+    """
+
+    def fresh():
+        return _split_set(pyoutfit_env, ZTF_observatory, traj_data, math.inf)[2]
+
+    strict = IODParams.builder().n_noise_realizations(0).max_ecc(1e-4).build()
+    relaxed = IODParams.builder().n_noise_realizations(0).build()
+
+    strict_ok, _ = fresh().estimate_all_orbits(pyoutfit_env, strict, seed=11)
+    relaxed_ok, _ = fresh().estimate_all_orbits(pyoutfit_env, relaxed, seed=11)
+    recovered = set(relaxed_ok) - set(strict_ok)
+    assert recovered
+    for g, _ in strict_ok.values():
+        assert g.params_index is None
+
+    ok, err = fresh().estimate_all_orbits(
+        pyoutfit_env, strict, seed=11, fallback_params=[strict, relaxed]
+    )
+    assert set(ok) == set(strict_ok) | set(relaxed_ok)
+    for key, (g, rms) in ok.items():
+        if key in strict_ok:
+            assert g.params_index == 0
+            assert rms == pytest.approx(strict_ok[key][1])
+        else:
+            # The repeated strict set fails again: the orbit comes from `relaxed`.
+            assert g.params_index == 2
+            assert rms == pytest.approx(relaxed_ok[key][1])
+    assert set(err) == set(traj_data[0].tolist()) - set(ok)
+
+    # Retries are seeded per (trajectory, attempt): same outcome in parallel, and
+    # through the OrbitResults entry point.
+    strict_par = IODParams.builder().n_noise_realizations(0).max_ecc(1e-4).do_parallel().build()
+    ok_par, _ = fresh().estimate_all_orbits(
+        pyoutfit_env, strict_par, seed=11, fallback_params=[strict, relaxed]
+    )
+    assert {k: g.params_index for k, (g, _) in ok_par.items()} == {
+        k: g.params_index for k, (g, _) in ok.items()
+    }
+    results = fresh().estimate_orbits(pyoutfit_env, strict, seed=11, fallback_params=[relaxed])
+    for key in recovered:
+        assert results[key][0].params_index == 1
+        assert results[key][1] == pytest.approx(ok[key][1])
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_initial_guesses_bypass_triplet_search(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer, traj_data
+):
+    """
+    A slightly perturbed orbit passed as initial guess is corrected back over the whole
+    arc; a guess on the wrong side of the Sun diverges and falls back to Gauss.
+    """
+
+    def fresh():
+        return _split_set(pyoutfit_env, ZTF_observatory, traj_data, math.inf)[2]
+
+    params = IODParams.builder().n_noise_realizations(0).build()
+    ok, _ = fresh().estimate_all_orbits(pyoutfit_env, params, seed=5)
+    assert all(g.iod_path is None for g, _ in ok.values())
+
+    keys = sorted(k for k, (g, _) in ok.items() if g.keplerian() is not None)
+    assert len(keys) >= 2
+    good, bad = keys[0], keys[1]
+    truth = ok[good][0].keplerian()
+    near = KeplerianElements(
+        truth.reference_epoch,
+        truth.semi_major_axis * (1.0 + 1e-3),
+        truth.eccentricity,
+        truth.inclination,
+        truth.ascending_node_longitude,
+        truth.periapsis_argument,
+        truth.mean_anomaly + 1e-3,
+    )
+    k = ok[bad][0].keplerian()
+    wrong = KeplerianElements(
+        k.reference_epoch, 40.0, 0.2, k.inclination, k.ascending_node_longitude,
+        k.periapsis_argument, k.mean_anomaly + math.pi,
+    )
+
+    guessed, _ = fresh().estimate_all_orbits(
+        pyoutfit_env, params, seed=5, initial_guesses={good: near, bad: wrong}
+    )
+    g, rms = guessed[good]
+    assert g.iod_path == "guess"
+    assert g.is_corrected()
+    # The corrector fits the whole arc: no worse than the Gauss orbit on it.
+    assert g.fit_statistics.rms_arcsec <= ok[good][0].fit_statistics.rms_arcsec * 1.01
+    fitted = g.keplerian()
+    assert fitted.semi_major_axis == pytest.approx(truth.semi_major_axis, rel=1e-2)
+    assert fitted.eccentricity == pytest.approx(truth.eccentricity, abs=1e-2)
+
+    g_bad, rms_bad = guessed[bad]
+    assert g_bad.iod_path == "fallback"
+    assert rms_bad == pytest.approx(ok[bad][1])
+    assert {g.iod_path for k, (g, _) in guessed.items() if k not in (good, bad)} == {"gauss"}
+
+    with pytest.raises(KeyError, match="unknown trajectory"):
+        fresh().estimate_all_orbits(pyoutfit_env, params, initial_guesses={10**6: near})
+    with pytest.raises(TypeError, match="KeplerianElements"):
+        fresh().estimate_all_orbits(pyoutfit_env, params, initial_guesses={good: 1.0})
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+@pytest.mark.parametrize("kind", ["SH", "D"])
+def test_find_duplicates_clusters_planted_copy(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer, traj_data, kind
+):
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    # Plant object 0 a second time under identifier 100.
+    dup = tid == 0
+    tid2 = np.concatenate([tid, np.full(dup.sum(), 100, dtype=np.uint32)])
+    traj_set = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        tid2,
+        np.concatenate([ra_deg, ra_deg[dup]]),
+        np.concatenate([dec_deg, dec_deg[dup]]),
+        0.5,
+        0.5,
+        np.concatenate([mjd_tt, mjd_tt[dup]]),
+        ZTF_observatory,
+    )
+    params = IODParams.builder().n_noise_realizations(0).build()
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=1)
+    if 0 not in results.ok():
+        pytest.skip("object 0 did not converge")
+
+    clusters = results.find_duplicates(d_max=0.05, kind=kind)
+    planted = [c for c in clusters if 0 in c]
+    assert len(planted) == 1 and 100 in planted[0]
+    assert [len(c) for c in clusters] == sorted((len(c) for c in clusters), reverse=True)
+    for c in clusters:
+        assert c == sorted(c)
+
+    # A tiny threshold still matches exact copies.
+    assert any({0, 100} <= set(c) for c in results.find_duplicates(d_max=1e-6, kind=kind))
+
+    with pytest.raises(ValueError):
+        results.find_duplicates(kind="nope")
+    with pytest.raises(ValueError):
+        results.find_duplicates(d_max=-1.0)
+
+
+def _fit_weighted(env: PyOutfit, traj_data, accuracy: float, sigma_ra=0.0):
+    """Fit `traj_data` from a ZTF-like site whose accuracy (arcsec) fills zero sigmas."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    site = Observer(
+        longitude=243.140213,
+        latitude=33.357336,
+        elevation=1663.96,
+        name=f"ZTF accuracy {accuracy}",
+        ra_accuracy=accuracy * RADSEC,
+        dec_accuracy=2.0 * accuracy * RADSEC,
+    )
+    traj_set = TrajectorySet.from_numpy_degrees(
+        env, tid, ra_deg, dec_deg, sigma_ra, 0.0, mjd_tt, site, bad_sigma="model"
+    )
+    params = IODParams.builder().n_noise_realizations(0).build()
+    return traj_set.estimate_orbits(env, params, seed=7)
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_effective_weights_follow_site_accuracy(pyoutfit_env: PyOutfit, traj_data):
+    fine = _fit_weighted(pyoutfit_env, traj_data, 0.3)
+    coarse = _fit_weighted(pyoutfit_env, traj_data, 0.6)
+    assert fine.ok() and sorted(fine.ok()) == sorted(coarse.ok())
+
+    table = fine.to_dict()
+    for key in fine.ok():
+        w_ra, w_dec = fine.effective_weights(key)
+        assert w_ra.shape == w_dec.shape == (len(fine.observations(key)),)
+        # The batch correction only inflates the uncertainties.
+        assert np.all(w_ra <= 1.0 / 0.3**2 * (1 + 1e-12))
+        np.testing.assert_allclose(w_dec, w_ra / 4.0)
+
+        c_ra, c_dec = coarse.effective_weights(key)
+        np.testing.assert_allclose(w_ra, 4.0 * c_ra)
+        np.testing.assert_allclose(w_dec, 4.0 * c_dec)
+
+        row = table["object_id"].index(key)
+        both = np.concatenate([w_ra, w_dec])
+        assert table["weight_min"][row] == pytest.approx(both.min())
+        assert table["weight_median"][row] == pytest.approx(np.median(both))
+        assert table["weight_max"][row] == pytest.approx(both.max())
+
+    for key in fine.errors():
+        with pytest.raises(KeyError, match="IOD failed"):
+            fine.effective_weights(key)
+        assert table["weight_min"][table["object_id"].index(key)] is None
+    with pytest.raises(KeyError):
+        fine.effective_weights(12345)
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_effective_weights_reflect_per_observation_sigmas(
+    pyoutfit_env: PyOutfit, traj_data
+):
+    sigma_ra = np.full(traj_data[0].size, 0.5)
+    uniform = _fit_weighted(pyoutfit_env, traj_data, 0.3, sigma_ra=sigma_ra)
+    # Observation 0 down-weighted 100x through a 10x larger uncertainty.
+    sigma_ra[0] = 5.0
+    downweighted = _fit_weighted(pyoutfit_env, traj_data, 0.3, sigma_ra=sigma_ra)
+
+    key = int(traj_data[0][0])
+    assert key in uniform.ok() and key in downweighted.ok()
+    mjd0 = traj_data[3][0]
+    epochs = downweighted.observations(key).to_numpy()[0]
+    i = int(np.flatnonzero(epochs == mjd0)[0])
+
+    w_ref, _ = uniform.effective_weights(key)
+    w, _ = downweighted.effective_weights(key)
+    assert w[i] == pytest.approx(w_ref[i] / 100.0)
+    np.testing.assert_allclose(np.delete(w, i), np.delete(w_ref, i))
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_rows_are_typed_views_of_successes_and_failures(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer, traj_data
+):
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    # Trajectory 9 has two observations: no triplet, so its IOD fails.
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        np.concatenate([tid, np.array([9, 9], dtype=np.uint32)]),
+        np.concatenate([ra_deg, ra_deg[:2]]),
+        np.concatenate([dec_deg, dec_deg[:2]]),
+        0.5,
+        0.5,
+        np.concatenate([mjd_tt, mjd_tt[:2] + 0.5]),
+        ZTF_observatory,
+    )
+    params = IODParams.builder().n_noise_realizations(0).collect_costs(True).build()
+    results = ts.estimate_orbits(pyoutfit_env, params, seed=7)
+    ok, err = results.ok(), results.errors()
+    assert 9 in err and ok
+
+    rows = list(results.rows())
+    assert [row.id for row in rows] == results.keys()
+    for row in rows:
+        assert isinstance(row, ResultRow)
+        assert row.n_obs == len(results.observations(row.id))
+        assert row.costs == results.costs()[row.id]
+        if row.ok:
+            g, rms = ok[row.id]
+            assert isinstance(row.orbit, GaussResult)
+            assert row.orbit.to_dict() == g.to_dict()
+            assert row.rms == rms
+            assert row.error_kind is None and row.error_message is None
+            assert "ok=True" in repr(row)
+        else:
+            assert row.orbit is None and row.rms is None
+            assert row.error_message == err[row.id]
+            assert isinstance(row.error_kind, str) and row.error_kind
+            assert row.error_kind in repr(row)
+    assert sum(row.ok for row in rows) == len(ok)
+
+    # Rows keep the results alive once the container itself is dropped.
+    rows = results.rows()
+    del results
+    assert len(list(rows)) == len(ok) + len(err)
+
+
+def _schema_names(export: str, degrees: bool = False):
+    import py_outfit
+
+    return [c["name"] for c in py_outfit.schemas(degrees=degrees)[export]]
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+@pytest.mark.parametrize("degrees", [False, True])
+def test_exports_match_declared_schemas(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict], degrees: bool
+):
+    import pandas as pd
+    import py_outfit
+
+    spec = py_outfit.schemas(degrees=degrees)
+    assert isinstance(spec["schema_version"], int)
+    assert spec["schema_version"] == py_outfit.SCHEMA_VERSION
+    for export in ("results", "observations", "trajectory_set"):
+        for col in spec[export]:
+            assert set(col) == {"name", "dtype", "unit", "nullable"}
+
+    traj_set, counts = small_traj_set
+    params = IODParams.builder().n_noise_realizations(0).build()
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=7)
+
+    exports = {
+        "results": results.to_dict(include_units=True, degrees=degrees),
+        "trajectory_set": traj_set.to_dict(include_units=True, degrees=degrees),
+        "observations": traj_set[next(iter(counts))].to_dict(
+            include_units=True, degrees=degrees
+        ),
+    }
+    for export, table in exports.items():
+        units = table.pop("units")
+        columns = spec[export]
+        assert list(table) == _schema_names(export, degrees)
+        assert units == {c["name"]: c["unit"] for c in columns if c["unit"] is not None}
+
+        df = pd.DataFrame(table)
+        assert list(df.columns) == [c["name"] for c in columns]
+        for c in columns:
+            if not c["nullable"]:
+                assert df[c["name"]].notna().all(), (export, c["name"])
+            if c["dtype"] == "float64":
+                values = [v for v in table[c["name"]] if v is not None]
+                assert all(isinstance(v, float) for v in values), (export, c["name"])
+
+    assert len(exports["results"]["object_id"]) == len(counts)
+    assert len(exports["trajectory_set"]["trajectory_id"]) == sum(counts.values())
+    for k, (g, rms) in results.ok().items():
+        row = exports["results"]["object_id"].index(k)
+        assert exports["results"]["rms"][row] == pytest.approx(rms)
+        assert exports["results"]["element_type"][row] == g.to_dict()["type"]
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_to_qtable_units_and_values(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    pytest.importorskip("astropy")
+    import astropy.units as u
+    from astropy.table import QTable
+    from astropy.time import Time
+
+    traj_set, _ = small_traj_set
+    params = IODParams.builder().n_noise_realizations(0).build()
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=7)
+
+    qt = results.to_qtable()
+    d = results.to_dict(degrees=True)
+    assert isinstance(qt, QTable)
+    assert qt.colnames == _schema_names("results", degrees=True)
+    assert len(qt) == len(results.keys())
+
+    assert isinstance(qt["reference_epoch"], Time)
+    assert qt["reference_epoch"].scale == "tdb"
+    assert qt["fit_epoch"].scale == "tdb"
+    assert qt["semi_major_axis"].unit == u.AU
+    assert qt["inclination"].unit == u.deg
+    assert qt["rms_arcsec"].unit == u.arcsec
+    assert qt["rms"].unit is None
+
+    ok_rows = [i for i, err in enumerate(d["error"]) if err is None][:3]
+    assert ok_rows
+    for i in ok_rows:
+        assert qt["object_id"][i] == d["object_id"][i]
+        assert qt["element_type"][i] == d["element_type"][i]
+        assert qt["rms"][i] == pytest.approx(d["rms"][i])
+        assert qt["rms_arcsec"][i].to_value(u.arcsec) == pytest.approx(d["rms_arcsec"][i])
+        assert qt["n_points"][i] == d["n_points"][i]
+        assert qt["reference_epoch"][i].mjd == pytest.approx(d["reference_epoch"][i])
+        assert qt["fit_epoch"][i].mjd == pytest.approx(d["fit_epoch"][i])
+        if d["element_type"][i] == "keplerian":
+            assert qt["semi_major_axis"][i].to_value(u.AU) == pytest.approx(
+                d["semi_major_axis"][i]
+            )
+            assert qt["inclination"][i].to_value(u.rad) == pytest.approx(
+                math.radians(d["inclination"][i])
+            )
+
+    rad = results.to_qtable(degrees=False)
+    assert rad["inclination"].unit == u.rad
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_exported_rows_rebuild_results(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    import pandas as pd
+    from py_outfit import GaussResult
+
+    traj_set, _ = small_traj_set
+    params = IODParams.builder().n_noise_realizations(0).collect_costs(True).build()
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=7)
+    ok = results.ok()
+    assert ok
+
+    for g, rms in ok.values():
+        back = GaussResult.from_dict(dict(g.to_dict(include_units=True), rms=rms))
+        assert back.to_dict() == g.to_dict()
+
+    for degrees in (False, True):
+        table = results.to_dict(include_units=True, degrees=degrees)
+        units = table.pop("units")
+        frame = pd.DataFrame(table)
+        for i, key in enumerate(table["object_id"]):
+            row = {name: column[i] for name, column in table.items()}
+            if key not in ok:
+                with pytest.raises(ValueError):
+                    GaussResult.from_row(row, units)
+                continue
+            expected = ok[key][0].to_dict()
+            for back in (
+                GaussResult.from_row(row, units),
+                GaussResult.from_row(frame.iloc[i], units),
+            ):
+                got = back.to_dict()
+                assert got["stage"] == expected["stage"]
+                assert got["type"] == expected["type"]
+                assert got["fit_epoch"] == pytest.approx(expected["fit_epoch"])
+                assert got["fit_statistics"] == pytest.approx(expected["fit_statistics"])
+                for name, value in expected["elements"].items():
+                    assert got["elements"][name] == pytest.approx(value, rel=1e-12), name
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_to_sqlite_matches_dict_export(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict], tmp_path
+):
+    import sqlite3
+
+    traj_set, _ = small_traj_set
+    params = IODParams.builder().n_noise_realizations(0).build()
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=7)
+    path = tmp_path / "catalogue.db"
+
+    assert results.to_sqlite(path, degrees=True) == len(results)
+    expected = results.to_dict(degrees=True)
+    with sqlite3.connect(path) as conn:
+        cursor = conn.execute('SELECT * FROM "orbits" ORDER BY object_id')
+        names = [c[0] for c in cursor.description]
+        rows = cursor.fetchall()
+        indexes = [r[1] for r in conn.execute('PRAGMA index_list("orbits")')]
+    conn.close()
+    assert names == _schema_names("results")
+    assert "orbits_object_id" in indexes
+    # REAL columns hold the exported doubles exactly.
+    for j, name in enumerate(names):
+        assert [row[j] for row in rows] == expected[name], name
+
+    with pytest.raises(ValueError, match="already exists"):
+        results.to_sqlite(path)
+    assert results.to_sqlite(path, if_exists="append") == len(results)
+    assert results.to_sqlite(path, if_exists="replace") == len(results)
+    with sqlite3.connect(path) as conn:
+        assert conn.execute('SELECT COUNT(*) FROM "orbits"').fetchone()[0] == len(results)
+        inclination = conn.execute(
+            'SELECT inclination FROM "orbits" WHERE inclination IS NOT NULL'
+        ).fetchone()[0]
+    conn.close()
+    assert inclination < math.pi
+
+    with pytest.raises(ValueError, match="if_exists"):
+        results.to_sqlite(path, if_exists="overwrite")
+    with pytest.raises(ValueError, match="columns"):
+        traj_set.observations_to_sqlite(pyoutfit_env, path, table="orbits", if_exists="append")
+
+
+def test_observations_to_sqlite_round_trip(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict], tmp_path
+):
+    import sqlite3
+
+    traj_set, _ = small_traj_set
+    path = tmp_path / "catalogue.db"
+    n = traj_set.observations_to_sqlite(pyoutfit_env, path)
+    assert n == traj_set.total_observations()
+
+    with sqlite3.connect(path) as conn:
+        rows = conn.execute('SELECT * FROM "observations"').fetchall()
+        declared = {r[1]: r[2] for r in conn.execute('PRAGMA table_info("observations")')}
+    conn.close()
+    assert list(declared) == _schema_names("trajectory_set_parquet")
+    assert declared["trajectory_id"] == "INTEGER" and declared["ra"] == "REAL"
+    assert len(rows) == n
+
+    for key in traj_set.keys():
+        got = np.array([row[1:6] for row in rows if row[0] == key])
+        expected = np.column_stack(traj_set[key].to_numpy())
+        np.testing.assert_array_equal(got, expected)
+    assert all(row[6] is None or len(row[6]) == 3 for row in rows)
+
+    # Database errors surface as OSError; a directory is not a database file.
+    with pytest.raises(OSError, match="SQLite database"):
+        traj_set.observations_to_sqlite(pyoutfit_env, tmp_path)
+
+
+def _predicted_by_residuals(env, observer, orbit, mjd):
+    """Apparent (RA, DEC) in degrees of `orbit` at `mjd`, from the residual API."""
+    n = mjd.size
+    ra0 = np.full(n, 30.0)
+    dec0 = np.full(n, 20.0)
+    probe = TrajectorySet.from_numpy_degrees(
+        env, np.zeros(n, dtype=np.uint32), ra0, dec0, 0.5, 0.5, mjd, observer
+    )
+    _, dra, ddec = probe[0].residuals(env, orbit)
+    dec = dec0 - ddec / 3600.0
+    ra = np.mod(ra0 - dra / 3600.0 / np.cos(np.radians(dec0)), 360.0)
+    return ra, dec
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_predict_all_matches_single_orbit_predictions(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    small_traj_set: Tuple[TrajectorySet, dict],
+    tmp_path,
+):
+    traj_set, _ = small_traj_set
+    params = IODParams.builder().n_noise_realizations(0).build()
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=7)
+    n_ok = len(results.ok())
+    assert n_ok
+
+    g0, _ = results[results.keys()[0]]
+    epochs = g0.fit_epoch + np.array([1.0, 1.5, 2.0, 10.0])
+    pred = results.predict_all(pyoutfit_env, ZTF_observatory, epochs)
+
+    assert list(pred["object_id"]) == list(results.ok().keys())
+    assert pred["ra"].shape == (n_ok, epochs.size)
+    assert pred["dec_rate"].shape == (n_ok, epochs.size)
+    np.testing.assert_array_equal(pred["mjd_tt"], epochs)
+
+    for row, key in list(enumerate(pred["object_id"]))[:3]:
+        if row in pred["failed_rows"]:
+            continue
+        ra, dec = _predicted_by_residuals(
+            pyoutfit_env, ZTF_observatory, results[key][0], epochs
+        )
+        d_ra = (pred["ra"][row] - ra + 180.0) % 360.0 - 180.0
+        np.testing.assert_allclose(d_ra * 3600.0, 0.0, atol=1e-3)
+        np.testing.assert_allclose(pred["dec"][row], dec, atol=1e-3 / 3600.0)
+
+        # Rates agree with a finite difference over a quarter of an hour.
+        _, dec2 = _predicted_by_residuals(
+            pyoutfit_env, ZTF_observatory, results[key][0], epochs[:1] + 0.01
+        )
+        expected = (dec2[0] - dec[0]) / 0.01
+        assert pred["dec_rate"][row, 0] == pytest.approx(expected, rel=1e-2, abs=1e-6)
+
+    path = tmp_path / "pred.parquet"
+    results.predict_all(pyoutfit_env, ZTF_observatory, epochs, path=str(path))
+    pq = pytest.importorskip("pyarrow.parquet")
+    table = pq.read_table(path)
+    assert table.num_rows == n_ok * epochs.size
+    assert table.column_names == ["object_id", "mjd_tt", "ra", "dec", "ra_rate", "dec_rate"]
+    np.testing.assert_allclose(
+        table.column("ra").to_numpy(), pred["ra"].ravel(), equal_nan=True
+    )
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_geocentric_astrometry_needs_topocentric_false(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer, traj_data
+):
+    """
+    Synthetic astrometry reduced to the geocentre of a near-Earth orbit: only the
+    ingestion with `topocentric=False` fits the true orbit, and both kinds of rows can
+    live in one set.
+    """
+    geocentre = pyoutfit_env.get_observer_from_mpc_code("500")
+    t0 = float(traj_data[3].min())
+    truth = GaussResult.from_keplerian(
+        KeplerianElements(t0, 1.3, 0.2, 0.3, 1.0, 0.5, 0.3), corrected=True
+    )
+    nights = t0 + np.repeat(np.arange(0.0, 20.0, 2.0), 3)
+    # Hours apart within a night, so that the diurnal parallax cannot be absorbed.
+    mjd = nights + np.tile([0.0, 0.15, 0.3], nights.size // 3)
+    ra_geo, dec_geo = _predicted_by_residuals(pyoutfit_env, geocentre, truth, mjd)
+    ra_topo, dec_topo = _predicted_by_residuals(pyoutfit_env, ZTF_observatory, truth, mjd)
+    tid = np.zeros(mjd.size, dtype=np.uint32)
+
+    def ingest(ra, dec, topocentric, ids=tid):
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, ids, ra, dec, 0.5, 0.5, mjd, ZTF_observatory,
+            topocentric=topocentric,
+        )
+
+    geo = ingest(ra_geo, dec_geo, False)
+    wrong = ingest(ra_geo, dec_geo, True)
+    assert not geo[0].topocentric(pyoutfit_env).any()
+    assert wrong[0].topocentric(pyoutfit_env).all()
+
+    # The residual machinery uses the site recorded at ingestion.
+    _, dra, ddec = geo[0].residuals(pyoutfit_env, truth)
+    assert np.abs(np.hypot(dra, ddec)).max() < 1e-3
+    _, dra, ddec = wrong[0].residuals(pyoutfit_env, truth)
+    assert np.abs(np.hypot(dra, ddec)).max() > 1.0
+
+    params = IODParams.builder().n_noise_realizations(0).build()
+    ok_geo, _ = geo.estimate_all_orbits(pyoutfit_env, params, seed=3)
+    g, rms = ok_geo[0]
+    assert g.keplerian().semi_major_axis == pytest.approx(1.3, rel=0.05)
+    ok_wrong, _ = wrong.estimate_all_orbits(pyoutfit_env, params, seed=3)
+    assert 0 not in ok_wrong or ok_wrong[0][1] > 10.0 * rms
+
+    # Mixing: a topocentric trajectory appended to the geocentric set.
+    mixed = ingest(ra_geo, dec_geo, False)
+    mixed.extend(ingest(ra_topo, dec_topo, True, ids=tid + 1))
+    assert sorted(mixed.keys()) == [0, 1]
+    assert not mixed[0].topocentric(pyoutfit_env).any()
+    assert mixed[1].topocentric(pyoutfit_env).all()
+    for key in (0, 1):
+        _, dra, ddec = mixed[key].residuals(pyoutfit_env, truth)
+        assert np.abs(np.hypot(dra, ddec)).max() < 1e-3
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="builtin:approx needs Linux or macOS")
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_builtin_ephemeris_recovers_main_belt_orbit(ZTF_observatory: Observer):
+    """
+    The whole pipeline runs on the compiled-in ephemeris: synthetic astrometry of a
+    main-belt asteroid, ingestion, Gauss IOD and differential correction.
+    """
+    env = PyOutfit("builtin:approx", "FCCT14")
+    t0 = 60000.0
+    truth = GaussResult.from_keplerian(
+        KeplerianElements(t0, 2.7, 0.1, 0.2, 1.0, 0.5, 0.3), corrected=True
+    )
+    nights = t0 + np.repeat(np.arange(0.0, 20.0, 2.0), 3)
+    mjd = nights + np.tile([0.0, 0.02, 0.04], nights.size // 3)
+    ra, dec = _predicted_by_residuals(env, ZTF_observatory, truth, mjd)
+    ts = TrajectorySet.from_numpy_degrees(
+        env, np.zeros(mjd.size, dtype=np.uint32), ra, dec, 0.5, 0.5, mjd, ZTF_observatory
+    )
+
+    params = IODParams.builder().n_noise_realizations(0).build()
+    ok, errors = ts.estimate_all_orbits(env, params, seed=5)
+    assert 0 in ok, errors
+    orbit = ok[0][0].keplerian()
+    assert orbit is not None and orbit.eccentricity < 1.0
+    assert orbit.semi_major_axis == pytest.approx(2.7, rel=0.05)
+
+
+def test_predict_all_fills_nan_outside_ephemeris(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    small_traj_set: Tuple[TrajectorySet, dict],
+):
+    traj_set, _ = small_traj_set
+    params = IODParams.builder().n_noise_realizations(0).build()
+    results = traj_set.estimate_orbits(pyoutfit_env, params, seed=7)
+    g0, _ = results[results.keys()[0]]
+
+    pred = results.predict_all(
+        pyoutfit_env, ZTF_observatory, [g0.fit_epoch + 1.0, 1.0e7]
+    )
+    assert np.isnan(pred["ra"][:, 1]).all()
+    assert list(pred["failed_rows"]) == list(range(len(pred["object_id"])))
+    assert all("ephemeris segment" in e for e in pred["errors"])
+
+    with pytest.raises(ValueError):
+        results.predict_all(pyoutfit_env, ZTF_observatory, [float("nan")])
@@ -1,5 +1,7 @@
 # -*- coding: utf-8 -*-
 import math
+
+import numpy as np
 import pytest
 
 from py_outfit import (
@@ -173,6 +175,83 @@ def test_cometary_parabolic_conversions_raise(fake_comet_parab):
         _ = c.to_equinoctial()
 
 
+GAUSS_K = 0.01720209895
+
+
+def _barker_time(q: float, nu: float) -> float:
+    """Days from perihelion on a parabola (Barker's equation)."""
+    d = math.tan(nu / 2)
+    return math.sqrt(2 * q**3) / GAUSS_K * (d + d**3 / 3)
+
+
+@pytest.mark.parametrize(
+    "q,e",
+    [
+        (1.3, 0.6),  # elliptical (short-period comet)
+        (0.5, 0.97),
+        (1.0, 1.0 - 1e-7),  # near-parabolic
+        (1.0, 1.0),
+        (1.0, 1.0 + 1e-7),
+        (0.9, 1.2),  # hyperbolic
+        (2.5, 4.0),
+    ],
+)
+def test_cometary_perihelion_time_round_trip(q: float, e: float):
+    nu_max = math.acos(-1 / e) if e > 1 else math.pi
+    for frac in (-0.9, -0.3, 0.0, 0.2, 0.7, 0.95):
+        c = CometaryElements(60000.0, q, e, 0.4, 1.8, 0.2, frac * nu_max)
+        t_p = c.perihelion_time()
+        back = CometaryElements.from_perihelion_time(t_p, q, e, 0.4, 1.8, 0.2, 60000.0)
+        assert back.true_anomaly == pytest.approx(c.true_anomaly, abs=1e-10)
+        assert back.perihelion_time() == pytest.approx(t_p, abs=1e-8)
+        assert (back.inclination, back.ascending_node_longitude, back.periapsis_argument) == (
+            0.4,
+            1.8,
+            0.2,
+        )
+        # Perihelion is before the epoch for an outgoing body.
+        assert (t_p < 60000.0) == (frac > 0)
+
+
+def test_cometary_perihelion_time_reference_values():
+    # Parabola: Barker's equation; near-parabolic orbits are continuous with it.
+    t_p = CometaryElements(60000.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.3).perihelion_time()
+    assert t_p == pytest.approx(60000.0 - _barker_time(1.0, 1.3), abs=1e-9)
+    for e in (1.0 - 1e-9, 1.0 + 1e-9):
+        near = CometaryElements(60000.0, 1.0, e, 0.0, 0.0, 0.0, 1.3).perihelion_time()
+        assert near == pytest.approx(t_p, abs=1e-6)
+
+    # Ellipse: M = E - e sin E, and T_p is the passage closest to the epoch.
+    q, e, nu = 1.2, 0.5, 1.0
+    a = q / (1 - e)
+    ecc = 2 * math.atan(math.sqrt((1 - e) / (1 + e)) * math.tan(nu / 2))
+    dt = (ecc - e * math.sin(ecc)) / (GAUSS_K / a**1.5)
+    c = CometaryElements(60000.0, q, e, 0.0, 0.0, 0.0, nu)
+    assert c.perihelion_time() == pytest.approx(60000.0 - dt, abs=1e-9)
+    period = 2 * math.pi * a**1.5 / GAUSS_K
+    earlier = CometaryElements.from_perihelion_time(
+        60000.0 - dt - 3 * period, q, e, 0.0, 0.0, 0.0, 60000.0
+    )
+    assert earlier.true_anomaly == pytest.approx(nu, abs=1e-10)
+
+    # Hyperbola: M = e sinh F - F.
+    q, e, nu = 0.9, 1.2, -0.8
+    f = 2 * math.atanh(math.sqrt((e - 1) / (e + 1)) * math.tan(nu / 2))
+    dt = (e * math.sinh(f) - f) / (GAUSS_K / (q / (e - 1)) ** 1.5)
+    c = CometaryElements(60000.0, q, e, 0.0, 0.0, 0.0, nu)
+    assert c.perihelion_time() == pytest.approx(60000.0 - dt, abs=1e-9)
+
+
+def test_cometary_perihelion_time_rejects_invalid_elements():
+    beyond = math.acos(-1 / 1.2) + 0.01
+    with pytest.raises(ValueError, match="asymptotes"):
+        CometaryElements(60000.0, 0.9, 1.2, 0.0, 0.0, 0.0, beyond).perihelion_time()
+    with pytest.raises(ValueError, match="invalid conic"):
+        CometaryElements.from_perihelion_time(59000.0, -1.0, 1.0, 0.0, 0.0, 0.0, 60000.0)
+    with pytest.raises(ValueError, match="invalid conic"):
+        CometaryElements(60000.0, 1.0, -0.1, 0.0, 0.0, 0.0, 0.0).perihelion_time()
+
+
 # ------------------------- tests: GaussResult constructors & accessors -------------------------
 
 
@@ -230,6 +309,145 @@ def test_gaussresult_from_cometary_and_extract(fake_comet_hyperb):
     assert_float_eq(d["elements"]["eccentricity"], fake_comet_hyperb["eccentricity"])
 
 
+# ------------------------- tests: unit metadata and round-trips -------------------------
+
+
+def test_gaussresult_to_dict_units_block(fake_kepler):
+    g = GaussResult.from_keplerian(KeplerianElements(**fake_kepler), True)
+    assert "units" not in g.to_dict()
+
+    d = g.to_dict(include_units=True)
+    assert d["units"] == {
+        "reference_epoch": "MJD_TDB",
+        "semi_major_axis": "AU",
+        "eccentricity": "",
+        "inclination": "rad",
+        "ascending_node_longitude": "rad",
+        "periapsis_argument": "rad",
+        "mean_anomaly": "rad",
+    }
+
+    d_deg = g.to_dict(degrees=True)
+    assert d_deg["units"]["inclination"] == "deg"
+    assert d_deg["units"]["semi_major_axis"] == "AU"
+    assert_float_eq(
+        d_deg["elements"]["inclination"], math.degrees(fake_kepler["inclination"])
+    )
+    assert_float_eq(
+        d_deg["elements"]["semi_major_axis"], fake_kepler["semi_major_axis"]
+    )
+
+
+@pytest.mark.parametrize("degrees", [False, True])
+@pytest.mark.parametrize("family", ["keplerian", "equinoctial", "cometary"])
+def test_gaussresult_dict_and_json_round_trip(
+    family, degrees, fake_kepler, fake_equino, fake_comet_hyperb
+):
+    if family == "keplerian":
+        g = GaussResult.from_keplerian(KeplerianElements(**fake_kepler), True)
+    elif family == "equinoctial":
+        g = GaussResult.from_equinoctial(EquinoctialElements(**fake_equino), False)
+    else:
+        g = GaussResult.from_cometary(CometaryElements(**fake_comet_hyperb), True)
+    ref = g.to_dict()
+
+    for back in (
+        GaussResult.from_dict(g.to_dict(degrees=degrees)),
+        GaussResult.from_json(g.to_json(degrees=degrees)),
+    ):
+        got = back.to_dict()
+        assert got["stage"] == ref["stage"]
+        assert got["type"] == family
+        for key, value in ref["elements"].items():
+            assert_float_eq(got["elements"][key], value, msg=key)
+
+
+def test_gaussresult_from_dict_without_units_assumes_radians(fake_kepler):
+    d = GaussResult.from_keplerian(KeplerianElements(**fake_kepler)).to_dict()
+    back = GaussResult.from_dict(d)
+    assert_float_eq(back.keplerian().inclination, fake_kepler["inclination"])
+
+
+def test_gaussresult_from_dict_rejects_bad_input(fake_kepler):
+    d = GaussResult.from_keplerian(KeplerianElements(**fake_kepler)).to_dict(
+        include_units=True
+    )
+
+    bad_unit = dict(d, units=dict(d["units"], semi_major_axis="km"))
+    with pytest.raises(ValueError, match="semi_major_axis"):
+        GaussResult.from_dict(bad_unit)
+
+    with pytest.raises(ValueError, match="stage"):
+        GaussResult.from_dict(dict(d, stage="final"))
+
+    missing = dict(d, elements={k: v for k, v in d["elements"].items() if k != "mean_anomaly"})
+    with pytest.raises(KeyError, match="mean_anomaly"):
+        GaussResult.from_dict(missing)
+
+
+def _build(family, corrected, fake_kepler, fake_equino, fake_comet_hyperb):
+    if family == "keplerian":
+        return GaussResult.from_keplerian(KeplerianElements(**fake_kepler), corrected)
+    if family == "equinoctial":
+        return GaussResult.from_equinoctial(EquinoctialElements(**fake_equino), corrected)
+    return GaussResult.from_cometary(CometaryElements(**fake_comet_hyperb), corrected)
+
+
+def _export_row(g, degrees):
+    """Flat row laid out as `OrbitResults.to_dict`, with its units block."""
+    d = g.to_dict(degrees=degrees)
+    units = d["units"] if degrees else None
+    row = dict(object_id="K24A00A", stage=d["stage"], element_type=d["type"], rms=0.4)
+    row.update(fit_epoch=float("nan"), error=None, mean_longitude=None)
+    row.update(d["elements"])
+    return row, units
+
+
+@pytest.mark.parametrize("corrected", [False, True])
+@pytest.mark.parametrize("family", ["keplerian", "equinoctial", "cometary"])
+def test_gaussresult_from_dict_and_from_row_round_trip(
+    family, corrected, fake_kepler, fake_equino, fake_comet_hyperb
+):
+    g = _build(family, corrected, fake_kepler, fake_equino, fake_comet_hyperb)
+    ref = g.to_dict()
+
+    d = dict(g.to_dict(include_units=True), fit_epoch=59990.0, rms=0.4)
+    back = GaussResult.from_dict(d)
+    assert back.to_dict() == dict(ref, fit_epoch=59990.0)
+
+    for degrees in (False, True):
+        row, units = _export_row(g, degrees)
+        got = GaussResult.from_row(row, units).to_dict()
+        assert (got["stage"], got["type"]) == (ref["stage"], family)
+        assert got["fit_epoch"] is None and got["fit_statistics"] is None
+        for key, value in ref["elements"].items():
+            assert_float_eq(got["elements"][key], value, msg=key)
+
+
+def test_gaussresult_from_dict_and_from_row_strict_validation(fake_kepler):
+    g = GaussResult.from_keplerian(KeplerianElements(**fake_kepler), True)
+    d = g.to_dict()
+
+    with pytest.raises(ValueError, match="elemnts"):
+        GaussResult.from_dict(dict(d, elemnts={}))
+    with pytest.raises(ValueError, match="type"):
+        GaussResult.from_dict(dict(d, type=3))
+    with pytest.raises(KeyError, match="n_points.*fit_statistics"):
+        stats = dict(rms_arcsec=0.4, rms_ra_arcsec=0.3, rms_dec_arcsec=0.2, chi2=4.0)
+        GaussResult.from_dict(dict(d, fit_statistics=dict(stats, reduced_chi2=1.0)))
+
+    row, _ = _export_row(g, False)
+    for key in ("stage", "element_type", "mean_anomaly"):
+        with pytest.raises(KeyError, match=key):
+            GaussResult.from_row({k: v for k, v in row.items() if k != key})
+    with pytest.raises(KeyError, match="rms_ra_arcsec"):
+        GaussResult.from_row(dict(row, rms_arcsec=0.4))
+    with pytest.raises(ValueError, match="no convergence"):
+        GaussResult.from_row(dict(row, error="no convergence"))
+    with pytest.raises(ValueError, match="stage"):
+        GaussResult.from_row(dict(row, stage="final"))
+
+
 # ------------------------- smoke tests: __str__/__repr__ on GaussResult -------------------------
 
 
@@ -240,3 +458,113 @@ def test_gaussresult_str_repr(fake_kepler):
     r = repr(g)
     assert isinstance(s, str) and len(s) > 0
     assert r.startswith("<PyGaussResult ")
+
+
+# ------------------------- tests: vectorized construction -------------------------
+
+
+def _kepler_columns(n):
+    rng = np.random.default_rng(7)
+    return (
+        np.full(n, 60000.0),
+        rng.uniform(1.5, 3.5, n),
+        rng.uniform(0.0, 0.3, n),
+        rng.uniform(0.0, 0.5, n),
+        rng.uniform(0.0, 2 * math.pi, n),
+        rng.uniform(0.0, 2 * math.pi, n),
+        rng.uniform(0.0, 2 * math.pi, n),
+    )
+
+
+def test_keplerian_from_arrays_values():
+    cols = _kepler_columns(1000)
+    elems = KeplerianElements.from_arrays(*cols)
+    assert len(elems) == 1000
+    for k in (0, 499, 999):
+        e = elems[k]
+        got = (
+            e.reference_epoch,
+            e.semi_major_axis,
+            e.eccentricity,
+            e.inclination,
+            e.ascending_node_longitude,
+            e.periapsis_argument,
+            e.mean_anomaly,
+        )
+        assert got == tuple(c[k] for c in cols)
+
+    assert KeplerianElements.from_arrays(*(np.empty(0),) * 7) == []
+    with pytest.raises(ValueError, match="Length mismatch"):
+        KeplerianElements.from_arrays(*cols[:6], cols[6][:-1])
+
+
+def test_gaussresult_from_keplerian_arrays_values():
+    cols = _kepler_columns(100)
+    pre = GaussResult.from_keplerian_arrays(*cols)
+    cor = GaussResult.from_keplerian_arrays(*cols, corrected=True)
+    assert all(g.is_preliminary() for g in pre)
+    assert all(g.is_corrected() for g in cor)
+    k = cor[42].keplerian()
+    assert k.semi_major_axis == cols[1][42]
+    assert k.mean_anomaly == cols[6][42]
+
+
+@pytest.mark.benchmark
+def test_gaussresult_from_keplerian_arrays_benchmark(best_of):
+    """Batch construction against a Python loop; timings are only reported."""
+    cols = _kepler_columns(100_000)
+
+    def loop():
+        return [
+            GaussResult.from_keplerian(KeplerianElements(*row), True)
+            for row in zip(*cols)
+        ]
+
+    def batch():
+        return GaussResult.from_keplerian_arrays(*cols, corrected=True)
+
+    def elements(results):
+        return [
+            (g.is_corrected(), k.semi_major_axis, k.eccentricity, k.mean_anomaly)
+            for g in results
+            for k in (g.keplerian(),)
+        ]
+
+    assert elements(batch()) == elements(loop())
+    t_loop = best_of(loop)
+    t_batch = best_of(batch)
+    print(f"\nPython loop {t_loop:.3f}s vs from_keplerian_arrays {t_batch:.3f}s")
+
+
+# ------------------------- tests: time scales -------------------------
+
+
+@pytest.mark.parametrize("mjd_tdb", [51544.5, 55000.0, 60000.0, 60180.0])
+def test_reference_epoch_tt_tdb_offset(fake_kepler, mjd_tdb):
+    """TT accessor removes the periodic TDB - TT term (dominant annual term)."""
+    k = KeplerianElements(**dict(fake_kepler, reference_epoch=mjd_tdb))
+    assert k.reference_epoch_tdb == k.reference_epoch == mjd_tdb
+
+    t = (mjd_tdb - 51544.5) / 36525.0
+    g = 628.3076 * t + 6.2401
+    expected_s = 0.001657 * math.sin(g) + 0.000014 * math.sin(2 * g)
+    offset_s = (k.reference_epoch_tdb - k.reference_epoch_tt) * 86400.0
+    assert abs(offset_s) <= 1.7e-3
+    # The dominant terms agree with the full series to a few tens of microseconds.
+    assert offset_s == pytest.approx(expected_s, abs=4e-5)
+
+
+def test_reference_epoch_tt_offset_is_periodic(fake_kepler):
+    """Half a year apart, the offset changes sign (annual term)."""
+    offsets = []
+    for mjd in (60000.0 + 0.25 * 365.25, 60000.0 + 0.75 * 365.25):
+        k = KeplerianElements(**dict(fake_kepler, reference_epoch=mjd))
+        offsets.append((k.reference_epoch_tdb - k.reference_epoch_tt) * 86400.0)
+    assert offsets[0] * offsets[1] < 0
+    assert min(abs(o) for o in offsets) > 0.9e-3
+
+
+def test_reference_epoch_tt_on_every_family(fake_equino, fake_comet_hyperb):
+    for e in (EquinoctialElements(**fake_equino), CometaryElements(**fake_comet_hyperb)):
+        assert e.reference_epoch_tt != e.reference_epoch_tdb
+        assert abs(e.reference_epoch_tt - e.reference_epoch_tdb) * 86400.0 < 2e-3
@@ -1,9 +1,14 @@
 # tests/test_pyoutfit.py
 # Tests for the thin Python wrapper around the Outfit state.
 
+import json
+import struct
+import sys
+
+import numpy as np
 import pytest
 
-from py_outfit import PyOutfit, Observer
+from py_outfit import PyOutfit, Observer, TrajectorySet
 
 
 def _make_outfit_or_skip(ephem="horizon:DE440", error_model="FCCT14"):
@@ -62,6 +67,14 @@ def test_get_observer_from_mpc_code_returns_observer(mpc_code):
     assert isinstance(obs, Observer)
 
 
+def test_get_observer_from_mpc_code_unknown_code_raises():
+    state = _make_outfit_or_skip()
+    with pytest.raises(ValueError, match="unknown observatory code 'Z9Z'"):
+        state.get_observer_from_mpc_code("Z9Z")
+    # The MPC table is enumerated by the lookup, and shared with the clones.
+    assert "  I41  " in state.clone_shallow().show_observatories(filter="i41")
+
+
 def test_multiple_outfit_instances_are_independent():
     """
     Two PyOutfit instances should not share user-defined observers implicitly.
@@ -96,3 +109,511 @@ def test_show_observatories_is_string_even_when_empty():
     txt = state.show_observatories()
     assert isinstance(txt, str)
     # avoidance of over-specification: don't assert exact wording/formatting
+
+
+def _site(name, lon=10.0, lat=20.0, elevation=1.0):
+    return Observer(
+        longitude=lon,
+        latitude=lat,
+        elevation=elevation,
+        name=name,
+        ra_accuracy=None,
+        dec_accuracy=None,
+    )
+
+
+def test_user_observatories_and_filtering():
+    state = _make_outfit_or_skip()
+    for name in ("Alpha Site", "Beta Site", "Gamma Hill"):
+        state.add_observer(_site(name))
+
+    users = state.user_observatories()
+    assert [str(o) for o in users] == [str(_site(n)) for n in ("Alpha Site", "Beta Site", "Gamma Hill")]
+
+    txt = state.show_observatories(only_user_added=True, filter="site")
+    assert "Alpha Site" in txt and "Beta Site" in txt
+    assert "Gamma Hill" not in txt
+
+    txt = state.show_observatories(only_user_added=True, filter="nothing-like-this")
+    assert "No observatories match" in txt
+    assert txt.isascii()
+
+
+def test_show_observatories_limit_and_ordering():
+    state = _make_outfit_or_skip()
+    state.add_observer(_site("Local Site"))
+    # Loads the MPC table
+    state.get_observer_from_mpc_code("I41")
+
+    txt = state.show_observatories(limit=5)
+    site_lines = [l for l in txt.splitlines() if l.startswith("  ")]
+    assert len(site_lines) == 5
+    assert "more observatories" in txt.splitlines()[-1]
+    assert "Local Site" in site_lines[0]
+
+    # MPC section sorted by code, identical across calls
+    full = state.show_observatories()
+    assert full == state.show_observatories()
+    mpc_lines = full.split("MPC observers:\n", 1)[1].splitlines()
+    codes = [l.split()[0] for l in mpc_lines if l.startswith("  ")]
+    assert len(codes) > 100
+    assert codes == sorted(codes)
+
+    txt = state.show_observatories(filter="i41")
+    assert "  I41  " in txt
+    assert "Local Site" not in txt
+
+
+def test_add_replace_remove_user_observer():
+    state = _make_outfit_or_skip()
+    h_alpha = state.add_observer(_site("Alpha Site"))
+    h_typo = state.add_observer(_site("Btea Site", lon=30.0))
+    assert isinstance(h_alpha, int) and h_typo != h_alpha
+
+    # Lookup by handle and by name.
+    assert str(state.user_observer(h_typo)) == str(_site("Btea Site", lon=30.0))
+    assert str(state.user_observer("Alpha Site")) == str(_site("Alpha Site"))
+
+    # Observations ingested with the site before it is replaced.
+    old = state.user_observer(h_typo)
+    ts = TrajectorySet.from_numpy_degrees(
+        state,
+        np.array([7, 7, 7], dtype=np.uint32),
+        np.array([10.0, 10.1, 10.2]),
+        np.array([5.0, 5.1, 5.2]),
+        0.5,
+        0.5,
+        np.array([60000.0, 60000.1, 60000.2]),
+        old,
+    )
+
+    # Replace: same handle and position, new site visible to lookups and listings.
+    assert state.replace_observer(h_typo, _site("Beta Site", lon=31.0)) == h_typo
+    assert str(state.user_observer(h_typo)) == str(_site("Beta Site", lon=31.0))
+    assert [str(o) for o in state.user_observatories()] == [
+        str(_site("Alpha Site")),
+        str(_site("Beta Site", lon=31.0)),
+    ]
+    txt = state.show_observatories(only_user_added=True)
+    assert "Beta Site" in txt and "Btea Site" not in txt
+    with pytest.raises(KeyError):
+        state.user_observer("Btea Site")
+
+    # Remove by name: handle becomes invalid, other sites are untouched.
+    removed = state.remove_observer("Alpha Site")
+    assert str(removed) == str(_site("Alpha Site"))
+    assert [str(o) for o in state.user_observatories()] == [str(_site("Beta Site", lon=31.0))]
+    for key in (h_alpha, "Alpha Site"):
+        with pytest.raises(KeyError):
+            state.user_observer(key)
+        with pytest.raises(KeyError):
+            state.remove_observer(key)
+    with pytest.raises(TypeError):
+        state.user_observer(1.5)
+
+    # The earlier set still resolves its (replaced) site through the environment.
+    assert "Btea Site" in ts[7].show_with_env(state)
+
+    # Handles are not reused after a removal.
+    assert state.add_observer(_site("Gamma Hill")) not in (h_alpha, h_typo)
+
+
+def test_available_bodies_covers_iod_requirements():
+    """A full Horizons kernel exposes every body used by the IOD pipeline."""
+    state = _make_outfit_or_skip()
+    bodies = state.available_bodies()
+    assert isinstance(bodies, list)
+    for required in ("Earth", "Moon", "Sun"):
+        assert required in bodies
+
+
+def test_ephemeris_incomplete_error_is_a_runtime_error():
+    """The typed error keeps `except RuntimeError` handlers working."""
+    from py_outfit import EphemerisIncompleteError
+
+    assert issubclass(EphemerisIncompleteError, RuntimeError)
+    err = EphemerisIncompleteError("missing")
+    with pytest.raises(RuntimeError):
+        raise err
+
+
+def _write_bodyless_spk(path):
+    """
+    Write a NAIF SPK kernel with a valid DAF layout and text header but no segment:
+    every body lookup of the core fails (panics) on it.
+    """
+    header = b"DAF/SPK " + struct.pack("<ii", 2, 6) + b"bodyless test kernel".ljust(60)
+    header += struct.pack("<iii", 3, 3, 385) + b"LTL-IEEE" + bytes(603)
+    header += b"FTPSTR:\r:\n:\r\n:\r\x00:\x81:\x10\xce:ENDFTP"
+    comment = (
+        b"JPL planetary and lunar ephemeris DE438\n"
+        b"Integrated 1 January 2018\n"
+        b"Time span covered by ephemeris:\n\n"
+        b"31-DEC-1549 00:00 to   25-JAN-2650 00:00\n"
+        b"JD   2287184.5   to   JD   2688976.5\n"
+    )
+    # Record 3: summary record with no summary (next, previous, count all zero).
+    path.parent.mkdir(parents=True)
+    path.write_bytes(header.ljust(1024, b"\0") + comment.ljust(1024, b"\0") + bytes(1024))
+
+
+@pytest.mark.skipif(
+    not sys.platform.startswith("linux"), reason="kernel cache located via XDG_CACHE_HOME"
+)
+def test_kernel_without_required_bodies_raises_incomplete(tmp_path, monkeypatch, capfd):
+    """A kernel lacking the Earth-Moon barycenter is rejected with typed attributes."""
+    from py_outfit import EphemerisIncompleteError
+
+    _make_outfit_or_skip()  # the environment also needs the UT1 data of the core
+    monkeypatch.setenv("XDG_CACHE_HOME", str(tmp_path))
+    _write_bodyless_spk(tmp_path / "outfit_cache" / "jpl_ephem" / "naif" / "de438.bsp")
+
+    with pytest.raises(EphemerisIncompleteError) as info:
+        PyOutfit("naif:DE438", "FCCT14")
+    err = info.value
+    assert err.missing_bodies == ["Earth-Moon barycenter"]
+    assert err.suggested_kernels == ["horizon:DE440", "horizon:DE430", "naif:DE440", "naif:DE442"]
+    assert "Earth-Moon barycenter" in str(err)
+
+    # The body probes panic in the core; none of them reaches stderr.
+    assert "panicked" not in capfd.readouterr().err
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="builtin:approx needs Linux or macOS")
+def test_builtin_approx_ephemeris_tracks_de440(observer):
+    """The compiled-in ephemeris needs no kernel and stays close to DE440."""
+    state = _make_outfit_or_skip("builtin:approx")
+    assert state.available_bodies() == ["Earth-Moon barycenter"]
+    start, end = state.ephemeris_time_range()
+    assert start == pytest.approx(15020.0) and end == pytest.approx(88076.0)
+
+    with pytest.raises(ValueError, match="builtin:approx"):
+        PyOutfit("builtin:exact", "FCCT14")
+    restricted = PyOutfit("builtin:approx[2020-2030]", "FCCT14")
+    assert restricted.ephemeris_time_range() == (58849.0, 62867.0)
+
+    reference = _make_outfit_or_skip()
+    mjd = np.linspace(45000.0, 65000.0, 41)
+    n = mjd.size
+
+    def observer_positions(env):
+        ts = TrajectorySet.from_numpy_degrees(
+            env, np.zeros(n, dtype=np.uint32), np.full(n, 30.0), np.full(n, 20.0),
+            0.5, 0.5, mjd, observer,
+        )
+        return ts[0].geometry(env)["observer_position"]
+
+    # Arcminute-level model: the heliocentric observer within ~1e-4 AU of DE440.
+    offset = np.linalg.norm(observer_positions(state) - observer_positions(reference), axis=1)
+    assert offset.max() < 5e-4
+
+
+def test_ephemeris_time_range_full_kernel():
+    """Without restriction the range is the kernel coverage, around J2000."""
+    state = _make_outfit_or_skip()
+    start, end = state.ephemeris_time_range()
+    assert start < 51544.5 < end
+    # DE440 spans several centuries on each side of J2000.
+    assert end - start > 365.25 * 500
+
+
+@pytest.mark.parametrize(
+    "make",
+    [
+        lambda: PyOutfit("horizon:DE440[2020-2030]", "FCCT14"),
+        lambda: PyOutfit("horizon:DE440", "FCCT14", time_span=(58849.0, 62867.0)),
+    ],
+    ids=["selector", "time_span"],
+)
+def test_restricted_span_rejects_out_of_range_ingestion(make, observer):
+    _make_outfit_or_skip()
+    state = make()
+    # 2020-01-01 .. 2031-01-01 (both years of the selector included).
+    assert state.ephemeris_time_range() == (58849.0, 62867.0)
+
+    def ingest(mjd):
+        return TrajectorySet.from_numpy_degrees(
+            state,
+            np.array([1, 1, 1], dtype=np.uint32),
+            np.array([10.0, 10.1, 10.2]),
+            np.array([5.0, 5.05, 5.1]),
+            0.5,
+            0.5,
+            np.asarray(mjd, dtype=np.float64),
+            observer,
+        )
+
+    assert len(ingest([60000.0, 60000.1, 60000.2])) == 1
+
+    with pytest.raises(ValueError) as exc:
+        ingest([60000.0, 60000.1, 70000.0])
+    msg = str(exc.value)
+    assert "1 observation(s)" in msg
+    assert "mjd 70000" in msg
+    assert "58849.000000" in msg and "62867.000000" in msg
+
+
+def test_restricted_span_validation():
+    _make_outfit_or_skip()
+    with pytest.raises(ValueError, match="malformed"):
+        PyOutfit("horizon:DE440[2030-2020]", "FCCT14")
+    with pytest.raises(ValueError, match="not both"):
+        PyOutfit("horizon:DE440[2020-2030]", "FCCT14", time_span=(58849.0, 60000.0))
+    with pytest.raises(ValueError, match="valid range") as excinfo:
+        PyOutfit("horizon:DE440", "FCCT14", time_span=(-1.0e7, 60000.0))
+    msg = str(excinfo.value)
+    assert "  " not in msg
+    assert "is empty or not covered by 'horizon:DE440', whose valid range is [" in msg
+
+
+def test_nearest_observatories_finds_greenwich():
+    state = _make_outfit_or_skip()
+    # Royal Observatory, Greenwich (MPC code 000).
+    hits = state.nearest_observatories(51.4769, -0.0005, max_distance_km=50.0, limit=3)
+
+    assert 1 <= len(hits) <= 3
+    first, distance = hits[0]
+    assert isinstance(first, Observer)
+    assert repr(first) == repr(state.get_observer_from_mpc_code("000"))
+    assert distance < 1.0
+    distances = [d for _, d in hits]
+    assert distances == sorted(distances)
+    assert all(d <= 50.0 for d in distances)
+
+
+def test_nearest_observatories_radius_and_validation():
+    state = _make_outfit_or_skip()
+    # Middle of the South Pacific: no site within 50 km.
+    assert state.nearest_observatories(-48.0, -123.0) == []
+    everything = state.nearest_observatories(51.4769, -0.0005, max_distance_km=100.0, limit=None)
+    assert len(everything) >= len(state.nearest_observatories(51.4769, -0.0005, limit=3))
+
+    with pytest.raises(ValueError):
+        state.nearest_observatories(91.0, 0.0)
+    with pytest.raises(ValueError):
+        state.nearest_observatories(0.0, 0.0, max_distance_km=-1.0)
+
+
+def test_ephemeris_segments_of_restricted_span():
+    _make_outfit_or_skip()
+    state = PyOutfit("horizon:DE440[2020-2030]", "FCCT14")
+    # DE440 is continuous: a single segment, equal to the restricted span.
+    assert state.ephemeris_segments() == [(58849.0, 62867.0)]
+
+
+def test_ingestion_near_segment_boundary_raises_coverage_error(observer):
+    from py_outfit import EphemerisCoverageError
+
+    _make_outfit_or_skip()
+
+    def ingest(state, mjd):
+        return TrajectorySet.from_numpy_degrees(
+            state,
+            np.array([1, 1, 1], dtype=np.uint32),
+            np.array([10.0, 10.1, 10.2]),
+            np.array([5.0, 5.05, 5.1]),
+            0.5,
+            0.5,
+            np.asarray(mjd, dtype=np.float64),
+            observer,
+        )
+
+    state = PyOutfit("horizon:DE440[2020-2030]", "FCCT14")
+    assert len(ingest(state, [58850.0, 58850.1, 58850.2])) == 1
+
+    with pytest.raises(EphemerisCoverageError) as exc:
+        ingest(state, [58849.2, 58850.1, 62866.5])
+    assert isinstance(exc.value, ValueError)
+    assert exc.value.epochs == [58849.2, 62866.5]
+    assert exc.value.nearest_boundaries == [58849.0, 62867.0]
+    assert "ephemeris_segments" in str(exc.value)
+
+    unchecked = PyOutfit("horizon:DE440[2020-2030]", "FCCT14", check_ephemeris_coverage=False)
+    assert len(ingest(unchecked, [58849.2, 58850.1, 62866.5])) == 1
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_estimation_near_segment_boundary_is_rejected(pyoutfit_env, ZTF_observatory, traj_data):
+    from py_outfit import EphemerisCoverageError, IODParams
+
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    traj_set = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+    )
+    # The span starts half a day before the first epoch of the batch.
+    first = float(mjd_tt.min())
+    narrow = PyOutfit("horizon:DE440", "FCCT14", time_span=(first - 0.5, first + 100.0))
+    params = IODParams.builder().n_noise_realizations(0).build()
+
+    ok, errors = traj_set.estimate_all_orbits(narrow, params, seed=42)
+    culprit = int(tid[np.argmin(mjd_tt)])
+    assert culprit not in ok
+    assert errors[culprit].startswith("EphemerisCoverageError:")
+    assert set(ok) | set(errors) == set(int(t) for t in np.unique(tid))
+
+    with pytest.raises(EphemerisCoverageError):
+        traj_set[culprit].estimate_best_orbit(narrow, params, seed=42)
+
+
+def test_clone_shallow_isolates_observer_registries():
+    base = _make_outfit_or_skip()
+    h_common = base.add_observer(_site("Common Site"))
+
+    clone = base.clone_shallow()
+    assert clone.shares_engine_with(base)
+    assert clone.ephemeris_time_range() == base.ephemeris_time_range()
+    assert str(clone.user_observer(h_common)) == str(_site("Common Site"))
+
+    # Registrations on either side stay private and keep the engine shared.
+    h_a = base.add_observer(_site("Only In Base", lon=40.0))
+    h_b = clone.add_observer(_site("Only In Clone", lon=50.0))
+    assert h_a == h_b  # both continue from the same handle counter
+    assert clone.shares_engine_with(base)
+
+    assert "Only In Base" in base.show_observatories(only_user_added=True)
+    assert "Only In Base" not in clone.show_observatories(only_user_added=True)
+    assert "Only In Clone" in clone.show_observatories(only_user_added=True)
+    assert "Only In Clone" not in base.show_observatories(only_user_added=True)
+
+    clone.remove_observer(h_common)
+    assert str(base.user_observer(h_common)) == str(_site("Common Site"))
+
+    # Clones of clones share it too.
+    other = clone.clone_shallow()
+    other.add_observer(_site("Third"))
+    assert other.shares_engine_with(clone) and other.shares_engine_with(base)
+    assert not base.shares_engine_with(_make_outfit_or_skip())
+
+
+def test_clone_shallow_ingestion_keeps_the_ephemeris_shared():
+    base = _make_outfit_or_skip()
+    clone = base.clone_shallow()
+    site = _site("Tenant Site", lon=60.0)
+    clone.add_observer(site)
+
+    tid = np.array([0, 0, 0], dtype=np.uint32)
+    ra = np.array([10.0, 10.1, 10.2])
+    dec = np.array([5.0, 5.05, 5.1])
+    mjd = np.array([60000.0, 60000.5, 60001.0])
+    from_clone = TrajectorySet.from_numpy_degrees(clone, tid, ra, dec, 0.5, 0.5, mjd, site)
+    from_base = TrajectorySet.from_numpy_degrees(base, tid, ra, dec, 0.5, 0.5, mjd, site)
+
+    # Ingesting on the clone indexes its site without copying the loaded kernel.
+    assert clone.shares_engine_with(base)
+    assert "Tenant Site" not in base.show_observatories(only_user_added=True)
+    for a, b in zip(from_clone[0].to_numpy(), from_base[0].to_numpy()):
+        np.testing.assert_array_equal(a, b)
+
+
+def test_clone_shallow_writer_does_not_deadlock_a_running_iod(small_traj_set):
+    """
+    A registration on one clone while the IOD of another sits in its triplet scorer,
+    then a read with the GIL held, must all go through: the scorer runs with the engine
+    released and nobody waits for the engine while holding the GIL.
+    """
+    import threading
+
+    from py_outfit import IODParams
+
+    traj_set, _ = small_traj_set
+    base = _make_outfit_or_skip()
+    fitter, writer = base.clone_shallow(), base.clone_shallow()
+    scoring, release = threading.Event(), threading.Event()
+
+    def scorer(epochs, indices):
+        if not scoring.is_set():
+            scoring.set()
+            release.wait(timeout=30)
+        return 0.0
+
+    params = IODParams.builder().n_noise_realizations(0).triplet_scorer(scorer).build()
+    out = {}
+    iod = threading.Thread(
+        target=lambda: out.update(run=traj_set.estimate_all_orbits(fitter, params, seed=1)),
+        daemon=True,
+    )
+    iod.start()
+    assert scoring.wait(timeout=30)
+
+    add = threading.Thread(target=lambda: writer.add_observer(_site("Late Site")), daemon=True)
+    add.start()
+    add.join(timeout=30)
+    assert not add.is_alive()
+    assert base.available_bodies()  # read with the GIL held, IOD still in the scorer
+
+    release.set()
+    iod.join(timeout=120)
+    assert not iod.is_alive()
+    ok, err = out["run"]
+    assert len(ok) + len(err) == len(traj_set)
+    assert writer.shares_engine_with(fitter)
+
+
+def test_user_observers_json_round_trip(tmp_path):
+    src = _make_outfit_or_skip()
+    sites = [
+        Observer(12.5, -30.25, 2.4, name="Private A", ra_accuracy=1e-6, dec_accuracy=2e-6),
+        Observer(-70.0, 45.0, 0.1, name="Private B", ra_accuracy=None, dec_accuracy=None),
+        Observer(100.0, 10.0, 0.0, name=None, ra_accuracy=3e-6, dec_accuracy=None),
+    ]
+    for site in sites:
+        src.add_observer(site)
+    path = tmp_path / "sites.json"
+    assert src.export_user_observers(path) == 3
+
+    doc = json.loads(path.read_text())
+    assert doc["format"] == "py_outfit.user_observers" and doc["version"] == 1
+    assert doc["observers"][0]["ra_accuracy"] == 1e-6
+    assert doc["observers"][1]["dec_accuracy"] is None
+    assert doc["observers"][0]["latitude_deg"] == pytest.approx(-30.25, abs=1e-6)
+
+    dst = _make_outfit_or_skip()
+    report = dst.import_user_observers(path)
+    assert len(report["added"]) == 3
+    assert report["unchanged"] == report["replaced"] == report["conflicts"] == []
+    assert [repr(o) for o in dst.user_observatories()] == [repr(o) for o in sites]
+
+    # Re-importing leaves the named sites alone (unnamed ones cannot be matched);
+    # the exported records are stable.
+    again = dst.import_user_observers(path)
+    assert len(again["added"]) == 1 and len(again["unchanged"]) == 2
+    dst.export_user_observers(tmp_path / "again.json")
+    exported = json.loads((tmp_path / "again.json").read_text())["observers"]
+    strip = lambda r: {k: v for k, v in r.items() if k != "handle"}
+    assert [strip(r) for r in exported[:3]] == [strip(r) for r in doc["observers"]]
+
+
+def test_import_user_observers_conflicts_and_validation(tmp_path):
+    src = _make_outfit_or_skip()
+    src.add_observer(_site("Shared Name", lon=10.0))
+    path = tmp_path / "sites.json"
+    src.export_user_observers(path)
+
+    dst = _make_outfit_or_skip()
+    h = dst.add_observer(_site("Shared Name", lon=20.0))
+    report = dst.import_user_observers(path)
+    assert report["conflicts"] == ["Shared Name"] and report["added"] == []
+    assert str(dst.user_observer(h)) == str(_site("Shared Name", lon=20.0))
+
+    report = dst.import_user_observers(path, merge=False)
+    assert report["replaced"] == [h]
+    assert str(dst.user_observer(h)) == str(_site("Shared Name", lon=10.0))
+
+    doc = json.loads(path.read_text())
+    del doc["observers"][0]["rho_sin_phi"]
+    bad = tmp_path / "bad.json"
+    bad.write_text(json.dumps(doc))
+    with pytest.raises(KeyError, match="rho_sin_phi"):
+        dst.import_user_observers(bad)
+
+    bad.write_text(json.dumps({"format": "something else", "version": 1, "observers": []}))
+    with pytest.raises(ValueError):
+        dst.import_user_observers(bad)
+    n_before = len(dst.user_observatories())
+    doc = json.loads(path.read_text())
+    doc["observers"].append({"longitude_deg": "east", "rho_cos_phi": 0.5, "rho_sin_phi": 0.5})
+    doc["observers"][0]["name"] = "Fresh Name"
+    bad.write_text(json.dumps(doc))
+    with pytest.raises(ValueError, match=r"observers\[1\]"):
+        dst.import_user_observers(bad)
+    assert len(dst.user_observatories()) == n_before  # nothing registered
@@ -0,0 +1,75 @@
+import math
+
+import numpy as np
+import pytest
+
+from py_outfit import parse_sexagesimal
+
+
+def _hms(h: float, m: float, s: float) -> float:
+    return math.radians(15.0 * (h + m / 60.0 + s / 3600.0))
+
+
+def _dms(sign: int, d: float, m: float, s: float) -> float:
+    return sign * math.radians(d + m / 60.0 + s / 3600.0)
+
+
+def test_parse_sexagesimal_separators_signs_and_missing_seconds():
+    ra, dec = parse_sexagesimal(
+        ["12 34 56.78", "12:34:56.78", " +01  02  03 ", "23 59.5", "0:0"],
+        ["-05 43 21.0", "+05:43:21", "-00 30 00", "- 89 59.5", "90 00 00"],
+    )
+    assert ra.dtype == np.float64 and dec.dtype == np.float64
+    np.testing.assert_allclose(
+        ra,
+        [_hms(12, 34, 56.78), _hms(12, 34, 56.78), _hms(1, 2, 3), _hms(23, 59.5, 0), 0.0],
+        rtol=1e-15,
+    )
+    np.testing.assert_allclose(
+        dec,
+        [_dms(-1, 5, 43, 21), _dms(1, 5, 43, 21), _dms(-1, 0, 30, 0), _dms(-1, 89, 59.5, 0),
+         math.pi / 2],
+        rtol=1e-15,
+    )
+
+
+def test_parse_sexagesimal_accepts_arrays():
+    strings = np.array(["01 00 00", "02 00 00"])
+    ra, dec = parse_sexagesimal(strings, strings.astype(object))
+    np.testing.assert_allclose(ra, np.radians([15.0, 30.0]), rtol=1e-15)
+    np.testing.assert_allclose(dec, np.radians([1.0, 2.0]), rtol=1e-15)
+
+    ra, dec = parse_sexagesimal([], np.array([], dtype=object))
+    assert ra.shape == dec.shape == (0,)
+
+
+@pytest.mark.parametrize(
+    "ra, dec, message",
+    [
+        ("12 3x 56", "00 00 00", r'ra_strings\[1\] = "12 3x 56": minutes "3x" is not an integer'),
+        ("24 00 00", "00 00 00", r'ra_strings\[1\] = "24 00 00": right ascension beyond 24 hours'),
+        ("-01 00 00", "00 00 00", "a right ascension cannot be negative"),
+        ("12 60 00", "00 00 00", 'minutes "60" must be below 60'),
+        ("12 00 60", "00 00 00", 'seconds "60" must be below 60'),
+        ("12.5 30", "00 00 00", 'hours "12.5" is not an integer'),
+        ("12", "00 00 00", "expected 2 or 3 fields"),
+        ("12 00 00 00", "00 00 00", "expected 2 or 3 fields"),
+        ("12::00", "00 00 00", "empty field"),
+        ("", "00 00 00", "empty field"),
+        ("12 00 00", "+91 00 00", r'dec_strings\[1\] = "\+91 00 00": declination beyond 90'),
+        ("12 00 00", "+-5 00 00", 'degrees "-5" is not an integer'),
+        ("12 00 00", "05 00 -1", 'seconds "-1" is not a non-negative number'),
+    ],
+)
+def test_parse_sexagesimal_reports_row_and_string(ra: str, dec: str, message: str):
+    with pytest.raises(ValueError, match=message):
+        parse_sexagesimal(["01 00 00", ra], ["00 00 00", dec])
+
+
+def test_parse_sexagesimal_rejects_bad_inputs():
+    with pytest.raises(ValueError, match="different lengths"):
+        parse_sexagesimal(["01 00 00"], ["00 00 00", "00 00 00"])
+    with pytest.raises(TypeError, match=r"ra_strings\[1\] = None \(NoneType\)"):
+        parse_sexagesimal(np.array(["01 00 00", None], dtype=object), ["00 00", "00 00"])
+    with pytest.raises(TypeError, match="single str"):
+        parse_sexagesimal("01 00 00", "00 00 00")
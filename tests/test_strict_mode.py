@@ -0,0 +1,115 @@
+# tests/test_strict_mode.py
+# Strict mode: documented fallbacks warn by default and raise when strict.
+
+import numpy as np
+import pytest
+
+import py_outfit
+from py_outfit import (
+    FallbackWarning,
+    Observer,
+    PyOutfit,
+    StrictModeError,
+    TrajectorySet,
+)
+
+
+@pytest.fixture
+def strict_mode():
+    """Enable strict mode for one test, restoring the previous setting."""
+    previous = py_outfit.set_strict(True)
+    yield
+    py_outfit.set_strict(previous)
+
+
+def _dirty_ingestion(env: PyOutfit, observer: Observer, **kwargs) -> TrajectorySet:
+    """Two trajectories, one out of time order, one with a zero uncertainty patched."""
+    tid = np.array([0, 0, 0, 1, 1, 1], dtype=np.uint32)
+    ra = np.array([10.0, 10.2, 10.1, 33.4, 33.5, 33.6])
+    dec = np.array([-5.0, -4.8, -4.9, 2.0, 2.1, 2.2])
+    mjd = np.array([60000.0, 60000.2, 60000.1, 60001.0, 60001.1, 60001.2])
+    return TrajectorySet.from_numpy_degrees(
+        env,
+        tid,
+        ra,
+        dec,
+        0.0,
+        0.5,
+        mjd,
+        observer,
+        bad_sigma="value",
+        bad_sigma_value=0.5,
+        **kwargs,
+    )
+
+
+def test_strict_checks_lists_fallbacks():
+    checks = py_outfit.strict_checks()
+    assert set(checks) == {
+        "unknown_error_model",
+        "patched_sigma",
+        "unsorted_epochs",
+        "skipped_lines",
+        "missing_ids",
+    }
+    assert all(isinstance(v, str) and v for v in checks.values())
+
+
+def test_set_strict_returns_previous_setting():
+    assert py_outfit.set_strict(True) is False
+    assert py_outfit.set_strict(False) is True
+
+
+def test_dirty_ingestion_warns_in_permissive_mode(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    with pytest.warns(FallbackWarning) as record:
+        ts = _dirty_ingestion(pyoutfit_env, observer)
+    messages = [str(w.message) for w in record]
+    assert any(m.startswith("[unsorted_epochs]") for m in messages)
+    assert any(m.startswith("[patched_sigma]") for m in messages)
+    assert ts.patched_sigma_count == 6
+    assert ts.total_observations() == 6
+
+
+def test_dirty_ingestion_raises_in_strict_mode(
+    pyoutfit_env: PyOutfit, observer: Observer, strict_mode
+):
+    with pytest.raises(StrictModeError, match="unsorted_epochs"):
+        _dirty_ingestion(pyoutfit_env, observer)
+    # StrictModeError is a ValueError.
+    with pytest.raises(ValueError):
+        _dirty_ingestion(pyoutfit_env, observer)
+
+
+def test_per_call_override(pyoutfit_env: PyOutfit, observer: Observer, strict_mode):
+    with pytest.warns(FallbackWarning):
+        _dirty_ingestion(pyoutfit_env, observer, strict=False)
+
+    py_outfit.set_strict(False)
+    with pytest.raises(StrictModeError):
+        _dirty_ingestion(pyoutfit_env, observer, strict=True)
+
+
+def test_unknown_error_model_warns_or_raises():
+    with pytest.warns(FallbackWarning, match="unknown_error_model"):
+        PyOutfit("horizon:DE440", "unknown-model")
+    with pytest.raises(StrictModeError, match="unknown_error_model"):
+        PyOutfit("horizon:DE440", "unknown-model", strict=True)
+
+
+def test_clean_ingestion_is_silent_in_strict_mode(
+    pyoutfit_env: PyOutfit, observer: Observer, strict_mode
+):
+    tid = np.array([0, 0, 0], dtype=np.uint32)
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        tid,
+        np.array([10.0, 10.1, 10.2]),
+        np.array([-5.0, -4.9, -4.8]),
+        0.5,
+        0.5,
+        np.array([60000.0, 60000.1, 60000.2]),
+        observer,
+    )
+    assert ts.patched_sigma_count == 0
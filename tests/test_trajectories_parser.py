@@ -141,6 +141,78 @@ def test_add_from_mpc_80col_monotonic(pyoutfit_env: PyOutfit):
     ), "Total observations should not decrease after add_from_mpc_80col()"
 
 
+def _multi_object_80col(tmp_path: Path, extra: list[str] = ()) -> tuple[Path, list[Path]]:
+    """Concatenate the 80-column files of tests/data, with `extra` lines in between."""
+    files = sorted(_data_dir().glob("*.obs"))
+    lines: list[str] = []
+    for f in files:
+        lines.extend(f.read_text().splitlines())
+        lines.extend(extra)
+    path = tmp_path / "multi.obs"
+    path.write_text("\n".join(lines) + "\n")
+    return path, files
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_mpc_80col_groups_multi_object_files(pyoutfit_env: PyOutfit, tmp_path: Path):
+    """
+    One file holding several objects gives one trajectory per packed designation,
+    identical to reading each object's file with the core reader.
+
+    Exercises: TrajectorySet.from_mpc_80col
+    """
+    import numpy as np
+
+    path, files = _multi_object_80col(tmp_path, extra=[""])
+    ts = TrajectorySet.from_mpc_80col(pyoutfit_env, path)
+
+    expected = {}
+    for f in files:
+        single = TrajectorySet.new_from_mpc_80col(pyoutfit_env, f)
+        (obs,) = single.values()
+        expected[f.read_text()[:12].strip().split()[0]] = obs
+    assert sorted(ts.keys()) == sorted(expected)
+    assert ts.total_observations() == sum(len(o) for o in expected.values())
+    for key, obs in expected.items():
+        got, ref = ts[key].to_numpy(), obs.to_numpy()
+        for col_got, col_ref in zip(got, ref):
+            np.testing.assert_allclose(col_got, col_ref, rtol=1e-12, atol=1e-9)
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_mpc_80col_reports_malformed_lines(pyoutfit_env: PyOutfit, tmp_path: Path):
+    """
+    Malformed lines raise with their line numbers, or are skipped with a warning.
+
+    Exercises: TrajectorySet.from_mpc_80col(on_error=...)
+    """
+    import py_outfit as pf
+
+    good = (_data_dir() / "K25D50B.obs").read_text().splitlines()
+    bad_ra = good[1][:32] + "25 18 37.429" + good[1][44:]
+    unknown_site = good[2][:77] + "ZZZ"
+    path = tmp_path / "bad.obs"
+    path.write_text("\n".join([good[0], "too short", bad_ra, unknown_site] + good[3:]))
+
+    with pytest.raises(ValueError) as excinfo:
+        TrajectorySet.from_mpc_80col(pyoutfit_env, path)
+    message = str(excinfo.value)
+    assert "3 malformed line(s)" in message
+    assert "line 2: expected 80 columns" in message
+    assert "line 3: invalid RA" in message
+    assert "line 4: unknown observatory code" in message
+
+    with pytest.warns(pf.FallbackWarning, match=r"\[skipped_lines\].*line 2"):
+        ts = TrajectorySet.from_mpc_80col(pyoutfit_env, path, on_error="skip")
+    assert ts.keys() == ["K25D50B"]
+    assert ts.total_observations() == len(good) - 3
+
+    with pytest.raises(pf.StrictModeError, match="skipped_lines"):
+        TrajectorySet.from_mpc_80col(pyoutfit_env, path, on_error="skip", strict=True)
+    with pytest.raises(ValueError, match="on_error"):
+        TrajectorySet.from_mpc_80col(pyoutfit_env, path, on_error="ignore")
+
+
 # ---------------------------------------------------------------------------
 # ADES ingestion
 # ---------------------------------------------------------------------------
@@ -194,6 +266,457 @@ def test_add_from_ades_monotonic(pyoutfit_env):
     ), "Total observations should not decrease after add_from_ades()"
 
 
+def _iso_utc(mjd_tt: float) -> str:
+    """ISO 8601 UTC string of an epoch after 2017 (TT - UTC = 69.184 s)."""
+    from datetime import datetime, timedelta
+
+    utc = datetime(1858, 11, 17) + timedelta(days=float(mjd_tt) - 69.184 / 86400.0)
+    return utc.strftime("%Y-%m-%dT%H:%M:%S.%fZ")
+
+
+def _ades_xml(rows: list[dict]) -> str:
+    """Flat ADES XML document with one `<optical>` per row (field → value)."""
+    lines = ["<?xml version='1.0' encoding='UTF-8'?>", '<ades version="2022">']
+    for row in rows:
+        lines.append("  <optical>")
+        lines.extend(f"    <{k}>{v}</{k}>" for k, v in row.items())
+        lines.append("  </optical>")
+    lines.append("</ades>")
+    return "\n".join(lines) + "\n"
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_ades_xml_matches_numpy_ingestion(pyoutfit_env: PyOutfit, traj_data, tmp_path: Path):
+    """
+    An ADES XML export of the synthetic tracklets reads back in the bindings' units
+    (radians, MJD TT), grouped by trkSub, and runs through the batch IOD.
+
+    Exercises: TrajectorySet.from_ades_xml
+    """
+    import numpy as np
+    from py_outfit import IODParams
+
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    rows = [
+        dict(trkSub=f"trk{t}", stn="I41", obsTime=_iso_utc(m), ra=f"{r:.7f}", dec=f"{d:.7f}",
+             rmsRA="0.5", rmsDec="0.5")
+        for t, r, d, m in zip(tid, ra_deg, dec_deg, mjd_tt)
+    ]
+    path = tmp_path / "tracklets.xml"
+    path.write_text(_ades_xml(rows))
+
+    ts = TrajectorySet.from_ades_xml(pyoutfit_env, path)
+    ref = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt,
+        pyoutfit_env.get_observer_from_mpc_code("I41"),
+    )
+    assert sorted(ts.keys()) == ["trk0", "trk1", "trk2"]
+    for t in (0, 1, 2):
+        mjd, ra, dec, sigma_ra, sigma_dec = ts[f"trk{t}"].to_numpy()
+        mjd_ref, ra_ref, dec_ref, _, sigma_dec_ref = ref[t].to_numpy()
+        np.testing.assert_allclose(mjd, mjd_ref, rtol=0, atol=1e-9)
+        np.testing.assert_allclose(ra, ra_ref, rtol=1e-12)
+        np.testing.assert_allclose(dec, dec_ref, rtol=1e-12)
+        np.testing.assert_allclose(sigma_dec, sigma_dec_ref, rtol=1e-12)
+        # rmsRA is on the sky; the stored uncertainty is that of the RA coordinate.
+        np.testing.assert_allclose(sigma_ra * np.cos(dec), sigma_dec_ref, rtol=1e-12)
+
+    params = IODParams.builder().n_noise_realizations(0).build()
+    ok, errors = ts.estimate_all_orbits(pyoutfit_env, params, seed=7)
+    assert set(ok) | set(errors) == {"trk0", "trk1", "trk2"}
+    assert ok
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_ades_xml_defaults_grouping_and_errors(pyoutfit_env: PyOutfit, tmp_path: Path):
+    """
+    Default uncertainties, identifier fallbacks, structured documents and the
+    reporting of invalid records.
+
+    Exercises: TrajectorySet.from_ades_xml
+    """
+    import math
+
+    import py_outfit as pf
+
+    base = dict(stn="F51", obsTime="2024-01-15T12:27:53.2Z", ra="45.0", dec="10.0")
+    rows = [
+        dict(base, provID="2024 AB", rmsRA="0.2", rmsDec="0.3"),
+        dict(base, provID="2024 AB", obsTime="2024-01-15T13:27:53.2Z"),
+        dict(base, trkSub="tk1", provID="2024 AC"),
+    ]
+    path = tmp_path / "flat.xml"
+    path.write_text(_ades_xml(rows))
+
+    with pytest.raises(ValueError, match="bad_sigma"):
+        TrajectorySet.from_ades_xml(pyoutfit_env, path)
+    ts = TrajectorySet.from_ades_xml(pyoutfit_env, path, 1.0, 2.0)
+    assert sorted(ts.keys()) == ["2024 AB", "tk1"]
+    _, _, dec, sigma_ra, sigma_dec = ts["2024 AB"].to_numpy()
+    arcsec = math.radians(1.0 / 3600.0)
+    assert sigma_dec == pytest.approx([0.3 * arcsec, 2.0 * arcsec])
+    assert sigma_ra * math.cos(math.radians(10.0)) == pytest.approx([0.2 * arcsec, arcsec])
+
+    by_prov = TrajectorySet.from_ades_xml(
+        pyoutfit_env, path, 1.0, 2.0, group_by=["provID"]
+    )
+    assert sorted(by_prov.keys()) == ["2024 AB", "2024 AC"]
+
+    structured = TrajectorySet.from_ades_xml(
+        pyoutfit_env, _data_dir() / "example_ades.xml", 0.5, 0.5
+    )
+    _assert_trajset_basic(structured)
+
+    bad = [
+        dict(base, provID="2024 AB", rmsRA="0.2", rmsDec="0.3"),
+        dict(base, ra="400.0", provID="2024 AB"),
+        dict(base, stn="ZZZ", provID="2024 AB"),
+        {k: v for k, v in base.items() if k != "obsTime"} | dict(provID="2024 AB"),
+        dict(base),
+    ]
+    path = tmp_path / "bad.xml"
+    path.write_text(_ades_xml(bad))
+    with pytest.raises(ValueError) as excinfo:
+        TrajectorySet.from_ades_xml(pyoutfit_env, path, 1.0, 1.0)
+    message = str(excinfo.value)
+    assert "4 malformed record(s)" in message
+    assert 'line 12: missing or out of range field "ra"' in message
+    assert 'line 19: unknown observatory code "ZZZ"' in message
+    assert 'line 26: missing field "obsTime"' in message
+    assert "line 32: none of trkSub, provID, permID is set" in message
+
+    with pytest.warns(pf.FallbackWarning, match="skipped_lines"):
+        ts = TrajectorySet.from_ades_xml(pyoutfit_env, path, 1.0, 1.0, on_error="skip")
+    assert ts.total_observations() == 1
+
+    path.write_text("<ades><optical><ra>1</ra></ades>")
+    with pytest.raises(ValueError, match="invalid ADES XML"):
+        TrajectorySet.from_ades_xml(pyoutfit_env, path, on_error="skip")
+
+
+def _ades_psv(blocks: list[tuple[str | None, list[dict]]]) -> str:
+    """ADES PSV document, one block per `(mpcCode, rows)` with padded columns."""
+    lines = []
+    for code, rows in blocks:
+        lines.append("# version=2017")
+        if code is not None:
+            lines += ["# observatory", f"! mpcCode {code}", "! name Somewhere"]
+        names = list(dict.fromkeys(k for row in rows for k in row))
+        width = {k: max([len(k)] + [len(str(row.get(k, ""))) for row in rows]) for k in names}
+        lines.append("|".join(k.ljust(width[k]) for k in names))
+        lines += ["|".join(str(row.get(k, "")).ljust(width[k]) for k in names) for row in rows]
+    return "\n".join(lines) + "\n"
+
+
+def _ades_json(blocks: list[tuple[str | None, list[dict]]]) -> str:
+    """ADES JSON document, one `obsBlock` per `(mpcCode, rows)`."""
+    import json
+
+    obs_blocks = [
+        dict(
+            obsContext=dict(observatory=dict(mpcCode=code)) if code is not None else {},
+            obsData=dict(optical=rows),
+        )
+        for code, rows in blocks
+    ]
+    return json.dumps(dict(ades=dict(version="2022", obsBlock=obs_blocks)))
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_ades_psv_and_json_match_xml(pyoutfit_env: PyOutfit, traj_data, tmp_path: Path):
+    """
+    The same records, written as XML, PSV and JSON, read back into the same set,
+    including mixed stations, block stations and the photometric fields.
+
+    Exercises: TrajectorySet.from_ades_psv, TrajectorySet.from_ades_json
+    """
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    rows = [
+        dict(trkSub=f"trk{t}", stn="I41" if i % 2 else "F51", obsTime=_iso_utc(m),
+             ra=f"{r:.7f}", dec=f"{d:.7f}", rmsRA="0.5", rmsDec="0.4", astCat="Gaia2",
+             mag="19.5", band="G")
+        for i, (t, r, d, m) in enumerate(zip(tid, ra_deg, dec_deg, mjd_tt))
+    ]
+    paths = {ext: tmp_path / f"tracklets.{ext}" for ext in ("xml", "psv", "json")}
+    paths["xml"].write_text(_ades_xml(rows))
+    paths["psv"].write_text(_ades_psv([(None, rows)]))
+    # Numbers as JSON numbers; the first record (F51) takes its station from its block.
+    json_rows = [
+        {k: float(v) if k in ("ra", "dec", "rmsRA", "mag") else v for k, v in row.items()}
+        for row in rows
+    ]
+    first = {k: v for k, v in json_rows[0].items() if k != "stn"}
+    paths["json"].write_text(_ades_json([("F51", [first]), (None, json_rows[1:])]))
+
+    xml = TrajectorySet.from_ades_xml(pyoutfit_env, paths["xml"])
+    psv = TrajectorySet.from_ades_psv(pyoutfit_env, paths["psv"])
+    js = TrajectorySet.from_ades_json(pyoutfit_env, paths["json"])
+    assert sorted(xml.keys()) == ["trk0", "trk1", "trk2"]
+    _same_content(xml, psv)
+    _same_content(xml, js)
+
+    # Block stations in PSV.
+    f51 = [{k: v for k, v in row.items() if k != "stn"} for row in rows if row["stn"] == "F51"]
+    i41 = [row for row in rows if row["stn"] == "I41"]
+    paths["psv"].write_text(_ades_psv([("F51", f51), (None, i41)]))
+    blocks = TrajectorySet.from_ades_psv(pyoutfit_env, paths["psv"])
+    assert blocks.total_observations() == xml.total_observations()
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_ades_psv_and_json_report_errors(pyoutfit_env: PyOutfit, tmp_path: Path):
+    """
+    Invalid rows and records are reported with their position; syntax errors and
+    missing field headers raise whatever `on_error`.
+
+    Exercises: TrajectorySet.from_ades_psv, TrajectorySet.from_ades_json
+    """
+    import py_outfit as pf
+
+    base = dict(provID="2024 AB", stn="F51", obsTime="2024-01-15T12:27:53.2Z", ra="45.0",
+                dec="10.0")
+    path = tmp_path / "obs.psv"
+    path.write_text(
+        "# version=2017\n"
+        "provID |stn|obsTime                  |ra  |dec |mag\n"
+        "2024 AB|F51|2024-01-15T12:27:53.2Z   |45.0|10.0|19.1\n"
+        "2024 AB|F51|2024-01-15T13:27:53.2Z   |45.0|10.0\n"
+        "2024 AB|F51|2024-01-15T14:27:53.2Z   |45.0|10.0|bright\n"
+    )
+    with pytest.raises(ValueError) as excinfo:
+        TrajectorySet.from_ades_psv(pyoutfit_env, path, 1.0, 1.0)
+    message = str(excinfo.value)
+    assert "2 malformed record(s)" in message
+    assert "line 4: expected 6 fields, got 5" in message
+    assert 'line 5: invalid mag "bright"' in message
+    with pytest.warns(pf.FallbackWarning, match="skipped_lines"):
+        ts = TrajectorySet.from_ades_psv(pyoutfit_env, path, 1.0, 1.0, on_error="skip")
+    assert ts.total_observations() == 1
+
+    path.write_text("2024 AB|F51|2024-01-15T12:27:53.2Z|45.0|10.0\n")
+    with pytest.raises(ValueError, match="invalid ADES PSV.*line 1: expected a field header"):
+        TrajectorySet.from_ades_psv(pyoutfit_env, path, 1.0, 1.0, on_error="skip")
+
+    path = tmp_path / "obs.json"
+    path.write_text(_ades_json([(None, [base, dict(base, stn="ZZZ"), dict(base, ra=None)])]))
+    with pytest.raises(ValueError) as excinfo:
+        TrajectorySet.from_ades_json(pyoutfit_env, path, 1.0, 1.0)
+    message = str(excinfo.value)
+    assert 'record 2: unknown observatory code "ZZZ"' in message
+    assert 'record 3: missing or out of range field "ra"' in message
+
+    path.write_text('{"ades": {"optical": [')
+    with pytest.raises(ValueError, match="invalid ADES JSON"):
+        TrajectorySet.from_ades_json(pyoutfit_env, path, on_error="skip")
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_ades_honours_rejection_flags(pyoutfit_env: PyOutfit, tmp_path: Path):
+    """
+    Records flagged by `selAst` or `exclude` are dropped by default, or kept and flagged
+    per observation with `include_rejected=True`.
+
+    Exercises: TrajectorySet.from_ades_xml / from_ades_psv, Observations.rejected,
+    TrajectorySet.drop_rejected
+    """
+    base = dict(trkSub="tk1", stn="F51", ra="45.0", dec="10.0", rmsRA="0.2", rmsDec="0.2")
+    rows = [
+        dict(base, obsTime="2024-01-15T12:00:00Z", selAst="A", exclude=""),
+        dict(base, obsTime="2024-01-15T12:30:00Z", selAst="D", exclude=""),
+        dict(base, obsTime="2024-01-15T13:00:00Z", selAst="a", exclude="1"),
+        dict(base, obsTime="2024-01-15T13:30:00Z", selAst="A", exclude="no"),
+        dict(base, trkSub="tk2", obsTime="2024-01-15T14:00:00Z", selAst="d", exclude=""),
+    ]
+    xml, psv = tmp_path / "flags.xml", tmp_path / "flags.psv"
+    xml.write_text(_ades_xml(rows))
+    psv.write_text(_ades_psv([(None, rows)]))
+
+    for read in (TrajectorySet.from_ades_xml, TrajectorySet.from_ades_psv):
+        path = xml if read is TrajectorySet.from_ades_xml else psv
+        ts = read(pyoutfit_env, path)
+        assert list(ts.keys()) == ["tk1"]
+        assert len(ts["tk1"]) == 2
+        assert not ts["tk1"].rejected().any()
+
+        flagged = read(pyoutfit_env, path, include_rejected=True)
+        assert sorted(flagged.keys()) == ["tk1", "tk2"]
+        assert flagged["tk1"].rejected().tolist() == [False, True, True, False]
+        assert flagged["tk2"].rejected().tolist() == [True]
+        assert flagged.drop_rejected() == {"tk1": 2, "tk2": 1}
+        _same_content(flagged, ts)
+        assert flagged.drop_rejected() == {}
+
+
+_OBS_CONTEXT = {
+    "observers": ["A. Observer", "B. Observer"],
+    "measurers": "C. Measurer",
+    "telescope": {"name": "Survey", "design": "Reflector", "aperture": 1.2, "detector": "CCD"},
+    "comment": "synthetic tracklets",
+}
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_to_ades_xml_round_trip(pyoutfit_env: PyOutfit, traj_data, tmp_path: Path):
+    """
+    An ADES XML export reads back with from_ades_xml to the same observations, up to
+    the precision of the written fields, and validates against the ADES schema when
+    one is available (path in the ADES_XSD environment variable, lxml installed).
+
+    Exercises: TrajectorySet.to_ades_xml
+    """
+    import os
+    import numpy as np
+
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.3, mjd_tt,
+        pyoutfit_env.get_observer_from_mpc_code("I41"),
+    )
+    path = tmp_path / "submission.xml"
+    n = ts.to_ades_xml(pyoutfit_env, path, _OBS_CONTEXT, ast_cat="Gaia2")
+    assert n == ts.total_observations()
+
+    text = path.read_text()
+    assert text.count("<optical>") == n
+    assert "<stn>I41</stn>" in text and "<astCat>Gaia2</astCat>" in text
+    assert "<name>A. Observer</name>" in text and "<name>C. Measurer</name>" in text
+
+    back = TrajectorySet.from_ades_xml(pyoutfit_env, path)
+    assert sorted(back.keys()) == sorted(str(k) for k in ts.keys())
+    for key in ts.keys():
+        mjd, ra, dec, sigma_ra, sigma_dec = back[str(key)].to_numpy()
+        mjd_ref, ra_ref, dec_ref, sigma_ra_ref, sigma_dec_ref = ts[key].to_numpy()
+        np.testing.assert_allclose(mjd, mjd_ref, rtol=0, atol=1e-8)
+        np.testing.assert_allclose(ra, ra_ref, rtol=0, atol=1e-10)
+        np.testing.assert_allclose(dec, dec_ref, rtol=0, atol=1e-10)
+        np.testing.assert_allclose(sigma_ra, sigma_ra_ref, rtol=1e-3)
+        np.testing.assert_allclose(sigma_dec, sigma_dec_ref, rtol=1e-3)
+
+    xsd = os.environ.get("ADES_XSD")
+    if not xsd:
+        pytest.skip("ADES_XSD not set; schema validation skipped")
+    etree = pytest.importorskip("lxml.etree")
+    schema = etree.XMLSchema(etree.parse(xsd))
+    assert schema.validate(etree.parse(str(path))), schema.error_log
+
+
+def test_to_ades_xml_rejects_invalid_input(
+    pyoutfit_env: PyOutfit, observer, traj_data, tmp_path: Path
+):
+    """
+    Sites without an MPC code, ids unfit for the id field and malformed contexts are
+    reported before anything is written.
+
+    Exercises: TrajectorySet.to_ades_xml
+    """
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    i41 = pyoutfit_env.get_observer_from_mpc_code("I41")
+    path = tmp_path / "submission.xml"
+
+    pyoutfit_env.add_observer(observer)
+    user_site = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, observer
+    )
+    with pytest.raises(ValueError, match="has no MPC code"):
+        user_site.to_ades_xml(pyoutfit_env, path, _OBS_CONTEXT)
+
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, i41
+    )
+    long_ids = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid + 123456789, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, i41
+    )
+    with pytest.raises(ValueError, match="choose another id_field"):
+        long_ids.to_ades_xml(pyoutfit_env, path, _OBS_CONTEXT)
+    with pytest.raises(ValueError, match="id_field"):
+        ts.to_ades_xml(pyoutfit_env, path, _OBS_CONTEXT, id_field="name")
+
+    missing = {k: v for k, v in _OBS_CONTEXT.items() if k != "telescope"}
+    with pytest.raises(KeyError, match="telescope"):
+        ts.to_ades_xml(pyoutfit_env, path, missing)
+    with pytest.raises(ValueError, match="unknown"):
+        ts.to_ades_xml(pyoutfit_env, path, {**_OBS_CONTEXT, "site": "I41"})
+    assert not path.exists()
+
+    with pytest.raises(OSError):
+        ts.to_ades_xml(pyoutfit_env, tmp_path / "missing" / "out.xml", _OBS_CONTEXT)
+
+
+# First record of tests/data/2015AB.obs, and its export (the discovery asterisk,
+# magnitude, band and reference are not stored).
+_MPC_RECORD = "     K09R05F* C2009 09 15.22735 22 52 23.37 -14 47 05.4          20.7 Vr~097wG96"
+_MPC_EXPORT = "     K09R05F  C2009 09 15.22735 22 52 23.37 -14 47 05.4 " + " " * 21 + "G96"
+
+
+def test_to_mpc_80col_matches_known_record(pyoutfit_env: PyOutfit, tmp_path: Path):
+    """
+    A published MPC record read with from_mpc_80col is written back with the same
+    designation, date, RA, Dec and observatory columns, by both exports.
+
+    Exercises: Observations.to_mpc_80col, TrajectorySet.to_mpc_80col
+    """
+    src = tmp_path / "record.obs"
+    src.write_text(_MPC_RECORD + "\n")
+    ts = TrajectorySet.from_mpc_80col(pyoutfit_env, src)
+
+    lines = ts["K09R05F"].to_mpc_80col("K09R05F", "G96")
+    assert lines == [_MPC_EXPORT]
+    assert len(lines[0]) == 80
+
+    out = tmp_path / "export.obs"
+    assert ts.to_mpc_80col(pyoutfit_env, out) == 1
+    assert out.read_text() == _MPC_EXPORT + "\n"
+    back = TrajectorySet.from_mpc_80col(pyoutfit_env, out)
+    assert back["K09R05F"].to_mpc_80col("K09R05F", "G96") == [_MPC_EXPORT]
+
+
+def test_to_mpc_80col_rounding_and_designations(pyoutfit_env: PyOutfit, tmp_path: Path):
+    """
+    Values are rounded to their last digit with carries into the minutes, hours and
+    day; integer ids are zero-padded or packed numbers; ids and sites without an
+    80-column form are rejected.
+
+    Exercises: Observations.to_mpc_80col, TrajectorySet.to_mpc_80col
+    """
+    import numpy as np
+
+    i41 = pyoutfit_env.get_observer_from_mpc_code("I41")
+    # 23h59m59.996s, -0.002", and a UTC day fraction of .999996 (TT - UTC = 69.184 s).
+    ra = np.array([2 * np.pi - 0.004 * np.pi / 43200, 1.0])
+    dec = np.array([-1e-8, -0.5])
+    mjd_tt = np.array([60000.999996 + 69.184 / 86400, 60001.5 + 69.184 / 86400])
+    ts = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, np.array([123456, 123456], dtype=np.uint32), ra, dec,
+        1e-6, 1e-6, mjd_tt, i41,
+    )
+    first, second = ts[123456].to_mpc_80col(123456, "I41")
+    assert first[:56] == "C3456         C2023 02 26.00000 00 00 00.00 +00 00 00.0 "
+    assert second[15:56] == "2023 02 26.50000 03 49 10.99 -28 38 52.4 "
+    assert ts[123456].to_mpc_80col(433, "I41")[0][:12] == "00433       "
+    assert ts[123456].to_mpc_80col(620000, "I41")[0][:12] == "~0000       "
+
+    with pytest.raises(ValueError, match="80-column designation"):
+        ts[123456].to_mpc_80col("K09R05FX", "I41")
+    with pytest.raises(ValueError, match="largest packed MPC number"):
+        ts[123456].to_mpc_80col(15396336, "I41")
+    with pytest.raises(ValueError, match="observatory code"):
+        ts[123456].to_mpc_80col(1, "I4")
+
+    from py_outfit import Observer
+
+    user_site = Observer(
+        longitude=10.0, latitude=45.0, elevation=100.0, name="Backyard",
+        ra_accuracy=None, dec_accuracy=None,
+    )
+    pyoutfit_env.add_observer(user_site)
+    user = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, np.array([1], dtype=np.uint32), ra[:1], dec[:1], 1e-6, 1e-6,
+        mjd_tt[:1], user_site,
+    )
+    with pytest.raises(ValueError, match="has no MPC code"):
+        user.to_mpc_80col(pyoutfit_env, tmp_path / "user.obs")
+
+
 # ---------------------------------------------------------------------------
 # Mixed ingestion
 # ---------------------------------------------------------------------------
@@ -241,3 +764,403 @@ def test_mixed_ingestion_monotonic(pyoutfit_env):
             pytest.skip("Only one ADES file available")
     else:
         pytest.skip("No files found for mixed ingestion test")
+
+
+# ---------------------------------------------------------------------------
+# Format auto-detection
+# ---------------------------------------------------------------------------
+
+
+def _same_content(a: TrajectorySet, b: TrajectorySet):
+    import numpy as np
+
+    assert sorted(a.keys(), key=str) == sorted(b.keys(), key=str)
+    for key in a.keys():
+        for col_a, col_b in zip(a[key].to_numpy(), b[key].to_numpy()):
+            np.testing.assert_allclose(col_a, col_b, rtol=1e-12, atol=0)
+
+
+def _write_tabular(traj_data, tmp_path: Path) -> dict[str, Path]:
+    """Write `traj_data` as CSV, padded PSV (with a comment header) and Parquet."""
+    import pandas as pd
+
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    df = pd.DataFrame({"tid": tid.astype("int64"), "mjd": mjd_tt, "ra": ra_deg, "dec": dec_deg})
+
+    paths = {"csv": tmp_path / "obs.csv", "psv": tmp_path / "obs.psv", "parquet": tmp_path / "obs.parquet"}
+    df.to_csv(paths["csv"], index=False, float_format="%.17g")
+    with open(paths["psv"], "w") as fh:
+        fh.write("# exported for the from_file test\n")
+        fh.write(" tid | mjd                   | ra                    | dec\n")
+        for row in df.itertuples(index=False):
+            fh.write(f" {row.tid:3d} | {row.mjd:<21.17g} | {row.ra:<21.17g} | {row.dec:.17g}\n")
+    df.to_parquet(paths["parquet"], index=False)
+    return paths
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_file_dispatches_file_formats(pyoutfit_env: PyOutfit):
+    """MPC 80-column and ADES XML files go to their dedicated loaders."""
+    data_dir = _data_dir()
+    obs80 = data_dir / "2015AB.obs"
+    ades = data_dir / "example_ades.xml"
+
+    _same_content(
+        TrajectorySet.from_file(pyoutfit_env, obs80),
+        TrajectorySet.new_from_mpc_80col(pyoutfit_env, obs80),
+    )
+    _same_content(
+        TrajectorySet.from_file(pyoutfit_env, str(ades), error_ra_arcsec=0.5, error_dec_arcsec=0.5),
+        TrajectorySet.new_from_ades(pyoutfit_env, ades, 0.5, 0.5),
+    )
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+@pytest.mark.parametrize("fmt", ["psv", "psv-no-version", "json"])
+def test_from_file_dispatches_ades_psv_and_json(
+    pyoutfit_env: PyOutfit, traj_data, tmp_path: Path, fmt: str
+):
+    """ADES PSV (version line or field header) and ADES JSON go to their loaders."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    rows = [
+        dict(trkSub=f"trk{t}", stn="I41", obsTime=_iso_utc(m), ra=f"{r:.7f}",
+             dec=f"{d:.7f}", rmsRA="0.5", rmsDec="0.4")
+        for t, r, d, m in zip(tid, ra_deg, dec_deg, mjd_tt)
+    ]
+    # The extension is irrelevant: detection relies on the content.
+    path = tmp_path / "tracklets.dat"
+    if fmt == "json":
+        path.write_text(_ades_json([(None, rows)]))
+        ref = TrajectorySet.from_ades_json(pyoutfit_env, path)
+    else:
+        text = _ades_psv([(None, rows)])
+        if fmt == "psv-no-version":
+            text = text.removeprefix("# version=2017\n")
+        path.write_text(text)
+        ref = TrajectorySet.from_ades_psv(pyoutfit_env, path)
+
+    got = TrajectorySet.from_file(pyoutfit_env, path)
+    assert got.total_observations() == len(rows)
+    _same_content(got, ref)
+
+    with pytest.raises(ValueError, match="observatory codes"):
+        TrajectorySet.from_file(pyoutfit_env, path, pyoutfit_env.get_observer_from_mpc_code("I41"))
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+@pytest.mark.parametrize("fmt", ["csv", "psv", "parquet"])
+def test_from_file_dispatches_tabular_formats(
+    pyoutfit_env: PyOutfit, ZTF_observatory, traj_data, tmp_path: Path, fmt: str
+):
+    """CSV, PSV and Parquet files match direct NumPy ingestion."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    paths = _write_tabular(traj_data, tmp_path)
+
+    # The extension is irrelevant: detection relies on the content.
+    blind = paths[fmt].with_suffix(".dat")
+    paths[fmt].rename(blind)
+
+    got = TrajectorySet.from_file(
+        pyoutfit_env, blind, ZTF_observatory, error_ra=0.5, error_dec=0.5
+    )
+    ref = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+    )
+    _same_content(got, ref)
+
+
+def test_from_file_reports_considered_formats(pyoutfit_env: PyOutfit, tmp_path: Path):
+    unknown = tmp_path / "notes.dat"
+    unknown.write_text("just some words\nwithout any delimiter\n")
+    with pytest.raises(ValueError, match="considered"):
+        TrajectorySet.from_file(pyoutfit_env, unknown)
+
+
+def test_from_file_rejects_observer_for_self_describing_formats(
+    pyoutfit_env: PyOutfit, ZTF_observatory
+):
+    with pytest.raises(ValueError, match="observatory codes"):
+        TrajectorySet.from_file(pyoutfit_env, _data_dir() / "2015AB.obs", ZTF_observatory)
+
+
+def test_from_file_requires_observer_for_csv(pyoutfit_env: PyOutfit, tmp_path: Path):
+    path = tmp_path / "obs.csv"
+    path.write_text("tid,mjd,ra,dec\n1,60000.0,10.0,5.0\n")
+    with pytest.raises(ValueError, match="observer is required"):
+        TrajectorySet.from_file(pyoutfit_env, path)
+
+
+# ---------------------------------------------------------------------------
+# Time order
+# ---------------------------------------------------------------------------
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_text_readers_sort_unsorted_files(pyoutfit_env: PyOutfit, traj_data, tmp_path: Path):
+    """
+    Files listing observations out of time order give the same sets as the sorted
+    files, through the MPC 80-column and ADES readers and through appends.
+
+    Exercises: TrajectorySet.is_time_sorted
+    """
+    import py_outfit as pf
+
+    source = _data_dir() / "2015AB.obs"
+    lines = source.read_text().splitlines()
+    reference = TrajectorySet.new_from_mpc_80col(pyoutfit_env, source)
+    assert reference.is_time_sorted()
+
+    reversed_80col = tmp_path / "reversed.obs"
+    reversed_80col.write_text("\n".join(lines[::-1]) + "\n")
+    for reader in (TrajectorySet.new_from_mpc_80col, TrajectorySet.from_mpc_80col):
+        with pytest.warns(pf.FallbackWarning, match="unsorted_epochs"):
+            ts = reader(pyoutfit_env, reversed_80col)
+        assert ts.is_time_sorted()
+    _same_content(ts, TrajectorySet.from_mpc_80col(pyoutfit_env, source))
+
+    # Later half first, earlier half appended: merged into place.
+    halves = [tmp_path / "late.obs", tmp_path / "early.obs"]
+    halves[0].write_text("\n".join(lines[len(lines) // 2 :]) + "\n")
+    halves[1].write_text("\n".join(lines[: len(lines) // 2]) + "\n")
+    ts = TrajectorySet.new_from_mpc_80col(pyoutfit_env, halves[0])
+    ts.add_from_mpc_80col(pyoutfit_env, halves[1])
+    assert ts.is_time_sorted()
+    _same_content(ts, reference)
+
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    rows = [
+        dict(trkSub=f"trk{t}", stn="I41", obsTime=_iso_utc(m), ra=f"{r:.7f}",
+             dec=f"{d:.7f}", rmsRA="0.5", rmsDec="0.4", astCat="Gaia2")
+        for t, r, d, m in zip(tid, ra_deg, dec_deg, mjd_tt)
+    ]
+    sorted_psv, reversed_psv = tmp_path / "sorted.psv", tmp_path / "reversed.psv"
+    sorted_psv.write_text(_ades_psv([(None, rows)]))
+    reversed_psv.write_text(_ades_psv([(None, rows[::-1])]))
+    with pytest.warns(pf.FallbackWarning, match="unsorted_epochs"):
+        ts = TrajectorySet.from_ades_psv(pyoutfit_env, reversed_psv)
+    assert ts.is_time_sorted()
+    _same_content(ts, TrajectorySet.from_ades_psv(pyoutfit_env, sorted_psv))
+
+
+# ---------------------------------------------------------------------------
+# CSV ingestion
+# ---------------------------------------------------------------------------
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_csv_selects_columns_by_name_or_index(
+    pyoutfit_env: PyOutfit, ZTF_observatory, traj_data, tmp_path: Path
+):
+    """Named and indexed columns, quoting and gzip all read back the NumPy set."""
+    import gzip
+
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    ref = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+    )
+    lines = ["# survey export", "ssnamenr;mjd;RA_deg;Dec_deg;comment"]
+    lines += [
+        f'{t};{m:.17g};{r:.17g};{d:.17g};"free text; with ""quotes"""'
+        for t, r, d, m in zip(tid, ra_deg, dec_deg, mjd_tt)
+    ]
+    text = "\n".join(lines) + "\n"
+    named = tmp_path / "obs.csv"
+    named.write_text(text)
+    columns = {"ra": "RA_deg", "dec": "Dec_deg", "time": "mjd", "id": "ssnamenr"}
+
+    def read(path, **kw) -> TrajectorySet:
+        return TrajectorySet.from_csv(
+            pyoutfit_env, path, ZTF_observatory, delimiter=";", error_ra=0.5,
+            error_dec=0.5, **kw
+        )
+
+    _same_content(read(named, columns=columns), ref)
+    assert sorted(read(named, columns=columns).keys()) == sorted(set(tid.tolist()))
+
+    compressed = tmp_path / "obs.csv.gz"
+    compressed.write_bytes(gzip.compress(text.encode()))
+    _same_content(read(compressed, columns=columns), ref)
+
+    headless = tmp_path / "headless.csv"
+    headless.write_text("\n".join(lines[2:]) + "\n")
+    by_index = {"id": 0, "time": 1, "ra": 2, "dec": 3}
+    _same_content(read(headless, columns=by_index, header=False), ref)
+    with pytest.raises(ValueError, match="header=False"):
+        read(headless, columns=columns, header=False)
+    with pytest.raises(KeyError, match='column "RA" not in the header'):
+        read(named, columns=dict(columns, ra="RA"))
+
+    # Per-row uncertainties are read from their columns when no uniform value is given.
+    sigma = tmp_path / "sigma.csv"
+    sigma.write_text(
+        "trajectory_id,mjd_tt,ra,dec,sigma_ra,sigma_dec\n"
+        + "".join(
+            f"{t},{m:.17g},{r:.17g},{d:.17g},0.5,0.5\n"
+            for t, r, d, m in zip(tid, ra_deg, dec_deg, mjd_tt)
+        )
+    )
+    _same_content(TrajectorySet.from_csv(pyoutfit_env, sigma, ZTF_observatory), ref)
+
+
+def test_from_csv_reports_bad_rows_with_line_numbers(
+    pyoutfit_env: PyOutfit, ZTF_observatory, tmp_path: Path
+):
+    """Unreadable rows are listed by line; `on_error="skip"` keeps the others."""
+    import py_outfit as pf
+
+    path = tmp_path / "bad.csv"
+    path.write_text(
+        "id,mjd_tt,ra,dec\n"
+        "a,60000.0,10.0,5.0\n"
+        "a,60000.1,10.1\n"
+        "\n"
+        "a,sixty,10.2,5.2\n"
+        ",60000.3,10.3,5.3\n"
+        "a,60000.4,10.4,5.4\n"
+    )
+    columns = {"id": "id"}
+
+    def read(**kw) -> TrajectorySet:
+        return TrajectorySet.from_csv(
+            pyoutfit_env, path, ZTF_observatory, columns=columns, error_ra=0.5,
+            error_dec=0.5, **kw
+        )
+
+    with pytest.raises(ValueError) as excinfo:
+        read()
+    message = str(excinfo.value)
+    assert "3 malformed row(s)" in message
+    assert "line 3: expected at least 4 fields, got 3" in message
+    assert 'line 5: invalid time "sixty"' in message
+    assert "line 6: empty trajectory id" in message
+
+    with pytest.warns(pf.FallbackWarning, match="skipped_lines"):
+        ts = read(on_error="skip")
+    assert ts.keys() == ["a"] and ts.total_observations() == 2
+
+    path.write_text('id,mjd_tt,ra,dec\na,60000.0,10.0,"5.0\n')
+    with pytest.raises(ValueError, match="line 2: unterminated quoted field"):
+        read()
+    with pytest.raises(ValueError, match="delimiter"):
+        read(delimiter="ab")
+
+
+def test_from_csv_reads_quoted_fields(pyoutfit_env: PyOutfit, ZTF_observatory, tmp_path: Path):
+    """Quoted fields hold the delimiter, doubled quotes and line breaks."""
+    import py_outfit as pf
+
+    path = tmp_path / "quoted.csv"
+    path.write_text(
+        'trajectory_id,mjd_tt,ra,dec,comment\n'
+        '"2015 AB, night 1",60000.0,10.0,5.0,plain\n'
+        '"2015 AB, night 1",60000.1,10.1,5.1,"two\nlines"\n'
+        '"the ""bright"" one",60000.2,10.2,5.2,"a, b"\n'
+        'bad,60000.3,10.3\n'
+    )
+    with pytest.raises(ValueError, match="line 6: expected at least 4 fields"):
+        TrajectorySet.from_csv(pyoutfit_env, path, ZTF_observatory, error_ra=0.5, error_dec=0.5)
+
+    with pytest.warns(pf.FallbackWarning, match="skipped_lines"):
+        ts = TrajectorySet.from_csv(
+            pyoutfit_env, path, ZTF_observatory, error_ra=0.5, error_dec=0.5, on_error="skip"
+        )
+    assert sorted(ts.keys()) == ["2015 AB, night 1", 'the "bright" one']
+    assert len(ts["2015 AB, night 1"]) == 2
+
+
+# ---------------------------------------------------------------------------
+# Multi-file ingestion
+# ---------------------------------------------------------------------------
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_files_merges_ades_nights(pyoutfit_env: PyOutfit, traj_data, tmp_path: Path):
+    """
+    Per-night ADES files merge by trkSub into the set of the whole export, a detection
+    exported with both nights being kept once.
+
+    Exercises: TrajectorySet.from_files
+    """
+    import numpy as np
+
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    rows = [
+        dict(trkSub=f"trk{t}", stn="I41", obsTime=_iso_utc(m), ra=f"{r:.7f}", dec=f"{d:.7f}",
+             rmsRA="0.5", rmsDec="0.5")
+        for t, r, d, m in zip(tid, ra_deg, dec_deg, mjd_tt)
+    ]
+    cut = np.median(mjd_tt)
+    night1 = [row for row, m in zip(rows, mjd_tt) if m < cut]
+    night2 = [row for row, m in zip(rows, mjd_tt) if m >= cut]
+    whole, first, second = (tmp_path / f"{name}.xml" for name in ("whole", "night1", "night2"))
+    whole.write_text(_ades_xml(rows))
+    first.write_text(_ades_xml(night1))
+    second.write_text(_ades_xml(night1[-1:] + night2))
+
+    ref = TrajectorySet.from_ades_xml(pyoutfit_env, whole)
+    for parallel in (True, False):
+        ts = TrajectorySet.from_files(pyoutfit_env, [first, second], parallel=parallel)
+        _same_content(ts, ref)
+        assert ts.duplicates_removed == {night1[-1]["trkSub"]: 1}
+
+    kept = TrajectorySet.from_files(pyoutfit_env, [first, second], dedup=False)
+    assert kept.total_observations() == ref.total_observations() + 1
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_files_reads_tabular_files(
+    pyoutfit_env: PyOutfit, ZTF_observatory, traj_data, tmp_path: Path
+):
+    """
+    CSV and Parquet files split by night read back the NumPy set; bad arguments and
+    unreadable files raise before any ingestion.
+
+    Exercises: TrajectorySet.from_files
+    """
+    import numpy as np
+
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    ref = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+    )
+    nights = (mjd_tt < np.median(mjd_tt), mjd_tt >= np.median(mjd_tt))
+    csv = []
+    for k, rows in enumerate(nights):
+        path = tmp_path / f"night{k}.csv"
+        path.write_text(
+            "trajectory_id,mjd_tt,ra,dec\n"
+            + "".join(
+                f"{t},{m:.17g},{r:.17g},{d:.17g}\n"
+                for t, r, d, m in zip(tid[rows], ra_deg[rows], dec_deg[rows], mjd_tt[rows])
+            )
+        )
+        csv.append(path)
+
+    def read(paths, fmt, **kw) -> TrajectorySet:
+        return TrajectorySet.from_files(
+            pyoutfit_env, paths, fmt, ZTF_observatory, error_ra=0.5, error_dec=0.5, **kw
+        )
+
+    _same_content(read(csv, "csv"), ref)
+
+    with pytest.raises(ValueError, match="format must be"):
+        read(csv, "fits")
+    with pytest.raises(ValueError, match="observer is required"):
+        TrajectorySet.from_files(pyoutfit_env, csv, "csv")
+    with pytest.raises(ValueError, match="observer is not used"):
+        read(csv, "ades_xml")
+    with pytest.raises(OSError, match="missing.csv"):
+        read(csv + [tmp_path / "missing.csv"], "csv")
+
+    pd = pytest.importorskip("pandas")
+    pytest.importorskip("pyarrow")
+    parquet = []
+    for k, rows in enumerate(nights):
+        path = tmp_path / f"night{k}.parquet"
+        pd.DataFrame(
+            {"tid": tid[rows].astype("int64"), "mjd": mjd_tt[rows], "ra": ra_deg[rows],
+             "dec": dec_deg[rows]}
+        ).to_parquet(path, index=False)
+        parquet.append(path)
+    _same_content(read(parquet, "parquet"), ref)
+    _same_content(read(parquet, "parquet", parallel=False), ref)
@@ -1,10 +1,14 @@
+import json
 import math
-from typing import Tuple
+import subprocess
+import sys
+from pathlib import Path
+from typing import Optional, Tuple
 import numpy as np
 import pytest
 import py_outfit
 
-from py_outfit import GaussResult, KeplerianElements, TrajectorySet, PyOutfit, Observer
+from py_outfit import GaussResult, KeplerianElements, Observations, TrajectorySet, PyOutfit, Observer
 
 
 def _build_arrays_degrees() -> (
@@ -143,6 +147,142 @@ def test_length_mismatch_raises(pyoutfit_env: PyOutfit, observer: Observer):
         )
 
 
+def _build_bad_sigma_set(env: PyOutfit, observer: Observer, **kwargs) -> TrajectorySet:
+    """Degrees batch whose RA sigma is 0 and DEC sigma is -1 ("unknown" encodings)."""
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    return TrajectorySet.from_numpy_degrees(
+        env, tid, ra_deg, dec_deg, 0.0, -1.0, mjd, observer, **kwargs
+    )
+
+
+def _all_sigmas(ts: TrajectorySet) -> Tuple[np.ndarray, np.ndarray]:
+    arrays = [obs.to_numpy() for obs in ts.values()]
+    return (
+        np.concatenate([a[3] for a in arrays]),
+        np.concatenate([a[4] for a in arrays]),
+    )
+
+
+def test_bad_sigma_error_policy_is_default(pyoutfit_env: PyOutfit, observer: Observer):
+    with pytest.raises(ValueError, match="non-positive"):
+        _build_bad_sigma_set(pyoutfit_env, observer)
+    with pytest.raises(ValueError, match="non-positive"):
+        _build_bad_sigma_set(pyoutfit_env, observer, bad_sigma="error")
+
+
+def test_bad_sigma_value_policy(pyoutfit_env: PyOutfit, observer: Observer):
+    ts = _build_bad_sigma_set(
+        pyoutfit_env, observer, bad_sigma="value", bad_sigma_value=0.7
+    )
+    assert ts.patched_sigma_count == ts.total_observations() == 5
+
+    sra, sdec = _all_sigmas(ts)
+    np.testing.assert_allclose(sra, 0.7 * py_outfit.RADSEC)
+    np.testing.assert_allclose(sdec, 0.7 * py_outfit.RADSEC)
+
+
+def test_bad_sigma_model_policy(pyoutfit_env: PyOutfit):
+    site = Observer(
+        longitude=10.0,
+        latitude=45.0,
+        elevation=1.0,
+        name="Modelled site",
+        ra_accuracy=0.4 * py_outfit.RADSEC,
+        dec_accuracy=0.6 * py_outfit.RADSEC,
+    )
+    ts = _build_bad_sigma_set(pyoutfit_env, site, bad_sigma="model")
+    assert ts.patched_sigma_count == 5
+
+    sra, sdec = _all_sigmas(ts)
+    np.testing.assert_allclose(sra, 0.4 * py_outfit.RADSEC)
+    np.testing.assert_allclose(sdec, 0.6 * py_outfit.RADSEC)
+
+
+def test_bad_sigma_model_policy_requires_accuracy(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    # The `observer` fixture has no error-model accuracy.
+    with pytest.raises(ValueError, match="error-model accuracy"):
+        _build_bad_sigma_set(pyoutfit_env, observer, bad_sigma="model")
+
+
+def test_bad_sigma_argument_validation(pyoutfit_env: PyOutfit, observer: Observer):
+    with pytest.raises(ValueError, match="bad_sigma_value"):
+        _build_bad_sigma_set(pyoutfit_env, observer, bad_sigma="value")
+    with pytest.raises(ValueError, match="bad_sigma must be"):
+        _build_bad_sigma_set(pyoutfit_env, observer, bad_sigma="ignore")
+
+
+def test_valid_sigmas_are_not_patched(pyoutfit_env: PyOutfit, observer: Observer):
+    tid, ra_deg, dec_deg, sra, sdec, mjd = _build_arrays_degrees()
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, sra, sdec, mjd, observer, bad_sigma="value",
+        bad_sigma_value=9.0,
+    )
+    assert ts.patched_sigma_count == 0
+
+
+def _sigmas_by_trajectory(ts: TrajectorySet) -> Tuple[np.ndarray, np.ndarray]:
+    arrays = [ts[k].to_numpy() for k in (0, 1)]
+    return (
+        np.concatenate([a[3] for a in arrays]),
+        np.concatenate([a[4] for a in arrays]),
+    )
+
+
+def test_per_row_sigmas_radians(pyoutfit_env: PyOutfit, observer: Observer):
+    tid, ra, dec, _, _, mjd = _build_arrays_radians()
+    sra = np.array([1.0, 2.0, 3.0, 4.0, 5.0]) * py_outfit.RADSEC
+    sdec = sra * 2.0
+    ts = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid, ra, dec, sra, sdec, mjd, observer
+    )
+    got_ra, got_dec = _sigmas_by_trajectory(ts)
+    np.testing.assert_allclose(got_ra, sra)
+    np.testing.assert_allclose(got_dec, sdec)
+
+
+def test_per_row_sigmas_degrees_mixed_with_scalar(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    sra_arcsec = np.array([0.1, 0.2, 0.3, 0.4, 0.5])
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, sra_arcsec, 0.7, mjd, observer
+    )
+    got_ra, got_dec = _sigmas_by_trajectory(ts)
+    np.testing.assert_allclose(got_ra, sra_arcsec * py_outfit.RADSEC)
+    np.testing.assert_allclose(got_dec, 0.7 * py_outfit.RADSEC)
+
+
+def test_per_row_sigmas_length_mismatch(pyoutfit_env: PyOutfit, observer: Observer):
+    tid, ra_deg, dec_deg, _, sdec, mjd = _build_arrays_degrees()
+    with pytest.raises(ValueError, match="error_ra"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra_deg, dec_deg, np.ones(4), sdec, mjd, observer
+        )
+
+
+def test_per_row_sigmas_follow_bad_sigma_policy(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    tid, ra_deg, dec_deg, _, sdec, mjd = _build_arrays_degrees()
+    sra = np.array([0.5, 0.0, 0.5, 0.5, 0.5])
+    with pytest.raises(ValueError, match="non-positive"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra_deg, dec_deg, sra, sdec, mjd, observer
+        )
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, sra, sdec, mjd, observer,
+        bad_sigma="value", bad_sigma_value=0.9,
+    )
+    assert ts.patched_sigma_count == 1
+    got_ra, _ = _sigmas_by_trajectory(ts)
+    np.testing.assert_allclose(
+        got_ra, np.array([0.5, 0.9, 0.5, 0.5, 0.5]) * py_outfit.RADSEC
+    )
+
+
 def _assert_kepler_reasonable(
     k: KeplerianElements,
     *,
@@ -278,6 +418,61 @@ def test_len_and_contains_and_getitem_types(small_traj_set: Tuple[TrajectorySet,
         assert isinstance(obs, py_outfit.Observations)
 
 
+def test_ids_are_an_array_of_int_ids_or_the_keys(
+    small_traj_set: Tuple[TrajectorySet, dict], pyoutfit_env: PyOutfit, observer: Observer
+):
+    traj_set, counts = small_traj_set
+    ids = traj_set.ids()
+    assert isinstance(ids, np.ndarray) and ids.dtype == np.uint64
+    assert ids.tolist() == traj_set.keys()
+    assert sorted(ids.tolist()) == sorted(counts)
+
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd = _build_arrays_degrees()
+    wide = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid.astype(np.uint64) + 2**40, ra_deg, dec_deg, err_ra, err_dec, mjd,
+        observer,
+    )
+    assert sorted(wide.ids().tolist()) == [2**40, 2**40 + 1]
+
+    names = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, [f"obj{t}" for t in tid], ra_deg, dec_deg, err_ra, err_dec, mjd, observer
+    )
+    assert isinstance(names.ids(), list) and sorted(names.ids()) == ["obj0", "obj1"]
+
+
+def test_arc_statistics_columns(
+    pyoutfit_env: PyOutfit, observer: Observer, ZTF_observatory: Observer
+):
+    """Epochs, counts, nights (split at local noon) and sites of each trajectory."""
+    tid = np.array([0, 0, 0, 0, 1, 1], dtype=np.uint32)
+    mjd = np.array([60000.6, 60000.7, 60001.3, 60001.6, 60000.6, 60000.65])
+    ra = np.linspace(10.0, 10.05, tid.size)
+    dec = np.linspace(5.0, 5.05, tid.size)
+    traj_set = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, 0.5, 0.5, mjd, observer
+    )
+    traj_set.extend(
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, np.array([0], dtype=np.uint32), np.array([10.1]), np.array([5.1]),
+            0.5, 0.5, np.array([60001.9]), ZTF_observatory,
+        )
+    )
+
+    stats = traj_set.arc_statistics(pyoutfit_env)
+    assert stats["trajectory_id"].tolist() == [0, 1]
+    np.testing.assert_allclose(stats["first_mjd"], [60000.6, 60000.6])
+    np.testing.assert_allclose(stats["last_mjd"], [60001.9, 60000.65])
+    np.testing.assert_allclose(stats["arc_days"], [1.3, 0.05], atol=1e-9)
+    assert stats["n_obs"].tolist() == [5, 2]
+    assert stats["n_nights"].tolist() == [2, 1]
+    assert stats["n_observers"].tolist() == [2, 1]
+
+    names = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, [f"obj{t}" for t in tid], ra, dec, 0.5, 0.5, mjd, observer
+    )
+    assert names.arc_statistics(pyoutfit_env)["trajectory_id"] == ["obj0", "obj1"]
+
+
 def test_keys_values_items_roundtrip(small_traj_set: Tuple[TrajectorySet, dict]):
     """Check keys/values/items consistency and lengths."""
     traj_set, counts = small_traj_set
@@ -303,6 +498,12 @@ def test_keys_values_items_roundtrip(small_traj_set: Tuple[TrajectorySet, dict])
     for v in values:
         assert isinstance(v, py_outfit.Observations)
 
+    # Same order as keys(), and the same trajectories as indexing
+    assert keys_from_items == keys
+    for (tid, obs), value in zip(items, values):
+        assert len(obs) == len(value) == counts[tid]
+        np.testing.assert_array_equal(obs.to_numpy()[0], traj_set[tid].to_numpy()[0])
+
 
 def test_iter_over_keys_matches_keys_list(small_traj_set: Tuple[TrajectorySet, dict]):
     """__iter__ yields exactly the same set of keys as keys()."""
@@ -312,11 +513,90 @@ def test_iter_over_keys_matches_keys_list(small_traj_set: Tuple[TrajectorySet, d
     assert keys_iter == keys_list
 
 
+def test_iter_streams_ids_and_detects_size_changes(
+    small_traj_set: Tuple[TrajectorySet, dict], pyoutfit_env: PyOutfit, observer: Observer
+):
+    """`for tid in ts: ts[tid]` walks the ids lazily, like a dict."""
+    import operator
+
+    traj_set, counts = small_traj_set
+    it = iter(traj_set)
+    assert isinstance(it, py_outfit.TrajectoryIdIterator) and iter(it) is it
+    assert operator.length_hint(it) == len(counts)
+    seen = []
+    for tid in it:
+        seen.append(tid)
+        assert len(traj_set[tid]) == counts[tid]
+    assert seen == traj_set.keys()
+    assert operator.length_hint(it) == 0
+    with pytest.raises(StopIteration):
+        next(it)
+
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd = _build_arrays_degrees()
+    other = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid + 10, ra_deg, dec_deg, err_ra, err_dec, mjd, observer
+    )
+    with pytest.raises(RuntimeError, match="changed size during iteration"):
+        for _ in traj_set:
+            traj_set.extend(other)
+
+
 def test_getitem_raises_keyerror_on_missing(small_traj_set: Tuple[TrajectorySet, dict]):
     """Indexing with a missing key must raise KeyError."""
     traj_set, _ = small_traj_set
+    for key in (424242, -1, 2**70, "missing"):
+        with pytest.raises(KeyError) as info:
+            _ = traj_set[key]
+        assert info.value.args == (key,)
+        assert key not in traj_set
+    with pytest.raises(ValueError, match="expected int or str"):
+        _ = traj_set[1.5]
+
+
+def test_contains_accepts_int_numpy_and_str_keys(
+    small_traj_set: Tuple[TrajectorySet, dict], pyoutfit_env: PyOutfit, observer: Observer
+):
+    """`tid in ts` is a lookup for ints, NumPy integer scalars and strings."""
+    traj_set, counts = small_traj_set
+    for key in counts:
+        assert key in traj_set
+        assert np.uint32(key) in traj_set and np.int64(key) in traj_set
+    for key in (424242, -1, np.int64(-1), np.uint64(2**40), "missing"):
+        assert key not in traj_set
     with pytest.raises(KeyError):
-        _ = traj_set[424242]
+        _ = traj_set[np.int64(-1)]
+    assert traj_set.get(np.int64(-1)) is None
+
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd = _build_arrays_degrees()
+    names = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, [f"obj{t}" for t in tid], ra_deg, dec_deg, err_ra, err_dec, mjd, observer
+    )
+    assert "obj0" in names and "obj7" not in names and 0 not in names
+
+
+def test_get_returns_observations_or_default(
+    small_traj_set: Tuple[TrajectorySet, dict], pyoutfit_env: PyOutfit, observer: Observer
+):
+    """`get` mirrors `dict.get` for int and str ids."""
+    traj_set, counts = small_traj_set
+    for key, count in counts.items():
+        obs = traj_set.get(key)
+        assert isinstance(obs, Observations) and len(obs) == count
+        np.testing.assert_array_equal(obs.to_numpy()[0], traj_set[key].to_numpy()[0])
+    sentinel = object()
+    for key in (424242, -1, "missing"):
+        assert traj_set.get(key) is None
+        assert traj_set.get(key, sentinel) is sentinel
+        assert traj_set.get(key, default=0) == 0
+    with pytest.raises(ValueError, match="expected int or str"):
+        traj_set.get(1.5)
+
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd = _build_arrays_degrees()
+    names = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, [f"obj{t}" for t in tid], ra_deg, dec_deg, err_ra, err_dec, mjd, observer
+    )
+    assert len(names.get("obj0")) == 3
+    assert names.get("obj7") is None and names.get(0) is None
 
 
 # ----------------------------------------------------------------------
@@ -400,3 +680,2934 @@ def test_counts_match_grouping_by_tid(small_traj_set):
     # Length per key from the mapping interface
     got = {k: len(traj_set[k]) for k in traj_set}
     assert got == dict(counts)
+
+
+def test_split_on_gaps_mapping_and_counts(pyoutfit_env: PyOutfit, observer: Observer):
+    tid = np.array([3, 3, 3, 3, 3, 3, 4, 4], dtype=np.uint32)
+    mjd = np.array(
+        [60000.0, 60000.1, 60100.0, 60100.2, 60000.2, 60300.0, 60000.0, 60001.0]
+    )
+    ra = np.linspace(10.0, 11.0, tid.size)
+    dec = np.linspace(5.0, 6.0, tid.size)
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, 0.5, 0.5, mjd, observer
+    )
+
+    mapping = ts.split_on_gaps(max_gap_days=30.0)
+    assert mapping == {3: ["3_a", "3_b", "3_c"]}
+
+    assert 3 not in ts and 4 in ts
+    assert [len(ts[k]) for k in ("3_a", "3_b", "3_c")] == [3, 2, 1]
+    assert ts.total_observations() == tid.size
+
+    # Segments are time ordered
+    epochs = ts["3_a"].to_numpy()[0]
+    assert list(epochs) == sorted(epochs)
+    assert epochs.max() < ts["3_b"].to_numpy()[0].min()
+
+    # Nothing left to split
+    assert ts.split_on_gaps(max_gap_days=30.0) == {}
+
+    with pytest.raises(ValueError):
+        ts.split_on_gaps(max_gap_days=0.0)
+
+
+def _reversed_loaders(env, observer, tmp_path):
+    """Every in-memory loader, fed the degrees test rows in reverse order."""
+    tid, ra, dec, err_ra, err_dec, mjd = (
+        v[::-1].copy() if isinstance(v, np.ndarray) else v for v in _build_arrays_degrees()
+    )
+    columns = {"trajectory_id": tid, "mjd_tt": mjd, "ra": ra, "dec": dec}
+    kwargs = dict(error_ra=err_ra, error_dec=err_dec)
+    arrow_columns = {"tid": tid, "mjd": mjd, "ra": ra, "dec": dec}
+
+    def parquet():
+        pa = pytest.importorskip("pyarrow")
+        pq = pytest.importorskip("pyarrow.parquet")
+        path = tmp_path / "reversed.parquet"
+        pq.write_table(pa.table(arrow_columns), path, row_group_size=2)
+        return TrajectorySet.from_parquet(env, path, observer, **kwargs)
+
+    def arrow():
+        pa = pytest.importorskip("pyarrow")
+        # Several batches: rows of one trajectory arrive in later batches first.
+        table = pa.Table.from_batches(pa.table(arrow_columns).to_batches(max_chunksize=2))
+        return TrajectorySet.from_arrow(env, table, observer, **kwargs)
+
+    def radians():
+        return TrajectorySet.from_numpy_radians(
+            env, tid, np.deg2rad(ra), np.deg2rad(dec),
+            float(np.deg2rad(err_ra / 3600.0)), float(np.deg2rad(err_dec / 3600.0)),
+            mjd, observer,
+        )
+
+    return {
+        "numpy_degrees": lambda: TrajectorySet.from_numpy_degrees(
+            env, tid, ra, dec, err_ra, err_dec, mjd, observer
+        ),
+        "numpy_radians": radians,
+        "pandas": lambda: TrajectorySet.from_pandas(
+            env, pytest.importorskip("pandas").DataFrame(columns), observer, **kwargs
+        ),
+        "polars": lambda: TrajectorySet.from_polars(
+            env, pytest.importorskip("polars").DataFrame(columns), observer,
+            units="degrees", **kwargs,
+        ),
+        "arrow": arrow,
+        "parquet": parquet,
+    }
+
+
+@pytest.mark.parametrize(
+    "loader", ["numpy_degrees", "numpy_radians", "pandas", "polars", "arrow", "parquet"]
+)
+def test_unsorted_input_is_sorted_at_ingestion(
+    pyoutfit_env: PyOutfit, observer: Observer, tmp_path, loader: str
+):
+    """Rows given in reverse time order are stored sorted, with a fallback warning."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    direct = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+    )
+    assert direct.is_time_sorted()
+
+    ingest = _reversed_loaders(pyoutfit_env, observer, tmp_path)[loader]
+    with pytest.warns(py_outfit.FallbackWarning, match="unsorted_epochs"):
+        ts = ingest()
+    assert ts.is_time_sorted()
+    if loader == "numpy_radians":
+        assert sorted(ts.keys()) == sorted(direct.keys())
+        for key in ts.keys():
+            np.testing.assert_array_equal(ts[key].to_numpy()[0], direct[key].to_numpy()[0])
+    else:
+        _assert_same_sets(ts, direct)
+
+
+@pytest.mark.filterwarnings("error::py_outfit.FallbackWarning")
+def test_sort_true_ingests_shuffled_rows_silently(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    traj_data: Tuple[np.ndarray, np.ndarray, np.ndarray, np.ndarray],
+):
+    """Shuffled rows with `sort=True` give the orbits of their sorted twin, unreported."""
+    tid, ra, dec, mjd = traj_data
+    perm = np.random.default_rng(7).permutation(tid.size)
+    shuffled = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid[perm], ra[perm], dec[perm], 0.5, 0.5, mjd[perm], ZTF_observatory,
+        strict=True, sort=True,
+    )
+    with pytest.raises(py_outfit.StrictModeError, match="unsorted_epochs"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid[perm], ra[perm], dec[perm], 0.5, 0.5, mjd[perm],
+            ZTF_observatory, strict=True,
+        )
+    twin = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, 0.5, 0.5, mjd, ZTF_observatory
+    )
+    assert shuffled.is_time_sorted()
+    assert shuffled.sort_observations() == 0
+    _assert_same_sets(shuffled, twin)
+
+    params = (
+        py_outfit.IODParams.builder()
+        .n_noise_realizations(0)
+        .max_triplets(30)
+        .do_sequential()
+        .build()
+    )
+    ok, err = shuffled.estimate_all_orbits(pyoutfit_env, params, seed=42)
+    ok_ref, err_ref = twin.estimate_all_orbits(pyoutfit_env, params, seed=42)
+    assert err == err_ref == {}
+    assert sorted(ok.keys()) == sorted(ok_ref.keys())
+    for key in ok_ref.keys():
+        assert ok[key][1] == ok_ref[key][1]
+        assert ok[key][0].to_dict() == ok_ref[key][0].to_dict()
+
+
+def test_extend_merges_in_time_order(pyoutfit_env: PyOutfit, observer: Observer):
+    """Appending earlier observations merges them into place; equal epochs by RA."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    late = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd + 1.0, observer
+    )
+    early = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra + 1.0, dec, err_ra, err_dec, mjd, observer
+    )
+    late.extend(early)
+    assert late.is_time_sorted()
+    assert late.total_observations() == 2 * tid.size
+    epochs = late[0].to_numpy()[0]
+    np.testing.assert_array_equal(epochs, np.sort(np.concatenate([mjd[:3], mjd[:3] + 1.0])))
+
+    same = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+    )
+    same.extend(early)
+    ra_rad = same[0].to_numpy()[1]
+    np.testing.assert_allclose(np.rad2deg(ra_rad[::2]), ra[:3])
+    np.testing.assert_allclose(np.rad2deg(ra_rad[1::2]), ra[:3] + 1.0)
+
+    tid_r, ra_r, dec_r, err_ra_r, err_dec_r, mjd_r = _build_arrays_radians()
+    added = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid_r, ra_r, dec_r, float(err_ra_r), float(err_dec_r), mjd_r - 1.0,
+        observer,
+    )
+    late.extend(added)
+    assert late.is_time_sorted()
+
+
+def test_merge_moves_trajectories_and_handles_conflicts(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    """`merge` empties `other` into the set; shared ids are merged or rejected."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    first = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+    )
+    names = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, [f"obj{t}" for t in tid], ra, dec, err_ra, err_dec, mjd, observer
+    )
+    first.merge(names, on_conflict="error")
+    assert len(names) == 0 and names.total_observations() == 0
+    assert sorted(first.keys(), key=str) == [0, 1, "obj0", "obj1"]
+    assert first.total_observations() == 2 * tid.size
+
+    later = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd + 1.0, observer
+    )
+    with pytest.raises(ValueError, match=r"2 trajectory id\(s\) present in both sets: "):
+        first.merge(later, on_conflict="error")
+    assert len(later) == 2 and first.total_observations() == 2 * tid.size
+
+    first.merge(later)
+    assert len(later) == 0 and len(first) == 4
+    assert first.is_time_sorted()
+    np.testing.assert_array_equal(
+        first[0].to_numpy()[0], np.concatenate([mjd[:3], mjd[:3] + 1.0])
+    )
+
+    with pytest.raises(ValueError, match="into itself"):
+        first.merge(first)
+    with pytest.raises(ValueError, match="on_conflict must be"):
+        first.merge(later, on_conflict="skip")
+
+
+def test_remap_and_offset_ids(
+    small_traj_set: Tuple[TrajectorySet, dict], pyoutfit_env: PyOutfit, observer: Observer
+):
+    """Renaming moves the trajectories and rejects collisions up front."""
+    traj_set, counts = small_traj_set
+    epochs = {k: traj_set[k].to_numpy()[0] for k in counts}
+
+    traj_set.remap_ids({0: "first", 1: 2, 2: 1})
+    assert sorted(traj_set.keys(), key=str) == [1, 2, "first"]
+    np.testing.assert_array_equal(traj_set["first"].to_numpy()[0], epochs[0])
+    np.testing.assert_array_equal(traj_set[2].to_numpy()[0], epochs[1])
+
+    with pytest.raises(KeyError):
+        traj_set.remap_ids({1: 10, 7: 11})
+    with pytest.raises(ValueError, match="held by a trajectory that is not renamed"):
+        traj_set.remap_ids({1: "first"})
+    with pytest.raises(ValueError, match="several trajectories renamed to 10"):
+        traj_set.remap_ids({1: 10, 2: 10})
+    assert sorted(traj_set.keys(), key=str) == [1, 2, "first"]
+
+    traj_set.offset_ids(2**32)
+    assert sorted(traj_set.keys(), key=str) == sorted([2**32 + 1, 2**32 + 2, "first"], key=str)
+    traj_set.offset_ids(-(2**32))
+    assert 1 in traj_set and 2 in traj_set
+    with pytest.raises(ValueError, match="out of the 64-bit unsigned range"):
+        traj_set.offset_ids(-2)
+    assert sorted(traj_set.keys(), key=str) == [1, 2, "first"]
+
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    other = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+    )
+    other.offset_ids(1_000_000)
+    traj_set.merge(other, on_conflict="error")
+    assert {1_000_000, 1_000_001} <= set(traj_set.keys())
+
+
+def test_subset_and_sample_copy_the_selected_trajectories(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    """`subset` / `sample` build new sets and leave the original untouched."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    ids = np.concatenate([tid, tid + 2, tid + 4])
+    traj_set = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, ids, np.tile(ra, 3), np.tile(dec, 3), err_ra, err_dec, np.tile(mjd, 3),
+        observer,
+    )
+
+    picked = traj_set.subset(np.array([4, 1, 1], dtype=np.uint64))
+    assert sorted(picked.keys()) == [1, 4] and len(traj_set) == 6
+    np.testing.assert_array_equal(picked[4].to_numpy()[0], traj_set[4].to_numpy()[0])
+
+    with pytest.warns(py_outfit.FallbackWarning, match=r"\[missing_ids\] 2 id\(s\).*7, 'x'"):
+        partial = traj_set.subset([0, 7, "x"])
+    assert partial.keys() == [0]
+    with pytest.raises(py_outfit.StrictModeError, match="missing_ids"):
+        traj_set.subset([0, 7], strict=True)
+    with pytest.raises(TypeError, match="single str"):
+        traj_set.subset("0")
+
+    sample = traj_set.sample(3, seed=7)
+    assert len(sample) == 3 and set(sample.keys()) <= set(traj_set.keys())
+    assert sorted(traj_set.sample(3, seed=7).keys()) == sorted(sample.keys())
+    assert len(traj_set.sample(0)) == 0 and len(traj_set.sample(6)) == 6
+    with pytest.raises(ValueError, match="cannot sample 7 trajectories from a set of 6"):
+        traj_set.sample(7)
+
+
+def test_filter_by_time_keeps_the_window(pyoutfit_env: PyOutfit, observer: Observer):
+    """Observations outside `[mjd_min, mjd_max]` are left out, per trajectory."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    traj_set = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+    )
+    stored = traj_set[0].to_numpy()[0]
+
+    window = traj_set.filter_by_time(60000.005, 60000.025)
+    assert window.keys() == [0] and len(traj_set) == 2
+    np.testing.assert_array_equal(window[0].to_numpy()[0], stored[1:])
+    kept = traj_set.filter_by_time(60000.005, 60000.025, drop_empty=False)
+    assert sorted(kept.keys()) == [0, 1] and len(kept[1]) == 0
+    assert len(traj_set.filter_by_time(60000.005, min_obs=3)) == 0
+    assert sorted(traj_set.filter_by_time(mjd_max=60000.035, min_obs=1).keys()) == [0, 1]
+    assert traj_set.filter_by_time().total_observations() == tid.size
+    assert len(traj_set.filter_by_time(mjd_min=70000.0)) == 0
+
+    obs = traj_set[0]
+    np.testing.assert_array_equal(
+        obs.filter_by_time(mjd_min=stored[1], mjd_max=stored[2]).to_numpy()[0], stored[1:]
+    )
+    assert len(obs.filter_by_time(mjd_max=59000.0)) == 0 and len(obs) == 3
+    with pytest.raises(ValueError, match="must not exceed"):
+        obs.filter_by_time(60001.0, 60000.0)
+    with pytest.raises(ValueError, match="NaN"):
+        traj_set.filter_by_time(mjd_min=float("nan"))
+
+
+def test_filter_by_uncertainty_drops_imprecise_observations(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    """Observations above the threshold go first, then the trajectories left too short."""
+    tid, ra, dec, _, _, mjd = _build_arrays_degrees()
+    err_ra = np.array([0.5, 3.0, 0.5, 0.5, 0.5])
+    err_dec = np.array([0.5, 0.5, 0.5, 0.5, 4.0])
+    traj_set = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+    )
+    obs = traj_set[0].filter_by_uncertainty(1.0)
+    assert len(obs) == 2 and len(traj_set[0]) == 3
+    assert len(traj_set[1].filter_by_uncertainty(10.0)) == 2
+    with pytest.raises(ValueError, match="max_sigma_arcsec must be a non-negative number"):
+        traj_set[0].filter_by_uncertainty(float("nan"))
+
+    summary = traj_set.filter_by_uncertainty(1.0, drop_trajectories_below=2)
+    assert summary == {
+        "observations_dropped": 3, "trajectories_dropped": 1, "dropped_ids": [1]
+    }
+    assert traj_set.keys() == [0] and len(traj_set[0]) == 2
+
+    summary = traj_set.filter_by_uncertainty(0.1, drop_trajectories_below=0)
+    assert summary["observations_dropped"] == 2 and summary["trajectories_dropped"] == 0
+    assert len(traj_set[0]) == 0
+    with pytest.raises(ValueError, match="non-negative"):
+        traj_set.filter_by_uncertainty(-1.0)
+
+
+def test_find_and_remove_shared_observations(pyoutfit_env: PyOutfit, observer: Observer):
+    """Detections placed in several trajectories are listed per pair, then removed."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    arcsec = 1.0 / 3600.0
+    # Trajectory 2 copies rows 0 (exactly) and 1 (0.02 arcsec away) of trajectory 0 and
+    # holds a row 1 arcsec away from row 3 (not shared); trajectory 3 copies row 0 and
+    # holds one row of its own.
+    tid = np.concatenate([tid, [2, 2, 2, 3, 3]]).astype(np.uint32)
+    ra = np.concatenate([ra, [ra[0], ra[1], ra[3], ra[0], 50.0]])
+    dec = np.concatenate(
+        [dec, [dec[0], dec[1] + 0.02 * arcsec, dec[3] + arcsec, dec[0], 0.0]]
+    )
+    mjd = np.concatenate([mjd, [mjd[0], mjd[1], mjd[3], mjd[0], 60000.05]])
+
+    def build() -> TrajectorySet:
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+        )
+
+    ts = build()
+    assert ts.find_shared_observations() == [(0, 2, 2), (0, 3, 1), (2, 3, 1)]
+    one_each = [(0, 2, 1), (0, 3, 1), (2, 3, 1)]
+    assert ts.find_shared_observations(pos_tol_arcsec=0.01) == one_each
+    assert ts.find_shared_observations(time_tol_days=0.0, pos_tol_arcsec=0.0) == one_each
+
+    assert ts.remove_shared() == {2: 2, 3: 1}
+    assert len(ts[0]) == 3 and len(ts[2]) == 1 and len(ts[3]) == 1
+    assert ts.find_shared_observations() == []
+
+    ts = build()
+    assert ts.remove_shared(keep="last") == {0: 2, 2: 1}
+    assert len(ts[0]) == 1 and len(ts[3]) == 2
+
+    ts = build()
+    assert ts.remove_shared(keep="none") == {0: 2, 2: 2, 3: 1}
+    assert ts.total_observations() == tid.size - 5
+
+    with pytest.raises(ValueError, match="keep must be"):
+        ts.remove_shared(keep="all")
+    with pytest.raises(ValueError, match="pos_tol_arcsec must be a non-negative finite"):
+        ts.find_shared_observations(pos_tol_arcsec=-1.0)
+
+
+def test_split_at_and_split_fraction_partition_each_trajectory(
+    small_traj_set: Tuple[TrajectorySet, dict]
+):
+    """Both halves of a split hold every observation once, earliest first."""
+    traj_set, counts = small_traj_set
+    epochs = {k: traj_set[k].to_numpy()[0] for k in counts}
+    cut = float(np.median(np.concatenate(list(epochs.values()))))
+
+    before, after = traj_set.split_at(cut)
+    for key, mjd in epochs.items():
+        head = before[key].to_numpy()[0] if key in before else np.empty(0)
+        tail = after[key].to_numpy()[0] if key in after else np.empty(0)
+        np.testing.assert_array_equal(np.concatenate([head, tail]), mjd)
+        assert np.all(head < cut) and np.all(tail >= cut)
+    only_before, nothing = traj_set.split_at(np.inf)
+    assert len(only_before) == len(traj_set) and len(nothing) == 0
+
+    fit, holdout = traj_set.split_fraction(0.5, by="count")
+    for key, n in counts.items():
+        assert len(fit[key]) == math.ceil(n / 2)
+        assert len(fit[key]) + len(holdout[key]) == n
+    fit, holdout = traj_set.split_fraction(0.5)
+    for key, mjd in epochs.items():
+        end = mjd[0] + 0.5 * (mjd[-1] - mjd[0])
+        np.testing.assert_array_equal(fit[key].to_numpy()[0], mjd[mjd <= end])
+    fit, holdout = traj_set.split_fraction(1.0)
+    assert fit.total_observations() == traj_set.total_observations() and len(holdout) == 0
+    fit, holdout = traj_set.split_fraction(0.0, by="count")
+    assert len(fit) == 0 and len(holdout) == len(traj_set)
+
+    with pytest.raises(ValueError, match=r"frac must lie in \[0, 1\]"):
+        traj_set.split_fraction(1.5)
+    with pytest.raises(ValueError, match="by must be 'time' or 'count'"):
+        traj_set.split_fraction(0.5, by="nights")
+
+
+def test_obs_count_stats_dict(small_traj_set: Tuple[TrajectorySet, dict]):
+    """Machine-readable counterpart of `get_traj_stat`."""
+    traj_set, counts = small_traj_set
+    stats = traj_set.obs_count_stats_dict()
+    assert stats == {
+        "min": 6, "p25": 6, "median": 6, "p95": 7, "max": 7,
+        "mean": pytest.approx(19 / 3), "total": 19, "n_trajectories": 3,
+    }
+    histogram = traj_set.obs_count_stats_dict(histogram=True)["histogram"]
+    assert histogram == {6: 2, 7: 1} and list(histogram) == [6, 7]
+
+    empty = traj_set.subset([]).obs_count_stats_dict(histogram=True)
+    assert empty["median"] is None and math.isnan(empty["mean"])
+    assert empty["total"] == empty["n_trajectories"] == 0 and empty["histogram"] == {}
+
+
+def test_time_span_and_epoch_histogram(pyoutfit_env: PyOutfit, observer: Observer):
+    """Coverage of the whole set, without exporting the epochs."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    traj_set = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+    )
+    mjd_min, mjd_max, span = traj_set.time_span()
+    assert mjd_min == pytest.approx(60000.0) and mjd_max == pytest.approx(60000.04)
+    assert span == pytest.approx(0.04)
+
+    edges, counts = traj_set.epoch_histogram()
+    np.testing.assert_allclose(edges, [60000.0, 60001.0])
+    assert counts.dtype == np.int64 and counts.tolist() == [5]
+    edges, counts = traj_set.epoch_histogram(bin_days=0.025)
+    np.testing.assert_allclose(edges, [60000.0, 60000.025, 60000.05])
+    assert counts.tolist() == [3, 2]
+
+    for bad in (0.0, -1.0, float("nan")):
+        with pytest.raises(ValueError, match="bin_days must be a positive finite number"):
+            traj_set.epoch_histogram(bin_days=bad)
+    with pytest.raises(ValueError, match="use wider bins"):
+        traj_set.epoch_histogram(bin_days=1e-9)
+
+    empty = traj_set.subset([])
+    assert all(math.isnan(v) for v in empty.time_span())
+    edges, counts = empty.epoch_histogram()
+    assert edges.size == counts.size == 0
+
+
+def test_filter_by_obs_count_returns_the_dropped_ids(
+    small_traj_set: Tuple[TrajectorySet, dict]
+):
+    """Trajectories outside `[min_obs, max_obs]` are left out and reported."""
+    traj_set, counts = small_traj_set
+    assert counts == {0: 6, 1: 6, 2: 7}
+
+    kept, dropped = traj_set.filter_by_obs_count(min_obs=7)
+    assert kept is not traj_set and kept.keys() == [2] and dropped == [0, 1]
+    assert len(traj_set) == 3
+
+    kept, dropped = traj_set.filter_by_obs_count(max_obs=6)
+    assert sorted(kept.keys()) == [0, 1] and dropped == [2]
+    np.testing.assert_array_equal(kept[0].to_numpy()[0], traj_set[0].to_numpy()[0])
+    kept, dropped = traj_set.filter_by_obs_count()
+    assert len(kept) == 3 and dropped == []
+    with pytest.raises(ValueError, match=r"max_obs \(1\) must be at least min_obs \(3\)"):
+        traj_set.filter_by_obs_count(max_obs=1)
+
+    same, dropped = traj_set.filter_by_obs_count(min_obs=7, inplace=True)
+    assert same is traj_set and traj_set.keys() == [2] and dropped == [0, 1]
+
+
+def test_canonical_order_is_independent_of_operation_order(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    """Same observations, different ingestion and merge orders: identical exports."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    other_site = Observer(
+        longitude=-70.0, latitude=-30.0, elevation=2.0, name="Second site"
+    )
+
+    def part(site, ra_shift, dec_shift):
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra + ra_shift, dec + dec_shift, err_ra, err_dec, mjd, site
+        )
+
+    # Same epochs everywhere: the order is decided by RA, then Dec, then site.
+    parts = [
+        lambda: part(observer, 0.0, 0.0),
+        lambda: part(other_site, 0.0, 0.0),
+        lambda: part(observer, 0.0, -0.5),
+        lambda: part(observer, -0.5, 0.0),
+    ]
+
+    def pipeline(order):
+        ts = parts[order[0]]()
+        for i in order[1:]:
+            ts.extend(parts[i]())
+        return ts
+
+    first = pipeline([0, 1, 2, 3])
+    second = pipeline([3, 1, 0, 2])
+    third = parts[2]()
+    third.extend(pipeline([1, 3]))
+    third.extend(parts[0]())
+
+    for ts in (second, third):
+        assert sorted(ts.keys()) == sorted(first.keys())
+        for key in first.keys():
+            for a, b in zip(first[key].to_numpy(), ts[key].to_numpy()):
+                np.testing.assert_array_equal(a, b)
+            assert first[key].to_dict() == ts[key].to_dict()
+
+    view = first[0]
+    assert view.canonicalize() is False
+    rows = list(view)
+    assert rows == sorted(rows, key=lambda r: (r[0], r[1], r[2]))
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_from_observations_matches_array_ingestion(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    traj_data: Tuple[np.ndarray, np.ndarray, np.ndarray, np.ndarray],
+):
+    """Trajectories taken out of a set rebuild it, keep their sites and fit alike."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+
+    def direct() -> TrajectorySet:
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+        )
+
+    source = direct()
+    _assert_same_sets(
+        TrajectorySet.from_observations(pyoutfit_env, dict(source.items())), source
+    )
+
+    picked = {"a": source[0], "b": source[2]}
+    kept = TrajectorySet.from_observations(pyoutfit_env, picked)
+    relocated = TrajectorySet.from_observations(
+        pyoutfit_env, picked, observer=ZTF_observatory
+    )
+    assert sorted(kept.keys()) == sorted(relocated.keys()) == ["a", "b"]
+    for new, old in (("a", 0), ("b", 2)):
+        for ts in (kept, relocated):
+            for col, ref in zip(ts[new].to_numpy(), source[old].to_numpy()):
+                np.testing.assert_array_equal(col, ref)
+
+    params = (
+        py_outfit.IODParams.builder()
+        .n_noise_realizations(0)
+        .max_triplets(30)
+        .do_sequential()
+        .build()
+    )
+    ok_ref, err_ref = direct().estimate_all_orbits(pyoutfit_env, params, seed=42)
+    assert err_ref == {}
+    for ts in (kept, relocated):
+        ok, err = ts.estimate_all_orbits(pyoutfit_env, params, seed=42)
+        assert err == {}
+        for new, old in (("a", 0), ("b", 2)):
+            assert ok[new][1] == ok_ref[old][1]
+            assert ok[new][0].to_dict() == ok_ref[old][0].to_dict()
+
+
+def test_from_observations_rejects_bad_mappings(
+    pyoutfit_env: PyOutfit, small_traj_set: Tuple[TrajectorySet, dict]
+):
+    ts, _ = small_traj_set
+    with pytest.raises(TypeError, match="must be Observations"):
+        TrajectorySet.from_observations(pyoutfit_env, {0: ts[0], 1: ts[1].to_numpy()})
+    with pytest.raises(ValueError, match="Unsupported key type"):
+        TrajectorySet.from_observations(pyoutfit_env, {-1: ts[0]})
+    assert len(TrajectorySet.from_observations(pyoutfit_env, {})) == 0
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_estimate_all_orbits_validate(small_traj_set: Tuple[TrajectorySet, dict], pyoutfit_env):
+    """Sets built by the loaders pass validation: every trajectory is fitted."""
+    traj_set, counts = small_traj_set
+    assert traj_set.is_time_sorted()
+    params = py_outfit.IODParams.builder().n_noise_realizations(0).build()
+    ok, err = traj_set.estimate_all_orbits(pyoutfit_env, params, seed=7, validate=True)
+    assert set(ok) | set(err) == set(counts)
+    assert not any(m.startswith("UnsortedEpochsError:") for m in err.values())
+    # The set keeps every observation after the run.
+    assert traj_set.total_observations() == sum(counts.values())
+    assert issubclass(py_outfit.UnsortedEpochsError, ValueError)
+
+
+def _synthetic_positions(env, observer, orbit, mjd):
+    """Exact (RA, DEC) in degrees of `orbit` at `mjd`, derived from the residual API."""
+    n = mjd.size
+    ra0 = np.full(n, 30.0)
+    dec0 = np.full(n, 20.0)
+    probe = TrajectorySet.from_numpy_degrees(
+        env, np.zeros(n, dtype=np.uint32), ra0, dec0, 0.5, 0.5, mjd, observer
+    )
+    epochs, dra, ddec = probe[0].residuals(env, orbit)
+    dec = dec0 - ddec / 3600.0
+    ra = np.mod(ra0 - dra / 3600.0 / np.cos(np.radians(dec0)), 360.0)
+    return epochs, ra, dec
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_split_on_gaps_recovers_two_apparitions(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    traj_data,
+    small_traj_set: Tuple[TrajectorySet, dict],
+):
+    """An arc merging two apparitions fits poorly; its halves fit well once split."""
+    traj_set, _ = small_traj_set
+    params = py_outfit.IODParams.builder().n_noise_realizations(0).build()
+    ok, _ = traj_set.estimate_all_orbits(pyoutfit_env, params, seed=42)
+    other_orbit = ok[1][0]
+
+    # First apparition: real observations of trajectory 0.
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    first = tid == 0
+    # Second apparition, 180 days later, observing another object.
+    epochs2, ra2, dec2 = _synthetic_positions(
+        pyoutfit_env, ZTF_observatory, other_orbit, mjd_tt[tid == 1] + 180.0
+    )
+
+    mjd = np.concatenate([mjd_tt[first], epochs2])
+    merged = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        np.full(mjd.size, 7, dtype=np.uint32),
+        np.concatenate([ra_deg[first], ra2]),
+        np.concatenate([dec_deg[first], dec2]),
+        0.5,
+        0.5,
+        mjd,
+        ZTF_observatory,
+    )
+    merged_fit = merged.estimate_orbits(pyoutfit_env, params, seed=42)
+
+    assert merged.split_on_gaps(max_gap_days=30.0) == {7: ["7_a", "7_b"]}
+    split_ok, split_err = merged.estimate_all_orbits(pyoutfit_env, params, seed=42)
+    assert split_err == {}
+    halves = [split_ok[k][0].fit_statistics.rms_arcsec for k in ("7_a", "7_b")]
+    assert all(rms < 5.0 for rms in halves)
+
+    if 7 in merged_fit.ok():
+        merged_rms = merged_fit[7][0].fit_statistics.rms_arcsec
+        assert merged_rms > 10.0 * max(halves)
+
+
+def _arrow_reader(table, max_chunksize: int):
+    """RecordBatchReader over `table` split into batches of `max_chunksize` rows."""
+    import pyarrow as pa
+
+    batches = table.to_batches(max_chunksize=max_chunksize)
+    return pa.RecordBatchReader.from_batches(table.schema, batches)
+
+
+def _assert_same_sets(
+    a: TrajectorySet, b: TrajectorySet, rtol: float = 0.0, env: Optional[PyOutfit] = None
+):
+    """
+    Same keys and observations, equal to `rtol` (exactly by default). With `env`, the
+    observer positions of every trajectory must be identical as well.
+    """
+    assert sorted(a.keys()) == sorted(b.keys())
+    for key in a.keys():
+        for col_a, col_b in zip(a[key].to_numpy(), b[key].to_numpy()):
+            np.testing.assert_allclose(col_a, col_b, rtol=rtol, atol=0.0)
+        if env is not None:
+            np.testing.assert_array_equal(
+                a[key].geometry(env)["observer_position"],
+                b[key].geometry(env)["observer_position"],
+            )
+
+
+@pytest.mark.parametrize("max_chunksize", [1, 2, 5])
+def test_from_arrow_ipc_matches_numpy_ingestion(
+    pyoutfit_env: PyOutfit, observer: Observer, max_chunksize: int
+):
+    """Batch-by-batch Arrow ingestion equals direct ingestion, whatever the batch size."""
+    pa = pytest.importorskip("pyarrow")
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd = _build_arrays_degrees()
+
+    direct = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, err_ra, err_dec, mjd, observer
+    )
+    table = pa.table(
+        {"object": tid.astype(np.int64), "epoch": mjd, "alpha": ra_deg, "delta": dec_deg}
+    )
+    streamed = TrajectorySet.from_arrow_ipc(
+        pyoutfit_env,
+        _arrow_reader(table, max_chunksize),
+        observer=observer,
+        columns={"tid": "object", "mjd": "epoch", "ra": "alpha", "dec": "delta"},
+        error_ra=err_ra,
+        error_dec=err_dec,
+    )
+
+    assert streamed.total_observations() == tid.size
+    _assert_same_sets(streamed, direct)
+
+
+def test_from_arrow_ipc_radians_and_observer_column(pyoutfit_env: PyOutfit):
+    """Radian inputs with per-row MPC codes match the radians loader."""
+    pa = pytest.importorskip("pyarrow")
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_radians()
+    site = pyoutfit_env.get_observer_from_mpc_code("I41")
+
+    direct = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid, ra, dec, float(err_ra), float(err_dec), mjd, site
+    )
+    table = pa.table(
+        {"tid": tid, "mjd": mjd, "ra": ra, "dec": dec, "observer": ["I41"] * tid.size}
+    )
+    streamed = TrajectorySet.from_arrow_ipc(
+        pyoutfit_env,
+        _arrow_reader(table, 2),
+        units="radians",
+        error_ra=float(err_ra),
+        error_dec=float(err_dec),
+    )
+
+    _assert_same_sets(streamed, direct)
+
+
+def test_from_numpy_multi_site_keeps_the_site_of_each_row(
+    pyoutfit_env: PyOutfit, tmp_path: Path
+):
+    """Codes, integer indices and per-site ingestion give the same set."""
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    sra = np.array([0.1, 0.2, 0.3, 0.4, 0.5])
+    codes = ["I41", "F51", "I41", "F51", "F51"]
+    sites = {c: pyoutfit_env.get_observer_from_mpc_code(c) for c in ("I41", "F51")}
+
+    reference = None
+    for code, site in sites.items():
+        rows = np.array([c == code for c in codes])
+        part = TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid[rows], ra_deg[rows], dec_deg[rows], sra[rows], 0.5,
+            mjd[rows], site,
+        )
+        if reference is None:
+            reference = part
+        else:
+            reference.extend(part)
+
+    by_code = TrajectorySet.from_numpy_multi_site(
+        pyoutfit_env, tid, ra_deg, dec_deg, sra, 0.5, mjd, codes
+    )
+    by_str_array = TrajectorySet.from_numpy_multi_site(
+        pyoutfit_env, tid, ra_deg, dec_deg, sra, 0.5, mjd, np.array(codes)
+    )
+    by_index = TrajectorySet.from_numpy_multi_site(
+        pyoutfit_env,
+        tid,
+        ra_deg,
+        dec_deg,
+        sra,
+        0.5,
+        mjd,
+        np.array([0, 1, 0, 1, 1], dtype=np.uint8),
+        observers=[sites["I41"], sites["F51"]],
+    )
+    for ts in (by_code, by_str_array, by_index):
+        _assert_same_sets(ts, reference)
+        assert ts.to_dict() == reference.to_dict()
+
+    # Records are written by trajectory, then time: codes follow the input rows.
+    out = tmp_path / "multi.obs"
+    assert by_index.to_mpc_80col(pyoutfit_env, out) == tid.size
+    order = np.lexsort((mjd, tid))
+    assert [line[77:80] for line in out.read_text().splitlines()] == [
+        codes[i] for i in order
+    ]
+
+    radians = TrajectorySet.from_numpy_multi_site(
+        pyoutfit_env, tid, np.deg2rad(ra_deg), np.deg2rad(dec_deg),
+        sra * py_outfit.RADSEC, 0.5 * py_outfit.RADSEC, mjd, codes, units="radians",
+    )
+    for key in reference.keys():
+        for a, b in zip(radians[key].to_numpy(), reference[key].to_numpy()):
+            np.testing.assert_allclose(a, b, rtol=1e-12)
+
+
+def test_from_numpy_multi_site_rejects_bad_sites(pyoutfit_env: PyOutfit, observer: Observer):
+    tid, ra_deg, dec_deg, sra, sdec, mjd = _build_arrays_degrees()
+
+    def build(observer_codes, **kwargs):
+        return TrajectorySet.from_numpy_multi_site(
+            pyoutfit_env, tid, ra_deg, dec_deg, sra, sdec, mjd, observer_codes, **kwargs
+        )
+
+    with pytest.raises(ValueError, match="observer_codes=4"):
+        build(["I41"] * 4)
+    with pytest.raises(ValueError, match=r"observer_codes\[2\] = 3 is out of range"):
+        build(np.array([0, 0, 3, 0, 0]), observers=[observer])
+    with pytest.raises(ValueError, match="out of range"):
+        build(np.array([0, 0, -1, 0, 0]), observers=[observer])
+    with pytest.raises(ValueError, match="unknown observatory code 'ZZZ'"):
+        build(["I41", "I41", "I41", "ZZZ", "I41"])
+    with pytest.raises(ValueError, match="observers"):
+        build(np.zeros(5, dtype=np.int64))
+    with pytest.raises(ValueError, match="observers"):
+        build(["I41"] * 5, observers=[observer])
+    with pytest.raises(TypeError, match="observer_codes"):
+        build(np.full(5, 0.5))
+
+
+def test_from_numpy_radians_indexed_records_the_site_of_each_row(
+    pyoutfit_env: PyOutfit, observer: Observer, ZTF_observatory: Observer
+):
+    """Each row keeps the `Observer` its index selects; the set equals per-site ingestion."""
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    ra, dec, sigma = np.deg2rad(ra_deg), np.deg2rad(dec_deg), 0.5 * py_outfit.RADSEC
+    sites = [observer, ZTF_observatory]
+    idx = np.array([1, 0, 1, 1, 0], dtype=np.uint16)
+
+    ts = TrajectorySet.from_numpy_radians_indexed(
+        pyoutfit_env, tid, ra, dec, sigma, sigma, mjd, sites, idx
+    )
+    reference = TrajectorySet.from_numpy_multi_site(
+        pyoutfit_env, tid, ra, dec, sigma, sigma, mjd, idx, observers=sites, units="radians"
+    )
+    _assert_same_sets(ts, reference)
+
+    for key in ts.keys():
+        obs = ts[key]
+        rows = np.flatnonzero(tid == key)
+        stored = obs.geometry(pyoutfit_env)["observer_position"]
+        for i, row in enumerate(rows[np.argsort(mjd[rows])]):
+            own = obs.geometry(pyoutfit_env, observer=sites[idx[row]])["observer_position"]
+            np.testing.assert_allclose(stored[i], own[i], rtol=0, atol=1e-12)
+
+
+def test_from_numpy_radians_indexed_rejects_bad_indices(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    """Out-of-range indices raise before the epochs are read; other dtypes are refused."""
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    ra, dec = np.deg2rad(ra_deg), np.deg2rad(dec_deg)
+
+    def build(idx, mjd_tt=mjd):
+        return TrajectorySet.from_numpy_radians_indexed(
+            pyoutfit_env, tid, ra, dec, 1e-6, 1e-6, mjd_tt, [observer], idx
+        )
+
+    with pytest.raises(ValueError, match=r"observer_idx\[3\] = 2 is out of range for 1 observers"):
+        build(np.array([0, 0, 0, 2, 0], dtype=np.uint16))
+    # Unparsable epochs would fail later: the index check runs first.
+    with pytest.raises(ValueError, match="observer_idx"):
+        build(np.array([0, 7, 0, 0, 0], dtype=np.uint16), mjd_tt=["not a date"] * 5)
+    with pytest.raises(TypeError):
+        build(np.zeros(5, dtype=np.int64))
+    with pytest.raises(ValueError, match="ra=5, observer_idx=4"):
+        build(np.zeros(4, dtype=np.uint16))
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_string_trajectory_ids(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    traj_data: Tuple[np.ndarray, np.ndarray, np.ndarray, np.ndarray],
+):
+    """String ids key the set and the IOD results; the data match integer ids."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    names = np.array(["ZTF21aabcdef", "ZTF21aaghijk", "C/2021 A1"])
+    str_ids = names[tid]
+
+    by_number = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+    )
+    sets = [
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, ids, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+        )
+        for ids in (str_ids.tolist(), str_ids, str_ids.astype(object))
+    ]
+    sets.append(
+        TrajectorySet.from_numpy_radians(
+            pyoutfit_env, str_ids.tolist(), np.deg2rad(ra_deg), np.deg2rad(dec_deg),
+            0.5 * py_outfit.RADSEC, 0.5 * py_outfit.RADSEC, mjd_tt, ZTF_observatory,
+        )
+    )
+    sets.append(
+        TrajectorySet.from_numpy_multi_site(
+            pyoutfit_env, str_ids, ra_deg, dec_deg, 0.5, 0.5, mjd_tt,
+            np.zeros(tid.size, dtype=np.int64), observers=[ZTF_observatory],
+        )
+    )
+    for ts in sets:
+        assert sorted(ts.keys()) == sorted(names.tolist())
+        for number, name in enumerate(names):
+            for col, ref in zip(ts[name].to_numpy(), by_number[number].to_numpy()):
+                np.testing.assert_allclose(col, ref, rtol=1e-12)
+
+    params = (
+        py_outfit.IODParams.builder()
+        .n_noise_realizations(0)
+        .max_triplets(30)
+        .do_sequential()
+        .build()
+    )
+    ok, err = sets[0].estimate_all_orbits(pyoutfit_env, params, seed=42)
+    assert err == {}
+    assert sorted(ok) == sorted(names.tolist())
+
+    with pytest.raises(TypeError):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, ["a", 1, "b", "a", "b"], ra_deg[:5], dec_deg[:5], 0.5, 0.5,
+            mjd_tt[:5], ZTF_observatory,
+        )
+    with pytest.raises(ValueError, match="Length mismatch"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, str_ids[:-1].tolist(), ra_deg, dec_deg, 0.5, 0.5, mjd_tt,
+            ZTF_observatory,
+        )
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_64_bit_trajectory_ids(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    traj_data: Tuple[np.ndarray, np.ndarray, np.ndarray, np.ndarray],
+):
+    """Ids beyond uint32 keep their full value and never collide once narrowed."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    # 1 and 2**32 + 1 share their low 32 bits.
+    wide = np.array([1, 2**32 + 1, 1_234_567_890_123_456_789], dtype=np.uint64)[tid]
+
+    def build(ids) -> TrajectorySet:
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, ids, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+        )
+
+    by_number = build(tid)
+    expected = sorted(int(v) for v in set(wide.tolist()))
+    for ts in (build(wide), build(wide.astype(np.int64))):
+        assert sorted(ts.keys()) == expected
+        assert all(type(k) is int for k in ts.keys())
+        assert 2**32 + 1 in ts and 1 in ts
+        for number, key in enumerate([1, 2**32 + 1, 1_234_567_890_123_456_789]):
+            for col, ref in zip(ts[key].to_numpy(), by_number[number].to_numpy()):
+                np.testing.assert_array_equal(col, ref)
+        assert set(ts.to_dict()["trajectory_id"]) == set(expected)
+
+    # 64-bit arrays whose ids fit in uint32 give plain integer keys.
+    assert sorted(build(tid.astype(np.uint64)).keys()) == sorted(by_number.keys())
+
+    params = (
+        py_outfit.IODParams.builder()
+        .n_noise_realizations(0)
+        .max_triplets(30)
+        .do_sequential()
+        .build()
+    )
+    ok, err = build(wide).estimate_all_orbits(pyoutfit_env, params, seed=42)
+    assert err == {}
+    assert sorted(ok) == expected
+
+    negative = tid.astype(np.int64)
+    negative[4] = -7
+    with pytest.raises(ValueError, match=r"trajectory_id\[4\] = -7 is negative"):
+        build(negative)
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_object_trajectory_ids(
+    pyoutfit_env: PyOutfit,
+    ZTF_observatory: Observer,
+    traj_data: Tuple[np.ndarray, np.ndarray, np.ndarray, np.ndarray],
+):
+    """Object arrays and lists of Python ints take the integer path, of str the string one."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+
+    def build(ids) -> TrajectorySet:
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, ids, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+        )
+
+    by_number = build(tid)
+    wide = np.array([1, 2**32 + 1, 7], dtype=np.uint64)[tid]
+    for ids, reference in (
+        (tid.astype(object), by_number),
+        (tid.tolist(), by_number),
+        (wide.astype(object), build(wide)),
+    ):
+        ts = build(ids)
+        assert sorted(ts.keys()) == sorted(reference.keys())
+        assert all(type(k) is int for k in ts.keys())
+        for key in ts.keys():
+            for col, ref in zip(ts[key].to_numpy(), reference[key].to_numpy()):
+                np.testing.assert_array_equal(col, ref)
+    names = np.array(["a", "b", "c"], dtype=object)[tid]
+    assert sorted(build(names).keys()) == ["a", "b", "c"]
+
+    masked = np.ma.masked_array(tid.astype(object), mask=np.arange(tid.size) == 0)
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, masked, ra_deg, dec_deg, 0.5, 0.5, mjd_tt, ZTF_observatory
+    )
+    assert ts.total_observations() == tid.size - 1
+
+    def offending(row, value):
+        ids = tid.astype(object)
+        ids[row] = value
+        return ids
+
+    cases = [
+        (offending(3, None), TypeError, r"trajectory_id\[3\] = None \(NoneType\)"),
+        (offending(2, 1.0), TypeError, r"trajectory_id\[2\] = 1.0 \(float\)"),
+        (offending(4, True), TypeError, r"trajectory_id\[4\] = True \(bool\)"),
+        (offending(1, "x"), TypeError, r"trajectory_id\[1\] = 'x' \(str\).*all int or all str"),
+        (offending(5, -2), ValueError, r"trajectory_id\[5\] = -2 is negative"),
+        (offending(0, 2**64), ValueError, r"trajectory_id\[0\] = \d+ \(int\) does not fit"),
+    ]
+    for ids, error, message in cases:
+        with pytest.raises(error, match=message):
+            build(ids)
+
+
+def test_from_arrow_ipc_rejects_bad_inputs(pyoutfit_env: PyOutfit, observer: Observer):
+    pa = pytest.importorskip("pyarrow")
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    table = pa.table({"tid": tid, "mjd": mjd, "ra": ra_deg, "dec": dec_deg})
+
+    with pytest.raises(TypeError):
+        TrajectorySet.from_arrow_ipc(pyoutfit_env, [1, 2, 3], observer=observer)
+    with pytest.raises(KeyError):
+        TrajectorySet.from_arrow_ipc(
+            pyoutfit_env, table, observer=observer, columns={"ra": "alpha"}
+        )
+    with pytest.raises(ValueError):
+        TrajectorySet.from_arrow_ipc(
+            pyoutfit_env, table, observer=observer, columns={"epoch": "mjd"}
+        )
+    # Unknown uncertainties are subject to the bad_sigma policy.
+    with pytest.raises(ValueError):
+        TrajectorySet.from_arrow_ipc(pyoutfit_env, table, observer=observer)
+    ts = TrajectorySet.from_arrow_ipc(
+        pyoutfit_env, table, observer=observer, bad_sigma="value", bad_sigma_value=0.5
+    )
+    assert ts.patched_sigma_count == tid.size
+
+
+def test_from_arrow_record_batch_and_chunked_table(pyoutfit_env: PyOutfit, observer: Observer):
+    """C Data Interface import of a record batch and of a multi-chunk table."""
+    pa = pytest.importorskip("pyarrow")
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_radians()
+    direct = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid, ra, dec, float(err_ra), float(err_dec), mjd, observer
+    )
+    kwargs = dict(units="radians", error_ra=float(err_ra), error_dec=float(err_dec))
+
+    batch = pa.record_batch({"tid": tid, "mjd": mjd, "ra": ra, "dec": dec})
+    _assert_same_sets(TrajectorySet.from_arrow(pyoutfit_env, batch, observer, **kwargs), direct)
+
+    table = pa.Table.from_batches(batch.to_table().to_batches(max_chunksize=2))
+    assert table["ra"].num_chunks == 3
+    _assert_same_sets(TrajectorySet.from_arrow(pyoutfit_env, table, observer, **kwargs), direct)
+
+    # UInt64 ids and renamed columns; degrees by default.
+    tid_d, ra_deg, dec_deg, err_ra_d, err_dec_d, _ = _build_arrays_degrees()
+    renamed = pa.table(
+        {"object": tid_d.astype(np.uint64), "epoch": mjd, "alpha": ra_deg, "delta": dec_deg}
+    )
+    ts = TrajectorySet.from_arrow(
+        pyoutfit_env,
+        renamed,
+        observer,
+        columns={"tid": "object", "mjd": "epoch", "ra": "alpha", "dec": "delta"},
+        error_ra=err_ra_d,
+        error_dec=err_dec_d,
+    )
+    _assert_same_sets(
+        ts,
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid_d, ra_deg, dec_deg, err_ra_d, err_dec_d, mjd, observer
+        ),
+    )
+
+
+def test_from_arrow_rejects_unsupported_columns(pyoutfit_env: PyOutfit, observer: Observer):
+    pa = pytest.importorskip("pyarrow")
+    tid, ra, dec, _, _, mjd = _build_arrays_radians()
+    kwargs = dict(units="radians", error_ra=1e-6, error_dec=1e-6)
+
+    def batch(**overrides):
+        cols = {"tid": tid, "mjd": mjd, "ra": ra, "dec": dec}
+        cols.update(overrides)
+        return pa.record_batch(cols)
+
+    with pytest.raises(TypeError, match=r'"ra" has unsupported Arrow type Float32'):
+        TrajectorySet.from_arrow(pyoutfit_env, batch(ra=ra.astype(np.float32)), observer, **kwargs)
+    with pytest.raises(TypeError, match=r'"tid" has unsupported Arrow type Int16'):
+        TrajectorySet.from_arrow(pyoutfit_env, batch(tid=tid.astype(np.int16)), observer, **kwargs)
+    with pytest.raises(ValueError, match="trajectory id -1 is negative"):
+        negative = tid.astype(np.int64) - 1
+        TrajectorySet.from_arrow(pyoutfit_env, batch(tid=negative), observer, **kwargs)
+    with pytest.raises(ValueError, match="null value"):
+        nullable = pa.array([None] + dec[1:].tolist(), type=pa.float64())
+        TrajectorySet.from_arrow(pyoutfit_env, batch(dec=nullable), observer, **kwargs)
+    with pytest.raises(KeyError, match="not found"):
+        TrajectorySet.from_arrow(
+            pyoutfit_env, batch(), observer, columns={"mjd": "epoch"}, **kwargs
+        )
+    with pytest.raises(TypeError, match="__arrow_c_array__"):
+        TrajectorySet.from_arrow(pyoutfit_env, [1, 2, 3], observer, **kwargs)
+    with pytest.raises(TypeError, match="expected a record batch"):
+        TrajectorySet.from_arrow(pyoutfit_env, pa.array(ra), observer, **kwargs)
+
+
+@pytest.mark.parametrize(
+    "loader", ["from_arrow", "from_arrow_ipc", "from_parquet", "from_polars"]
+)
+def test_tabular_loaders_accept_64_bit_ids(
+    pyoutfit_env: PyOutfit, observer: Observer, tmp_path, loader: str
+):
+    """UInt64 / Int64 id columns keep their full value, across batches; negative ids raise."""
+    pa = pytest.importorskip("pyarrow")
+    pq = pytest.importorskip("pyarrow.parquet")
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_radians()
+    err_ra, err_dec = float(err_ra), float(err_dec)
+    kwargs = dict(units="radians", error_ra=err_ra, error_dec=err_dec)
+    # 1 and 2**32 + 1 share their low 32 bits.
+    wide = np.array([1, 2**32 + 1], dtype=np.uint64)[tid]
+    direct = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, wide, ra, dec, err_ra, err_dec, mjd, observer
+    )
+
+    def build(ids) -> TrajectorySet:
+        cols = {"trajectory_id": ids, "mjd_tt": mjd, "ra": ra, "dec": dec}
+        if loader == "from_polars":
+            pl = pytest.importorskip("polars")
+            return TrajectorySet.from_polars(pyoutfit_env, pl.DataFrame(cols), observer, **kwargs)
+        # Batches of two rows: the first holds only ids in the uint32 range.
+        table = pa.Table.from_batches(pa.table(cols).to_batches(max_chunksize=2))
+        columns = {"tid": "trajectory_id", "mjd": "mjd_tt"}
+        if loader == "from_parquet":
+            path = tmp_path / "obs.parquet"
+            pq.write_table(table, path, row_group_size=2)
+            return TrajectorySet.from_parquet(
+                pyoutfit_env, path, observer, columns=columns, batch_size=2, **kwargs
+            )
+        load = getattr(TrajectorySet, loader)
+        return load(pyoutfit_env, table, observer, columns=columns, **kwargs)
+
+    for ids in (wide, wide.astype(np.int64)):
+        ts = build(ids)
+        assert sorted(ts.keys()) == [1, 2**32 + 1]
+        _assert_same_sets(ts, direct)
+    with pytest.raises(ValueError, match="negative"):
+        build(tid.astype(np.int64) - 1)
+
+
+@pytest.mark.parametrize("batch_size", [1, 3, 65536])
+def test_from_parquet_streams_row_groups(
+    pyoutfit_env: PyOutfit, observer: Observer, tmp_path, batch_size: int
+):
+    """Parquet decoded in Rust equals direct ingestion, whatever the batch size."""
+    pa = pytest.importorskip("pyarrow")
+    pq = pytest.importorskip("pyarrow.parquet")
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd = _build_arrays_degrees()
+    direct = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, err_ra, err_dec, mjd, observer
+    )
+
+    # int64 ids, as written by pandas; several row groups.
+    path = tmp_path / "obs.parquet"
+    table = pa.table(
+        {"object": tid.astype(np.int64), "epoch": mjd, "alpha": ra_deg, "delta": dec_deg}
+    )
+    pq.write_table(table, path, row_group_size=2)
+    ts = TrajectorySet.from_parquet(
+        pyoutfit_env,
+        str(path),
+        observer,
+        columns={"tid": "object", "mjd": "epoch", "ra": "alpha", "dec": "delta"},
+        error_ra=err_ra,
+        error_dec=err_dec,
+        batch_size=batch_size,
+    )
+    assert ts.total_observations() == tid.size
+    _assert_same_sets(ts, direct)
+
+
+def test_from_parquet_observer_column(pyoutfit_env: PyOutfit, tmp_path):
+    """Per-row MPC codes, plain or dictionary-encoded, resolve the observing sites."""
+    pa = pytest.importorskip("pyarrow")
+    pq = pytest.importorskip("pyarrow.parquet")
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_radians()
+    site = pyoutfit_env.get_observer_from_mpc_code("I41")
+    direct = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid, ra, dec, float(err_ra), float(err_dec), mjd, site
+    )
+    kwargs = dict(units="radians", error_ra=float(err_ra), error_dec=float(err_dec))
+
+    codes = pa.array(["I41"] * tid.size)
+    for stn in (codes, codes.dictionary_encode()):
+        path = tmp_path / "stn.parquet"
+        pq.write_table(
+            pa.table({"tid": tid, "mjd": mjd, "ra": ra, "dec": dec, "stn": stn}),
+            path,
+            row_group_size=3,
+        )
+        ts = TrajectorySet.from_parquet(
+            pyoutfit_env, path, columns={"observer": "stn"}, **kwargs
+        )
+        _assert_same_sets(ts, direct)
+
+
+def test_from_parquet_rejects_bad_inputs(
+    pyoutfit_env: PyOutfit, observer: Observer, tmp_path
+):
+    pa = pytest.importorskip("pyarrow")
+    pq = pytest.importorskip("pyarrow.parquet")
+    tid, ra, dec, _, _, mjd = _build_arrays_radians()
+    kwargs = dict(units="radians", error_ra=1e-6, error_dec=1e-6)
+
+    path = tmp_path / "obs.parquet"
+    pq.write_table(
+        pa.table({"tid": tid, "mjd": mjd, "ra": ra.astype(np.float32), "dec": dec}), path
+    )
+    with pytest.raises(TypeError, match=r'"ra" has unsupported Arrow type Float32'):
+        TrajectorySet.from_parquet(pyoutfit_env, path, observer, **kwargs)
+    with pytest.raises(KeyError, match="not found"):
+        TrajectorySet.from_parquet(
+            pyoutfit_env, path, observer, columns={"mjd": "epoch"}, **kwargs
+        )
+    with pytest.raises(KeyError, match='"observer" not found'):
+        TrajectorySet.from_parquet(pyoutfit_env, path, **kwargs)
+
+    negative = tmp_path / "negative.parquet"
+    pq.write_table(
+        pa.table({"tid": tid.astype(np.int64) - 1, "mjd": mjd, "ra": ra, "dec": dec}),
+        negative,
+    )
+    with pytest.raises(ValueError, match="is negative"):
+        TrajectorySet.from_parquet(pyoutfit_env, negative, observer, **kwargs)
+
+    not_parquet = tmp_path / "notes.parquet"
+    not_parquet.write_text("not a parquet file\n")
+    with pytest.raises(ValueError, match="Parquet"):
+        TrajectorySet.from_parquet(pyoutfit_env, not_parquet, observer, **kwargs)
+    with pytest.raises(OSError):
+        TrajectorySet.from_parquet(pyoutfit_env, tmp_path / "missing.parquet", observer)
+
+
+_CHECKPOINT_COLUMNS = {"tid": "trajectory_id", "mjd": "mjd_tt"}
+
+
+def test_to_parquet_round_trip(pyoutfit_env: PyOutfit, tmp_path):
+    """A set written with `to_parquet` reads back unchanged, per-row sigmas included."""
+    pq = pytest.importorskip("pyarrow.parquet")
+    tid, ra, dec, _, _, mjd = _build_arrays_radians()
+    site = pyoutfit_env.get_observer_from_mpc_code("I41")
+    ts = TrajectorySet.from_numpy_radians(pyoutfit_env, tid, ra, dec, 1e-6, 2e-6, mjd, site)
+
+    path = tmp_path / "checkpoint.parquet"
+    assert ts.to_parquet(path) == tid.size
+    back = TrajectorySet.from_parquet(
+        pyoutfit_env, path, columns=_CHECKPOINT_COLUMNS, units="radians"
+    )
+    _assert_same_sets(back, ts)
+    assert back.is_time_sorted()
+
+    table = pq.read_table(path)
+    spec = py_outfit.schemas()["trajectory_set_parquet"]
+    assert table.column_names == [c["name"] for c in spec]
+    assert str(table.schema.field("trajectory_id").type) == "uint32"
+    assert table.schema.field("ra").metadata == {b"unit": b"rad"}
+    assert table.schema.metadata[b"py_outfit.schema_version"] == str(
+        py_outfit.SCHEMA_VERSION
+    ).encode()
+    assert set(table.column("observer").to_pylist()) == {"I41"}
+    ids = table.column("trajectory_id").to_pylist()
+    assert ids == sorted(ids)
+
+
+def test_to_parquet_string_ids_and_user_sites(
+    pyoutfit_env: PyOutfit, observer: Observer, tmp_path
+):
+    pq = pytest.importorskip("pyarrow.parquet")
+    obs80 = Path(__file__).resolve().parent / "data" / "2015AB.obs"
+    ts = TrajectorySet.new_from_mpc_80col(pyoutfit_env, obs80)
+    path = tmp_path / "mpc.parquet"
+    ts.to_parquet(str(path), compression="none")
+    assert str(pq.read_table(path).schema.field("trajectory_id").type) == "string"
+    back = TrajectorySet.from_parquet(
+        pyoutfit_env, path, columns=_CHECKPOINT_COLUMNS, units="radians"
+    )
+    _assert_same_sets(back, ts)
+
+    # Sites without an MPC code are written as null and need an explicit observer.
+    tid, ra, dec, _, _, mjd = _build_arrays_radians()
+    user = TrajectorySet.from_numpy_radians(pyoutfit_env, tid, ra, dec, 1e-6, 1e-6, mjd, observer)
+    user.subset(user.ids()).to_parquet(path)
+    assert pq.read_table(path).column("observer").null_count == tid.size
+    with pytest.raises(ValueError, match="null"):
+        TrajectorySet.from_parquet(pyoutfit_env, path, columns=_CHECKPOINT_COLUMNS, units="radians")
+    back = TrajectorySet.from_parquet(
+        pyoutfit_env, path, observer, columns=_CHECKPOINT_COLUMNS, units="radians"
+    )
+    _assert_same_sets(back, user)
+
+
+def test_to_parquet_rejects_bad_arguments(
+    pyoutfit_env: PyOutfit, observer: Observer, tmp_path
+):
+    tid, ra, dec, _, _, mjd = _build_arrays_radians()
+    ts = TrajectorySet.from_numpy_radians(pyoutfit_env, tid, ra, dec, 1e-6, 1e-6, mjd, observer)
+    with pytest.raises(ValueError, match="zstd compression is not available"):
+        ts.to_parquet(tmp_path / "obs.parquet", compression="zstd")
+    with pytest.raises(ValueError, match="unknown compression"):
+        ts.to_parquet(tmp_path / "obs.parquet", compression="gzip")
+    with pytest.raises(OSError, match="cannot create"):
+        ts.to_parquet(tmp_path / "missing" / "obs.parquet", env=pyoutfit_env)
+    empty = TrajectorySet.from_chunks(pyoutfit_env, [], observer)
+    with pytest.raises(ValueError, match="pass env="):
+        empty.to_parquet(tmp_path / "empty.parquet")
+
+
+def test_from_pandas_matches_numpy_ingestion(pyoutfit_env: PyOutfit, observer: Observer):
+    """Any integer or object id dtype and column names map to the NumPy loaders."""
+    pd = pytest.importorskip("pandas")
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd = _build_arrays_degrees()
+    direct = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, err_ra, err_dec, mjd, observer
+    )
+
+    df = pd.DataFrame(
+        {"trajectory_id": tid.astype(np.uint64), "mjd_tt": mjd, "ra": ra_deg, "dec": dec_deg}
+    )
+    ts = TrajectorySet.from_pandas(
+        pyoutfit_env, df, observer, error_ra=err_ra, error_dec=err_dec
+    )
+    _assert_same_sets(ts, direct)
+
+    renamed = df.rename(columns={"trajectory_id": "obj", "mjd_tt": "t", "ra": "a", "dec": "d"})
+    renamed["obj"] = renamed["obj"].astype("int16")
+    ts = TrajectorySet.from_pandas(
+        pyoutfit_env, renamed, observer, ra_col="a", dec_col="d", time_col="t",
+        id_col="obj", error_ra=err_ra, error_dec=err_dec,
+    )
+    _assert_same_sets(ts, direct)
+
+    # pandas often stores ids as `object`, even when they look numeric.
+    ts = TrajectorySet.from_pandas(
+        pyoutfit_env, df.astype({"trajectory_id": object}), observer,
+        error_ra=err_ra, error_dec=err_dec,
+    )
+    _assert_same_sets(ts, direct)
+    names = df.assign(trajectory_id=[f"obj{t}" for t in tid])
+    ts = TrajectorySet.from_pandas(pyoutfit_env, names, observer, error_ra=err_ra, error_dec=err_dec)
+    assert sorted(ts.keys()) == sorted({f"obj{t}" for t in tid})
+
+    tid, ra, dec, err_ra_rad, err_dec_rad, mjd = _build_arrays_radians()
+    direct = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid, ra, dec, float(err_ra_rad), float(err_dec_rad), mjd, observer
+    )
+    df = pd.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra, "dec": dec})
+    ts = TrajectorySet.from_pandas(
+        pyoutfit_env, df, observer, units="radians",
+        error_ra=float(err_ra_rad), error_dec=float(err_dec_rad),
+    )
+    _assert_same_sets(ts, direct)
+
+
+def test_from_pandas_per_row_uncertainties(pyoutfit_env: PyOutfit, observer: Observer):
+    pd = pytest.importorskip("pandas")
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    # Rows of the two trajectories interleaved.
+    order = np.array([0, 3, 1, 4, 2])
+    sigma_ra = np.array([0.1, 0.2, 0.3, 0.4, 0.5])
+    df = pd.DataFrame(
+        {
+            "trajectory_id": tid[order],
+            "mjd_tt": mjd[order],
+            "ra": ra_deg[order],
+            "dec": dec_deg[order],
+            "sigma_ra": sigma_ra,
+            "sigma_dec": 2 * sigma_ra,
+        }
+    )
+
+    ts = TrajectorySet.from_pandas(pyoutfit_env, df, observer)
+    arcsec = math.radians(1.0 / 3600.0)
+    for key in (0, 1):
+        rows = df[df["trajectory_id"] == key]
+        _, _, _, s_ra, s_dec = ts[key].to_numpy()
+        np.testing.assert_allclose(s_ra, rows["sigma_ra"].to_numpy() * arcsec, rtol=1e-12)
+        np.testing.assert_allclose(s_dec, rows["sigma_dec"].to_numpy() * arcsec, rtol=1e-12)
+
+    # Uniform uncertainties take precedence over the columns.
+    ts = TrajectorySet.from_pandas(pyoutfit_env, df, observer, error_ra=0.5, error_dec=0.5)
+    _, _, _, s_ra, _ = ts[0].to_numpy()
+    np.testing.assert_allclose(s_ra, 0.5 * arcsec, rtol=1e-12)
+
+    # Missing per-row values follow the bad_sigma policy.
+    df.loc[1, "sigma_ra"] = np.nan
+    with pytest.raises(ValueError, match="non-positive or non-finite"):
+        TrajectorySet.from_pandas(pyoutfit_env, df, observer)
+    ts = TrajectorySet.from_pandas(
+        pyoutfit_env, df, observer, bad_sigma="value", bad_sigma_value=0.7
+    )
+    assert ts.patched_sigma_count == 1
+
+
+def test_from_pandas_rejects_bad_frames(pyoutfit_env: PyOutfit, observer: Observer):
+    pd = pytest.importorskip("pandas")
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    df = pd.DataFrame({"tid": tid, "mjd_tt": mjd, "ra": ra_deg, "sigma_ra": 0.5})
+
+    with pytest.raises(ValueError) as info:
+        TrajectorySet.from_pandas(pyoutfit_env, df, observer)
+    message = str(info.value)
+    for missing in ("'trajectory_id' (id_col)", "'dec' (dec_col)", "'sigma_dec' (sigma_dec_col)"):
+        assert missing in message
+    assert "'mjd_tt'" not in message.split(";")[0]
+
+    good = pd.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra_deg, "dec": dec_deg})
+    bad_cases = {
+        "must hold non-null integer trajectory ids": good.assign(
+            trajectory_id=tid.astype(float)
+        ),
+        "outside the uint32 range": good.assign(trajectory_id=tid.astype(np.int64) - 1),
+        "must be numeric": good.assign(ra=ra_deg.astype(str)),
+    }
+    for message, frame in bad_cases.items():
+        with pytest.raises(ValueError, match=message):
+            TrajectorySet.from_pandas(pyoutfit_env, frame, observer, error_ra=0.5, error_dec=0.5)
+    with pytest.raises(TypeError, match=r"column 'trajectory_id': trajectory_id\[2\] = None"):
+        TrajectorySet.from_pandas(
+            pyoutfit_env, good.assign(trajectory_id=pd.Series([0, 0, None, 1, 1], dtype=object)),
+            observer,
+            error_ra=0.5, error_dec=0.5,
+        )
+    with pytest.raises(ValueError, match="given together"):
+        TrajectorySet.from_pandas(pyoutfit_env, good, observer, error_ra=0.5)
+    with pytest.raises(ValueError, match="units"):
+        TrajectorySet.from_pandas(
+            pyoutfit_env, good, observer, units="gradians", error_ra=0.5, error_dec=0.5
+        )
+
+
+def test_from_polars_matches_numpy_ingestion(pyoutfit_env: PyOutfit, observer: Observer):
+    """Zero-copy columns and the cast / rechunk fallback give the radians loader's set."""
+    pl = pytest.importorskip("polars")
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_radians()
+    err_ra, err_dec = float(err_ra), float(err_dec)
+    direct = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+    )
+    df = pl.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra, "dec": dec})
+    assert df["trajectory_id"].dtype == pl.UInt32
+
+    ts = TrajectorySet.from_polars(pyoutfit_env, df, observer, error_ra=err_ra, error_dec=err_dec)
+    _assert_same_sets(ts, direct)
+
+    # Chunked frame with 64-bit ids and renamed columns: cast + rechunk path.
+    chunked = pl.concat([df[:2], df[2:]], rechunk=False).with_columns(
+        pl.col("trajectory_id").cast(pl.Int64)
+    ).rename({"trajectory_id": "obj", "mjd_tt": "t"})
+    assert chunked["t"].n_chunks() == 2
+    ts = TrajectorySet.from_polars(
+        pyoutfit_env, chunked, observer, id_col="obj", time_col="t",
+        error_ra=err_ra, error_dec=err_dec,
+    )
+    _assert_same_sets(ts, direct)
+
+    tid, ra_deg, dec_deg, err_ra_as, err_dec_as, mjd = _build_arrays_degrees()
+    direct = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, err_ra_as, err_dec_as, mjd, observer
+    )
+    df = pl.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra_deg, "dec": dec_deg})
+    ts = TrajectorySet.from_polars(
+        pyoutfit_env, df, observer, units="degrees", error_ra=err_ra_as, error_dec=err_dec_as
+    )
+    _assert_same_sets(ts, direct)
+
+
+def test_from_polars_rejects_bad_frames(pyoutfit_env: PyOutfit, observer: Observer):
+    pl = pytest.importorskip("polars")
+    tid, ra, dec, _, _, mjd = _build_arrays_radians()
+    good = pl.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra, "dec": dec})
+
+    with pytest.raises(ValueError, match=r"'mjd_tt' \(time_col\), 'dec' \(dec_col\)"):
+        TrajectorySet.from_polars(pyoutfit_env, good.drop("dec", "mjd_tt"), observer)
+    bad_cases = {
+        "must hold non-null integer trajectory ids": good.with_columns(
+            pl.col("trajectory_id").cast(pl.Float64)
+        ),
+        "is negative": good.with_columns(
+            pl.col("trajectory_id").cast(pl.Int64) - 1
+        ),
+        "must be numeric": good.with_columns(pl.col("ra").cast(pl.Utf8)),
+    }
+    for message, frame in bad_cases.items():
+        with pytest.raises(ValueError, match=message):
+            TrajectorySet.from_polars(pyoutfit_env, frame, observer, error_ra=1e-6, error_dec=1e-6)
+    nullable = good.with_columns(
+        pl.when(pl.int_range(pl.len()) == 0).then(None).otherwise(pl.col("trajectory_id"))
+        .alias("trajectory_id")
+    )
+    with pytest.raises(ValueError, match="non-null"):
+        TrajectorySet.from_polars(pyoutfit_env, nullable, observer, error_ra=1e-6, error_dec=1e-6)
+
+
+def test_from_polars_validates_drops_and_deduplicates_rows(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    """The rows go through the NumPy loaders' checks: validate, drop_invalid and dedup."""
+    pl = pytest.importorskip("polars")
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    kwargs = dict(units="degrees", error_ra=err_ra, error_dec=err_dec)
+    df = pl.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra, "dec": dec})
+
+    # A null RA reads as NaN: rejected by default, dropped on request.
+    holed = df.with_columns(
+        pl.when(pl.int_range(pl.len()) == 1).then(None).otherwise(pl.col("ra")).alias("ra")
+    )
+    with pytest.raises(ValueError, match=r"ra_col: .*rows 1"):
+        TrajectorySet.from_polars(pyoutfit_env, holed, observer, **kwargs)
+    ts = TrajectorySet.from_polars(pyoutfit_env, holed, observer, drop_invalid=True, **kwargs)
+    keep = np.arange(tid.size) != 1
+    reference = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid[keep], ra[keep], dec[keep], err_ra, err_dec, mjd[keep], observer
+    )
+    _assert_same_sets(ts, reference)
+    assert ts.invalid_rows_dropped == {0: 1}
+
+    # Out-of-range values are reported with the argument naming their column.
+    tilted = df.with_columns(pl.col("dec") + 100.0)
+    with pytest.raises(ValueError, match=r"dec_col: .*rows 0, 1, 2, 3, 4"):
+        TrajectorySet.from_polars(pyoutfit_env, tilted, observer, **kwargs)
+
+    doubled = pl.concat([df, df[:2]])
+    ts = TrajectorySet.from_polars(pyoutfit_env, doubled, observer, dedup=True, **kwargs)
+    _assert_same_sets(ts, TrajectorySet.from_polars(pyoutfit_env, df, observer, **kwargs))
+    assert ts.duplicates_removed == {0: 2}
+
+
+def test_from_dataframe_reads_pandas_frames(pyoutfit_env: PyOutfit, observer: Observer):
+    """Integer, string and categorical ids of any width give the NumPy loader's set."""
+    pd = pytest.importorskip("pandas")
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd = _build_arrays_degrees()
+    kwargs = dict(error_ra=err_ra, error_dec=err_dec)
+    direct = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, err_ra, err_dec, mjd, observer
+    )
+    df = pd.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra_deg, "dec": dec_deg})
+
+    _assert_same_sets(TrajectorySet.from_dataframe(pyoutfit_env, df, observer, **kwargs), direct)
+    narrow = df.astype({"trajectory_id": np.int16, "ra": np.float32}).rename(
+        columns={"trajectory_id": "obj", "mjd_tt": "t"}
+    )
+    ts = TrajectorySet.from_dataframe(
+        pyoutfit_env, narrow, observer, id_col="obj", time_col="t", **kwargs
+    )
+    expected = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg.astype(np.float32).astype(np.float64), dec_deg,
+        err_ra, err_dec, mjd, observer,
+    )
+    _assert_same_sets(ts, expected)
+
+    names = [f"obj{t}" for t in tid]
+    by_name = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, names, ra_deg, dec_deg, err_ra, err_dec, mjd, observer
+    )
+    for ids in (names, pd.Categorical(names)):
+        ts = TrajectorySet.from_dataframe(
+            pyoutfit_env, df.assign(trajectory_id=ids), observer, **kwargs
+        )
+        _assert_same_sets(ts, by_name)
+    ts = TrajectorySet.from_dataframe(
+        pyoutfit_env, df.assign(trajectory_id=pd.Categorical(tid.astype(np.int64))), observer,
+        **kwargs,
+    )
+    _assert_same_sets(ts, direct)
+
+    wide = tid.astype(np.uint64) + 2**40
+    ts = TrajectorySet.from_dataframe(
+        pyoutfit_env, df.assign(trajectory_id=wide), observer, **kwargs
+    )
+    assert sorted(ts.keys()) == sorted({int(t) for t in wide})
+
+
+def test_from_dataframe_reads_polars_frames(pyoutfit_env: PyOutfit, observer: Observer):
+    """Chunked columns are concatenated and null values follow `bad_sigma`."""
+    pl = pytest.importorskip("polars")
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_radians()
+    err_ra, err_dec = float(err_ra), float(err_dec)
+    direct = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer
+    )
+    df = pl.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra, "dec": dec})
+    kwargs = dict(units="radians", error_ra=err_ra, error_dec=err_dec)
+
+    ts = TrajectorySet.from_dataframe(pyoutfit_env, df, observer, **kwargs)
+    _assert_same_sets(ts, direct)
+    chunked = pl.concat([df[:2], df[2:3], df[3:]], rechunk=False).with_columns(
+        pl.col("trajectory_id").cast(pl.Int64)
+    )
+    assert chunked["mjd_tt"].n_chunks() == 3
+    _assert_same_sets(TrajectorySet.from_dataframe(pyoutfit_env, chunked, observer, **kwargs), direct)
+
+    names = [f"obj{t}" for t in tid]
+    categorical = df.with_columns(pl.Series("trajectory_id", names, dtype=pl.Categorical))
+    ts = TrajectorySet.from_dataframe(pyoutfit_env, categorical, observer, **kwargs)
+    assert sorted(ts.keys()) == sorted(set(names))
+
+    # A null per-row uncertainty is missing, whatever the null representation.
+    sigmas = df.with_columns(
+        sigma_ra=pl.Series([err_ra, None, err_ra, err_ra, err_ra]),
+        sigma_dec=pl.lit(err_dec),
+    )
+    with pytest.raises(ValueError, match="non-positive or non-finite"):
+        TrajectorySet.from_dataframe(pyoutfit_env, sigmas, observer, units="radians")
+    ts = TrajectorySet.from_dataframe(
+        pyoutfit_env, sigmas, observer, units="radians", bad_sigma="value",
+        bad_sigma_value=2 * err_ra,
+    )
+    assert ts.patched_sigma_count == 1
+
+
+def test_from_dataframe_rejects_bad_frames(pyoutfit_env: PyOutfit, observer: Observer):
+    pd = pytest.importorskip("pandas")
+    pl = pytest.importorskip("polars")
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    good = pd.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra_deg, "dec": dec_deg})
+    kwargs = dict(error_ra=0.5, error_dec=0.5)
+
+    with pytest.raises(TypeError, match="interchange protocol"):
+        TrajectorySet.from_dataframe(pyoutfit_env, {"ra": ra_deg}, observer, **kwargs)
+    with pytest.raises(ValueError, match=r"'mjd_tt' \(time_col\), 'dec' \(dec_col\)"):
+        TrajectorySet.from_dataframe(
+            pyoutfit_env, good.drop(columns=["mjd_tt", "dec"]), observer, **kwargs
+        )
+    bad_cases = {
+        "must hold integer or string trajectory ids, got dtype float64": good.assign(
+            trajectory_id=tid.astype(float)
+        ),
+        "trajectory id -1 of row 0 is negative": good.assign(
+            trajectory_id=tid.astype(np.int64) - 1
+        ),
+        "must be numeric, got dtype string": good.assign(ra=ra_deg.astype(str)),
+        "unsupported dtype bool": good.assign(dec=True),
+    }
+    for message, frame in bad_cases.items():
+        with pytest.raises(ValueError, match=message):
+            TrajectorySet.from_dataframe(pyoutfit_env, frame, observer, **kwargs)
+
+    nullable = pl.from_pandas(good).with_columns(
+        pl.when(pl.int_range(pl.len()) == 2).then(None).otherwise(pl.col("trajectory_id"))
+        .alias("trajectory_id")
+    )
+    with pytest.raises(ValueError, match="the trajectory id of row 2 is null"):
+        TrajectorySet.from_dataframe(pyoutfit_env, nullable, observer, **kwargs)
+
+
+def _fink_columns() -> dict:
+    """Fink tracklet columns of the degrees test rows (ZTF ids, JD in UTC)."""
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+    return {
+        "objectId": [f"ZTF21aa{t:05d}" for t in tid],
+        "ra": ra_deg,
+        "dec": dec_deg,
+        "jd": mjd + 2400000.5,
+        "sigmara": np.array([0.1, 0.2, 0.3, 0.4, 0.5]),
+        "sigmadec": np.array([0.2, 0.3, 0.4, 0.5, 0.6]),
+        "fid": np.array([1, 2, 1, 3, 2], dtype=np.int32),
+    }
+
+
+@pytest.mark.parametrize("library", ["pandas", "polars"])
+def test_from_fink_matches_numpy_ingestion(pyoutfit_env: PyOutfit, library: str):
+    """JD (UTC) epochs, arcsecond sigmas, ZTF site and filter bands of the schema."""
+    lib = pytest.importorskip(library)
+    columns = _fink_columns()
+    ztf = pyoutfit_env.get_observer_from_mpc_code("I41")
+    direct = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, columns["objectId"], columns["ra"], columns["dec"],
+        columns["sigmara"], columns["sigmadec"], columns["jd"], ztf,
+        time_scale="UTC", epoch_format="jd",
+        band=[{1: "g", 2: "r", 3: "i"}[f] for f in columns["fid"]],
+    )
+
+    ts = TrajectorySet.from_fink(pyoutfit_env, lib.DataFrame(columns))
+    _assert_same_sets(ts, direct)
+    assert sorted(ts.keys()) == ["ZTF21aa00000", "ZTF21aa00001"]
+    for key in ts.keys():
+        mag, band = ts[key].magnitudes()
+        assert np.isnan(mag).all()
+        assert list(band) == list(direct[key].magnitudes()[1])
+
+    # `magpsf` is kept when present; extra columns are ignored.
+    extra = dict(columns, magpsf=np.array([18.0, 18.1, 18.2, 19.0, 19.1]), candid=np.arange(5))
+    ts = TrajectorySet.from_fink(pyoutfit_env, lib.DataFrame(extra))
+    mag, _ = ts["ZTF21aa00001"].magnitudes()
+    np.testing.assert_array_equal(np.sort(mag), [19.0, 19.1])
+
+
+def test_from_fink_rejects_bad_tables(pyoutfit_env: PyOutfit):
+    pd = pytest.importorskip("pandas")
+    columns = _fink_columns()
+
+    df = pd.DataFrame(columns).drop(columns=["jd", "fid"])
+    with pytest.raises(ValueError) as info:
+        TrajectorySet.from_fink(pyoutfit_env, df)
+    assert "'jd' (epoch), 'fid' (ZTF filter)" in str(info.value)
+
+    with pytest.raises(ValueError, match="unknown ZTF filter id 4"):
+        TrajectorySet.from_fink(pyoutfit_env, pd.DataFrame(dict(columns, fid=[1, 2, 4, 1, 2])))
+    with pytest.raises(ValueError, match="looks like an MJD"):
+        TrajectorySet.from_fink(pyoutfit_env, pd.DataFrame(dict(columns, jd=columns["jd"] - 2400000.5)))
+    bad_sigma = dict(columns, sigmara=np.array([0.1, 0.0, 0.3, 0.4, 0.5]))
+    with pytest.raises(ValueError, match="non-positive or non-finite"):
+        TrajectorySet.from_fink(pyoutfit_env, pd.DataFrame(bad_sigma))
+    ts = TrajectorySet.from_fink(
+        pyoutfit_env, pd.DataFrame(bad_sigma), bad_sigma="value", bad_sigma_value=0.5
+    )
+    assert ts.patched_sigma_count == 1
+
+
+def test_from_astropy_table_units_and_time_scales(pyoutfit_env: PyOutfit, observer: Observer):
+    """Attached units and Time scales are honoured: the set equals the degrees loader's."""
+    pytest.importorskip("astropy")
+    import astropy.units as u
+    from astropy.table import QTable, Table
+    from astropy.time import Time
+
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd_utc = _build_arrays_degrees()
+    times = Time(mjd_utc, format="mjd", scale="utc")
+    direct = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, err_ra, err_dec, times.tt.mjd, observer
+    )
+
+    qt = QTable(
+        {
+            "trajectory_id": tid.astype(np.int64),
+            "time": times,
+            "ra": ra_deg * u.deg,
+            "dec": np.deg2rad(dec_deg) * u.rad,
+        }
+    )
+    ts = TrajectorySet.from_astropy_table(
+        pyoutfit_env, qt, observer, error_ra=err_ra * u.arcsec, error_dec=err_dec
+    )
+    _assert_same_sets(ts, direct, rtol=1e-13)
+
+    # Plain columns: unitless angles with `units`, epochs as MJD (TT) in days.
+    t = Table(
+        {"obj": tid, "epoch": times.tt.mjd, "ra": ra_deg, "dec": dec_deg}
+    )
+    t["epoch"].unit = u.day
+    ts = TrajectorySet.from_astropy_table(
+        pyoutfit_env, t, observer, id_col="obj", time_col="epoch", units="degrees",
+        error_ra=err_ra, error_dec=err_dec,
+    )
+    _assert_same_sets(ts, direct, rtol=1e-13)
+
+    # A TDB Time column is converted to TT as well.
+    qt["time"] = times.tdb
+    ts = TrajectorySet.from_astropy_table(
+        pyoutfit_env, qt, observer, error_ra=err_ra, error_dec=err_dec
+    )
+    np.testing.assert_allclose(ts[0].to_numpy()[0], times.tt.mjd[:3], rtol=0, atol=1e-9)
+
+
+def test_from_astropy_table_rejects_unit_mismatches(pyoutfit_env: PyOutfit, observer: Observer):
+    pytest.importorskip("astropy")
+    import astropy.units as u
+    from astropy.table import MaskedColumn, QTable
+
+    tid, ra_deg, dec_deg, _, _, mjd = _build_arrays_degrees()
+
+    def table(**overrides):
+        cols = {"trajectory_id": tid, "time": mjd, "ra": ra_deg * u.deg, "dec": dec_deg * u.deg}
+        cols.update(overrides)
+        return QTable(cols)
+
+    def build(t, **kwargs):
+        return TrajectorySet.from_astropy_table(
+            pyoutfit_env, t, observer, error_ra=0.5, error_dec=0.5, **kwargs
+        )
+
+    cases = [
+        (table(ra=ra_deg), {}, '"ra" has no unit'),
+        (table(), {"units": "radians"}, '"ra" is in \'deg\' but units=\'radians\''),
+        (table(dec=dec_deg * u.m), {}, '"dec" has unit \'m\', which is not an angle'),
+        (table(time=mjd * u.deg), {}, "expected an astropy Time"),
+        (table(ra=MaskedColumn(ra_deg, mask=[0, 1, 0, 0, 0], unit="deg")), {}, "masked"),
+        (table(trajectory_id=tid.astype(float)), {}, "integer trajectory ids"),
+        (table()[["ra", "dec"]], {}, "'trajectory_id' \\(id_col\\), 'time'"),
+    ]
+    for t, kwargs, message in cases:
+        with pytest.raises(ValueError, match=message):
+            build(t, **kwargs)
+    with pytest.raises(u.UnitConversionError):
+        TrajectorySet.from_astropy_table(
+            pyoutfit_env, table(), observer, error_ra=0.5 * u.s, error_dec=0.5
+        )
+
+
+def test_from_polars_reads_columns_in_place(
+    pyoutfit_env: PyOutfit, observer: Observer, monkeypatch
+):
+    """
+    Non-null single-chunk Float64 / UInt32 columns are handed to the loader as no-copy
+    views of the frame's own Arrow buffers; a chunked column is rechunked into a copy.
+    """
+    pl = pytest.importorskip("polars")
+    tid, ra, dec, _, _, mjd = _build_arrays_radians()
+    df = pl.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra, "dec": dec})
+    buffers = {c: df[c].to_numpy() for c in df.columns}
+
+    views = {}
+    to_numpy = pl.Series.to_numpy
+
+    def spy(self, *args, **kwargs):
+        out = to_numpy(self, *args, **kwargs)
+        views[self.name] = (kwargs.get("allow_copy", True), out)
+        return out
+
+    monkeypatch.setattr(pl.Series, "to_numpy", spy)
+    ts = TrajectorySet.from_polars(pyoutfit_env, df, observer, error_ra=1e-6, error_dec=1e-6)
+    assert ts.total_observations() == tid.size
+    for c in df.columns:
+        allow_copy, view = views[c]
+        assert allow_copy is False, c
+        assert np.shares_memory(view, buffers[c]), c
+
+    views.clear()
+    chunked = pl.concat([df[:2], df[2:]], rechunk=False)
+    TrajectorySet.from_polars(pyoutfit_env, chunked, observer, error_ra=1e-6, error_dec=1e-6)
+    assert not np.shares_memory(views["ra"][1], buffers["ra"])
+
+
+@pytest.mark.benchmark
+def test_from_polars_benchmark(pyoutfit_env: PyOutfit, observer: Observer, best_of):
+    """
+    Benchmark against the NumPy loader on 200k rows. Timings are only reported; the
+    no-copy behaviour is checked by `test_from_polars_reads_columns_in_place`.
+    """
+    pl = pytest.importorskip("polars")
+    n_traj, n_obs = 2_000, 100
+    tid = np.repeat(np.arange(n_traj, dtype=np.uint32), n_obs)
+    mjd = 60000.0 + np.tile(np.arange(n_obs) * 0.01, n_traj)
+    ra = np.linspace(0.1, 0.2, tid.size)
+    dec = np.linspace(-0.1, 0.1, tid.size)
+    df = pl.DataFrame({"trajectory_id": tid, "mjd_tt": mjd, "ra": ra, "dec": dec})
+
+    def via_numpy():
+        cols = [df[c].to_numpy() for c in ("trajectory_id", "ra", "dec")]
+        return TrajectorySet.from_numpy_radians(
+            pyoutfit_env, cols[0], cols[1], cols[2], 1e-6, 1e-6, df["mjd_tt"].to_numpy(), observer
+        )
+
+    def via_polars():
+        return TrajectorySet.from_polars(pyoutfit_env, df, observer, error_ra=1e-6, error_dec=1e-6)
+
+    assert via_polars().total_observations() == via_numpy().total_observations() == tid.size
+    t_numpy = best_of(via_numpy)
+    t_polars = best_of(via_polars)
+    print(f"\nfrom_polars {t_polars:.3f}s vs from_numpy_radians {t_numpy:.3f}s")
+
+
+def test_from_numpy_degrees_matches_radians_across_chunks(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    """
+    Degrees are converted a chunk of rows at a time: a batch spanning several chunks, with
+    trajectories split across chunk boundaries, gives the same observations as the
+    radians path.
+    """
+    n = 150_000
+    rng = np.random.default_rng(7)
+    tid = (np.arange(n, dtype=np.uint32) * 7919) % 300
+    mjd = 60000.0 + (np.arange(n) % 500) * 0.01
+    ra = rng.uniform(0.0, 360.0, n)
+    dec = rng.uniform(-89.0, 89.0, n)
+
+    deg = TrajectorySet.from_numpy_degrees(pyoutfit_env, tid, ra, dec, 0.5, 0.3, mjd, observer)
+    rad = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid, np.radians(ra), np.radians(dec),
+        np.radians(0.5 / 3600.0), np.radians(0.3 / 3600.0), mjd, observer,
+    )
+    assert deg.total_observations() == rad.total_observations() == n
+    assert sorted(deg.keys()) == sorted(rad.keys())
+    for key in deg.keys():
+        np.testing.assert_array_equal(deg[key].to_numpy(), rad[key].to_numpy())
+
+
+_PEAK_RSS_SCRIPT = """
+import resource, sys
+import numpy as np
+from py_outfit import Observer, PyOutfit, TrajectorySet
+
+env = PyOutfit(ephem="horizon:DE440", error_model="FCCT14")
+observer = Observer(0.123456, 45.0, 1234.0, "UnitTest Observatory", None, None)
+n = int(sys.argv[2])
+tid = np.repeat(np.arange(n // 100, dtype=np.uint32), 100)
+mjd = 60000.0 + np.tile(np.arange(100) * 0.01, n // 100)
+ra = np.linspace(10.0, 20.0, n)
+dec = np.linspace(-5.0, 5.0, n)
+if sys.argv[1] == "radians":
+    ra, dec = np.radians(ra), np.radians(dec)
+    load = TrajectorySet.from_numpy_radians
+else:
+    load = TrajectorySet.from_numpy_degrees
+before = resource.getrusage(resource.RUSAGE_SELF).ru_maxrss
+ts = load(env, tid, ra, dec, 1e-6, 1e-6, mjd, observer)
+after = resource.getrusage(resource.RUSAGE_SELF).ru_maxrss
+assert ts.total_observations() == n
+print((after - before) * 1024)
+"""
+
+
+@pytest.mark.skipif(not sys.platform.startswith("linux"), reason="ru_maxrss in KiB on Linux")
+def test_from_numpy_degrees_peak_memory_matches_radians_path():
+    """
+    Ingesting degrees allocates no full-size converted copy of the columns: the peak
+    resident memory of the call stays within a few bytes per row of the radians path
+    (a copy of the ids, angles and epochs would add 28 bytes per row).
+    """
+    n = 2_000_000
+
+    def peak(path: str) -> int:
+        out = subprocess.run(
+            [sys.executable, "-c", _PEAK_RSS_SCRIPT, path, str(n)],
+            capture_output=True, text=True, check=True,
+        )
+        return int(out.stdout.split()[-1])
+
+    radians, degrees = peak("radians"), peak("degrees")
+    assert degrees - radians < 8 * n, f"degrees +{degrees} B vs radians +{radians} B"
+
+
+_STREAMING_SCRIPT = """
+import json, sys
+import numpy as np
+from py_outfit import Observer, PyOutfit, TrajectorySet
+
+def status(field):
+    with open("/proc/self/status") as f:
+        line = next(l for l in f if l.startswith(field + ":"))
+    return int(line.split()[1]) * 1024
+
+env = PyOutfit(ephem="horizon:DE440", error_model="FCCT14")
+observer = Observer(0.123456, 45.0, 1234.0, "UnitTest Observatory", None, None)
+n, n_traj = int(sys.argv[1]), int(sys.argv[2])
+rng = np.random.default_rng(2)
+tid = rng.integers(0, n_traj, n).astype(np.uint32)
+mjd = 60000.0 + rng.integers(0, 2_000, n) * 0.001
+ra = rng.uniform(0.0, 360.0, n)
+dec = rng.uniform(-89.0, 89.0, n)
+sigma = rng.uniform(0.1, 1.0, n)
+
+before = status("VmRSS")
+ts = TrajectorySet.from_numpy_degrees(env, tid, ra, dec, sigma, 0.3, mjd, observer, sort=True)
+added = status("VmHWM") - before
+usage = ts.memory_usage()
+assert ts.total_observations() == n
+
+# Observations of a sample of trajectories: the rows of their id, in canonical order ...
+order = np.argsort(tid, kind="stable")
+sorted_tid = tid[order]
+keys = rng.choice(np.unique(tid), 1_000, replace=False)
+for key in keys:
+    rows = order[np.searchsorted(sorted_tid, key):np.searchsorted(sorted_tid, key, "right")]
+    rows = rows[np.lexsort((dec[rows], ra[rows], mjd[rows]))]
+    t, ra_rad, dec_rad, sigma_ra, sigma_dec = ts[int(key)].to_numpy()
+    np.testing.assert_array_equal(t, mjd[rows])
+    np.testing.assert_array_equal(ra_rad, np.radians(ra[rows]))
+    np.testing.assert_array_equal(dec_rad, np.radians(dec[rows]))
+    np.testing.assert_allclose(sigma_ra, np.radians(sigma[rows] / 3600.0), rtol=1e-12)
+    np.testing.assert_allclose(sigma_dec, np.radians(0.3 / 3600.0), rtol=1e-12)
+
+# ... and, positions included, those the core constructor builds from the same rows.
+reference = TrajectorySet.from_observations(
+    env, {int(k): ts[int(k)] for k in keys}, observer=observer, sort=True
+)
+for key in keys:
+    a, b = ts[int(key)], reference[int(key)]
+    for x, y in zip(a.to_numpy(), b.to_numpy()):
+        np.testing.assert_array_equal(x, y)
+    np.testing.assert_array_equal(
+        a.geometry(env)["observer_position"], b.geometry(env)["observer_position"]
+    )
+print(json.dumps({"added": added, "total": usage["total"], "spare": usage["spare_capacity"]}))
+"""
+
+
+@pytest.mark.skipif(not sys.platform.startswith("linux"), reason="reads /proc/self/status")
+def test_streamed_ingestion_peak_memory_and_contents():
+    """
+    Stress test on 10M rows over 500k interleaved trajectories: the observations are
+    streamed into trajectories allocated at their final size, so the call adds at most
+    1.1x the final set to the peak resident memory and leaves no spare capacity, and
+    they match the observations the core constructor builds from the same rows.
+    """
+    out = subprocess.run(
+        [sys.executable, "-c", _STREAMING_SCRIPT, str(10_000_000), str(500_000)],
+        capture_output=True, text=True,
+    )
+    assert out.returncode == 0, out.stderr
+    stats = json.loads(out.stdout.splitlines()[-1])
+    assert stats["spare"] == 0
+    assert stats["added"] <= 1.1 * stats["total"], stats
+
+
+def test_memory_usage_scales_with_observations_and_shrinks(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    def build(n_per_traj: int) -> TrajectorySet:
+        n_traj = 10
+        tid = np.repeat(np.arange(n_traj, dtype=np.uint32), n_per_traj)
+        mjd = 60000.0 + np.tile(np.arange(n_per_traj) * 0.01, n_traj)
+        ra = np.linspace(10.0, 20.0, tid.size)
+        dec = np.linspace(-5.0, 5.0, tid.size)
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra, dec, 0.5, 0.5, mjd, observer
+        )
+
+    usages = {n: build(n).memory_usage() for n in (50, 100, 200)}
+    keys = {
+        "observations", "prepared_geometry", "observer_interning", "spare_capacity",
+        "trajectory_overhead", "total",
+    }
+    obs_size = set()
+    for n, usage in usages.items():
+        assert set(usage) == keys
+        assert usage["total"] == sum(v for k, v in usage.items() if k != "total")
+        # Per-observation categories are exactly linear.
+        per_obs = usage["observations"] + usage["prepared_geometry"] + usage["observer_interning"]
+        assert per_obs % (10 * n) == 0
+        obs_size.add(per_obs // (10 * n))
+        assert usage["prepared_geometry"] == 10 * n * 48
+        assert usage["observer_interning"] == 10 * n * 2
+    assert len(obs_size) == 1
+    for small, large in ((50, 100), (100, 200)):
+        ratio = usages[large]["total"] / usages[small]["total"]
+        assert 1.5 < ratio < 2.5
+
+    ts = build(100)
+    before = [ts[k].to_numpy() for k in sorted(ts.keys())]
+    usage = ts.memory_usage()
+    released = ts.shrink_to_fit()
+    after = ts.memory_usage()
+    assert released == usage["total"] - after["total"] >= 0
+    assert after["spare_capacity"] == 0
+    assert after["observations"] == usage["observations"]
+    assert ts.total_observations() == 1000
+    for cols_before, key in zip(before, sorted(ts.keys())):
+        for a, b in zip(cols_before, ts[key].to_numpy()):
+            np.testing.assert_array_equal(a, b)
+    assert ts.shrink_to_fit() == 0
+
+
+@pytest.mark.filterwarnings("ignore::RuntimeWarning")
+def test_estimate_arc_growth_final_checkpoint_matches_full_arc(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer, traj_data
+):
+    """A checkpoint after the last observation reproduces the plain full-arc run."""
+    tid, ra_deg, dec_deg, mjd_tt = traj_data
+    sel = tid == 0
+    traj_set = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        tid[sel].astype(np.uint32),
+        ra_deg[sel],
+        dec_deg[sel],
+        0.5,
+        0.5,
+        mjd_tt[sel],
+        ZTF_observatory,
+    )
+    params = py_outfit.IODParams.builder().n_noise_realizations(0).build()
+
+    epochs = np.sort(mjd_tt[sel])
+    too_early = float(epochs[1])  # only one observation before it
+    middle = float(epochs[len(epochs) // 2])
+    final = float(epochs[-1]) + 1.0
+    ok, errors = traj_set.estimate_arc_growth(
+        pyoutfit_env, params, [too_early, middle, final], seed=42
+    )
+
+    assert (0, too_early) not in ok and (0, too_early) not in errors
+    assert set(ok) | set(errors) <= {(0, middle), (0, final)}
+    if (0, middle) in ok:
+        assert ok[(0, middle)][0].fit_statistics.n_points == len(epochs) // 2
+
+    full_ok, full_err = traj_set.estimate_all_orbits(pyoutfit_env, params, seed=42)
+    assert full_err == {}
+    grown, rms = ok[(0, final)]
+    full, full_rms = full_ok[0]
+    assert rms == pytest.approx(full_rms, rel=1e-12)
+    assert grown.fit_statistics.n_points == full.fit_statistics.n_points
+    for name, value in grown.to_dict()["elements"].items():
+        assert value == pytest.approx(full.to_dict()["elements"][name], rel=1e-12)
+
+
+def test_time_scale_converts_utc_and_tai_epochs_to_tt(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer
+):
+    """UTC and TAI epochs are stored in TT, across the 2016-12-31 leap second."""
+    from astropy.time import Time
+
+    mjd = np.array([57753.5, 57753.99999, 57754.0, 57754.00001, 58789.3])
+    tid = np.arange(mjd.size, dtype=np.uint32)
+    ra = np.full(mjd.size, 20.0)
+    dec = np.full(mjd.size, -5.0)
+
+    def epochs(time_scale: str) -> np.ndarray:
+        ts = TrajectorySet.from_numpy_degrees(
+            pyoutfit_env,
+            tid,
+            ra,
+            dec,
+            0.5,
+            0.5,
+            mjd,
+            ZTF_observatory,
+            time_scale=time_scale,
+        )
+        return np.array([ts[k].to_numpy()[0][0] for k in range(mjd.size)])
+
+    np.testing.assert_array_equal(epochs("TT"), mjd)
+    expected = Time(mjd, format="mjd", scale="utc").tt.mjd
+    np.testing.assert_allclose(epochs("UTC"), expected, rtol=0, atol=1e-9)
+    np.testing.assert_allclose(
+        epochs("TAI"), mjd + 32.184 / 86400.0, rtol=0, atol=1e-12
+    )
+
+    with pytest.raises(ValueError, match="time_scale"):
+        epochs("TDB")
+    with pytest.raises(ValueError, match="1972"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env,
+            tid[:1],
+            ra[:1],
+            dec[:1],
+            0.5,
+            0.5,
+            np.array([41000.0]),
+            ZTF_observatory,
+            time_scale="UTC",
+        )
+
+
+def test_epoch_format_accepts_julian_dates_and_rejects_mixups(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer
+):
+    """JD epochs match their MJD counterparts; a format mixup raises ValueError."""
+    from astropy.time import Time
+
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd_tt = _build_arrays_degrees()
+    jd = mjd_tt + 2400000.5
+
+    def build(epochs, **kwargs) -> TrajectorySet:
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env,
+            tid,
+            ra_deg,
+            dec_deg,
+            err_ra,
+            err_dec,
+            epochs,
+            ZTF_observatory,
+            **kwargs,
+        )
+
+    def epochs(ts: TrajectorySet) -> np.ndarray:
+        return np.concatenate([ts[k].to_numpy()[0] for k in sorted(ts.keys())])
+
+    by_mjd = epochs(build(mjd_tt))
+    np.testing.assert_allclose(
+        epochs(build(jd, epoch_format="jd")), by_mjd, rtol=0, atol=1e-8
+    )
+    np.testing.assert_allclose(
+        epochs(
+            TrajectorySet.from_numpy_radians(
+                pyoutfit_env,
+                tid,
+                np.radians(ra_deg),
+                np.radians(dec_deg),
+                1e-6,
+                1e-6,
+                jd,
+                ZTF_observatory,
+                epoch_format="jd",
+            )
+        ),
+        by_mjd,
+        rtol=0,
+        atol=1e-8,
+    )
+
+    # Format first, then scale: JD (UTC) matches astropy.
+    utc = epochs(build(jd, epoch_format="jd", time_scale="UTC"))
+    expected = Time(jd, format="jd", scale="utc").tt.mjd
+    np.testing.assert_allclose(np.sort(utc), np.sort(expected), rtol=0, atol=1e-8)
+
+    with pytest.raises(ValueError, match=r"mjd\[0\].*looks like a Julian Date.*'jd'"):
+        build(jd)
+    with pytest.raises(ValueError, match=r"looks like an MJD.*'mjd'"):
+        build(mjd_tt, epoch_format="jd")
+    with pytest.raises(ValueError, match="epoch_format"):
+        build(mjd_tt, epoch_format="JD")
+
+
+def test_iso_timestamps_are_parsed_as_utc_epochs(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer
+):
+    """ISO-8601 strings give the same TT epochs as astropy, offsets and leap second included."""
+    from astropy.time import Time
+
+    stamps = [
+        "2024-03-14T02:31:18.220Z",
+        "2024-03-14T03:31:18.220+01:00",
+        "2024-03-13T21:01:18.220-0530",
+        "2024-03-14 02:31:18.22",
+        "2016-12-31T23:59:60.5Z",
+        "2017-01-01T00:59:60.5+01",
+        "2019-11-05",
+    ]
+    reference = Time(
+        [
+            "2024-03-14T02:31:18.220",
+            "2024-03-14T02:31:18.220",
+            "2024-03-14T02:31:18.220",
+            "2024-03-14T02:31:18.220",
+            "2016-12-31T23:59:60.500",
+            "2016-12-31T23:59:60.500",
+            "2019-11-05T00:00:00",
+        ],
+        format="isot",
+        scale="utc",
+    )
+    tid = np.arange(len(stamps), dtype=np.uint32)
+    ra = np.full(len(stamps), 20.0)
+    dec = np.full(len(stamps), -5.0)
+
+    def epochs(values, **kwargs) -> np.ndarray:
+        ts = TrajectorySet.from_numpy_degrees(
+            pyoutfit_env,
+            tid[: len(values)],
+            ra[: len(values)],
+            dec[: len(values)],
+            0.5,
+            0.5,
+            values,
+            ZTF_observatory,
+            **kwargs,
+        )
+        return np.array([ts[k].to_numpy()[0][0] for k in range(len(values))])
+
+    expected = reference.tt.mjd
+    for values in (stamps, np.array(stamps), np.array(stamps, dtype=object)):
+        np.testing.assert_allclose(epochs(values), expected, rtol=0, atol=1e-9)
+
+    # An explicit time scale applies to the wall-clock time of the strings.
+    np.testing.assert_allclose(
+        epochs(["2024-03-14T02:31:18.220"], time_scale="TT"),
+        Time("2024-03-14T02:31:18.220", scale="tt").mjd,
+        rtol=0,
+        atol=1e-9,
+    )
+
+    for bad in (
+        "2024-03-14T25:00:00Z",
+        "2024-02-30T00:00:00Z",
+        "2024-03-14T02:31:18+1",
+        "2024-03-14T23:59:60Z",
+        "14/03/2024",
+    ):
+        with pytest.raises(ValueError, match=r"mjd\[1\] = .*" + bad[:4]):
+            epochs([stamps[0], bad])
+    with pytest.raises(ValueError, match="epoch_format"):
+        epochs(stamps[:1], epoch_format="jd")
+
+
+def test_datetime64_epochs_are_converted_from_utc(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer
+):
+    """datetime64[ns] / [us] epochs match astropy to below a microsecond; NaT is rejected."""
+    from astropy.time import Time
+
+    stamps = np.array(
+        [
+            "2016-12-31T23:59:59.999999",
+            "2017-01-01T00:00:00.000001",
+            "2024-03-14T02:31:18.220123",
+            "2024-03-14T02:31:18.220124",
+        ],
+        dtype="datetime64[us]",
+    )
+    tid = np.arange(stamps.size, dtype=np.uint32)
+    ra = np.full(stamps.size, 20.0)
+    dec = np.full(stamps.size, -5.0)
+
+    def epochs(values, **kwargs) -> np.ndarray:
+        ts = TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra, dec, 0.5, 0.5, values, ZTF_observatory, **kwargs
+        )
+        return np.array([ts[k].to_numpy()[0][0] for k in range(stamps.size)])
+
+    expected = Time(stamps, scale="utc").tt.mjd
+    for values in (stamps, stamps.astype("datetime64[ns]")):
+        got = epochs(values)
+        np.testing.assert_allclose(got, expected, rtol=0, atol=1e-11)
+        # One microsecond apart stays one microsecond apart.
+        assert got[3] > got[2]
+        assert (got[3] - got[2]) * 86400e6 == pytest.approx(1.0, abs=0.7)
+
+    np.testing.assert_allclose(
+        epochs(stamps, time_scale="TAI"),
+        Time(stamps, scale="tai").tt.mjd,
+        rtol=0,
+        atol=1e-11,
+    )
+
+    with_nat = stamps.astype("datetime64[ns]")
+    with_nat[[1, 3]] = np.datetime64("NaT")
+    with pytest.raises(ValueError, match=r"2 NaT value\(s\), at rows 1, 3"):
+        epochs(with_nat)
+    with pytest.raises(ValueError, match="epoch_format"):
+        epochs(stamps, epoch_format="jd")
+
+
+def test_numpy_loaders_accept_any_real_dtype_and_layout(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer
+):
+    """Strided views and non-float64 dtypes give the same set as contiguous float64."""
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd_tt = _build_arrays_degrees()
+
+    def build(ids, ra, dec, mjd, sigma=err_ra) -> TrajectorySet:
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, ids, ra, dec, sigma, err_dec, mjd, ZTF_observatory
+        )
+
+    def columns(ts: TrajectorySet):
+        return {k: ts[k].to_numpy() for k in ts.keys()}
+
+    def assert_same(a: TrajectorySet, b: TrajectorySet):
+        ca, cb = columns(a), columns(b)
+        assert ca.keys() == cb.keys()
+        for k in ca:
+            for x, y in zip(ca[k], cb[k]):
+                np.testing.assert_array_equal(x, y)
+
+    reference = build(tid, ra_deg, dec_deg, mjd_tt)
+
+    # Strided views: every other element of interleaved buffers, and reversed arrays.
+    def strided(a):
+        buf = np.empty(2 * a.size, dtype=a.dtype)
+        buf[::2] = a
+        return buf[::2]
+
+    view = build(
+        strided(tid),
+        strided(ra_deg),
+        strided(dec_deg),
+        strided(mjd_tt),
+        sigma=strided(np.full(tid.size, err_ra)),
+    )
+    assert not strided(ra_deg).flags.c_contiguous
+    assert_same(view, reference)
+    assert_same(
+        build(tid[::-1], ra_deg[::-1], dec_deg[::-1], mjd_tt[::-1]), reference
+    )
+
+    # Other dtypes are converted once; float32 keeps its own rounding.
+    ra32, dec32 = ra_deg.astype(np.float32), dec_deg.astype(np.float32)
+    assert_same(
+        build(tid.astype(np.int16), ra32, dec32, mjd_tt),
+        build(tid, ra32.astype(np.float64), dec32.astype(np.float64), mjd_tt),
+    )
+    for dtype in (np.uint8, np.int32, np.int64, np.uint64):
+        assert_same(build(tid.astype(dtype), ra_deg, dec_deg, mjd_tt), reference)
+    whole_days = np.floor(mjd_tt).astype(np.int64)
+    assert_same(
+        build(tid, ra_deg, dec_deg, whole_days),
+        build(tid, ra_deg, dec_deg, whole_days.astype(np.float64)),
+    )
+
+    with pytest.raises(TypeError, match=r"ra_deg.*complex128"):
+        build(tid, ra_deg.astype(np.complex128), dec_deg, mjd_tt)
+    with pytest.raises(TypeError, match="dec_deg"):
+        build(tid, ra_deg, dec_deg.astype(str), mjd_tt)
+    with pytest.raises(TypeError, match="trajectory_id"):
+        build(tid.astype(np.float64), ra_deg, dec_deg, mjd_tt)
+
+
+def test_magnitude_and_band_follow_the_observations(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer
+):
+    """Photometry given at ingestion survives sorting, splitting, merging and rebuilds."""
+    tid, ra_deg, dec_deg, err_ra, err_dec, mjd_tt = _build_arrays_degrees()
+    mag = np.array([18.0, 18.1, 18.2, 19.0, np.nan])
+    band = ["g", "r", "g", None, "i"]
+    expected = {t: (m, b) for t, m, b in zip(mjd_tt, mag, band)}
+
+    # Rows out of time order: the photometry must follow the sorting of the set.
+    perm = np.array([2, 0, 4, 1, 3])
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        tid[perm],
+        ra_deg[perm],
+        dec_deg[perm],
+        err_ra,
+        err_dec,
+        mjd_tt[perm],
+        ZTF_observatory,
+        mag=mag[perm],
+        band=[band[i] for i in perm],
+    )
+
+    def photometry(ts: TrajectorySet) -> dict:
+        out = {}
+        for obs in ts.values():
+            t, *_, m, b = obs.to_numpy(photometry=True)
+            assert len(obs.to_numpy(photometry=True)) == 7
+            np.testing.assert_array_equal(m, obs.magnitudes()[0])
+            out.update(zip(t, zip(m, b)))
+        return out
+
+    def assert_expected(found: dict):
+        assert found.keys() == expected.keys()
+        for t, (m, b) in found.items():
+            np.testing.assert_array_equal(m, expected[t][0])
+            assert b == expected[t][1]
+
+    mags, bands = ts[0].magnitudes()
+    np.testing.assert_array_equal(mags, [18.0, 18.1, 18.2])
+    assert bands.dtype == object and list(bands) == ["g", "r", "g"]
+    assert_expected(photometry(ts))
+
+    d = ts[1].to_dict()
+    assert d["band"] == [None, "i"] and d["mag"][0] == 19.0 and d["mag"][1] is None
+    assert "mag" not in TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, err_ra, err_dec, mjd_tt, ZTF_observatory
+    )[0].to_dict()
+
+    first = ts[0]
+    # Rebuilt sets keep the photometry, with or without a new observer.
+    for observer in (None, ZTF_observatory):
+        rebuilt = TrajectorySet.from_observations(
+            pyoutfit_env, dict(ts.items()), observer=observer
+        )
+        assert_expected(photometry(rebuilt))
+
+    # Merged sets keep both tables; splitting keeps every row.
+    other = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        tid + 10,
+        ra_deg,
+        dec_deg,
+        err_ra,
+        err_dec,
+        mjd_tt + 1.0,
+        ZTF_observatory,
+    )
+    ts.extend(other)
+    assert np.isnan(ts[10].magnitudes()[0]).all()
+    assert list(ts[10].magnitudes()[1]) == [None] * 3
+    ts.split_on_gaps(max_gap_days=0.005)
+    found = photometry(ts)
+    assert_expected({t: found[t] for t in expected})
+
+    with pytest.raises(ValueError, match="mag"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env,
+            tid,
+            ra_deg,
+            dec_deg,
+            err_ra,
+            err_dec,
+            mjd_tt,
+            ZTF_observatory,
+            mag=mag[:-1],
+        )
+
+    pandas = pytest.importorskip("pandas")
+    df = first.to_pandas(degrees=True)
+    assert isinstance(df, pandas.DataFrame)
+    assert {"mjd_tt", "ra", "dec", "mag", "band"} <= set(df.columns)
+    assert list(df["band"]) == ["g", "r", "g"]
+
+
+def test_from_records_matches_the_numpy_loader(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer
+):
+    """One dict per observation gives the set of the equivalent NumPy arrays."""
+    tid, ra_deg, dec_deg, err_ra, _, mjd_tt = _build_arrays_degrees()
+    records = [
+        {
+            "trajectory_id": int(i),
+            "ra_deg": float(ra),
+            "dec_deg": float(dec),
+            "sigma_arcsec": err_ra,
+            "mjd_tt": float(t),
+        }
+        for i, ra, dec, t in zip(tid, ra_deg, dec_deg, mjd_tt)
+    ]
+    ts = TrajectorySet.from_records(pyoutfit_env, records, ZTF_observatory)
+    reference = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra_deg, dec_deg, err_ra, err_ra, mjd_tt, ZTF_observatory
+    )
+    assert sorted(ts.keys()) == sorted(reference.keys()) == [0, 1]
+    for k in ts.keys():
+        for x, y in zip(ts[k].to_numpy(), reference[k].to_numpy()):
+            np.testing.assert_array_equal(x, y)
+
+    # String ids, optional photometry and any iterable of mappings.
+    named = TrajectorySet.from_records(
+        pyoutfit_env,
+        (
+            dict(r, trajectory_id=f"obj{r['trajectory_id']}", mag=18.0 + n)
+            for n, r in enumerate(records)
+        ),
+        ZTF_observatory,
+    )
+    assert sorted(named.keys()) == ["obj0", "obj1"]
+    np.testing.assert_array_equal(named["obj1"].magnitudes()[0], [21.0, 22.0])
+
+    incomplete = records[:3] + [{k: v for k, v in records[3].items() if k != "mjd_tt"}]
+    with pytest.raises(KeyError, match=r"record 3 has no 'mjd_tt'"):
+        TrajectorySet.from_records(pyoutfit_env, incomplete, ZTF_observatory)
+    with pytest.raises(TypeError, match=r"record 1: 'ra_deg'"):
+        TrajectorySet.from_records(
+            pyoutfit_env,
+            [records[0], dict(records[1], ra_deg="10.01")],
+            ZTF_observatory,
+        )
+    with pytest.raises(TypeError, match=r"record 1: 'trajectory_id'"):
+        TrajectorySet.from_records(
+            pyoutfit_env,
+            [records[0], dict(records[1], trajectory_id="a")],
+            ZTF_observatory,
+        )
+    with pytest.raises(TypeError, match="record 0 is not a mapping"):
+        TrajectorySet.from_records(pyoutfit_env, [(0, 10.0)], ZTF_observatory)
+
+
+@pytest.mark.parametrize("seed", [0, 1, 2])
+def test_from_chunks_matches_concatenated_arrays(
+    pyoutfit_env: PyOutfit, ZTF_observatory: Observer, seed: int
+):
+    """Chunked ingestion gives the set of the concatenated arrays, whatever the cuts."""
+    rng = np.random.default_rng(seed)
+    n = 400
+    tid = rng.integers(0, 40, n).astype(np.uint32)
+    ra_deg = rng.uniform(0.0, 360.0, n)
+    dec_deg = rng.uniform(-60.0, 60.0, n)
+    sigma = rng.uniform(0.1, 1.0, n)
+    sigma[::37] = 0.0  # patched by the "value" policy, counted over all chunks
+    mjd_tt = 60000.0 + np.sort(rng.uniform(0.0, 20.0, n))
+    mag = rng.uniform(15.0, 22.0, n)
+    columns = (tid, ra_deg, dec_deg, sigma, sigma, mjd_tt, mag)
+
+    cuts = np.sort(rng.choice(np.arange(1, n), size=rng.integers(1, 12), replace=False))
+    chunks = [tuple(np.split(c, cuts)[k] for c in columns) for k in range(cuts.size + 1)]
+    # Dict chunks are accepted alongside tuples.
+    names = ("trajectory_id", "ra_deg", "dec_deg", "error_ra_arcsec", "error_dec_arcsec")
+    chunks[0] = dict(zip(names + ("mjd_tt", "mag"), chunks[0]))
+
+    streamed = TrajectorySet.from_chunks(
+        pyoutfit_env,
+        iter(chunks),
+        ZTF_observatory,
+        bad_sigma="value",
+        bad_sigma_value=0.5,
+    )
+    reference = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env,
+        *columns[:6],
+        ZTF_observatory,
+        bad_sigma="value",
+        bad_sigma_value=0.5,
+        mag=mag,
+    )
+    assert streamed.patched_sigma_count == reference.patched_sigma_count > 0
+    assert sorted(streamed.keys()) == sorted(reference.keys())
+    for k in reference.keys():
+        a = streamed[k].to_numpy(photometry=True)
+        b = reference[k].to_numpy(photometry=True)
+        for x, y in zip(a, b):
+            np.testing.assert_array_equal(x, y)
+
+    with pytest.raises(KeyError, match="chunk 1 has no 'mjd_tt'"):
+        TrajectorySet.from_chunks(
+            pyoutfit_env,
+            [chunks[1], {"trajectory_id": tid, "ra_deg": ra_deg}],
+            ZTF_observatory,
+        )
+    with pytest.raises(ValueError, match="chunk 0: .*ra_deg"):
+        TrajectorySet.from_chunks(
+            pyoutfit_env, [(tid, ra_deg[:-1], dec_deg, 0.5, 0.5, mjd_tt)], ZTF_observatory
+        )
+
+
+def test_dedup_drops_exact_and_near_duplicates(pyoutfit_env: PyOutfit, observer: Observer):
+    """`dedup=` and `deduplicate` drop repeated detections within the tolerances."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    mas = 1.0 / 3.6e6  # degrees
+    # Trajectory 0 gets an exact copy of its first row, a copy of its second row 5e-8 day
+    # and 0.5 mas away, and a row 2 mas away from its third one (not a duplicate).
+    tid = np.concatenate([tid, [0, 0, 0]]).astype(np.uint32)
+    ra = np.concatenate([ra, [ra[0], ra[1], ra[2]]])
+    dec = np.concatenate([dec, [dec[0], dec[1] + 0.5 * mas, dec[2] + 2.0 * mas]])
+    mjd = np.concatenate([mjd, [mjd[0], mjd[1] + 5e-8, mjd[2]]])
+    args = (pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer)
+
+    kept = TrajectorySet.from_numpy_degrees(*args)
+    assert kept.total_observations() == tid.size
+    assert kept.duplicates_removed == {}
+
+    ts = TrajectorySet.from_numpy_degrees(*args, dedup=True)
+    assert ts.duplicates_removed == {0: 2}
+    assert len(ts[0]) == 4 and len(ts[1]) == 2
+    assert ts.is_time_sorted()
+
+    tight = TrajectorySet.from_numpy_degrees(*args, dedup=(1e-7, 0.1))
+    assert tight.duplicates_removed == {0: 1}
+
+    assert kept.deduplicate() == {0: 2}
+    assert kept.deduplicate() == {}
+    assert kept.duplicates_removed == {0: 2}
+    for a, b in zip(kept[0].to_numpy(), ts[0].to_numpy()):
+        np.testing.assert_array_equal(a, b)
+    assert kept.deduplicate(sep_mas=5.0) == {0: 1}
+    assert kept.duplicates_removed == {0: 3}
+
+    # Duplicates spread over two chunks are found once the chunks are merged.
+    chunks = [(c[:5], c[5:]) for c in (tid, ra, dec)] + [(mjd[:5], mjd[5:])]
+    chunks = [(t, r, d, 0.5, 0.5, m) for t, r, d, m in zip(*chunks)]
+    streamed = TrajectorySet.from_chunks(pyoutfit_env, chunks, observer, dedup=True)
+    assert streamed.duplicates_removed == {0: 2}
+
+    with pytest.raises(ValueError, match="time_tol"):
+        kept.deduplicate(time_tol=-1.0)
+    with pytest.raises(TypeError, match="dedup"):
+        TrajectorySet.from_numpy_degrees(*args, dedup="yes")
+
+
+def test_invalid_rows_are_reported_by_column(pyoutfit_env: PyOutfit, observer: Observer):
+    """NaN and out-of-range values are rejected at ingestion, with their rows."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+
+    bad_ra, bad_dec = ra.copy(), dec.copy()
+    bad_ra[1] = np.nan
+    bad_dec[3] = 95.0
+    with pytest.raises(ValueError, match="validate=False") as info:
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, bad_ra, bad_dec, err_ra, err_dec, mjd, observer
+        )
+    message = str(info.value)
+    assert "ra_deg: 1 value(s) not finite or outside [0, 360), at rows 1" in message
+    assert "dec_deg: 1 value(s) not finite or outside [-90, 90], at rows 3" in message
+    assert "mjd_tt" not in message
+
+    old = mjd.copy()
+    old[4] = -58000.0  # 1700
+    with pytest.raises(ValueError, match=r"mjd_tt: 1 value\(s\) .*1800-2200, at rows 4"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra, dec, err_ra, err_dec, old, observer
+        )
+
+    tid_r, ra_r, dec_r, err_ra_r, err_dec_r, mjd_r = _build_arrays_radians()
+    with pytest.raises(ValueError, match=r"ra: .*\[0, 2π\), at rows 0"):
+        TrajectorySet.from_numpy_radians(
+            pyoutfit_env, tid_r, ra_r - 1.0, dec_r, err_ra_r, err_dec_r, mjd_r, observer
+        )
+
+    # Per-row uncertainties are checked under bad_sigma="error" only.
+    sigma = np.full(tid.size, 0.5)
+    sigma[[0, 2]] = [0.0, np.inf]
+    with pytest.raises(ValueError, match=r"error_dec_arcsec: 2 value\(s\) .*rows 0, 2"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra, dec, err_ra, sigma, mjd, observer
+        )
+    patched = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, sigma, mjd, observer,
+        bad_sigma="value", bad_sigma_value=0.5,
+    )
+    assert patched.patched_sigma_count == 2
+
+    # At most 20 rows are listed per column.
+    n = 30
+    with pytest.raises(ValueError, match=r"30 value\(s\).*, 19 \(and 10 more\)"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, np.zeros(n, dtype=np.uint32), np.full(n, 400.0),
+            np.zeros(n), 0.5, 0.5, 60000.0 + np.arange(n) / 100, observer,
+        )
+
+    # The check can be skipped for data known to be clean.
+    wrapped = ra.copy()
+    wrapped[0] += 360.0
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, wrapped, dec, err_ra, err_dec, mjd, observer, validate=False
+    )
+    assert ts.total_observations() == tid.size
+
+    records = [
+        {"trajectory_id": 0, "ra_deg": 10.0, "dec_deg": 5.0, "sigma_arcsec": 0.5, "mjd_tt": t}
+        for t in mjd
+    ]
+    records[2]["dec_deg"] = -91.0
+    with pytest.raises(ValueError, match=r"dec_deg: .*rows 2"):
+        TrajectorySet.from_records(pyoutfit_env, records, observer)
+
+
+def test_masked_rows_are_dropped_and_counted(pyoutfit_env: PyOutfit, observer: Observer):
+    """Masked rows of any column are left out; the other rows stay aligned."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    keep = np.array([True, False, True, True, False])
+    reference = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid[keep], ra[keep], dec[keep], err_ra, err_dec, mjd[keep], observer
+    )
+    assert reference.invalid_rows_dropped == {}
+
+    masked_ra = np.ma.masked_array(ra, mask=[0, 1, 0, 0, 0])
+    masked_mjd = np.ma.masked_array(mjd, mask=[0, 0, 0, 0, 1])
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, masked_ra, dec, err_ra, err_dec, masked_mjd, observer
+    )
+    _assert_same_sets(ts, reference)
+    assert ts.invalid_rows_dropped == {0: 1, 1: 1}
+
+    # The data under the mask is never read: a negative id, an out-of-range angle.
+    ids = np.ma.masked_array(tid.astype(np.int64), mask=[0, 1, 0, 0, 0])
+    ids.data[1] = -1
+    sigma = np.ma.masked_array(np.full(tid.size, 0.5), mask=[0, 0, 0, 0, 1])
+    wild = ra.copy()
+    wild[[1, 4]] = 1e9
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, ids, wild, dec, err_ra, sigma, mjd, observer
+    )
+    _assert_same_sets(ts, reference)
+    assert ts.invalid_rows_dropped == {None: 1, 1: 1}
+
+    # A masked magnitude is unknown, the row is kept.
+    mag = np.ma.masked_array([18.0, 18.1, 18.2, 18.3, 18.4], mask=[0, 0, 1, 0, 0])
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer, mag=mag
+    )
+    assert ts.total_observations() == tid.size
+    assert np.isnan(ts[0].magnitudes()[0][2])
+
+    radians = TrajectorySet.from_numpy_radians(
+        pyoutfit_env, tid, np.deg2rad(masked_ra), np.deg2rad(dec),
+        err_ra * py_outfit.RADSEC, err_dec * py_outfit.RADSEC, masked_mjd, observer,
+    )
+    assert radians.total_observations() == 3
+    assert radians.invalid_rows_dropped == {0: 1, 1: 1}
+
+
+def test_drop_invalid_drops_non_finite_rows(pyoutfit_env: PyOutfit, observer: Observer):
+    """NaN rows are rejected by default and dropped with drop_invalid=True."""
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    bad_dec, bad_mjd = dec.copy(), mjd.copy()
+    bad_dec[0] = np.nan
+    bad_mjd[3] = np.inf
+
+    with pytest.raises(ValueError, match="not finite"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra, bad_dec, err_ra, err_dec, bad_mjd, observer
+        )
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, bad_dec, err_ra, err_dec, bad_mjd, observer,
+        drop_invalid=True,
+    )
+    keep = np.array([False, True, True, False, True])
+    reference = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid[keep], ra[keep], dec[keep], err_ra, err_dec, mjd[keep], observer
+    )
+    _assert_same_sets(ts, reference)
+    assert ts.invalid_rows_dropped == {0: 1, 1: 1}
+
+    # Per-row uncertainties count only when the policy would reject them.
+    sigma = np.full(tid.size, 0.5)
+    sigma[2] = np.nan
+    strict = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, sigma, mjd, observer, drop_invalid=True
+    )
+    assert strict.invalid_rows_dropped == {0: 1}
+    patched = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, sigma, mjd, observer,
+        bad_sigma="value", bad_sigma_value=0.5, drop_invalid=True,
+    )
+    assert patched.invalid_rows_dropped == {}
+    assert patched.patched_sigma_count == 1
+
+    # NaT epochs are missing values as well.
+    stamps = np.array(
+        ["2023-02-25T00:00", "NaT", "2023-02-25T02:00", "2023-02-25T03:00", "2023-02-25T04:00"],
+        dtype="datetime64[ns]",
+    )
+    with pytest.raises(ValueError, match="NaT"):
+        TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra, dec, err_ra, err_dec, stamps, observer
+        )
+    ts = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, err_ra, err_dec, stamps, observer, drop_invalid=True
+    )
+    assert ts.invalid_rows_dropped == {0: 1}
+
+    # Counts accumulate through extend; string ids are reported as given.
+    names = np.array(["a", "a", "a", "b", "b"])
+    named = TrajectorySet.from_numpy_multi_site(
+        pyoutfit_env, names, ra, bad_dec, err_ra, err_dec, bad_mjd,
+        np.zeros(tid.size, dtype=np.uint8), observers=[observer], drop_invalid=True,
+    )
+    assert named.invalid_rows_dropped == {"a": 1, "b": 1}
+    named.extend(named)
+    assert named.invalid_rows_dropped == {"a": 2, "b": 2}
+
+
+def test_from_chunks_drops_masked_rows(pyoutfit_env: PyOutfit, observer: Observer):
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    masked_ra = np.ma.masked_array(ra, mask=[0, 0, 1, 0, 0])
+    bad_dec = dec.copy()
+    bad_dec[4] = np.nan
+    chunks = [
+        (tid[:3], masked_ra[:3], dec[:3], err_ra, err_dec, mjd[:3]),
+        (tid[3:], masked_ra[3:], bad_dec[3:], err_ra, err_dec, mjd[3:]),
+    ]
+    ts = TrajectorySet.from_chunks(pyoutfit_env, iter(chunks), observer, drop_invalid=True)
+    assert ts.total_observations() == 3
+    assert ts.invalid_rows_dropped == {0: 1, 1: 1}
+
+
+@pytest.mark.parametrize("n_threads", [None, 1, 3, 8])
+def test_parallel_construction_matches_serial_build(
+    pyoutfit_env: PyOutfit, observer: Observer, n_threads
+):
+    """
+    Rows interleaved across trajectories, with repeated epochs and per-row uncertainties,
+    give the same observations (positions and storage order included) on any number of
+    threads.
+    """
+    n = 20_000
+    rng = np.random.default_rng(11)
+    tid = rng.integers(0, 700, n).astype(np.uint32)
+    mjd = 60000.0 + rng.integers(0, 400, n) * 0.01
+    ra = rng.uniform(0.0, 360.0, n)
+    dec = rng.uniform(-89.0, 89.0, n)
+    sigma = rng.uniform(0.1, 1.0, n)
+
+    serial = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, sigma, 0.3, mjd, observer, sort=True
+    )
+    parallel = TrajectorySet.from_numpy_degrees(
+        pyoutfit_env, tid, ra, dec, sigma, 0.3, mjd, observer, sort=True,
+        parallel=True, n_threads=n_threads,
+    )
+    _assert_same_sets(serial, parallel, env=pyoutfit_env)
+
+    rad = (tid, np.radians(ra), np.radians(dec), 1e-6, 2e-6, mjd, observer)
+    _assert_same_sets(
+        TrajectorySet.from_numpy_radians(pyoutfit_env, *rad, sort=True),
+        TrajectorySet.from_numpy_radians(
+            pyoutfit_env, *rad, sort=True, parallel=True, n_threads=n_threads
+        ),
+        env=pyoutfit_env,
+    )
+
+
+def test_parallel_construction_of_multi_site_batches(pyoutfit_env: PyOutfit):
+    n = 5_000
+    rng = np.random.default_rng(5)
+    tid = rng.integers(0, 200, n).astype(np.uint32)
+    mjd = 60000.0 + rng.integers(0, 100, n) * 0.01
+    ra = rng.uniform(0.0, 360.0, n)
+    dec = rng.uniform(-89.0, 89.0, n)
+    codes = np.array(["I41", "F51", "500"])[rng.integers(0, 3, n)]
+
+    def build(**kwargs):
+        return TrajectorySet.from_numpy_multi_site(
+            pyoutfit_env, tid, ra, dec, 0.5, 0.5, mjd, codes, sort=True, **kwargs
+        )
+
+    _assert_same_sets(build(), build(parallel=True, n_threads=4), env=pyoutfit_env)
+
+
+def test_parallel_construction_rejects_bad_thread_counts(
+    pyoutfit_env: PyOutfit, observer: Observer
+):
+    tid, ra, dec, err_ra, err_dec, mjd = _build_arrays_degrees()
+    args = (pyoutfit_env, tid, ra, dec, err_ra, err_dec, mjd, observer)
+    with pytest.raises(ValueError, match="n_threads must be positive"):
+        TrajectorySet.from_numpy_degrees(*args, parallel=True, n_threads=0)
+    with pytest.raises(ValueError, match="n_threads requires parallel=True"):
+        TrajectorySet.from_numpy_degrees(*args, n_threads=4)
+
+
+@pytest.mark.benchmark
+def test_parallel_construction_benchmark(pyoutfit_env: PyOutfit, observer: Observer, best_of):
+    """
+    Benchmark on 2M rows over 200k trajectories, observed in 2000 exposures: grouping on
+    four threads against the serial build, which it must reproduce.
+    """
+    n, n_traj = 2_000_000, 200_000
+    rng = np.random.default_rng(3)
+    tid = rng.integers(0, n_traj, n).astype(np.uint32)
+    mjd = 60000.0 + rng.integers(0, 2_000, n) * 0.001
+    ra = rng.uniform(0.0, 360.0, n)
+    dec = rng.uniform(-89.0, 89.0, n)
+
+    def build(**kwargs):
+        return TrajectorySet.from_numpy_degrees(
+            pyoutfit_env, tid, ra, dec, 0.5, 0.5, mjd, observer, validate=False, sort=True,
+            **kwargs,
+        )
+
+    _assert_same_sets(build(), build(parallel=True, n_threads=4))
+    t_serial = best_of(build)
+    t_parallel = best_of(lambda: build(parallel=True, n_threads=4))
+    print(f"parallel construction: {t_parallel:.3f}s on 4 threads, serial {t_serial:.3f}s")